@@ -0,0 +1,409 @@
+use crate::database::Database;
+use crate::key_helper;
+use crate::models::*;
+use reqwest::Method;
+use serde_json::Value;
+use sqlx::Error as SqlxError;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+/// 余额缓存的有效期，过期后由后台任务重新查询
+pub const BALANCE_CACHE_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// 后台任务检查缓存是否过期的间隔
+const REFRESH_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+const QUERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 一次余额查询的 HTTP 请求
+#[derive(Debug)]
+pub struct BalanceRequest {
+    pub method: Method,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<String>,
+}
+
+/// 中转服务的余额查询接口
+pub trait BalanceProvider: Send + Sync {
+    /// 构造查询指定令牌余额的请求
+    fn build_request(&self, base_url: &str, token: &str) -> Result<BalanceRequest, String>;
+
+    /// 从响应 JSON 中取出剩余额度
+    fn parse_balance(&self, body: &Value) -> Result<f64, String>;
+
+    /// 余额单位
+    fn unit(&self) -> &str;
+}
+
+fn render_template(template: &str, base_url: &str, token: &str) -> String {
+    template
+        .replace("{base_url}", base_url.trim_end_matches('/'))
+        .replace("{token}", token)
+}
+
+/// 数据库中配置的适配器：按模板构造请求，按 JSON 路径读取余额
+impl BalanceProvider for BalanceAdapter {
+    fn build_request(&self, base_url: &str, token: &str) -> Result<BalanceRequest, String> {
+        let method = Method::from_bytes(self.method.trim().to_uppercase().as_bytes())
+            .map_err(|_| format!("不支持的请求方法: {}", self.method))?;
+
+        let mut headers = Vec::new();
+        if let Some(raw) = self.headers.as_deref().filter(|h| !h.trim().is_empty()) {
+            let map: HashMap<String, Value> = serde_json::from_str(raw)
+                .map_err(|e| format!("请求头不是有效的 JSON 对象: {}", e))?;
+            for (name, value) in map {
+                let value = match value {
+                    Value::String(s) => s,
+                    other => other.to_string(),
+                };
+                headers.push((name, render_template(&value, base_url, token)));
+            }
+        }
+
+        Ok(BalanceRequest {
+            method,
+            url: render_template(&self.url_template, base_url, token),
+            headers,
+            body: self
+                .body_template
+                .as_deref()
+                .filter(|b| !b.trim().is_empty())
+                .map(|b| render_template(b, base_url, token)),
+        })
+    }
+
+    fn parse_balance(&self, body: &Value) -> Result<f64, String> {
+        let value = json_path(body, &self.balance_path)
+            .ok_or_else(|| format!("响应中没有字段 {}", self.balance_path))?;
+        let raw = match value {
+            Value::Number(n) => n.as_f64(),
+            Value::String(s) => s.trim().parse().ok(),
+            _ => None,
+        }
+        .ok_or_else(|| format!("字段 {} 不是数字", self.balance_path))?;
+
+        let divisor = if self.divisor == 0.0 {
+            1.0
+        } else {
+            self.divisor
+        };
+        Ok(raw / divisor)
+    }
+
+    fn unit(&self) -> &str {
+        &self.unit
+    }
+}
+
+/// 按路径读取 JSON 值，支持 `data.balance`、`data.items[0].remain`，可省略前缀 `$.`
+pub fn json_path<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    let path = path.trim().trim_start_matches('$').trim_start_matches('.');
+    let mut current = value;
+    for segment in path.split('.').filter(|s| !s.is_empty()) {
+        let (key, indexes) = match segment.find('[') {
+            Some(pos) => (&segment[..pos], &segment[pos..]),
+            None => (segment, ""),
+        };
+        if !key.is_empty() {
+            current = current.get(key)?;
+        }
+        for index in indexes.split('[').filter(|s| !s.is_empty()) {
+            let index: usize = index.trim_end_matches(']').parse().ok()?;
+            current = current.get(index)?;
+        }
+    }
+    Some(current)
+}
+
+/// 通过余额查询接口查询令牌的剩余额度
+pub async fn fetch_balance(
+    provider: &dyn BalanceProvider,
+    base_url: &str,
+    token: &str,
+    timeout: Duration,
+) -> Result<f64, String> {
+    let request = provider.build_request(base_url, token)?;
+    let client = reqwest::Client::builder()
+        .timeout(timeout)
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let mut builder = client.request(request.method, &request.url);
+    for (name, value) in &request.headers {
+        builder = builder.header(name.as_str(), value.as_str());
+    }
+    if let Some(body) = request.body {
+        if !request
+            .headers
+            .iter()
+            .any(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+        {
+            builder = builder.header("content-type", "application/json");
+        }
+        builder = builder.body(body);
+    }
+
+    let response = builder
+        .send()
+        .await
+        .map_err(|e| format!("请求失败: {}", e))?;
+    let status = response.status();
+    let text = response
+        .text()
+        .await
+        .map_err(|e| format!("读取响应失败: {}", e))?;
+    if !status.is_success() {
+        return Err(format!(
+            "HTTP {}: {}",
+            status.as_u16(),
+            text.chars().take(200).collect::<String>()
+        ));
+    }
+
+    let body: Value =
+        serde_json::from_str(&text).map_err(|e| format!("响应不是有效的 JSON: {}", e))?;
+    provider.parse_balance(&body)
+}
+
+/// 余额缓存文件，供状态栏脚本 show-status.mjs 读取
+pub fn balance_cache_path() -> Option<PathBuf> {
+    std::env::var("HOME")
+        .or_else(|_| std::env::var("USERPROFILE"))
+        .ok()
+        .map(|home| {
+            PathBuf::from(home)
+                .join(".claude-config-manager")
+                .join("balances.json")
+        })
+}
+
+/// 写入余额缓存文件，按账号名称（即状态栏中的 USER_NAME）索引
+pub fn write_cache_file(accounts: &[Account], balances: &[AccountBalance]) -> std::io::Result<()> {
+    let path = match balance_cache_path() {
+        Some(path) => path,
+        None => return Ok(()),
+    };
+
+    let mut entries = serde_json::Map::new();
+    for balance in balances {
+        if let Some(account) = accounts.iter().find(|a| a.id == balance.account_id) {
+            entries.insert(
+                account.name.clone(),
+                serde_json::json!({
+                    "balance": balance.balance,
+                    "unit": balance.unit,
+                    "error": balance.error,
+                    "base_url": account.base_url,
+                    "fetched_at": balance.fetched_at,
+                }),
+            );
+        }
+    }
+    let content = serde_json::json!({
+        "updated_at": chrono::Utc::now(),
+        "accounts": entries,
+    });
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, serde_json::to_string_pretty(&content)?)
+}
+
+/// 需要查询余额的账号及其 API 地址的适配器，force 为 false 时跳过缓存未过期的账号
+async fn balance_targets(
+    db: &Database,
+    force: bool,
+) -> Result<Vec<(Account, BalanceAdapter)>, SqlxError> {
+    let base_urls = db.get_base_urls().await?;
+    let adapters: HashMap<String, BalanceAdapter> = db
+        .get_balance_adapters()
+        .await?
+        .into_iter()
+        .filter(|adapter| adapter.enabled)
+        .filter_map(|adapter| {
+            base_urls
+                .iter()
+                .find(|bu| bu.id == adapter.base_url_id)
+                .map(|bu| (bu.url.clone(), adapter))
+        })
+        .collect();
+    let fetched_at: HashMap<i64, chrono::DateTime<chrono::Utc>> = db
+        .get_account_balances()
+        .await?
+        .into_iter()
+        .map(|b| (b.account_id, b.fetched_at))
+        .collect();
+
+    let ttl = chrono::Duration::from_std(BALANCE_CACHE_TTL).unwrap_or_default();
+    Ok(db
+        .get_all_accounts()
+        .await?
+        .into_iter()
+        .filter(|account| {
+            force
+                || fetched_at
+                    .get(&account.id)
+                    .map(|time| chrono::Utc::now() - *time > ttl)
+                    .unwrap_or(true)
+        })
+        .filter_map(|account| {
+            adapters
+                .get(&account.base_url)
+                .cloned()
+                .map(|adapter| (account, adapter))
+        })
+        .collect())
+}
+
+/// 查询账号余额并更新缓存，查询期间不持有数据库锁
+pub async fn refresh_balances(
+    db: &Arc<Mutex<Database>>,
+    force: bool,
+) -> Result<Vec<AccountBalance>, SqlxError> {
+    let db_lock = db.lock().await;
+    let targets = balance_targets(&db_lock, force).await?;
+    drop(db_lock);
+
+    if targets.is_empty() && !force {
+        return Ok(Vec::new());
+    }
+
+    let mut results = Vec::new();
+    for (account, adapter) in targets {
+        // 辅助命令模式的账号先执行命令获取密钥
+        let result = match key_helper::resolve_token(&account).await {
+            Ok(token) => fetch_balance(&adapter, &account.base_url, &token, QUERY_TIMEOUT).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = &result {
+            tracing::warn!("账号 {} 的余额查询失败: {}", account.name, e);
+        }
+
+        let db_lock = db.lock().await;
+        let balance = match result {
+            Ok(value) => {
+                db_lock
+                    .save_account_balance(account.id, Some(value), Some(adapter.unit()), None)
+                    .await?
+            }
+            Err(e) => {
+                db_lock
+                    .save_account_balance(account.id, None, Some(adapter.unit()), Some(&e))
+                    .await?
+            }
+        };
+        results.push(balance);
+    }
+
+    let db_lock = db.lock().await;
+    let accounts = db_lock.get_all_accounts().await?;
+    let balances = db_lock.get_account_balances().await?;
+    drop(db_lock);
+    if let Err(e) = write_cache_file(&accounts, &balances) {
+        tracing::warn!("写入余额缓存文件失败: {}", e);
+    }
+
+    Ok(results)
+}
+
+/// 后台定时刷新已过期的余额缓存
+pub async fn run_refresh_loop(db: Arc<Mutex<Database>>) {
+    let mut interval = tokio::time::interval(REFRESH_CHECK_INTERVAL);
+    loop {
+        interval.tick().await;
+        if let Err(e) = refresh_balances(&db, false).await {
+            tracing::warn!("刷新账号余额失败: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    fn adapter(url_template: &str, balance_path: &str, divisor: f64) -> BalanceAdapter {
+        BalanceAdapter {
+            id: 1,
+            base_url_id: 1,
+            method: "GET".to_string(),
+            url_template: url_template.to_string(),
+            headers: Some(r#"{"Authorization": "Bearer {token}"}"#.to_string()),
+            body_template: None,
+            balance_path: balance_path.to_string(),
+            divisor,
+            unit: "USD".to_string(),
+            enabled: true,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    /// 本地模拟余额接口：只接受 Bearer sk-valid
+    async fn start_stub_server() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buffer = vec![0u8; 4096];
+                let n = stream.read(&mut buffer).await.unwrap_or(0);
+                let request = String::from_utf8_lossy(&buffer[..n]).to_lowercase();
+                let (status, body) = if request.contains("authorization: bearer sk-valid") {
+                    (
+                        "200 OK",
+                        r#"{"data":{"quota":"2500000","items":[{"remain":12.5}]}}"#,
+                    )
+                } else {
+                    ("401 Unauthorized", r#"{"error":"invalid token"}"#)
+                };
+                let response = format!(
+                    "HTTP/1.1 {}\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+
+        format!("http://{}", addr)
+    }
+
+    #[test]
+    fn test_json_path() {
+        let value = serde_json::json!({"data": {"items": [{"remain": 3}], "total": 5}});
+        assert_eq!(json_path(&value, "data.total"), Some(&serde_json::json!(5)));
+        assert_eq!(
+            json_path(&value, "$.data.items[0].remain"),
+            Some(&serde_json::json!(3))
+        );
+        assert_eq!(json_path(&value, "data.missing"), None);
+        assert_eq!(json_path(&value, "data.items[1]"), None);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_balance_with_stub_server() {
+        let url = start_stub_server().await;
+
+        let quota = adapter("{base_url}/api/user/self", "data.quota", 500000.0);
+        let balance = fetch_balance(&quota, &url, "sk-valid", QUERY_TIMEOUT).await;
+        assert_eq!(balance, Ok(5.0));
+
+        let remain = adapter("{base_url}/balance", "data.items[0].remain", 1.0);
+        let balance = fetch_balance(&remain, &url, "sk-valid", QUERY_TIMEOUT).await;
+        assert_eq!(balance, Ok(12.5));
+
+        let error = fetch_balance(&quota, &url, "sk-invalid", QUERY_TIMEOUT)
+            .await
+            .unwrap_err();
+        assert!(error.starts_with("HTTP 401"), "{}", error);
+    }
+}
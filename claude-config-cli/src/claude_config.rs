@@ -1,12 +1,12 @@
+use crate::definitions::{builtin_definitions, definition_subdir, render_template};
+use crate::key_helper;
+use crate::models::{Account, ClaudeDefinition, McpServer};
 use anyhow::Result;
-use include_dir::{include_dir, Dir};
 use serde_json::{json, Value};
+use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 
-// 在编译时嵌入整个 commands 目录
-static COMMANDS_DIR: Dir = include_dir!("$CARGO_MANIFEST_DIR/resources/config/commands");
-
 pub struct ClaudeConfigManager {
     directory_path: String,
 }
@@ -24,6 +24,10 @@ impl ClaudeConfigManager {
         format!("{}/settings.local.json", self.get_claude_dir())
     }
 
+    fn get_mcp_json_file(&self) -> String {
+        format!("{}/.mcp.json", self.directory_path)
+    }
+
     fn get_alternative_settings_files(&self) -> Vec<String> {
         vec![
             format!("{}/settings.json", self.get_claude_dir()),
@@ -102,6 +106,22 @@ impl ClaudeConfigManager {
         Ok(())
     }
 
+    /// 读取当前的环境变量配置（settings.local.json，不存在时依次尝试其他配置文件和 CLAUDE.md）
+    pub fn get_env_config(&self) -> Result<HashMap<String, String>> {
+        let settings = self.read_settings()?;
+        let mut env_config = HashMap::new();
+
+        if let Some(obj) = settings.get("env").and_then(|env| env.as_object()) {
+            for (key, value) in obj {
+                if let Some(str_value) = value.as_str() {
+                    env_config.insert(key.clone(), str_value.to_string());
+                }
+            }
+        }
+
+        Ok(env_config)
+    }
+
     /// 检查目标目录是否存在 CLAUDE.local.md 文件
     pub fn has_claude_local_md(&self) -> bool {
         let target_file = Path::new(&self.directory_path).join("CLAUDE.local.md");
@@ -145,12 +165,230 @@ impl ClaudeConfigManager {
             self.copy_claude_local_md()?;
         }
 
-        // 复制 commands 目录下的文件
-        self.copy_commands()?;
-
         Ok(true)
     }
 
+    /// 读取 settings.local.json 中已启用的项目级 MCP 服务器
+    pub fn get_enabled_mcp_servers(&self) -> Vec<String> {
+        fs::read_to_string(self.get_settings_file())
+            .ok()
+            .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+            .and_then(|settings| settings.get("enabledMcpjsonServers").cloned())
+            .and_then(|v| serde_json::from_value::<Vec<String>>(v).ok())
+            .unwrap_or_default()
+    }
+
+    fn get_mcp_manifest_file(&self) -> String {
+        format!("{}/managed-mcp-servers.json", self.get_claude_dir())
+    }
+
+    /// 将 MCP 服务器合并写入项目根目录的 .mcp.json
+    /// 用户自己添加的服务器保持不变，上次写入但本次未分配的服务器会被移除，
+    /// 写入的服务器记录在 .claude/managed-mcp-servers.json 中，返回 (写入的名称, 移除的名称)
+    /// .mcp.json 无法解析时返回错误，不覆盖用户的配置
+    pub fn write_mcp_servers(&self, servers: &[McpServer]) -> Result<(Vec<String>, Vec<String>)> {
+        let mcp_file = self.get_mcp_json_file();
+        let manifest_file = self.get_mcp_manifest_file();
+
+        let previous: Vec<String> = fs::read_to_string(&manifest_file)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        let names: Vec<String> = servers.iter().map(|s| s.name.clone()).collect();
+        let removed: Vec<String> = previous
+            .into_iter()
+            .filter(|name| !names.contains(name))
+            .collect();
+        if names.is_empty() && removed.is_empty() {
+            return Ok((names, removed));
+        }
+
+        let mut mcp_config = if Path::new(&mcp_file).exists() {
+            let content = fs::read_to_string(&mcp_file)?;
+            serde_json::from_str::<Value>(&content)
+                .map_err(|e| anyhow::anyhow!("无法解析 {}，请修复后重试: {}", mcp_file, e))?
+        } else {
+            json!({})
+        };
+
+        let servers_obj = mcp_config
+            .as_object_mut()
+            .ok_or_else(|| anyhow::anyhow!("{} 的内容不是 JSON 对象", mcp_file))?
+            .entry("mcpServers")
+            .or_insert_with(|| json!({}))
+            .as_object_mut()
+            .ok_or_else(|| anyhow::anyhow!("{} 中的 mcpServers 不是 JSON 对象", mcp_file))?;
+        for name in &removed {
+            servers_obj.remove(name);
+        }
+        for server in servers {
+            servers_obj.insert(server.name.clone(), server.to_mcp_json());
+        }
+
+        fs::write(&mcp_file, serde_json::to_string_pretty(&mcp_config)?)?;
+        self.ensure_claude_dir()?;
+        fs::write(&manifest_file, serde_json::to_string_pretty(&names)?)?;
+        tracing::info!(
+            "成功写入 {} 个 MCP 服务器到 {}，移除 {} 个不再分配的服务器",
+            names.len(),
+            mcp_file,
+            removed.len()
+        );
+
+        Ok((names, removed))
+    }
+
+    /// 更新 settings.local.json 的 enabledMcpjsonServers：保留已有项，加入 names 并去掉 removed
+    pub fn merge_enabled_mcp_servers(&self, names: &[String], removed: &[String]) -> Result<()> {
+        let settings_file = self.get_settings_file();
+        let mut settings = if Path::new(&settings_file).exists() {
+            serde_json::from_str::<Value>(&fs::read_to_string(&settings_file)?)?
+        } else {
+            json!({})
+        };
+
+        if !settings.is_object() {
+            settings = json!({});
+        }
+
+        let mut enabled: Vec<String> = settings
+            .get("enabledMcpjsonServers")
+            .cloned()
+            .and_then(|v| serde_json::from_value(v).ok())
+            .unwrap_or_default();
+        enabled.retain(|name| !removed.contains(name));
+        for name in names {
+            if !enabled.contains(name) {
+                enabled.push(name.clone());
+            }
+        }
+
+        settings["enabledMcpjsonServers"] = json!(enabled);
+        self.write_settings(&settings)
+    }
+
+    /// 切换时写入 MCP 服务器：合并 .mcp.json，并在 enabledMcpjsonServers 中保留之前已启用的服务器，
+    /// 不再分配的托管服务器同时从两处移除
+    pub fn apply_mcp_servers(
+        &self,
+        servers: &[McpServer],
+        previously_enabled: Vec<String>,
+    ) -> Result<()> {
+        let (written, removed) = self.write_mcp_servers(servers)?;
+        let mut enabled = previously_enabled;
+        enabled.retain(|name| !removed.contains(name));
+        for name in written {
+            if !enabled.contains(&name) {
+                enabled.push(name);
+            }
+        }
+        if !enabled.is_empty() || !removed.is_empty() {
+            self.merge_enabled_mcp_servers(&enabled, &removed)?;
+        }
+        Ok(())
+    }
+
+    fn get_definitions_manifest_file(&self) -> String {
+        format!("{}/managed-definitions.json", self.get_claude_dir())
+    }
+
+    /// 部署启用的斜杠命令和子代理定义，并删除上次部署但已被禁用的文件
+    /// 已部署的文件记录在 .claude/managed-definitions.json 中，返回本次写入的相对路径
+    pub fn apply_definitions(
+        &self,
+        definitions: &[ClaudeDefinition],
+        vars: &HashMap<String, String>,
+    ) -> Result<Vec<String>> {
+        self.ensure_claude_dir()?;
+        let claude_dir = Path::new(&self.get_claude_dir()).to_path_buf();
+        let manifest_file = self.get_definitions_manifest_file();
+
+        // 旧版本没有清单文件，此时把内置命令视为已托管的文件
+        let previous: Vec<String> = if Path::new(&manifest_file).exists() {
+            fs::read_to_string(&manifest_file)
+                .ok()
+                .and_then(|content| serde_json::from_str(&content).ok())
+                .unwrap_or_default()
+        } else {
+            builtin_definitions()
+                .iter()
+                .map(|d| format!("{}/{}.md", definition_subdir(&d.kind), d.name))
+                .collect()
+        };
+
+        let mut deployed = Vec::new();
+        for definition in definitions {
+            let relative = definition.relative_path();
+            let file_path = claude_dir.join(&relative);
+            if let Some(parent) = file_path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&file_path, render_template(&definition.content, vars))?;
+            tracing::info!("成功写入 {} 到 {}", relative, file_path.display());
+            deployed.push(relative);
+        }
+
+        for relative in previous.iter().filter(|p| !deployed.contains(p)) {
+            // 忽略清单中指向 .claude 目录之外的路径
+            if relative.split('/').any(|segment| segment == "..") {
+                continue;
+            }
+            let file_path = claude_dir.join(relative);
+            if file_path.is_file() {
+                fs::remove_file(&file_path)?;
+                tracing::info!("已移除禁用的定义 {}", file_path.display());
+            }
+        }
+
+        fs::write(&manifest_file, serde_json::to_string_pretty(&deployed)?)?;
+        Ok(deployed)
+    }
+
+    /// 将环境变量合并到 settings.local.json 的 env 段，其他配置保持不变
+    /// 现有文件无法解析时重新生成
+    pub fn merge_env(&self, vars: &[(String, Value)]) -> Result<()> {
+        let mut settings = self.read_settings().unwrap_or_else(|_| json!({}));
+        if !settings.is_object() {
+            settings = json!({});
+        }
+        if !settings.get("env").map(|v| v.is_object()).unwrap_or(false) {
+            settings["env"] = json!({});
+        }
+        for (key, value) in vars {
+            settings["env"][key] = value.clone();
+        }
+        self.write_settings(&settings)
+    }
+
+    /// 从 settings.local.json 的 env 段移除指定的环境变量
+    pub fn remove_env(&self, keys: &[&str]) -> Result<()> {
+        let mut settings = self.read_settings()?;
+        if let Some(env) = settings.get_mut("env").and_then(|v| v.as_object_mut()) {
+            for key in keys {
+                env.remove(*key);
+            }
+        }
+        self.write_settings(&settings)
+    }
+
+    /// 按账号的凭据模式更新 settings.local.json：helper 模式写入 apiKeyHelper 并移除 env 中的静态密钥，
+    /// token 模式移除遗留的 apiKeyHelper
+    pub fn apply_key_helper(&self, account: &Account, api_key_name: &str) -> Result<()> {
+        let mut settings = self.read_settings()?;
+        key_helper::apply_key_helper_to_settings(&mut settings, account, api_key_name);
+        self.write_settings(&settings)
+    }
+
+    /// 写入状态栏脚本 .claude/show-status.mjs
+    pub fn write_status_script(&self) -> Result<()> {
+        const STATUS_SCRIPT_CONTENT: &str = include_str!("../resources/config/show-status.mjs");
+
+        self.ensure_claude_dir()?;
+        let status_script_file = Path::new(&self.get_claude_dir()).join("show-status.mjs");
+        fs::write(&status_script_file, STATUS_SCRIPT_CONTENT)?;
+        Ok(())
+    }
+
     #[allow(dead_code)]
     pub fn clear_env_config(&self) -> Result<bool> {
         let mut settings = self.read_settings()?;
@@ -194,34 +432,99 @@ impl ClaudeConfigManager {
 
         Ok(())
     }
+}
 
-    fn copy_commands(&self) -> Result<()> {
-        // 确保 .claude/commands 目录存在
-        let commands_dir = Path::new(&self.directory_path).join(".claude/commands");
-        if !commands_dir.exists() {
-            fs::create_dir_all(&commands_dir)?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn server(name: &str) -> McpServer {
+        McpServer {
+            id: 0,
+            name: name.to_string(),
+            transport: "stdio".to_string(),
+            command: Some("npx".to_string()),
+            args: "[]".to_string(),
+            env: "{}".to_string(),
+            url: None,
+            description: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
         }
+    }
 
-        // 遍历并复制所有嵌入的文件
-        for file in COMMANDS_DIR.files() {
-            let file_path = commands_dir.join(file.path());
+    fn temp_project(name: &str) -> std::path::PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("claude-config-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
 
-            // 确保父目录存在（如果有子目录）
-            if let Some(parent) = file_path.parent() {
-                if !parent.exists() {
-                    fs::create_dir_all(parent)?;
-                }
-            }
+    fn read_json(path: std::path::PathBuf) -> Value {
+        serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+    }
 
-            // 写入文件内容
-            fs::write(&file_path, file.contents())?;
-            tracing::info!(
-                "成功写入 {} 到 {}",
-                file.path().display(),
-                file_path.display()
-            );
-        }
+    #[test]
+    fn test_apply_mcp_servers_keeps_user_servers_and_prunes_unassigned() {
+        let dir = temp_project("mcp-merge");
+        fs::write(
+            dir.join(".mcp.json"),
+            r#"{"mcpServers": {"mine": {"command": "my-server"}}}"#,
+        )
+        .unwrap();
+        let manager = ClaudeConfigManager::new(dir.display().to_string());
+
+        manager
+            .apply_mcp_servers(&[server("a"), server("b")], vec!["mine".to_string()])
+            .unwrap();
+        let mcp = read_json(dir.join(".mcp.json"));
+        let names: Vec<&String> = mcp["mcpServers"].as_object().unwrap().keys().collect();
+        assert_eq!(names.len(), 3);
+        assert_eq!(mcp["mcpServers"]["mine"]["command"], "my-server");
+        assert_eq!(
+            manager.get_enabled_mcp_servers(),
+            vec!["mine".to_string(), "a".to_string(), "b".to_string()]
+        );
+
+        // b 不再分配：从 .mcp.json 和 enabledMcpjsonServers 中移除，用户的 mine 保留
+        manager
+            .apply_mcp_servers(&[server("a")], manager.get_enabled_mcp_servers())
+            .unwrap();
+        let mcp = read_json(dir.join(".mcp.json"));
+        assert!(mcp["mcpServers"].get("b").is_none());
+        assert!(mcp["mcpServers"].get("mine").is_some());
+        assert_eq!(
+            manager.get_enabled_mcp_servers(),
+            vec!["mine".to_string(), "a".to_string()]
+        );
+
+        // 全部取消分配后只剩用户自己的服务器
+        manager
+            .apply_mcp_servers(&[], manager.get_enabled_mcp_servers())
+            .unwrap();
+        let mcp = read_json(dir.join(".mcp.json"));
+        assert_eq!(mcp["mcpServers"].as_object().unwrap().len(), 1);
+        assert_eq!(manager.get_enabled_mcp_servers(), vec!["mine".to_string()]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
 
-        Ok(())
+    #[test]
+    fn test_write_mcp_servers_rejects_invalid_mcp_json() {
+        let dir = temp_project("mcp-invalid");
+        fs::write(dir.join(".mcp.json"), "{ not json").unwrap();
+        let manager = ClaudeConfigManager::new(dir.display().to_string());
+
+        assert!(manager
+            .apply_mcp_servers(&[server("a")], Vec::new())
+            .is_err());
+        assert_eq!(
+            fs::read_to_string(dir.join(".mcp.json")).unwrap(),
+            "{ not json"
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
     }
 }
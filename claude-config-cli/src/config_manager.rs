@@ -213,4 +213,27 @@ impl ConfigManager {
     pub fn get_default_database_config(&self) -> Option<&DatabaseConfig> {
         self.get_database_config(None)
     }
+
+    pub fn set_default_connection(&mut self, connection_name: &str) -> Result<()> {
+        // 检查连接是否存在
+        if !self.config.connections.contains_key(connection_name) {
+            return Err(anyhow::anyhow!(
+                "Database connection '{}' not found",
+                connection_name
+            ));
+        }
+
+        // 如果有配置文件路径，只改写文件中的 current，保留未建模的字段
+        if let Some(config_file) = &self.config_file {
+            let content = fs::read_to_string(config_file)?;
+            let mut file_config: serde_json::Value = serde_json::from_str(&content)?;
+            file_config["current"] = serde_json::Value::String(connection_name.to_string());
+            fs::write(config_file, serde_json::to_string_pretty(&file_config)?)?;
+        }
+
+        // 文件写入成功后再更新内存中的默认连接，避免两者不一致
+        self.config.current = connection_name.to_string();
+
+        Ok(())
+    }
 }
@@ -0,0 +1,191 @@
+use crate::models::{Account, BaseUrl};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Claude Code 读取自定义请求头的环境变量，每行一个 "Name: Value"
+pub const CUSTOM_HEADERS_ENV: &str = "ANTHROPIC_CUSTOM_HEADERS";
+
+/// 自定义请求头
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomHeader {
+    pub name: String,
+    pub value: String,
+}
+
+impl CustomHeader {
+    pub fn new(name: &str, value: &str) -> Self {
+        Self {
+            name: name.trim().to_string(),
+            value: value.trim().to_string(),
+        }
+    }
+}
+
+/// 解析数据库中保存的 JSON 请求头列表，格式无效时返回空列表
+pub fn parse_headers(json: &str) -> Vec<CustomHeader> {
+    serde_json::from_str(json).unwrap_or_default()
+}
+
+/// 解析 "Name: Value" 格式的多行文本，忽略空行和没有冒号的行
+pub fn parse_header_lines(text: &str) -> Vec<CustomHeader> {
+    text.lines()
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| CustomHeader::new(name, value))
+        .filter(|header| !header.name.is_empty())
+        .collect()
+}
+
+/// 序列化为 ANTHROPIC_CUSTOM_HEADERS 的值
+pub fn to_header_lines(headers: &[CustomHeader]) -> String {
+    headers
+        .iter()
+        .map(|h| format!("{}: {}", h.name, h.value))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 校验请求头名称和值是否合法，名称不区分大小写且不能重复
+pub fn validate_headers(headers: &[CustomHeader]) -> Result<(), String> {
+    let mut seen: Vec<String> = Vec::new();
+    for header in headers {
+        HeaderName::from_bytes(header.name.as_bytes())
+            .map_err(|_| format!("无效的请求头名称 '{}'", header.name))?;
+        if header.value.contains(['\r', '\n']) {
+            return Err(format!("请求头 '{}' 的值不能包含换行", header.name));
+        }
+        HeaderValue::from_str(&header.value)
+            .map_err(|_| format!("请求头 '{}' 的值无效", header.name))?;
+
+        let lower = header.name.to_ascii_lowercase();
+        if seen.contains(&lower) {
+            return Err(format!("请求头 '{}' 重复", header.name));
+        }
+        seen.push(lower);
+    }
+    Ok(())
+}
+
+/// 按层合并请求头：后面的层覆盖前面同名（不区分大小写）的请求头，保留首次出现的顺序
+pub fn merge_headers(layers: &[&[CustomHeader]]) -> Vec<CustomHeader> {
+    let mut merged: Vec<CustomHeader> = Vec::new();
+    for header in layers.iter().flat_map(|layer| layer.iter()) {
+        match merged
+            .iter_mut()
+            .find(|h| h.name.eq_ignore_ascii_case(&header.name))
+        {
+            Some(existing) => existing.value = header.value.clone(),
+            None => merged.push(header.clone()),
+        }
+    }
+    merged
+}
+
+impl BaseUrl {
+    /// URL 级别的自定义请求头
+    pub fn header_list(&self) -> Vec<CustomHeader> {
+        parse_headers(&self.custom_headers)
+    }
+}
+
+impl Account {
+    /// 账号级别的自定义请求头
+    pub fn header_list(&self) -> Vec<CustomHeader> {
+        parse_headers(&self.custom_headers)
+    }
+}
+
+/// 账号实际发送的请求头：URL 级别在前，账号级别覆盖同名请求头
+pub fn effective_headers(
+    base_url: Option<&BaseUrl>,
+    account: Option<&Account>,
+) -> Vec<CustomHeader> {
+    let url_headers = base_url.map(BaseUrl::header_list).unwrap_or_default();
+    let account_headers = account.map(Account::header_list).unwrap_or_default();
+    merge_headers(&[&url_headers, &account_headers])
+}
+
+/// 把请求头合并写入 env：env 中已有的 ANTHROPIC_CUSTOM_HEADERS（如自定义环境变量中手写的值）
+/// 作为最底层，没有结构化请求头时保持原值
+pub fn apply_headers_to_env(env: &mut Map<String, Value>, headers: &[CustomHeader]) {
+    if headers.is_empty() {
+        return;
+    }
+    let existing = env
+        .get(CUSTOM_HEADERS_ENV)
+        .and_then(Value::as_str)
+        .map(parse_header_lines)
+        .unwrap_or_default();
+    let merged = merge_headers(&[&existing, headers]);
+    env.insert(
+        CUSTOM_HEADERS_ENV.to_string(),
+        Value::String(to_header_lines(&merged)),
+    );
+}
+
+/// 转换为 HTTP 请求头，忽略无效的请求头
+pub fn header_map(headers: &[CustomHeader]) -> HeaderMap {
+    let mut map = HeaderMap::new();
+    for header in headers {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(header.name.as_bytes()),
+            HeaderValue::from_str(&header.value),
+        ) {
+            map.insert(name, value);
+        }
+    }
+    map
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_merge_and_validate() {
+        let url = vec![
+            CustomHeader::new("X-Tenant-Id", "team-a"),
+            CustomHeader::new("X-Route", "default"),
+        ];
+        let account = vec![CustomHeader::new("x-route", "gpu-pool")];
+        let merged = merge_headers(&[&url, &account]);
+        assert_eq!(
+            to_header_lines(&merged),
+            "X-Tenant-Id: team-a\nX-Route: gpu-pool"
+        );
+        assert_eq!(header_map(&merged)["x-route"], "gpu-pool");
+
+        assert!(validate_headers(&merged).is_ok());
+        assert!(validate_headers(&[CustomHeader::new("Bad Name", "v")]).is_err());
+        assert!(
+            validate_headers(&[CustomHeader::new("X-A", "1"), CustomHeader::new("x-a", "2")])
+                .is_err()
+        );
+        assert!(validate_headers(&[CustomHeader::new("X-A", "line\nbreak")]).is_err());
+
+        assert_eq!(
+            parse_header_lines("X-A: 1\n\ninvalid\n X-B : a:b "),
+            vec![
+                CustomHeader::new("X-A", "1"),
+                CustomHeader::new("X-B", "a:b")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_apply_headers_to_env() {
+        // 手写在自定义环境变量中的请求头作为底层，结构化请求头覆盖同名项
+        let mut env = json!({ "ANTHROPIC_CUSTOM_HEADERS": "X-Legacy: 1\nX-Route: old" });
+        let vars = env.as_object_mut().unwrap();
+        apply_headers_to_env(vars, &[CustomHeader::new("X-Route", "new")]);
+        assert_eq!(
+            env,
+            json!({ "ANTHROPIC_CUSTOM_HEADERS": "X-Legacy: 1\nX-Route: new" })
+        );
+
+        let mut env = json!({});
+        apply_headers_to_env(env.as_object_mut().unwrap(), &[]);
+        assert_eq!(env, json!({}));
+    }
+}
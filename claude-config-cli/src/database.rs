@@ -1,19 +1,30 @@
 use crate::config_manager::ConfigManager;
+use crate::custom_headers::CustomHeader;
+use crate::db_backend::{self, Backend, DbPool};
+use crate::migrations::{self, MigrationStatus};
 use crate::models::*;
-use chrono::Utc;
-use sqlx::{sqlite::SqlitePool, Error as SqlxError, Row};
+use crate::providers;
+use chrono::{DateTime, Utc};
+use sqlx::Error as SqlxError;
 use std::path::PathBuf;
 use tracing::{error, info, warn};
 
 pub struct Database {
-    pub pool: SqlitePool,
+    pub pool: DbPool,
 }
 
 impl Database {
     /// 获取数据库连接池引用
-    pub fn get_pool(&self) -> &SqlitePool {
+    pub fn get_pool(&self) -> &DbPool {
         &self.pool
     }
+    /// 配置的数据库类型，无法识别时返回 None
+    pub fn configured_backend() -> Option<Backend> {
+        ConfigManager::new()
+            .get_default_database_config()
+            .and_then(|config| Backend::from_url(&config.url).ok())
+    }
+
     /// 创建带有回退策略的数据库连接
     /// 当正常初始化失败时，尝试在用户主目录创建数据库
     pub async fn create_with_fallback() -> Result<Self, SqlxError> {
@@ -66,7 +77,7 @@ impl Database {
         }
 
         // 连接数据库
-        let pool = SqlitePool::connect(&database_url).await.map_err(|e| {
+        let pool = DbPool::connect(&database_url).await.map_err(|e| {
             error!("回退策略连接数据库失败: {}", e);
             println!("回退策略数据库连接失败: {}", e);
             e
@@ -91,16 +102,39 @@ impl Database {
     }
 
     pub async fn new() -> Result<Self, SqlxError> {
+        let db = Self::connect().await?;
+
+        info!("开始数据库迁移和初始化");
+        match db.migrate().await {
+            Ok(_) => info!("数据库迁移和初始化完成"),
+            Err(e) => {
+                error!("数据库迁移和初始化失败: {}", e);
+                return Err(e);
+            }
+        }
+
+        Ok(db)
+    }
+
+    /// 只连接配置的数据库，不执行迁移（用于查看迁移状态）
+    pub async fn connect() -> Result<Self, SqlxError> {
+        Self::connect_to(&ConfigManager::new(), None).await
+    }
+
+    /// 连接 config.json 中指定名称的数据库，None 表示 current 指向的连接
+    async fn connect_to(
+        config_manager: &ConfigManager,
+        connection_name: Option<&str>,
+    ) -> Result<Self, SqlxError> {
         info!("开始初始化数据库");
 
         // 使用配置管理器获取数据库配置
-        let config_manager = ConfigManager::new();
         let db_config = config_manager
-            .get_default_database_config()
+            .get_database_config(connection_name)
             .ok_or_else(|| SqlxError::Configuration("No database configuration found".into()))?;
 
         let mut database_url = db_config.url.clone();
-        info!("原始数据库URL: {}", database_url);
+        info!("原始数据库URL: {}", db_backend::redact_url(&database_url));
 
         // 处理SQLite相对路径，将数据库放在用户数据目录而不是resources目录
         if database_url.starts_with("sqlite:///") && !database_url.starts_with("sqlite:////") {
@@ -217,15 +251,19 @@ impl Database {
             }
         }
 
-        info!("尝试连接数据库: {}", database_url);
+        info!("尝试连接数据库: {}", db_backend::redact_url(&database_url));
 
-        let pool = match SqlitePool::connect(&database_url).await {
+        let pool = match DbPool::connect(&database_url).await {
             Ok(pool) => {
                 info!("数据库连接成功");
                 pool
             }
             Err(e) => {
-                error!("数据库连接失败，URL: {}, 错误: {}", database_url, e);
+                error!(
+                    "数据库连接失败，URL: {}, 错误: {}",
+                    db_backend::redact_url(&database_url),
+                    e
+                );
 
                 // 如果是相对路径，打印绝对路径信息
                 if database_url.starts_with("sqlite:") {
@@ -262,158 +300,22 @@ impl Database {
             }
         };
 
-        let db = Self { pool };
-
-        info!("开始数据库迁移和初始化");
-        match db.migrate().await {
-            Ok(_) => info!("数据库迁移和初始化完成"),
-            Err(e) => {
-                error!("数据库迁移和初始化失败: {}", e);
-                return Err(e);
-            }
-        }
-
-        Ok(db)
+        Ok(Self { pool })
     }
 
+    /// 初始化默认数据，表结构由迁移负责
     async fn initialize(&self) -> Result<(), SqlxError> {
-        // 启用外键约束
-        sqlx::query("PRAGMA foreign_keys = ON")
-            .execute(&self.pool)
-            .await?;
-
-        info!("已启用SQLite外键约束");
-
-        // Create accounts table
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS accounts (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                name TEXT NOT NULL UNIQUE,
-                token TEXT NOT NULL,
-                base_url TEXT NOT NULL,
-                model TEXT NOT NULL DEFAULT '',
-                is_active BOOLEAN NOT NULL DEFAULT FALSE,
-                custom_env_vars TEXT NOT NULL DEFAULT '{}',
-                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
-                updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
-            )
-            "#,
-        )
-        .execute(&self.pool)
-        .await?;
-
-        // Create directories table
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS directories (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                path TEXT NOT NULL UNIQUE,
-                name TEXT NOT NULL,
-                is_active BOOLEAN NOT NULL DEFAULT FALSE,
-                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
-                updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
-            )
-            "#,
-        )
-        .execute(&self.pool)
-        .await?;
-
-        // Create base_urls table
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS base_urls (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                name TEXT NOT NULL UNIQUE,
-                url TEXT NOT NULL UNIQUE,
-                description TEXT,
-                api_key TEXT NOT NULL DEFAULT 'ANTHROPIC_API_KEY',
-                is_default BOOLEAN NOT NULL DEFAULT FALSE,
-                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
-                updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
-            )
-            "#,
-        )
-        .execute(&self.pool)
-        .await?;
-
-        // Create account_directories table
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS account_directories (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                account_id INTEGER NOT NULL,
-                directory_id INTEGER NOT NULL,
-                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
-                FOREIGN KEY (account_id) REFERENCES accounts (id) ON DELETE CASCADE,
-                FOREIGN KEY (directory_id) REFERENCES directories (id) ON DELETE CASCADE,
-                UNIQUE(account_id, directory_id)
-            )
-            "#,
-        )
-        .execute(&self.pool)
-        .await?;
-
-        // Create claude_settings table
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS claude_settings (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                settings_json TEXT NOT NULL,
-                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
-                updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
-            )
-            "#,
-        )
-        .execute(&self.pool)
-        .await?;
-
-        // Create webdav_configs table for WebDAV synchronization
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS webdav_configs (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                name TEXT NOT NULL UNIQUE,
-                url TEXT NOT NULL,
-                username TEXT NOT NULL,
-                password TEXT NOT NULL,
-                remote_path TEXT NOT NULL DEFAULT '/claude-config',
-                auto_sync BOOLEAN NOT NULL DEFAULT FALSE,
-                sync_interval INTEGER NOT NULL DEFAULT 3600,
-                is_active BOOLEAN NOT NULL DEFAULT FALSE,
-                last_sync_at DATETIME,
-                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
-                updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
-            )
-            "#,
-        )
-        .execute(&self.pool)
-        .await?;
-
-        // Create sync_logs table for tracking synchronization history
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS sync_logs (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                webdav_config_id INTEGER NOT NULL,
-                sync_type TEXT NOT NULL CHECK(sync_type IN ('upload', 'download', 'auto')),
-                status TEXT NOT NULL CHECK(status IN ('success', 'failed', 'pending')),
-                message TEXT,
-                synced_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
-                FOREIGN KEY (webdav_config_id) REFERENCES webdav_configs (id) ON DELETE CASCADE
-            )
-            "#,
-        )
-        .execute(&self.pool)
-        .await?;
-
         // Initialize only essential default data
         self.initialize_default_base_urls().await?;
+        self.initialize_default_permission_presets().await?;
+        self.initialize_default_definitions().await?;
         // 不再初始化示例账号和目录数据
 
         // 输出初始化完成信息
-        let base_url_count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM base_urls")
-            .fetch_one(&self.pool)
+        let base_url_count: i64 = self
+            .pool
+            .query("SELECT COUNT(*) FROM base_urls")
+            .fetch_scalar()
             .await?;
 
         println!("数据库初始化完成 - 默认 API 端点: {} 个", base_url_count);
@@ -423,8 +325,10 @@ impl Database {
     }
 
     async fn initialize_default_base_urls(&self) -> Result<(), SqlxError> {
-        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM base_urls")
-            .fetch_one(&self.pool)
+        let count: i64 = self
+            .pool
+            .query("SELECT COUNT(*) FROM base_urls")
+            .fetch_scalar()
             .await?;
 
         if count == 0 {
@@ -439,7 +343,7 @@ impl Database {
             ];
 
             for (name, url, description, is_default) in default_urls {
-                sqlx::query(
+                self.pool.query(
                     "INSERT INTO base_urls (name, url, description, is_default, created_at, updated_at) 
                      VALUES (?, ?, ?, ?, ?, ?)"
                 )
@@ -449,7 +353,7 @@ impl Database {
                 .bind(is_default)
                 .bind(Utc::now())
                 .bind(Utc::now())
-                .execute(&self.pool)
+                .execute()
                 .await?;
             }
         }
@@ -457,73 +361,63 @@ impl Database {
         Ok(())
     }
 
-    /// 迁移数据库，确保所有表都存在
-    pub async fn migrate(&self) -> Result<(), SqlxError> {
-        info!("开始数据库迁移检查");
-
-        // 先运行 initialize，确保所有表都存在（使用 IF NOT EXISTS，不会影响现有表）
-        self.initialize().await?;
-
-        // 检查 accounts 表是否存在 model 字段
-        let has_model_field: i64 = sqlx::query_scalar(
-            "SELECT COUNT(*) FROM pragma_table_info('accounts') WHERE name = 'model'",
-        )
-        .fetch_one(&self.pool)
-        .await?;
+    /// 打开 config.json 中的另一个数据库连接：完成迁移并写回 current 后返回
+    /// 不影响当前使用的连接，任一步失败时关闭新连接
+    pub async fn open_connection(
+        config_manager: &mut ConfigManager,
+        connection_name: &str,
+    ) -> Result<Self, SqlxError> {
+        info!("切换数据库连接: {}", connection_name);
+
+        let db = Self::connect_to(config_manager, Some(connection_name)).await?;
+        if let Err(e) = db.migrate().await {
+            error!("新数据库连接迁移失败，保留原连接: {}", e);
+            db.pool.close().await;
+            return Err(e);
+        }
 
-        if has_model_field == 0 {
-            // 添加 model 字段
-            info!("检测到 accounts 表缺少 model 字段，开始添加...");
-            sqlx::query("ALTER TABLE accounts ADD COLUMN model TEXT NOT NULL DEFAULT ''")
-                .execute(&self.pool)
-                .await?;
-            info!("已成功添加 model 字段到 accounts 表");
-        } else {
-            info!("accounts 表已包含 model 字段，无需添加");
+        if let Err(e) = config_manager.set_default_connection(connection_name) {
+            error!("保存当前数据库连接失败，保留原连接: {}", e);
+            db.pool.close().await;
+            return Err(SqlxError::Configuration(
+                format!("保存当前数据库连接失败: {}", e).into(),
+            ));
         }
 
-        // 检查 base_urls 表是否存在 api_key 字段
-        let has_api_key_field: i64 = sqlx::query_scalar(
-            "SELECT COUNT(*) FROM pragma_table_info('base_urls') WHERE name = 'api_key'",
-        )
-        .fetch_one(&self.pool)
-        .await?;
+        Ok(db)
+    }
 
-        if has_api_key_field == 0 {
-            // 添加 api_key 字段
-            info!("检测到 base_urls 表缺少 api_key 字段，开始添加...");
-            sqlx::query("ALTER TABLE base_urls ADD COLUMN api_key TEXT NOT NULL DEFAULT 'ANTHROPIC_API_KEY'")
-                .execute(&self.pool)
-                .await?;
-            info!("已成功添加 api_key 字段到 base_urls 表");
-        } else {
-            info!("base_urls 表已包含 api_key 字段，无需添加");
-        }
+    /// 换用已打开的连接，返回旧连接池，由调用方释放锁后关闭
+    pub fn replace_connection(&mut self, db: Database) -> DbPool {
+        std::mem::replace(&mut self.pool, db.pool)
+    }
 
-        // 检查 accounts 表是否存在 custom_env_vars 字段
-        let has_custom_env_vars_field: i64 = sqlx::query_scalar(
-            "SELECT COUNT(*) FROM pragma_table_info('accounts') WHERE name = 'custom_env_vars'",
-        )
-        .fetch_one(&self.pool)
-        .await?;
+    /// 执行尚未执行的结构迁移，再初始化默认数据
+    pub async fn migrate(&self) -> Result<(), SqlxError> {
+        info!("开始数据库迁移检查");
 
-        if has_custom_env_vars_field == 0 {
-            // 添加 custom_env_vars 字段
-            info!("检测到 accounts 表缺少 custom_env_vars 字段，开始添加...");
-            sqlx::query(
-                "ALTER TABLE accounts ADD COLUMN custom_env_vars TEXT NOT NULL DEFAULT '{}'",
-            )
-            .execute(&self.pool)
-            .await?;
-            info!("已成功添加 custom_env_vars 字段到 accounts 表");
+        // 启用外键约束
+        self.pool.enable_foreign_keys().await?;
+        info!("已启用外键约束，数据库类型: {}", self.pool.backend().name());
+
+        let executed = migrations::run_migrations(&self.pool).await?;
+        if executed.is_empty() {
+            info!("数据库结构已是最新版本 {}", migrations::latest_version());
         } else {
-            info!("accounts 表已包含 custom_env_vars 字段，无需添加");
+            info!("已执行数据库迁移: {:?}", executed);
         }
 
+        self.initialize().await?;
+
         info!("数据库迁移完成");
         Ok(())
     }
 
+    /// 数据库迁移状态
+    pub async fn migration_status(&self) -> Result<Vec<MigrationStatus>, SqlxError> {
+        migrations::migration_status(&self.pool).await
+    }
+
     // Account methods
     pub async fn get_accounts(
         &self,
@@ -539,10 +433,11 @@ impl Database {
 
         if let Some(search) = &request.search {
             if !search.is_empty() {
-                query.push_str(" AND (name LIKE ? OR token LIKE ?)");
-                count_query.push_str(" AND (name LIKE ? OR token LIKE ?)");
+                query.push_str(" AND (name LIKE ? OR token LIKE ? OR notes LIKE ?)");
+                count_query.push_str(" AND (name LIKE ? OR token LIKE ? OR notes LIKE ?)");
                 let search_pattern = format!("%{}%", search);
                 params.push(search_pattern.clone());
+                params.push(search_pattern.clone());
                 params.push(search_pattern);
             }
         }
@@ -555,22 +450,58 @@ impl Database {
             }
         }
 
+        if let Some(enabled) = request.enabled {
+            let clause = if enabled {
+                " AND enabled = TRUE"
+            } else {
+                " AND enabled = FALSE"
+            };
+            query.push_str(clause);
+            count_query.push_str(clause);
+        }
+
+        // 标签以逗号分隔存储，首尾补逗号后按整词匹配
+        if let Some(tag) = &request.tag {
+            if !tag.trim().is_empty() {
+                query.push_str(" AND CONCAT(',', tags, ',') LIKE ? ESCAPE '!'");
+                count_query.push_str(" AND CONCAT(',', tags, ',') LIKE ? ESCAPE '!'");
+                params.push(format!("%,{},%", escape_like(tag.trim())));
+            }
+        }
+
+        // 已过期的账号同样算作即将到期；截止时间按时间类型绑定，放在其他参数之后
+        let expiring_before = request
+            .expiring_within_days
+            .map(days_from_now)
+            .transpose()?;
+        if expiring_before.is_some() {
+            query.push_str(" AND expires_at IS NOT NULL AND julianday(expires_at) <= julianday(?)");
+            count_query
+                .push_str(" AND expires_at IS NOT NULL AND julianday(expires_at) <= julianday(?)");
+        }
+
         query.push_str(" ORDER BY created_at DESC LIMIT ? OFFSET ?");
 
         let total: i64 = {
-            let mut q = sqlx::query_scalar(&count_query);
+            let mut q = self.pool.query(&count_query);
             for param in &params {
                 q = q.bind(param);
             }
-            q.fetch_one(&self.pool).await?
+            if let Some(expiring_before) = expiring_before {
+                q = q.bind(expiring_before);
+            }
+            q.fetch_scalar().await?
         };
 
         let accounts: Vec<Account> = {
-            let mut q = sqlx::query_as(&query);
+            let mut q = self.pool.query(&query);
             for param in &params {
                 q = q.bind(param);
             }
-            q.bind(per_page).bind(offset).fetch_all(&self.pool).await?
+            if let Some(expiring_before) = expiring_before {
+                q = q.bind(expiring_before);
+            }
+            q.bind(per_page).bind(offset).fetch_all().await?
         };
 
         let pages = (total + per_page - 1) / per_page;
@@ -603,23 +534,29 @@ impl Database {
             .custom_env_vars
             .map(|v| v.to_string())
             .unwrap_or_else(|| "{}".to_string());
-        let result = sqlx::query(
-            "INSERT INTO accounts (name, token, base_url, model, custom_env_vars, created_at, updated_at)
-             VALUES (?, ?, ?, ?, ?, ?, ?)",
+        let id = self.pool.query(
+            "INSERT INTO accounts (name, token, base_url, model, custom_env_vars, enabled, expires_at, notes, tags, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, datetime(?), ?, ?, ?, ?)",
         )
         .bind(&request.name)
         .bind(&request.token)
         .bind(&request.base_url)
         .bind(&request.model)
         .bind(&custom_env_vars)
+        .bind(request.enabled.unwrap_or(true))
+        .bind(request.expires_at.as_deref().filter(|v| !v.trim().is_empty()))
+        .bind(request.notes.as_deref().unwrap_or(""))
+        .bind(request.tags.as_deref().map(normalize_tags).unwrap_or_default())
         .bind(now)
         .bind(now)
-        .execute(&self.pool)
+        .insert()
         .await?;
 
-        let account = sqlx::query_as::<_, Account>("SELECT * FROM accounts WHERE id = ?")
-            .bind(result.last_insert_rowid())
-            .fetch_one(&self.pool)
+        let account = self
+            .pool
+            .query("SELECT * FROM accounts WHERE id = ?")
+            .bind(id)
+            .fetch_one::<Account>()
             .await?;
 
         Ok(account)
@@ -649,6 +586,20 @@ impl Database {
             updates.push("custom_env_vars = ?");
         }
 
+        if request.enabled.is_some() {
+            updates.push("enabled = ?");
+        }
+        // 空字符串表示清除到期时间
+        if request.expires_at.is_some() {
+            updates.push("expires_at = datetime(?)");
+        }
+        if request.notes.is_some() {
+            updates.push("notes = ?");
+        }
+        if request.tags.is_some() {
+            updates.push("tags = ?");
+        }
+
         if updates.is_empty() {
             return self.get_account(id).await;
         }
@@ -656,7 +607,7 @@ impl Database {
         updates.push("updated_at = ?");
         let query = format!("UPDATE accounts SET {} WHERE id = ?", updates.join(", "));
 
-        let mut q = sqlx::query(&query);
+        let mut q = self.pool.query(&query);
 
         if let Some(name) = &request.name {
             q = q.bind(name);
@@ -674,45 +625,142 @@ impl Database {
             q = q.bind(custom_env_vars.to_string());
         }
 
+        if let Some(enabled) = request.enabled {
+            q = q.bind(enabled);
+        }
+        if let Some(expires_at) = &request.expires_at {
+            q = q.bind(Some(expires_at.as_str()).filter(|v| !v.trim().is_empty()));
+        }
+        if let Some(notes) = &request.notes {
+            q = q.bind(notes);
+        }
+        if let Some(tags) = &request.tags {
+            q = q.bind(normalize_tags(tags));
+        }
+
         q = q.bind(now).bind(id);
-        q.execute(&self.pool).await?;
+        q.execute().await?;
 
         self.get_account(id).await
     }
 
     pub async fn get_account(&self, id: i64) -> Result<Account, SqlxError> {
-        sqlx::query_as::<_, Account>("SELECT * FROM accounts WHERE id = ?")
+        self.pool
+            .query("SELECT * FROM accounts WHERE id = ?")
             .bind(id)
-            .fetch_one(&self.pool)
+            .fetch_one::<Account>()
+            .await
+    }
+
+    /// 按最近一次切换时间倒序获取账号，从未使用过的按名称排在最后
+    pub async fn get_accounts_by_recent_use(
+        &self,
+        enabled_only: bool,
+    ) -> Result<Vec<Account>, SqlxError> {
+        let filter = if enabled_only {
+            "WHERE enabled = TRUE"
+        } else {
+            ""
+        };
+        self.pool
+            .query(format!(
+                "SELECT * FROM accounts {}
+             ORDER BY last_used_at IS NULL, julianday(last_used_at) DESC, name ASC",
+                filter
+            ))
+            .fetch_all::<Account>()
+            .await
+    }
+
+    /// 获取已启用且在指定天数内到期（含已过期）的账号，按到期时间升序
+    pub async fn get_expiring_accounts(&self, within_days: i64) -> Result<Vec<Account>, SqlxError> {
+        self.pool
+            .query(
+                "SELECT * FROM accounts
+             WHERE enabled = TRUE AND expires_at IS NOT NULL
+               AND julianday(expires_at) <= julianday(?)
+             ORDER BY expires_at ASC",
+            )
+            .bind(days_from_now(within_days)?)
+            .fetch_all::<Account>()
             .await
     }
 
+    /// 记录令牌校验结果
+    pub async fn update_account_token_status(
+        &self,
+        id: i64,
+        status: &str,
+        error: Option<&str>,
+    ) -> Result<Account, SqlxError> {
+        self.pool.query(
+            "UPDATE accounts SET token_status = ?, token_checked_at = ?, token_error = ? WHERE id = ?",
+        )
+        .bind(status)
+        .bind(Utc::now())
+        .bind(error)
+        .bind(id)
+        .execute()
+        .await?;
+
+        self.get_account(id).await
+    }
+
     pub async fn delete_account(&self, id: i64) -> Result<(), SqlxError> {
         // 启用外键约束
-        sqlx::query("PRAGMA foreign_keys = ON")
-            .execute(&self.pool)
-            .await?;
+        self.pool.enable_foreign_keys().await?;
 
         // 检查是否有关联的账号-目录记录
-        let association_count: i64 =
-            sqlx::query_scalar("SELECT COUNT(*) FROM account_directories WHERE account_id = ?")
-                .bind(id)
-                .fetch_one(&self.pool)
-                .await?;
+        let association_count: i64 = self
+            .pool
+            .query("SELECT COUNT(*) FROM account_directories WHERE account_id = ?")
+            .bind(id)
+            .fetch_scalar()
+            .await?;
 
         if association_count > 0 {
             // 先删除关联记录
             info!("删除账号 {} 的关联记录，共 {} 条", id, association_count);
-            sqlx::query("DELETE FROM account_directories WHERE account_id = ?")
+            self.pool
+                .query("DELETE FROM account_directories WHERE account_id = ?")
                 .bind(id)
-                .execute(&self.pool)
+                .execute()
                 .await?;
         }
 
+        // 删除账号绑定的 MCP 服务器
+        self.pool
+            .query("DELETE FROM account_mcp_servers WHERE account_id = ?")
+            .bind(id)
+            .execute()
+            .await?;
+
+        self.pool
+            .query("DELETE FROM account_balances WHERE account_id = ?")
+            .bind(id)
+            .execute()
+            .await?;
+
+        // 从账号池中移除
+        self.pool
+            .query("DELETE FROM account_pool_members WHERE account_id = ?")
+            .bind(id)
+            .execute()
+            .await?;
+
+        // 删除中转代理统计
+        self.pool
+            .query("DELETE FROM relay_stats WHERE account_id = ?")
+            .bind(id)
+            .execute()
+            .await?;
+
         // 删除账号记录
-        let result = sqlx::query("DELETE FROM accounts WHERE id = ?")
+        let result = self
+            .pool
+            .query("DELETE FROM accounts WHERE id = ?")
             .bind(id)
-            .execute(&self.pool)
+            .execute()
             .await?;
 
         if result.rows_affected() == 0 {
@@ -725,8 +773,39 @@ impl Database {
 
     // Directory methods
     pub async fn get_directories(&self) -> Result<Vec<Directory>, SqlxError> {
-        sqlx::query_as::<_, Directory>("SELECT * FROM directories ORDER BY created_at DESC")
-            .fetch_all(&self.pool)
+        self.pool
+            .query("SELECT * FROM directories ORDER BY created_at DESC")
+            .fetch_all::<Directory>()
+            .await
+    }
+
+    /// 按最近一次切换时间倒序获取目录，从未切换过的排在最后
+    pub async fn get_directories_by_recent_use(&self) -> Result<Vec<Directory>, SqlxError> {
+        self.pool
+            .query(
+                "SELECT d.* FROM directories d
+             LEFT JOIN (
+                 SELECT directory_id, MAX(julianday(created_at)) AS last_used
+                 FROM account_directories GROUP BY directory_id
+             ) r ON r.directory_id = d.id
+             ORDER BY r.last_used IS NULL, r.last_used DESC, d.created_at DESC",
+            )
+            .fetch_all::<Directory>()
+            .await
+    }
+
+    /// 目录最近一次切换到的账号
+    pub async fn get_last_account_for_directory(
+        &self,
+        directory_id: i64,
+    ) -> Result<Option<i64>, SqlxError> {
+        self.pool
+            .query(
+                "SELECT account_id FROM account_directories WHERE directory_id = ?
+             ORDER BY julianday(created_at) DESC LIMIT 1",
+            )
+            .bind(directory_id)
+            .fetch_optional_scalar()
             .await
     }
 
@@ -735,19 +814,23 @@ impl Database {
         request: CreateDirectoryRequest,
     ) -> Result<Directory, SqlxError> {
         let now = Utc::now();
-        let result = sqlx::query(
-            "INSERT INTO directories (path, name, created_at, updated_at) VALUES (?, ?, ?, ?)",
-        )
-        .bind(&request.path)
-        .bind(&request.name)
-        .bind(now)
-        .bind(now)
-        .execute(&self.pool)
-        .await?;
+        let id = self
+            .pool
+            .query(
+                "INSERT INTO directories (path, name, created_at, updated_at) VALUES (?, ?, ?, ?)",
+            )
+            .bind(&request.path)
+            .bind(&request.name)
+            .bind(now)
+            .bind(now)
+            .insert()
+            .await?;
 
-        let directory = sqlx::query_as::<_, Directory>("SELECT * FROM directories WHERE id = ?")
-            .bind(result.last_insert_rowid())
-            .fetch_one(&self.pool)
+        let directory = self
+            .pool
+            .query("SELECT * FROM directories WHERE id = ?")
+            .bind(id)
+            .fetch_one::<Directory>()
             .await?;
 
         Ok(directory)
@@ -775,7 +858,7 @@ impl Database {
         updates.push("updated_at = ?");
         let query = format!("UPDATE directories SET {} WHERE id = ?", updates.join(", "));
 
-        let mut q = sqlx::query(&query);
+        let mut q = self.pool.query(&query);
 
         if let Some(path) = &request.path {
             q = q.bind(path);
@@ -785,31 +868,30 @@ impl Database {
         }
 
         q = q.bind(now).bind(id);
-        q.execute(&self.pool).await?;
+        q.execute().await?;
 
         self.get_directory(id).await
     }
 
     pub async fn get_directory(&self, id: i64) -> Result<Directory, SqlxError> {
-        sqlx::query_as::<_, Directory>("SELECT * FROM directories WHERE id = ?")
+        self.pool
+            .query("SELECT * FROM directories WHERE id = ?")
             .bind(id)
-            .fetch_one(&self.pool)
+            .fetch_one::<Directory>()
             .await
     }
 
     pub async fn delete_directory(&self, id: i64) -> Result<(), SqlxError> {
         // 启用外键约束
-        sqlx::query("PRAGMA foreign_keys = ON")
-            .execute(&self.pool)
-            .await?;
+        self.pool.enable_foreign_keys().await?;
 
         // 先获取目录信息，检查文件系统中是否存在
-        let directory = match sqlx::query_as::<_, crate::models::Directory>(
-            "SELECT * FROM directories WHERE id = ?",
-        )
-        .bind(id)
-        .fetch_one(&self.pool)
-        .await
+        let directory = match self
+            .pool
+            .query("SELECT * FROM directories WHERE id = ?")
+            .bind(id)
+            .fetch_one::<crate::models::Directory>()
+            .await
         {
             Ok(dir) => dir,
             Err(_) => return Err(SqlxError::RowNotFound),
@@ -828,25 +910,50 @@ impl Database {
         }
 
         // 检查是否有关联的账号-目录记录
-        let association_count: i64 =
-            sqlx::query_scalar("SELECT COUNT(*) FROM account_directories WHERE directory_id = ?")
-                .bind(id)
-                .fetch_one(&self.pool)
-                .await?;
+        let association_count: i64 = self
+            .pool
+            .query("SELECT COUNT(*) FROM account_directories WHERE directory_id = ?")
+            .bind(id)
+            .fetch_scalar()
+            .await?;
 
         if association_count > 0 {
             // 先删除关联记录
             info!("删除目录 {} 的关联记录，共 {} 条", id, association_count);
-            sqlx::query("DELETE FROM account_directories WHERE directory_id = ?")
+            self.pool
+                .query("DELETE FROM account_directories WHERE directory_id = ?")
                 .bind(id)
-                .execute(&self.pool)
+                .execute()
                 .await?;
         }
 
+        // 删除目录绑定的 Hook
+        self.pool
+            .query("DELETE FROM directory_hooks WHERE directory_id = ?")
+            .bind(id)
+            .execute()
+            .await?;
+
+        // 删除目录的定义启用设置
+        self.pool
+            .query("DELETE FROM directory_definitions WHERE directory_id = ?")
+            .bind(id)
+            .execute()
+            .await?;
+
+        // 删除目录绑定的 MCP 服务器
+        self.pool
+            .query("DELETE FROM directory_mcp_servers WHERE directory_id = ?")
+            .bind(id)
+            .execute()
+            .await?;
+
         // 删除目录记录
-        let result = sqlx::query("DELETE FROM directories WHERE id = ?")
+        let result = self
+            .pool
+            .query("DELETE FROM directories WHERE id = ?")
             .bind(id)
-            .execute(&self.pool)
+            .execute()
             .await?;
 
         if result.rows_affected() == 0 {
@@ -864,11 +971,25 @@ impl Database {
 
     // BaseUrl methods
     pub async fn get_base_urls(&self) -> Result<Vec<BaseUrl>, SqlxError> {
-        sqlx::query_as::<_, BaseUrl>(
-            "SELECT * FROM base_urls ORDER BY is_default DESC, created_at DESC",
-        )
-        .fetch_all(&self.pool)
-        .await
+        self.pool
+            .query("SELECT * FROM base_urls ORDER BY is_default DESC, created_at DESC")
+            .fetch_all::<BaseUrl>()
+            .await
+    }
+
+    /// 按使用该地址的账号最近一次切换时间倒序获取 Base URL
+    pub async fn get_base_urls_by_recent_use(&self) -> Result<Vec<BaseUrl>, SqlxError> {
+        self.pool
+            .query(
+                "SELECT b.* FROM base_urls b
+             LEFT JOIN (
+                 SELECT base_url, MAX(julianday(last_used_at)) AS last_used
+                 FROM accounts GROUP BY base_url
+             ) r ON r.base_url = b.url
+             ORDER BY r.last_used IS NULL, r.last_used DESC, b.is_default DESC, b.created_at DESC",
+            )
+            .fetch_all::<BaseUrl>()
+            .await
     }
 
     pub async fn create_base_url(
@@ -880,36 +1001,48 @@ impl Database {
 
         // If setting as default, unset other defaults
         if is_default {
-            sqlx::query("UPDATE base_urls SET is_default = FALSE")
-                .execute(&self.pool)
+            self.pool
+                .query("UPDATE base_urls SET is_default = FALSE")
+                .execute()
                 .await?;
         }
 
-        let result = sqlx::query(
-            "INSERT INTO base_urls (name, url, description, is_default, created_at, updated_at)
-             VALUES (?, ?, ?, ?, ?, ?)",
+        let api_key = request
+            .api_key
+            .unwrap_or_else(|| "ANTHROPIC_API_KEY".to_string());
+        let provider_type = request
+            .provider_type
+            .unwrap_or_else(|| providers::DEFAULT_PROVIDER.to_string());
+        let id = self.pool.query(
+            "INSERT INTO base_urls (name, url, description, api_key, provider_type, is_default, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
         )
         .bind(&request.name)
         .bind(&request.url)
         .bind(&request.description)
+        .bind(&api_key)
+        .bind(&provider_type)
         .bind(is_default)
         .bind(now)
         .bind(now)
-        .execute(&self.pool)
+        .insert()
         .await?;
 
-        let base_url = sqlx::query_as::<_, BaseUrl>("SELECT * FROM base_urls WHERE id = ?")
-            .bind(result.last_insert_rowid())
-            .fetch_one(&self.pool)
+        let base_url = self
+            .pool
+            .query("SELECT * FROM base_urls WHERE id = ?")
+            .bind(id)
+            .fetch_one::<BaseUrl>()
             .await?;
 
         Ok(base_url)
     }
 
     pub async fn get_base_url(&self, id: i64) -> Result<BaseUrl, SqlxError> {
-        sqlx::query_as::<_, BaseUrl>("SELECT * FROM base_urls WHERE id = ?")
+        self.pool
+            .query("SELECT * FROM base_urls WHERE id = ?")
             .bind(id)
-            .fetch_one(&self.pool)
+            .fetch_one::<BaseUrl>()
             .await
     }
 
@@ -926,8 +1059,9 @@ impl Database {
 
         // If setting as default, unset other defaults
         if let Some(true) = request.is_default {
-            sqlx::query("UPDATE base_urls SET is_default = FALSE")
-                .execute(&self.pool)
+            self.pool
+                .query("UPDATE base_urls SET is_default = FALSE")
+                .execute()
                 .await?;
         }
 
@@ -941,6 +1075,12 @@ impl Database {
         if let Some(_description) = &request.description {
             updates.push("description = ?");
         }
+        if let Some(_api_key) = &request.api_key {
+            updates.push("api_key = ?");
+        }
+        if let Some(_provider_type) = &request.provider_type {
+            updates.push("provider_type = ?");
+        }
         if let Some(_is_default) = request.is_default {
             updates.push("is_default = ?");
         }
@@ -952,7 +1092,7 @@ impl Database {
         updates.push("updated_at = ?");
         let query = format!("UPDATE base_urls SET {} WHERE id = ?", updates.join(", "));
 
-        let mut q = sqlx::query(&query);
+        let mut q = self.pool.query(&query);
 
         if let Some(name) = &request.name {
             q = q.bind(name);
@@ -963,24 +1103,30 @@ impl Database {
         if let Some(description) = &request.description {
             q = q.bind(description);
         }
+        if let Some(api_key) = &request.api_key {
+            q = q.bind(api_key);
+        }
+        if let Some(provider_type) = &request.provider_type {
+            q = q.bind(provider_type);
+        }
         if let Some(is_default) = request.is_default {
             q = q.bind(is_default);
         }
 
         q = q.bind(now).bind(id);
-        q.execute(&self.pool).await?;
+        q.execute().await?;
 
         // 如果 URL 发生了变化，级联更新所有使用该 URL 的账号
         if let Some(new_url) = &request.url {
             if new_url != &old_url {
-                let result = sqlx::query(
-                    "UPDATE accounts SET base_url = ?, updated_at = ? WHERE base_url = ?",
-                )
-                .bind(new_url)
-                .bind(now)
-                .bind(&old_url)
-                .execute(&self.pool)
-                .await?;
+                let result = self
+                    .pool
+                    .query("UPDATE accounts SET base_url = ?, updated_at = ? WHERE base_url = ?")
+                    .bind(new_url)
+                    .bind(now)
+                    .bind(&old_url)
+                    .execute()
+                    .await?;
 
                 let affected_rows = result.rows_affected();
                 if affected_rows > 0 {
@@ -1000,11 +1146,12 @@ impl Database {
         let base_url = self.get_base_url(id).await?;
 
         // 查找使用这个 base_url 的所有账号
-        let affected_accounts: Vec<(i64, String)> =
-            sqlx::query_as("SELECT id, name FROM accounts WHERE base_url = ?")
-                .bind(&base_url.url)
-                .fetch_all(&self.pool)
-                .await?;
+        let affected_accounts: Vec<(i64, String)> = self
+            .pool
+            .query("SELECT id, name FROM accounts WHERE base_url = ?")
+            .bind(&base_url.url)
+            .fetch_all()
+            .await?;
 
         if !affected_accounts.is_empty() {
             info!(
@@ -1023,10 +1170,24 @@ impl Database {
             }
         }
 
+        self.pool
+            .query("DELETE FROM endpoint_checks WHERE base_url_id = ?")
+            .bind(id)
+            .execute()
+            .await?;
+
+        self.pool
+            .query("DELETE FROM balance_adapters WHERE base_url_id = ?")
+            .bind(id)
+            .execute()
+            .await?;
+
         // 删除 base_url 记录
-        let result = sqlx::query("DELETE FROM base_urls WHERE id = ?")
+        let result = self
+            .pool
+            .query("DELETE FROM base_urls WHERE id = ?")
             .bind(id)
-            .execute(&self.pool)
+            .execute()
             .await?;
 
         if result.rows_affected() == 0 {
@@ -1040,33 +1201,41 @@ impl Database {
     // Switch account functionality
     pub async fn switch_account(&self, request: SwitchAccountRequest) -> Result<String, SqlxError> {
         // Reset all active states
-        sqlx::query("UPDATE accounts SET is_active = FALSE")
-            .execute(&self.pool)
+        self.pool
+            .query("UPDATE accounts SET is_active = FALSE")
+            .execute()
             .await?;
-        sqlx::query("UPDATE directories SET is_active = FALSE")
-            .execute(&self.pool)
+        self.pool
+            .query("UPDATE directories SET is_active = FALSE")
+            .execute()
             .await?;
 
         // Set new active states
-        sqlx::query("UPDATE accounts SET is_active = TRUE WHERE id = ?")
-            .bind(request.account_id)
-            .execute(&self.pool)
-            .await?;
-        sqlx::query("UPDATE directories SET is_active = TRUE WHERE id = ?")
-            .bind(request.directory_id)
-            .execute(&self.pool)
-            .await?;
-
-        // Create or update association
-        sqlx::query(
-            "INSERT OR IGNORE INTO account_directories (account_id, directory_id, created_at) 
-             VALUES (?, ?, ?)",
+        self.pool.query(
+            "UPDATE accounts SET is_active = TRUE, last_used_at = ?, use_count = use_count + 1 WHERE id = ?",
         )
-        .bind(request.account_id)
-        .bind(request.directory_id)
         .bind(Utc::now())
-        .execute(&self.pool)
+        .bind(request.account_id)
+        .execute()
         .await?;
+        self.pool
+            .query("UPDATE directories SET is_active = TRUE WHERE id = ?")
+            .bind(request.directory_id)
+            .execute()
+            .await?;
+
+        // Create or update association (created_at 记录最近一次切换时间)
+        self.pool
+            .query(
+                "INSERT INTO account_directories (account_id, directory_id, created_at)
+             VALUES (?, ?, ?)
+             ON CONFLICT(account_id, directory_id) DO UPDATE SET created_at = excluded.created_at",
+            )
+            .bind(request.account_id)
+            .bind(request.directory_id)
+            .bind(Utc::now())
+            .execute()
+            .await?;
 
         // Get account and directory info for response
         let account = self.get_account(request.account_id).await?;
@@ -1080,47 +1249,58 @@ impl Database {
 
     // Claude Settings methods
     pub async fn save_claude_settings(&self, settings_json: &str) -> Result<(), SqlxError> {
-        // First try to update existing settings
-        let result = sqlx::query(
-            r#"
+        // First try to update existing settings (MySQL 不允许在 UPDATE 的子查询中读取同一张表)
+        let first_id: Option<i64> = self
+            .pool
+            .query("SELECT MIN(id) FROM claude_settings")
+            .fetch_scalar()
+            .await?;
+        let result = self
+            .pool
+            .query(
+                r#"
             UPDATE claude_settings 
             SET settings_json = ?, updated_at = CURRENT_TIMESTAMP
-            WHERE id = (SELECT MIN(id) FROM claude_settings)
+            WHERE id = ?
             "#,
-        )
-        .bind(settings_json)
-        .execute(&self.pool)
-        .await?;
+            )
+            .bind(settings_json)
+            .bind(first_id)
+            .execute()
+            .await?;
 
         // If no rows were affected, insert a new record
         if result.rows_affected() == 0 {
-            sqlx::query(
-                r#"
+            self.pool
+                .query(
+                    r#"
                 INSERT INTO claude_settings (settings_json)
                 VALUES (?)
                 "#,
-            )
-            .bind(settings_json)
-            .execute(&self.pool)
-            .await?;
-        }
+                )
+                .bind(settings_json)
+                .execute()
+                .await?;
+        }
 
         Ok(())
     }
 
     pub async fn get_claude_settings(&self) -> Result<String, SqlxError> {
-        let row = sqlx::query(
-            r#"
+        let row = self
+            .pool
+            .query(
+                r#"
             SELECT settings_json FROM claude_settings 
             ORDER BY updated_at DESC 
             LIMIT 1
             "#,
-        )
-        .fetch_optional(&self.pool)
-        .await?;
+            )
+            .fetch_optional_scalar::<String>()
+            .await?;
 
         match row {
-            Some(row) => Ok(row.get("settings_json")),
+            Some(settings_json) => Ok(settings_json),
             None => {
                 // Return default settings if no settings exist
                 let default_settings = r#"{
@@ -1138,4 +1318,2258 @@ impl Database {
             }
         }
     }
+
+    // MCP server methods
+    pub async fn get_mcp_servers(&self) -> Result<Vec<McpServer>, SqlxError> {
+        self.pool
+            .query("SELECT * FROM mcp_servers ORDER BY name ASC")
+            .fetch_all::<McpServer>()
+            .await
+    }
+
+    pub async fn get_mcp_server(&self, id: i64) -> Result<McpServer, SqlxError> {
+        self.pool
+            .query("SELECT * FROM mcp_servers WHERE id = ?")
+            .bind(id)
+            .fetch_one::<McpServer>()
+            .await
+    }
+
+    pub async fn create_mcp_server(
+        &self,
+        request: CreateMcpServerRequest,
+    ) -> Result<McpServer, SqlxError> {
+        let now = Utc::now();
+        let args = serde_json::to_string(&request.args.unwrap_or_default())
+            .unwrap_or_else(|_| "[]".to_string());
+        let env = request
+            .env
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "{}".to_string());
+
+        let id = self.pool.query(
+            "INSERT INTO mcp_servers (name, transport, command, args, env, url, description, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&request.name)
+        .bind(&request.transport)
+        .bind(&request.command)
+        .bind(&args)
+        .bind(&env)
+        .bind(&request.url)
+        .bind(&request.description)
+        .bind(now)
+        .bind(now)
+        .insert()
+        .await?;
+
+        self.get_mcp_server(id).await
+    }
+
+    pub async fn update_mcp_server(
+        &self,
+        id: i64,
+        request: UpdateMcpServerRequest,
+    ) -> Result<McpServer, SqlxError> {
+        let now = Utc::now();
+        let mut updates = Vec::new();
+
+        if request.name.is_some() {
+            updates.push("name = ?");
+        }
+        if request.transport.is_some() {
+            updates.push("transport = ?");
+        }
+        if request.command.is_some() {
+            updates.push("command = ?");
+        }
+        if request.args.is_some() {
+            updates.push("args = ?");
+        }
+        if request.env.is_some() {
+            updates.push("env = ?");
+        }
+        if request.url.is_some() {
+            updates.push("url = ?");
+        }
+        if request.description.is_some() {
+            updates.push("description = ?");
+        }
+
+        if updates.is_empty() {
+            return self.get_mcp_server(id).await;
+        }
+
+        updates.push("updated_at = ?");
+        let query = format!("UPDATE mcp_servers SET {} WHERE id = ?", updates.join(", "));
+
+        let mut q = self.pool.query(&query);
+
+        if let Some(name) = &request.name {
+            q = q.bind(name);
+        }
+        if let Some(transport) = &request.transport {
+            q = q.bind(transport);
+        }
+        if let Some(command) = &request.command {
+            q = q.bind(command);
+        }
+        if let Some(args) = &request.args {
+            q = q.bind(serde_json::to_string(args).unwrap_or_else(|_| "[]".to_string()));
+        }
+        if let Some(env) = &request.env {
+            q = q.bind(env.to_string());
+        }
+        if let Some(url) = &request.url {
+            q = q.bind(url);
+        }
+        if let Some(description) = &request.description {
+            q = q.bind(description);
+        }
+
+        q = q.bind(now).bind(id);
+        q.execute().await?;
+
+        self.get_mcp_server(id).await
+    }
+
+    pub async fn delete_mcp_server(&self, id: i64) -> Result<(), SqlxError> {
+        self.pool
+            .query("DELETE FROM directory_mcp_servers WHERE mcp_server_id = ?")
+            .bind(id)
+            .execute()
+            .await?;
+        self.pool
+            .query("DELETE FROM account_mcp_servers WHERE mcp_server_id = ?")
+            .bind(id)
+            .execute()
+            .await?;
+
+        let result = self
+            .pool
+            .query("DELETE FROM mcp_servers WHERE id = ?")
+            .bind(id)
+            .execute()
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(SqlxError::RowNotFound);
+        }
+
+        info!("成功删除 MCP 服务器，ID: {}", id);
+        Ok(())
+    }
+
+    pub async fn get_directory_mcp_server_ids(
+        &self,
+        directory_id: i64,
+    ) -> Result<Vec<i64>, SqlxError> {
+        self.pool.query(
+            "SELECT mcp_server_id FROM directory_mcp_servers WHERE directory_id = ? ORDER BY mcp_server_id",
+        )
+        .bind(directory_id)
+        .fetch_all_scalar()
+        .await
+    }
+
+    /// 设置目录绑定的 MCP 服务器（整体替换）
+    pub async fn set_directory_mcp_servers(
+        &self,
+        directory_id: i64,
+        server_ids: &[i64],
+    ) -> Result<(), SqlxError> {
+        let mut tx = self.pool.begin().await?;
+
+        tx.query("DELETE FROM directory_mcp_servers WHERE directory_id = ?")
+            .bind(directory_id)
+            .execute()
+            .await?;
+
+        for server_id in server_ids {
+            tx.query(
+                "INSERT OR IGNORE INTO directory_mcp_servers (directory_id, mcp_server_id, created_at)
+                 VALUES (?, ?, ?)",
+            )
+            .bind(directory_id)
+            .bind(server_id)
+            .bind(Utc::now())
+            .execute()
+            .await?;
+        }
+
+        tx.commit().await
+    }
+
+    pub async fn get_account_mcp_server_ids(&self, account_id: i64) -> Result<Vec<i64>, SqlxError> {
+        self.pool.query(
+            "SELECT mcp_server_id FROM account_mcp_servers WHERE account_id = ? ORDER BY mcp_server_id",
+        )
+        .bind(account_id)
+        .fetch_all_scalar()
+        .await
+    }
+
+    /// 设置账号绑定的 MCP 服务器（整体替换）
+    pub async fn set_account_mcp_servers(
+        &self,
+        account_id: i64,
+        server_ids: &[i64],
+    ) -> Result<(), SqlxError> {
+        let mut tx = self.pool.begin().await?;
+
+        tx.query("DELETE FROM account_mcp_servers WHERE account_id = ?")
+            .bind(account_id)
+            .execute()
+            .await?;
+
+        for server_id in server_ids {
+            tx.query(
+                "INSERT OR IGNORE INTO account_mcp_servers (account_id, mcp_server_id, created_at)
+                 VALUES (?, ?, ?)",
+            )
+            .bind(account_id)
+            .bind(server_id)
+            .bind(Utc::now())
+            .execute()
+            .await?;
+        }
+
+        tx.commit().await
+    }
+
+    /// 获取切换时需要写入的 MCP 服务器（目录绑定 + 账号绑定，去重）
+    pub async fn get_mcp_servers_for_switch(
+        &self,
+        account_id: i64,
+        directory_id: i64,
+    ) -> Result<Vec<McpServer>, SqlxError> {
+        self.pool
+            .query(
+                r#"
+            SELECT * FROM mcp_servers
+            WHERE id IN (SELECT mcp_server_id FROM directory_mcp_servers WHERE directory_id = ?)
+               OR id IN (SELECT mcp_server_id FROM account_mcp_servers WHERE account_id = ?)
+            ORDER BY name ASC
+            "#,
+            )
+            .bind(directory_id)
+            .bind(account_id)
+            .fetch_all::<McpServer>()
+            .await
+    }
+
+    // Hook methods
+    pub async fn get_hooks(&self) -> Result<Vec<Hook>, SqlxError> {
+        self.pool
+            .query("SELECT * FROM hooks ORDER BY event ASC, name ASC")
+            .fetch_all::<Hook>()
+            .await
+    }
+
+    pub async fn get_hook(&self, id: i64) -> Result<Hook, SqlxError> {
+        self.pool
+            .query("SELECT * FROM hooks WHERE id = ?")
+            .bind(id)
+            .fetch_one::<Hook>()
+            .await
+    }
+
+    pub async fn create_hook(&self, request: CreateHookRequest) -> Result<Hook, SqlxError> {
+        let now = Utc::now();
+        let id = self.pool.query(
+            "INSERT INTO hooks (name, event, matcher, command, timeout, is_global, description, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&request.name)
+        .bind(&request.event)
+        .bind(&request.matcher)
+        .bind(&request.command)
+        .bind(request.timeout)
+        .bind(request.is_global.unwrap_or(false))
+        .bind(&request.description)
+        .bind(now)
+        .bind(now)
+        .insert()
+        .await?;
+
+        self.get_hook(id).await
+    }
+
+    pub async fn update_hook(
+        &self,
+        id: i64,
+        request: UpdateHookRequest,
+    ) -> Result<Hook, SqlxError> {
+        let now = Utc::now();
+        let mut updates = Vec::new();
+
+        if request.name.is_some() {
+            updates.push("name = ?");
+        }
+        if request.event.is_some() {
+            updates.push("event = ?");
+        }
+        if request.matcher.is_some() {
+            updates.push("matcher = ?");
+        }
+        if request.command.is_some() {
+            updates.push("command = ?");
+        }
+        if request.timeout.is_some() {
+            updates.push("timeout = ?");
+        }
+        if request.is_global.is_some() {
+            updates.push("is_global = ?");
+        }
+        if request.description.is_some() {
+            updates.push("description = ?");
+        }
+
+        if updates.is_empty() {
+            return self.get_hook(id).await;
+        }
+
+        updates.push("updated_at = ?");
+        let query = format!("UPDATE hooks SET {} WHERE id = ?", updates.join(", "));
+
+        let mut q = self.pool.query(&query);
+
+        if let Some(name) = &request.name {
+            q = q.bind(name);
+        }
+        if let Some(event) = &request.event {
+            q = q.bind(event);
+        }
+        if let Some(matcher) = &request.matcher {
+            // 空字符串表示清除 matcher
+            q = q.bind(Some(matcher.clone()).filter(|m| !m.is_empty()));
+        }
+        if let Some(command) = &request.command {
+            q = q.bind(command);
+        }
+        if let Some(timeout) = request.timeout {
+            // 0 表示清除超时设置
+            q = q.bind(Some(timeout).filter(|t| *t > 0));
+        }
+        if let Some(is_global) = request.is_global {
+            q = q.bind(is_global);
+        }
+        if let Some(description) = &request.description {
+            q = q.bind(description);
+        }
+
+        q = q.bind(now).bind(id);
+        q.execute().await?;
+
+        self.get_hook(id).await
+    }
+
+    pub async fn delete_hook(&self, id: i64) -> Result<(), SqlxError> {
+        self.pool
+            .query("DELETE FROM directory_hooks WHERE hook_id = ?")
+            .bind(id)
+            .execute()
+            .await?;
+
+        let result = self
+            .pool
+            .query("DELETE FROM hooks WHERE id = ?")
+            .bind(id)
+            .execute()
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(SqlxError::RowNotFound);
+        }
+
+        info!("成功删除 Hook，ID: {}", id);
+        Ok(())
+    }
+
+    pub async fn get_directory_hook_ids(&self, directory_id: i64) -> Result<Vec<i64>, SqlxError> {
+        self.pool
+            .query("SELECT hook_id FROM directory_hooks WHERE directory_id = ? ORDER BY hook_id")
+            .bind(directory_id)
+            .fetch_all_scalar()
+            .await
+    }
+
+    /// 设置目录绑定的 Hook（整体替换）
+    pub async fn set_directory_hooks(
+        &self,
+        directory_id: i64,
+        hook_ids: &[i64],
+    ) -> Result<(), SqlxError> {
+        let mut tx = self.pool.begin().await?;
+
+        tx.query("DELETE FROM directory_hooks WHERE directory_id = ?")
+            .bind(directory_id)
+            .execute()
+            .await?;
+
+        for hook_id in hook_ids {
+            tx.query(
+                "INSERT OR IGNORE INTO directory_hooks (directory_id, hook_id, created_at) VALUES (?, ?, ?)",
+            )
+            .bind(directory_id)
+            .bind(hook_id)
+            .bind(Utc::now())
+            .execute()
+            .await?;
+        }
+
+        tx.commit().await
+    }
+
+    /// 获取切换时需要写入的 Hook（全局 Hook + 目录绑定的 Hook）
+    pub async fn get_hooks_for_switch(&self, directory_id: i64) -> Result<Vec<Hook>, SqlxError> {
+        self.pool
+            .query(
+                r#"
+            SELECT * FROM hooks
+            WHERE is_global = TRUE
+               OR id IN (SELECT hook_id FROM directory_hooks WHERE directory_id = ?)
+            ORDER BY event ASC, name ASC
+            "#,
+            )
+            .bind(directory_id)
+            .fetch_all::<Hook>()
+            .await
+    }
+
+    // Permission preset methods
+    async fn initialize_default_permission_presets(&self) -> Result<(), SqlxError> {
+        for (name, default_mode, allow, deny, ask, description) in
+            crate::permissions::BUILTIN_PRESETS
+        {
+            self.pool.query(
+                "INSERT OR IGNORE INTO permission_presets (name, default_mode, allow, deny, ask, description, is_builtin, created_at, updated_at)
+                 VALUES (?, ?, ?, ?, ?, ?, TRUE, ?, ?)",
+            )
+            .bind(name)
+            .bind(default_mode)
+            .bind(serde_json::to_string(allow).unwrap_or_else(|_| "[]".to_string()))
+            .bind(serde_json::to_string(deny).unwrap_or_else(|_| "[]".to_string()))
+            .bind(serde_json::to_string(ask).unwrap_or_else(|_| "[]".to_string()))
+            .bind(description)
+            .bind(Utc::now())
+            .bind(Utc::now())
+            .execute()
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn get_permission_presets(&self) -> Result<Vec<PermissionPreset>, SqlxError> {
+        self.pool
+            .query("SELECT * FROM permission_presets ORDER BY is_builtin DESC, name ASC")
+            .fetch_all::<PermissionPreset>()
+            .await
+    }
+
+    pub async fn get_permission_preset(&self, id: i64) -> Result<PermissionPreset, SqlxError> {
+        self.pool
+            .query("SELECT * FROM permission_presets WHERE id = ?")
+            .bind(id)
+            .fetch_one::<PermissionPreset>()
+            .await
+    }
+
+    pub async fn create_permission_preset(
+        &self,
+        request: CreatePermissionPresetRequest,
+    ) -> Result<PermissionPreset, SqlxError> {
+        let now = Utc::now();
+        let id = self.pool.query(
+            "INSERT INTO permission_presets (name, default_mode, allow, deny, ask, description, is_builtin, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, FALSE, ?, ?)",
+        )
+        .bind(&request.name)
+        .bind(&request.default_mode)
+        .bind(serde_json::to_string(&request.allow).unwrap_or_else(|_| "[]".to_string()))
+        .bind(serde_json::to_string(&request.deny).unwrap_or_else(|_| "[]".to_string()))
+        .bind(serde_json::to_string(&request.ask).unwrap_or_else(|_| "[]".to_string()))
+        .bind(&request.description)
+        .bind(now)
+        .bind(now)
+        .insert()
+        .await?;
+
+        self.get_permission_preset(id).await
+    }
+
+    pub async fn update_permission_preset(
+        &self,
+        id: i64,
+        request: UpdatePermissionPresetRequest,
+    ) -> Result<PermissionPreset, SqlxError> {
+        let now = Utc::now();
+        let mut updates = Vec::new();
+
+        if request.name.is_some() {
+            updates.push("name = ?");
+        }
+        if request.default_mode.is_some() {
+            updates.push("default_mode = ?");
+        }
+        if request.allow.is_some() {
+            updates.push("allow = ?");
+        }
+        if request.deny.is_some() {
+            updates.push("deny = ?");
+        }
+        if request.ask.is_some() {
+            updates.push("ask = ?");
+        }
+        if request.description.is_some() {
+            updates.push("description = ?");
+        }
+
+        if updates.is_empty() {
+            return self.get_permission_preset(id).await;
+        }
+
+        updates.push("updated_at = ?");
+        let query = format!(
+            "UPDATE permission_presets SET {} WHERE id = ?",
+            updates.join(", ")
+        );
+
+        let mut q = self.pool.query(&query);
+
+        if let Some(name) = &request.name {
+            q = q.bind(name);
+        }
+        if let Some(default_mode) = &request.default_mode {
+            q = q.bind(default_mode);
+        }
+        for rules in [&request.allow, &request.deny, &request.ask]
+            .into_iter()
+            .flatten()
+        {
+            q = q.bind(serde_json::to_string(rules).unwrap_or_else(|_| "[]".to_string()));
+        }
+        if let Some(description) = &request.description {
+            q = q.bind(description);
+        }
+
+        q = q.bind(now).bind(id);
+        q.execute().await?;
+
+        self.get_permission_preset(id).await
+    }
+
+    /// 删除权限预设（内置预设不可删除），并解除目录和账号上的引用
+    pub async fn delete_permission_preset(&self, id: i64) -> Result<(), SqlxError> {
+        let preset = self.get_permission_preset(id).await?;
+        if preset.is_builtin {
+            return Err(SqlxError::Protocol(format!(
+                "内置权限预设 '{}' 不可删除",
+                preset.name
+            )));
+        }
+
+        self.pool
+            .query(
+                "UPDATE directories SET permission_preset_id = NULL WHERE permission_preset_id = ?",
+            )
+            .bind(id)
+            .execute()
+            .await?;
+
+        self.pool
+            .query("UPDATE accounts SET permission_preset_id = NULL WHERE permission_preset_id = ?")
+            .bind(id)
+            .execute()
+            .await?;
+
+        self.pool
+            .query("DELETE FROM permission_presets WHERE id = ?")
+            .bind(id)
+            .execute()
+            .await?;
+
+        info!("成功删除权限预设，ID: {}", id);
+        Ok(())
+    }
+
+    /// 设置目录使用的权限预设，None 表示取消绑定
+    pub async fn set_directory_permission_preset(
+        &self,
+        directory_id: i64,
+        preset_id: Option<i64>,
+    ) -> Result<(), SqlxError> {
+        self.pool
+            .query("UPDATE directories SET permission_preset_id = ?, updated_at = ? WHERE id = ?")
+            .bind(preset_id)
+            .bind(Utc::now())
+            .bind(directory_id)
+            .execute()
+            .await?;
+        Ok(())
+    }
+
+    /// 设置账号使用的权限预设，None 表示取消绑定
+    pub async fn set_account_permission_preset(
+        &self,
+        account_id: i64,
+        preset_id: Option<i64>,
+    ) -> Result<(), SqlxError> {
+        self.pool
+            .query("UPDATE accounts SET permission_preset_id = ?, updated_at = ? WHERE id = ?")
+            .bind(preset_id)
+            .bind(Utc::now())
+            .bind(account_id)
+            .execute()
+            .await?;
+        Ok(())
+    }
+
+    /// 获取切换时使用的权限预设：目录绑定优先，其次是账号绑定
+    pub async fn get_permission_preset_for_switch(
+        &self,
+        account_id: i64,
+        directory_id: i64,
+    ) -> Result<Option<PermissionPreset>, SqlxError> {
+        self.pool.query(
+            r#"
+            SELECT p.* FROM permission_presets p
+            WHERE p.id = (SELECT permission_preset_id FROM directories WHERE id = ?)
+               OR p.id = (SELECT permission_preset_id FROM accounts WHERE id = ?)
+            ORDER BY CASE WHEN p.id = (SELECT permission_preset_id FROM directories WHERE id = ?) THEN 0 ELSE 1 END
+            LIMIT 1
+            "#,
+        )
+        .bind(directory_id)
+        .bind(account_id)
+        .bind(directory_id)
+        .fetch_optional::<PermissionPreset>()
+        .await
+    }
+
+    // Claude definition (slash command / subagent) methods
+    async fn initialize_default_definitions(&self) -> Result<(), SqlxError> {
+        let count: i64 = self
+            .pool
+            .query("SELECT COUNT(*) FROM claude_definitions")
+            .fetch_scalar()
+            .await?;
+
+        if count == 0 {
+            self.import_definitions(crate::definitions::builtin_definitions(), false)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn get_definitions(&self) -> Result<Vec<ClaudeDefinition>, SqlxError> {
+        self.pool
+            .query("SELECT * FROM claude_definitions ORDER BY kind ASC, name ASC")
+            .fetch_all::<ClaudeDefinition>()
+            .await
+    }
+
+    pub async fn get_definition(&self, id: i64) -> Result<ClaudeDefinition, SqlxError> {
+        self.pool
+            .query("SELECT * FROM claude_definitions WHERE id = ?")
+            .bind(id)
+            .fetch_one::<ClaudeDefinition>()
+            .await
+    }
+
+    pub async fn create_definition(
+        &self,
+        request: CreateClaudeDefinitionRequest,
+    ) -> Result<ClaudeDefinition, SqlxError> {
+        let now = Utc::now();
+        let id = self.pool.query(
+            "INSERT INTO claude_definitions (kind, name, description, content, is_default, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&request.kind)
+        .bind(&request.name)
+        .bind(&request.description)
+        .bind(&request.content)
+        .bind(request.is_default.unwrap_or(false))
+        .bind(now)
+        .bind(now)
+        .insert()
+        .await?;
+
+        self.get_definition(id).await
+    }
+
+    pub async fn update_definition(
+        &self,
+        id: i64,
+        request: UpdateClaudeDefinitionRequest,
+    ) -> Result<ClaudeDefinition, SqlxError> {
+        let now = Utc::now();
+        let mut updates = Vec::new();
+
+        if request.name.is_some() {
+            updates.push("name = ?");
+        }
+        if request.description.is_some() {
+            updates.push("description = ?");
+        }
+        if request.content.is_some() {
+            updates.push("content = ?");
+        }
+        if request.is_default.is_some() {
+            updates.push("is_default = ?");
+        }
+
+        if updates.is_empty() {
+            return self.get_definition(id).await;
+        }
+
+        updates.push("updated_at = ?");
+        let query = format!(
+            "UPDATE claude_definitions SET {} WHERE id = ?",
+            updates.join(", ")
+        );
+
+        let mut q = self.pool.query(&query);
+
+        if let Some(name) = &request.name {
+            q = q.bind(name);
+        }
+        if let Some(description) = &request.description {
+            // 空字符串表示清除描述
+            q = q.bind(Some(description.clone()).filter(|d| !d.is_empty()));
+        }
+        if let Some(content) = &request.content {
+            q = q.bind(content);
+        }
+        if let Some(is_default) = request.is_default {
+            q = q.bind(is_default);
+        }
+
+        q = q.bind(now).bind(id);
+        q.execute().await?;
+
+        self.get_definition(id).await
+    }
+
+    pub async fn delete_definition(&self, id: i64) -> Result<(), SqlxError> {
+        self.pool
+            .query("DELETE FROM directory_definitions WHERE definition_id = ?")
+            .bind(id)
+            .execute()
+            .await?;
+
+        let result = self
+            .pool
+            .query("DELETE FROM claude_definitions WHERE id = ?")
+            .bind(id)
+            .execute()
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(SqlxError::RowNotFound);
+        }
+
+        info!("成功删除定义，ID: {}", id);
+        Ok(())
+    }
+
+    /// 获取目录实际启用的定义 ID（目录单独设置优先，否则使用 is_default）
+    pub async fn get_directory_enabled_definition_ids(
+        &self,
+        directory_id: i64,
+    ) -> Result<Vec<i64>, SqlxError> {
+        Ok(self
+            .get_definitions_for_switch(directory_id)
+            .await?
+            .into_iter()
+            .map(|d| d.id)
+            .collect())
+    }
+
+    /// 设置目录启用的定义，仅保存与默认状态不同的记录
+    pub async fn set_directory_definitions(
+        &self,
+        directory_id: i64,
+        enabled_ids: &[i64],
+    ) -> Result<(), SqlxError> {
+        let definitions = self.get_definitions().await?;
+        let mut tx = self.pool.begin().await?;
+
+        tx.query("DELETE FROM directory_definitions WHERE directory_id = ?")
+            .bind(directory_id)
+            .execute()
+            .await?;
+
+        for definition in definitions {
+            let enabled = enabled_ids.contains(&definition.id);
+            if enabled == definition.is_default {
+                continue;
+            }
+            tx.query(
+                "INSERT INTO directory_definitions (directory_id, definition_id, enabled, created_at) VALUES (?, ?, ?, ?)",
+            )
+            .bind(directory_id)
+            .bind(definition.id)
+            .bind(enabled)
+            .bind(Utc::now())
+            .execute()
+            .await?;
+        }
+
+        tx.commit().await
+    }
+
+    /// 获取切换时需要部署到目录的定义
+    pub async fn get_definitions_for_switch(
+        &self,
+        directory_id: i64,
+    ) -> Result<Vec<ClaudeDefinition>, SqlxError> {
+        self.pool
+            .query(
+                r#"
+            SELECT d.* FROM claude_definitions d
+            LEFT JOIN directory_definitions dd
+                ON dd.definition_id = d.id AND dd.directory_id = ?
+            WHERE COALESCE(dd.enabled, d.is_default) = TRUE
+            ORDER BY d.kind ASC, d.name ASC
+            "#,
+            )
+            .bind(directory_id)
+            .fetch_all::<ClaudeDefinition>()
+            .await
+    }
+
+    /// 批量导入定义，返回 (导入数量, 跳过数量)
+    /// overwrite 为 true 时覆盖同名定义的内容和描述
+    pub async fn import_definitions(
+        &self,
+        requests: Vec<CreateClaudeDefinitionRequest>,
+        overwrite: bool,
+    ) -> Result<(usize, usize), SqlxError> {
+        let mut imported = 0;
+        let mut skipped = 0;
+        let mut tx = self.pool.begin().await?;
+
+        for request in requests {
+            let now = Utc::now();
+            let sql = if overwrite {
+                "INSERT INTO claude_definitions (kind, name, description, content, is_default, created_at, updated_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?)
+                 ON CONFLICT(kind, name) DO UPDATE SET
+                     description = excluded.description,
+                     content = excluded.content,
+                     updated_at = excluded.updated_at"
+            } else {
+                "INSERT OR IGNORE INTO claude_definitions (kind, name, description, content, is_default, created_at, updated_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?)"
+            };
+
+            let result = tx
+                .query(sql)
+                .bind(&request.kind)
+                .bind(&request.name)
+                .bind(&request.description)
+                .bind(&request.content)
+                .bind(request.is_default.unwrap_or(false))
+                .bind(now)
+                .bind(now)
+                .execute()
+                .await?;
+
+            if result.rows_affected() > 0 {
+                imported += 1;
+            } else {
+                skipped += 1;
+            }
+        }
+
+        tx.commit().await?;
+        info!("导入定义完成：导入 {} 个，跳过 {} 个", imported, skipped);
+        Ok((imported, skipped))
+    }
+
+    /// 获取目录当前绑定的账号（最近一次切换到该目录的账号）
+    pub async fn get_directory_bound_account(
+        &self,
+        directory_id: i64,
+    ) -> Result<Option<Account>, SqlxError> {
+        self.pool
+            .query(
+                r#"
+            SELECT a.* FROM accounts a
+            JOIN account_directories ad ON ad.account_id = a.id
+            WHERE ad.directory_id = ?
+            ORDER BY ad.created_at DESC, ad.id DESC
+            LIMIT 1
+            "#,
+            )
+            .bind(directory_id)
+            .fetch_optional::<Account>()
+            .await
+    }
+
+    // Discovery methods
+    pub async fn get_discovery_roots(&self) -> Result<Vec<DiscoveryRoot>, SqlxError> {
+        self.pool
+            .query("SELECT * FROM discovery_roots ORDER BY path ASC")
+            .fetch_all::<DiscoveryRoot>()
+            .await
+    }
+
+    pub async fn add_discovery_root(&self, path: &str) -> Result<DiscoveryRoot, SqlxError> {
+        self.pool
+            .query("INSERT OR IGNORE INTO discovery_roots (path, created_at) VALUES (?, ?)")
+            .bind(path)
+            .bind(Utc::now())
+            .execute()
+            .await?;
+
+        self.pool
+            .query("SELECT * FROM discovery_roots WHERE path = ?")
+            .bind(path)
+            .fetch_one::<DiscoveryRoot>()
+            .await
+    }
+
+    pub async fn delete_discovery_root(&self, id: i64) -> Result<(), SqlxError> {
+        let result = self
+            .pool
+            .query("DELETE FROM discovery_roots WHERE id = ?")
+            .bind(id)
+            .execute()
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(SqlxError::RowNotFound);
+        }
+
+        Ok(())
+    }
+
+    /// 按令牌查找账号，用于发现项目时判断令牌是否已登记
+    pub async fn find_account_by_token(&self, token: &str) -> Result<Option<Account>, SqlxError> {
+        self.pool
+            .query("SELECT * FROM accounts WHERE token = ? ORDER BY created_at ASC LIMIT 1")
+            .bind(token)
+            .fetch_optional::<Account>()
+            .await
+    }
+
+    // Endpoint check methods
+    pub async fn create_endpoint_check(
+        &self,
+        request: CreateEndpointCheckRequest,
+    ) -> Result<EndpointCheck, SqlxError> {
+        let id = self.pool.query(
+            "INSERT INTO endpoint_checks (base_url_id, account_id, connect_ms, tls_ms, latency_ms, status_code, auth_status_code, success, error, checked_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(request.base_url_id)
+        .bind(request.account_id)
+        .bind(request.connect_ms)
+        .bind(request.tls_ms)
+        .bind(request.latency_ms)
+        .bind(request.status_code)
+        .bind(request.auth_status_code)
+        .bind(request.success)
+        .bind(&request.error)
+        .bind(Utc::now())
+        .insert()
+        .await?;
+
+        self.pool
+            .query("SELECT * FROM endpoint_checks WHERE id = ?")
+            .bind(id)
+            .fetch_one::<EndpointCheck>()
+            .await
+    }
+
+    /// 获取 API 地址的检查历史（最新的在前）
+    pub async fn get_endpoint_checks(
+        &self,
+        base_url_id: i64,
+        limit: i64,
+    ) -> Result<Vec<EndpointCheck>, SqlxError> {
+        self.pool.query(
+            "SELECT * FROM endpoint_checks WHERE base_url_id = ? ORDER BY checked_at DESC, id DESC LIMIT ?",
+        )
+        .bind(base_url_id)
+        .bind(limit)
+        .fetch_all::<EndpointCheck>()
+        .await
+    }
+
+    /// 获取每个 API 地址最近一次的检查结果
+    pub async fn get_latest_endpoint_checks(&self) -> Result<Vec<EndpointCheck>, SqlxError> {
+        self.pool.query(
+            "SELECT * FROM endpoint_checks WHERE id IN (SELECT MAX(id) FROM endpoint_checks GROUP BY base_url_id)",
+        )
+        .fetch_all::<EndpointCheck>()
+        .await
+    }
+
+    pub async fn get_all_accounts(&self) -> Result<Vec<Account>, SqlxError> {
+        self.pool
+            .query("SELECT * FROM accounts ORDER BY name ASC")
+            .fetch_all::<Account>()
+            .await
+    }
+
+    /// 获取所有账号与目录的绑定关系
+    pub async fn get_account_bindings(&self) -> Result<Vec<AccountBinding>, SqlxError> {
+        self.pool
+            .query(
+                r#"
+            SELECT ad.account_id, a.name AS account_name, d.path AS directory_path
+            FROM account_directories ad
+            JOIN accounts a ON a.id = ad.account_id
+            JOIN directories d ON d.id = ad.directory_id
+            ORDER BY d.path ASC, a.name ASC
+            "#,
+            )
+            .fetch_all::<AccountBinding>()
+            .await
+    }
+
+    /// 添加账号与目录的绑定，已存在时不做修改，返回是否新增
+    pub async fn add_account_binding(
+        &self,
+        account_id: i64,
+        directory_id: i64,
+    ) -> Result<bool, SqlxError> {
+        let result = self.pool.query(
+            "INSERT OR IGNORE INTO account_directories (account_id, directory_id, created_at) VALUES (?, ?, ?)",
+        )
+        .bind(account_id)
+        .bind(directory_id)
+        .bind(Utc::now())
+        .execute()
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    // Balance adapter methods
+    pub async fn get_balance_adapters(&self) -> Result<Vec<BalanceAdapter>, SqlxError> {
+        self.pool
+            .query("SELECT * FROM balance_adapters ORDER BY base_url_id")
+            .fetch_all::<BalanceAdapter>()
+            .await
+    }
+
+    pub async fn get_balance_adapter(
+        &self,
+        base_url_id: i64,
+    ) -> Result<Option<BalanceAdapter>, SqlxError> {
+        self.pool
+            .query("SELECT * FROM balance_adapters WHERE base_url_id = ?")
+            .bind(base_url_id)
+            .fetch_optional::<BalanceAdapter>()
+            .await
+    }
+
+    /// 保存 API 地址的余额查询适配器，已存在时覆盖
+    pub async fn save_balance_adapter(
+        &self,
+        request: SaveBalanceAdapterRequest,
+    ) -> Result<BalanceAdapter, SqlxError> {
+        let now = Utc::now();
+        self.pool.query(
+            r#"
+            INSERT INTO balance_adapters (base_url_id, method, url_template, headers, body_template, balance_path, divisor, unit, enabled, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT(base_url_id) DO UPDATE SET
+                method = excluded.method,
+                url_template = excluded.url_template,
+                headers = excluded.headers,
+                body_template = excluded.body_template,
+                balance_path = excluded.balance_path,
+                divisor = excluded.divisor,
+                unit = excluded.unit,
+                enabled = excluded.enabled,
+                updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(request.base_url_id)
+        .bind(request.method.unwrap_or_else(|| "GET".to_string()).to_uppercase())
+        .bind(&request.url_template)
+        .bind(&request.headers)
+        .bind(&request.body_template)
+        .bind(&request.balance_path)
+        .bind(request.divisor.unwrap_or(1.0))
+        .bind(request.unit.unwrap_or_else(|| "USD".to_string()))
+        .bind(request.enabled.unwrap_or(true))
+        .bind(now)
+        .bind(now)
+        .execute()
+        .await?;
+
+        self.get_balance_adapter(request.base_url_id)
+            .await?
+            .ok_or(SqlxError::RowNotFound)
+    }
+
+    pub async fn delete_balance_adapter(&self, base_url_id: i64) -> Result<(), SqlxError> {
+        self.pool
+            .query("DELETE FROM balance_adapters WHERE base_url_id = ?")
+            .bind(base_url_id)
+            .execute()
+            .await?;
+        Ok(())
+    }
+
+    // Account balance methods
+    pub async fn get_account_balances(&self) -> Result<Vec<AccountBalance>, SqlxError> {
+        self.pool
+            .query("SELECT * FROM account_balances")
+            .fetch_all::<AccountBalance>()
+            .await
+    }
+
+    /// 记录账号余额的查询结果，查询失败时 balance 为空并记录错误
+    pub async fn save_account_balance(
+        &self,
+        account_id: i64,
+        balance: Option<f64>,
+        unit: Option<&str>,
+        error: Option<&str>,
+    ) -> Result<AccountBalance, SqlxError> {
+        self.pool
+            .query(
+                r#"
+            INSERT INTO account_balances (account_id, balance, unit, error, fetched_at)
+            VALUES (?, ?, ?, ?, ?)
+            ON CONFLICT(account_id) DO UPDATE SET
+                balance = excluded.balance,
+                unit = excluded.unit,
+                error = excluded.error,
+                fetched_at = excluded.fetched_at
+            "#,
+            )
+            .bind(account_id)
+            .bind(balance)
+            .bind(unit)
+            .bind(error)
+            .bind(Utc::now())
+            .execute()
+            .await?;
+
+        self.pool
+            .query("SELECT * FROM account_balances WHERE account_id = ?")
+            .bind(account_id)
+            .fetch_one::<AccountBalance>()
+            .await
+    }
+
+    // Account pool methods
+    pub async fn get_account_pools(&self) -> Result<Vec<AccountPool>, SqlxError> {
+        self.pool
+            .query("SELECT * FROM account_pools ORDER BY name ASC")
+            .fetch_all::<AccountPool>()
+            .await
+    }
+
+    pub async fn get_account_pool(&self, id: i64) -> Result<AccountPool, SqlxError> {
+        self.pool
+            .query("SELECT * FROM account_pools WHERE id = ?")
+            .bind(id)
+            .fetch_one::<AccountPool>()
+            .await
+    }
+
+    pub async fn create_account_pool(
+        &self,
+        request: CreateAccountPoolRequest,
+    ) -> Result<AccountPool, SqlxError> {
+        let now = Utc::now();
+        let id = self.pool.query(
+            "INSERT INTO account_pools (name, description, created_at, updated_at) VALUES (?, ?, ?, ?)",
+        )
+        .bind(&request.name)
+        .bind(&request.description)
+        .bind(now)
+        .bind(now)
+        .insert()
+        .await?;
+
+        self.get_account_pool(id).await
+    }
+
+    pub async fn delete_account_pool(&self, id: i64) -> Result<(), SqlxError> {
+        // 解除目录绑定并删除成员
+        self.pool
+            .query("UPDATE directories SET account_pool_id = NULL WHERE account_pool_id = ?")
+            .bind(id)
+            .execute()
+            .await?;
+        self.pool
+            .query("DELETE FROM account_pool_members WHERE pool_id = ?")
+            .bind(id)
+            .execute()
+            .await?;
+
+        let result = self
+            .pool
+            .query("DELETE FROM account_pools WHERE id = ?")
+            .bind(id)
+            .execute()
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(SqlxError::RowNotFound);
+        }
+        Ok(())
+    }
+
+    /// 获取账号池成员，按优先级和权重排序；pool_id 为 None 时返回所有账号池的成员
+    pub async fn get_account_pool_members(
+        &self,
+        pool_id: Option<i64>,
+    ) -> Result<Vec<AccountPoolMember>, SqlxError> {
+        self.pool.query(
+            "SELECT * FROM account_pool_members WHERE ? IS NULL OR pool_id = ? ORDER BY pool_id, priority ASC, weight DESC",
+        )
+        .bind(pool_id)
+        .bind(pool_id)
+        .fetch_all::<AccountPoolMember>()
+        .await
+    }
+
+    /// 添加账号池成员，已存在时更新权重和优先级
+    pub async fn save_account_pool_member(
+        &self,
+        request: SaveAccountPoolMemberRequest,
+    ) -> Result<AccountPoolMember, SqlxError> {
+        self.pool
+            .query(
+                r#"
+            INSERT INTO account_pool_members (pool_id, account_id, weight, priority, created_at)
+            VALUES (?, ?, ?, ?, ?)
+            ON CONFLICT(pool_id, account_id) DO UPDATE SET
+                weight = excluded.weight,
+                priority = excluded.priority
+            "#,
+            )
+            .bind(request.pool_id)
+            .bind(request.account_id)
+            .bind(request.weight.unwrap_or(100).max(0))
+            .bind(request.priority.unwrap_or(1))
+            .bind(Utc::now())
+            .execute()
+            .await?;
+
+        self.pool
+            .query("SELECT * FROM account_pool_members WHERE pool_id = ? AND account_id = ?")
+            .bind(request.pool_id)
+            .bind(request.account_id)
+            .fetch_one::<AccountPoolMember>()
+            .await
+    }
+
+    pub async fn remove_account_pool_member(
+        &self,
+        pool_id: i64,
+        account_id: i64,
+    ) -> Result<(), SqlxError> {
+        self.pool
+            .query("DELETE FROM account_pool_members WHERE pool_id = ? AND account_id = ?")
+            .bind(pool_id)
+            .bind(account_id)
+            .execute()
+            .await?;
+        Ok(())
+    }
+
+    /// 设置目录绑定的账号池，None 表示取消绑定
+    pub async fn set_directory_account_pool(
+        &self,
+        directory_id: i64,
+        pool_id: Option<i64>,
+    ) -> Result<(), SqlxError> {
+        self.pool
+            .query("UPDATE directories SET account_pool_id = ?, updated_at = ? WHERE id = ?")
+            .bind(pool_id)
+            .bind(Utc::now())
+            .bind(directory_id)
+            .execute()
+            .await?;
+        Ok(())
+    }
+
+    /// 保存一次账号池故障切换
+    pub async fn create_pool_failover(&self, failover: &PoolFailover) -> Result<(), SqlxError> {
+        self.pool.query(
+            "INSERT INTO pool_failovers (pool_id, directory_id, pool_name, directory_name, from_account, to_account, error, created_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(failover.pool_id)
+        .bind(failover.directory_id)
+        .bind(&failover.pool_name)
+        .bind(&failover.directory_name)
+        .bind(&failover.from_account)
+        .bind(&failover.to_account)
+        .bind(&failover.error)
+        .bind(Utc::now())
+        .insert()
+        .await?;
+        Ok(())
+    }
+
+    /// 获取最近的故障切换记录（最新的在前），pool_id 为 None 时返回所有账号池的记录
+    pub async fn get_pool_failovers(
+        &self,
+        pool_id: Option<i64>,
+        limit: i64,
+    ) -> Result<Vec<PoolFailoverRecord>, SqlxError> {
+        match pool_id {
+            Some(pool_id) => self.pool.query(
+                "SELECT * FROM pool_failovers WHERE pool_id = ? ORDER BY created_at DESC, id DESC LIMIT ?",
+            )
+            .bind(pool_id)
+            .bind(limit)
+            .fetch_all::<PoolFailoverRecord>()
+            .await,
+            None => self.pool.query(
+                "SELECT * FROM pool_failovers ORDER BY created_at DESC, id DESC LIMIT ?",
+            )
+            .bind(limit)
+            .fetch_all::<PoolFailoverRecord>()
+            .await,
+        }
+    }
+
+    // Relay proxy methods
+    pub async fn get_relay_stats(&self) -> Result<Vec<RelayStat>, SqlxError> {
+        self.pool
+            .query("SELECT * FROM relay_stats ORDER BY request_count DESC")
+            .fetch_all::<RelayStat>()
+            .await
+    }
+
+    /// 记录一次经本地中转代理转发的请求
+    pub async fn record_relay_request(
+        &self,
+        account_id: i64,
+        status: Option<i64>,
+        latency_ms: i64,
+        success: bool,
+    ) -> Result<(), SqlxError> {
+        self.pool.query(
+            r#"
+            INSERT INTO relay_stats (account_id, request_count, failure_count, total_latency_ms, last_status, last_latency_ms, last_used_at)
+            VALUES (?, 1, ?, ?, ?, ?, ?)
+            ON CONFLICT(account_id) DO UPDATE SET
+                request_count = relay_stats.request_count + 1,
+                failure_count = relay_stats.failure_count + excluded.failure_count,
+                total_latency_ms = relay_stats.total_latency_ms + excluded.total_latency_ms,
+                last_status = excluded.last_status,
+                last_latency_ms = excluded.last_latency_ms,
+                last_used_at = excluded.last_used_at
+            "#,
+        )
+        .bind(account_id)
+        .bind(if success { 0 } else { 1 })
+        .bind(latency_ms)
+        .bind(status)
+        .bind(latency_ms)
+        .bind(Utc::now())
+        .execute()
+        .await?;
+        Ok(())
+    }
+
+    /// 设置目录通过本地中转代理访问的端口，None 表示直连
+    pub async fn set_directory_relay_port(
+        &self,
+        directory_id: i64,
+        port: Option<i64>,
+    ) -> Result<(), SqlxError> {
+        self.pool
+            .query("UPDATE directories SET relay_port = ?, updated_at = ? WHERE id = ?")
+            .bind(port)
+            .bind(Utc::now())
+            .bind(directory_id)
+            .execute()
+            .await?;
+        Ok(())
+    }
+
+    // Proxy methods
+    pub async fn get_proxies(&self) -> Result<Vec<Proxy>, SqlxError> {
+        self.pool
+            .query("SELECT * FROM proxies ORDER BY name ASC")
+            .fetch_all::<Proxy>()
+            .await
+    }
+
+    pub async fn get_proxy(&self, id: i64) -> Result<Proxy, SqlxError> {
+        self.pool
+            .query("SELECT * FROM proxies WHERE id = ?")
+            .bind(id)
+            .fetch_one::<Proxy>()
+            .await
+    }
+
+    pub async fn create_proxy(&self, request: CreateProxyRequest) -> Result<Proxy, SqlxError> {
+        let now = Utc::now();
+        let id = self.pool.query(
+            "INSERT INTO proxies (name, url, no_proxy, username, password, description, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&request.name)
+        .bind(&request.url)
+        .bind(&request.no_proxy)
+        .bind(&request.username)
+        .bind(&request.password)
+        .bind(&request.description)
+        .bind(now)
+        .bind(now)
+        .insert()
+        .await?;
+
+        info!("成功创建代理: {}", request.name);
+        self.get_proxy(id).await
+    }
+
+    pub async fn update_proxy(
+        &self,
+        id: i64,
+        request: UpdateProxyRequest,
+    ) -> Result<Proxy, SqlxError> {
+        let fields = [
+            ("name", &request.name),
+            ("url", &request.url),
+            ("no_proxy", &request.no_proxy),
+            ("username", &request.username),
+            ("password", &request.password),
+            ("description", &request.description),
+        ];
+        let updates: Vec<String> = fields
+            .iter()
+            .filter(|(_, value)| value.is_some())
+            .map(|(column, _)| format!("{} = ?", column))
+            .collect();
+
+        if updates.is_empty() {
+            return self.get_proxy(id).await;
+        }
+
+        let query = format!(
+            "UPDATE proxies SET {}, updated_at = ? WHERE id = ?",
+            updates.join(", ")
+        );
+        let mut q = self.pool.query(&query);
+        for value in fields.iter().filter_map(|(_, value)| value.as_ref()) {
+            q = q.bind(value);
+        }
+        q.bind(Utc::now()).bind(id).execute().await?;
+
+        self.get_proxy(id).await
+    }
+
+    /// 删除代理，并解除 API 地址、账号、目录和 WebDAV 配置上的引用
+    pub async fn delete_proxy(&self, id: i64) -> Result<(), SqlxError> {
+        for table in ["base_urls", "accounts", "directories", "webdav_configs"] {
+            self.pool
+                .query(format!(
+                    "UPDATE {} SET proxy_id = NULL WHERE proxy_id = ?",
+                    table
+                ))
+                .bind(id)
+                .execute()
+                .await?;
+        }
+
+        self.pool
+            .query("DELETE FROM proxies WHERE id = ?")
+            .bind(id)
+            .execute()
+            .await?;
+
+        info!("成功删除代理，ID: {}", id);
+        Ok(())
+    }
+
+    /// 设置 API 地址使用的代理，None 表示取消绑定
+    pub async fn set_base_url_proxy(
+        &self,
+        base_url_id: i64,
+        proxy_id: Option<i64>,
+    ) -> Result<(), SqlxError> {
+        self.pool
+            .query("UPDATE base_urls SET proxy_id = ?, updated_at = ? WHERE id = ?")
+            .bind(proxy_id)
+            .bind(Utc::now())
+            .bind(base_url_id)
+            .execute()
+            .await?;
+        Ok(())
+    }
+
+    /// 设置账号使用的代理，None 表示取消绑定
+    pub async fn set_account_proxy(
+        &self,
+        account_id: i64,
+        proxy_id: Option<i64>,
+    ) -> Result<(), SqlxError> {
+        self.pool
+            .query("UPDATE accounts SET proxy_id = ?, updated_at = ? WHERE id = ?")
+            .bind(proxy_id)
+            .bind(Utc::now())
+            .bind(account_id)
+            .execute()
+            .await?;
+        Ok(())
+    }
+
+    /// 设置账号的凭据模式，helper 模式下切换时写入 apiKeyHelper 而不是令牌
+    pub async fn set_account_credential(
+        &self,
+        account_id: i64,
+        mode: &str,
+        api_key_helper: Option<&str>,
+        ttl_ms: Option<i64>,
+    ) -> Result<Account, SqlxError> {
+        self.pool.query(
+            "UPDATE accounts SET credential_mode = ?, api_key_helper = ?, api_key_helper_ttl_ms = ?, updated_at = ? WHERE id = ?",
+        )
+        .bind(mode)
+        .bind(api_key_helper.map(str::trim).filter(|c| !c.is_empty()))
+        .bind(ttl_ms)
+        .bind(Utc::now())
+        .bind(account_id)
+        .execute()
+        .await?;
+        self.get_account(account_id).await
+    }
+
+    /// 保存 API 地址的自定义请求头
+    pub async fn set_base_url_headers(
+        &self,
+        base_url_id: i64,
+        headers: &[CustomHeader],
+    ) -> Result<(), SqlxError> {
+        let json = serde_json::to_string(headers).unwrap_or_else(|_| "[]".to_string());
+        self.pool
+            .query("UPDATE base_urls SET custom_headers = ?, updated_at = ? WHERE id = ?")
+            .bind(json)
+            .bind(Utc::now())
+            .bind(base_url_id)
+            .execute()
+            .await?;
+        Ok(())
+    }
+
+    /// 保存 API 地址的默认环境变量，导入时使用
+    pub async fn set_base_url_default_env_vars(
+        &self,
+        base_url_id: i64,
+        env: &std::collections::HashMap<String, String>,
+    ) -> Result<(), SqlxError> {
+        let json = serde_json::to_string(env).unwrap_or_else(|_| "{}".to_string());
+        self.pool
+            .query("UPDATE base_urls SET default_env_vars = ?, updated_at = ? WHERE id = ?")
+            .bind(json)
+            .bind(Utc::now())
+            .bind(base_url_id)
+            .execute()
+            .await?;
+        Ok(())
+    }
+
+    /// 保存账号的自定义请求头，同名请求头覆盖 API 地址上的设置
+    pub async fn set_account_headers(
+        &self,
+        account_id: i64,
+        headers: &[CustomHeader],
+    ) -> Result<(), SqlxError> {
+        let json = serde_json::to_string(headers).unwrap_or_else(|_| "[]".to_string());
+        self.pool
+            .query("UPDATE accounts SET custom_headers = ?, updated_at = ? WHERE id = ?")
+            .bind(json)
+            .bind(Utc::now())
+            .bind(account_id)
+            .execute()
+            .await?;
+        Ok(())
+    }
+
+    /// 设置目录使用的代理，None 表示取消绑定
+    pub async fn set_directory_proxy(
+        &self,
+        directory_id: i64,
+        proxy_id: Option<i64>,
+    ) -> Result<(), SqlxError> {
+        self.pool
+            .query("UPDATE directories SET proxy_id = ?, updated_at = ? WHERE id = ?")
+            .bind(proxy_id)
+            .bind(Utc::now())
+            .bind(directory_id)
+            .execute()
+            .await?;
+        Ok(())
+    }
+
+    /// 获取账号使用的代理：目录绑定优先，其次是账号绑定，最后是账号 API 地址上的绑定
+    /// directory_id 为 None 时（如令牌校验）只看账号和 API 地址
+    pub async fn get_proxy_for_switch(
+        &self,
+        account_id: i64,
+        directory_id: Option<i64>,
+    ) -> Result<Option<Proxy>, SqlxError> {
+        self.pool.query(
+            r#"
+            SELECT * FROM proxies WHERE id = COALESCE(
+                (SELECT proxy_id FROM directories WHERE id = ?),
+                (SELECT proxy_id FROM accounts WHERE id = ?),
+                (SELECT b.proxy_id FROM base_urls b JOIN accounts a ON a.base_url = b.url WHERE a.id = ?)
+            )
+            "#,
+        )
+        .bind(directory_id)
+        .bind(account_id)
+        .bind(account_id)
+        .fetch_optional::<Proxy>()
+        .await
+    }
+
+    /// 获取 API 地址绑定的代理
+    pub async fn get_proxy_for_base_url(
+        &self,
+        base_url_id: i64,
+    ) -> Result<Option<Proxy>, SqlxError> {
+        self.pool
+            .query("SELECT * FROM proxies WHERE id = (SELECT proxy_id FROM base_urls WHERE id = ?)")
+            .bind(base_url_id)
+            .fetch_optional::<Proxy>()
+            .await
+    }
+}
+
+/// 距今指定天数的时间，天数超出时间可表示的范围时返回错误
+fn days_from_now(days: i64) -> Result<DateTime<Utc>, SqlxError> {
+    chrono::TimeDelta::try_days(days)
+        .and_then(|delta| Utc::now().checked_add_signed(delta))
+        .ok_or_else(|| SqlxError::InvalidArgument(format!("天数超出范围: {}", days)))
+}
+
+/// 转义 LIKE 模式中的通配符，配合 ESCAPE '!' 使用（各数据库对反斜杠的处理不同）
+fn escape_like(value: &str) -> String {
+    value
+        .replace('!', "!!")
+        .replace('%', "!%")
+        .replace('_', "!_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db_backend::tests::test_pools;
+
+    /// 测试用的数据库：每个后端一个，已执行迁移
+    async fn test_databases(schema: &str) -> Vec<Database> {
+        let mut databases = Vec::new();
+        for pool in test_pools(schema).await {
+            let db = Database { pool };
+            db.migrate().await.unwrap();
+            databases.push(db);
+        }
+        databases
+    }
+
+    fn account_request(name: &str) -> CreateAccountRequest {
+        CreateAccountRequest {
+            name: name.to_string(),
+            token: format!("sk-{}", name),
+            base_url: "https://api.anthropic.com".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn expires_in(days: i64) -> Option<String> {
+        Some(
+            (Utc::now() + chrono::Duration::days(days))
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string(),
+        )
+    }
+
+    fn names(accounts: &[Account]) -> Vec<&str> {
+        let mut names: Vec<&str> = accounts.iter().map(|a| a.name.as_str()).collect();
+        names.sort();
+        names
+    }
+
+    async fn filtered(db: &Database, request: GetAccountsRequest) -> Vec<Account> {
+        db.get_accounts(GetAccountsRequest {
+            per_page: Some(100),
+            ..request
+        })
+        .await
+        .unwrap()
+        .accounts
+    }
+
+    #[tokio::test]
+    async fn test_account_lifecycle_filters() {
+        for db in test_databases("test_account_lifecycle_filters").await {
+            db.create_account(CreateAccountRequest {
+                tags: Some(vec!["team-a, prod".to_string()]),
+                ..account_request("active")
+            })
+            .await
+            .unwrap();
+            db.create_account(CreateAccountRequest {
+                enabled: Some(false),
+                expires_at: expires_in(-1),
+                ..account_request("disabled")
+            })
+            .await
+            .unwrap();
+            let expired = db
+                .create_account(CreateAccountRequest {
+                    expires_at: expires_in(-2),
+                    ..account_request("expired")
+                })
+                .await
+                .unwrap();
+            let expiring = db
+                .create_account(CreateAccountRequest {
+                    expires_at: expires_in(3),
+                    tags: Some(vec!["production".to_string()]),
+                    ..account_request("expiring")
+                })
+                .await
+                .unwrap();
+            db.create_account(CreateAccountRequest {
+                expires_at: expires_in(30),
+                ..account_request("later")
+            })
+            .await
+            .unwrap();
+
+            assert!(expired.is_expired());
+            assert!(!expiring.is_expired());
+            assert_eq!(expiring.days_until_expiry(), Some(2));
+
+            let enabled = filtered(
+                &db,
+                GetAccountsRequest {
+                    enabled: Some(true),
+                    ..Default::default()
+                },
+            )
+            .await;
+            assert_eq!(
+                names(&enabled),
+                vec!["active", "expired", "expiring", "later"]
+            );
+
+            let disabled = filtered(
+                &db,
+                GetAccountsRequest {
+                    enabled: Some(false),
+                    ..Default::default()
+                },
+            )
+            .await;
+            assert_eq!(names(&disabled), vec!["disabled"]);
+
+            // 已过期的账号同样算作即将到期
+            let expiring_soon = filtered(
+                &db,
+                GetAccountsRequest {
+                    expiring_within_days: Some(EXPIRY_WARNING_DAYS),
+                    ..Default::default()
+                },
+            )
+            .await;
+            assert_eq!(
+                names(&expiring_soon),
+                vec!["disabled", "expired", "expiring"]
+            );
+
+            // 启动提示只包含已启用的账号，按到期时间排序
+            let warnings = db.get_expiring_accounts(EXPIRY_WARNING_DAYS).await.unwrap();
+            let warning_names: Vec<&str> = warnings.iter().map(|a| a.name.as_str()).collect();
+            assert_eq!(warning_names, vec!["expired", "expiring"]);
+
+            // 标签按整词匹配
+            let tagged = filtered(
+                &db,
+                GetAccountsRequest {
+                    tag: Some("prod".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await;
+            assert_eq!(names(&tagged), vec!["active"]);
+
+            // 标签中的 _ 和 % 按字面匹配，不作为通配符
+            for wildcard in ["team_a", "%"] {
+                let tagged = filtered(
+                    &db,
+                    GetAccountsRequest {
+                        tag: Some(wildcard.to_string()),
+                        ..Default::default()
+                    },
+                )
+                .await;
+                assert!(tagged.is_empty(), "{}", wildcard);
+            }
+
+            // 超出范围的天数返回错误而不是 panic
+            assert!(db
+                .get_accounts(GetAccountsRequest {
+                    expiring_within_days: Some(i64::MAX),
+                    ..Default::default()
+                })
+                .await
+                .is_err());
+            assert!(db.get_expiring_accounts(i64::MAX).await.is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_recent_use_ordering_for_pickers() {
+        for db in test_databases("test_recent_use_ordering_for_pickers").await {
+            let relay = db
+                .create_base_url(CreateBaseUrlRequest {
+                    name: "relay".to_string(),
+                    url: "https://relay.example.com".to_string(),
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+            let alpha = db.create_account(account_request("alpha")).await.unwrap();
+            let beta = db
+                .create_account(CreateAccountRequest {
+                    base_url: relay.url.clone(),
+                    ..account_request("beta")
+                })
+                .await
+                .unwrap();
+            db.create_account(CreateAccountRequest {
+                enabled: Some(false),
+                ..account_request("charlie")
+            })
+            .await
+            .unwrap();
+            db.create_account(account_request("delta")).await.unwrap();
+
+            let mut directories = Vec::new();
+            for name in ["one", "two", "three"] {
+                let directory = db
+                    .create_directory(CreateDirectoryRequest {
+                        path: format!("/projects/{}", name),
+                        name: name.to_string(),
+                    })
+                    .await
+                    .unwrap();
+                directories.push(directory);
+            }
+
+            for (account, directory) in [
+                (&alpha, &directories[0]),
+                (&beta, &directories[1]),
+                (&alpha, &directories[1]),
+            ] {
+                db.switch_account(SwitchAccountRequest {
+                    account_id: account.id,
+                    directory_id: directory.id,
+                })
+                .await
+                .unwrap();
+                tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            }
+
+            // 最近使用的在前，从未使用的按名称排在最后
+            let accounts = db.get_accounts_by_recent_use(true).await.unwrap();
+            let account_names: Vec<&str> = accounts.iter().map(|a| a.name.as_str()).collect();
+            assert_eq!(account_names, vec!["alpha", "beta", "delta"]);
+            let accounts = db.get_accounts_by_recent_use(false).await.unwrap();
+            let account_names: Vec<&str> = accounts.iter().map(|a| a.name.as_str()).collect();
+            assert_eq!(account_names, vec!["alpha", "beta", "charlie", "delta"]);
+
+            let recent = db.get_directories_by_recent_use().await.unwrap();
+            let directory_names: Vec<&str> = recent.iter().map(|d| d.name.as_str()).collect();
+            assert_eq!(directory_names, vec!["two", "one", "three"]);
+
+            // 目录预选最近一次切换到的账号
+            assert_eq!(
+                db.get_last_account_for_directory(directories[1].id)
+                    .await
+                    .unwrap(),
+                Some(alpha.id)
+            );
+            assert_eq!(
+                db.get_last_account_for_directory(directories[2].id)
+                    .await
+                    .unwrap(),
+                None
+            );
+
+            let base_urls = db.get_base_urls_by_recent_use().await.unwrap();
+            let urls: Vec<&str> = base_urls.iter().map(|u| u.url.as_str()).collect();
+            assert_eq!(
+                &urls[..2],
+                &["https://api.anthropic.com", "https://relay.example.com"]
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_account_and_base_url_crud() {
+        for db in test_databases("test_account_and_base_url_crud").await {
+            let backend = db.pool.backend().name();
+            let base_url = db
+                .create_base_url(CreateBaseUrlRequest {
+                    name: "relay".to_string(),
+                    url: "https://relay.example.com".to_string(),
+                    description: Some("中转".to_string()),
+                    api_key: Some("ANTHROPIC_AUTH_TOKEN".to_string()),
+                    is_default: Some(true),
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+            assert_eq!(base_url.api_key, "ANTHROPIC_AUTH_TOKEN", "{}", backend);
+            // 新的默认地址取消其他地址的默认标记
+            let defaults: Vec<String> = db
+                .get_base_urls()
+                .await
+                .unwrap()
+                .into_iter()
+                .filter(|b| b.is_default)
+                .map(|b| b.name)
+                .collect();
+            assert_eq!(defaults, vec!["relay".to_string()], "{}", backend);
+
+            let base_url = db
+                .update_base_url(
+                    base_url.id,
+                    UpdateBaseUrlRequest {
+                        name: Some("relay-2".to_string()),
+                        ..Default::default()
+                    },
+                )
+                .await
+                .unwrap();
+            assert_eq!(base_url.name, "relay-2", "{}", backend);
+            assert_eq!(base_url.url, "https://relay.example.com", "{}", backend);
+
+            let account = db
+                .create_account(CreateAccountRequest {
+                    base_url: base_url.url.clone(),
+                    custom_env_vars: Some(serde_json::json!({"ANTHROPIC_MODEL": "opus"})),
+                    ..account_request("main")
+                })
+                .await
+                .unwrap();
+            assert_eq!(db.get_account(account.id).await.unwrap().token, "sk-main");
+
+            let account = db
+                .update_account(
+                    account.id,
+                    UpdateAccountRequest {
+                        token: Some("sk-rotated".to_string()),
+                        notes: Some("轮换过".to_string()),
+                        ..Default::default()
+                    },
+                )
+                .await
+                .unwrap();
+            assert_eq!(account.token, "sk-rotated", "{}", backend);
+            assert_eq!(account.name, "main", "{}", backend);
+            assert_eq!(
+                account.get_custom_env_vars().unwrap()["ANTHROPIC_MODEL"],
+                "opus",
+                "{}",
+                backend
+            );
+            assert_eq!(
+                db.find_account_by_token("sk-rotated")
+                    .await
+                    .unwrap()
+                    .map(|a| a.id),
+                Some(account.id),
+                "{}",
+                backend
+            );
+
+            // 删除 Base URL 时一并删除使用它的账号
+            db.delete_base_url(base_url.id).await.unwrap();
+            assert!(
+                matches!(
+                    db.get_account(account.id).await,
+                    Err(SqlxError::RowNotFound)
+                ),
+                "{}",
+                backend
+            );
+            assert!(
+                matches!(
+                    db.get_base_url(base_url.id).await,
+                    Err(SqlxError::RowNotFound)
+                ),
+                "{}",
+                backend
+            );
+            assert!(
+                matches!(
+                    db.delete_account(account.id).await,
+                    Err(SqlxError::RowNotFound)
+                ),
+                "{}",
+                backend
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_directory_crud_and_associations() {
+        for db in test_databases("test_directory_crud_and_associations").await {
+            let backend = db.pool.backend().name();
+            let first = db.create_account(account_request("first")).await.unwrap();
+            let second = db.create_account(account_request("second")).await.unwrap();
+            let directory = db
+                .create_directory(CreateDirectoryRequest {
+                    path: "/nonexistent/claude-config/project".to_string(),
+                    name: "project".to_string(),
+                })
+                .await
+                .unwrap();
+            let directory = db
+                .update_directory(
+                    directory.id,
+                    UpdateDirectoryRequest {
+                        name: Some("renamed".to_string()),
+                        ..Default::default()
+                    },
+                )
+                .await
+                .unwrap();
+            assert_eq!(directory.name, "renamed", "{}", backend);
+            assert_eq!(
+                directory.path, "/nonexistent/claude-config/project",
+                "{}",
+                backend
+            );
+
+            for account in [&first, &second] {
+                db.switch_account(SwitchAccountRequest {
+                    account_id: account.id,
+                    directory_id: directory.id,
+                })
+                .await
+                .unwrap();
+            }
+            let active: Vec<String> = db
+                .get_all_accounts()
+                .await
+                .unwrap()
+                .into_iter()
+                .filter(|a| a.is_active)
+                .map(|a| a.name)
+                .collect();
+            assert_eq!(active, vec!["second".to_string()], "{}", backend);
+            assert_eq!(
+                db.get_directory_bound_account(directory.id)
+                    .await
+                    .unwrap()
+                    .map(|a| a.id),
+                Some(second.id),
+                "{}",
+                backend
+            );
+            assert!(db.get_directory(directory.id).await.unwrap().is_active);
+
+            // 已存在的绑定不重复添加
+            assert!(!db
+                .add_account_binding(first.id, directory.id)
+                .await
+                .unwrap());
+            let bindings: Vec<String> = db
+                .get_account_bindings()
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|b| format!("{}@{}", b.account_name, b.directory_path))
+                .collect();
+            assert_eq!(
+                bindings,
+                vec![
+                    "first@/nonexistent/claude-config/project".to_string(),
+                    "second@/nonexistent/claude-config/project".to_string(),
+                ],
+                "{}",
+                backend
+            );
+
+            // 删除账号同时删除其绑定，删除目录同时删除剩余绑定
+            db.delete_account(first.id).await.unwrap();
+            assert_eq!(db.get_account_bindings().await.unwrap().len(), 1);
+            db.delete_directory(directory.id).await.unwrap();
+            assert!(db.get_account_bindings().await.unwrap().is_empty());
+            assert!(
+                matches!(
+                    db.get_directory(directory.id).await,
+                    Err(SqlxError::RowNotFound)
+                ),
+                "{}",
+                backend
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_claude_settings_and_webdav_crud() {
+        for db in test_databases("test_claude_settings_and_webdav_crud").await {
+            let backend = db.pool.backend().name();
+            // 没有保存过设置时返回默认设置
+            let defaults: serde_json::Value =
+                serde_json::from_str(&db.get_claude_settings().await.unwrap()).unwrap();
+            assert_eq!(defaults["permissions"]["defaultMode"], "bypassPermissions");
+
+            db.save_claude_settings(r#"{"env":{"A":"1"}}"#)
+                .await
+                .unwrap();
+            db.save_claude_settings(r#"{"env":{"A":"2"}}"#)
+                .await
+                .unwrap();
+            assert_eq!(
+                db.get_claude_settings().await.unwrap(),
+                r#"{"env":{"A":"2"}}"#,
+                "{}",
+                backend
+            );
+            let rows: i64 = db
+                .pool
+                .query("SELECT COUNT(*) FROM claude_settings")
+                .fetch_scalar()
+                .await
+                .unwrap();
+            assert_eq!(rows, 1, "{}", backend);
+
+            let config = crate::webdav::create_webdav_config(
+                &db.pool,
+                "backup",
+                "https://dav.example.com",
+                "user",
+                "secret",
+                "/claude-config",
+                true,
+                3600,
+            )
+            .await
+            .unwrap();
+            assert!(config.auto_sync, "{}", backend);
+            assert_eq!(config.sync_interval, 3600, "{}", backend);
+            assert!(config.last_sync_at.is_none(), "{}", backend);
+
+            crate::webdav::create_sync_log(
+                &db.pool,
+                CreateSyncLogRequest {
+                    webdav_config_id: config.id,
+                    sync_type: "upload".to_string(),
+                    status: "success".to_string(),
+                    message: Some("ok".to_string()),
+                },
+            )
+            .await
+            .unwrap();
+            crate::webdav::update_last_sync_time(&db.pool, config.id)
+                .await
+                .unwrap();
+            let config = crate::webdav::get_webdav_config_by_id(&db.pool, config.id)
+                .await
+                .unwrap()
+                .unwrap();
+            assert!(config.last_sync_at.is_some(), "{}", backend);
+            let logs: Vec<(String, String)> = db
+                .pool
+                .query("SELECT sync_type, status FROM sync_logs WHERE webdav_config_id = ?")
+                .bind(config.id)
+                .fetch_all()
+                .await
+                .unwrap();
+            assert_eq!(
+                logs,
+                vec![("upload".to_string(), "success".to_string())],
+                "{}",
+                backend
+            );
+
+            crate::webdav::delete_webdav_config(&db.pool, config.id)
+                .await
+                .unwrap();
+            assert!(crate::webdav::get_webdav_configs(&db.pool)
+                .await
+                .unwrap()
+                .is_empty());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_switch_connection_keeps_old_pool_on_failure() {
+        let root =
+            std::env::temp_dir().join(format!("claude-config-switch-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+        // 只读打开的空库无法执行迁移
+        std::fs::write(root.join("readonly.db"), b"").unwrap();
+        let config_file = root.join("config.json");
+        std::fs::write(
+            &config_file,
+            serde_json::json!({
+                "connections": {
+                    "default": {"url": "sqlite:///claude_config.db"},
+                    "readonly": {"url": format!("sqlite:///{}?mode=ro", root.join("readonly.db").display())},
+                    "other": {"url": format!("sqlite:///{}?mode=rwc", root.join("other.db").display())},
+                },
+                "current": "default",
+                "extra": true,
+            })
+            .to_string(),
+        )
+        .unwrap();
+        let mut config_manager = ConfigManager::new();
+        config_manager.load_from_file(&config_file).unwrap();
+        let current = |config_file: &std::path::Path| -> serde_json::Value {
+            serde_json::from_str::<serde_json::Value>(
+                &std::fs::read_to_string(config_file).unwrap(),
+            )
+            .unwrap()["current"]
+                .clone()
+        };
+
+        let mut db = test_databases("test_switch_connection_keeps_old_pool_on_failure")
+            .await
+            .remove(0);
+        db.create_account(account_request("kept")).await.unwrap();
+
+        assert!(Database::open_connection(&mut config_manager, "readonly")
+            .await
+            .is_err());
+        assert_eq!(names(&db.get_all_accounts().await.unwrap()), vec!["kept"]);
+        assert_eq!(config_manager.config.current, "default");
+        assert_eq!(current(&config_file), "default");
+
+        let other = Database::open_connection(&mut config_manager, "other")
+            .await
+            .unwrap();
+        db.replace_connection(other).close().await;
+        assert!(db.get_all_accounts().await.unwrap().is_empty());
+        assert_eq!(config_manager.config.current, "other");
+        assert_eq!(current(&config_file), "other");
+        db.pool.close().await;
+
+        // 写回配置文件失败时不改动内存中的当前连接
+        std::fs::remove_file(&config_file).unwrap();
+        assert!(config_manager.set_default_connection("default").is_err());
+        assert_eq!(config_manager.config.current, "other");
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
 }
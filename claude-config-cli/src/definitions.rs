@@ -0,0 +1,253 @@
+use crate::models::{Account, ClaudeDefinition, CreateClaudeDefinitionRequest, Directory};
+use anyhow::Result;
+use include_dir::{include_dir, Dir};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+// 在编译时嵌入内置的 commands 目录，作为命令库的初始内容
+static COMMANDS_DIR: Dir = include_dir!("$CARGO_MANIFEST_DIR/resources/config/commands");
+
+/// 支持的定义类型：斜杠命令和子代理
+pub const DEFINITION_KINDS: [&str; 2] = ["command", "agent"];
+
+/// 定义类型对应的 .claude 子目录
+pub fn definition_subdir(kind: &str) -> &'static str {
+    match kind {
+        "agent" => "agents",
+        _ => "commands",
+    }
+}
+
+/// 校验定义的类型、名称和内容
+/// 命令名称可以包含 `/` 作为命名空间（对应子目录），子代理名称不允许
+pub fn validate_definition(kind: &str, name: &str, content: &str) -> Result<(), String> {
+    if !DEFINITION_KINDS.contains(&kind) {
+        return Err(format!("不支持的定义类型: {}", kind));
+    }
+    if name.trim().is_empty() {
+        return Err("名称不能为空".to_string());
+    }
+    if kind == "agent" && name.contains('/') {
+        return Err("子代理名称不能包含 /".to_string());
+    }
+    let valid = name.split('/').all(|segment| {
+        !segment.is_empty()
+            && segment != "."
+            && segment != ".."
+            && segment
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '-' || c == '_' || c == '.')
+    });
+    if !valid {
+        return Err(format!("无效的名称: {}", name));
+    }
+    if content.trim().is_empty() {
+        return Err("内容不能为空".to_string());
+    }
+    Ok(())
+}
+
+/// 拆分 Markdown 的 front-matter 和正文，没有 front-matter 时返回空字符串
+pub fn split_front_matter(content: &str) -> (&str, &str) {
+    let rest = match content
+        .strip_prefix("---\r\n")
+        .or_else(|| content.strip_prefix("---\n"))
+    {
+        Some(rest) => rest,
+        None => return ("", content),
+    };
+
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == "---" {
+            return (&rest[..offset], &rest[offset + line.len()..]);
+        }
+        offset += line.len();
+    }
+
+    ("", content)
+}
+
+/// 读取 front-matter 中的单个字段（仅支持 `key: value` 形式）
+pub fn front_matter_value(content: &str, key: &str) -> Option<String> {
+    let (front_matter, _) = split_front_matter(content);
+    front_matter.lines().find_map(|line| {
+        let (k, v) = line.split_once(':')?;
+        if k.trim() != key {
+            return None;
+        }
+        let v = v.trim().trim_matches('"').trim_matches('\'');
+        Some(v.to_string()).filter(|v| !v.is_empty())
+    })
+}
+
+/// 替换 `{{KEY}}` 形式的占位符，未知的占位符（如 `{{PROMPT}}`）保持不变
+pub fn render_template(content: &str, vars: &HashMap<String, String>) -> String {
+    let mut rendered = content.to_string();
+    for (key, value) in vars {
+        rendered = rendered.replace(&format!("{{{{{}}}}}", key), value);
+    }
+    rendered
+}
+
+/// 切换时可用的模板变量
+pub fn template_vars(account: &Account, directory: &Directory) -> HashMap<String, String> {
+    HashMap::from([
+        ("ACCOUNT_NAME".to_string(), account.name.clone()),
+        ("BASE_URL".to_string(), account.base_url.clone()),
+        ("MODEL".to_string(), account.model.clone()),
+        ("DIRECTORY_NAME".to_string(), directory.name.clone()),
+        ("DIRECTORY_PATH".to_string(), directory.path.clone()),
+        (
+            "DATE".to_string(),
+            chrono::Local::now().format("%Y-%m-%d").to_string(),
+        ),
+    ])
+}
+
+impl ClaudeDefinition {
+    /// 相对于 .claude 目录的文件路径，如 `commands/git/commit.md`
+    pub fn relative_path(&self) -> String {
+        format!("{}/{}.md", definition_subdir(&self.kind), self.name)
+    }
+}
+
+/// 构造创建请求，描述为空时从 front-matter 的 description 读取
+pub fn definition_request(
+    kind: &str,
+    name: &str,
+    content: String,
+    description: Option<String>,
+    is_default: bool,
+) -> CreateClaudeDefinitionRequest {
+    let description = description
+        .filter(|d| !d.trim().is_empty())
+        .or_else(|| front_matter_value(&content, "description"));
+    CreateClaudeDefinitionRequest {
+        kind: kind.to_string(),
+        name: name.to_string(),
+        description,
+        content,
+        is_default: Some(is_default),
+    }
+}
+
+/// 内置的命令定义（默认对所有目录启用）
+pub fn builtin_definitions() -> Vec<CreateClaudeDefinitionRequest> {
+    let mut files = Vec::new();
+    collect_embedded_files(&COMMANDS_DIR, &mut files);
+    files
+        .into_iter()
+        .filter_map(|(path, content)| {
+            let name = path.strip_suffix(".md")?.to_string();
+            Some(definition_request("command", &name, content, None, true))
+        })
+        .collect()
+}
+
+fn collect_embedded_files(dir: &Dir, files: &mut Vec<(String, String)>) {
+    for file in dir.files() {
+        if let Some(content) = file.contents_utf8() {
+            let path = file.path().to_string_lossy().replace('\\', "/");
+            files.push((path, content.to_string()));
+        }
+    }
+    for sub_dir in dir.dirs() {
+        collect_embedded_files(sub_dir, files);
+    }
+}
+
+/// 扫描项目 .claude 目录下的 commands 和 agents，用于导入到库中
+pub fn scan_claude_dir(project_path: &str) -> Result<Vec<CreateClaudeDefinitionRequest>> {
+    let claude_dir = Path::new(project_path).join(".claude");
+    let mut requests = Vec::new();
+
+    for kind in DEFINITION_KINDS {
+        let root = claude_dir.join(definition_subdir(kind));
+        if !root.is_dir() {
+            continue;
+        }
+
+        let mut files = Vec::new();
+        collect_markdown_files(&root, &mut files)?;
+
+        for file in files {
+            let relative = file
+                .strip_prefix(&root)?
+                .to_string_lossy()
+                .replace('\\', "/");
+            let name = match relative.strip_suffix(".md") {
+                Some(name) => name.to_string(),
+                None => continue,
+            };
+            let content = fs::read_to_string(&file)?;
+            if validate_definition(kind, &name, &content).is_err() {
+                tracing::warn!("跳过无效的定义文件: {}", file.display());
+                continue;
+            }
+            requests.push(definition_request(kind, &name, content, None, false));
+        }
+    }
+
+    Ok(requests)
+}
+
+fn collect_markdown_files(dir: &Path, files: &mut Vec<std::path::PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_markdown_files(&path, files)?;
+        } else if path.extension().map(|e| e == "md").unwrap_or(false) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_front_matter() {
+        let content = "---\ndescription: 测试命令\nmodel: sonnet\n---\n\n正文 {{PROMPT}}\n";
+        let (front_matter, body) = split_front_matter(content);
+        assert_eq!(front_matter, "description: 测试命令\nmodel: sonnet\n");
+        assert_eq!(body, "\n正文 {{PROMPT}}\n");
+        assert_eq!(
+            front_matter_value(content, "description").as_deref(),
+            Some("测试命令")
+        );
+
+        let (front_matter, body) = split_front_matter("没有 front-matter");
+        assert_eq!(front_matter, "");
+        assert_eq!(body, "没有 front-matter");
+    }
+
+    #[test]
+    fn test_render_template_keeps_unknown_placeholders() {
+        let vars = HashMap::from([("DIRECTORY_NAME".to_string(), "demo".to_string())]);
+        assert_eq!(
+            render_template("{{DIRECTORY_NAME}}: {{PROMPT}}", &vars),
+            "demo: {{PROMPT}}"
+        );
+    }
+
+    #[test]
+    fn test_validate_definition() {
+        assert!(validate_definition("command", "git/commit", "内容").is_ok());
+        assert!(validate_definition("agent", "reviewer", "内容").is_ok());
+        assert!(validate_definition("agent", "a/b", "内容").is_err());
+        assert!(validate_definition("command", "../escape", "内容").is_err());
+        assert!(validate_definition("command", "ok", "  ").is_err());
+        assert!(validate_definition("skill", "ok", "内容").is_err());
+    }
+
+    #[test]
+    fn test_builtin_definitions() {
+        let builtins = builtin_definitions();
+        assert!(builtins.iter().any(|d| d.name == "throttled-task"));
+        assert!(builtins.iter().all(|d| d.description.is_some()));
+    }
+}
@@ -0,0 +1,391 @@
+use crate::claude_config::ClaudeConfigManager;
+use crate::custom_headers;
+use crate::database::Database;
+use crate::definitions;
+use crate::key_helper;
+use crate::models::*;
+use crate::providers;
+use crate::relay;
+use serde_json::{json, Value};
+use sqlx::Error as SqlxError;
+use std::fs;
+use std::path::Path;
+
+/// 漂移类型
+pub const DRIFT_MISSING_DIRECTORY: &str = "missing_directory";
+pub const DRIFT_MISSING_SETTINGS: &str = "missing_settings";
+pub const DRIFT_INVALID_SETTINGS: &str = "invalid_settings";
+pub const DRIFT_STALE_TOKEN: &str = "stale_token";
+pub const DRIFT_ENV_CHANGED: &str = "env_changed";
+pub const DRIFT_MISSING_ASSET: &str = "missing_asset";
+
+/// 按当前绑定的账号切换时会写入目录的内容
+pub struct ExpectedState {
+    pub account: Account,
+    pub token_key: String,
+    pub env: Vec<(String, Value)>,
+    pub definitions: Vec<ClaudeDefinition>,
+    pub mcp_servers: Vec<McpServer>,
+}
+
+/// 隐藏令牌中间部分，避免在诊断输出中泄露
+pub fn mask_secret(secret: &str) -> String {
+    let chars: Vec<char> = secret.chars().collect();
+    if chars.len() <= 12 {
+        return "*".repeat(chars.len());
+    }
+    let head: String = chars[..6].iter().collect();
+    let tail: String = chars[chars.len() - 4..].iter().collect();
+    format!("{}...{}", head, tail)
+}
+
+fn env_value_to_string(value: &Value) -> String {
+    value
+        .as_str()
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| value.to_string())
+}
+
+fn issue(
+    kind: &str,
+    key: Option<String>,
+    expected: Option<String>,
+    actual: Option<String>,
+) -> DriftIssue {
+    DriftIssue {
+        kind: kind.to_string(),
+        key,
+        expected,
+        actual,
+    }
+}
+
+/// 对比 settings.local.json 中的 env 与期望值
+pub fn compare_env(
+    settings: &Value,
+    expected: &[(String, Value)],
+    token_key: &str,
+) -> Vec<DriftIssue> {
+    let mut issues = Vec::new();
+
+    for (key, value) in expected {
+        let expected_value = env_value_to_string(value);
+        let actual_value = settings
+            .get("env")
+            .and_then(|env| env.get(key))
+            .map(env_value_to_string);
+
+        if actual_value.as_deref() == Some(expected_value.as_str()) {
+            continue;
+        }
+
+        if key == token_key {
+            issues.push(issue(
+                DRIFT_STALE_TOKEN,
+                Some(key.clone()),
+                Some(mask_secret(&expected_value)),
+                actual_value.map(|v| mask_secret(&v)),
+            ));
+        } else {
+            issues.push(issue(
+                DRIFT_ENV_CHANGED,
+                Some(key.clone()),
+                Some(expected_value),
+                actual_value,
+            ));
+        }
+    }
+
+    issues
+}
+
+/// 获取目录的期望状态，目录从未切换过账号时返回 None
+pub async fn expected_state(
+    db: &Database,
+    directory: &Directory,
+) -> Result<Option<ExpectedState>, SqlxError> {
+    let account = match db.get_directory_bound_account(directory.id).await? {
+        Some(account) => account,
+        None => return Ok(None),
+    };
+
+    let base_url = db
+        .get_base_urls()
+        .await?
+        .into_iter()
+        .find(|bu| bu.url == account.base_url);
+    let (api_key_name, provider_type) = base_url
+        .as_ref()
+        .map(|bu| (bu.api_key.clone(), bu.provider_type.clone()))
+        .unwrap_or_else(|| {
+            (
+                "ANTHROPIC_API_KEY".to_string(),
+                providers::DEFAULT_PROVIDER.to_string(),
+            )
+        });
+
+    // 通过本地中转代理访问时，目录只保存代理地址和占位令牌，真实令牌由代理注入
+    let (token_key, mut env) =
+        if let (Some(port), Some(pool_id)) = (directory.relay_port, directory.account_pool_id) {
+            let env = vec![
+                (
+                    "ANTHROPIC_BASE_URL".to_string(),
+                    json!(relay::relay_base_url(port as u16, pool_id)),
+                ),
+                (api_key_name.clone(), json!(relay::RELAY_TOKEN)),
+            ];
+            (api_key_name, env)
+        } else {
+            let token_key = providers::schema_or_default(&provider_type)
+                .token_env(&api_key_name)
+                .unwrap_or_default()
+                .to_string();
+            let env = providers::provider_env(
+                &provider_type,
+                &api_key_name,
+                &account.token,
+                &account.base_url,
+            )
+            .into_iter()
+            .map(|(key, value)| (key, json!(value)))
+            .collect();
+            (token_key, env)
+        };
+    // 辅助命令模式的账号不在 env 中保存密钥
+    if account.uses_key_helper() && directory.relay_port.is_none() {
+        env.retain(|(key, _)| key != &token_key);
+    }
+
+    // URL 默认环境变量和账号自定义环境变量，与切换时的覆盖顺序一致
+    for (key, value) in extra_env_vars(base_url.as_ref(), &account) {
+        env.retain(|(k, _)| k != &key);
+        env.push((key, parse_env_value(&value)));
+    }
+
+    // API 地址和账号的自定义请求头，与 env 中已有的 ANTHROPIC_CUSTOM_HEADERS 合并
+    let headers = custom_headers::effective_headers(base_url.as_ref(), Some(&account));
+    if !headers.is_empty() {
+        let existing = env
+            .iter()
+            .find(|(key, _)| key == custom_headers::CUSTOM_HEADERS_ENV)
+            .and_then(|(_, value)| value.as_str())
+            .map(custom_headers::parse_header_lines)
+            .unwrap_or_default();
+        let merged = custom_headers::merge_headers(&[&existing, &headers]);
+        env.retain(|(key, _)| key != custom_headers::CUSTOM_HEADERS_ENV);
+        env.push((
+            custom_headers::CUSTOM_HEADERS_ENV.to_string(),
+            json!(custom_headers::to_header_lines(&merged)),
+        ));
+    }
+
+    let definitions = db.get_definitions_for_switch(directory.id).await?;
+    let mcp_servers = db
+        .get_mcp_servers_for_switch(account.id, directory.id)
+        .await?;
+
+    Ok(Some(ExpectedState {
+        account,
+        token_key,
+        env,
+        definitions,
+        mcp_servers,
+    }))
+}
+
+/// 对比磁盘上的 .claude 状态与期望状态
+pub fn detect_drift(directory: &Directory, expected: &ExpectedState) -> Vec<DriftIssue> {
+    let root = Path::new(&directory.path);
+    if !root.is_dir() {
+        return vec![issue(
+            DRIFT_MISSING_DIRECTORY,
+            None,
+            Some(directory.path.clone()),
+            None,
+        )];
+    }
+
+    let mut issues = Vec::new();
+    let claude_dir = root.join(".claude");
+    let settings_file = claude_dir.join("settings.local.json");
+
+    match fs::read_to_string(&settings_file) {
+        Ok(content) => match serde_json::from_str::<Value>(&content) {
+            Ok(settings) => {
+                issues.extend(compare_env(&settings, &expected.env, &expected.token_key))
+            }
+            Err(e) => issues.push(issue(
+                DRIFT_INVALID_SETTINGS,
+                Some(".claude/settings.local.json".to_string()),
+                None,
+                Some(e.to_string()),
+            )),
+        },
+        Err(_) => issues.push(issue(
+            DRIFT_MISSING_SETTINGS,
+            Some(".claude/settings.local.json".to_string()),
+            None,
+            None,
+        )),
+    }
+
+    let mut assets = vec!["show-status.mjs".to_string()];
+    assets.extend(expected.definitions.iter().map(|d| d.relative_path()));
+    for asset in assets {
+        if !claude_dir.join(&asset).is_file() {
+            issues.push(issue(
+                DRIFT_MISSING_ASSET,
+                Some(format!(".claude/{}", asset)),
+                None,
+                None,
+            ));
+        }
+    }
+
+    if !expected.mcp_servers.is_empty() {
+        let mcp_config = fs::read_to_string(root.join(".mcp.json"))
+            .ok()
+            .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+            .unwrap_or_else(|| json!({}));
+        for server in &expected.mcp_servers {
+            if mcp_config
+                .get("mcpServers")
+                .and_then(|s| s.get(&server.name))
+                .is_none()
+            {
+                issues.push(issue(
+                    DRIFT_MISSING_ASSET,
+                    Some(format!(".mcp.json: {}", server.name)),
+                    None,
+                    None,
+                ));
+            }
+        }
+    }
+
+    issues
+}
+
+/// 扫描所有已登记目录的配置漂移
+pub async fn scan_drift(db: &Database) -> Result<Vec<DirectoryDrift>, SqlxError> {
+    let mut results = Vec::new();
+
+    for directory in db.get_directories().await? {
+        let expected = expected_state(db, &directory).await?;
+        let issues = match &expected {
+            Some(expected) => detect_drift(&directory, expected),
+            None => Vec::new(),
+        };
+
+        results.push(DirectoryDrift {
+            directory_id: directory.id,
+            directory_name: directory.name.clone(),
+            directory_path: directory.path.clone(),
+            account_id: expected.as_ref().map(|e| e.account.id),
+            account_name: expected.map(|e| e.account.name),
+            issues,
+        });
+    }
+
+    Ok(results)
+}
+
+/// 按期望状态修复目录：合并账号环境变量并重新部署状态栏脚本、定义和 MCP 服务器
+pub fn fix_drift(directory: &Directory, expected: &ExpectedState) -> anyhow::Result<()> {
+    if !Path::new(&directory.path).is_dir() {
+        anyhow::bail!("目录不存在: {}", directory.path);
+    }
+
+    let config_manager = ClaudeConfigManager::new(directory.path.clone());
+    let previously_enabled_mcp = config_manager.get_enabled_mcp_servers();
+
+    config_manager.merge_env(&expected.env)?;
+    // 与切换账号一致：辅助命令模式写入 apiKeyHelper 并移除静态密钥，否则移除遗留的 apiKeyHelper
+    // 通过中转代理访问时密钥由代理注入，目录按令牌模式处理
+    if directory.relay_port.is_some() && expected.account.uses_key_helper() {
+        let relay_account = Account {
+            credential_mode: key_helper::MODE_TOKEN.to_string(),
+            ..expected.account.clone()
+        };
+        config_manager.apply_key_helper(&relay_account, &expected.token_key)?;
+    } else {
+        config_manager.apply_key_helper(&expected.account, &expected.token_key)?;
+    }
+    config_manager.write_status_script()?;
+    config_manager.apply_definitions(
+        &expected.definitions,
+        &definitions::template_vars(&expected.account, directory),
+    )?;
+    config_manager.apply_mcp_servers(&expected.mcp_servers, previously_enabled_mcp)?;
+
+    Ok(())
+}
+
+/// 修复所有存在漂移的目录，返回 (修复数量, 失败列表)
+pub async fn fix_all_drift(db: &Database) -> Result<(usize, Vec<(String, String)>), SqlxError> {
+    let mut fixed = 0;
+    let mut failures = Vec::new();
+
+    for directory in db.get_directories().await? {
+        let expected = match expected_state(db, &directory).await? {
+            Some(expected) => expected,
+            None => continue,
+        };
+        if detect_drift(&directory, &expected).is_empty() {
+            continue;
+        }
+
+        match fix_drift(&directory, &expected) {
+            Ok(_) => {
+                tracing::info!("已修复目录 {} 的配置漂移", directory.path);
+                fixed += 1;
+            }
+            Err(e) => {
+                tracing::warn!("修复目录 {} 失败: {}", directory.path, e);
+                failures.push((directory.name.clone(), e.to_string()));
+            }
+        }
+    }
+
+    Ok((fixed, failures))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_env() {
+        let settings = json!({
+            "env": {
+                "ANTHROPIC_AUTH_TOKEN": "sk-old-token-1234567890",
+                "ANTHROPIC_BASE_URL": "https://old.example.com",
+                "DISABLE_TELEMETRY": 1
+            }
+        });
+        let expected = vec![
+            (
+                "ANTHROPIC_AUTH_TOKEN".to_string(),
+                json!("sk-new-token-1234567890"),
+            ),
+            (
+                "ANTHROPIC_BASE_URL".to_string(),
+                json!("https://api.example.com"),
+            ),
+            ("DISABLE_TELEMETRY".to_string(), json!("1")),
+        ];
+
+        let issues = compare_env(&settings, &expected, "ANTHROPIC_AUTH_TOKEN");
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].kind, DRIFT_STALE_TOKEN);
+        assert!(!issues[0].actual.as_deref().unwrap().contains("old-token"));
+        assert_eq!(issues[1].kind, DRIFT_ENV_CHANGED);
+        assert_eq!(issues[1].actual.as_deref(), Some("https://old.example.com"));
+    }
+
+    #[test]
+    fn test_mask_secret() {
+        assert_eq!(mask_secret("short"), "*****");
+        assert_eq!(mask_secret("sk-ant-1234567890abcd"), "sk-ant...abcd");
+    }
+}
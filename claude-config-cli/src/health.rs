@@ -0,0 +1,461 @@
+use crate::custom_headers::{self, CustomHeader};
+use crate::database::Database;
+use crate::key_helper;
+use crate::models::*;
+use crate::proxy;
+use reqwest::header::{HeaderMap, HeaderValue};
+use sqlx::Error as SqlxError;
+use std::time::{Duration, Instant};
+use tokio::net::TcpStream;
+use tokio_native_tls::{native_tls, TlsConnector};
+
+/// 单次探测的超时时间
+pub const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+/// 令牌对应的认证头：ANTHROPIC_AUTH_TOKEN 使用 Bearer，其他使用 x-api-key
+pub fn auth_headers(token_key: &str, token: &str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        "anthropic-version",
+        HeaderValue::from_static(ANTHROPIC_VERSION),
+    );
+    let header = if token_key == "ANTHROPIC_AUTH_TOKEN" {
+        ("authorization", format!("Bearer {}", token))
+    } else {
+        ("x-api-key", token.to_string())
+    };
+    if let Ok(value) = HeaderValue::from_str(&header.1) {
+        headers.insert(header.0, value);
+    }
+    headers
+}
+
+fn elapsed_ms(start: Instant) -> Option<i64> {
+    Some(start.elapsed().as_millis() as i64)
+}
+
+/// 在已建立的 TCP 连接上完成 TLS 握手，只用于计时，握手后即断开
+async fn tls_handshake(stream: TcpStream, host: &str, timeout: Duration) -> Result<(), String> {
+    let connector =
+        native_tls::TlsConnector::new().map_err(|e| format!("TLS 初始化失败: {}", e))?;
+    match tokio::time::timeout(timeout, TlsConnector::from(connector).connect(host, stream)).await {
+        Ok(Ok(_)) => Ok(()),
+        Ok(Err(e)) => Err(format!("TLS 握手失败: {}", e)),
+        Err(_) => Err("TLS 握手超时".to_string()),
+    }
+}
+
+/// 探测 API 地址：TCP 连接耗时、https 地址的 TLS 握手耗时、根路径的 HTTP 状态，以及可选的带认证请求（GET /v1/models）
+/// auth 为 (令牌变量名, 令牌)，extra_headers 为随每个请求发送的自定义请求头，返回的请求中 base_url_id 由调用方填写
+/// 指定代理时 HTTP 请求经过代理发送，TCP 连接耗时测量的是到代理服务器的连接，不单独测量 TLS 握手
+pub async fn probe_endpoint(
+    url: &str,
+    auth: Option<(&str, &str)>,
+    extra_headers: &[CustomHeader],
+    proxy: Option<&Proxy>,
+    timeout: Duration,
+) -> CreateEndpointCheckRequest {
+    let mut result = CreateEndpointCheckRequest::default();
+
+    let parsed = match reqwest::Url::parse(url) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            result.error = Some(format!("无效的地址: {}", e));
+            return result;
+        }
+    };
+    let (host, port) = match (parsed.host_str(), parsed.port_or_known_default()) {
+        (Some(host), Some(port)) => (host.to_string(), port),
+        _ => {
+            result.error = Some("地址缺少主机或端口".to_string());
+            return result;
+        }
+    };
+    let tls_host = (parsed.scheme() == "https" && proxy.is_none()).then(|| host.clone());
+    let (host, port) = match proxy {
+        Some(proxy) => match proxy.address() {
+            Some(address) => address,
+            None => {
+                result.error = Some(format!("代理 {} 的地址无效", proxy.name));
+                return result;
+            }
+        },
+        None => (host, port),
+    };
+
+    let start = Instant::now();
+    let stream =
+        match tokio::time::timeout(timeout, TcpStream::connect((host.as_str(), port))).await {
+            Ok(Ok(stream)) => {
+                result.connect_ms = elapsed_ms(start);
+                stream
+            }
+            Ok(Err(e)) => {
+                result.error = Some(format!("连接失败: {}", e));
+                return result;
+            }
+            Err(_) => {
+                result.error = Some("连接超时".to_string());
+                return result;
+            }
+        };
+
+    // 计时用的连接不再使用，不需要 TLS 握手时立即关闭
+    match tls_host {
+        Some(tls_host) => {
+            let start = Instant::now();
+            match tls_handshake(stream, &tls_host, timeout).await {
+                Ok(()) => result.tls_ms = elapsed_ms(start),
+                Err(e) => {
+                    result.error = Some(e);
+                    return result;
+                }
+            }
+        }
+        None => drop(stream),
+    }
+
+    let client = match proxy::build_client(proxy, timeout) {
+        Ok(client) => client,
+        Err(e) => {
+            result.error = Some(e);
+            return result;
+        }
+    };
+
+    let extra_headers = custom_headers::header_map(extra_headers);
+    let start = Instant::now();
+    match client.get(url).headers(extra_headers.clone()).send().await {
+        Ok(response) => {
+            result.latency_ms = elapsed_ms(start);
+            result.status_code = Some(response.status().as_u16() as i64);
+        }
+        Err(e) => {
+            result.error = Some(format!("请求失败: {}", e));
+            return result;
+        }
+    }
+
+    if let Some((token_key, token)) = auth {
+        let models_url = format!("{}/v1/models", url.trim_end_matches('/'));
+        match client
+            .get(&models_url)
+            .headers(extra_headers)
+            .headers(auth_headers(token_key, token))
+            .send()
+            .await
+        {
+            Ok(response) => {
+                let status = response.status();
+                result.auth_status_code = Some(status.as_u16() as i64);
+                if !status.is_success() {
+                    result.error = Some(format!("认证请求返回 HTTP {}", status.as_u16()));
+                }
+            }
+            Err(e) => result.error = Some(format!("认证请求失败: {}", e)),
+        }
+    }
+
+    let reachable = result.status_code.map(|code| code < 500).unwrap_or(false);
+    if !reachable && result.error.is_none() {
+        result.error = Some(format!("服务返回 HTTP {}", result.status_code.unwrap_or(0)));
+    }
+    result.success = reachable && result.error.is_none();
+    result
+}
+
+/// 令牌校验结果
+pub const TOKEN_VALID: &str = "valid";
+pub const TOKEN_INVALID: &str = "invalid";
+pub const TOKEN_ERROR: &str = "error";
+
+/// 使用令牌发送最小的认证请求（GET /v1/models），返回 (校验结果, 错误信息)
+/// 401/403 视为令牌无效，其他失败（网络错误、服务不支持该接口等）记为 error
+pub async fn verify_token(
+    url: &str,
+    token_key: &str,
+    token: &str,
+    extra_headers: &[CustomHeader],
+    proxy: Option<&Proxy>,
+    timeout: Duration,
+) -> (&'static str, Option<String>) {
+    let client = match proxy::build_client(proxy, timeout) {
+        Ok(client) => client,
+        Err(e) => return (TOKEN_ERROR, Some(e)),
+    };
+
+    let models_url = format!("{}/v1/models", url.trim_end_matches('/'));
+    match client
+        .get(&models_url)
+        .headers(custom_headers::header_map(extra_headers))
+        .headers(auth_headers(token_key, token))
+        .send()
+        .await
+    {
+        Ok(response) => {
+            let status = response.status();
+            if status.is_success() {
+                (TOKEN_VALID, None)
+            } else {
+                let body = response.text().await.unwrap_or_default();
+                let message = format!(
+                    "HTTP {}: {}",
+                    status.as_u16(),
+                    body.chars().take(200).collect::<String>()
+                );
+                if status.as_u16() == 401 || status.as_u16() == 403 {
+                    (TOKEN_INVALID, Some(message))
+                } else {
+                    (TOKEN_ERROR, Some(message))
+                }
+            }
+        }
+        Err(e) => (TOKEN_ERROR, Some(format!("请求失败: {}", e))),
+    }
+}
+
+/// 检查 API 地址并保存结果，指定账号时同时发送带认证的请求
+/// 指定账号时使用账号绑定的代理，否则使用 API 地址绑定的代理；请求头为 API 地址和账号的自定义请求头
+pub async fn check_base_url(
+    db: &Database,
+    base_url_id: i64,
+    account_id: Option<i64>,
+) -> Result<EndpointCheck, SqlxError> {
+    let base_url = db.get_base_url(base_url_id).await?;
+    let account = match account_id {
+        Some(id) => Some(db.get_account(id).await?),
+        None => None,
+    };
+    let proxy = match account_id {
+        Some(id) => db.get_proxy_for_switch(id, None).await?,
+        None => db.get_proxy_for_base_url(base_url_id).await?,
+    };
+
+    // 辅助命令模式的账号先执行命令获取密钥，命令失败时记为检查失败
+    let token = match &account {
+        Some(account) => Some(key_helper::resolve_token(account).await),
+        None => None,
+    };
+    let headers = custom_headers::effective_headers(Some(&base_url), account.as_ref());
+    let mut request = match token {
+        Some(Err(e)) => CreateEndpointCheckRequest {
+            error: Some(e),
+            ..Default::default()
+        },
+        Some(Ok(token)) => {
+            let auth = Some((base_url.api_key.as_str(), token.as_str()));
+            probe_endpoint(&base_url.url, auth, &headers, proxy.as_ref(), PROBE_TIMEOUT).await
+        }
+        None => probe_endpoint(&base_url.url, None, &headers, proxy.as_ref(), PROBE_TIMEOUT).await,
+    };
+    request.base_url_id = base_url_id;
+    request.account_id = account_id;
+
+    if request.success {
+        tracing::info!("API 地址 {} 检查通过", base_url.url);
+    } else {
+        tracing::warn!(
+            "API 地址 {} 检查失败: {}",
+            base_url.url,
+            request.error.as_deref().unwrap_or("")
+        );
+    }
+
+    db.create_endpoint_check(request).await
+}
+
+/// 校验账号令牌并记录结果，令牌变量名取自账号 API 地址的配置
+/// 辅助命令模式的账号先执行命令获取密钥，命令失败时记为 error
+pub async fn verify_account(db: &Database, account_id: i64) -> Result<Account, SqlxError> {
+    let account = db.get_account(account_id).await?;
+    let token_key = token_key_for(db, &account.base_url).await?;
+    let proxy = db.get_proxy_for_switch(account_id, None).await?;
+    let base_url = db
+        .get_base_urls()
+        .await?
+        .into_iter()
+        .find(|bu| bu.url == account.base_url);
+    let headers = custom_headers::effective_headers(base_url.as_ref(), Some(&account));
+
+    let (status, error) = match key_helper::resolve_token(&account).await {
+        Ok(token) => {
+            verify_token(
+                &account.base_url,
+                &token_key,
+                &token,
+                &headers,
+                proxy.as_ref(),
+                PROBE_TIMEOUT,
+            )
+            .await
+        }
+        Err(e) => (TOKEN_ERROR, Some(e)),
+    };
+    if status != TOKEN_VALID {
+        tracing::warn!(
+            "账号 {} 的令牌校验未通过: {}",
+            account.name,
+            error.as_deref().unwrap_or("")
+        );
+    }
+
+    db.update_account_token_status(account_id, status, error.as_deref())
+        .await
+}
+
+/// API 地址配置的令牌变量名，未登记时默认为 ANTHROPIC_API_KEY
+pub async fn token_key_for(db: &Database, base_url: &str) -> Result<String, SqlxError> {
+    Ok(db
+        .get_base_urls()
+        .await?
+        .into_iter()
+        .find(|bu| bu.url == base_url)
+        .map(|bu| bu.api_key)
+        .unwrap_or_else(|| "ANTHROPIC_API_KEY".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tokio::sync::mpsc;
+
+    /// 本地模拟 HTTP 服务：根路径返回 200，/v1/models 校验 x-api-key
+    async fn start_mock_server() -> (String, mpsc::UnboundedReceiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (sender, receiver) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buffer = vec![0u8; 4096];
+                let n = stream.read(&mut buffer).await.unwrap_or(0);
+                let request = String::from_utf8_lossy(&buffer[..n]).to_lowercase();
+                let authorized = !request.starts_with("get /v1/models")
+                    || request.contains("x-api-key: sk-valid");
+                let status = if authorized {
+                    "200 OK"
+                } else {
+                    "401 Unauthorized"
+                };
+                let _ = sender.send(request);
+                let response = format!(
+                    "HTTP/1.1 {}\r\ncontent-length: 2\r\nconnection: close\r\n\r\n{{}}",
+                    status
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+
+        (format!("http://{}", addr), receiver)
+    }
+
+    #[tokio::test]
+    async fn test_probe_endpoint_with_mock_server() {
+        let (url, mut requests) = start_mock_server().await;
+
+        let result = probe_endpoint(&url, None, &[], None, PROBE_TIMEOUT).await;
+        assert!(result.success, "{:?}", result.error);
+        assert!(result.connect_ms.is_some());
+        assert_eq!(result.tls_ms, None);
+        assert_eq!(result.status_code, Some(200));
+        assert_eq!(result.auth_status_code, None);
+
+        // https 地址单独进行 TLS 握手，对端不是 TLS 服务时记为握手失败
+        let https_url = url.replacen("http://", "https://", 1);
+        let result = probe_endpoint(&https_url, None, &[], None, PROBE_TIMEOUT).await;
+        assert!(!result.success);
+        assert!(result.connect_ms.is_some());
+        assert_eq!(result.tls_ms, None);
+        assert_eq!(result.status_code, None);
+        assert!(result.error.unwrap().contains("TLS"));
+
+        let result = probe_endpoint(
+            &url,
+            Some(("ANTHROPIC_API_KEY", "sk-valid")),
+            &[],
+            None,
+            PROBE_TIMEOUT,
+        )
+        .await;
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.auth_status_code, Some(200));
+
+        let result = probe_endpoint(
+            &url,
+            Some(("ANTHROPIC_API_KEY", "sk-invalid")),
+            &[],
+            None,
+            PROBE_TIMEOUT,
+        )
+        .await;
+        assert!(!result.success);
+        assert_eq!(result.auth_status_code, Some(401));
+
+        // 跳过前面请求的记录，确认 Bearer 头和自定义请求头的发送方式
+        while requests.try_recv().is_ok() {}
+        probe_endpoint(
+            &url,
+            Some(("ANTHROPIC_AUTH_TOKEN", "sk-bearer")),
+            &[CustomHeader::new("X-Tenant-Id", "team-a")],
+            None,
+            PROBE_TIMEOUT,
+        )
+        .await;
+        let mut saw_bearer = false;
+        let mut tenant_requests = 0;
+        while let Ok(request) = requests.try_recv() {
+            saw_bearer |= request.contains("authorization: bearer sk-bearer");
+            if request.contains("x-tenant-id: team-a") {
+                tenant_requests += 1;
+            }
+        }
+        assert!(saw_bearer);
+        assert_eq!(tenant_requests, 2);
+    }
+
+    #[tokio::test]
+    async fn test_verify_token() {
+        let (url, _requests) = start_mock_server().await;
+
+        let (status, error) = verify_token(
+            &url,
+            "ANTHROPIC_API_KEY",
+            "sk-valid",
+            &[],
+            None,
+            PROBE_TIMEOUT,
+        )
+        .await;
+        assert_eq!(status, TOKEN_VALID);
+        assert!(error.is_none());
+
+        let (status, error) = verify_token(
+            &url,
+            "ANTHROPIC_API_KEY",
+            "sk-expired",
+            &[],
+            None,
+            PROBE_TIMEOUT,
+        )
+        .await;
+        assert_eq!(status, TOKEN_INVALID);
+        assert!(error.unwrap().starts_with("HTTP 401"));
+    }
+
+    #[tokio::test]
+    async fn test_probe_endpoint_connection_refused() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+
+        let result =
+            probe_endpoint(&format!("http://{}", addr), None, &[], None, PROBE_TIMEOUT).await;
+        assert!(!result.success);
+        assert!(result.connect_ms.is_none());
+        assert!(result.error.is_some());
+    }
+}
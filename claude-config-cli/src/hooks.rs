@@ -0,0 +1,193 @@
+use crate::models::Hook;
+use serde_json::{json, Value};
+
+/// Claude Code 支持的 Hook 事件
+pub const HOOK_EVENTS: [&str; 9] = [
+    "PreToolUse",
+    "PostToolUse",
+    "Notification",
+    "UserPromptSubmit",
+    "Stop",
+    "SubagentStop",
+    "PreCompact",
+    "SessionStart",
+    "SessionEnd",
+];
+
+/// 只有工具相关的事件支持 matcher
+pub fn hook_event_supports_matcher(event: &str) -> bool {
+    matches!(event, "PreToolUse" | "PostToolUse")
+}
+
+/// 校验 Hook 的事件、命令和超时时间
+pub fn validate_hook(event: &str, command: &str, timeout: Option<i64>) -> Result<(), String> {
+    if !HOOK_EVENTS.contains(&event) {
+        return Err(format!("不支持的 Hook 事件: {}", event));
+    }
+    if command.trim().is_empty() {
+        return Err("Hook 命令不能为空".to_string());
+    }
+    if let Some(timeout) = timeout {
+        if timeout <= 0 {
+            return Err("超时时间必须大于 0".to_string());
+        }
+    }
+    Ok(())
+}
+
+/// 将 Hook 合并到 settings 的 hooks 段
+/// 按 事件 + matcher 分组，已存在的相同命令不会重复添加
+pub fn merge_hooks_into_settings(settings: &mut Value, hooks: &[Hook]) {
+    if hooks.is_empty() {
+        return;
+    }
+    if !settings.is_object() {
+        *settings = json!({});
+    }
+    if !settings
+        .get("hooks")
+        .map(|h| h.is_object())
+        .unwrap_or(false)
+    {
+        settings["hooks"] = json!({});
+    }
+
+    let hooks_obj = settings["hooks"].as_object_mut().unwrap();
+
+    for hook in hooks {
+        let matcher = if hook_event_supports_matcher(&hook.event) {
+            hook.matcher.clone().unwrap_or_default()
+        } else {
+            String::new()
+        };
+
+        let mut hook_entry = json!({
+            "type": "command",
+            "command": hook.command,
+        });
+        if let Some(timeout) = hook.timeout {
+            hook_entry["timeout"] = json!(timeout);
+        }
+
+        let groups = hooks_obj
+            .entry(hook.event.clone())
+            .or_insert_with(|| json!([]));
+        if !groups.is_array() {
+            *groups = json!([]);
+        }
+        let groups = groups.as_array_mut().unwrap();
+
+        let group = groups
+            .iter_mut()
+            .find(|g| g.get("matcher").and_then(Value::as_str).unwrap_or("") == matcher);
+
+        match group {
+            Some(group) => {
+                if !group.get("hooks").map(|h| h.is_array()).unwrap_or(false) {
+                    group["hooks"] = json!([]);
+                }
+                let entries = group["hooks"].as_array_mut().unwrap();
+                let exists = entries.iter().any(|e| {
+                    e.get("command").and_then(Value::as_str) == Some(hook.command.as_str())
+                });
+                if !exists {
+                    entries.push(hook_entry);
+                }
+            }
+            None => {
+                let mut new_group = json!({ "hooks": [hook_entry] });
+                if !matcher.is_empty() {
+                    new_group["matcher"] = json!(matcher);
+                }
+                groups.push(new_group);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use serde_json::json;
+
+    fn hook(event: &str, matcher: Option<&str>, command: &str, timeout: Option<i64>) -> Hook {
+        Hook {
+            id: 0,
+            name: command.to_string(),
+            event: event.to_string(),
+            matcher: matcher.map(str::to_string),
+            command: command.to_string(),
+            timeout,
+            is_global: false,
+            description: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_merge_hooks_groups_by_event_and_matcher() {
+        let mut settings = json!({ "env": { "A": "1" } });
+        merge_hooks_into_settings(
+            &mut settings,
+            &[
+                hook("PreToolUse", Some("Bash"), "check.sh", Some(10)),
+                hook("PreToolUse", Some("Bash"), "audit.sh", None),
+                hook("PreToolUse", Some("Edit"), "lint.sh", None),
+                // 非工具事件忽略 matcher
+                hook("Stop", Some("Bash"), "notify.sh", None),
+            ],
+        );
+
+        assert_eq!(settings["env"]["A"], "1");
+        let pre = settings["hooks"]["PreToolUse"].as_array().unwrap();
+        assert_eq!(pre.len(), 2);
+        assert_eq!(pre[0]["matcher"], "Bash");
+        assert_eq!(
+            pre[0]["hooks"],
+            json!([
+                { "type": "command", "command": "check.sh", "timeout": 10 },
+                { "type": "command", "command": "audit.sh" }
+            ])
+        );
+        assert_eq!(pre[1]["matcher"], "Edit");
+        let stop = settings["hooks"]["Stop"].as_array().unwrap();
+        assert!(stop[0].get("matcher").is_none());
+    }
+
+    #[test]
+    fn test_merge_hooks_keeps_existing_and_skips_duplicates() {
+        let mut settings = json!({
+            "hooks": {
+                "PostToolUse": [
+                    { "matcher": "Write", "hooks": [{ "type": "command", "command": "mine.sh" }] }
+                ]
+            }
+        });
+        let hooks = [
+            hook("PostToolUse", Some("Write"), "mine.sh", None),
+            hook("PostToolUse", Some("Write"), "format.sh", None),
+        ];
+        merge_hooks_into_settings(&mut settings, &hooks);
+        merge_hooks_into_settings(&mut settings, &hooks);
+
+        let groups = settings["hooks"]["PostToolUse"].as_array().unwrap();
+        assert_eq!(groups.len(), 1);
+        let commands: Vec<&str> = groups[0]["hooks"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|h| h["command"].as_str().unwrap())
+            .collect();
+        assert_eq!(commands, vec!["mine.sh", "format.sh"]);
+    }
+
+    #[test]
+    fn test_validate_hook() {
+        assert!(validate_hook("PreToolUse", "check.sh", Some(5)).is_ok());
+        assert!(validate_hook("OnSave", "check.sh", None).is_err());
+        assert!(validate_hook("Stop", "  ", None).is_err());
+        assert!(validate_hook("Stop", "check.sh", Some(0)).is_err());
+    }
+}
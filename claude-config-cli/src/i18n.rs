@@ -111,6 +111,16 @@ static TRANSLATIONS: Lazy<HashMap<Language, HashMap<TransKey, &'static str>>> =
     zh_cn.insert("menu.settings.current_lang", "当前语言");
     zh_cn.insert("menu.settings.select_lang", "请选择语言");
     zh_cn.insert("menu.settings.lang_changed", "语言已切换");
+    zh_cn.insert("menu.settings.database", "🗄️  数据库连接");
+    zh_cn.insert("menu.settings.current_db", "当前数据库连接");
+    zh_cn.insert("menu.settings.select_db", "请选择要切换的数据库连接");
+    zh_cn.insert("menu.settings.db_already_current", "已经在使用该数据库连接");
+    zh_cn.insert("menu.settings.db_switching", "正在连接并迁移新数据库...");
+    zh_cn.insert("menu.settings.db_switched", "已切换到数据库连接");
+    zh_cn.insert(
+        "menu.settings.db_switch_failed",
+        "切换数据库连接失败，继续使用原连接",
+    );
 
     // 通用操作
     zh_cn.insert("common.success", "✓ 操作成功");
@@ -145,12 +155,17 @@ static TRANSLATIONS: Lazy<HashMap<Language, HashMap<TransKey, &'static str>>> =
     zh_cn.insert("account.menu.delete", "🗑️  删除账号");
     zh_cn.insert("account.menu.import", "📥 批量导入");
     zh_cn.insert("account.menu.export", "📤 批量导出");
+    zh_cn.insert("account.menu.verify", "🔑 校验令牌");
+    zh_cn.insert("account.menu.balance", "💰 刷新余额");
+    zh_cn.insert("account.menu.pools", "🏊 账号池");
     zh_cn.insert("account.list.no_records", "暂无账号记录");
     zh_cn.insert("account.list.header_id", "ID");
     zh_cn.insert("account.list.header_name", "账号名称");
     zh_cn.insert("account.list.header_base_url", "Base URL");
     zh_cn.insert("account.list.header_model", "模型");
     zh_cn.insert("account.list.header_status", "状态");
+    zh_cn.insert("account.list.header_token", "令牌");
+    zh_cn.insert("account.list.header_balance", "余额");
     zh_cn.insert("account.list.status_active", "🟢 活跃");
     zh_cn.insert("account.list.status_inactive", "⚪ 未活跃");
     zh_cn.insert("account.list.total", "共 {} 个账号");
@@ -172,26 +187,15 @@ static TRANSLATIONS: Lazy<HashMap<Language, HashMap<TransKey, &'static str>>> =
     zh_cn.insert("account.delete.error", "✗ 删除失败: {}");
     zh_cn.insert("account.export.title", "批量导出账号");
     zh_cn.insert("account.export.no_accounts", "暂无账号可导出");
-    zh_cn.insert(
-        "account.export.success",
-        "✓ 成功导出 {} 个账号到文件: {file}",
-    );
+    zh_cn.insert("account.export.success", "✓ 成功导出 {} 个账号到: {file}");
     zh_cn.insert("account.import.title", "批量导入账号");
-    zh_cn.insert("account.import.prompt_file", "请输入JSON文件路径");
-    zh_cn.insert("account.import.error_read", "✗ 读取文件失败: {}");
-    zh_cn.insert("account.import.error_parse", "✗ 解析JSON失败: {}");
     zh_cn.insert(
-        "account.import.error_format",
-        "✗ 文件格式无效，缺少 providers 数组",
+        "account.import.prompt_file",
+        "请输入要导入的文件路径 (JSON / CSV / .env)",
     );
+    zh_cn.insert("account.import.error_read", "✗ 读取文件失败: {}");
+    zh_cn.insert("account.import.error_parse", "✗ 无法解析文件: {}");
     zh_cn.insert("account.import.no_accounts", "文件中没有账号数据");
-    zh_cn.insert("account.import.processing", "正在处理导入...");
-    zh_cn.insert("account.import.skip_invalid", "跳过无效数据");
-    zh_cn.insert("account.import.skip_exists", "跳过已存在");
-    zh_cn.insert("account.import.imported", "已导入");
-    zh_cn.insert("account.import.failed", "导入失败");
-    zh_cn.insert("account.import.result_imported", "✓ 成功导入 {} 个账号");
-    zh_cn.insert("account.import.result_skipped", "⊖ 跳过 {} 个账号");
     zh_cn.insert("account.default_indicator", "(默认)");
 
     // 目录管理
@@ -242,6 +246,7 @@ static TRANSLATIONS: Lazy<HashMap<Language, HashMap<TransKey, &'static str>>> =
     zh_cn.insert("url.list.header_description", "描述");
     zh_cn.insert("url.list.header_api_key", "API Key 环境变量");
     zh_cn.insert("url.list.header_default", "默认");
+    zh_cn.insert("url.list.header_health", "最近检查");
     zh_cn.insert("url.list.default_yes", "是");
     zh_cn.insert("url.list.default_no", "否");
     zh_cn.insert("url.list.total", "共 {} 个 URL");
@@ -345,12 +350,6 @@ static TRANSLATIONS: Lazy<HashMap<Language, HashMap<TransKey, &'static str>>> =
     zh_cn.insert("webdav.upload.select_config", "选择 WebDAV 配置");
     zh_cn.insert("webdav.upload.prompt_filename", "文件名");
     zh_cn.insert("webdav.upload.uploading", "正在上传配置到云端...");
-    zh_cn.insert("webdav.upload.clearing", "正在清空现有配置...");
-    zh_cn.insert("webdav.upload.cleared", "✓ 已清空现有账号和 Base URLs");
-    zh_cn.insert("webdav.upload.importing_accounts", "正在导入账号...");
-    zh_cn.insert("webdav.upload.imported_accounts", "✓ 成功导入 {} 个账号");
-    zh_cn.insert("webdav.upload.importing_urls", "正在导入 Base URLs...");
-    zh_cn.insert("webdav.upload.imported_urls", "✓ 成功导入 {} 个 Base URL");
     zh_cn.insert("webdav.upload.success", "✓ 配置已成功上传到 WebDAV: {}");
     zh_cn.insert("webdav.upload.success_log", "成功上传配置文件: {}");
     zh_cn.insert("webdav.upload.error", "✗ 上传失败: {}");
@@ -409,6 +408,634 @@ static TRANSLATIONS: Lazy<HashMap<Language, HashMap<TransKey, &'static str>>> =
     zh_cn.insert("remove_root.error_stderr", "错误输出:\n{}");
     zh_cn.insert("remove_root.error", "✗ 删除限制代码脚本不存在: {}");
 
+    // MCP 服务器
+    zh_cn.insert("menu.main.mcp", "🔌 MCP 服务器");
+    zh_cn.insert("mcp.menu.title", "MCP 服务器管理");
+    zh_cn.insert("mcp.menu.list", "📝 查看所有 MCP 服务器");
+    zh_cn.insert("mcp.menu.add", "➕ 添加 MCP 服务器");
+    zh_cn.insert("mcp.menu.edit", "✏️  编辑 MCP 服务器");
+    zh_cn.insert("mcp.menu.delete", "🗑️  删除 MCP 服务器");
+    zh_cn.insert("mcp.menu.assign_directory", "📁 分配到目录");
+    zh_cn.insert("mcp.menu.assign_account", "👤 分配到账号");
+    zh_cn.insert("mcp.list.no_records", "暂无 MCP 服务器");
+    zh_cn.insert("mcp.list.header_id", "ID");
+    zh_cn.insert("mcp.list.header_name", "名称");
+    zh_cn.insert("mcp.list.header_transport", "传输方式");
+    zh_cn.insert("mcp.list.header_target", "命令 / URL");
+    zh_cn.insert("mcp.list.header_description", "描述");
+    zh_cn.insert("mcp.list.total", "共 {} 个 MCP 服务器");
+    zh_cn.insert("mcp.add.title", "添加 MCP 服务器");
+    zh_cn.insert("mcp.add.prompt_name", "名称");
+    zh_cn.insert("mcp.add.prompt_transport", "传输方式");
+    zh_cn.insert("mcp.add.prompt_command", "命令");
+    zh_cn.insert("mcp.add.prompt_args", "参数（空格分隔）");
+    zh_cn.insert("mcp.add.prompt_env", "环境变量（KEY=VALUE，逗号分隔）");
+    zh_cn.insert("mcp.add.prompt_url", "URL");
+    zh_cn.insert("mcp.add.prompt_description", "描述（可选）");
+    zh_cn.insert("mcp.add.success", "✓ MCP 服务器 '{}' 创建成功");
+    zh_cn.insert("mcp.add.error", "✗ 创建失败: {}");
+    zh_cn.insert("mcp.edit.prompt", "选择要编辑的 MCP 服务器");
+    zh_cn.insert("mcp.edit.success", "✓ MCP 服务器更新成功");
+    zh_cn.insert("mcp.edit.error", "✗ 更新失败: {}");
+    zh_cn.insert("mcp.delete.prompt", "选择要删除的 MCP 服务器");
+    zh_cn.insert("mcp.delete.confirm", "确定要删除 MCP 服务器 '{}' 吗?");
+    zh_cn.insert("mcp.delete.success", "✓ MCP 服务器删除成功");
+    zh_cn.insert("mcp.delete.error", "✗ 删除失败: {}");
+    zh_cn.insert(
+        "mcp.assign.prompt_servers",
+        "选择要启用的 MCP 服务器（空格选择，回车确认）",
+    );
+    zh_cn.insert("mcp.assign.success", "✓ MCP 服务器分配已保存");
+    zh_cn.insert("mcp.assign.error", "✗ 保存分配失败: {}");
+    zh_cn.insert("switch.mcp_servers", "  MCP 服务器: {}");
+    zh_cn.insert("switch.warn_mcp_fail", "警告: MCP 服务器配置写入失败: {}");
+
+    // Hook 管理
+    zh_cn.insert("menu.main.hooks", "🪝 Hook 管理");
+    zh_cn.insert("hooks.menu.title", "Hook 管理");
+    zh_cn.insert("hooks.menu.list", "📝 查看所有 Hook");
+    zh_cn.insert("hooks.menu.add", "➕ 添加 Hook");
+    zh_cn.insert("hooks.menu.edit", "✏️  编辑 Hook");
+    zh_cn.insert("hooks.menu.delete", "🗑️  删除 Hook");
+    zh_cn.insert("hooks.menu.assign_directory", "📁 分配到目录");
+    zh_cn.insert("hooks.list.no_records", "暂无 Hook");
+    zh_cn.insert("hooks.list.header_id", "ID");
+    zh_cn.insert("hooks.list.header_name", "名称");
+    zh_cn.insert("hooks.list.header_event", "事件");
+    zh_cn.insert("hooks.list.header_matcher", "匹配器");
+    zh_cn.insert("hooks.list.header_command", "命令");
+    zh_cn.insert("hooks.list.header_timeout", "超时(秒)");
+    zh_cn.insert("hooks.list.header_global", "全局");
+    zh_cn.insert("hooks.list.total", "共 {} 个 Hook");
+    zh_cn.insert("hooks.add.title", "添加 Hook");
+    zh_cn.insert("hooks.add.prompt_name", "名称");
+    zh_cn.insert("hooks.add.prompt_event", "事件");
+    zh_cn.insert(
+        "hooks.add.prompt_matcher",
+        "匹配器（如 Bash、Edit|Write，留空匹配全部）",
+    );
+    zh_cn.insert("hooks.add.prompt_command", "命令");
+    zh_cn.insert("hooks.add.prompt_timeout", "超时秒数（可选）");
+    zh_cn.insert("hooks.add.invalid_timeout", "请输入有效的数字");
+    zh_cn.insert(
+        "hooks.add.prompt_global",
+        "挂载到全局 Claude 配置（所有目录生效）?",
+    );
+    zh_cn.insert("hooks.add.prompt_description", "描述（可选）");
+    zh_cn.insert("hooks.add.success", "✓ Hook '{}' 创建成功");
+    zh_cn.insert("hooks.add.error", "✗ 创建失败: {}");
+    zh_cn.insert("hooks.edit.prompt", "选择要编辑的 Hook");
+    zh_cn.insert("hooks.edit.success", "✓ Hook 更新成功");
+    zh_cn.insert("hooks.edit.error", "✗ 更新失败: {}");
+    zh_cn.insert("hooks.delete.prompt", "选择要删除的 Hook");
+    zh_cn.insert("hooks.delete.confirm", "确定要删除 Hook '{}' 吗?");
+    zh_cn.insert("hooks.delete.success", "✓ Hook 删除成功");
+    zh_cn.insert("hooks.delete.error", "✗ 删除失败: {}");
+    zh_cn.insert(
+        "hooks.assign.no_local_hooks",
+        "暂无可分配的 Hook（全局 Hook 会自动应用到所有目录）",
+    );
+    zh_cn.insert(
+        "hooks.assign.prompt_hooks",
+        "选择要启用的 Hook（空格选择，回车确认）",
+    );
+    zh_cn.insert("hooks.assign.success", "✓ Hook 分配已保存");
+    zh_cn.insert("hooks.assign.error", "✗ 保存分配失败: {}");
+
+    // 权限预设
+    zh_cn.insert("menu.main.permissions", "🛡️  权限预设");
+    zh_cn.insert("permissions.menu.title", "权限预设管理");
+    zh_cn.insert("permissions.menu.list", "📝 查看所有预设");
+    zh_cn.insert("permissions.menu.add", "➕ 添加预设");
+    zh_cn.insert("permissions.menu.edit", "✏️  编辑预设");
+    zh_cn.insert("permissions.menu.delete", "🗑️  删除预设");
+    zh_cn.insert("permissions.menu.assign_directory", "📁 为目录选择预设");
+    zh_cn.insert("permissions.menu.assign_account", "👤 为账号选择预设");
+    zh_cn.insert("permissions.list.no_records", "暂无权限预设");
+    zh_cn.insert("permissions.list.header_id", "ID");
+    zh_cn.insert("permissions.list.header_name", "名称");
+    zh_cn.insert("permissions.list.header_mode", "默认模式");
+    zh_cn.insert("permissions.list.header_allow", "允许");
+    zh_cn.insert("permissions.list.header_deny", "拒绝");
+    zh_cn.insert("permissions.list.header_ask", "询问");
+    zh_cn.insert("permissions.list.header_builtin", "内置");
+    zh_cn.insert("permissions.list.total", "共 {} 个预设");
+    zh_cn.insert("permissions.add.title", "添加权限预设");
+    zh_cn.insert("permissions.add.prompt_name", "预设名称");
+    zh_cn.insert("permissions.add.prompt_mode", "默认权限模式");
+    zh_cn.insert(
+        "permissions.add.rules_hint",
+        "多条规则用逗号分隔，例如: Read, Bash(npm run test:*), mcp__github",
+    );
+    zh_cn.insert("permissions.add.prompt_allow", "允许规则 (allow)");
+    zh_cn.insert("permissions.add.prompt_deny", "拒绝规则 (deny)");
+    zh_cn.insert("permissions.add.prompt_ask", "询问规则 (ask)");
+    zh_cn.insert("permissions.add.prompt_description", "描述 (可选)");
+    zh_cn.insert("permissions.add.success", "✓ 权限预设 '{}' 添加成功");
+    zh_cn.insert("permissions.add.error", "✗ 添加权限预设失败: {}");
+    zh_cn.insert("permissions.edit.prompt", "选择要编辑的预设");
+    zh_cn.insert("permissions.edit.success", "✓ 权限预设更新成功");
+    zh_cn.insert("permissions.edit.error", "✗ 更新权限预设失败: {}");
+    zh_cn.insert("permissions.delete.prompt", "选择要删除的预设");
+    zh_cn.insert("permissions.delete.builtin", "内置预设不可删除");
+    zh_cn.insert(
+        "permissions.delete.confirm",
+        "确定要删除预设 '{}' 吗？使用该预设的目录和账号将解除绑定",
+    );
+    zh_cn.insert("permissions.delete.success", "✓ 权限预设删除成功");
+    zh_cn.insert("permissions.delete.error", "✗ 删除权限预设失败: {}");
+    zh_cn.insert("permissions.assign.prompt_preset", "选择权限预设");
+    zh_cn.insert("permissions.assign.none", "(不使用预设)");
+    zh_cn.insert("permissions.assign.success", "✓ 权限预设已设置");
+    zh_cn.insert("permissions.assign.error", "✗ 设置权限预设失败: {}");
+    zh_cn.insert("switch.using_permission_preset", "使用权限预设: {}");
+    zh_cn.insert("switch.permission_preset", "预设 {}");
+
+    // 命令与子代理
+    zh_cn.insert("menu.main.definitions", "📚 命令与子代理");
+    zh_cn.insert("definitions.menu.title", "命令与子代理管理");
+    zh_cn.insert("definitions.menu.list", "📝 查看所有定义");
+    zh_cn.insert("definitions.menu.add", "➕ 添加定义");
+    zh_cn.insert("definitions.menu.edit", "✏️  编辑定义");
+    zh_cn.insert("definitions.menu.delete", "🗑️  删除定义");
+    zh_cn.insert("definitions.menu.import", "📥 从项目 .claude 目录导入");
+    zh_cn.insert("definitions.menu.assign_directory", "📁 按目录启用/禁用");
+    zh_cn.insert("definitions.kind.command", "斜杠命令");
+    zh_cn.insert("definitions.kind.agent", "子代理");
+    zh_cn.insert("definitions.list.no_records", "暂无定义");
+    zh_cn.insert("definitions.list.header_id", "ID");
+    zh_cn.insert("definitions.list.header_kind", "类型");
+    zh_cn.insert("definitions.list.header_name", "名称");
+    zh_cn.insert("definitions.list.header_description", "描述");
+    zh_cn.insert("definitions.list.header_default", "默认启用");
+    zh_cn.insert("definitions.list.total", "共 {} 个定义");
+    zh_cn.insert("definitions.add.title", "添加定义");
+    zh_cn.insert("definitions.add.prompt_kind", "类型");
+    zh_cn.insert(
+        "definitions.add.prompt_name",
+        "名称（命令可用 / 分隔子目录）",
+    );
+    zh_cn.insert("definitions.add.prompt_file", "Markdown 文件路径");
+    zh_cn.insert("definitions.add.read_error", "✗ 读取文件失败: {}");
+    zh_cn.insert(
+        "definitions.add.prompt_description",
+        "描述（留空则使用 front-matter 中的 description）",
+    );
+    zh_cn.insert("definitions.add.prompt_default", "是否默认对所有目录启用?");
+    zh_cn.insert("definitions.add.success", "✓ 定义 '{}' 创建成功");
+    zh_cn.insert("definitions.add.error", "✗ 创建失败: {}");
+    zh_cn.insert("definitions.edit.prompt", "选择要编辑的定义");
+    zh_cn.insert(
+        "definitions.edit.prompt_file",
+        "新的 Markdown 文件路径（留空保留原内容）",
+    );
+    zh_cn.insert("definitions.edit.success", "✓ 定义更新成功");
+    zh_cn.insert("definitions.edit.error", "✗ 更新失败: {}");
+    zh_cn.insert("definitions.delete.prompt", "选择要删除的定义");
+    zh_cn.insert("definitions.delete.confirm", "确定要删除定义 '{}' 吗?");
+    zh_cn.insert("definitions.delete.success", "✓ 定义删除成功");
+    zh_cn.insert("definitions.delete.error", "✗ 删除失败: {}");
+    zh_cn.insert("definitions.import.title", "从项目导入定义");
+    zh_cn.insert("definitions.import.prompt_path", "项目目录路径");
+    zh_cn.insert(
+        "definitions.import.no_files",
+        "未在 .claude/commands 或 .claude/agents 中找到定义",
+    );
+    zh_cn.insert("definitions.import.prompt_overwrite", "是否覆盖同名定义?");
+    zh_cn.insert(
+        "definitions.import.success",
+        "✓ 导入完成：导入 {} 个，跳过 {} 个",
+    );
+    zh_cn.insert("definitions.import.error", "✗ 导入失败: {}");
+    zh_cn.insert(
+        "definitions.assign.prompt_definitions",
+        "选择该目录启用的定义（空格切换，回车确认）",
+    );
+    zh_cn.insert("definitions.assign.success", "✓ 目录定义设置已保存");
+    zh_cn.insert("definitions.assign.error", "✗ 保存失败: {}");
+    zh_cn.insert("switch.definitions", "  命令/子代理: {}");
+    zh_cn.insert(
+        "switch.warn_definitions_fail",
+        "警告: 命令/子代理部署失败: {}",
+    );
+
+    // 配置漂移检测
+    zh_cn.insert("menu.main.doctor", "🩺 配置漂移检测");
+    zh_cn.insert("doctor.usage", "用法: claude-config doctor drift [--fix]");
+    zh_cn.insert("doctor.title", "配置漂移检测");
+    zh_cn.insert("doctor.header_directory", "目录");
+    zh_cn.insert("doctor.header_account", "绑定账号");
+    zh_cn.insert("doctor.header_status", "状态");
+    zh_cn.insert("doctor.header_details", "详情");
+    zh_cn.insert("doctor.status_unbound", "未切换过");
+    zh_cn.insert("doctor.status_ok", "✓ 一致");
+    zh_cn.insert("doctor.status_drifted", "⚠ {} 项漂移");
+    zh_cn.insert("doctor.issue.missing_directory", "目录不存在");
+    zh_cn.insert("doctor.issue.missing_settings", "缺少配置文件");
+    zh_cn.insert("doctor.issue.invalid_settings", "配置文件无法解析");
+    zh_cn.insert("doctor.issue.stale_token", "令牌已过期");
+    zh_cn.insert("doctor.issue.env_changed", "环境变量已修改");
+    zh_cn.insert("doctor.issue.missing_asset", "缺少文件");
+    zh_cn.insert("doctor.summary_ok", "✓ 所有目录配置一致");
+    zh_cn.insert("doctor.summary_drifted", "⚠ {} 个目录存在配置漂移");
+    zh_cn.insert("doctor.prompt_fix_all", "是否全部修复?");
+    zh_cn.insert("doctor.fix_success", "✓ 已修复 {} 个目录");
+    zh_cn.insert("doctor.fix_error", "✗ 修复 {} 失败: {}");
+
+    // 项目发现
+    zh_cn.insert("directory.menu.discover", "🔍 从磁盘发现项目");
+    zh_cn.insert("discovery.title", "=== 发现已有的 Claude 项目 ===");
+    zh_cn.insert("discovery.roots", "扫描根目录:");
+    zh_cn.insert("discovery.no_roots", "尚未配置扫描根目录");
+    zh_cn.insert("discovery.menu.title", "请选择操作");
+    zh_cn.insert("discovery.menu.scan", "🔍 扫描并导入");
+    zh_cn.insert("discovery.menu.add_root", "➕ 添加扫描根目录");
+    zh_cn.insert("discovery.menu.remove_root", "🗑️  删除扫描根目录");
+    zh_cn.insert("discovery.prompt_root", "扫描根目录（支持 ~ 和 * 通配符）");
+    zh_cn.insert("discovery.prompt_remove_root", "选择要删除的扫描根目录");
+    zh_cn.insert(
+        "discovery.no_projects",
+        "未发现包含 .claude 目录或 CLAUDE.md 的项目",
+    );
+    zh_cn.insert("discovery.header_status", "状态");
+    zh_cn.insert("discovery.header_account", "账号");
+    zh_cn.insert("discovery.status_registered", "已登记");
+    zh_cn.insert("discovery.status_new", "新项目");
+    zh_cn.insert("discovery.new_token", "未登记的令牌");
+    zh_cn.insert("discovery.nothing_to_import", "所有项目和令牌均已登记");
+    zh_cn.insert(
+        "discovery.prompt_select",
+        "选择要导入的项目（空格切换，回车确认）",
+    );
+    zh_cn.insert(
+        "discovery.prompt_create_accounts",
+        "同时为未登记的令牌创建账号和 API 地址?",
+    );
+    zh_cn.insert(
+        "discovery.import_success",
+        "导入完成：新增 {} 个目录、{} 个账号、{} 个 API 地址",
+    );
+
+    // API 地址健康检查
+    zh_cn.insert("url.menu.health", "🩺 健康检查");
+    zh_cn.insert("url.menu.balance", "💰 余额查询配置");
+    zh_cn.insert("url.health.all", "检查全部 URL");
+    zh_cn.insert("url.health.prompt_select", "选择要检查的 URL");
+    zh_cn.insert("url.health.prompt_account", "选择用于认证检查的账号");
+    zh_cn.insert("url.health.no_account", "不使用账号（仅检查连通性）");
+    zh_cn.insert("url.health.checking", "正在检查...");
+    zh_cn.insert("url.health.history", "=== 最近的检查记录 ===");
+    zh_cn.insert("url.health.header_result", "结果");
+    zh_cn.insert("url.health.header_connect", "TCP 连接");
+    zh_cn.insert("url.health.header_tls", "TLS 握手");
+    zh_cn.insert("url.health.header_latency", "HTTP 耗时");
+    zh_cn.insert("url.health.header_status", "HTTP 状态 (根路径 / 认证)");
+    zh_cn.insert("url.health.header_time", "检查时间");
+    zh_cn.insert("url.health.ok", "✓ 正常");
+    zh_cn.insert("url.health.failed", "✗ 异常");
+
+    // 令牌校验
+    zh_cn.insert("account.token.valid", "✓ 有效");
+    zh_cn.insert("account.token.invalid", "✗ 无效");
+    zh_cn.insert("account.token.error", "⚠ 校验出错");
+    zh_cn.insert("account.token.unchecked", "未校验");
+    zh_cn.insert("account.verify.all", "校验全部账号");
+    zh_cn.insert("account.verify.prompt", "选择要校验令牌的账号");
+    zh_cn.insert("account.verify.checking", "正在校验令牌...");
+
+    // 余额查询
+    zh_cn.insert("account.balance.refreshing", "正在查询余额...");
+    zh_cn.insert("account.balance.failed", "✗ 查询失败");
+    zh_cn.insert(
+        "account.balance.no_adapter",
+        "没有配置余额查询的 URL，请先在 URL 管理中配置",
+    );
+    zh_cn.insert("account.balance.cache_file", "余额已缓存到 {}");
+    zh_cn.insert("url.balance.prompt_select", "选择要配置余额查询的 URL");
+    zh_cn.insert("url.balance.current", "=== 当前的余额查询配置 ===");
+    zh_cn.insert("url.balance.action_edit", "修改配置");
+    zh_cn.insert("url.balance.action_remove", "删除配置");
+    zh_cn.insert("url.balance.removed", "✓ 已删除余额查询配置");
+    zh_cn.insert("url.balance.prompt_method", "请求方法");
+    zh_cn.insert(
+        "url.balance.prompt_url",
+        "请求地址（支持 {base_url} 和 {token} 占位符）",
+    );
+    zh_cn.insert(
+        "url.balance.prompt_headers",
+        "请求头 JSON（值支持占位符，留空表示不设置）",
+    );
+    zh_cn.insert(
+        "url.balance.invalid_headers",
+        "✗ 请求头不是有效的 JSON 对象",
+    );
+    zh_cn.insert("url.balance.prompt_body", "请求体（支持占位符）");
+    zh_cn.insert("url.balance.prompt_path", "余额字段路径");
+    zh_cn.insert("url.balance.prompt_divisor", "换算除数（原始值除以该数）");
+    zh_cn.insert("url.balance.prompt_unit", "单位");
+    zh_cn.insert("url.balance.prompt_enabled", "启用余额查询?");
+    zh_cn.insert("url.balance.saved", "✓ 余额查询配置已保存");
+
+    // 账号池
+    zh_cn.insert("pool.menu.title", "账号池管理");
+    zh_cn.insert("pool.menu.list", "📝 查看所有账号池");
+    zh_cn.insert("pool.menu.add", "➕ 创建账号池");
+    zh_cn.insert("pool.menu.delete", "🗑️  删除账号池");
+    zh_cn.insert("pool.menu.members", "👥 管理成员（优先级 / 权重）");
+    zh_cn.insert("pool.menu.bind", "📁 目录绑定账号池");
+    zh_cn.insert("pool.prompt_select", "选择账号池");
+    zh_cn.insert("pool.list.no_records", "暂无账号池");
+    zh_cn.insert("pool.list.directories", "绑定的目录: {}");
+    zh_cn.insert("pool.list.header_priority", "优先级");
+    zh_cn.insert("pool.list.header_weight", "权重");
+    zh_cn.insert("pool.add.prompt_name", "账号池名称");
+    zh_cn.insert("pool.add.prompt_description", "描述（可选）");
+    zh_cn.insert("pool.add.success", "✓ 账号池 {} 创建成功");
+    zh_cn.insert("pool.add.error", "✗ 创建账号池失败: {}");
+    zh_cn.insert(
+        "pool.delete.confirm",
+        "确定删除账号池 {} 吗？绑定的目录将解除绑定",
+    );
+    zh_cn.insert("pool.delete.success", "✓ 账号池 {} 已删除");
+    zh_cn.insert("pool.members.prompt_account", "选择要加入或调整的账号");
+    zh_cn.insert("pool.members.action_edit", "修改优先级和权重");
+    zh_cn.insert("pool.members.action_remove", "移出账号池");
+    zh_cn.insert("pool.members.removed", "✓ 已移出账号池");
+    zh_cn.insert("pool.members.prompt_priority", "优先级（数值越小越优先）");
+    zh_cn.insert(
+        "pool.members.prompt_weight",
+        "权重（同一优先级内按权重分配）",
+    );
+    zh_cn.insert("pool.members.saved", "✓ 成员已保存");
+    zh_cn.insert("pool.bind.none", "不绑定账号池");
+    zh_cn.insert("pool.bind.success", "✓ 目录的账号池绑定已更新");
+    zh_cn.insert("pool.bind.switched", "目录已切换到账号 {}");
+    zh_cn.insert(
+        "pool.failover.line",
+        "账号池 {pool}：目录 {directory} 的账号 {from} 不可用",
+    );
+    zh_cn.insert("pool.failover.none", "池中没有其他可用账号");
+    zh_cn.insert("pool.menu.failovers", "📜 故障切换记录");
+    zh_cn.insert("pool.failovers.no_records", "暂无故障切换记录");
+    zh_cn.insert("pool.failovers.header_time", "时间");
+    zh_cn.insert("pool.failovers.header_pool", "账号池");
+    zh_cn.insert("pool.failovers.header_directory", "目录");
+    zh_cn.insert("pool.failovers.header_from", "原账号");
+    zh_cn.insert("pool.failovers.header_to", "切换到");
+
+    // 本地中转代理
+    zh_cn.insert("serve.usage", "用法: claude-config serve [--port 端口]");
+    zh_cn.insert("serve.bind_error", "中转代理无法监听端口");
+    zh_cn.insert("serve.listening", "✓ 本地中转代理已启动: {}");
+    zh_cn.insert(
+        "serve.hint",
+        "目录的 ANTHROPIC_BASE_URL 指向 /pools/{账号池ID} 即可通过账号池转发，按 Ctrl+C 停止",
+    );
+
+    // 账号池中转代理
+    zh_cn.insert("pool.menu.relay", "🔀 目录通过本地中转代理访问");
+    zh_cn.insert("pool.menu.relay_stats", "📊 中转代理统计");
+    zh_cn.insert(
+        "pool.relay.no_directories",
+        "没有绑定账号池的目录，请先绑定账号池",
+    );
+    zh_cn.insert("pool.relay.direct", "直连（写入账号的真实地址和令牌）");
+    zh_cn.insert(
+        "pool.relay.via_relay",
+        "通过本地中转代理（按账号池轮换账号）",
+    );
+    zh_cn.insert("pool.relay.prompt_mode", "选择访问方式");
+    zh_cn.insert("pool.relay.prompt_port", "中转代理端口");
+    zh_cn.insert("pool.relay.success", "✓ 目录配置已更新");
+    zh_cn.insert(
+        "pool.relay.serve_hint",
+        "运行 claude-config serve --port {} 启动中转代理",
+    );
+    zh_cn.insert("pool.relay.error", "✗ 更新目录配置失败: {}");
+    zh_cn.insert("pool.relay.no_stats", "中转代理还没有转发过请求");
+    zh_cn.insert("pool.relay.header_requests", "请求数");
+    zh_cn.insert("pool.relay.header_failures", "失败数");
+    zh_cn.insert("pool.relay.header_avg_latency", "平均延迟");
+    zh_cn.insert("pool.relay.header_last_status", "最近状态码");
+    zh_cn.insert("pool.relay.header_last_used", "最近使用");
+
+    // 账号生命周期
+    zh_cn.insert("account.filter.prompt", "筛选账号");
+    zh_cn.insert("account.filter.all", "全部账号");
+    zh_cn.insert("account.filter.enabled", "仅已启用");
+    zh_cn.insert("account.filter.disabled", "仅已停用");
+    zh_cn.insert("account.filter.expiring", "即将到期（7 天内）或已过期");
+    zh_cn.insert("account.filter.tag", "按标签筛选");
+    zh_cn.insert("account.filter.prompt_tag", "标签");
+    zh_cn.insert("account.list.header_expires", "到期时间");
+    zh_cn.insert("account.list.header_tags", "标签");
+    zh_cn.insert("account.list.header_usage", "使用次数 · 最近使用");
+    zh_cn.insert("account.list.status_disabled", "⛔ 已停用");
+    zh_cn.insert(
+        "account.add.prompt_expires_at",
+        "到期日期 (YYYY-MM-DD，留空表示不过期)",
+    );
+    zh_cn.insert("account.add.invalid_date", "日期格式应为 YYYY-MM-DD");
+    zh_cn.insert("account.add.prompt_notes", "备注");
+    zh_cn.insert("account.add.prompt_tags", "标签 (逗号分隔)");
+    zh_cn.insert(
+        "account.add.prompt_enabled",
+        "启用该账号？停用后不会出现在切换列表中",
+    );
+    zh_cn.insert("account.expiry.expired", "已过期");
+    zh_cn.insert("account.expiry.days_left", "{} 天后到期");
+    zh_cn.insert("account.expiry.warning", "⚠ 有 {} 个账号已过期或即将到期：");
+
+    // 账号导入适配器
+    zh_cn.insert("account.import.detected_format", "识别到的格式: {}");
+    zh_cn.insert(
+        "account.import.format_providers",
+        "账号导出文件 (providers)",
+    );
+    zh_cn.insert("account.import.format_backup", "配置备份文件");
+    zh_cn.insert("account.import.format_unknown", "未知格式");
+
+    // 账号导出
+    zh_cn.insert("account.export.prompt_format", "导出格式");
+    zh_cn.insert("account.export.filter_base_url", "按 API 地址筛选");
+    zh_cn.insert("account.export.filter_select", "手动选择账号");
+    zh_cn.insert("account.export.prompt_output", "输出文件");
+    zh_cn.insert(
+        "account.export.prompt_output_dir",
+        "输出目录（每个账号一个文件）",
+    );
+    zh_cn.insert("account.export.error", "✗ 导出失败: {}");
+    zh_cn.insert(
+        "account.export.format_json",
+        "完整 JSON（账号、API 地址、目录和绑定）",
+    );
+    zh_cn.insert("account.export.format_dotenv", ".env 文件（每个账号一个）");
+    zh_cn.insert("account.export.format_shell", "可 source 的 shell 脚本");
+
+    // 导入比对与冲突处理
+    zh_cn.insert("import.header_kind", "类型");
+    zh_cn.insert("import.header_name", "名称");
+    zh_cn.insert("import.header_status", "状态");
+    zh_cn.insert("import.header_diff", "差异");
+    zh_cn.insert("import.header_result", "结果");
+    zh_cn.insert("import.header_detail", "说明");
+    zh_cn.insert("import.kind.account", "账号");
+    zh_cn.insert("import.kind.base_url", "API 地址");
+    zh_cn.insert("import.kind.directory", "目录");
+    zh_cn.insert("import.kind.binding", "绑定");
+    zh_cn.insert("import.status.new", "新增");
+    zh_cn.insert("import.status.identical", "相同");
+    zh_cn.insert("import.status.changed", "有差异");
+    zh_cn.insert("import.status.duplicate", "文件内重复");
+    zh_cn.insert(
+        "import.strategy.prompt",
+        "有 {} 项与已有记录不同，选择处理方式",
+    );
+    zh_cn.insert("import.strategy.skip", "跳过，保留已有记录");
+    zh_cn.insert("import.strategy.overwrite", "覆盖已有记录");
+    zh_cn.insert("import.strategy.rename", "以新名称导入（仅名称冲突时）");
+    zh_cn.insert("import.strategy.merge", "合并，只补全缺少的字段");
+    zh_cn.insert("import.strategy.per_item", "逐项选择");
+    zh_cn.insert("import.strategy.item_prompt", "{} 的处理方式");
+    zh_cn.insert("import.nothing_to_do", "没有需要导入的内容");
+    zh_cn.insert("import.confirm", "确认导入？");
+    zh_cn.insert("import.action.created", "已创建");
+    zh_cn.insert("import.action.renamed", "已以新名称创建");
+    zh_cn.insert("import.action.overwritten", "已覆盖");
+    zh_cn.insert("import.action.merged", "已合并");
+    zh_cn.insert("import.action.unchanged", "无变化");
+    zh_cn.insert("import.action.skipped", "已跳过");
+    zh_cn.insert("import.action.failed", "失败");
+    zh_cn.insert(
+        "import.summary",
+        "新增 {created}，更新 {updated}，跳过 {skipped}，失败 {failed}",
+    );
+
+    // 代理管理
+    zh_cn.insert("menu.main.proxy", "🧭 代理管理");
+    zh_cn.insert("switch.using_proxy", "使用绑定的代理: {}");
+    zh_cn.insert("proxy.menu.title", "代理管理");
+    zh_cn.insert("proxy.menu.list", "📝 查看所有代理");
+    zh_cn.insert("proxy.menu.add", "➕ 添加代理");
+    zh_cn.insert("proxy.menu.edit", "✏️  编辑代理");
+    zh_cn.insert("proxy.menu.delete", "🗑️  删除代理");
+    zh_cn.insert("proxy.menu.test", "📡 测试代理");
+    zh_cn.insert("proxy.menu.assign_base_url", "🌐 为 URL 选择代理");
+    zh_cn.insert("proxy.menu.assign_account", "👤 为账号选择代理");
+    zh_cn.insert("proxy.menu.assign_directory", "📁 为目录选择代理");
+    zh_cn.insert("proxy.menu.assign_webdav", "☁️  为 WebDAV 配置选择代理");
+    zh_cn.insert("proxy.list.no_records", "暂无代理");
+    zh_cn.insert("proxy.list.header_id", "ID");
+    zh_cn.insert("proxy.list.header_name", "名称");
+    zh_cn.insert("proxy.list.header_url", "代理地址");
+    zh_cn.insert("proxy.list.header_no_proxy", "不走代理");
+    zh_cn.insert("proxy.list.header_username", "用户名");
+    zh_cn.insert("proxy.list.header_description", "描述");
+    zh_cn.insert("proxy.list.total", "共 {} 个代理");
+    zh_cn.insert("proxy.add.title", "添加代理");
+    zh_cn.insert("proxy.add.prompt_name", "代理名称");
+    zh_cn.insert(
+        "proxy.add.url_hint",
+        "支持 http://、https://、socks5://、socks5h:// 地址，如 http://127.0.0.1:7890",
+    );
+    zh_cn.insert("proxy.add.prompt_url", "代理地址");
+    zh_cn.insert(
+        "proxy.add.prompt_no_proxy",
+        "不走代理的主机，逗号分隔 (可选)",
+    );
+    zh_cn.insert("proxy.add.prompt_username", "用户名 (可选)");
+    zh_cn.insert("proxy.add.prompt_password", "密码 (可选)");
+    zh_cn.insert("proxy.add.prompt_description", "描述 (可选)");
+    zh_cn.insert("proxy.add.success", "✓ 代理 '{}' 添加成功");
+    zh_cn.insert("proxy.add.error", "✗ 添加代理失败: {}");
+    zh_cn.insert("proxy.edit.prompt", "选择要编辑的代理");
+    zh_cn.insert("proxy.edit.success", "✓ 代理更新成功");
+    zh_cn.insert("proxy.edit.error", "✗ 更新代理失败: {}");
+    zh_cn.insert("proxy.delete.prompt", "选择要删除的代理");
+    zh_cn.insert(
+        "proxy.delete.confirm",
+        "确定要删除代理 '{}' 吗？绑定该代理的 URL、账号、目录和 WebDAV 配置将解除绑定",
+    );
+    zh_cn.insert("proxy.delete.success", "✓ 代理删除成功");
+    zh_cn.insert("proxy.delete.error", "✗ 删除代理失败: {}");
+    zh_cn.insert("proxy.test.prompt", "选择要测试的代理");
+    zh_cn.insert("proxy.test.prompt_target", "测试地址");
+    zh_cn.insert("proxy.test.testing", "正在通过代理访问...");
+    zh_cn.insert("proxy.test.success", "✓ 代理可用，耗时 {} ms");
+    zh_cn.insert("proxy.test.error", "✗ 代理不可用: {}");
+    zh_cn.insert("proxy.assign.prompt_proxy", "选择代理");
+    zh_cn.insert("proxy.assign.none", "(不使用代理)");
+    zh_cn.insert("proxy.assign.select_base_url", "选择 URL");
+    zh_cn.insert("proxy.assign.select_webdav", "选择 WebDAV 配置");
+    zh_cn.insert("proxy.assign.success", "✓ 代理已设置");
+    zh_cn.insert("proxy.assign.error", "✗ 设置代理失败: {}");
+
+    // 供应商类型
+    zh_cn.insert("url.list.header_provider", "供应商");
+    zh_cn.insert("url.add.prompt_provider", "供应商类型");
+    zh_cn.insert(
+        "url.add.provider_hint",
+        "该 URL 将写入 {url}，账号需要填写: {vars}",
+    );
+    zh_cn.insert("account.add.provider_env_title", "{} 环境变量（留空跳过）");
+    zh_cn.insert("account.add.provider_env_optional", "（可选）");
+    zh_cn.insert("account.add.provider_invalid", "✗ 账号配置不完整: {}");
+    zh_cn.insert("switch.provider", "供应商: {}");
+
+    // 凭据模式
+    zh_cn.insert("account.credential.prompt_mode", "凭据模式");
+    zh_cn.insert("account.credential.mode_token", "静态令牌");
+    zh_cn.insert("account.credential.mode_helper", "辅助命令（apiKeyHelper）");
+    zh_cn.insert(
+        "account.credential.prompt_helper",
+        "生成密钥的命令（输出到标准输出）",
+    );
+    zh_cn.insert(
+        "account.credential.prompt_ttl",
+        "缓存时间（毫秒，留空使用默认值）",
+    );
+    zh_cn.insert("account.credential.invalid_ttl", "✗ 缓存时间必须是整数");
+    zh_cn.insert("account.credential.testing", "正在试运行辅助命令...");
+    zh_cn.insert(
+        "account.credential.test_ok",
+        "✓ 辅助命令可用，生成的密钥: {}",
+    );
+    zh_cn.insert("account.credential.test_failed", "✗ 辅助命令运行失败: {}");
+    zh_cn.insert("account.credential.save_anyway", "仍然保存?");
+    zh_cn.insert("switch.key_helper", "凭据: apiKeyHelper ({})");
+
+    // 自定义请求头
+    zh_cn.insert("headers.prompt_edit", "编辑自定义请求头？");
+    zh_cn.insert("headers.current", "自定义请求头: {}");
+    zh_cn.insert("headers.none", "无");
+    zh_cn.insert("headers.prompt_action", "请求头操作");
+    zh_cn.insert("headers.action_add", "添加/修改请求头");
+    zh_cn.insert("headers.action_remove", "删除请求头");
+    zh_cn.insert("headers.action_done", "完成");
+    zh_cn.insert("headers.prompt_line", "请求头 (Name: Value)");
+    zh_cn.insert("headers.prompt_remove", "选择要删除的请求头");
+    zh_cn.insert("headers.invalid", "请求头无效: {}");
+    zh_cn.insert("url.list.header_headers", "请求头");
+    zh_cn.insert("switch.headers", "自定义请求头: {}");
+
+    // 数据库迁移
+    zh_cn.insert("migrate.usage", "用法: claude-config migrate status");
+    zh_cn.insert("migrate.title", "数据库迁移状态");
+    zh_cn.insert(
+        "migrate.version",
+        "当前结构版本: {}，程序支持的最新版本: {}",
+    );
+    zh_cn.insert("migrate.header_version", "版本");
+    zh_cn.insert("migrate.header_name", "名称");
+    zh_cn.insert("migrate.header_applied_at", "执行时间");
+    zh_cn.insert("migrate.status_pending", "待执行");
+    zh_cn.insert("migrate.status_unknown", "未知迁移");
+    zh_cn.insert(
+        "migrate.too_new",
+        "数据库由更新版本的程序迁移过，请升级程序后再使用",
+    );
+    zh_cn.insert("migrate.pending_hint", "待执行的迁移会在下次启动时自动执行");
+
     translations.insert(Language::ZhCN, zh_cn);
 
     // 英文翻译
@@ -443,6 +1070,28 @@ static TRANSLATIONS: Lazy<HashMap<Language, HashMap<TransKey, &'static str>>> =
     en_us.insert("menu.settings.current_lang", "Current Language");
     en_us.insert("menu.settings.select_lang", "Please select a language");
     en_us.insert("menu.settings.lang_changed", "Language changed");
+    en_us.insert("menu.settings.database", "🗄️  Database Connection");
+    en_us.insert("menu.settings.current_db", "Current Database Connection");
+    en_us.insert(
+        "menu.settings.select_db",
+        "Please select a database connection",
+    );
+    en_us.insert(
+        "menu.settings.db_already_current",
+        "This database connection is already in use",
+    );
+    en_us.insert(
+        "menu.settings.db_switching",
+        "Connecting to and migrating the new database...",
+    );
+    en_us.insert(
+        "menu.settings.db_switched",
+        "Switched to database connection",
+    );
+    en_us.insert(
+        "menu.settings.db_switch_failed",
+        "Failed to switch database connection, keeping the current one",
+    );
 
     // Common operations
     en_us.insert("common.success", "✓ Operation successful");
@@ -483,12 +1132,17 @@ static TRANSLATIONS: Lazy<HashMap<Language, HashMap<TransKey, &'static str>>> =
     en_us.insert("account.menu.delete", "🗑️  Delete Account");
     en_us.insert("account.menu.import", "📥 Batch Import");
     en_us.insert("account.menu.export", "📤 Batch Export");
+    en_us.insert("account.menu.verify", "🔑 Verify Tokens");
+    en_us.insert("account.menu.balance", "💰 Refresh Balances");
+    en_us.insert("account.menu.pools", "🏊 Account Pools");
     en_us.insert("account.list.no_records", "No account records");
     en_us.insert("account.list.header_id", "ID");
     en_us.insert("account.list.header_name", "Account Name");
     en_us.insert("account.list.header_base_url", "Base URL");
     en_us.insert("account.list.header_model", "Model");
     en_us.insert("account.list.header_status", "Status");
+    en_us.insert("account.list.header_token", "Token");
+    en_us.insert("account.list.header_balance", "Balance");
     en_us.insert("account.list.status_active", "🟢 Active");
     en_us.insert("account.list.status_inactive", "⚪ Inactive");
     en_us.insert("account.list.total", "Total {} accounts");
@@ -518,27 +1172,16 @@ static TRANSLATIONS: Lazy<HashMap<Language, HashMap<TransKey, &'static str>>> =
     en_us.insert("account.export.no_accounts", "No accounts to export");
     en_us.insert(
         "account.export.success",
-        "✓ Successfully exported {} account(s) to file: {file}",
+        "✓ Successfully exported {} account(s) to: {file}",
     );
     en_us.insert("account.import.title", "Batch Import Accounts");
-    en_us.insert("account.import.prompt_file", "Enter JSON file path");
-    en_us.insert("account.import.error_read", "✗ Failed to read file: {}");
-    en_us.insert("account.import.error_parse", "✗ Failed to parse JSON: {}");
     en_us.insert(
-        "account.import.error_format",
-        "✗ Invalid file format, missing providers array",
+        "account.import.prompt_file",
+        "Enter the file path to import (JSON / CSV / .env)",
     );
+    en_us.insert("account.import.error_read", "✗ Failed to read file: {}");
+    en_us.insert("account.import.error_parse", "✗ Failed to parse file: {}");
     en_us.insert("account.import.no_accounts", "No account data in file");
-    en_us.insert("account.import.processing", "Processing import...");
-    en_us.insert("account.import.skip_invalid", "Skip invalid data");
-    en_us.insert("account.import.skip_exists", "Skip existing");
-    en_us.insert("account.import.imported", "Imported");
-    en_us.insert("account.import.failed", "Import failed");
-    en_us.insert(
-        "account.import.result_imported",
-        "✓ Successfully imported {} account(s)",
-    );
-    en_us.insert("account.import.result_skipped", "⊖ Skipped {} account(s)");
     en_us.insert("account.default_indicator", "(default)");
 
     // Directory Management
@@ -598,6 +1241,7 @@ static TRANSLATIONS: Lazy<HashMap<Language, HashMap<TransKey, &'static str>>> =
     en_us.insert("url.list.header_description", "Description");
     en_us.insert("url.list.header_api_key", "API Key Env Var");
     en_us.insert("url.list.header_default", "Default");
+    en_us.insert("url.list.header_health", "Last Check");
     en_us.insert("url.list.default_yes", "Yes");
     en_us.insert("url.list.default_no", "No");
     en_us.insert("url.list.total", "Total {} URLs");
@@ -695,24 +1339,6 @@ static TRANSLATIONS: Lazy<HashMap<Language, HashMap<TransKey, &'static str>>> =
     en_us.insert("webdav.menu.delete", "🗑️  Delete Configuration");
     en_us.insert("webdav.test.success", "✓ WebDAV connection test successful");
     en_us.insert("webdav.test.error", "✗ Connection test failed: {}");
-    en_us.insert(
-        "webdav.upload.clearing",
-        "Clearing existing configuration...",
-    );
-    en_us.insert(
-        "webdav.upload.cleared",
-        "✓ Cleared existing accounts and Base URLs",
-    );
-    en_us.insert("webdav.upload.importing_accounts", "Importing accounts...");
-    en_us.insert(
-        "webdav.upload.imported_accounts",
-        "✓ Successfully imported {} accounts",
-    );
-    en_us.insert("webdav.upload.importing_urls", "Importing Base URLs...");
-    en_us.insert(
-        "webdav.upload.imported_urls",
-        "✓ Successfully imported {} Base URLs",
-    );
     en_us.insert(
         "webdav.upload.success",
         "✓ Configuration successfully uploaded to WebDAV: {}",
@@ -792,6 +1418,805 @@ static TRANSLATIONS: Lazy<HashMap<Language, HashMap<TransKey, &'static str>>> =
         "✗ Root check removal script not found: {}",
     );
 
+    // MCP Servers
+    en_us.insert("menu.main.mcp", "🔌 MCP Servers");
+    en_us.insert("mcp.menu.title", "MCP Server Management");
+    en_us.insert("mcp.menu.list", "📝 View All MCP Servers");
+    en_us.insert("mcp.menu.add", "➕ Add MCP Server");
+    en_us.insert("mcp.menu.edit", "✏️  Edit MCP Server");
+    en_us.insert("mcp.menu.delete", "🗑️  Delete MCP Server");
+    en_us.insert("mcp.menu.assign_directory", "📁 Assign to Directory");
+    en_us.insert("mcp.menu.assign_account", "👤 Assign to Account");
+    en_us.insert("mcp.list.no_records", "No MCP servers");
+    en_us.insert("mcp.list.header_id", "ID");
+    en_us.insert("mcp.list.header_name", "Name");
+    en_us.insert("mcp.list.header_transport", "Transport");
+    en_us.insert("mcp.list.header_target", "Command / URL");
+    en_us.insert("mcp.list.header_description", "Description");
+    en_us.insert("mcp.list.total", "Total {} MCP servers");
+    en_us.insert("mcp.add.title", "Add MCP Server");
+    en_us.insert("mcp.add.prompt_name", "Name");
+    en_us.insert("mcp.add.prompt_transport", "Transport");
+    en_us.insert("mcp.add.prompt_command", "Command");
+    en_us.insert("mcp.add.prompt_args", "Arguments (space separated)");
+    en_us.insert(
+        "mcp.add.prompt_env",
+        "Environment variables (KEY=VALUE, comma separated)",
+    );
+    en_us.insert("mcp.add.prompt_url", "URL");
+    en_us.insert("mcp.add.prompt_description", "Description (optional)");
+    en_us.insert("mcp.add.success", "✓ MCP server '{}' created successfully");
+    en_us.insert("mcp.add.error", "✗ Creation failed: {}");
+    en_us.insert("mcp.edit.prompt", "Select MCP server to edit");
+    en_us.insert("mcp.edit.success", "✓ MCP server updated successfully");
+    en_us.insert("mcp.edit.error", "✗ Update failed: {}");
+    en_us.insert("mcp.delete.prompt", "Select MCP server to delete");
+    en_us.insert(
+        "mcp.delete.confirm",
+        "Are you sure you want to delete MCP server '{}'?",
+    );
+    en_us.insert("mcp.delete.success", "✓ MCP server deleted successfully");
+    en_us.insert("mcp.delete.error", "✗ Deletion failed: {}");
+    en_us.insert(
+        "mcp.assign.prompt_servers",
+        "Select MCP servers to enable (Space to toggle, Enter to confirm)",
+    );
+    en_us.insert("mcp.assign.success", "✓ MCP server assignment saved");
+    en_us.insert("mcp.assign.error", "✗ Failed to save assignment: {}");
+    en_us.insert("switch.mcp_servers", "  MCP Servers: {}");
+    en_us.insert(
+        "switch.warn_mcp_fail",
+        "Warning: Failed to write MCP server config: {}",
+    );
+
+    // Hooks
+    en_us.insert("menu.main.hooks", "🪝 Hook Management");
+    en_us.insert("hooks.menu.title", "Hook Management");
+    en_us.insert("hooks.menu.list", "📝 View All Hooks");
+    en_us.insert("hooks.menu.add", "➕ Add Hook");
+    en_us.insert("hooks.menu.edit", "✏️  Edit Hook");
+    en_us.insert("hooks.menu.delete", "🗑️  Delete Hook");
+    en_us.insert("hooks.menu.assign_directory", "📁 Assign to Directory");
+    en_us.insert("hooks.list.no_records", "No hooks");
+    en_us.insert("hooks.list.header_id", "ID");
+    en_us.insert("hooks.list.header_name", "Name");
+    en_us.insert("hooks.list.header_event", "Event");
+    en_us.insert("hooks.list.header_matcher", "Matcher");
+    en_us.insert("hooks.list.header_command", "Command");
+    en_us.insert("hooks.list.header_timeout", "Timeout (s)");
+    en_us.insert("hooks.list.header_global", "Global");
+    en_us.insert("hooks.list.total", "Total {} hooks");
+    en_us.insert("hooks.add.title", "Add Hook");
+    en_us.insert("hooks.add.prompt_name", "Name");
+    en_us.insert("hooks.add.prompt_event", "Event");
+    en_us.insert(
+        "hooks.add.prompt_matcher",
+        "Matcher (e.g. Bash, Edit|Write, empty matches all)",
+    );
+    en_us.insert("hooks.add.prompt_command", "Command");
+    en_us.insert("hooks.add.prompt_timeout", "Timeout in seconds (optional)");
+    en_us.insert("hooks.add.invalid_timeout", "Please enter a valid number");
+    en_us.insert(
+        "hooks.add.prompt_global",
+        "Attach to global Claude settings (applies to all directories)?",
+    );
+    en_us.insert("hooks.add.prompt_description", "Description (optional)");
+    en_us.insert("hooks.add.success", "✓ Hook '{}' created successfully");
+    en_us.insert("hooks.add.error", "✗ Creation failed: {}");
+    en_us.insert("hooks.edit.prompt", "Select hook to edit");
+    en_us.insert("hooks.edit.success", "✓ Hook updated successfully");
+    en_us.insert("hooks.edit.error", "✗ Update failed: {}");
+    en_us.insert("hooks.delete.prompt", "Select hook to delete");
+    en_us.insert(
+        "hooks.delete.confirm",
+        "Are you sure you want to delete hook '{}'?",
+    );
+    en_us.insert("hooks.delete.success", "✓ Hook deleted successfully");
+    en_us.insert("hooks.delete.error", "✗ Deletion failed: {}");
+    en_us.insert(
+        "hooks.assign.no_local_hooks",
+        "No hooks to assign (global hooks apply to all directories automatically)",
+    );
+    en_us.insert(
+        "hooks.assign.prompt_hooks",
+        "Select hooks to enable (Space to toggle, Enter to confirm)",
+    );
+    en_us.insert("hooks.assign.success", "✓ Hook assignment saved");
+    en_us.insert("hooks.assign.error", "✗ Failed to save assignment: {}");
+
+    // Permission presets
+    en_us.insert("menu.main.permissions", "🛡️  Permission Presets");
+    en_us.insert("permissions.menu.title", "Permission Preset Management");
+    en_us.insert("permissions.menu.list", "📝 List All Presets");
+    en_us.insert("permissions.menu.add", "➕ Add Preset");
+    en_us.insert("permissions.menu.edit", "✏️  Edit Preset");
+    en_us.insert("permissions.menu.delete", "🗑️  Delete Preset");
+    en_us.insert(
+        "permissions.menu.assign_directory",
+        "📁 Assign Preset to Directory",
+    );
+    en_us.insert(
+        "permissions.menu.assign_account",
+        "👤 Assign Preset to Account",
+    );
+    en_us.insert("permissions.list.no_records", "No permission presets");
+    en_us.insert("permissions.list.header_id", "ID");
+    en_us.insert("permissions.list.header_name", "Name");
+    en_us.insert("permissions.list.header_mode", "Default Mode");
+    en_us.insert("permissions.list.header_allow", "Allow");
+    en_us.insert("permissions.list.header_deny", "Deny");
+    en_us.insert("permissions.list.header_ask", "Ask");
+    en_us.insert("permissions.list.header_builtin", "Built-in");
+    en_us.insert("permissions.list.total", "Total {} presets");
+    en_us.insert("permissions.add.title", "Add Permission Preset");
+    en_us.insert("permissions.add.prompt_name", "Preset name");
+    en_us.insert("permissions.add.prompt_mode", "Default permission mode");
+    en_us.insert(
+        "permissions.add.rules_hint",
+        "Separate rules with commas, e.g. Read, Bash(npm run test:*), mcp__github",
+    );
+    en_us.insert("permissions.add.prompt_allow", "Allow rules");
+    en_us.insert("permissions.add.prompt_deny", "Deny rules");
+    en_us.insert("permissions.add.prompt_ask", "Ask rules");
+    en_us.insert(
+        "permissions.add.prompt_description",
+        "Description (optional)",
+    );
+    en_us.insert(
+        "permissions.add.success",
+        "✓ Permission preset '{}' added successfully",
+    );
+    en_us.insert(
+        "permissions.add.error",
+        "✗ Failed to add permission preset: {}",
+    );
+    en_us.insert("permissions.edit.prompt", "Select preset to edit");
+    en_us.insert(
+        "permissions.edit.success",
+        "✓ Permission preset updated successfully",
+    );
+    en_us.insert(
+        "permissions.edit.error",
+        "✗ Failed to update permission preset: {}",
+    );
+    en_us.insert("permissions.delete.prompt", "Select preset to delete");
+    en_us.insert(
+        "permissions.delete.builtin",
+        "Built-in presets cannot be deleted",
+    );
+    en_us.insert(
+        "permissions.delete.confirm",
+        "Delete preset '{}'? Directories and accounts using it will be unassigned",
+    );
+    en_us.insert(
+        "permissions.delete.success",
+        "✓ Permission preset deleted successfully",
+    );
+    en_us.insert(
+        "permissions.delete.error",
+        "✗ Failed to delete permission preset: {}",
+    );
+    en_us.insert(
+        "permissions.assign.prompt_preset",
+        "Select permission preset",
+    );
+    en_us.insert("permissions.assign.none", "(No preset)");
+    en_us.insert("permissions.assign.success", "✓ Permission preset assigned");
+    en_us.insert(
+        "permissions.assign.error",
+        "✗ Failed to assign permission preset: {}",
+    );
+    en_us.insert(
+        "switch.using_permission_preset",
+        "Using permission preset: {}",
+    );
+    en_us.insert("switch.permission_preset", "Preset {}");
+
+    // Commands and subagents
+    en_us.insert("menu.main.definitions", "📚 Commands & Subagents");
+    en_us.insert("definitions.menu.title", "Commands & Subagents Management");
+    en_us.insert("definitions.menu.list", "📝 View All Definitions");
+    en_us.insert("definitions.menu.add", "➕ Add Definition");
+    en_us.insert("definitions.menu.edit", "✏️  Edit Definition");
+    en_us.insert("definitions.menu.delete", "🗑️  Delete Definition");
+    en_us.insert(
+        "definitions.menu.import",
+        "📥 Import from Project .claude Folder",
+    );
+    en_us.insert(
+        "definitions.menu.assign_directory",
+        "📁 Enable/Disable per Directory",
+    );
+    en_us.insert("definitions.kind.command", "Slash Command");
+    en_us.insert("definitions.kind.agent", "Subagent");
+    en_us.insert("definitions.list.no_records", "No definitions");
+    en_us.insert("definitions.list.header_id", "ID");
+    en_us.insert("definitions.list.header_kind", "Kind");
+    en_us.insert("definitions.list.header_name", "Name");
+    en_us.insert("definitions.list.header_description", "Description");
+    en_us.insert("definitions.list.header_default", "Enabled by Default");
+    en_us.insert("definitions.list.total", "Total {} definitions");
+    en_us.insert("definitions.add.title", "Add Definition");
+    en_us.insert("definitions.add.prompt_kind", "Kind");
+    en_us.insert(
+        "definitions.add.prompt_name",
+        "Name (commands may use / for subfolders)",
+    );
+    en_us.insert("definitions.add.prompt_file", "Markdown file path");
+    en_us.insert("definitions.add.read_error", "✗ Failed to read file: {}");
+    en_us.insert(
+        "definitions.add.prompt_description",
+        "Description (empty to use front-matter description)",
+    );
+    en_us.insert(
+        "definitions.add.prompt_default",
+        "Enable for all directories by default?",
+    );
+    en_us.insert(
+        "definitions.add.success",
+        "✓ Definition '{}' created successfully",
+    );
+    en_us.insert("definitions.add.error", "✗ Creation failed: {}");
+    en_us.insert("definitions.edit.prompt", "Select definition to edit");
+    en_us.insert(
+        "definitions.edit.prompt_file",
+        "New Markdown file path (empty to keep content)",
+    );
+    en_us.insert(
+        "definitions.edit.success",
+        "✓ Definition updated successfully",
+    );
+    en_us.insert("definitions.edit.error", "✗ Update failed: {}");
+    en_us.insert("definitions.delete.prompt", "Select definition to delete");
+    en_us.insert(
+        "definitions.delete.confirm",
+        "Are you sure you want to delete definition '{}'?",
+    );
+    en_us.insert(
+        "definitions.delete.success",
+        "✓ Definition deleted successfully",
+    );
+    en_us.insert("definitions.delete.error", "✗ Deletion failed: {}");
+    en_us.insert(
+        "definitions.import.title",
+        "Import Definitions from Project",
+    );
+    en_us.insert("definitions.import.prompt_path", "Project directory path");
+    en_us.insert(
+        "definitions.import.no_files",
+        "No definitions found in .claude/commands or .claude/agents",
+    );
+    en_us.insert(
+        "definitions.import.prompt_overwrite",
+        "Overwrite definitions with the same name?",
+    );
+    en_us.insert(
+        "definitions.import.success",
+        "✓ Import finished: {} imported, {} skipped",
+    );
+    en_us.insert("definitions.import.error", "✗ Import failed: {}");
+    en_us.insert(
+        "definitions.assign.prompt_definitions",
+        "Select definitions enabled for this directory (Space to toggle, Enter to confirm)",
+    );
+    en_us.insert(
+        "definitions.assign.success",
+        "✓ Directory definitions saved",
+    );
+    en_us.insert("definitions.assign.error", "✗ Failed to save: {}");
+    en_us.insert("switch.definitions", "  Commands/Subagents: {}");
+    en_us.insert(
+        "switch.warn_definitions_fail",
+        "Warning: Failed to deploy commands/subagents: {}",
+    );
+
+    // Configuration drift check
+    en_us.insert("menu.main.doctor", "🩺 Configuration Drift Check");
+    en_us.insert("doctor.usage", "Usage: claude-config doctor drift [--fix]");
+    en_us.insert("doctor.title", "Configuration Drift Check");
+    en_us.insert("doctor.header_directory", "Directory");
+    en_us.insert("doctor.header_account", "Bound Account");
+    en_us.insert("doctor.header_status", "Status");
+    en_us.insert("doctor.header_details", "Details");
+    en_us.insert("doctor.status_unbound", "Never switched");
+    en_us.insert("doctor.status_ok", "✓ In sync");
+    en_us.insert("doctor.status_drifted", "⚠ {} issue(s)");
+    en_us.insert("doctor.issue.missing_directory", "Directory missing");
+    en_us.insert("doctor.issue.missing_settings", "Missing settings file");
+    en_us.insert("doctor.issue.invalid_settings", "Settings file is invalid");
+    en_us.insert("doctor.issue.stale_token", "Stale token");
+    en_us.insert("doctor.issue.env_changed", "Env changed");
+    en_us.insert("doctor.issue.missing_asset", "Missing asset");
+    en_us.insert("doctor.summary_ok", "✓ All directories are in sync");
+    en_us.insert("doctor.summary_drifted", "⚠ {} directories have drifted");
+    en_us.insert("doctor.prompt_fix_all", "Fix all?");
+    en_us.insert("doctor.fix_success", "✓ Fixed {} directories");
+    en_us.insert("doctor.fix_error", "✗ Failed to fix {}: {}");
+
+    // Project discovery
+    en_us.insert("directory.menu.discover", "🔍 Discover Projects on Disk");
+    en_us.insert(
+        "discovery.title",
+        "=== Discover Existing Claude Projects ===",
+    );
+    en_us.insert("discovery.roots", "Scan roots:");
+    en_us.insert("discovery.no_roots", "No scan roots configured");
+    en_us.insert("discovery.menu.title", "Choose an action");
+    en_us.insert("discovery.menu.scan", "🔍 Scan and Import");
+    en_us.insert("discovery.menu.add_root", "➕ Add Scan Root");
+    en_us.insert("discovery.menu.remove_root", "🗑️  Remove Scan Root");
+    en_us.insert(
+        "discovery.prompt_root",
+        "Scan root (supports ~ and * wildcards)",
+    );
+    en_us.insert(
+        "discovery.prompt_remove_root",
+        "Select the scan root to remove",
+    );
+    en_us.insert(
+        "discovery.no_projects",
+        "No folders with a .claude directory or CLAUDE.md found",
+    );
+    en_us.insert("discovery.header_status", "Status");
+    en_us.insert("discovery.header_account", "Account");
+    en_us.insert("discovery.status_registered", "Registered");
+    en_us.insert("discovery.status_new", "New");
+    en_us.insert("discovery.new_token", "Unregistered token");
+    en_us.insert(
+        "discovery.nothing_to_import",
+        "All projects and tokens are already registered",
+    );
+    en_us.insert(
+        "discovery.prompt_select",
+        "Select projects to import (Space to toggle, Enter to confirm)",
+    );
+    en_us.insert(
+        "discovery.prompt_create_accounts",
+        "Also create accounts and base URLs for unregistered tokens?",
+    );
+    en_us.insert(
+        "discovery.import_success",
+        "Import finished: {} directories, {} accounts and {} base URLs created",
+    );
+
+    // Base URL health check
+    en_us.insert("url.menu.health", "🩺 Health Check");
+    en_us.insert("url.menu.balance", "💰 Balance Query Settings");
+    en_us.insert("url.health.all", "Check all URLs");
+    en_us.insert("url.health.prompt_select", "Select the URL to check");
+    en_us.insert(
+        "url.health.prompt_account",
+        "Select an account for the authenticated check",
+    );
+    en_us.insert("url.health.no_account", "No account (connectivity only)");
+    en_us.insert("url.health.checking", "Checking...");
+    en_us.insert("url.health.history", "=== Recent Checks ===");
+    en_us.insert("url.health.header_result", "Result");
+    en_us.insert("url.health.header_connect", "TCP Connect");
+    en_us.insert("url.health.header_tls", "TLS Handshake");
+    en_us.insert("url.health.header_latency", "HTTP Latency");
+    en_us.insert("url.health.header_status", "HTTP Status (root / auth)");
+    en_us.insert("url.health.header_time", "Checked At");
+    en_us.insert("url.health.ok", "✓ Healthy");
+    en_us.insert("url.health.failed", "✗ Unhealthy");
+
+    // Token verification
+    en_us.insert("account.token.valid", "✓ Valid");
+    en_us.insert("account.token.invalid", "✗ Invalid");
+    en_us.insert("account.token.error", "⚠ Check failed");
+    en_us.insert("account.token.unchecked", "Not checked");
+    en_us.insert("account.verify.all", "Verify all accounts");
+    en_us.insert("account.verify.prompt", "Select the account to verify");
+    en_us.insert("account.verify.checking", "Verifying tokens...");
+
+    // Balance queries
+    en_us.insert("account.balance.refreshing", "Querying balances...");
+    en_us.insert("account.balance.failed", "✗ Query failed");
+    en_us.insert(
+        "account.balance.no_adapter",
+        "No URL has a balance query configured, set one up in URL Management first",
+    );
+    en_us.insert("account.balance.cache_file", "Balances cached to {}");
+    en_us.insert(
+        "url.balance.prompt_select",
+        "Select the URL to configure balance queries for",
+    );
+    en_us.insert(
+        "url.balance.current",
+        "=== Current Balance Query Settings ===",
+    );
+    en_us.insert("url.balance.action_edit", "Edit settings");
+    en_us.insert("url.balance.action_remove", "Remove settings");
+    en_us.insert("url.balance.removed", "✓ Balance query settings removed");
+    en_us.insert("url.balance.prompt_method", "Request method");
+    en_us.insert(
+        "url.balance.prompt_url",
+        "Request URL (supports {base_url} and {token} placeholders)",
+    );
+    en_us.insert(
+        "url.balance.prompt_headers",
+        "Request headers as JSON (values support placeholders, leave empty for none)",
+    );
+    en_us.insert(
+        "url.balance.invalid_headers",
+        "✗ Request headers are not a valid JSON object",
+    );
+    en_us.insert(
+        "url.balance.prompt_body",
+        "Request body (supports placeholders)",
+    );
+    en_us.insert("url.balance.prompt_path", "Balance field path");
+    en_us.insert(
+        "url.balance.prompt_divisor",
+        "Divisor (raw value is divided by this)",
+    );
+    en_us.insert("url.balance.prompt_unit", "Unit");
+    en_us.insert("url.balance.prompt_enabled", "Enable balance queries?");
+    en_us.insert("url.balance.saved", "✓ Balance query settings saved");
+
+    // Account pools
+    en_us.insert("pool.menu.title", "Account Pool Management");
+    en_us.insert("pool.menu.list", "📝 View All Pools");
+    en_us.insert("pool.menu.add", "➕ Create Pool");
+    en_us.insert("pool.menu.delete", "🗑️  Delete Pool");
+    en_us.insert("pool.menu.members", "👥 Manage Members (priority / weight)");
+    en_us.insert("pool.menu.bind", "📁 Bind Directory to Pool");
+    en_us.insert("pool.prompt_select", "Select a pool");
+    en_us.insert("pool.list.no_records", "No account pools");
+    en_us.insert("pool.list.directories", "Bound directories: {}");
+    en_us.insert("pool.list.header_priority", "Priority");
+    en_us.insert("pool.list.header_weight", "Weight");
+    en_us.insert("pool.add.prompt_name", "Pool name");
+    en_us.insert("pool.add.prompt_description", "Description (optional)");
+    en_us.insert("pool.add.success", "✓ Pool {} created");
+    en_us.insert("pool.add.error", "✗ Failed to create pool: {}");
+    en_us.insert(
+        "pool.delete.confirm",
+        "Delete pool {}? Bound directories will be unbound",
+    );
+    en_us.insert("pool.delete.success", "✓ Pool {} deleted");
+    en_us.insert(
+        "pool.members.prompt_account",
+        "Select an account to add or adjust",
+    );
+    en_us.insert("pool.members.action_edit", "Edit priority and weight");
+    en_us.insert("pool.members.action_remove", "Remove from pool");
+    en_us.insert("pool.members.removed", "✓ Removed from pool");
+    en_us.insert(
+        "pool.members.prompt_priority",
+        "Priority (lower is preferred)",
+    );
+    en_us.insert(
+        "pool.members.prompt_weight",
+        "Weight (share within the same priority)",
+    );
+    en_us.insert("pool.members.saved", "✓ Member saved");
+    en_us.insert("pool.bind.none", "No pool");
+    en_us.insert("pool.bind.success", "✓ Directory pool binding updated");
+    en_us.insert("pool.bind.switched", "Directory switched to account {}");
+    en_us.insert(
+        "pool.failover.line",
+        "Pool {pool}: account {from} of directory {directory} is unavailable",
+    );
+    en_us.insert(
+        "pool.failover.none",
+        "no other available account in the pool",
+    );
+    en_us.insert("pool.menu.failovers", "📜 Failover History");
+    en_us.insert("pool.failovers.no_records", "No failovers recorded");
+    en_us.insert("pool.failovers.header_time", "Time");
+    en_us.insert("pool.failovers.header_pool", "Pool");
+    en_us.insert("pool.failovers.header_directory", "Directory");
+    en_us.insert("pool.failovers.header_from", "From");
+    en_us.insert("pool.failovers.header_to", "Switched To");
+
+    // Local relay proxy
+    en_us.insert("serve.usage", "Usage: claude-config serve [--port PORT]");
+    en_us.insert(
+        "serve.bind_error",
+        "Relay proxy could not listen on the port",
+    );
+    en_us.insert("serve.listening", "✓ Local relay proxy listening on {}");
+    en_us.insert(
+        "serve.hint",
+        "Point a directory's ANTHROPIC_BASE_URL at /pools/{pool id} to forward through the pool, press Ctrl+C to stop",
+    );
+
+    // Pool relay
+    en_us.insert("pool.menu.relay", "🔀 Route Directory through Local Relay");
+    en_us.insert("pool.menu.relay_stats", "📊 Relay Statistics");
+    en_us.insert(
+        "pool.relay.no_directories",
+        "No directory is bound to a pool, bind one first",
+    );
+    en_us.insert(
+        "pool.relay.direct",
+        "Direct (write the account's real URL and token)",
+    );
+    en_us.insert(
+        "pool.relay.via_relay",
+        "Via local relay (rotate accounts in the pool)",
+    );
+    en_us.insert(
+        "pool.relay.prompt_mode",
+        "Select how the directory connects",
+    );
+    en_us.insert("pool.relay.prompt_port", "Relay port");
+    en_us.insert("pool.relay.success", "✓ Directory configuration updated");
+    en_us.insert(
+        "pool.relay.serve_hint",
+        "Run claude-config serve --port {} to start the relay",
+    );
+    en_us.insert(
+        "pool.relay.error",
+        "✗ Failed to update directory configuration: {}",
+    );
+    en_us.insert(
+        "pool.relay.no_stats",
+        "The relay has not forwarded any requests yet",
+    );
+    en_us.insert("pool.relay.header_requests", "Requests");
+    en_us.insert("pool.relay.header_failures", "Failures");
+    en_us.insert("pool.relay.header_avg_latency", "Avg Latency");
+    en_us.insert("pool.relay.header_last_status", "Last Status");
+    en_us.insert("pool.relay.header_last_used", "Last Used");
+
+    // Account lifecycle
+    en_us.insert("account.filter.prompt", "Filter accounts");
+    en_us.insert("account.filter.all", "All accounts");
+    en_us.insert("account.filter.enabled", "Enabled only");
+    en_us.insert("account.filter.disabled", "Disabled only");
+    en_us.insert(
+        "account.filter.expiring",
+        "Expiring within 7 days or expired",
+    );
+    en_us.insert("account.filter.tag", "By tag");
+    en_us.insert("account.filter.prompt_tag", "Tag");
+    en_us.insert("account.list.header_expires", "Expires");
+    en_us.insert("account.list.header_tags", "Tags");
+    en_us.insert("account.list.header_usage", "Uses · Last Used");
+    en_us.insert("account.list.status_disabled", "⛔ Disabled");
+    en_us.insert(
+        "account.add.prompt_expires_at",
+        "Expiry date (YYYY-MM-DD, empty for none)",
+    );
+    en_us.insert(
+        "account.add.invalid_date",
+        "Date must be in YYYY-MM-DD format",
+    );
+    en_us.insert("account.add.prompt_notes", "Notes");
+    en_us.insert("account.add.prompt_tags", "Tags (comma separated)");
+    en_us.insert(
+        "account.add.prompt_enabled",
+        "Enable this account? Disabled accounts are hidden from switching",
+    );
+    en_us.insert("account.expiry.expired", "expired");
+    en_us.insert("account.expiry.days_left", "expires in {} days");
+    en_us.insert(
+        "account.expiry.warning",
+        "⚠ {} account(s) expired or expiring soon:",
+    );
+
+    // Account import adapters
+    en_us.insert("account.import.detected_format", "Detected format: {}");
+    en_us.insert(
+        "account.import.format_providers",
+        "Account export (providers)",
+    );
+    en_us.insert("account.import.format_backup", "Configuration backup");
+    en_us.insert("account.import.format_unknown", "Unknown format");
+
+    // Account export
+    en_us.insert("account.export.prompt_format", "Export format");
+    en_us.insert("account.export.filter_base_url", "By API URL");
+    en_us.insert("account.export.filter_select", "Select accounts");
+    en_us.insert("account.export.prompt_output", "Output file");
+    en_us.insert(
+        "account.export.prompt_output_dir",
+        "Output directory (one file per account)",
+    );
+    en_us.insert("account.export.error", "✗ Export failed: {}");
+    en_us.insert(
+        "account.export.format_json",
+        "Full JSON (accounts, API URLs, directories and bindings)",
+    );
+    en_us.insert(
+        "account.export.format_dotenv",
+        ".env files (one per account)",
+    );
+    en_us.insert("account.export.format_shell", "Sourceable shell script");
+
+    // Import review and conflict handling
+    en_us.insert("import.header_kind", "Type");
+    en_us.insert("import.header_name", "Name");
+    en_us.insert("import.header_status", "Status");
+    en_us.insert("import.header_diff", "Differences");
+    en_us.insert("import.header_result", "Result");
+    en_us.insert("import.header_detail", "Detail");
+    en_us.insert("import.kind.account", "Account");
+    en_us.insert("import.kind.base_url", "API URL");
+    en_us.insert("import.kind.directory", "Directory");
+    en_us.insert("import.kind.binding", "Binding");
+    en_us.insert("import.status.new", "New");
+    en_us.insert("import.status.identical", "Identical");
+    en_us.insert("import.status.changed", "Changed");
+    en_us.insert("import.status.duplicate", "Duplicate in file");
+    en_us.insert(
+        "import.strategy.prompt",
+        "{} item(s) differ from existing records, choose how to handle them",
+    );
+    en_us.insert("import.strategy.skip", "Skip, keep existing");
+    en_us.insert("import.strategy.overwrite", "Overwrite existing");
+    en_us.insert(
+        "import.strategy.rename",
+        "Import under a new name (name conflicts only)",
+    );
+    en_us.insert(
+        "import.strategy.merge",
+        "Merge, only fill in missing fields",
+    );
+    en_us.insert("import.strategy.per_item", "Choose per item");
+    en_us.insert("import.strategy.item_prompt", "How to handle {}");
+    en_us.insert("import.nothing_to_do", "Nothing to import");
+    en_us.insert("import.confirm", "Proceed with import?");
+    en_us.insert("import.action.created", "Created");
+    en_us.insert("import.action.renamed", "Created under new name");
+    en_us.insert("import.action.overwritten", "Overwritten");
+    en_us.insert("import.action.merged", "Merged");
+    en_us.insert("import.action.unchanged", "Unchanged");
+    en_us.insert("import.action.skipped", "Skipped");
+    en_us.insert("import.action.failed", "Failed");
+    en_us.insert(
+        "import.summary",
+        "Created {created}, updated {updated}, skipped {skipped}, failed {failed}",
+    );
+
+    // Proxy management
+    en_us.insert("menu.main.proxy", "🧭 Proxy Management");
+    en_us.insert("switch.using_proxy", "Using bound proxy: {}");
+    en_us.insert("proxy.menu.title", "Proxy Management");
+    en_us.insert("proxy.menu.list", "📝 List All Proxies");
+    en_us.insert("proxy.menu.add", "➕ Add Proxy");
+    en_us.insert("proxy.menu.edit", "✏️  Edit Proxy");
+    en_us.insert("proxy.menu.delete", "🗑️  Delete Proxy");
+    en_us.insert("proxy.menu.test", "📡 Test Proxy");
+    en_us.insert("proxy.menu.assign_base_url", "🌐 Assign Proxy to URL");
+    en_us.insert("proxy.menu.assign_account", "👤 Assign Proxy to Account");
+    en_us.insert(
+        "proxy.menu.assign_directory",
+        "📁 Assign Proxy to Directory",
+    );
+    en_us.insert(
+        "proxy.menu.assign_webdav",
+        "☁️  Assign Proxy to WebDAV Config",
+    );
+    en_us.insert("proxy.list.no_records", "No proxies");
+    en_us.insert("proxy.list.header_id", "ID");
+    en_us.insert("proxy.list.header_name", "Name");
+    en_us.insert("proxy.list.header_url", "Proxy URL");
+    en_us.insert("proxy.list.header_no_proxy", "No Proxy");
+    en_us.insert("proxy.list.header_username", "Username");
+    en_us.insert("proxy.list.header_description", "Description");
+    en_us.insert("proxy.list.total", "Total {} proxies");
+    en_us.insert("proxy.add.title", "Add Proxy");
+    en_us.insert("proxy.add.prompt_name", "Proxy name");
+    en_us.insert(
+        "proxy.add.url_hint",
+        "Supports http://, https://, socks5:// and socks5h:// URLs, e.g. http://127.0.0.1:7890",
+    );
+    en_us.insert("proxy.add.prompt_url", "Proxy URL");
+    en_us.insert(
+        "proxy.add.prompt_no_proxy",
+        "Hosts that bypass the proxy, comma separated (optional)",
+    );
+    en_us.insert("proxy.add.prompt_username", "Username (optional)");
+    en_us.insert("proxy.add.prompt_password", "Password (optional)");
+    en_us.insert("proxy.add.prompt_description", "Description (optional)");
+    en_us.insert("proxy.add.success", "✓ Proxy '{}' added successfully");
+    en_us.insert("proxy.add.error", "✗ Failed to add proxy: {}");
+    en_us.insert("proxy.edit.prompt", "Select proxy to edit");
+    en_us.insert("proxy.edit.success", "✓ Proxy updated successfully");
+    en_us.insert("proxy.edit.error", "✗ Failed to update proxy: {}");
+    en_us.insert("proxy.delete.prompt", "Select proxy to delete");
+    en_us.insert(
+        "proxy.delete.confirm",
+        "Delete proxy '{}'? URLs, accounts, directories and WebDAV configs using it will be unbound",
+    );
+    en_us.insert("proxy.delete.success", "✓ Proxy deleted successfully");
+    en_us.insert("proxy.delete.error", "✗ Failed to delete proxy: {}");
+    en_us.insert("proxy.test.prompt", "Select proxy to test");
+    en_us.insert("proxy.test.prompt_target", "Test URL");
+    en_us.insert("proxy.test.testing", "Connecting through proxy...");
+    en_us.insert("proxy.test.success", "✓ Proxy works, took {} ms");
+    en_us.insert("proxy.test.error", "✗ Proxy unavailable: {}");
+    en_us.insert("proxy.assign.prompt_proxy", "Select proxy");
+    en_us.insert("proxy.assign.none", "(No proxy)");
+    en_us.insert("proxy.assign.select_base_url", "Select URL");
+    en_us.insert("proxy.assign.select_webdav", "Select WebDAV config");
+    en_us.insert("proxy.assign.success", "✓ Proxy assigned");
+    en_us.insert("proxy.assign.error", "✗ Failed to assign proxy: {}");
+
+    // Provider types
+    en_us.insert("url.list.header_provider", "Provider");
+    en_us.insert("url.add.prompt_provider", "Provider type");
+    en_us.insert(
+        "url.add.provider_hint",
+        "This URL is written to {url}; accounts must provide: {vars}",
+    );
+    en_us.insert(
+        "account.add.provider_env_title",
+        "{} environment variables (leave empty to skip)",
+    );
+    en_us.insert("account.add.provider_env_optional", " (optional)");
+    en_us.insert(
+        "account.add.provider_invalid",
+        "✗ Incomplete account settings: {}",
+    );
+    en_us.insert("switch.provider", "Provider: {}");
+
+    // Credential modes
+    en_us.insert("account.credential.prompt_mode", "Credential mode");
+    en_us.insert("account.credential.mode_token", "Static token");
+    en_us.insert(
+        "account.credential.mode_helper",
+        "Helper command (apiKeyHelper)",
+    );
+    en_us.insert(
+        "account.credential.prompt_helper",
+        "Command that prints the key to stdout",
+    );
+    en_us.insert(
+        "account.credential.prompt_ttl",
+        "Cache TTL in ms (leave empty for default)",
+    );
+    en_us.insert("account.credential.invalid_ttl", "✗ TTL must be an integer");
+    en_us.insert("account.credential.testing", "Running helper command...");
+    en_us.insert(
+        "account.credential.test_ok",
+        "✓ Helper works, generated key: {}",
+    );
+    en_us.insert(
+        "account.credential.test_failed",
+        "✗ Helper command failed: {}",
+    );
+    en_us.insert("account.credential.save_anyway", "Save anyway?");
+    en_us.insert("switch.key_helper", "Credential: apiKeyHelper ({})");
+
+    // Custom headers
+    en_us.insert("headers.prompt_edit", "Edit custom headers?");
+    en_us.insert("headers.current", "Custom headers: {}");
+    en_us.insert("headers.none", "none");
+    en_us.insert("headers.prompt_action", "Header action");
+    en_us.insert("headers.action_add", "Add or update header");
+    en_us.insert("headers.action_remove", "Remove header");
+    en_us.insert("headers.action_done", "Done");
+    en_us.insert("headers.prompt_line", "Header (Name: Value)");
+    en_us.insert("headers.prompt_remove", "Select the header to remove");
+    en_us.insert("headers.invalid", "Invalid header: {}");
+    en_us.insert("url.list.header_headers", "Headers");
+    en_us.insert("switch.headers", "Custom headers: {}");
+
+    // Database migrations
+    en_us.insert("migrate.usage", "Usage: claude-config migrate status");
+    en_us.insert("migrate.title", "Database Migration Status");
+    en_us.insert(
+        "migrate.version",
+        "Current schema version: {}, latest supported by this binary: {}",
+    );
+    en_us.insert("migrate.header_version", "Version");
+    en_us.insert("migrate.header_name", "Name");
+    en_us.insert("migrate.header_applied_at", "Applied At");
+    en_us.insert("migrate.status_pending", "Pending");
+    en_us.insert("migrate.status_unknown", "unknown migration");
+    en_us.insert(
+        "migrate.too_new",
+        "The database was migrated by a newer version; upgrade this binary before using it",
+    );
+    en_us.insert(
+        "migrate.pending_hint",
+        "Pending migrations run automatically on next start",
+    );
+
     translations.insert(Language::EnUS, en_us);
 
     translations
@@ -0,0 +1,966 @@
+use crate::custom_headers::CustomHeader;
+use crate::database::Database;
+use crate::discovery::base_url_name;
+use crate::models::*;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use sqlx::Error as SqlxError;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+pub const KIND_ACCOUNT: &str = "account";
+pub const KIND_BASE_URL: &str = "base_url";
+pub const KIND_DIRECTORY: &str = "directory";
+pub const KIND_BINDING: &str = "binding";
+
+pub const STATUS_NEW: &str = "new";
+pub const STATUS_IDENTICAL: &str = "identical";
+pub const STATUS_CHANGED: &str = "changed";
+pub const STATUS_DUPLICATE: &str = "duplicate";
+
+pub const STRATEGY_SKIP: &str = "skip";
+pub const STRATEGY_OVERWRITE: &str = "overwrite";
+pub const STRATEGY_RENAME: &str = "rename";
+pub const STRATEGY_MERGE: &str = "merge";
+
+/// 处理有差异的导入项时可选的策略，第一个为默认策略
+pub const STRATEGIES: &[&str] = &[
+    STRATEGY_SKIP,
+    STRATEGY_OVERWRITE,
+    STRATEGY_RENAME,
+    STRATEGY_MERGE,
+];
+
+pub const ACTION_CREATED: &str = "created";
+pub const ACTION_OVERWRITTEN: &str = "overwritten";
+pub const ACTION_MERGED: &str = "merged";
+pub const ACTION_RENAMED: &str = "renamed";
+pub const ACTION_UNCHANGED: &str = "unchanged";
+pub const ACTION_SKIPPED: &str = "skipped";
+pub const ACTION_FAILED: &str = "failed";
+
+/// 令牌指纹：只保留首尾几位，比对结果中不显示完整令牌
+pub fn fingerprint(token: &str) -> String {
+    let chars: Vec<char> = token.chars().collect();
+    if chars.len() <= 10 {
+        return "*".repeat(chars.len());
+    }
+    let head: String = chars[..6].iter().collect();
+    let tail: String = chars[chars.len() - 4..].iter().collect();
+    format!("{}…{}", head, tail)
+}
+
+/// 比对时使用的已有记录
+#[derive(Default)]
+pub struct ExistingRecords {
+    pub accounts: Vec<Account>,
+    pub base_urls: Vec<BaseUrl>,
+    pub directories: Vec<Directory>,
+}
+
+impl ExistingRecords {
+    pub async fn load(db: &Database) -> Result<Self, SqlxError> {
+        Ok(Self {
+            accounts: db.get_all_accounts().await?,
+            base_urls: db.get_base_urls().await?,
+            directories: db.get_directories().await?,
+        })
+    }
+}
+
+fn parse_env(raw: &str) -> BTreeMap<String, String> {
+    serde_json::from_str(raw).unwrap_or_default()
+}
+
+fn env_json(env: &BTreeMap<String, String>) -> String {
+    serde_json::to_string(env).unwrap_or_default()
+}
+
+fn sorted_env(env: &HashMap<String, String>) -> BTreeMap<String, String> {
+    env.iter().map(|(k, v)| (k.clone(), v.clone())).collect()
+}
+
+fn headers_json(headers: &[CustomHeader]) -> String {
+    serde_json::to_string(headers).unwrap_or_default()
+}
+
+/// 到期时间：RFC 3339、`YYYY-MM-DD HH:MM:SS` 或日期，按 UTC 解析
+fn parse_expiry(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    DateTime::parse_from_rfc3339(value)
+        .map(|t| t.with_timezone(&Utc))
+        .ok()
+        .or_else(|| {
+            NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+                .ok()
+                .map(|t| t.and_utc())
+        })
+        .or_else(|| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .ok()
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+                .map(|t| t.and_utc())
+        })
+}
+
+fn diff(field: &str, current: &str, incoming: &str) -> Option<FieldDiff> {
+    (current != incoming).then(|| FieldDiff {
+        field: field.to_string(),
+        current: current.to_string(),
+        incoming: incoming.to_string(),
+    })
+}
+
+fn item(kind: &str, index: usize, name: &str) -> ImportItem {
+    ImportItem {
+        kind: kind.to_string(),
+        index,
+        name: name.to_string(),
+        status: STATUS_NEW.to_string(),
+        existing_id: None,
+        matched_by: None,
+        diffs: Vec::new(),
+        strategy: None,
+    }
+}
+
+fn matched(mut item: ImportItem, id: i64, by: &str, diffs: Vec<Option<FieldDiff>>) -> ImportItem {
+    item.existing_id = Some(id);
+    item.matched_by = Some(by.to_string());
+    item.diffs = diffs.into_iter().flatten().collect();
+    item.status = if item.diffs.is_empty() {
+        STATUS_IDENTICAL
+    } else {
+        STATUS_CHANGED
+    }
+    .to_string();
+    item
+}
+
+/// 逐项比对导入数据与已有记录：账号按名称或令牌匹配，API 地址按 URL 或名称匹配，目录按路径匹配
+pub fn plan(bundle: &ImportBundle, existing: &ExistingRecords) -> Vec<ImportItem> {
+    let mut items = Vec::new();
+
+    let (mut names, mut urls) = (HashSet::new(), HashSet::new());
+    for (index, incoming) in bundle.base_urls.iter().enumerate() {
+        let mut entry = item(KIND_BASE_URL, index, &incoming.name);
+        let (new_name, new_url) = (
+            names.insert(incoming.name.as_str()),
+            urls.insert(incoming.url.as_str()),
+        );
+        if !new_name || !new_url {
+            entry.status = STATUS_DUPLICATE.to_string();
+        } else if let Some((current, by)) = existing
+            .base_urls
+            .iter()
+            .find(|bu| bu.url == incoming.url)
+            .map(|bu| (bu, "url"))
+            .or_else(|| {
+                existing
+                    .base_urls
+                    .iter()
+                    .find(|bu| bu.name == incoming.name)
+                    .map(|bu| (bu, "name"))
+            })
+        {
+            let description = incoming.description.as_deref().unwrap_or("");
+            let api_key = incoming.api_key.as_deref().unwrap_or("");
+            entry = matched(
+                entry,
+                current.id,
+                by,
+                vec![
+                    diff("name", &current.name, &incoming.name),
+                    diff("url", &current.url, &incoming.url),
+                    (!description.is_empty())
+                        .then(|| {
+                            diff(
+                                "description",
+                                current.description.as_deref().unwrap_or(""),
+                                description,
+                            )
+                        })
+                        .flatten(),
+                    (!api_key.is_empty())
+                        .then(|| diff("api_key", &current.api_key, api_key))
+                        .flatten(),
+                    incoming
+                        .provider_type
+                        .as_deref()
+                        .and_then(|p| diff("provider_type", &current.provider_type, p)),
+                    (!incoming.default_env_vars.is_empty())
+                        .then(|| {
+                            diff(
+                                "default_env_vars",
+                                &env_json(&parse_env(&current.default_env_vars)),
+                                &env_json(&sorted_env(&incoming.default_env_vars)),
+                            )
+                        })
+                        .flatten(),
+                    (!incoming.custom_headers.is_empty())
+                        .then(|| {
+                            diff(
+                                "custom_headers",
+                                &headers_json(&current.header_list()),
+                                &headers_json(&incoming.custom_headers),
+                            )
+                        })
+                        .flatten(),
+                ],
+            );
+        }
+        items.push(entry);
+    }
+
+    let mut paths = HashSet::new();
+    for (index, incoming) in bundle.directories.iter().enumerate() {
+        let mut entry = item(KIND_DIRECTORY, index, &incoming.name);
+        if !paths.insert(incoming.path.as_str()) {
+            entry.status = STATUS_DUPLICATE.to_string();
+        } else if let Some(current) = existing
+            .directories
+            .iter()
+            .find(|d| d.path == incoming.path)
+        {
+            entry = matched(
+                entry,
+                current.id,
+                "path",
+                vec![diff("name", &current.name, &incoming.name)],
+            );
+        }
+        items.push(entry);
+    }
+
+    let (mut names, mut tokens) = (HashSet::new(), HashSet::new());
+    for (index, incoming) in bundle.accounts.iter().enumerate() {
+        let mut entry = item(KIND_ACCOUNT, index, &incoming.name);
+        let (new_name, new_token) = (
+            names.insert(incoming.name.as_str()),
+            tokens.insert(incoming.token.as_str()),
+        );
+        if !new_name || !new_token {
+            entry.status = STATUS_DUPLICATE.to_string();
+        } else if let Some((current, by)) = existing
+            .accounts
+            .iter()
+            .find(|a| a.name == incoming.name)
+            .map(|a| (a, "name"))
+            .or_else(|| {
+                existing
+                    .accounts
+                    .iter()
+                    .find(|a| a.token == incoming.token)
+                    .map(|a| (a, "token"))
+            })
+        {
+            let incoming_env = sorted_env(&incoming.custom_env_vars);
+            let expires_at = incoming.expires_at.as_deref().map(|value| {
+                parse_expiry(value)
+                    .map(|t| t.to_rfc3339())
+                    .unwrap_or_else(|| value.to_string())
+            });
+            entry = matched(
+                entry,
+                current.id,
+                by,
+                vec![
+                    diff("name", &current.name, &incoming.name),
+                    (current.token != incoming.token).then(|| FieldDiff {
+                        field: "token".to_string(),
+                        current: fingerprint(&current.token),
+                        incoming: fingerprint(&incoming.token),
+                    }),
+                    diff("base_url", &current.base_url, &incoming.base_url),
+                    (!incoming.model.is_empty())
+                        .then(|| diff("model", &current.model, &incoming.model))
+                        .flatten(),
+                    (!incoming_env.is_empty())
+                        .then(|| {
+                            diff(
+                                "custom_env_vars",
+                                &env_json(&parse_env(&current.custom_env_vars)),
+                                &env_json(&incoming_env),
+                            )
+                        })
+                        .flatten(),
+                    incoming.enabled.and_then(|enabled| {
+                        diff(
+                            "enabled",
+                            &current.enabled.to_string(),
+                            &enabled.to_string(),
+                        )
+                    }),
+                    expires_at.and_then(|expires_at| {
+                        diff(
+                            "expires_at",
+                            &current
+                                .expires_at
+                                .map(|t| t.to_rfc3339())
+                                .unwrap_or_default(),
+                            &expires_at,
+                        )
+                    }),
+                    (!incoming.notes.is_empty())
+                        .then(|| diff("notes", &current.notes, &incoming.notes))
+                        .flatten(),
+                    (!incoming.tags.is_empty())
+                        .then(|| diff("tags", &current.tags, &normalize_tags(&incoming.tags)))
+                        .flatten(),
+                    incoming
+                        .credential_mode
+                        .as_deref()
+                        .and_then(|mode| diff("credential_mode", &current.credential_mode, mode)),
+                    incoming.api_key_helper.as_deref().and_then(|helper| {
+                        diff(
+                            "api_key_helper",
+                            current.api_key_helper.as_deref().unwrap_or(""),
+                            helper,
+                        )
+                    }),
+                    incoming.api_key_helper_ttl_ms.and_then(|ttl| {
+                        diff(
+                            "api_key_helper_ttl_ms",
+                            &current
+                                .api_key_helper_ttl_ms
+                                .map(|t| t.to_string())
+                                .unwrap_or_default(),
+                            &ttl.to_string(),
+                        )
+                    }),
+                    (!incoming.custom_headers.is_empty())
+                        .then(|| {
+                            diff(
+                                "custom_headers",
+                                &headers_json(&current.header_list()),
+                                &headers_json(&incoming.custom_headers),
+                            )
+                        })
+                        .flatten(),
+                ],
+            );
+        }
+        items.push(entry);
+    }
+
+    items
+}
+
+impl ImportReport {
+    fn record(&mut self, kind: &str, name: &str, action: &str, detail: Option<String>) {
+        match action {
+            ACTION_CREATED | ACTION_RENAMED => self.created += 1,
+            ACTION_OVERWRITTEN | ACTION_MERGED => self.updated += 1,
+            ACTION_FAILED => self.failed += 1,
+            _ => self.skipped += 1,
+        }
+        self.items.push(ImportReportItem {
+            kind: kind.to_string(),
+            name: name.to_string(),
+            action: action.to_string(),
+            detail,
+        });
+    }
+}
+
+type Outcome = Result<(&'static str, Option<String>), SqlxError>;
+
+/// 在已占用的名称之后追加序号，得到不重复的名称
+fn unique_name(name: &str, taken: &HashSet<String>) -> String {
+    (2..)
+        .map(|n| format!("{}-{}", name, n))
+        .find(|candidate| !taken.contains(candidate))
+        .unwrap_or_else(|| name.to_string())
+}
+
+/// 按名称匹配到的记录才能以新名称导入，按令牌、URL 或路径匹配到的是同一条记录
+fn can_rename(item: &ImportItem) -> bool {
+    item.matched_by.as_deref() == Some("name")
+}
+
+async fn apply_base_url(
+    db: &Database,
+    item: &ImportItem,
+    incoming: &ImportedBaseUrl,
+    strategy: &str,
+) -> Outcome {
+    // GUI 版本的请求还有 default_env_vars 字段
+    #[allow(clippy::needless_update)]
+    let create = |name: String| CreateBaseUrlRequest {
+        name,
+        url: incoming.url.clone(),
+        description: incoming.description.clone(),
+        api_key: incoming.api_key.clone().filter(|k| !k.is_empty()),
+        provider_type: incoming.provider_type.clone(),
+        is_default: Some(incoming.is_default),
+        ..Default::default()
+    };
+    let id = item.existing_id.unwrap_or_default();
+    match (item.status.as_str(), strategy) {
+        (STATUS_NEW, _) => {
+            let created = db.create_base_url(create(incoming.name.clone())).await?;
+            set_base_url_extras(db, created.id, incoming).await?;
+            Ok((ACTION_CREATED, None))
+        }
+        (STATUS_CHANGED, STRATEGY_OVERWRITE) => {
+            let request = UpdateBaseUrlRequest {
+                name: Some(incoming.name.clone()),
+                url: Some(incoming.url.clone()),
+                description: incoming.description.clone().filter(|d| !d.is_empty()),
+                api_key: incoming.api_key.clone().filter(|k| !k.is_empty()),
+                provider_type: incoming.provider_type.clone(),
+                ..Default::default()
+            };
+            db.update_base_url(id, request).await?;
+            set_base_url_extras(db, id, incoming).await?;
+            Ok((ACTION_OVERWRITTEN, None))
+        }
+        (STATUS_CHANGED, STRATEGY_MERGE) => {
+            // 只补全缺少的描述和请求头，默认环境变量取并集，已有的值优先
+            let current = db.get_base_url(id).await?;
+            let mut merged = false;
+            let description = incoming.description.clone().filter(|d| !d.is_empty());
+            if current.description.as_deref().unwrap_or("").is_empty() && description.is_some() {
+                let request = UpdateBaseUrlRequest {
+                    description,
+                    ..Default::default()
+                };
+                db.update_base_url(id, request).await?;
+                merged = true;
+            }
+            if current.header_list().is_empty() && !incoming.custom_headers.is_empty() {
+                db.set_base_url_headers(id, &incoming.custom_headers)
+                    .await?;
+                merged = true;
+            }
+            let current_env = parse_env(&current.default_env_vars);
+            let mut env = sorted_env(&incoming.default_env_vars);
+            env.extend(current_env.clone());
+            if env != current_env {
+                let env: HashMap<String, String> = env.into_iter().collect();
+                db.set_base_url_default_env_vars(id, &env).await?;
+                merged = true;
+            }
+            Ok((
+                if merged {
+                    ACTION_MERGED
+                } else {
+                    ACTION_UNCHANGED
+                },
+                None,
+            ))
+        }
+        (STATUS_CHANGED, STRATEGY_RENAME) if can_rename(item) => {
+            let taken = db
+                .get_base_urls()
+                .await?
+                .into_iter()
+                .map(|bu| bu.name)
+                .collect();
+            let name = unique_name(&incoming.name, &taken);
+            let created = db.create_base_url(create(name.clone())).await?;
+            set_base_url_extras(db, created.id, incoming).await?;
+            Ok((ACTION_RENAMED, Some(name)))
+        }
+        (STATUS_IDENTICAL, _) => Ok((ACTION_UNCHANGED, None)),
+        _ => Ok((ACTION_SKIPPED, None)),
+    }
+}
+
+async fn apply_directory(
+    db: &Database,
+    item: &ImportItem,
+    incoming: &ImportedDirectory,
+    strategy: &str,
+) -> Outcome {
+    match (item.status.as_str(), strategy) {
+        (STATUS_NEW, _) => {
+            let request = CreateDirectoryRequest {
+                path: incoming.path.clone(),
+                name: incoming.name.clone(),
+            };
+            db.create_directory(request).await?;
+            Ok((ACTION_CREATED, None))
+        }
+        (STATUS_CHANGED, STRATEGY_OVERWRITE) => {
+            let request = UpdateDirectoryRequest {
+                name: Some(incoming.name.clone()),
+                ..Default::default()
+            };
+            db.update_directory(item.existing_id.unwrap_or_default(), request)
+                .await?;
+            Ok((ACTION_OVERWRITTEN, None))
+        }
+        (STATUS_IDENTICAL, _) | (STATUS_CHANGED, STRATEGY_MERGE) => Ok((ACTION_UNCHANGED, None)),
+        _ => Ok((ACTION_SKIPPED, None)),
+    }
+}
+
+/// 账号使用的 API 地址尚未登记时自动创建，返回是否新建
+async fn ensure_base_url(db: &Database, url: &str) -> Result<bool, SqlxError> {
+    let base_urls = db.get_base_urls().await?;
+    if base_urls.iter().any(|bu| bu.url == url) {
+        return Ok(false);
+    }
+    let taken: HashSet<String> = base_urls.into_iter().map(|bu| bu.name).collect();
+    let name = base_url_name(url);
+    let request = CreateBaseUrlRequest {
+        name: if taken.contains(&name) {
+            unique_name(&name, &taken)
+        } else {
+            name
+        },
+        url: url.to_string(),
+        is_default: Some(false),
+        ..Default::default()
+    };
+    db.create_base_url(request).await?;
+    Ok(true)
+}
+
+/// 创建/覆盖请求里没有的字段：请求头和默认环境变量，导入数据为空时保留原值
+async fn set_base_url_extras(
+    db: &Database,
+    id: i64,
+    incoming: &ImportedBaseUrl,
+) -> Result<(), SqlxError> {
+    if !incoming.custom_headers.is_empty() {
+        db.set_base_url_headers(id, &incoming.custom_headers)
+            .await?;
+    }
+    if !incoming.default_env_vars.is_empty() {
+        db.set_base_url_default_env_vars(id, &incoming.default_env_vars)
+            .await?;
+    }
+    Ok(())
+}
+
+/// 创建/覆盖请求里没有的字段：凭据模式和请求头，导入数据为空时保留原值
+async fn set_account_extras(
+    db: &Database,
+    id: i64,
+    incoming: &ImportedAccount,
+) -> Result<(), SqlxError> {
+    if let Some(mode) = &incoming.credential_mode {
+        db.set_account_credential(
+            id,
+            mode,
+            incoming.api_key_helper.as_deref(),
+            incoming.api_key_helper_ttl_ms,
+        )
+        .await?;
+    }
+    if !incoming.custom_headers.is_empty() {
+        db.set_account_headers(id, &incoming.custom_headers).await?;
+    }
+    Ok(())
+}
+
+fn env_value(env: &BTreeMap<String, String>) -> Option<serde_json::Value> {
+    (!env.is_empty())
+        .then(|| serde_json::to_value(env).ok())
+        .flatten()
+}
+
+async fn apply_account(
+    db: &Database,
+    item: &ImportItem,
+    incoming: &ImportedAccount,
+    strategy: &str,
+    renamed: &mut HashMap<String, String>,
+) -> Outcome {
+    let incoming_env = sorted_env(&incoming.custom_env_vars);
+    let notes = Some(incoming.notes.clone()).filter(|n| !n.is_empty());
+    let tags = Some(incoming.tags.clone()).filter(|t| !t.is_empty());
+    let create = |name: String| CreateAccountRequest {
+        name,
+        token: incoming.token.clone(),
+        base_url: incoming.base_url.clone(),
+        model: incoming.model.clone(),
+        custom_env_vars: env_value(&incoming_env),
+        enabled: incoming.enabled,
+        expires_at: incoming.expires_at.clone(),
+        notes: notes.clone(),
+        tags: tags.clone(),
+    };
+    let id = item.existing_id.unwrap_or_default();
+    match (item.status.as_str(), strategy) {
+        (STATUS_NEW, _) => {
+            let created = db.create_account(create(incoming.name.clone())).await?;
+            set_account_extras(db, created.id, incoming).await?;
+            Ok((ACTION_CREATED, None))
+        }
+        (STATUS_CHANGED, STRATEGY_OVERWRITE) => {
+            let request = UpdateAccountRequest {
+                name: Some(incoming.name.clone()),
+                token: Some(incoming.token.clone()),
+                base_url: Some(incoming.base_url.clone()),
+                model: Some(incoming.model.clone()).filter(|m| !m.is_empty()),
+                custom_env_vars: env_value(&incoming_env),
+                enabled: incoming.enabled,
+                expires_at: incoming.expires_at.clone(),
+                notes,
+                tags,
+            };
+            db.update_account(id, request).await?;
+            set_account_extras(db, id, incoming).await?;
+            Ok((ACTION_OVERWRITTEN, None))
+        }
+        (STATUS_CHANGED, STRATEGY_MERGE) => {
+            // 只补全已有账号缺少的字段，自定义环境变量和标签取并集，已有的值优先
+            let current = db.get_account(id).await?;
+            let mut env = incoming_env.clone();
+            env.extend(parse_env(&current.custom_env_vars));
+            let mut merged_tags = vec![current.tags.clone()];
+            merged_tags.extend(incoming.tags.iter().cloned());
+            let merged_tags = normalize_tags(&merged_tags);
+            let request = UpdateAccountRequest {
+                model: (current.model.is_empty() && !incoming.model.is_empty())
+                    .then(|| incoming.model.clone()),
+                custom_env_vars: (env != parse_env(&current.custom_env_vars))
+                    .then(|| env_value(&env))
+                    .flatten(),
+                expires_at: incoming
+                    .expires_at
+                    .clone()
+                    .filter(|_| current.expires_at.is_none()),
+                notes: notes.filter(|_| current.notes.is_empty()),
+                tags: (merged_tags != current.tags)
+                    .then(|| merged_tags.split(',').map(str::to_string).collect()),
+                ..Default::default()
+            };
+            let fill_headers =
+                current.header_list().is_empty() && !incoming.custom_headers.is_empty();
+            if request.model.is_none()
+                && request.custom_env_vars.is_none()
+                && request.expires_at.is_none()
+                && request.notes.is_none()
+                && request.tags.is_none()
+                && !fill_headers
+            {
+                return Ok((ACTION_UNCHANGED, None));
+            }
+            db.update_account(id, request).await?;
+            if fill_headers {
+                db.set_account_headers(id, &incoming.custom_headers).await?;
+            }
+            Ok((ACTION_MERGED, None))
+        }
+        (STATUS_CHANGED, STRATEGY_RENAME) if can_rename(item) => {
+            let taken = db
+                .get_all_accounts()
+                .await?
+                .into_iter()
+                .map(|a| a.name)
+                .collect();
+            let name = unique_name(&incoming.name, &taken);
+            let created = db.create_account(create(name.clone())).await?;
+            set_account_extras(db, created.id, incoming).await?;
+            renamed.insert(incoming.name.clone(), name.clone());
+            Ok((ACTION_RENAMED, Some(name)))
+        }
+        (STATUS_IDENTICAL, _) => Ok((ACTION_UNCHANGED, None)),
+        _ => Ok((ACTION_SKIPPED, None)),
+    }
+}
+
+/// 按比对结果写入数据库：新项直接创建，相同的项保持不变，有差异的项按单独指定或全局策略处理
+pub async fn apply(
+    db: &Database,
+    bundle: &ImportBundle,
+    items: &[ImportItem],
+    default_strategy: &str,
+) -> Result<ImportReport, SqlxError> {
+    let mut report = ImportReport::default();
+    let mut renamed = HashMap::new();
+
+    // API 地址先于账号处理，账号引用的地址才能匹配到导入的名称和令牌变量名
+    for kind in [KIND_BASE_URL, KIND_DIRECTORY, KIND_ACCOUNT] {
+        for item in items.iter().filter(|i| i.kind == kind) {
+            let strategy = item.strategy.as_deref().unwrap_or(default_strategy);
+            if item.status == STATUS_DUPLICATE {
+                report.record(
+                    kind,
+                    &item.name,
+                    ACTION_SKIPPED,
+                    Some("文件中已有相同的项".to_string()),
+                );
+                continue;
+            }
+            let outcome = match kind {
+                KIND_BASE_URL => match bundle.base_urls.get(item.index) {
+                    Some(incoming) => apply_base_url(db, item, incoming, strategy).await,
+                    None => continue,
+                },
+                KIND_DIRECTORY => match bundle.directories.get(item.index) {
+                    Some(incoming) => apply_directory(db, item, incoming, strategy).await,
+                    None => continue,
+                },
+                _ => match bundle.accounts.get(item.index) {
+                    Some(incoming) => {
+                        let writes = item.status == STATUS_NEW
+                            || (item.status == STATUS_CHANGED
+                                && (strategy == STRATEGY_OVERWRITE
+                                    || (strategy == STRATEGY_RENAME && can_rename(item))));
+                        if writes {
+                            match ensure_base_url(db, &incoming.base_url).await {
+                                Ok(true) => report.record(
+                                    KIND_BASE_URL,
+                                    &base_url_name(&incoming.base_url),
+                                    ACTION_CREATED,
+                                    Some(incoming.base_url.clone()),
+                                ),
+                                Ok(false) => {}
+                                Err(e) => report.record(
+                                    KIND_BASE_URL,
+                                    &incoming.base_url,
+                                    ACTION_FAILED,
+                                    Some(e.to_string()),
+                                ),
+                            }
+                        }
+                        apply_account(db, item, incoming, strategy, &mut renamed).await
+                    }
+                    None => continue,
+                },
+            };
+            match outcome {
+                Ok((action, detail)) => report.record(kind, &item.name, action, detail),
+                Err(e) => report.record(kind, &item.name, ACTION_FAILED, Some(e.to_string())),
+            }
+        }
+    }
+
+    if !bundle.bindings.is_empty() {
+        let existing = ExistingRecords::load(db).await?;
+        for binding in &bundle.bindings {
+            let account_name = renamed
+                .get(&binding.account_name)
+                .unwrap_or(&binding.account_name);
+            let label = format!("{} → {}", account_name, binding.directory_path);
+            let account = existing.accounts.iter().find(|a| &a.name == account_name);
+            let directory = existing
+                .directories
+                .iter()
+                .find(|d| d.path == binding.directory_path);
+            match (account, directory) {
+                (Some(account), Some(directory)) => {
+                    match db.add_account_binding(account.id, directory.id).await {
+                        Ok(true) => report.record(KIND_BINDING, &label, ACTION_CREATED, None),
+                        Ok(false) => report.record(KIND_BINDING, &label, ACTION_UNCHANGED, None),
+                        Err(e) => {
+                            report.record(KIND_BINDING, &label, ACTION_FAILED, Some(e.to_string()))
+                        }
+                    }
+                }
+                _ => report.record(
+                    KIND_BINDING,
+                    &label,
+                    ACTION_SKIPPED,
+                    Some("账号或目录不存在".to_string()),
+                ),
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account(id: i64, name: &str, token: &str, model: &str) -> Account {
+        Account {
+            id,
+            name: name.to_string(),
+            token: token.to_string(),
+            model: model.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn incoming(name: &str, token: &str, model: &str) -> ImportedAccount {
+        ImportedAccount {
+            name: name.to_string(),
+            token: token.to_string(),
+            base_url: "https://api.example.com".to_string(),
+            model: model.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_plan_classifies_accounts() {
+        let existing = ExistingRecords {
+            accounts: vec![
+                account(1, "same", "sk-same-000000", "opus"),
+                account(2, "work", "sk-work-000000", "opus"),
+                account(3, "old-name", "sk-moved-00000", ""),
+            ],
+            ..Default::default()
+        };
+        let bundle = ImportBundle {
+            accounts: vec![
+                incoming("same", "sk-same-000000", ""),
+                incoming("work", "sk-work-111111", "sonnet"),
+                incoming("new-name", "sk-moved-00000", ""),
+                incoming("fresh", "sk-fresh-00000", ""),
+                incoming("fresh", "sk-other-00000", ""),
+            ],
+            ..Default::default()
+        };
+
+        let items = plan(&bundle, &existing);
+        let statuses: Vec<&str> = items.iter().map(|i| i.status.as_str()).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                STATUS_IDENTICAL,
+                STATUS_CHANGED,
+                STATUS_CHANGED,
+                STATUS_NEW,
+                STATUS_DUPLICATE
+            ]
+        );
+
+        let fields: Vec<&str> = items[1].diffs.iter().map(|d| d.field.as_str()).collect();
+        assert_eq!(fields, vec!["token", "model"]);
+        assert!(!items[1].diffs[0].incoming.contains("111111"));
+        assert_eq!(items[2].matched_by.as_deref(), Some("token"));
+        assert!(can_rename(&items[1]));
+        assert!(!can_rename(&items[2]));
+    }
+
+    async fn memory_db() -> Database {
+        let pool = crate::db_backend::DbPool::Sqlite(
+            sqlx::sqlite::SqlitePoolOptions::new()
+                .max_connections(1)
+                .connect("sqlite::memory:")
+                .await
+                .unwrap(),
+        );
+        let db = Database { pool };
+        db.migrate().await.unwrap();
+        db
+    }
+
+    async fn import_into(db: &Database, file_name: &str, content: &str) -> Vec<ImportItem> {
+        let (_, bundle) = crate::importers::parse(file_name, content).unwrap();
+        let items = plan(&bundle, &ExistingRecords::load(db).await.unwrap());
+        apply(db, &bundle, &items, STRATEGY_SKIP).await.unwrap();
+        plan(&bundle, &ExistingRecords::load(db).await.unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_export_import_round_trip() {
+        let source = memory_db().await;
+        let base_url = source
+            .create_base_url(CreateBaseUrlRequest {
+                name: "relay".to_string(),
+                url: "https://relay.example.com".to_string(),
+                description: Some("中转".to_string()),
+                provider_type: Some("bedrock".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let env: HashMap<String, String> =
+            [("AWS_REGION".to_string(), "us-east-1".to_string())].into();
+        source
+            .set_base_url_default_env_vars(base_url.id, &env)
+            .await
+            .unwrap();
+        let headers = vec![CustomHeader::new("X-Team", "infra")];
+        source
+            .set_base_url_headers(base_url.id, &headers)
+            .await
+            .unwrap();
+        let account = source
+            .create_account(CreateAccountRequest {
+                name: "work".to_string(),
+                token: "sk-work-000000".to_string(),
+                base_url: "https://relay.example.com".to_string(),
+                model: "opus".to_string(),
+                custom_env_vars: Some(serde_json::json!({"DISABLE_TELEMETRY": "1"})),
+                enabled: Some(false),
+                expires_at: Some("2030-01-02".to_string()),
+                notes: Some("第一行\n第二行, \"引号\"".to_string()),
+                tags: Some(vec!["team".to_string(), "prod".to_string()]),
+            })
+            .await
+            .unwrap();
+        source
+            .set_account_credential(account.id, "helper", Some("vault read key"), Some(60000))
+            .await
+            .unwrap();
+        source
+            .set_account_headers(account.id, &[CustomHeader::new("X-User", "me")])
+            .await
+            .unwrap();
+        let data = crate::exporters::collect(&source, &ExportFilter::default())
+            .await
+            .unwrap();
+        let expected = source.get_account(account.id).await.unwrap();
+
+        // 完整备份：所有字段原样恢复，再次导入时全部相同
+        let json = crate::exporters::find_exporter("json")
+            .unwrap()
+            .render(&data)
+            .unwrap()
+            .remove(0)
+            .content;
+        let target = memory_db().await;
+        let items = import_into(&target, "backup.json", &json).await;
+        assert!(
+            items.iter().all(|i| i.status == STATUS_IDENTICAL),
+            "{items:?}"
+        );
+        let restored = target
+            .get_base_urls()
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|bu| bu.name == "relay")
+            .unwrap();
+        assert_eq!(restored.provider_type, "bedrock");
+        assert_eq!(parse_env(&restored.default_env_vars), sorted_env(&env));
+        assert_eq!(restored.header_list(), headers);
+        let restored = target.get_all_accounts().await.unwrap().remove(0);
+        assert_eq!(restored.enabled, expected.enabled);
+        assert_eq!(restored.expires_at, expected.expires_at);
+        assert_eq!(restored.notes, expected.notes);
+        assert_eq!(restored.tags, expected.tags);
+        assert_eq!(restored.credential_mode, "helper");
+        assert_eq!(restored.api_key_helper.as_deref(), Some("vault read key"));
+        assert_eq!(restored.api_key_helper_ttl_ms, Some(60000));
+        assert_eq!(restored.header_list(), expected.header_list());
+        assert_eq!(
+            parse_env(&restored.custom_env_vars),
+            parse_env(&expected.custom_env_vars)
+        );
+
+        // 表格：多行备注和带逗号的标签不会拆散记录
+        let csv = crate::exporters::find_exporter("csv")
+            .unwrap()
+            .render(&data)
+            .unwrap()
+            .remove(0)
+            .content;
+        let target = memory_db().await;
+        let items = import_into(&target, "accounts.csv", &csv).await;
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].status, STATUS_IDENTICAL);
+        let restored = target.get_all_accounts().await.unwrap().remove(0);
+        assert_eq!(restored.notes, expected.notes);
+        assert_eq!(restored.tags, expected.tags);
+        assert!(!restored.enabled);
+        assert_eq!(restored.expires_at, expected.expires_at);
+    }
+
+    #[test]
+    fn test_unique_name_and_fingerprint() {
+        let taken: HashSet<String> = ["a-2".to_string()].into_iter().collect();
+        assert_eq!(unique_name("a", &taken), "a-3");
+        assert_eq!(fingerprint("sk-ant-1234567890"), "sk-ant…7890");
+        assert_eq!(fingerprint("short"), "*****");
+    }
+}
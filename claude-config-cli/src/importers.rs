@@ -0,0 +1,747 @@
+use crate::custom_headers::{self, CustomHeader};
+use crate::discovery::{base_url_name, TOKEN_KEYS};
+use crate::import_engine::{self, ExistingRecords};
+use crate::key_helper;
+use crate::models::*;
+use crate::providers;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// 未配置 ANTHROPIC_BASE_URL 时使用的官方地址
+const DEFAULT_BASE_URL: &str = "https://api.anthropic.com";
+
+/// 待导入的文件，JSON 内容预先解析一次供各适配器共用
+pub struct ImportSource<'a> {
+    pub file_name: &'a str,
+    pub content: &'a str,
+    pub json: Option<Value>,
+}
+
+impl<'a> ImportSource<'a> {
+    pub fn new(file_name: &'a str, content: &'a str) -> Self {
+        // 去掉 UTF-8 BOM，Windows 下导出的文件常带有
+        let content = content.trim_start_matches('\u{feff}');
+        Self {
+            file_name,
+            content,
+            json: serde_json::from_str(content).ok(),
+        }
+    }
+
+    fn has_extension(&self, ext: &str) -> bool {
+        self.file_name.to_lowercase().ends_with(ext)
+    }
+}
+
+/// 账号导入适配器：识别一种配置文件格式并解析出待创建的账号
+pub trait AccountImporter: Send + Sync {
+    /// 格式标识，前端据此显示格式名称
+    fn id(&self) -> &'static str;
+
+    /// 是否能识别该文件
+    fn detect(&self, source: &ImportSource) -> bool;
+
+    /// 解析出待创建的账号
+    fn parse(&self, source: &ImportSource) -> Result<Vec<ImportedAccount>, String>;
+
+    /// 解析出完整的导入数据，只含账号的格式使用默认实现
+    fn parse_bundle(&self, source: &ImportSource) -> Result<ImportBundle, String> {
+        Ok(ImportBundle {
+            accounts: self.parse(source)?,
+            ..Default::default()
+        })
+    }
+}
+
+/// 按顺序尝试的适配器，JSON 格式在前，文本格式在后
+const IMPORTERS: &[&dyn AccountImporter] = &[
+    &ProvidersImporter,
+    &BackupImporter,
+    &CcSwitchImporter,
+    &RouterImporter,
+    &SettingsImporter,
+    &CsvImporter,
+    &DotEnvImporter,
+];
+
+fn str_field<'a>(value: &'a Value, keys: &[&str]) -> &'a str {
+    keys.iter()
+        .find_map(|key| value.get(*key).and_then(|v| v.as_str()))
+        .unwrap_or("")
+        .trim()
+}
+
+fn optional_field(value: &Value, keys: &[&str]) -> Option<String> {
+    Some(str_field(value, keys).to_string()).filter(|v| !v.is_empty())
+}
+
+/// 完整导出中以 JSON 字符串保存的字段，也接受直接写成对象或数组的值
+fn embedded_json(value: &Value, key: &str) -> Option<Value> {
+    match value.get(key)? {
+        Value::String(raw) => serde_json::from_str(raw).ok(),
+        other => Some(other.clone()),
+    }
+}
+
+/// 自定义请求头，不合法时整体忽略
+fn headers_field(value: &Value) -> Vec<CustomHeader> {
+    embedded_json(value, "custom_headers")
+        .and_then(|v| serde_json::from_value::<Vec<CustomHeader>>(v).ok())
+        .filter(|headers| custom_headers::validate_headers(headers).is_ok())
+        .unwrap_or_default()
+}
+
+/// 标签：逗号分隔的字符串或字符串数组
+fn tags_field(value: &Value) -> Vec<String> {
+    let tags: Vec<String> = match value.get("tags") {
+        Some(Value::String(raw)) => raw.split(',').map(String::from).collect(),
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(|t| t.as_str())
+            .map(String::from)
+            .collect(),
+        _ => Vec::new(),
+    };
+    tags.into_iter()
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+fn bool_field(value: &Value, key: &str) -> Option<bool> {
+    match value.get(key)? {
+        Value::Bool(b) => Some(*b),
+        Value::String(s) => s.trim().to_lowercase().parse().ok(),
+        _ => None,
+    }
+}
+
+fn env_value(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(_) | Value::Bool(_) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+/// 从环境变量表构造账号：令牌按 TOKEN_KEYS 顺序查找，其余变量作为自定义环境变量
+fn account_from_env(name: Option<&str>, env: &HashMap<String, String>) -> Option<ImportedAccount> {
+    let token = TOKEN_KEYS
+        .iter()
+        .find_map(|key| env.get(*key).filter(|v| !v.trim().is_empty()))?;
+    let base_url = env
+        .get("ANTHROPIC_BASE_URL")
+        .filter(|v| !v.trim().is_empty())
+        .map(|v| v.trim().to_string())
+        .unwrap_or_else(|| DEFAULT_BASE_URL.to_string());
+    let model = env.get("ANTHROPIC_MODEL").cloned().unwrap_or_default();
+    let custom_env_vars = env
+        .iter()
+        .filter(|(key, _)| {
+            !TOKEN_KEYS.contains(&key.as_str())
+                && key.as_str() != "ANTHROPIC_BASE_URL"
+                && key.as_str() != "ANTHROPIC_MODEL"
+        })
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect();
+
+    Some(ImportedAccount {
+        name: name
+            .map(str::trim)
+            .filter(|n| !n.is_empty())
+            .map(String::from)
+            .unwrap_or_else(|| base_url_name(&base_url)),
+        token: token.trim().to_string(),
+        base_url,
+        model,
+        custom_env_vars,
+        ..Default::default()
+    })
+}
+
+fn env_map(value: &Value) -> HashMap<String, String> {
+    value
+        .as_object()
+        .map(|obj| {
+            obj.iter()
+                .filter_map(|(k, v)| env_value(v).map(|v| (k.clone(), v)))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 本工具导出的 `{"providers": [{"name", "url", "key"}]}`
+struct ProvidersImporter;
+
+impl AccountImporter for ProvidersImporter {
+    fn id(&self) -> &'static str {
+        "providers"
+    }
+
+    fn detect(&self, source: &ImportSource) -> bool {
+        source
+            .json
+            .as_ref()
+            .and_then(|j| j.get("providers"))
+            .map(|p| p.is_array())
+            .unwrap_or(false)
+    }
+
+    fn parse(&self, source: &ImportSource) -> Result<Vec<ImportedAccount>, String> {
+        let providers = source
+            .json
+            .as_ref()
+            .and_then(|j| j.get("providers"))
+            .and_then(|p| p.as_array())
+            .ok_or("缺少 providers 数组")?;
+        Ok(providers
+            .iter()
+            .filter_map(|p| {
+                let (name, url, key) = (
+                    str_field(p, &["name"]),
+                    str_field(p, &["url"]),
+                    str_field(p, &["key"]),
+                );
+                if name.is_empty() || url.is_empty() || key.is_empty() {
+                    return None;
+                }
+                Some(ImportedAccount {
+                    name: name.to_string(),
+                    token: key.to_string(),
+                    base_url: url.to_string(),
+                    model: str_field(p, &["model"]).to_string(),
+                    ..Default::default()
+                })
+            })
+            .collect())
+    }
+}
+
+/// WebDAV 上传的配置备份和完整导出文件 `{"accounts": [...], "base_urls": [...], "directories": [...], "bindings": [...]}`
+struct BackupImporter;
+
+impl AccountImporter for BackupImporter {
+    fn id(&self) -> &'static str {
+        "backup"
+    }
+
+    fn detect(&self, source: &ImportSource) -> bool {
+        source
+            .json
+            .as_ref()
+            .and_then(|j| j.get("accounts"))
+            .and_then(|a| a.as_array())
+            .map(|a| a.iter().any(|item| item.get("token").is_some()))
+            .unwrap_or(false)
+    }
+
+    fn parse(&self, source: &ImportSource) -> Result<Vec<ImportedAccount>, String> {
+        let accounts = source
+            .json
+            .as_ref()
+            .and_then(|j| j.get("accounts"))
+            .and_then(|a| a.as_array())
+            .ok_or("缺少 accounts 数组")?;
+        Ok(accounts
+            .iter()
+            .filter_map(|a| {
+                let (name, token, base_url) = (
+                    str_field(a, &["name"]),
+                    str_field(a, &["token"]),
+                    str_field(a, &["base_url"]),
+                );
+                if name.is_empty() || token.is_empty() || base_url.is_empty() {
+                    return None;
+                }
+                let custom_env_vars = embedded_json(a, "custom_env_vars")
+                    .map(|v| env_map(&v))
+                    .unwrap_or_default();
+                let (mode, helper, ttl_ms) = (
+                    optional_field(a, &["credential_mode"]),
+                    optional_field(a, &["api_key_helper"]),
+                    a.get("api_key_helper_ttl_ms").and_then(|v| v.as_i64()),
+                );
+                // 凭据配置不合法时按令牌模式导入
+                let valid = mode.as_deref().is_some_and(|mode| {
+                    key_helper::validate_credential(mode, helper.as_deref(), ttl_ms).is_ok()
+                });
+                Some(ImportedAccount {
+                    name: name.to_string(),
+                    token: token.to_string(),
+                    base_url: base_url.to_string(),
+                    model: str_field(a, &["model"]).to_string(),
+                    custom_env_vars,
+                    enabled: bool_field(a, "enabled"),
+                    expires_at: optional_field(a, &["expires_at"]),
+                    notes: str_field(a, &["notes"]).to_string(),
+                    tags: tags_field(a),
+                    credential_mode: mode.filter(|_| valid),
+                    api_key_helper: helper.filter(|_| valid),
+                    api_key_helper_ttl_ms: ttl_ms.filter(|_| valid),
+                    custom_headers: headers_field(a),
+                })
+            })
+            .collect())
+    }
+
+    fn parse_bundle(&self, source: &ImportSource) -> Result<ImportBundle, String> {
+        let json = source.json.as_ref().ok_or("不是 JSON 文件")?;
+        let list = |key: &str| {
+            json.get(key)
+                .and_then(|v| v.as_array())
+                .cloned()
+                .unwrap_or_default()
+        };
+        Ok(ImportBundle {
+            accounts: self.parse(source)?,
+            base_urls: list("base_urls")
+                .iter()
+                .filter_map(|b| {
+                    let (name, url) = (str_field(b, &["name"]), str_field(b, &["url"]));
+                    if name.is_empty() || url.is_empty() {
+                        return None;
+                    }
+                    Some(ImportedBaseUrl {
+                        name: name.to_string(),
+                        url: url.to_string(),
+                        description: optional_field(b, &["description"]),
+                        api_key: optional_field(b, &["api_key"]),
+                        is_default: bool_field(b, "is_default").unwrap_or(false),
+                        provider_type: optional_field(b, &["provider_type"])
+                            .filter(|p| providers::validate_provider_type(p).is_ok()),
+                        default_env_vars: embedded_json(b, "default_env_vars")
+                            .map(|v| env_map(&v))
+                            .unwrap_or_default(),
+                        custom_headers: headers_field(b),
+                    })
+                })
+                .collect(),
+            directories: list("directories")
+                .iter()
+                .filter_map(|d| {
+                    let (name, path) = (str_field(d, &["name"]), str_field(d, &["path"]));
+                    (!path.is_empty()).then(|| ImportedDirectory {
+                        name: name.to_string(),
+                        path: path.to_string(),
+                    })
+                })
+                .collect(),
+            bindings: list("bindings")
+                .iter()
+                .filter_map(|b| {
+                    let (account, path) = (
+                        str_field(b, &["account_name"]),
+                        str_field(b, &["directory_path"]),
+                    );
+                    (!account.is_empty() && !path.is_empty()).then(|| ImportedBinding {
+                        account_name: account.to_string(),
+                        directory_path: path.to_string(),
+                    })
+                })
+                .collect(),
+        })
+    }
+}
+
+/// cc-switch 的 `~/.cc-switch/config.json`，兼容 v1 的顶层 providers 和 v2 的 claude.providers
+struct CcSwitchImporter;
+
+impl CcSwitchImporter {
+    fn providers(json: &Value) -> Option<&serde_json::Map<String, Value>> {
+        json.get("claude")
+            .and_then(|c| c.get("providers"))
+            .or_else(|| json.get("providers"))
+            .and_then(|p| p.as_object())
+    }
+}
+
+impl AccountImporter for CcSwitchImporter {
+    fn id(&self) -> &'static str {
+        "cc_switch"
+    }
+
+    fn detect(&self, source: &ImportSource) -> bool {
+        source
+            .json
+            .as_ref()
+            .and_then(Self::providers)
+            .map(|p| p.values().any(|v| v.get("settingsConfig").is_some()))
+            .unwrap_or(false)
+    }
+
+    fn parse(&self, source: &ImportSource) -> Result<Vec<ImportedAccount>, String> {
+        let providers = source
+            .json
+            .as_ref()
+            .and_then(Self::providers)
+            .ok_or("缺少 providers 对象")?;
+        Ok(providers
+            .iter()
+            .filter_map(|(id, provider)| {
+                let env = provider
+                    .get("settingsConfig")
+                    .and_then(|s| s.get("env"))
+                    .map(env_map)
+                    .unwrap_or_default();
+                let name = Some(str_field(provider, &["name"]))
+                    .filter(|n| !n.is_empty())
+                    .unwrap_or(id);
+                account_from_env(Some(name), &env)
+            })
+            .collect())
+    }
+}
+
+/// claude-code-router 的 `~/.claude-code-router/config.json`
+struct RouterImporter;
+
+impl AccountImporter for RouterImporter {
+    fn id(&self) -> &'static str {
+        "claude_code_router"
+    }
+
+    fn detect(&self, source: &ImportSource) -> bool {
+        source
+            .json
+            .as_ref()
+            .and_then(|j| j.get("Providers"))
+            .map(|p| p.is_array())
+            .unwrap_or(false)
+    }
+
+    fn parse(&self, source: &ImportSource) -> Result<Vec<ImportedAccount>, String> {
+        let providers = source
+            .json
+            .as_ref()
+            .and_then(|j| j.get("Providers"))
+            .and_then(|p| p.as_array())
+            .ok_or("缺少 Providers 数组")?;
+        Ok(providers
+            .iter()
+            .filter_map(|p| {
+                let (name, url, key) = (
+                    str_field(p, &["name"]),
+                    str_field(p, &["api_base_url"]),
+                    str_field(p, &["api_key"]),
+                );
+                if name.is_empty() || url.is_empty() || key.is_empty() {
+                    return None;
+                }
+                let model = p
+                    .get("models")
+                    .and_then(|m| m.as_array())
+                    .and_then(|m| m.first())
+                    .and_then(|m| m.as_str())
+                    .unwrap_or("");
+                Some(ImportedAccount {
+                    name: name.to_string(),
+                    token: key.to_string(),
+                    base_url: url.to_string(),
+                    model: model.to_string(),
+                    ..Default::default()
+                })
+            })
+            .collect())
+    }
+}
+
+/// Claude Code 的 `settings.json`，从 env 块中读取一个账号
+struct SettingsImporter;
+
+impl AccountImporter for SettingsImporter {
+    fn id(&self) -> &'static str {
+        "settings_json"
+    }
+
+    fn detect(&self, source: &ImportSource) -> bool {
+        source
+            .json
+            .as_ref()
+            .and_then(|j| j.get("env"))
+            .map(|e| e.is_object())
+            .unwrap_or(false)
+    }
+
+    fn parse(&self, source: &ImportSource) -> Result<Vec<ImportedAccount>, String> {
+        let env = source
+            .json
+            .as_ref()
+            .and_then(|j| j.get("env"))
+            .map(env_map)
+            .unwrap_or_default();
+        let account = account_from_env(None, &env).ok_or("env 中没有找到 API 令牌")?;
+        Ok(vec![account])
+    }
+}
+
+/// 按记录读取 CSV：双引号包裹的字段可以包含逗号和换行，`""` 表示引号，空行忽略
+fn read_csv(content: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = content.chars().peekable();
+    let mut end_row = |row: &mut Vec<String>, field: &mut String| {
+        row.push(std::mem::take(field).trim().to_string());
+        let row = std::mem::take(row);
+        if row.iter().any(|f| !f.is_empty()) {
+            rows.push(row);
+        }
+    };
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => row.push(std::mem::take(&mut field).trim().to_string()),
+            '\r' if !in_quotes && chars.peek() == Some(&'\n') => {}
+            '\n' if !in_quotes => end_row(&mut row, &mut field),
+            _ => field.push(c),
+        }
+    }
+    end_row(&mut row, &mut field);
+    rows
+}
+
+/// CSV：列依次为 name, url, key, model, tags, notes, enabled, expires_at；首行为表头时按列名匹配
+struct CsvImporter;
+
+impl CsvImporter {
+    fn header_index(header: &[String], names: &[&str]) -> Option<usize> {
+        header
+            .iter()
+            .position(|h| names.contains(&h.to_lowercase().as_str()))
+    }
+}
+
+impl AccountImporter for CsvImporter {
+    fn id(&self) -> &'static str {
+        "csv"
+    }
+
+    fn detect(&self, source: &ImportSource) -> bool {
+        if source.json.is_some() {
+            return false;
+        }
+        if source.has_extension(".csv") {
+            return true;
+        }
+        read_csv(source.content)
+            .first()
+            .map(|row| row.len() >= 3 && !row.iter().any(|f| f.contains('=')))
+            .unwrap_or(false)
+    }
+
+    fn parse(&self, source: &ImportSource) -> Result<Vec<ImportedAccount>, String> {
+        let mut rows = read_csv(source.content).into_iter().peekable();
+
+        // 默认按位置读取，首行含 name 列时视为表头
+        let mut columns = [0, 1, 2, 3, 4, 5, 6, 7].map(Some);
+        if let Some(header) = rows.peek() {
+            if Self::header_index(header, &["name"]).is_some() {
+                columns = [
+                    Self::header_index(header, &["name"]),
+                    Self::header_index(header, &["url", "base_url"]),
+                    Self::header_index(header, &["key", "token", "api_key"]),
+                    Self::header_index(header, &["model"]),
+                    Self::header_index(header, &["tags"]),
+                    Self::header_index(header, &["notes"]),
+                    Self::header_index(header, &["enabled"]),
+                    Self::header_index(header, &["expires_at"]),
+                ];
+                rows.next();
+            }
+        }
+
+        let field = |row: &[String], column: Option<usize>| -> String {
+            column.and_then(|i| row.get(i)).cloned().unwrap_or_default()
+        };
+        Ok(rows
+            .filter_map(|row| {
+                let (name, url, key) = (
+                    field(&row, columns[0]),
+                    field(&row, columns[1]),
+                    field(&row, columns[2]),
+                );
+                if name.is_empty() || url.is_empty() || key.is_empty() {
+                    return None;
+                }
+                Some(ImportedAccount {
+                    name,
+                    token: key,
+                    base_url: url,
+                    model: field(&row, columns[3]),
+                    tags: field(&row, columns[4])
+                        .split(',')
+                        .map(|t| t.trim().to_string())
+                        .filter(|t| !t.is_empty())
+                        .collect(),
+                    notes: field(&row, columns[5]),
+                    enabled: field(&row, columns[6]).to_lowercase().parse().ok(),
+                    expires_at: Some(field(&row, columns[7])).filter(|e| !e.is_empty()),
+                    ..Default::default()
+                })
+            })
+            .collect())
+    }
+}
+
+/// `.env` 文件，只读取 ANTHROPIC_ 和 CLAUDE_ 开头的变量
+struct DotEnvImporter;
+
+impl DotEnvImporter {
+    fn variables(content: &str) -> HashMap<String, String> {
+        content
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.starts_with('#'))
+            .filter_map(|l| l.strip_prefix("export ").unwrap_or(l).split_once('='))
+            .map(|(k, v)| {
+                let v = v.trim();
+                let v = v
+                    .strip_prefix('"')
+                    .and_then(|v| v.strip_suffix('"'))
+                    .or_else(|| v.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+                    .unwrap_or(v);
+                (k.trim().to_string(), v.to_string())
+            })
+            .filter(|(k, _)| k.starts_with("ANTHROPIC_") || k.starts_with("CLAUDE_"))
+            .collect()
+    }
+}
+
+impl AccountImporter for DotEnvImporter {
+    fn id(&self) -> &'static str {
+        "dotenv"
+    }
+
+    fn detect(&self, source: &ImportSource) -> bool {
+        source.json.is_none()
+            && TOKEN_KEYS
+                .iter()
+                .any(|key| Self::variables(source.content).contains_key(*key))
+    }
+
+    fn parse(&self, source: &ImportSource) -> Result<Vec<ImportedAccount>, String> {
+        // 账号名取自文件名，如 work.env、.env.work
+        let file_name = std::path::Path::new(source.file_name)
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("");
+        let name = file_name
+            .trim_start_matches(".env")
+            .trim_end_matches(".env")
+            .trim_matches('.');
+        let account = account_from_env(Some(name), &Self::variables(source.content))
+            .ok_or("没有找到 API 令牌")?;
+        Ok(vec![account])
+    }
+}
+
+/// 识别文件格式并解析
+pub fn parse(file_name: &str, content: &str) -> Result<(&'static str, ImportBundle), String> {
+    let source = ImportSource::new(file_name, content);
+    let importer = IMPORTERS
+        .iter()
+        .find(|importer| importer.detect(&source))
+        .ok_or("无法识别的文件格式")?;
+    Ok((importer.id(), importer.parse_bundle(&source)?))
+}
+
+/// 解析 WebDAV 下载的配置备份
+pub fn parse_backup(json: &Value) -> Result<ImportBundle, String> {
+    let content = json.to_string();
+    BackupImporter.parse_bundle(&ImportSource::new("backup.json", &content))
+}
+
+/// 识别文件格式并解析，逐项与已有记录比对
+pub fn preview(
+    file_name: &str,
+    content: &str,
+    existing: &ExistingRecords,
+) -> Result<ImportPreview, String> {
+    let (format, bundle) = parse(file_name, content)?;
+    let items = import_engine::plan(&bundle, existing);
+    Ok(ImportPreview {
+        format: format.to_string(),
+        bundle,
+        items,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(file_name: &str, content: &str) -> (&'static str, Vec<ImportedAccount>) {
+        let (format, bundle) = super::parse(file_name, content).unwrap();
+        (format, bundle.accounts)
+    }
+
+    #[test]
+    fn test_detect_json_formats() {
+        let providers =
+            r#"{"providers":[{"name":"a","url":"https://a.example.com","key":"sk-a"}]}"#;
+        assert_eq!(parse("x.json", providers).0, "providers");
+
+        let cc_switch = r#"{"version":2,"claude":{"providers":{"p1":{"name":"Relay",
+            "settingsConfig":{"env":{"ANTHROPIC_AUTH_TOKEN":"sk-r","ANTHROPIC_BASE_URL":"https://r.example.com"}}}}}}"#;
+        let (format, accounts) = parse("config.json", cc_switch);
+        assert_eq!(format, "cc_switch");
+        assert_eq!(accounts[0].name, "Relay");
+        assert_eq!(accounts[0].base_url, "https://r.example.com");
+
+        let router = r#"{"Providers":[{"name":"deepseek","api_base_url":"https://api.deepseek.com/chat/completions",
+            "api_key":"sk-d","models":["deepseek-chat"]}],"Router":{}}"#;
+        let (format, accounts) = parse("config.json", router);
+        assert_eq!(format, "claude_code_router");
+        assert_eq!(accounts[0].model, "deepseek-chat");
+
+        let settings = r#"{"env":{"ANTHROPIC_API_KEY":"sk-s","DISABLE_AUTOUPDATER":1}}"#;
+        let (format, accounts) = parse("settings.json", settings);
+        assert_eq!(format, "settings_json");
+        assert_eq!(accounts[0].name, "api.anthropic.com");
+        assert_eq!(
+            accounts[0].custom_env_vars.get("DISABLE_AUTOUPDATER"),
+            Some(&"1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_csv_and_dotenv() {
+        let csv = "Name,URL,Key,Model\n\"relay, main\",https://r.example.com,sk-1,\nb,https://b.example.com,sk-2,opus\n";
+        let (format, accounts) = parse("accounts.csv", csv);
+        assert_eq!(format, "csv");
+        assert_eq!(accounts.len(), 2);
+        assert_eq!(accounts[0].name, "relay, main");
+        assert_eq!(accounts[1].model, "opus");
+
+        let env = "# relay\nexport ANTHROPIC_BASE_URL=\"https://e.example.com\"\nANTHROPIC_AUTH_TOKEN=sk-e\nDATABASE_URL=x\n";
+        let (format, accounts) = parse("/tmp/.env.work", env);
+        assert_eq!(format, "dotenv");
+        assert_eq!(accounts[0].name, "work");
+        assert_eq!(accounts[0].token, "sk-e");
+        assert!(accounts[0].custom_env_vars.is_empty());
+    }
+
+    #[test]
+    fn test_backup_bundle_and_preview() {
+        let backup = r#"{"format":"claude-config","accounts":[{"name":"a","token":"sk-1","base_url":"https://a.example.com",
+            "custom_env_vars":"{\"API_TIMEOUT_MS\":\"1\"}"}],
+            "base_urls":[{"name":"A","url":"https://a.example.com","api_key":"ANTHROPIC_AUTH_TOKEN"}],
+            "directories":[{"name":"p","path":"/tmp/p"}],
+            "bindings":[{"account_name":"a","directory_path":"/tmp/p"}]}"#;
+        let preview = preview("export.json", backup, &ExistingRecords::default()).unwrap();
+        assert_eq!(preview.format, "backup");
+        assert_eq!(preview.bundle.accounts[0].custom_env_vars.len(), 1);
+        assert_eq!(
+            preview.bundle.base_urls[0].api_key.as_deref(),
+            Some("ANTHROPIC_AUTH_TOKEN")
+        );
+        assert_eq!(preview.bundle.bindings.len(), 1);
+        assert_eq!(preview.items.len(), 3);
+        assert!(super::parse("x.txt", "hello").is_err());
+    }
+}
@@ -1,16 +1,36 @@
+mod balance;
 mod claude_config;
 mod config_manager;
+mod custom_headers;
 mod database;
+mod db_backend;
+mod definitions;
+mod discovery;
+mod drift;
+mod exporters;
+mod health;
+mod hooks;
 mod i18n;
+mod import_engine;
+mod importers;
+mod key_helper;
 mod logger;
+mod mcp;
 mod menu;
+mod migrations;
 mod models;
+mod permissions;
+mod pools;
+mod providers;
+mod proxy;
+mod relay;
 mod webdav;
 
 use anyhow::Result;
 use colored::Colorize;
 use console::Term;
 use database::Database;
+use db_backend::Backend;
 use dialoguer::Select;
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -63,12 +83,46 @@ async fn main() -> Result<()> {
         eprintln!("警告: 无法更新 ~/.claude.json: {}", e);
     }
 
+    // 命令行子命令：claude-config doctor drift [--fix]、claude-config serve [--port 端口]、
+    // claude-config migrate status
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let run_doctor = args.first().map(|a| a == "doctor").unwrap_or(false);
+    if run_doctor && args.get(1).map(|a| a != "drift").unwrap_or(true) {
+        eprintln!("{}", i18n::translate("doctor.usage"));
+        std::process::exit(2);
+    }
+    let run_serve = args.first().map(|a| a == "serve").unwrap_or(false);
+    let run_migrate = args.first().map(|a| a == "migrate").unwrap_or(false);
+    if run_migrate && args.get(1).map(|a| a != "status").unwrap_or(true) {
+        eprintln!("{}", i18n::translate("migrate.usage"));
+        std::process::exit(2);
+    }
+    let serve_port = match args.iter().position(|a| a == "--port") {
+        Some(idx) => match args.get(idx + 1).and_then(|p| p.parse::<u16>().ok()) {
+            Some(port) => port,
+            None => {
+                eprintln!("{}", i18n::translate("serve.usage"));
+                std::process::exit(2);
+            }
+        },
+        None => relay::DEFAULT_PORT,
+    };
+
     // 清屏
     let term = Term::stdout();
-    let _ = term.clear_screen();
+    if !run_doctor && !run_serve && !run_migrate {
+        let _ = term.clear_screen();
 
-    // 显示欢迎信息
-    print_banner();
+        // 显示欢迎信息
+        print_banner();
+    }
+
+    // 查看迁移状态时不执行迁移，数据库版本高于程序版本时也能查看
+    if run_migrate {
+        let database = Database::connect().await?;
+        menu::doctor::migration_status_command(&database).await?;
+        return Ok(());
+    }
 
     // 初始化数据库
     println!("{}", i18n::translate("db.init").cyan());
@@ -82,6 +136,13 @@ async fn main() -> Result<()> {
                 "{}",
                 format!("{}: {}", i18n::translate("db.init_error"), e).red()
             );
+            // 数据库由更新版本的程序创建，回退到新数据库会让用户误以为数据丢失；
+            // 共享的远程数据库连不上时回退到本地数据库会让数据分散在两处
+            if migrations::is_schema_too_new(&e)
+                || Database::configured_backend() != Some(Backend::Sqlite)
+            {
+                return Err(e.into());
+            }
             println!("\n{}", i18n::translate("db.fallback"));
             match Database::create_with_fallback().await {
                 Ok(database) => {
@@ -101,6 +162,35 @@ async fn main() -> Result<()> {
 
     println!();
 
+    if run_doctor {
+        let fix = args.iter().any(|a| a == "--fix");
+        let drifted = menu::doctor::drift_command(&db, fix).await?;
+        if drifted {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    if run_serve {
+        let listener = relay::bind(serve_port)
+            .await
+            .map_err(|e| anyhow::anyhow!("{}: {}", i18n::translate("serve.bind_error"), e))?;
+        println!(
+            "{}",
+            i18n::translate("serve.listening")
+                .replace("{}", &format!("http://127.0.0.1:{}", serve_port))
+                .green()
+        );
+        println!("{}", i18n::translate("serve.hint"));
+        relay::serve(listener, db).await;
+        return Ok(());
+    }
+
+    menu::account::print_expiry_warnings(&db).await;
+
+    // 后台定时刷新配置了余额查询的账号余额
+    tokio::spawn(balance::run_refresh_loop(db.clone()));
+
     // 主菜单循环
     loop {
         let selection = match show_main_menu()? {
@@ -126,18 +216,36 @@ async fn main() -> Result<()> {
                 menu::switch::switch_menu(&db).await?;
             }
             4 => {
-                menu::webdav::webdav_menu(&db).await?;
+                menu::mcp::mcp_menu(&db).await?;
             }
             5 => {
-                menu::logs::logs_menu().await?;
+                menu::hooks::hooks_menu(&db).await?;
             }
             6 => {
-                remove_root_check()?;
+                menu::permissions::permissions_menu(&db).await?;
             }
             7 => {
-                menu::settings::settings_menu().await?;
+                menu::proxy::proxy_menu(&db).await?;
             }
             8 => {
+                menu::definitions::definitions_menu(&db).await?;
+            }
+            9 => {
+                menu::doctor::doctor_menu(&db).await?;
+            }
+            10 => {
+                menu::webdav::webdav_menu(&db).await?;
+            }
+            11 => {
+                menu::logs::logs_menu().await?;
+            }
+            12 => {
+                remove_root_check()?;
+            }
+            13 => {
+                menu::settings::settings_menu(&db).await?;
+            }
+            14 => {
                 // 切换语言
                 let new_lang = match i18n::current_language() {
                     i18n::Language::ZhCN => i18n::Language::EnUS,
@@ -149,7 +257,7 @@ async fn main() -> Result<()> {
                 let _ = term.clear_screen();
                 print_banner();
             }
-            9 => {
+            15 => {
                 println!("\n{}", i18n::translate("app.exit_message").green().bold());
                 break;
             }
@@ -201,6 +309,12 @@ fn show_main_menu() -> Result<Option<usize>> {
         i18n::translate("menu.main.directory"),
         i18n::translate("menu.main.url"),
         i18n::translate("menu.main.switch"),
+        i18n::translate("menu.main.mcp"),
+        i18n::translate("menu.main.hooks"),
+        i18n::translate("menu.main.permissions"),
+        i18n::translate("menu.main.proxy"),
+        i18n::translate("menu.main.definitions"),
+        i18n::translate("menu.main.doctor"),
         i18n::translate("menu.main.webdav"),
         i18n::translate("menu.main.logs"),
         i18n::translate("menu.main.remove_root"),
@@ -0,0 +1,45 @@
+use crate::models::McpServer;
+
+/// 支持的 MCP 传输方式
+pub const MCP_TRANSPORTS: [&str; 3] = ["stdio", "sse", "http"];
+
+impl McpServer {
+    /// 生成写入 .mcp.json 的服务器定义
+    pub fn to_mcp_json(&self) -> serde_json::Value {
+        if self.transport == "stdio" {
+            let args: Vec<String> = serde_json::from_str(&self.args).unwrap_or_default();
+            let env: serde_json::Value =
+                serde_json::from_str(&self.env).unwrap_or_else(|_| serde_json::json!({}));
+            serde_json::json!({
+                "type": "stdio",
+                "command": self.command.clone().unwrap_or_default(),
+                "args": args,
+                "env": env,
+            })
+        } else {
+            serde_json::json!({
+                "type": self.transport,
+                "url": self.url.clone().unwrap_or_default(),
+            })
+        }
+    }
+}
+
+/// 校验 MCP 服务器的传输方式与 command/url 是否匹配
+pub fn validate_mcp_server(
+    transport: &str,
+    command: Option<&str>,
+    url: Option<&str>,
+) -> Result<(), String> {
+    if !MCP_TRANSPORTS.contains(&transport) {
+        return Err(format!("不支持的传输方式: {}", transport));
+    }
+    if transport == "stdio" {
+        if command.map(|c| c.trim().is_empty()).unwrap_or(true) {
+            return Err("stdio 传输方式必须指定 command".to_string());
+        }
+    } else if url.map(|u| u.trim().is_empty()).unwrap_or(true) {
+        return Err(format!("{} 传输方式必须指定 url", transport));
+    }
+    Ok(())
+}
@@ -1,8 +1,14 @@
-use crate::{models::*, t, DbState};
+use super::{import_review, picker};
+use crate::import_engine::ExistingRecords;
+use crate::{
+    balance, drift, exporters, health, importers, key_helper, models::*, pools, providers, t,
+    DbState,
+};
 use anyhow::Result;
 use colored::Colorize;
 use comfy_table::{Attribute, Cell, Color};
-use dialoguer::{Confirm, Input, Select};
+use dialoguer::{Confirm, Input, MultiSelect, Select};
+use std::collections::HashMap;
 
 pub async fn account_menu(db: &DbState) -> Result<()> {
     let mut last_selection = 0;
@@ -16,6 +22,9 @@ pub async fn account_menu(db: &DbState) -> Result<()> {
             t!("account.menu.delete"),
             t!("account.menu.import"),
             t!("account.menu.export"),
+            t!("account.menu.verify"),
+            t!("account.menu.balance"),
+            t!("account.menu.pools"),
         ];
 
         let selection = match Select::new()
@@ -42,6 +51,9 @@ pub async fn account_menu(db: &DbState) -> Result<()> {
             4 => delete_account(db).await?,
             5 => import_accounts(db).await?,
             6 => export_accounts(db).await?,
+            7 => verify_accounts(db).await?,
+            8 => refresh_balances(db).await?,
+            9 => super::pool::pool_menu(db).await?,
             _ => unreachable!(),
         }
     }
@@ -50,15 +62,47 @@ pub async fn account_menu(db: &DbState) -> Result<()> {
 }
 
 async fn list_accounts(db: &DbState) -> Result<()> {
-    let db_lock = db.lock().await;
-    let request = GetAccountsRequest {
+    let filters = vec![
+        t!("account.filter.all"),
+        t!("account.filter.enabled"),
+        t!("account.filter.disabled"),
+        t!("account.filter.expiring"),
+        t!("account.filter.tag"),
+    ];
+    let filter = match Select::new()
+        .with_prompt(t!("account.filter.prompt"))
+        .items(&filters)
+        .default(0)
+        .interact_opt()?
+    {
+        Some(filter) => filter,
+        None => return Ok(()),
+    };
+
+    let mut request = GetAccountsRequest {
         page: Some(1),
         per_page: Some(100),
         search: None,
         base_url: None,
+        ..Default::default()
     };
+    match filter {
+        1 => request.enabled = Some(true),
+        2 => request.enabled = Some(false),
+        3 => request.expiring_within_days = Some(EXPIRY_WARNING_DAYS),
+        4 => {
+            let tag: String = Input::new()
+                .with_prompt(t!("account.filter.prompt_tag"))
+                .allow_empty(true)
+                .interact_text()?;
+            request.tag = Some(tag);
+        }
+        _ => {}
+    }
 
+    let db_lock = db.lock().await;
     let response = db_lock.get_accounts(request).await?;
+    let balances = db_lock.get_account_balances().await?;
     drop(db_lock);
 
     if response.accounts.is_empty() {
@@ -83,20 +127,52 @@ async fn list_accounts(db: &DbState) -> Result<()> {
         Cell::new(t!("account.list.header_status"))
             .add_attribute(Attribute::Bold)
             .fg(Color::Cyan),
+        Cell::new(t!("account.list.header_token"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("account.list.header_balance"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("account.list.header_expires"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("account.list.header_tags"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("account.list.header_usage"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
     ]);
 
     for account in &response.accounts {
-        let status = if account.is_active {
+        let status = if !account.enabled {
+            t!("account.list.status_disabled")
+        } else if account.is_active {
             t!("account.list.status_active")
         } else {
             t!("account.list.status_inactive")
         };
+        let usage = match account.last_used_at {
+            Some(last_used_at) => format!(
+                "{} · {}",
+                account.use_count,
+                last_used_at
+                    .with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M")
+            ),
+            None => account.use_count.to_string(),
+        };
         table.add_row(vec![
-            account.id.to_string(),
-            account.name.clone(),
-            account.base_url.clone(),
-            account.model.clone(),
-            status.to_string(),
+            Cell::new(account.id),
+            Cell::new(&account.name),
+            Cell::new(&account.base_url),
+            Cell::new(&account.model),
+            Cell::new(status),
+            token_status_cell(account),
+            balance_cell(balances.iter().find(|b| b.account_id == account.id)),
+            expiry_cell(account),
+            Cell::new(account.tag_list().join(", ")),
+            Cell::new(usage),
         ]);
     }
 
@@ -128,19 +204,16 @@ async fn add_account(db: &DbState) -> Result<()> {
         return Ok(());
     }
 
-    let token: String = Input::new()
-        .with_prompt(t!("account.add.prompt_token"))
-        .allow_empty(true)
-        .interact_text()?;
-
-    if token.trim().is_empty() {
+    // Bedrock / Vertex 账号可以不填令牌，是否必填在选择 URL 后按供应商校验
+    let Some(credential) = prompt_credential(None).await? else {
         println!("\n{}", t!("common.cancel").yellow());
         return Ok(());
-    }
+    };
+    let token = credential.token.clone();
 
     // 获取所有 Base URL
     let db_lock = db.lock().await;
-    let base_urls = db_lock.get_base_urls().await?;
+    let base_urls = db_lock.get_base_urls_by_recent_use().await?;
     drop(db_lock);
 
     let base_url: String = if base_urls.is_empty() {
@@ -151,43 +224,63 @@ async fn add_account(db: &DbState) -> Result<()> {
             .default("https://api.anthropic.com".to_string())
             .interact()?
     } else {
-        // 从列表选择 Base URL
-        let items: Vec<String> = base_urls
-            .iter()
-            .map(|u| {
-                if u.is_default {
-                    format!("{} - {} {}", u.name, u.url, t!("account.default_indicator"))
-                } else {
-                    format!("{} - {}", u.name, u.url)
-                }
-            })
-            .collect();
-
-        let selection = Select::new()
-            .with_prompt(t!("account.add.select_base_url"))
-            .items(&items)
-            .default(0)
-            .interact()?;
-
-        base_urls[selection].url.clone()
+        // 从列表选择 Base URL，按最近使用排序
+        match picker::pick_base_url(t!("account.add.select_base_url"), &base_urls, None)? {
+            Some(base_url) => base_url.url.clone(),
+            None => {
+                println!("\n{}", t!("common.cancel").yellow());
+                return Ok(());
+            }
+        }
     };
 
+    let provider_type = provider_type_of(&base_urls, &base_url);
+    let env_vars = prompt_provider_env(provider_type, &HashMap::new())?;
+    if let Err(e) = providers::validate_provider_env(provider_type, credential.secret(), &env_vars)
+    {
+        println!(
+            "\n{}",
+            t!("account.add.provider_invalid").replace("{}", &e).red()
+        );
+        return Ok(());
+    }
+
     let model: String = Input::new()
         .with_prompt(t!("account.add.prompt_model"))
         .allow_empty(true)
         .interact_text()?;
 
+    let lifecycle = prompt_lifecycle(None)?;
+    let headers = super::prompt_custom_headers(&[])?;
+
     let db_lock = db.lock().await;
     let request = CreateAccountRequest {
         name: name.clone(),
         token,
         base_url,
         model,
-        custom_env_vars: None,
+        custom_env_vars: (!env_vars.is_empty()).then(|| serde_json::json!(env_vars)),
+        enabled: Some(lifecycle.enabled),
+        expires_at: Some(lifecycle.expires_at),
+        notes: Some(lifecycle.notes),
+        tags: Some(lifecycle.tags),
     };
 
     match db_lock.create_account(request).await {
-        Ok(_) => {
+        Ok(created) => {
+            if let Some(headers) = &headers {
+                db_lock.set_account_headers(created.id, headers).await?;
+            }
+            if credential.mode != key_helper::MODE_TOKEN {
+                db_lock
+                    .set_account_credential(
+                        created.id,
+                        credential.mode,
+                        credential.helper.as_deref(),
+                        credential.ttl_ms,
+                    )
+                    .await?;
+            }
             println!(
                 "\n{}",
                 t!("account.add.success").replace("{}", &name).green()
@@ -206,41 +299,14 @@ async fn add_account(db: &DbState) -> Result<()> {
 
 async fn edit_account(db: &DbState) -> Result<()> {
     // 先列出所有账号
-    let db_lock = db.lock().await;
-    let request = GetAccountsRequest {
-        page: Some(1),
-        per_page: Some(100),
-        search: None,
-        base_url: None,
-    };
-    let response = db_lock.get_accounts(request).await?;
-    drop(db_lock);
+    let accounts = db.lock().await.get_accounts_by_recent_use(false).await?;
 
-    if response.accounts.is_empty() {
+    if accounts.is_empty() {
         println!("\n{}", t!("account.list.no_records").yellow());
         return Ok(());
     }
 
-    let mut items: Vec<String> = vec![t!("common.cancel").to_string()];
-    items.extend(
-        response
-            .accounts
-            .iter()
-            .map(|a| format!("{} - {}", a.name, a.base_url)),
-    );
-
-    let selection = Select::new()
-        .with_prompt(t!("account.edit.prompt"))
-        .items(&items)
-        .interact_opt()?;
-
-    if let Some(idx) = selection {
-        if idx == 0 {
-            return Ok(());
-        }
-        let idx = idx - 1;
-        let account = &response.accounts[idx];
-
+    if let Some(account) = picker::pick_account(t!("account.edit.prompt"), &accounts, None)? {
         println!("{}", t!("common.input_cancel_hint").yellow());
 
         let name: String = Input::new()
@@ -255,21 +321,15 @@ async fn edit_account(db: &DbState) -> Result<()> {
             name
         };
 
-        let token: String = Input::new()
-            .with_prompt(t!("account.add.prompt_token"))
-            .default(account.token.clone())
-            .allow_empty(true)
-            .interact_text()?;
-
-        let token = if token.trim().is_empty() {
-            account.token.clone()
-        } else {
-            token
+        let Some(credential) = prompt_credential(Some(account)).await? else {
+            println!("\n{}", t!("common.cancel").yellow());
+            return Ok(());
         };
+        let token = credential.token.clone();
 
         // 获取所有 Base URL
         let db_lock = db.lock().await;
-        let base_urls = db_lock.get_base_urls().await?;
+        let base_urls = db_lock.get_base_urls_by_recent_use().await?;
         drop(db_lock);
 
         let base_url: String = if base_urls.is_empty() {
@@ -287,33 +347,28 @@ async fn edit_account(db: &DbState) -> Result<()> {
                 input_url
             }
         } else {
-            // 从列表选择 Base URL
-            let items: Vec<String> = base_urls
-                .iter()
-                .map(|u| {
-                    if u.is_default {
-                        format!("{} - {} {}", u.name, u.url, t!("account.default_indicator"))
-                    } else {
-                        format!("{} - {}", u.name, u.url)
-                    }
-                })
-                .collect();
-
-            // 查找当前账号使用的 Base URL 的索引
-            let default_index = base_urls
-                .iter()
-                .position(|u| u.url == account.base_url)
-                .unwrap_or(0);
-
-            let selection = Select::new()
-                .with_prompt(t!("account.add.select_base_url"))
-                .items(&items)
-                .default(default_index)
-                .interact()?;
-
-            base_urls[selection].url.clone()
+            // 从列表选择 Base URL，预选当前账号使用的地址，按 ESC 保持不变
+            picker::pick_base_url(
+                t!("account.add.select_base_url"),
+                &base_urls,
+                Some(&account.base_url),
+            )?
+            .map(|u| u.url.clone())
+            .unwrap_or_else(|| account.base_url.clone())
         };
 
+        let provider_type = provider_type_of(&base_urls, &base_url);
+        let env_vars = prompt_provider_env(provider_type, &account.custom_env_map())?;
+        if let Err(e) =
+            providers::validate_provider_env(provider_type, credential.secret(), &env_vars)
+        {
+            println!(
+                "\n{}",
+                t!("account.add.provider_invalid").replace("{}", &e).red()
+            );
+            return Ok(());
+        }
+
         let model: String = Input::new()
             .with_prompt(t!("account.add.prompt_model"))
             .default(account.model.clone())
@@ -326,17 +381,35 @@ async fn edit_account(db: &DbState) -> Result<()> {
             model
         };
 
+        let lifecycle = prompt_lifecycle(Some(account))?;
+        let headers = super::prompt_custom_headers(&account.header_list())?;
+
         let db_lock = db.lock().await;
         let request = UpdateAccountRequest {
             name: Some(name),
             token: Some(token),
             base_url: Some(base_url),
             model: Some(model),
-            custom_env_vars: None,
+            custom_env_vars: Some(serde_json::json!(env_vars)),
+            enabled: Some(lifecycle.enabled),
+            expires_at: Some(lifecycle.expires_at),
+            notes: Some(lifecycle.notes),
+            tags: Some(lifecycle.tags),
         };
 
         match db_lock.update_account(account.id, request).await {
             Ok(_) => {
+                if let Some(headers) = &headers {
+                    db_lock.set_account_headers(account.id, headers).await?;
+                }
+                db_lock
+                    .set_account_credential(
+                        account.id,
+                        credential.mode,
+                        credential.helper.as_deref(),
+                        credential.ttl_ms,
+                    )
+                    .await?;
                 println!("\n{}", t!("account.edit.success").green());
             }
             Err(e) => {
@@ -352,41 +425,14 @@ async fn edit_account(db: &DbState) -> Result<()> {
 }
 
 async fn delete_account(db: &DbState) -> Result<()> {
-    let db_lock = db.lock().await;
-    let request = GetAccountsRequest {
-        page: Some(1),
-        per_page: Some(100),
-        search: None,
-        base_url: None,
-    };
-    let response = db_lock.get_accounts(request).await?;
-    drop(db_lock);
+    let accounts = db.lock().await.get_accounts_by_recent_use(false).await?;
 
-    if response.accounts.is_empty() {
+    if accounts.is_empty() {
         println!("\n{}", t!("account.list.no_records").yellow());
         return Ok(());
     }
 
-    let mut items: Vec<String> = vec![t!("common.cancel").to_string()];
-    items.extend(
-        response
-            .accounts
-            .iter()
-            .map(|a| format!("{} - {}", a.name, a.base_url)),
-    );
-
-    let selection = Select::new()
-        .with_prompt(t!("account.delete.prompt"))
-        .items(&items)
-        .interact_opt()?;
-
-    if let Some(idx) = selection {
-        if idx == 0 {
-            return Ok(());
-        }
-        let idx = idx - 1;
-        let account = &response.accounts[idx];
-
+    if let Some(account) = picker::pick_account(t!("account.delete.prompt"), &accounts, None)? {
         if Confirm::new()
             .with_prompt(t!("account.delete.confirm").replace("{}", &account.name))
             .default(false)
@@ -412,61 +458,122 @@ async fn delete_account(db: &DbState) -> Result<()> {
     Ok(())
 }
 
-// 导出账号到JSON文件
+// 按选定格式和条件导出账号
 async fn export_accounts(db: &DbState) -> Result<()> {
-    use std::fs;
-
     println!("\n{}", t!("account.export.title").cyan().bold());
 
-    // 获取所有账号
     let db_lock = db.lock().await;
-    let request = GetAccountsRequest {
-        page: Some(1),
-        per_page: Some(10000),
-        search: None,
-        base_url: None,
-    };
-    let response = db_lock.get_accounts(request).await?;
+    let accounts = db_lock.get_all_accounts().await?;
+    let base_urls = db_lock.get_base_urls().await?;
     drop(db_lock);
 
-    if response.accounts.is_empty() {
+    if accounts.is_empty() {
         println!("\n{}", t!("account.export.no_accounts").yellow());
         return Ok(());
     }
 
-    // 构造导出数据（providers格式）
-    let export_data = serde_json::json!({
-        "providers": response.accounts.iter().map(|account| {
-            serde_json::json!({
-                "name": account.name,
-                "url": account.base_url,
-                "key": account.token,
-                "is_enabled": account.is_active,
-                "weight": 100,
-                "priority": 1
-            })
-        }).collect::<Vec<_>>()
-    });
-
-    // 生成文件名
-    let timestamp = chrono::Local::now().format("%Y-%m-%dT%H-%M-%S");
-    let filename = format!("accounts_export_{}.json", timestamp);
-
-    // 写入文件
-    let json_str = serde_json::to_string_pretty(&export_data)?;
-    fs::write(&filename, json_str)?;
+    let format_items: Vec<&str> = exporters::EXPORTERS
+        .iter()
+        .map(|e| export_format_name(e.id()))
+        .collect();
+    let exporter = match Select::new()
+        .with_prompt(t!("account.export.prompt_format"))
+        .items(&format_items)
+        .default(0)
+        .interact_opt()?
+    {
+        Some(idx) => exporters::EXPORTERS[idx],
+        None => return Ok(()),
+    };
 
-    println!(
-        "\n{}",
-        t!("account.export.success")
-            .replace("{}", &response.accounts.len().to_string())
-            .replace("{file}", &filename)
-            .green()
-    );
+    let filter_items = vec![
+        t!("account.filter.all"),
+        t!("account.filter.tag"),
+        t!("account.export.filter_base_url"),
+        t!("account.export.filter_select"),
+    ];
+    let mut filter = ExportFilter::default();
+    match Select::new()
+        .with_prompt(t!("account.filter.prompt"))
+        .items(&filter_items)
+        .default(0)
+        .interact_opt()?
+    {
+        Some(0) => {}
+        Some(1) => {
+            let tag: String = Input::new()
+                .with_prompt(t!("account.filter.prompt_tag"))
+                .interact_text()?;
+            filter.tag = Some(tag);
+        }
+        Some(2) => {
+            match picker::pick_base_url(t!("account.export.filter_base_url"), &base_urls, None)? {
+                Some(base_url) => filter.base_url = Some(base_url.url.clone()),
+                None => return Ok(()),
+            }
+        }
+        Some(_) => {
+            let items: Vec<String> = accounts
+                .iter()
+                .map(|a| format!("{} - {}", a.name, a.base_url))
+                .collect();
+            match MultiSelect::new()
+                .with_prompt(t!("account.export.filter_select"))
+                .items(&items)
+                .interact_opt()?
+            {
+                Some(indices) if !indices.is_empty() => {
+                    filter.account_ids = Some(indices.into_iter().map(|i| accounts[i].id).collect())
+                }
+                _ => return Ok(()),
+            }
+        }
+        None => return Ok(()),
+    }
+
+    let output: String = Input::new()
+        .with_prompt(if exporter.per_account() {
+            t!("account.export.prompt_output_dir")
+        } else {
+            t!("account.export.prompt_output")
+        })
+        .default(exporters::default_output(exporter))
+        .interact_text()?;
+
+    let db_lock = db.lock().await;
+    let result = exporters::export_accounts(
+        &db_lock,
+        exporter.id(),
+        &filter,
+        std::path::Path::new(output.trim()),
+    )
+    .await;
+    drop(db_lock);
+
+    match result {
+        Ok(result) => println!(
+            "\n{}",
+            t!("account.export.success")
+                .replace("{}", &result.accounts.to_string())
+                .replace("{file}", output.trim())
+                .green()
+        ),
+        Err(e) => println!("\n{}", t!("account.export.error").replace("{}", &e).red()),
+    }
 
     Ok(())
 }
 
+fn export_format_name(format: &str) -> &'static str {
+    match format {
+        "json" => t!("account.export.format_json"),
+        "providers" => t!("account.import.format_providers"),
+        "dotenv" => t!("account.export.format_dotenv"),
+        "shell" => t!("account.export.format_shell"),
+        _ => "CSV",
+    }
+}
+
 // 从JSON文件导入账号
 async fn import_accounts(db: &DbState) -> Result<()> {
     use std::fs;
@@ -477,9 +584,10 @@ async fn import_accounts(db: &DbState) -> Result<()> {
     let file_path: String = Input::new()
         .with_prompt(t!("account.import.prompt_file"))
         .interact_text()?;
+    let file_path = file_path.trim().trim_matches('"').trim_matches('\'');
 
     // 读取文件
-    let content = match fs::read_to_string(&file_path) {
+    let content = match fs::read_to_string(file_path) {
         Ok(c) => c,
         Err(e) => {
             println!(
@@ -492,132 +600,491 @@ async fn import_accounts(db: &DbState) -> Result<()> {
         }
     };
 
-    // 解析JSON
-    let data: serde_json::Value = match serde_json::from_str(&content) {
-        Ok(d) => d,
+    // 自动识别格式并与已有记录比对
+    let existing = ExistingRecords::load(&*db.lock().await).await?;
+    let preview = match importers::preview(file_path, &content, &existing) {
+        Ok(preview) => preview,
         Err(e) => {
             println!(
                 "\n{}",
-                t!("account.import.error_parse")
-                    .replace("{}", &e.to_string())
-                    .red()
+                t!("account.import.error_parse").replace("{}", &e).red()
             );
             return Ok(());
         }
     };
 
-    // 验证格式
-    let providers = match data.get("providers").and_then(|p| p.as_array()) {
-        Some(p) => p,
-        None => {
-            println!("\n{}", t!("account.import.error_format").red());
-            return Ok(());
-        }
-    };
-
-    if providers.is_empty() {
+    if preview.items.is_empty() {
         println!("\n{}", t!("account.import.no_accounts").yellow());
         return Ok(());
     }
 
-    // 导入账号
-    let mut imported_count = 0;
-    let mut skipped_count = 0;
+    println!(
+        "\n{}",
+        t!("account.import.detected_format")
+            .replace("{}", import_format_name(&preview.format))
+            .cyan()
+    );
 
-    println!("\n{}", t!("account.import.processing").cyan());
+    if let Some(report) =
+        import_review::review_and_apply(db, &preview.bundle, preview.items).await?
+    {
+        import_review::print_report(&report);
+    }
 
-    // 先获取所有现有账号，避免每次都查询
-    let db_lock = db.lock().await;
-    let existing_accounts = db_lock
-        .get_accounts(GetAccountsRequest {
-            page: Some(1),
-            per_page: Some(10000),
-            search: None, // 不使用搜索，获取所有账号
-            base_url: None,
-        })
-        .await?;
-    drop(db_lock);
+    Ok(())
+}
 
-    for provider in providers {
-        let name = provider.get("name").and_then(|n| n.as_str()).unwrap_or("");
-        let url = provider.get("url").and_then(|u| u.as_str()).unwrap_or("");
-        let key = provider.get("key").and_then(|k| k.as_str()).unwrap_or("");
+/// 导入格式的显示名称
+fn import_format_name(format: &str) -> &'static str {
+    match format {
+        "providers" => t!("account.import.format_providers"),
+        "backup" => t!("account.import.format_backup"),
+        "cc_switch" => "cc-switch",
+        "claude_code_router" => "claude-code-router",
+        "settings_json" => "settings.json (env)",
+        "csv" => "CSV",
+        "dotenv" => ".env",
+        _ => t!("account.import.format_unknown"),
+    }
+}
 
-        if name.is_empty() || url.is_empty() || key.is_empty() {
-            println!("  {} {}", "⚠".yellow(), t!("account.import.skip_invalid"));
-            skipped_count += 1;
-            continue;
+/// 账号生命周期字段的交互输入结果
+struct LifecycleInput {
+    enabled: bool,
+    expires_at: String,
+    notes: String,
+    tags: Vec<String>,
+}
+
+/// 询问到期日期、备注、标签和启用状态；编辑时以账号当前值作为初始内容
+/// 账号凭据输入结果
+struct CredentialInput {
+    mode: &'static str,
+    token: String,
+    helper: Option<String>,
+    ttl_ms: Option<i64>,
+}
+
+impl CredentialInput {
+    /// 用于供应商校验的凭据：辅助命令模式下由命令生成密钥，不要求保存令牌
+    fn secret(&self) -> &str {
+        match self.mode {
+            key_helper::MODE_HELPER => self.helper.as_deref().unwrap_or(""),
+            _ => &self.token,
         }
+    }
+}
 
-        // 检查是否已存在（精确匹配）
-        let is_duplicate = existing_accounts
-            .accounts
-            .iter()
-            .any(|acc| acc.name == name || acc.token == key);
+/// 选择凭据模式并输入令牌或辅助命令，输入辅助命令后立即试运行，返回 None 表示取消
+async fn prompt_credential(account: Option<&Account>) -> Result<Option<CredentialInput>> {
+    let current_token = account.map(|a| a.token.clone()).unwrap_or_default();
+    let modes = [
+        t!("account.credential.mode_token"),
+        t!("account.credential.mode_helper"),
+    ];
+    let default = match account.map(|a| a.credential_mode.as_str()) {
+        Some(key_helper::MODE_HELPER) => 1,
+        _ => 0,
+    };
+    let selection = Select::new()
+        .with_prompt(t!("account.credential.prompt_mode"))
+        .items(&modes)
+        .default(default)
+        .interact()?;
 
-        if is_duplicate {
-            println!(
-                "  {} {}: {}",
-                "⊖".yellow(),
-                t!("account.import.skip_exists"),
-                name
-            );
-            skipped_count += 1;
-            continue;
+    if selection == 0 {
+        let mut input = Input::<String>::new()
+            .with_prompt(t!("account.add.prompt_token"))
+            .allow_empty(true);
+        if !current_token.is_empty() {
+            input = input.default(current_token.clone());
         }
+        let token = input.interact_text()?;
+        let token = if token.trim().is_empty() {
+            current_token
+        } else {
+            token
+        };
+        return Ok(Some(CredentialInput {
+            mode: key_helper::MODE_TOKEN,
+            token,
+            helper: account.and_then(|a| a.api_key_helper.clone()),
+            ttl_ms: account.and_then(|a| a.api_key_helper_ttl_ms),
+        }));
+    }
 
-        // 创建账号
-        let db_lock = db.lock().await;
-        match db_lock
-            .create_account(CreateAccountRequest {
-                name: name.to_string(),
-                token: key.to_string(),
-                base_url: url.to_string(),
-                model: "".to_string(),
-                custom_env_vars: None,
-            })
-            .await
-        {
-            Ok(_) => {
-                println!(
-                    "  {} {}: {}",
-                    "✓".green(),
-                    t!("account.import.imported"),
-                    name
-                );
-                imported_count += 1;
+    let mut input = Input::<String>::new()
+        .with_prompt(t!("account.credential.prompt_helper"))
+        .allow_empty(true);
+    if let Some(helper) = account.and_then(|a| a.api_key_helper.clone()) {
+        input = input.default(helper);
+    }
+    let helper = input.interact_text()?;
+    if helper.trim().is_empty() {
+        return Ok(None);
+    }
+
+    let mut input = Input::<String>::new()
+        .with_prompt(t!("account.credential.prompt_ttl"))
+        .allow_empty(true);
+    if let Some(ttl) = account.and_then(|a| a.api_key_helper_ttl_ms) {
+        input = input.default(ttl.to_string());
+    }
+    let ttl_input = input.interact_text()?;
+    let ttl_ms = if ttl_input.trim().is_empty() {
+        None
+    } else {
+        match ttl_input.trim().parse::<i64>() {
+            Ok(ttl) => Some(ttl),
+            Err(_) => {
+                println!("{}", t!("account.credential.invalid_ttl").red());
+                return Ok(None);
             }
-            Err(e) => {
-                println!(
-                    "  {} {}: {} ({})",
-                    "✗".red(),
-                    t!("account.import.failed"),
-                    name,
-                    e
-                );
-                skipped_count += 1;
+        }
+    };
+    if let Err(e) = key_helper::validate_credential(key_helper::MODE_HELPER, Some(&helper), ttl_ms)
+    {
+        println!("{}", e.red());
+        return Ok(None);
+    }
+
+    // 试运行辅助命令，失败时由用户决定是否仍然保存
+    println!("{}", t!("account.credential.testing").cyan());
+    match key_helper::run_key_helper(&helper, key_helper::HELPER_TIMEOUT).await {
+        Ok(key) => println!(
+            "{}",
+            t!("account.credential.test_ok")
+                .replace("{}", &drift::mask_secret(&key))
+                .green()
+        ),
+        Err(e) => {
+            println!(
+                "{}",
+                t!("account.credential.test_failed").replace("{}", &e).red()
+            );
+            if !Confirm::new()
+                .with_prompt(t!("account.credential.save_anyway"))
+                .default(false)
+                .interact()?
+            {
+                return Ok(None);
             }
         }
-        drop(db_lock);
     }
 
-    // 显示结果
-    println!("\n{}", "=".repeat(50).cyan());
+    Ok(Some(CredentialInput {
+        mode: key_helper::MODE_HELPER,
+        token: current_token,
+        helper: Some(helper.trim().to_string()),
+        ttl_ms,
+    }))
+}
+
+/// 账号所用 URL 的供应商类型，手动输入的地址按默认供应商处理
+fn provider_type_of(base_urls: &[BaseUrl], url: &str) -> &'static str {
+    base_urls
+        .iter()
+        .find(|u| u.url == url)
+        .and_then(|u| providers::provider_schema(&u.provider_type))
+        .map(|p| p.id)
+        .unwrap_or(providers::DEFAULT_PROVIDER)
+}
+
+/// 按供应商定义逐项输入环境变量，保留账号原有的其他自定义变量
+fn prompt_provider_env(
+    provider_type: &str,
+    current: &HashMap<String, String>,
+) -> Result<HashMap<String, String>> {
+    let mut env_vars = current.clone();
+    let Some(schema) = providers::provider_schema(provider_type) else {
+        return Ok(env_vars);
+    };
+    if schema.required.is_empty() && schema.optional.is_empty() {
+        return Ok(env_vars);
+    }
+
+    println!(
+        "\n{}",
+        t!("account.add.provider_env_title")
+            .replace("{}", schema.name)
+            .cyan()
+    );
+    for (index, var) in schema.env_vars().enumerate() {
+        let optional = if index < schema.required.len() {
+            ""
+        } else {
+            t!("account.add.provider_env_optional")
+        };
+        let mut input = Input::<String>::new()
+            .with_prompt(format!("{}{} ({})", var.key, optional, var.description))
+            .allow_empty(true);
+        if let Some(existing) = current.get(var.key) {
+            input = input.default(existing.clone());
+        }
+        let value = input.interact_text()?;
+        if value.trim().is_empty() {
+            env_vars.remove(var.key);
+        } else {
+            env_vars.insert(var.key.to_string(), value.trim().to_string());
+        }
+    }
+
+    Ok(env_vars)
+}
+
+fn prompt_lifecycle(account: Option<&Account>) -> Result<LifecycleInput> {
+    let expires_at: String = Input::new()
+        .with_prompt(t!("account.add.prompt_expires_at"))
+        .with_initial_text(
+            account
+                .and_then(|a| a.expires_at)
+                .map(|d| d.format("%Y-%m-%d").to_string())
+                .unwrap_or_default(),
+        )
+        .allow_empty(true)
+        .validate_with(|input: &String| -> Result<(), &str> {
+            let input = input.trim();
+            if input.is_empty() || chrono::NaiveDate::parse_from_str(input, "%Y-%m-%d").is_ok() {
+                Ok(())
+            } else {
+                Err(t!("account.add.invalid_date"))
+            }
+        })
+        .interact_text()?;
+
+    let notes: String = Input::new()
+        .with_prompt(t!("account.add.prompt_notes"))
+        .with_initial_text(account.map(|a| a.notes.clone()).unwrap_or_default())
+        .allow_empty(true)
+        .interact_text()?;
+
+    let tags: String = Input::new()
+        .with_prompt(t!("account.add.prompt_tags"))
+        .with_initial_text(account.map(|a| a.tag_list().join(", ")).unwrap_or_default())
+        .allow_empty(true)
+        .interact_text()?;
+
+    let enabled = Confirm::new()
+        .with_prompt(t!("account.add.prompt_enabled"))
+        .default(account.map(|a| a.enabled).unwrap_or(true))
+        .interact()?;
+
+    Ok(LifecycleInput {
+        enabled,
+        expires_at: expires_at.trim().to_string(),
+        notes: notes.trim().to_string(),
+        tags: vec![tags],
+    })
+}
+
+/// 到期时间的显示文本：日期加上剩余天数或已过期标记
+fn expiry_label(account: &Account) -> String {
+    let Some(expires_at) = account.expires_at else {
+        return "-".to_string();
+    };
+    let date = expires_at.format("%Y-%m-%d").to_string();
+    if account.is_expired() {
+        format!("{} ({})", date, t!("account.expiry.expired"))
+    } else {
+        let days = account.days_until_expiry().unwrap_or_default();
+        format!(
+            "{} ({})",
+            date,
+            t!("account.expiry.days_left").replace("{}", &days.to_string())
+        )
+    }
+}
+
+fn expiry_cell(account: &Account) -> Cell {
+    let cell = Cell::new(expiry_label(account));
+    match account.days_until_expiry() {
+        _ if account.is_expired() => cell.fg(Color::Red),
+        Some(days) if days < EXPIRY_WARNING_DAYS => cell.fg(Color::Yellow),
+        _ => cell,
+    }
+}
+
+/// 启动时提示已过期或即将到期的已启用账号
+pub async fn print_expiry_warnings(db: &DbState) {
+    let accounts = match db
+        .lock()
+        .await
+        .get_expiring_accounts(EXPIRY_WARNING_DAYS)
+        .await
+    {
+        Ok(accounts) => accounts,
+        Err(_) => return,
+    };
+    if accounts.is_empty() {
+        return;
+    }
+
     println!(
         "{}",
-        t!("account.import.result_imported")
-            .replace("{}", &imported_count.to_string())
-            .green()
+        t!("account.expiry.warning")
+            .replace("{}", &accounts.len().to_string())
+            .yellow()
+            .bold()
     );
-    if skipped_count > 0 {
-        println!(
-            "{}",
-            t!("account.import.result_skipped")
-                .replace("{}", &skipped_count.to_string())
-                .yellow()
+    for account in &accounts {
+        println!("  • {} - {}", account.name, expiry_label(account));
+    }
+    println!();
+}
+
+fn token_status_cell(account: &Account) -> Cell {
+    let checked_at = account
+        .token_checked_at
+        .map(|t| {
+            t.with_timezone(&chrono::Local)
+                .format("%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or_default();
+    match account.token_status.as_deref() {
+        Some(health::TOKEN_VALID) => {
+            Cell::new(format!("{} {}", t!("account.token.valid"), checked_at)).fg(Color::Green)
+        }
+        Some(health::TOKEN_INVALID) => {
+            Cell::new(format!("{} {}", t!("account.token.invalid"), checked_at)).fg(Color::Red)
+        }
+        Some(_) => {
+            Cell::new(format!("{} {}", t!("account.token.error"), checked_at)).fg(Color::Yellow)
+        }
+        None => Cell::new(t!("account.token.unchecked")).fg(Color::DarkGrey),
+    }
+}
+
+async fn verify_accounts(db: &DbState) -> Result<()> {
+    let db_lock = db.lock().await;
+    let request = GetAccountsRequest {
+        page: Some(1),
+        per_page: Some(100),
+        search: None,
+        base_url: None,
+        ..Default::default()
+    };
+    let response = db_lock.get_accounts(request).await?;
+    drop(db_lock);
+
+    if response.accounts.is_empty() {
+        println!("\n{}", t!("account.list.no_records").yellow());
+        return Ok(());
+    }
+
+    let mut items: Vec<String> = vec![
+        t!("common.cancel").to_string(),
+        t!("account.verify.all").to_string(),
+    ];
+    items.extend(
+        response
+            .accounts
+            .iter()
+            .map(|a| format!("{} - {}", a.name, a.base_url)),
+    );
+
+    let targets: Vec<&Account> = match Select::new()
+        .with_prompt(t!("account.verify.prompt"))
+        .items(&items)
+        .default(1)
+        .interact_opt()?
+    {
+        Some(0) | None => return Ok(()),
+        Some(1) => response.accounts.iter().collect(),
+        Some(idx) => vec![&response.accounts[idx - 2]],
+    };
+
+    println!("\n{}", t!("account.verify.checking").cyan());
+    for account in targets {
+        let db_lock = db.lock().await;
+        let verified = health::verify_account(&db_lock, account.id).await?;
+        // 令牌确认失效时，绑定了账号池的目录切换到池中的其他账号；校验出错不触发切换
+        let failovers = if verified.token_status.as_deref() == Some(health::TOKEN_INVALID) {
+            pools::failover_accounts(&db_lock, &[verified.id]).await?
+        } else {
+            Vec::new()
+        };
+        drop(db_lock);
+
+        let line = format!(
+            "{}: {}",
+            verified.name,
+            token_status_cell(&verified).content()
         );
+        match verified.token_status.as_deref() {
+            Some(health::TOKEN_VALID) => println!("{}", line.green()),
+            Some(health::TOKEN_INVALID) => println!("{}", line.red()),
+            _ => println!("{}", line.yellow()),
+        }
+        if let Some(error) = &verified.token_error {
+            println!("  {}", error.dimmed());
+        }
+        super::print_failovers(&failovers);
     }
-    println!("{}", "=".repeat(50).cyan());
+
+    let _ = Input::<String>::new()
+        .with_prompt(t!("common.continue"))
+        .allow_empty(true)
+        .interact()?;
+
+    Ok(())
+}
+
+fn balance_cell(balance: Option<&AccountBalance>) -> Cell {
+    match balance {
+        Some(AccountBalance {
+            balance: Some(value),
+            unit,
+            ..
+        }) => Cell::new(format!("{:.2} {}", value, unit.as_deref().unwrap_or(""))),
+        Some(_) => Cell::new(t!("account.balance.failed")).fg(Color::Red),
+        None => Cell::new("-").fg(Color::DarkGrey),
+    }
+}
+
+async fn refresh_balances(db: &DbState) -> Result<()> {
+    println!("\n{}", t!("account.balance.refreshing").cyan());
+    let balances = balance::refresh_balances(db, true).await?;
+
+    if balances.is_empty() {
+        println!("{}", t!("account.balance.no_adapter").yellow());
+    } else {
+        let db_lock = db.lock().await;
+        let accounts = db_lock.get_all_accounts().await?;
+        drop(db_lock);
+
+        for item in &balances {
+            let name = accounts
+                .iter()
+                .find(|a| a.id == item.account_id)
+                .map(|a| a.name.as_str())
+                .unwrap_or("-");
+            match (&item.balance, &item.error) {
+                (Some(_), _) => {
+                    println!("{}: {}", name, balance_cell(Some(item)).content().green())
+                }
+                (None, error) => println!(
+                    "{}: {} {}",
+                    name,
+                    t!("account.balance.failed").red(),
+                    error.as_deref().unwrap_or("").dimmed()
+                ),
+            }
+        }
+        if let Some(path) = balance::balance_cache_path() {
+            println!(
+                "\n{}",
+                t!("account.balance.cache_file")
+                    .replace("{}", &path.display().to_string())
+                    .dimmed()
+            );
+        }
+    }
+
+    let _ = Input::<String>::new()
+        .with_prompt(t!("common.continue"))
+        .allow_empty(true)
+        .interact()?;
 
     Ok(())
 }
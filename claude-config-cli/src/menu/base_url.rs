@@ -1,4 +1,4 @@
-use crate::{models::*, t, DbState};
+use crate::{health, models::*, pools, providers, t, DbState};
 use anyhow::Result;
 use colored::Colorize;
 use comfy_table::{Attribute, Cell, Color};
@@ -14,6 +14,8 @@ pub async fn base_url_menu(db: &DbState) -> Result<()> {
             t!("url.menu.add"),
             t!("url.menu.edit"),
             t!("url.menu.delete"),
+            t!("url.menu.health"),
+            t!("url.menu.balance"),
         ];
 
         let selection = match Select::new()
@@ -38,6 +40,8 @@ pub async fn base_url_menu(db: &DbState) -> Result<()> {
             2 => add_base_url(db).await?,
             3 => edit_base_url(db).await?,
             4 => delete_base_url(db).await?,
+            5 => health_check(db).await?,
+            6 => balance_adapter_menu(db).await?,
             _ => unreachable!(),
         }
     }
@@ -48,6 +52,7 @@ pub async fn base_url_menu(db: &DbState) -> Result<()> {
 async fn list_base_urls(db: &DbState) -> Result<()> {
     let db_lock = db.lock().await;
     let base_urls = db_lock.get_base_urls().await?;
+    let latest_checks = db_lock.get_latest_endpoint_checks().await?;
     drop(db_lock);
 
     if base_urls.is_empty() {
@@ -69,15 +74,31 @@ async fn list_base_urls(db: &DbState) -> Result<()> {
         Cell::new(t!("url.list.header_description"))
             .add_attribute(Attribute::Bold)
             .fg(Color::Cyan),
+        Cell::new(t!("url.list.header_provider"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
         Cell::new(t!("url.list.header_api_key"))
             .add_attribute(Attribute::Bold)
             .fg(Color::Cyan),
         Cell::new(t!("url.list.header_default"))
             .add_attribute(Attribute::Bold)
             .fg(Color::Cyan),
+        Cell::new(t!("url.list.header_headers"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("url.list.header_health"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
     ]);
 
     for base_url in &base_urls {
+        let health = match latest_checks.iter().find(|c| c.base_url_id == base_url.id) {
+            Some(check) if check.success => {
+                format!("{} {}", t!("url.health.ok"), format_ms(check.latency_ms))
+            }
+            Some(_) => t!("url.health.failed").to_string(),
+            None => "-".to_string(),
+        };
         let is_default = if base_url.is_default {
             t!("url.list.default_yes")
         } else {
@@ -93,8 +114,13 @@ async fn list_base_urls(db: &DbState) -> Result<()> {
             base_url.name.clone(),
             base_url.url.clone(),
             description.to_string(),
+            providers::provider_schema(&base_url.provider_type)
+                .map(|p| p.name.to_string())
+                .unwrap_or_else(|| base_url.provider_type.clone()),
             base_url.api_key.clone(),
             is_default.to_string(),
+            super::header_names(&base_url.header_list()),
+            health,
         ]);
     }
 
@@ -112,6 +138,38 @@ async fn list_base_urls(db: &DbState) -> Result<()> {
     Ok(())
 }
 
+/// 选择供应商类型，并提示账号需要提供的环境变量
+fn prompt_provider_type(current: &str) -> Result<String> {
+    let items: Vec<String> = providers::PROVIDERS
+        .iter()
+        .map(|p| format!("{} ({})", p.name, p.id))
+        .collect();
+    let default = providers::PROVIDERS
+        .iter()
+        .position(|p| p.id == current)
+        .unwrap_or(0);
+
+    let idx = Select::new()
+        .with_prompt(t!("url.add.prompt_provider"))
+        .items(&items)
+        .default(default)
+        .interact()?;
+    let schema = &providers::PROVIDERS[idx];
+
+    if !schema.required.is_empty() {
+        let required: Vec<&str> = schema.required.iter().map(|v| v.key).collect();
+        println!(
+            "{}",
+            t!("url.add.provider_hint")
+                .replace("{url}", schema.base_url_env)
+                .replace("{vars}", &required.join(", "))
+                .cyan()
+        );
+    }
+
+    Ok(schema.id.to_string())
+}
+
 async fn add_base_url(db: &DbState) -> Result<()> {
     println!("\n{}", t!("url.add.title").green().bold());
     println!("{}", t!("common.input_cancel_hint").yellow());
@@ -142,11 +200,18 @@ async fn add_base_url(db: &DbState) -> Result<()> {
         .allow_empty(true)
         .interact_text()?;
 
-    let api_key: String = Input::new()
-        .with_prompt(t!("url.add.prompt_api_key"))
-        .default("ANTHROPIC_API_KEY".to_string())
-        .allow_empty(true)
-        .interact_text()?;
+    let provider_type = prompt_provider_type(providers::DEFAULT_PROVIDER)?;
+
+    // 只有 Anthropic 类型使用 URL 上的 API KEY 变量名
+    let api_key: String = if provider_type == providers::DEFAULT_PROVIDER {
+        Input::new()
+            .with_prompt(t!("url.add.prompt_api_key"))
+            .default("ANTHROPIC_API_KEY".to_string())
+            .allow_empty(true)
+            .interact_text()?
+    } else {
+        String::new()
+    };
 
     let api_key = if api_key.trim().is_empty() {
         "ANTHROPIC_API_KEY".to_string()
@@ -159,6 +224,8 @@ async fn add_base_url(db: &DbState) -> Result<()> {
         .default(false)
         .interact()?;
 
+    let headers = super::prompt_custom_headers(&[])?;
+
     let db_lock = db.lock().await;
     let request = CreateBaseUrlRequest {
         name: name.clone(),
@@ -169,11 +236,15 @@ async fn add_base_url(db: &DbState) -> Result<()> {
             Some(description)
         },
         api_key: Some(api_key),
+        provider_type: Some(provider_type),
         is_default: Some(is_default),
     };
 
     match db_lock.create_base_url(request).await {
-        Ok(_) => {
+        Ok(created) => {
+            if let Some(headers) = &headers {
+                db_lock.set_base_url_headers(created.id, headers).await?;
+            }
             println!("\n{}", t!("url.add.success").replace("{}", &name).green());
         }
         Err(e) => {
@@ -244,11 +315,17 @@ async fn edit_base_url(db: &DbState) -> Result<()> {
             .allow_empty(true)
             .interact_text()?;
 
-        let api_key: String = Input::new()
-            .with_prompt(t!("url.add.prompt_api_key"))
-            .default(base_url.api_key.clone())
-            .allow_empty(true)
-            .interact_text()?;
+        let provider_type = prompt_provider_type(&base_url.provider_type)?;
+
+        let api_key: String = if provider_type == providers::DEFAULT_PROVIDER {
+            Input::new()
+                .with_prompt(t!("url.add.prompt_api_key"))
+                .default(base_url.api_key.clone())
+                .allow_empty(true)
+                .interact_text()?
+        } else {
+            base_url.api_key.clone()
+        };
 
         let api_key = if api_key.trim().is_empty() {
             "ANTHROPIC_API_KEY".to_string()
@@ -261,6 +338,8 @@ async fn edit_base_url(db: &DbState) -> Result<()> {
             .default(base_url.is_default)
             .interact()?;
 
+        let headers = super::prompt_custom_headers(&base_url.header_list())?;
+
         let db_lock = db.lock().await;
         let request = UpdateBaseUrlRequest {
             name: Some(name),
@@ -271,11 +350,15 @@ async fn edit_base_url(db: &DbState) -> Result<()> {
                 Some(description)
             },
             api_key: Some(api_key),
+            provider_type: Some(provider_type),
             is_default: Some(is_default),
         };
 
         match db_lock.update_base_url(base_url.id, request).await {
             Ok(_) => {
+                if let Some(headers) = &headers {
+                    db_lock.set_base_url_headers(base_url.id, headers).await?;
+                }
                 println!("\n{}", t!("url.edit.success").green());
             }
             Err(e) => {
@@ -341,3 +424,335 @@ async fn delete_base_url(db: &DbState) -> Result<()> {
 
     Ok(())
 }
+
+async fn health_check(db: &DbState) -> Result<()> {
+    let db_lock = db.lock().await;
+    let base_urls = db_lock.get_base_urls().await?;
+    drop(db_lock);
+
+    if base_urls.is_empty() {
+        println!("\n{}", t!("url.list.no_records").yellow());
+        return Ok(());
+    }
+
+    let mut items: Vec<String> = vec![
+        t!("common.cancel").to_string(),
+        t!("url.health.all").to_string(),
+    ];
+    items.extend(base_urls.iter().map(|u| format!("{} - {}", u.name, u.url)));
+
+    let idx = match Select::new()
+        .with_prompt(t!("url.health.prompt_select"))
+        .items(&items)
+        .default(1)
+        .interact_opt()?
+    {
+        Some(0) | None => return Ok(()),
+        Some(idx) => idx,
+    };
+
+    // 检查全部地址时只做连通性探测，单个地址可选择账号发送带认证的请求
+    let targets: Vec<(&BaseUrl, Option<i64>)> = if idx == 1 {
+        base_urls.iter().map(|u| (u, None)).collect()
+    } else {
+        let base_url = &base_urls[idx - 2];
+        vec![(base_url, select_probe_account(db, base_url).await?)]
+    };
+
+    println!("\n{}", t!("url.health.checking").cyan());
+    let mut checks = Vec::new();
+    let mut failovers = Vec::new();
+    for (base_url, account_id) in &targets {
+        let db_lock = db.lock().await;
+        let check = health::check_base_url(&db_lock, base_url.id, *account_id).await?;
+        failovers.extend(pools::failover_after_check(&db_lock, &check).await?);
+        drop(db_lock);
+        checks.push(((*base_url).clone(), check));
+    }
+    print_checks(&checks);
+    super::print_failovers(&failovers);
+
+    if targets.len() == 1 {
+        let db_lock = db.lock().await;
+        let history = db_lock.get_endpoint_checks(targets[0].0.id, 10).await?;
+        drop(db_lock);
+        if history.len() > 1 {
+            println!("\n{}", t!("url.health.history").green().bold());
+            let rows: Vec<(BaseUrl, EndpointCheck)> = history
+                .into_iter()
+                .map(|check| (targets[0].0.clone(), check))
+                .collect();
+            print_checks(&rows);
+        }
+    }
+
+    let _ = Input::<String>::new()
+        .with_prompt(t!("common.continue"))
+        .allow_empty(true)
+        .interact()?;
+
+    Ok(())
+}
+
+async fn select_probe_account(db: &DbState, base_url: &BaseUrl) -> Result<Option<i64>> {
+    let db_lock = db.lock().await;
+    let accounts = db_lock
+        .get_accounts(GetAccountsRequest {
+            page: Some(1),
+            per_page: Some(100),
+            search: None,
+            base_url: Some(base_url.url.clone()),
+            ..Default::default()
+        })
+        .await?
+        .accounts;
+    drop(db_lock);
+
+    if accounts.is_empty() {
+        return Ok(None);
+    }
+
+    let mut items: Vec<String> = vec![t!("url.health.no_account").to_string()];
+    items.extend(accounts.iter().map(|a| a.name.clone()));
+    let selection = Select::new()
+        .with_prompt(t!("url.health.prompt_account"))
+        .items(&items)
+        .default(0)
+        .interact_opt()?;
+
+    Ok(match selection {
+        Some(idx) if idx > 0 => Some(accounts[idx - 1].id),
+        _ => None,
+    })
+}
+
+fn format_ms(value: Option<i64>) -> String {
+    value
+        .map(|ms| format!("{} ms", ms))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn print_checks(checks: &[(BaseUrl, EndpointCheck)]) {
+    let mut table = super::create_table();
+    table.set_header(vec![
+        Cell::new(t!("url.list.header_name"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("url.health.header_result"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("url.health.header_connect"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("url.health.header_tls"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("url.health.header_latency"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("url.health.header_status"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("url.health.header_time"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+    ]);
+
+    for (base_url, check) in checks {
+        let result = if check.success {
+            Cell::new(t!("url.health.ok")).fg(Color::Green)
+        } else {
+            Cell::new(format!(
+                "{}\n{}",
+                t!("url.health.failed"),
+                check.error.clone().unwrap_or_default()
+            ))
+            .fg(Color::Red)
+        };
+        let status = match (check.status_code, check.auth_status_code) {
+            (Some(code), Some(auth)) => format!("{} / {}", code, auth),
+            (Some(code), None) => code.to_string(),
+            _ => "-".to_string(),
+        };
+        table.add_row(vec![
+            Cell::new(&base_url.name),
+            result,
+            Cell::new(format_ms(check.connect_ms)),
+            Cell::new(format_ms(check.tls_ms)),
+            Cell::new(format_ms(check.latency_ms)),
+            Cell::new(status),
+            Cell::new(
+                check
+                    .checked_at
+                    .with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M:%S")
+                    .to_string(),
+            ),
+        ]);
+    }
+
+    println!("\n{}", table);
+}
+
+async fn balance_adapter_menu(db: &DbState) -> Result<()> {
+    let db_lock = db.lock().await;
+    let base_urls = db_lock.get_base_urls().await?;
+    drop(db_lock);
+
+    if base_urls.is_empty() {
+        println!("\n{}", t!("url.list.no_records").yellow());
+        return Ok(());
+    }
+
+    let mut items: Vec<String> = vec![t!("common.cancel").to_string()];
+    items.extend(base_urls.iter().map(|u| format!("{} - {}", u.name, u.url)));
+    let base_url = match Select::new()
+        .with_prompt(t!("url.balance.prompt_select"))
+        .items(&items)
+        .default(0)
+        .interact_opt()?
+    {
+        Some(0) | None => return Ok(()),
+        Some(idx) => &base_urls[idx - 1],
+    };
+
+    let db_lock = db.lock().await;
+    let existing = db_lock.get_balance_adapter(base_url.id).await?;
+    drop(db_lock);
+
+    if let Some(adapter) = &existing {
+        println!("\n{}", t!("url.balance.current").green().bold());
+        println!("  {} {}", adapter.method, adapter.url_template);
+        println!(
+            "  {}: {} / {} {}",
+            t!("url.balance.prompt_path"),
+            adapter.balance_path,
+            adapter.divisor,
+            adapter.unit
+        );
+
+        let actions = vec![
+            t!("common.cancel"),
+            t!("url.balance.action_edit"),
+            t!("url.balance.action_remove"),
+        ];
+        match Select::new()
+            .with_prompt(t!("common.select_operation"))
+            .items(&actions)
+            .default(1)
+            .interact_opt()?
+        {
+            Some(1) => {}
+            Some(2) => {
+                let db_lock = db.lock().await;
+                db_lock.delete_balance_adapter(base_url.id).await?;
+                println!("\n{}", t!("url.balance.removed").green());
+                return Ok(());
+            }
+            _ => return Ok(()),
+        }
+    }
+
+    let methods = ["GET", "POST"];
+    let method_idx = Select::new()
+        .with_prompt(t!("url.balance.prompt_method"))
+        .items(&methods)
+        .default(
+            existing
+                .as_ref()
+                .map(|a| (a.method == "POST") as usize)
+                .unwrap_or(0),
+        )
+        .interact()?;
+
+    let url_template: String = Input::new()
+        .with_prompt(t!("url.balance.prompt_url"))
+        .default(
+            existing
+                .as_ref()
+                .map(|a| a.url_template.clone())
+                .unwrap_or_else(|| "{base_url}/api/user/self".to_string()),
+        )
+        .interact_text()?;
+
+    let headers: String = Input::new()
+        .with_prompt(t!("url.balance.prompt_headers"))
+        .default(
+            existing
+                .as_ref()
+                .and_then(|a| a.headers.clone())
+                .unwrap_or_else(|| r#"{"Authorization": "Bearer {token}"}"#.to_string()),
+        )
+        .allow_empty(true)
+        .interact_text()?;
+    if !headers.trim().is_empty()
+        && serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(&headers).is_err()
+    {
+        println!("\n{}", t!("url.balance.invalid_headers").red());
+        return Ok(());
+    }
+
+    let body_template = if methods[method_idx] == "POST" {
+        Input::<String>::new()
+            .with_prompt(t!("url.balance.prompt_body"))
+            .default(
+                existing
+                    .as_ref()
+                    .and_then(|a| a.body_template.clone())
+                    .unwrap_or_default(),
+            )
+            .allow_empty(true)
+            .interact_text()?
+    } else {
+        String::new()
+    };
+
+    let balance_path: String = Input::new()
+        .with_prompt(t!("url.balance.prompt_path"))
+        .default(
+            existing
+                .as_ref()
+                .map(|a| a.balance_path.clone())
+                .unwrap_or_else(|| "data.quota".to_string()),
+        )
+        .interact_text()?;
+
+    let divisor: f64 = Input::new()
+        .with_prompt(t!("url.balance.prompt_divisor"))
+        .default(existing.as_ref().map(|a| a.divisor).unwrap_or(1.0))
+        .interact_text()?;
+
+    let unit: String = Input::new()
+        .with_prompt(t!("url.balance.prompt_unit"))
+        .default(
+            existing
+                .as_ref()
+                .map(|a| a.unit.clone())
+                .unwrap_or_else(|| "USD".to_string()),
+        )
+        .interact_text()?;
+
+    let enabled = Confirm::new()
+        .with_prompt(t!("url.balance.prompt_enabled"))
+        .default(existing.as_ref().map(|a| a.enabled).unwrap_or(true))
+        .interact()?;
+
+    let request = SaveBalanceAdapterRequest {
+        base_url_id: base_url.id,
+        method: Some(methods[method_idx].to_string()),
+        url_template,
+        headers: Some(headers).filter(|h| !h.trim().is_empty()),
+        body_template: Some(body_template).filter(|b| !b.trim().is_empty()),
+        balance_path,
+        divisor: Some(divisor),
+        unit: Some(unit),
+        enabled: Some(enabled),
+    };
+
+    let db_lock = db.lock().await;
+    db_lock.save_balance_adapter(request).await?;
+    drop(db_lock);
+    println!("\n{}", t!("url.balance.saved").green());
+
+    Ok(())
+}
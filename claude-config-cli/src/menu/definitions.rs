@@ -0,0 +1,476 @@
+use crate::{definitions, models::*, t, DbState};
+use anyhow::Result;
+use colored::Colorize;
+use comfy_table::{Attribute, Cell, Color};
+use dialoguer::{Confirm, Input, MultiSelect, Select};
+use std::fs;
+
+pub async fn definitions_menu(db: &DbState) -> Result<()> {
+    let mut last_selection = 0;
+
+    loop {
+        let items = vec![
+            t!("common.back"),
+            t!("definitions.menu.list"),
+            t!("definitions.menu.add"),
+            t!("definitions.menu.edit"),
+            t!("definitions.menu.delete"),
+            t!("definitions.menu.import"),
+            t!("definitions.menu.assign_directory"),
+        ];
+
+        let selection = match Select::new()
+            .with_prompt(format!(
+                "\n{} (ESC {})",
+                t!("definitions.menu.title"),
+                t!("common.to_back")
+            ))
+            .items(&items)
+            .default(last_selection)
+            .interact_opt()?
+        {
+            Some(sel) => sel,
+            None => break, // 用户按了ESC，返回上一级
+        };
+
+        last_selection = selection;
+
+        match selection {
+            0 => break,
+            1 => list_definitions(db).await?,
+            2 => add_definition(db).await?,
+            3 => edit_definition(db).await?,
+            4 => delete_definition(db).await?,
+            5 => import_definitions(db).await?,
+            6 => assign_to_directory(db).await?,
+            _ => unreachable!(),
+        }
+    }
+
+    Ok(())
+}
+
+fn kind_label(kind: &str) -> &'static str {
+    match kind {
+        "agent" => t!("definitions.kind.agent"),
+        _ => t!("definitions.kind.command"),
+    }
+}
+
+async fn list_definitions(db: &DbState) -> Result<()> {
+    let db_lock = db.lock().await;
+    let definitions = db_lock.get_definitions().await?;
+    drop(db_lock);
+
+    if definitions.is_empty() {
+        println!("\n{}", t!("definitions.list.no_records").yellow());
+        return Ok(());
+    }
+
+    let mut table = super::create_table();
+    table.set_header(vec![
+        Cell::new(t!("definitions.list.header_id"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("definitions.list.header_kind"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("definitions.list.header_name"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("definitions.list.header_description"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("definitions.list.header_default"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+    ]);
+
+    for definition in &definitions {
+        table.add_row(vec![
+            definition.id.to_string(),
+            kind_label(&definition.kind).to_string(),
+            definition.name.clone(),
+            definition.description.clone().unwrap_or_default(),
+            if definition.is_default {
+                t!("url.list.default_yes").to_string()
+            } else {
+                String::new()
+            },
+        ]);
+    }
+
+    println!("\n{}", table);
+    println!(
+        "{}",
+        t!("definitions.list.total").replace("{}", &definitions.len().to_string())
+    );
+
+    let _ = Input::<String>::new()
+        .with_prompt(t!("common.continue"))
+        .allow_empty(true)
+        .interact()?;
+
+    Ok(())
+}
+
+/// 从文件读取定义内容，路径为空时返回 None
+fn prompt_content_file(prompt: &str) -> Result<Option<String>> {
+    let path: String = Input::new()
+        .with_prompt(prompt)
+        .allow_empty(true)
+        .interact_text()?;
+
+    let path = path.trim();
+    if path.is_empty() {
+        return Ok(None);
+    }
+
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(Some(content)),
+        Err(e) => {
+            println!(
+                "\n{}",
+                t!("definitions.add.read_error")
+                    .replace("{}", &e.to_string())
+                    .red()
+            );
+            Ok(None)
+        }
+    }
+}
+
+async fn add_definition(db: &DbState) -> Result<()> {
+    println!("\n{}", t!("definitions.add.title").green().bold());
+    println!("{}", t!("common.input_cancel_hint").yellow());
+
+    let kind_items: Vec<&str> = definitions::DEFINITION_KINDS
+        .iter()
+        .map(|k| kind_label(k))
+        .collect();
+    let kind_idx = Select::new()
+        .with_prompt(t!("definitions.add.prompt_kind"))
+        .items(&kind_items)
+        .default(0)
+        .interact()?;
+    let kind = definitions::DEFINITION_KINDS[kind_idx];
+
+    let name: String = Input::new()
+        .with_prompt(t!("definitions.add.prompt_name"))
+        .allow_empty(true)
+        .interact_text()?;
+
+    if name.trim().is_empty() || name.trim().eq_ignore_ascii_case("q") {
+        println!("\n{}", t!("common.cancel").yellow());
+        return Ok(());
+    }
+
+    let content = match prompt_content_file(t!("definitions.add.prompt_file"))? {
+        Some(content) => content,
+        None => {
+            println!("\n{}", t!("common.cancel").yellow());
+            return Ok(());
+        }
+    };
+
+    if let Err(e) = definitions::validate_definition(kind, name.trim(), &content) {
+        println!("\n{}", t!("definitions.add.error").replace("{}", &e).red());
+        return Ok(());
+    }
+
+    let description: String = Input::new()
+        .with_prompt(t!("definitions.add.prompt_description"))
+        .allow_empty(true)
+        .interact_text()?;
+
+    let is_default = Confirm::new()
+        .with_prompt(t!("definitions.add.prompt_default"))
+        .default(false)
+        .interact()?;
+
+    let request =
+        definitions::definition_request(kind, name.trim(), content, Some(description), is_default);
+
+    let db_lock = db.lock().await;
+    match db_lock.create_definition(request).await {
+        Ok(_) => {
+            println!(
+                "\n{}",
+                t!("definitions.add.success")
+                    .replace("{}", name.trim())
+                    .green()
+            );
+        }
+        Err(e) => {
+            println!(
+                "\n{}",
+                t!("definitions.add.error")
+                    .replace("{}", &e.to_string())
+                    .red()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+async fn select_definition(db: &DbState, prompt: &str) -> Result<Option<ClaudeDefinition>> {
+    let db_lock = db.lock().await;
+    let definitions = db_lock.get_definitions().await?;
+    drop(db_lock);
+
+    if definitions.is_empty() {
+        println!("\n{}", t!("definitions.list.no_records").yellow());
+        return Ok(None);
+    }
+
+    let mut items: Vec<String> = vec![t!("common.cancel").to_string()];
+    items.extend(
+        definitions
+            .iter()
+            .map(|d| format!("[{}] {}", kind_label(&d.kind), d.name)),
+    );
+
+    let selection = Select::new()
+        .with_prompt(prompt)
+        .items(&items)
+        .interact_opt()?;
+
+    match selection {
+        Some(idx) if idx > 0 => Ok(definitions.into_iter().nth(idx - 1)),
+        _ => Ok(None),
+    }
+}
+
+async fn edit_definition(db: &DbState) -> Result<()> {
+    let definition = match select_definition(db, t!("definitions.edit.prompt")).await? {
+        Some(definition) => definition,
+        None => return Ok(()),
+    };
+
+    println!("{}", t!("common.input_cancel_hint").yellow());
+
+    let name: String = Input::new()
+        .with_prompt(t!("definitions.add.prompt_name"))
+        .default(definition.name.clone())
+        .allow_empty(true)
+        .interact_text()?;
+
+    let name = if name.trim().is_empty() {
+        definition.name.clone()
+    } else {
+        name.trim().to_string()
+    };
+
+    // 文件路径留空则保留原有内容
+    let content = prompt_content_file(t!("definitions.edit.prompt_file"))?;
+
+    if let Err(e) = definitions::validate_definition(
+        &definition.kind,
+        &name,
+        content.as_deref().unwrap_or(&definition.content),
+    ) {
+        println!("\n{}", t!("definitions.edit.error").replace("{}", &e).red());
+        return Ok(());
+    }
+
+    let description: String = Input::new()
+        .with_prompt(t!("definitions.add.prompt_description"))
+        .default(definition.description.clone().unwrap_or_default())
+        .allow_empty(true)
+        .interact_text()?;
+
+    let is_default = Confirm::new()
+        .with_prompt(t!("definitions.add.prompt_default"))
+        .default(definition.is_default)
+        .interact()?;
+
+    let db_lock = db.lock().await;
+    let request = UpdateClaudeDefinitionRequest {
+        name: Some(name),
+        description: Some(description),
+        content,
+        is_default: Some(is_default),
+    };
+
+    match db_lock.update_definition(definition.id, request).await {
+        Ok(_) => {
+            println!("\n{}", t!("definitions.edit.success").green());
+        }
+        Err(e) => {
+            println!(
+                "\n{}",
+                t!("definitions.edit.error")
+                    .replace("{}", &e.to_string())
+                    .red()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+async fn delete_definition(db: &DbState) -> Result<()> {
+    let definition = match select_definition(db, t!("definitions.delete.prompt")).await? {
+        Some(definition) => definition,
+        None => return Ok(()),
+    };
+
+    if Confirm::new()
+        .with_prompt(t!("definitions.delete.confirm").replace("{}", &definition.name))
+        .default(false)
+        .interact()?
+    {
+        let db_lock = db.lock().await;
+        match db_lock.delete_definition(definition.id).await {
+            Ok(_) => {
+                println!("\n{}", t!("definitions.delete.success").green());
+            }
+            Err(e) => {
+                println!(
+                    "\n{}",
+                    t!("definitions.delete.error")
+                        .replace("{}", &e.to_string())
+                        .red()
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn import_definitions(db: &DbState) -> Result<()> {
+    println!("\n{}", t!("definitions.import.title").green().bold());
+    println!("{}", t!("common.input_cancel_hint").yellow());
+
+    let path: String = Input::new()
+        .with_prompt(t!("definitions.import.prompt_path"))
+        .allow_empty(true)
+        .interact_text()?;
+
+    if path.trim().is_empty() || path.trim().eq_ignore_ascii_case("q") {
+        println!("\n{}", t!("common.cancel").yellow());
+        return Ok(());
+    }
+
+    let requests = match definitions::scan_claude_dir(path.trim()) {
+        Ok(requests) => requests,
+        Err(e) => {
+            println!(
+                "\n{}",
+                t!("definitions.import.error")
+                    .replace("{}", &e.to_string())
+                    .red()
+            );
+            return Ok(());
+        }
+    };
+
+    if requests.is_empty() {
+        println!("\n{}", t!("definitions.import.no_files").yellow());
+        return Ok(());
+    }
+
+    let overwrite = Confirm::new()
+        .with_prompt(t!("definitions.import.prompt_overwrite"))
+        .default(false)
+        .interact()?;
+
+    let db_lock = db.lock().await;
+    match db_lock.import_definitions(requests, overwrite).await {
+        Ok((imported, skipped)) => {
+            println!(
+                "\n{}",
+                t!("definitions.import.success")
+                    .replacen("{}", &imported.to_string(), 1)
+                    .replacen("{}", &skipped.to_string(), 1)
+                    .green()
+            );
+        }
+        Err(e) => {
+            println!(
+                "\n{}",
+                t!("definitions.import.error")
+                    .replace("{}", &e.to_string())
+                    .red()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+async fn assign_to_directory(db: &DbState) -> Result<()> {
+    let db_lock = db.lock().await;
+    let definitions = db_lock.get_definitions().await?;
+    let directories = db_lock.get_directories().await?;
+    drop(db_lock);
+
+    if definitions.is_empty() {
+        println!("\n{}", t!("definitions.list.no_records").yellow());
+        return Ok(());
+    }
+    if directories.is_empty() {
+        println!("\n{}", t!("switch.no_directories").yellow());
+        return Ok(());
+    }
+
+    let mut items: Vec<String> = vec![t!("common.cancel").to_string()];
+    items.extend(
+        directories
+            .iter()
+            .map(|d| format!("{} - {}", d.name, d.path)),
+    );
+
+    let selection = Select::new()
+        .with_prompt(t!("switch.select_directory"))
+        .items(&items)
+        .interact_opt()?;
+
+    let directory = match selection {
+        Some(idx) if idx > 0 => &directories[idx - 1],
+        _ => return Ok(()),
+    };
+
+    let db_lock = db.lock().await;
+    let enabled_ids = db_lock
+        .get_directory_enabled_definition_ids(directory.id)
+        .await?;
+    drop(db_lock);
+
+    let definition_items: Vec<String> = definitions
+        .iter()
+        .map(|d| format!("[{}] {}", kind_label(&d.kind), d.name))
+        .collect();
+    let defaults: Vec<bool> = definitions
+        .iter()
+        .map(|d| enabled_ids.contains(&d.id))
+        .collect();
+
+    let definition_ids: Vec<i64> = match MultiSelect::new()
+        .with_prompt(t!("definitions.assign.prompt_definitions"))
+        .items(&definition_items)
+        .defaults(&defaults)
+        .interact_opt()?
+    {
+        Some(indices) => indices.into_iter().map(|i| definitions[i].id).collect(),
+        None => return Ok(()),
+    };
+
+    let db_lock = db.lock().await;
+    match db_lock
+        .set_directory_definitions(directory.id, &definition_ids)
+        .await
+    {
+        Ok(_) => println!("\n{}", t!("definitions.assign.success").green()),
+        Err(e) => println!(
+            "\n{}",
+            t!("definitions.assign.error")
+                .replace("{}", &e.to_string())
+                .red()
+        ),
+    }
+
+    Ok(())
+}
@@ -1,8 +1,9 @@
-use crate::{models::*, t, DbState};
+use super::picker;
+use crate::{discovery, drift, models::*, t, DbState};
 use anyhow::Result;
 use colored::Colorize;
 use comfy_table::{Attribute, Cell, Color};
-use dialoguer::{Confirm, Input, Select};
+use dialoguer::{Confirm, Input, MultiSelect, Select};
 
 pub async fn directory_menu(db: &DbState) -> Result<()> {
     let mut last_selection = 0;
@@ -14,6 +15,7 @@ pub async fn directory_menu(db: &DbState) -> Result<()> {
             t!("directory.menu.add"),
             t!("directory.menu.edit"),
             t!("directory.menu.delete"),
+            t!("directory.menu.discover"),
         ];
 
         let selection = match Select::new()
@@ -38,6 +40,7 @@ pub async fn directory_menu(db: &DbState) -> Result<()> {
             2 => add_directory(db).await?,
             3 => edit_directory(db).await?,
             4 => delete_directory(db).await?,
+            5 => discover_menu(db).await?,
             _ => unreachable!(),
         }
     }
@@ -172,34 +175,17 @@ async fn add_directory(db: &DbState) -> Result<()> {
 }
 
 async fn edit_directory(db: &DbState) -> Result<()> {
-    let db_lock = db.lock().await;
-    let directories = db_lock.get_directories().await?;
-    drop(db_lock);
+    let directories = db.lock().await.get_directories_by_recent_use().await?;
 
     if directories.is_empty() {
         println!("\n{}", t!("directory.list.no_records").yellow());
         return Ok(());
     }
 
-    let mut items: Vec<String> = vec![t!("common.cancel").to_string()];
-    items.extend(
-        directories
-            .iter()
-            .map(|d| format!("{} - {}", d.name, d.path)),
-    );
-
-    let selection = Select::new()
-        .with_prompt(t!("directory.edit.prompt"))
-        .items(&items)
-        .interact_opt()?;
-
-    if let Some(idx) = selection {
-        if idx == 0 {
-            return Ok(());
-        }
-        let idx = idx - 1;
-        let directory = &directories[idx];
-
+    let default_id = picker::directory_for_cwd(&directories);
+    if let Some(directory) =
+        picker::pick_directory(t!("directory.edit.prompt"), &directories, default_id)?
+    {
         println!("{}", t!("common.input_cancel_hint").yellow());
 
         let name: String = Input::new()
@@ -251,34 +237,17 @@ async fn edit_directory(db: &DbState) -> Result<()> {
 }
 
 async fn delete_directory(db: &DbState) -> Result<()> {
-    let db_lock = db.lock().await;
-    let directories = db_lock.get_directories().await?;
-    drop(db_lock);
+    let directories = db.lock().await.get_directories_by_recent_use().await?;
 
     if directories.is_empty() {
         println!("\n{}", t!("directory.list.no_records").yellow());
         return Ok(());
     }
 
-    let mut items: Vec<String> = vec![t!("common.cancel").to_string()];
-    items.extend(
-        directories
-            .iter()
-            .map(|d| format!("{} - {}", d.name, d.path)),
-    );
-
-    let selection = Select::new()
-        .with_prompt(t!("directory.delete.prompt"))
-        .items(&items)
-        .interact_opt()?;
-
-    if let Some(idx) = selection {
-        if idx == 0 {
-            return Ok(());
-        }
-        let idx = idx - 1;
-        let directory = &directories[idx];
-
+    let default_id = picker::directory_for_cwd(&directories);
+    if let Some(directory) =
+        picker::pick_directory(t!("directory.delete.prompt"), &directories, default_id)?
+    {
         if Confirm::new()
             .with_prompt(format!(
                 "{} {}",
@@ -307,3 +276,186 @@ async fn delete_directory(db: &DbState) -> Result<()> {
 
     Ok(())
 }
+
+async fn discover_menu(db: &DbState) -> Result<()> {
+    loop {
+        let db_lock = db.lock().await;
+        let roots = db_lock.get_discovery_roots().await?;
+        drop(db_lock);
+
+        println!("\n{}", t!("discovery.title").green().bold());
+        if roots.is_empty() {
+            println!("{}", t!("discovery.no_roots").yellow());
+        } else {
+            println!("{}", t!("discovery.roots"));
+            for root in &roots {
+                println!("  - {}", root.path);
+            }
+        }
+
+        let items = vec![
+            t!("common.back"),
+            t!("discovery.menu.scan"),
+            t!("discovery.menu.add_root"),
+            t!("discovery.menu.remove_root"),
+        ];
+        let selection = match Select::new()
+            .with_prompt(t!("discovery.menu.title"))
+            .items(&items)
+            .default(if roots.is_empty() { 2 } else { 1 })
+            .interact_opt()?
+        {
+            Some(sel) => sel,
+            None => break,
+        };
+
+        match selection {
+            0 => break,
+            1 => {
+                let paths: Vec<String> = roots.into_iter().map(|r| r.path).collect();
+                scan_and_import(db, &paths).await?;
+            }
+            2 => {
+                let path: String = Input::new()
+                    .with_prompt(t!("discovery.prompt_root"))
+                    .default("~/code/*".to_string())
+                    .interact_text()?;
+                if !path.trim().is_empty() {
+                    db.lock().await.add_discovery_root(path.trim()).await?;
+                }
+            }
+            3 => {
+                if roots.is_empty() {
+                    continue;
+                }
+                let labels: Vec<&str> = roots.iter().map(|r| r.path.as_str()).collect();
+                if let Some(index) = Select::new()
+                    .with_prompt(t!("discovery.prompt_remove_root"))
+                    .items(&labels)
+                    .interact_opt()?
+                {
+                    db.lock()
+                        .await
+                        .delete_discovery_root(roots[index].id)
+                        .await?;
+                }
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    Ok(())
+}
+
+async fn scan_and_import(db: &DbState, roots: &[String]) -> Result<()> {
+    if roots.is_empty() {
+        println!("\n{}", t!("discovery.no_roots").yellow());
+        return Ok(());
+    }
+
+    let db_lock = db.lock().await;
+    let projects = discovery::discover_projects(&db_lock, roots).await?;
+    drop(db_lock);
+
+    if projects.is_empty() {
+        println!("\n{}", t!("discovery.no_projects").yellow());
+        return Ok(());
+    }
+
+    let mut table = super::create_table();
+    table.set_header(vec![
+        Cell::new(t!("directory.list.header_name"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("directory.list.header_path"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("discovery.header_status"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("discovery.header_account"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+    ]);
+    for project in &projects {
+        let status = if project.directory_id.is_some() {
+            Cell::new(t!("discovery.status_registered")).fg(Color::DarkGrey)
+        } else {
+            Cell::new(t!("discovery.status_new")).fg(Color::Green)
+        };
+        let account = match (&project.account_name, &project.token) {
+            (Some(name), _) => Cell::new(name),
+            (None, Some(token)) => Cell::new(format!(
+                "{} ({})",
+                t!("discovery.new_token"),
+                drift::mask_secret(token)
+            ))
+            .fg(Color::Yellow),
+            (None, None) => Cell::new("-"),
+        };
+        table.add_row(vec![
+            Cell::new(&project.name),
+            Cell::new(&project.path),
+            status,
+            account,
+        ]);
+    }
+    println!("\n{}", table);
+
+    let candidates: Vec<&DiscoveredProject> = projects
+        .iter()
+        .filter(|p| p.directory_id.is_none() || (p.account_id.is_none() && p.token.is_some()))
+        .collect();
+    if candidates.is_empty() {
+        println!("{}", t!("discovery.nothing_to_import").yellow());
+        return Ok(());
+    }
+
+    let labels: Vec<String> = candidates
+        .iter()
+        .map(|p| format!("{} ({})", p.name, p.path))
+        .collect();
+    let defaults = vec![true; labels.len()];
+    let selected = match MultiSelect::new()
+        .with_prompt(t!("discovery.prompt_select"))
+        .items(&labels)
+        .defaults(&defaults)
+        .interact_opt()?
+    {
+        Some(selected) if !selected.is_empty() => selected,
+        _ => {
+            println!("\n{}", t!("common.cancel").yellow());
+            return Ok(());
+        }
+    };
+    let selected: Vec<DiscoveredProject> = selected
+        .into_iter()
+        .map(|i| candidates[i].clone())
+        .collect();
+
+    let create_accounts = selected
+        .iter()
+        .any(|p| p.account_id.is_none() && p.token.is_some())
+        && Confirm::new()
+            .with_prompt(t!("discovery.prompt_create_accounts"))
+            .default(true)
+            .interact()?;
+
+    let db_lock = db.lock().await;
+    let result = discovery::import_projects(&db_lock, &selected, create_accounts).await?;
+    drop(db_lock);
+
+    println!(
+        "\n{}",
+        t!("discovery.import_success")
+            .replacen("{}", &result.directories_created.to_string(), 1)
+            .replacen("{}", &result.accounts_created.to_string(), 1)
+            .replacen("{}", &result.base_urls_created.to_string(), 1)
+            .green()
+    );
+    for error in &result.errors {
+        println!("{}", error.red());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,221 @@
+use crate::database::Database;
+use crate::migrations::latest_version;
+use crate::{drift, models::*, t, DbState};
+use anyhow::Result;
+use colored::Colorize;
+use comfy_table::{Attribute, Cell, Color};
+use dialoguer::{Confirm, Input};
+
+pub async fn doctor_menu(db: &DbState) -> Result<()> {
+    let db_lock = db.lock().await;
+    let results = drift::scan_drift(&db_lock).await?;
+    drop(db_lock);
+
+    print_drift_report(&results);
+
+    if results.iter().any(|r| !r.issues.is_empty())
+        && Confirm::new()
+            .with_prompt(t!("doctor.prompt_fix_all"))
+            .default(false)
+            .interact()?
+    {
+        fix_all(db).await?;
+    }
+
+    let _ = Input::<String>::new()
+        .with_prompt(t!("common.continue"))
+        .allow_empty(true)
+        .interact()?;
+
+    Ok(())
+}
+
+/// 命令行 `claude-config doctor drift [--fix]`，存在未修复的漂移时返回 true
+pub async fn drift_command(db: &DbState, fix: bool) -> Result<bool> {
+    let db_lock = db.lock().await;
+    let results = drift::scan_drift(&db_lock).await?;
+    drop(db_lock);
+
+    print_drift_report(&results);
+
+    let drifted = results.iter().any(|r| !r.issues.is_empty());
+    if drifted && fix {
+        return fix_all(db).await;
+    }
+
+    Ok(drifted)
+}
+
+/// claude-config migrate status：列出所有迁移及执行时间
+pub async fn migration_status_command(db: &Database) -> Result<()> {
+    let status = db.migration_status().await?;
+    let current = status
+        .iter()
+        .filter(|s| s.applied_at.is_some())
+        .map(|s| s.version)
+        .max()
+        .unwrap_or(0);
+
+    println!("\n{}", t!("migrate.title").green().bold());
+    println!(
+        "{}",
+        t!("migrate.version")
+            .replacen("{}", &current.to_string(), 1)
+            .replacen("{}", &latest_version().to_string(), 1)
+    );
+
+    let mut table = super::create_table();
+    table.set_header(vec![
+        Cell::new(t!("migrate.header_version"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("migrate.header_name"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("migrate.header_applied_at"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+    ]);
+
+    for migration in &status {
+        let applied = match migration.applied_at {
+            Some(time) if migration.known => {
+                Cell::new(time.format("%Y-%m-%d %H:%M:%S").to_string()).fg(Color::Green)
+            }
+            Some(time) => Cell::new(format!(
+                "{} ({})",
+                time.format("%Y-%m-%d %H:%M:%S"),
+                t!("migrate.status_unknown")
+            ))
+            .fg(Color::Red),
+            None => Cell::new(t!("migrate.status_pending")).fg(Color::Yellow),
+        };
+        table.add_row(vec![
+            Cell::new(migration.version),
+            Cell::new(&migration.name),
+            applied,
+        ]);
+    }
+    println!("{}", table);
+
+    if current > latest_version() {
+        println!("\n{}", t!("migrate.too_new").red());
+    } else if status.iter().any(|s| s.applied_at.is_none()) {
+        println!("\n{}", t!("migrate.pending_hint").yellow());
+    }
+
+    Ok(())
+}
+
+/// 修复所有漂移，存在修复失败的目录时返回 true
+async fn fix_all(db: &DbState) -> Result<bool> {
+    let db_lock = db.lock().await;
+    let (fixed, failures) = drift::fix_all_drift(&db_lock).await?;
+    drop(db_lock);
+
+    println!(
+        "\n{}",
+        t!("doctor.fix_success")
+            .replace("{}", &fixed.to_string())
+            .green()
+    );
+    for (name, error) in &failures {
+        println!(
+            "{}",
+            t!("doctor.fix_error")
+                .replacen("{}", name, 1)
+                .replacen("{}", error, 1)
+                .red()
+        );
+    }
+
+    Ok(!failures.is_empty())
+}
+
+fn issue_label(kind: &str) -> &'static str {
+    match kind {
+        drift::DRIFT_MISSING_DIRECTORY => t!("doctor.issue.missing_directory"),
+        drift::DRIFT_MISSING_SETTINGS => t!("doctor.issue.missing_settings"),
+        drift::DRIFT_INVALID_SETTINGS => t!("doctor.issue.invalid_settings"),
+        drift::DRIFT_STALE_TOKEN => t!("doctor.issue.stale_token"),
+        drift::DRIFT_ENV_CHANGED => t!("doctor.issue.env_changed"),
+        _ => t!("doctor.issue.missing_asset"),
+    }
+}
+
+fn describe_issue(issue: &DriftIssue) -> String {
+    let mut text = issue_label(&issue.kind).to_string();
+    if let Some(key) = &issue.key {
+        text.push_str(&format!(" {}", key));
+    }
+    if issue.kind == drift::DRIFT_STALE_TOKEN || issue.kind == drift::DRIFT_ENV_CHANGED {
+        text.push_str(&format!(
+            " ({} → {})",
+            issue.actual.as_deref().unwrap_or("-"),
+            issue.expected.as_deref().unwrap_or("-")
+        ));
+    }
+    text
+}
+
+fn print_drift_report(results: &[DirectoryDrift]) {
+    println!("\n{}", t!("doctor.title").green().bold());
+
+    if results.is_empty() {
+        println!("\n{}", t!("directory.list.no_records").yellow());
+        return;
+    }
+
+    let mut table = super::create_table();
+    table.set_header(vec![
+        Cell::new(t!("doctor.header_directory"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("doctor.header_account"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("doctor.header_status"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("doctor.header_details"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+    ]);
+
+    for result in results {
+        let status = if result.account_id.is_none() {
+            Cell::new(t!("doctor.status_unbound")).fg(Color::DarkGrey)
+        } else if result.issues.is_empty() {
+            Cell::new(t!("doctor.status_ok")).fg(Color::Green)
+        } else {
+            Cell::new(t!("doctor.status_drifted").replace("{}", &result.issues.len().to_string()))
+                .fg(Color::Yellow)
+        };
+
+        let details: Vec<String> = result.issues.iter().map(describe_issue).collect();
+
+        table.add_row(vec![
+            Cell::new(format!(
+                "{}\n{}",
+                result.directory_name, result.directory_path
+            )),
+            Cell::new(result.account_name.clone().unwrap_or_default()),
+            status,
+            Cell::new(details.join("\n")),
+        ]);
+    }
+
+    println!("\n{}", table);
+
+    let drifted = results.iter().filter(|r| !r.issues.is_empty()).count();
+    if drifted == 0 {
+        println!("{}", t!("doctor.summary_ok").green());
+    } else {
+        println!(
+            "{}",
+            t!("doctor.summary_drifted")
+                .replace("{}", &drifted.to_string())
+                .yellow()
+        );
+    }
+}
@@ -0,0 +1,419 @@
+use crate::hooks::{hook_event_supports_matcher, validate_hook, HOOK_EVENTS};
+use crate::{models::*, t, DbState};
+use anyhow::Result;
+use colored::Colorize;
+use comfy_table::{Attribute, Cell, Color};
+use dialoguer::{Confirm, Input, MultiSelect, Select};
+
+pub async fn hooks_menu(db: &DbState) -> Result<()> {
+    let mut last_selection = 0;
+
+    loop {
+        let items = vec![
+            t!("common.back"),
+            t!("hooks.menu.list"),
+            t!("hooks.menu.add"),
+            t!("hooks.menu.edit"),
+            t!("hooks.menu.delete"),
+            t!("hooks.menu.assign_directory"),
+        ];
+
+        let selection = match Select::new()
+            .with_prompt(format!(
+                "\n{} (ESC {})",
+                t!("hooks.menu.title"),
+                t!("common.to_back")
+            ))
+            .items(&items)
+            .default(last_selection)
+            .interact_opt()?
+        {
+            Some(sel) => sel,
+            None => break, // 用户按了ESC，返回上一级
+        };
+
+        last_selection = selection;
+
+        match selection {
+            0 => break,
+            1 => list_hooks(db).await?,
+            2 => add_hook(db).await?,
+            3 => edit_hook(db).await?,
+            4 => delete_hook(db).await?,
+            5 => assign_to_directory(db).await?,
+            _ => unreachable!(),
+        }
+    }
+
+    Ok(())
+}
+
+async fn list_hooks(db: &DbState) -> Result<()> {
+    let db_lock = db.lock().await;
+    let hooks = db_lock.get_hooks().await?;
+    drop(db_lock);
+
+    if hooks.is_empty() {
+        println!("\n{}", t!("hooks.list.no_records").yellow());
+        return Ok(());
+    }
+
+    let mut table = super::create_table();
+    table.set_header(vec![
+        Cell::new(t!("hooks.list.header_id"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("hooks.list.header_name"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("hooks.list.header_event"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("hooks.list.header_matcher"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("hooks.list.header_command"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("hooks.list.header_timeout"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("hooks.list.header_global"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+    ]);
+
+    for hook in &hooks {
+        table.add_row(vec![
+            hook.id.to_string(),
+            hook.name.clone(),
+            hook.event.clone(),
+            hook.matcher.clone().unwrap_or_default(),
+            hook.command.clone(),
+            hook.timeout.map(|t| t.to_string()).unwrap_or_default(),
+            if hook.is_global {
+                t!("url.list.default_yes").to_string()
+            } else {
+                String::new()
+            },
+        ]);
+    }
+
+    println!("\n{}", table);
+    println!(
+        "{}",
+        t!("hooks.list.total").replace("{}", &hooks.len().to_string())
+    );
+
+    let _ = Input::<String>::new()
+        .with_prompt(t!("common.continue"))
+        .allow_empty(true)
+        .interact()?;
+
+    Ok(())
+}
+
+/// 交互式输入 Hook 字段，返回 (event, matcher, command, timeout, is_global)
+#[allow(clippy::type_complexity)]
+fn prompt_hook_fields(
+    existing: Option<&Hook>,
+) -> Result<(String, Option<String>, String, Option<i64>, bool)> {
+    let default_event = existing
+        .and_then(|h| HOOK_EVENTS.iter().position(|e| *e == h.event))
+        .unwrap_or(0);
+    let event_idx = Select::new()
+        .with_prompt(t!("hooks.add.prompt_event"))
+        .items(&HOOK_EVENTS)
+        .default(default_event)
+        .interact()?;
+    let event = HOOK_EVENTS[event_idx].to_string();
+
+    let matcher = if hook_event_supports_matcher(&event) {
+        let matcher: String = Input::new()
+            .with_prompt(t!("hooks.add.prompt_matcher"))
+            .with_initial_text(existing.and_then(|h| h.matcher.clone()).unwrap_or_default())
+            .allow_empty(true)
+            .interact_text()?;
+        Some(matcher.trim().to_string()).filter(|m| !m.is_empty())
+    } else {
+        None
+    };
+
+    let command: String = Input::new()
+        .with_prompt(t!("hooks.add.prompt_command"))
+        .with_initial_text(existing.map(|h| h.command.clone()).unwrap_or_default())
+        .allow_empty(true)
+        .interact_text()?;
+
+    let timeout: String = Input::new()
+        .with_prompt(t!("hooks.add.prompt_timeout"))
+        .with_initial_text(
+            existing
+                .and_then(|h| h.timeout)
+                .map(|t| t.to_string())
+                .unwrap_or_default(),
+        )
+        .allow_empty(true)
+        .validate_with(|input: &String| -> Result<(), &str> {
+            if input.trim().is_empty() || input.trim().parse::<i64>().is_ok() {
+                Ok(())
+            } else {
+                Err(t!("hooks.add.invalid_timeout"))
+            }
+        })
+        .interact_text()?;
+
+    let is_global = Confirm::new()
+        .with_prompt(t!("hooks.add.prompt_global"))
+        .default(existing.map(|h| h.is_global).unwrap_or(false))
+        .interact()?;
+
+    Ok((
+        event,
+        matcher,
+        command.trim().to_string(),
+        timeout.trim().parse::<i64>().ok(),
+        is_global,
+    ))
+}
+
+async fn add_hook(db: &DbState) -> Result<()> {
+    println!("\n{}", t!("hooks.add.title").green().bold());
+    println!("{}", t!("common.input_cancel_hint").yellow());
+
+    let name: String = Input::new()
+        .with_prompt(t!("hooks.add.prompt_name"))
+        .allow_empty(true)
+        .interact_text()?;
+
+    if name.trim().is_empty() || name.trim().eq_ignore_ascii_case("q") {
+        println!("\n{}", t!("common.cancel").yellow());
+        return Ok(());
+    }
+
+    let (event, matcher, command, timeout, is_global) = prompt_hook_fields(None)?;
+
+    if let Err(e) = validate_hook(&event, &command, timeout) {
+        println!("\n{}", t!("hooks.add.error").replace("{}", &e).red());
+        return Ok(());
+    }
+
+    let description: String = Input::new()
+        .with_prompt(t!("hooks.add.prompt_description"))
+        .allow_empty(true)
+        .interact_text()?;
+
+    let db_lock = db.lock().await;
+    let request = CreateHookRequest {
+        name: name.trim().to_string(),
+        event,
+        matcher,
+        command,
+        timeout,
+        is_global: Some(is_global),
+        description: if description.is_empty() {
+            None
+        } else {
+            Some(description)
+        },
+    };
+
+    match db_lock.create_hook(request).await {
+        Ok(_) => {
+            println!(
+                "\n{}",
+                t!("hooks.add.success").replace("{}", name.trim()).green()
+            );
+        }
+        Err(e) => {
+            println!(
+                "\n{}",
+                t!("hooks.add.error").replace("{}", &e.to_string()).red()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+async fn select_hook(db: &DbState, prompt: &str) -> Result<Option<Hook>> {
+    let db_lock = db.lock().await;
+    let hooks = db_lock.get_hooks().await?;
+    drop(db_lock);
+
+    if hooks.is_empty() {
+        println!("\n{}", t!("hooks.list.no_records").yellow());
+        return Ok(None);
+    }
+
+    let mut items: Vec<String> = vec![t!("common.cancel").to_string()];
+    items.extend(
+        hooks
+            .iter()
+            .map(|h| format!("{} [{}] - {}", h.name, h.event, h.command)),
+    );
+
+    let selection = Select::new()
+        .with_prompt(prompt)
+        .items(&items)
+        .interact_opt()?;
+
+    match selection {
+        Some(idx) if idx > 0 => Ok(hooks.into_iter().nth(idx - 1)),
+        _ => Ok(None),
+    }
+}
+
+async fn edit_hook(db: &DbState) -> Result<()> {
+    let hook = match select_hook(db, t!("hooks.edit.prompt")).await? {
+        Some(hook) => hook,
+        None => return Ok(()),
+    };
+
+    println!("{}", t!("common.input_cancel_hint").yellow());
+
+    let name: String = Input::new()
+        .with_prompt(t!("hooks.add.prompt_name"))
+        .default(hook.name.clone())
+        .allow_empty(true)
+        .interact_text()?;
+
+    let name = if name.trim().is_empty() {
+        hook.name.clone()
+    } else {
+        name.trim().to_string()
+    };
+
+    let (event, matcher, command, timeout, is_global) = prompt_hook_fields(Some(&hook))?;
+
+    if let Err(e) = validate_hook(&event, &command, timeout) {
+        println!("\n{}", t!("hooks.edit.error").replace("{}", &e).red());
+        return Ok(());
+    }
+
+    let description: String = Input::new()
+        .with_prompt(t!("hooks.add.prompt_description"))
+        .default(hook.description.clone().unwrap_or_default())
+        .allow_empty(true)
+        .interact_text()?;
+
+    let db_lock = db.lock().await;
+    let request = UpdateHookRequest {
+        name: Some(name),
+        event: Some(event),
+        matcher: Some(matcher.unwrap_or_default()),
+        command: Some(command),
+        timeout: Some(timeout.unwrap_or(0)),
+        is_global: Some(is_global),
+        description: Some(description),
+    };
+
+    match db_lock.update_hook(hook.id, request).await {
+        Ok(_) => {
+            println!("\n{}", t!("hooks.edit.success").green());
+        }
+        Err(e) => {
+            println!(
+                "\n{}",
+                t!("hooks.edit.error").replace("{}", &e.to_string()).red()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+async fn delete_hook(db: &DbState) -> Result<()> {
+    let hook = match select_hook(db, t!("hooks.delete.prompt")).await? {
+        Some(hook) => hook,
+        None => return Ok(()),
+    };
+
+    if Confirm::new()
+        .with_prompt(t!("hooks.delete.confirm").replace("{}", &hook.name))
+        .default(false)
+        .interact()?
+    {
+        let db_lock = db.lock().await;
+        match db_lock.delete_hook(hook.id).await {
+            Ok(_) => {
+                println!("\n{}", t!("hooks.delete.success").green());
+            }
+            Err(e) => {
+                println!(
+                    "\n{}",
+                    t!("hooks.delete.error").replace("{}", &e.to_string()).red()
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn assign_to_directory(db: &DbState) -> Result<()> {
+    let db_lock = db.lock().await;
+    let hooks = db_lock.get_hooks().await?;
+    let directories = db_lock.get_directories().await?;
+    drop(db_lock);
+
+    // 全局 Hook 总是会写入，无需分配
+    let hooks: Vec<Hook> = hooks.into_iter().filter(|h| !h.is_global).collect();
+
+    if hooks.is_empty() {
+        println!("\n{}", t!("hooks.assign.no_local_hooks").yellow());
+        return Ok(());
+    }
+    if directories.is_empty() {
+        println!("\n{}", t!("switch.no_directories").yellow());
+        return Ok(());
+    }
+
+    let mut items: Vec<String> = vec![t!("common.cancel").to_string()];
+    items.extend(
+        directories
+            .iter()
+            .map(|d| format!("{} - {}", d.name, d.path)),
+    );
+
+    let selection = Select::new()
+        .with_prompt(t!("switch.select_directory"))
+        .items(&items)
+        .interact_opt()?;
+
+    let directory = match selection {
+        Some(idx) if idx > 0 => &directories[idx - 1],
+        _ => return Ok(()),
+    };
+
+    let db_lock = db.lock().await;
+    let selected_ids = db_lock.get_directory_hook_ids(directory.id).await?;
+    drop(db_lock);
+
+    let hook_items: Vec<String> = hooks
+        .iter()
+        .map(|h| format!("{} [{}]", h.name, h.event))
+        .collect();
+    let defaults: Vec<bool> = hooks.iter().map(|h| selected_ids.contains(&h.id)).collect();
+
+    let hook_ids: Vec<i64> = match MultiSelect::new()
+        .with_prompt(t!("hooks.assign.prompt_hooks"))
+        .items(&hook_items)
+        .defaults(&defaults)
+        .interact_opt()?
+    {
+        Some(indices) => indices.into_iter().map(|i| hooks[i].id).collect(),
+        None => return Ok(()),
+    };
+
+    let db_lock = db.lock().await;
+    match db_lock.set_directory_hooks(directory.id, &hook_ids).await {
+        Ok(_) => println!("\n{}", t!("hooks.assign.success").green()),
+        Err(e) => println!(
+            "\n{}",
+            t!("hooks.assign.error").replace("{}", &e.to_string()).red()
+        ),
+    }
+
+    Ok(())
+}
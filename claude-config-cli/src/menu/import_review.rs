@@ -0,0 +1,179 @@
+//! 导入前的比对结果展示和冲突处理策略选择，文件导入和 WebDAV 下载共用
+use crate::import_engine::{self as engine, STRATEGIES};
+use crate::{models::*, t, DbState};
+use anyhow::Result;
+use colored::Colorize;
+use comfy_table::{Attribute, Cell, Color};
+use dialoguer::{Confirm, Select};
+
+fn kind_label(kind: &str) -> &'static str {
+    match kind {
+        engine::KIND_ACCOUNT => t!("import.kind.account"),
+        engine::KIND_BASE_URL => t!("import.kind.base_url"),
+        engine::KIND_DIRECTORY => t!("import.kind.directory"),
+        _ => t!("import.kind.binding"),
+    }
+}
+
+fn status_cell(status: &str) -> Cell {
+    match status {
+        engine::STATUS_NEW => Cell::new(t!("import.status.new")).fg(Color::Green),
+        engine::STATUS_CHANGED => Cell::new(t!("import.status.changed")).fg(Color::Yellow),
+        engine::STATUS_DUPLICATE => Cell::new(t!("import.status.duplicate")).fg(Color::DarkGrey),
+        _ => Cell::new(t!("import.status.identical")).fg(Color::DarkGrey),
+    }
+}
+
+fn strategy_label(strategy: &str) -> &'static str {
+    match strategy {
+        engine::STRATEGY_OVERWRITE => t!("import.strategy.overwrite"),
+        engine::STRATEGY_RENAME => t!("import.strategy.rename"),
+        engine::STRATEGY_MERGE => t!("import.strategy.merge"),
+        _ => t!("import.strategy.skip"),
+    }
+}
+
+fn action_cell(action: &str) -> Cell {
+    match action {
+        engine::ACTION_CREATED => Cell::new(t!("import.action.created")).fg(Color::Green),
+        engine::ACTION_RENAMED => Cell::new(t!("import.action.renamed")).fg(Color::Green),
+        engine::ACTION_OVERWRITTEN => Cell::new(t!("import.action.overwritten")).fg(Color::Cyan),
+        engine::ACTION_MERGED => Cell::new(t!("import.action.merged")).fg(Color::Cyan),
+        engine::ACTION_FAILED => Cell::new(t!("import.action.failed")).fg(Color::Red),
+        engine::ACTION_SKIPPED => Cell::new(t!("import.action.skipped")).fg(Color::Yellow),
+        _ => Cell::new(t!("import.action.unchanged")).fg(Color::DarkGrey),
+    }
+}
+
+fn diff_text(item: &ImportItem) -> String {
+    item.diffs
+        .iter()
+        .map(|d| format!("{}: {} → {}", d.field, d.current, d.incoming))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn header(labels: &[&str]) -> Vec<Cell> {
+    labels
+        .iter()
+        .map(|label| {
+            Cell::new(label)
+                .add_attribute(Attribute::Bold)
+                .fg(Color::Cyan)
+        })
+        .collect()
+}
+
+/// 显示比对结果，选择有差异项的处理策略后写入数据库，用户取消或没有可导入的内容时返回 None
+pub async fn review_and_apply(
+    db: &DbState,
+    bundle: &ImportBundle,
+    mut items: Vec<ImportItem>,
+) -> Result<Option<ImportReport>> {
+    let mut table = super::create_table();
+    table.set_header(header(&[
+        t!("import.header_kind"),
+        t!("import.header_name"),
+        t!("import.header_status"),
+        t!("import.header_diff"),
+    ]));
+    for item in &items {
+        table.add_row(vec![
+            Cell::new(kind_label(&item.kind)),
+            Cell::new(&item.name),
+            status_cell(&item.status),
+            Cell::new(diff_text(item)),
+        ]);
+    }
+    println!("{}", table);
+
+    let changed: Vec<usize> = items
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| item.status == engine::STATUS_CHANGED)
+        .map(|(idx, _)| idx)
+        .collect();
+    let has_new = items.iter().any(|item| item.status == engine::STATUS_NEW);
+    if changed.is_empty() && !has_new && bundle.bindings.is_empty() {
+        println!("\n{}", t!("import.nothing_to_do").yellow());
+        return Ok(None);
+    }
+
+    let mut strategy = engine::STRATEGY_SKIP;
+    if !changed.is_empty() {
+        let mut options: Vec<&str> = STRATEGIES.iter().map(|s| strategy_label(s)).collect();
+        options.push(t!("import.strategy.per_item"));
+        let selection = Select::new()
+            .with_prompt(t!("import.strategy.prompt").replace("{}", &changed.len().to_string()))
+            .items(&options)
+            .default(0)
+            .interact_opt()?;
+        match selection {
+            Some(idx) if idx < STRATEGIES.len() => strategy = STRATEGIES[idx],
+            Some(_) => {
+                let labels: Vec<&str> = STRATEGIES.iter().map(|s| strategy_label(s)).collect();
+                for idx in changed {
+                    let item = &mut items[idx];
+                    println!(
+                        "\n{} {}\n{}",
+                        kind_label(&item.kind).cyan(),
+                        item.name.bold(),
+                        diff_text(item)
+                    );
+                    match Select::new()
+                        .with_prompt(t!("import.strategy.item_prompt").replace("{}", &item.name))
+                        .items(&labels)
+                        .default(0)
+                        .interact_opt()?
+                    {
+                        Some(choice) => item.strategy = Some(STRATEGIES[choice].to_string()),
+                        None => return Ok(None),
+                    }
+                }
+            }
+            None => return Ok(None),
+        }
+    }
+
+    if !Confirm::new()
+        .with_prompt(t!("import.confirm"))
+        .default(true)
+        .interact()?
+    {
+        println!("\n{}", t!("common.cancel").yellow());
+        return Ok(None);
+    }
+
+    let db_lock = db.lock().await;
+    let report = engine::apply(&db_lock, bundle, &items, strategy).await?;
+    Ok(Some(report))
+}
+
+/// 输出每个导入项的处理结果
+pub fn print_report(report: &ImportReport) {
+    let mut table = super::create_table();
+    table.set_header(header(&[
+        t!("import.header_kind"),
+        t!("import.header_name"),
+        t!("import.header_result"),
+        t!("import.header_detail"),
+    ]));
+    for item in &report.items {
+        table.add_row(vec![
+            Cell::new(kind_label(&item.kind)),
+            Cell::new(&item.name),
+            action_cell(&item.action),
+            Cell::new(item.detail.as_deref().unwrap_or("")),
+        ]);
+    }
+    println!("\n{}", table);
+    println!(
+        "{}",
+        t!("import.summary")
+            .replace("{created}", &report.created.to_string())
+            .replace("{updated}", &report.updated.to_string())
+            .replace("{skipped}", &report.skipped.to_string())
+            .replace("{failed}", &report.failed.to_string())
+            .green()
+    );
+}
@@ -0,0 +1,522 @@
+use crate::mcp::{validate_mcp_server, MCP_TRANSPORTS};
+use crate::{models::*, t, DbState};
+use anyhow::Result;
+use colored::Colorize;
+use comfy_table::{Attribute, Cell, Color};
+use dialoguer::{Confirm, Input, MultiSelect, Select};
+
+pub async fn mcp_menu(db: &DbState) -> Result<()> {
+    let mut last_selection = 0;
+
+    loop {
+        let items = vec![
+            t!("common.back"),
+            t!("mcp.menu.list"),
+            t!("mcp.menu.add"),
+            t!("mcp.menu.edit"),
+            t!("mcp.menu.delete"),
+            t!("mcp.menu.assign_directory"),
+            t!("mcp.menu.assign_account"),
+        ];
+
+        let selection = match Select::new()
+            .with_prompt(format!(
+                "\n{} (ESC {})",
+                t!("mcp.menu.title"),
+                t!("common.to_back")
+            ))
+            .items(&items)
+            .default(last_selection)
+            .interact_opt()?
+        {
+            Some(sel) => sel,
+            None => break, // 用户按了ESC，返回上一级
+        };
+
+        last_selection = selection;
+
+        match selection {
+            0 => break,
+            1 => list_mcp_servers(db).await?,
+            2 => add_mcp_server(db).await?,
+            3 => edit_mcp_server(db).await?,
+            4 => delete_mcp_server(db).await?,
+            5 => assign_to_directory(db).await?,
+            6 => assign_to_account(db).await?,
+            _ => unreachable!(),
+        }
+    }
+
+    Ok(())
+}
+
+fn format_target(server: &McpServer) -> String {
+    if server.transport == "stdio" {
+        let args: Vec<String> = serde_json::from_str(&server.args).unwrap_or_default();
+        let mut target = server.command.clone().unwrap_or_default();
+        if !args.is_empty() {
+            target.push(' ');
+            target.push_str(&args.join(" "));
+        }
+        target
+    } else {
+        server.url.clone().unwrap_or_default()
+    }
+}
+
+async fn list_mcp_servers(db: &DbState) -> Result<()> {
+    let db_lock = db.lock().await;
+    let servers = db_lock.get_mcp_servers().await?;
+    drop(db_lock);
+
+    if servers.is_empty() {
+        println!("\n{}", t!("mcp.list.no_records").yellow());
+        return Ok(());
+    }
+
+    let mut table = super::create_table();
+    table.set_header(vec![
+        Cell::new(t!("mcp.list.header_id"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("mcp.list.header_name"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("mcp.list.header_transport"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("mcp.list.header_target"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("mcp.list.header_description"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+    ]);
+
+    for server in &servers {
+        table.add_row(vec![
+            server.id.to_string(),
+            server.name.clone(),
+            server.transport.clone(),
+            format_target(server),
+            server.description.clone().unwrap_or_default(),
+        ]);
+    }
+
+    println!("\n{}", table);
+    println!(
+        "{}",
+        t!("mcp.list.total").replace("{}", &servers.len().to_string())
+    );
+
+    let _ = Input::<String>::new()
+        .with_prompt(t!("common.continue"))
+        .allow_empty(true)
+        .interact()?;
+
+    Ok(())
+}
+
+/// 交互式输入传输方式相关的字段，返回 (transport, command, args, env, url)
+#[allow(clippy::type_complexity)]
+fn prompt_transport_fields(
+    existing: Option<&McpServer>,
+) -> Result<(
+    String,
+    Option<String>,
+    Vec<String>,
+    serde_json::Value,
+    Option<String>,
+)> {
+    let default_transport = existing
+        .and_then(|s| MCP_TRANSPORTS.iter().position(|t| *t == s.transport))
+        .unwrap_or(0);
+    let transport_idx = Select::new()
+        .with_prompt(t!("mcp.add.prompt_transport"))
+        .items(&MCP_TRANSPORTS)
+        .default(default_transport)
+        .interact()?;
+    let transport = MCP_TRANSPORTS[transport_idx].to_string();
+
+    if transport == "stdio" {
+        let command: String = Input::new()
+            .with_prompt(t!("mcp.add.prompt_command"))
+            .with_initial_text(existing.and_then(|s| s.command.clone()).unwrap_or_default())
+            .allow_empty(true)
+            .interact_text()?;
+
+        let existing_args: Vec<String> = existing
+            .map(|s| serde_json::from_str(&s.args).unwrap_or_default())
+            .unwrap_or_default();
+        let args: String = Input::new()
+            .with_prompt(t!("mcp.add.prompt_args"))
+            .with_initial_text(existing_args.join(" "))
+            .allow_empty(true)
+            .interact_text()?;
+
+        let existing_env = existing
+            .and_then(|s| serde_json::from_str::<serde_json::Value>(&s.env).ok())
+            .and_then(|v| v.as_object().cloned())
+            .map(|obj| {
+                obj.iter()
+                    .map(|(k, v)| format!("{}={}", k, v.as_str().unwrap_or_default()))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            })
+            .unwrap_or_default();
+        let env_input: String = Input::new()
+            .with_prompt(t!("mcp.add.prompt_env"))
+            .with_initial_text(existing_env)
+            .allow_empty(true)
+            .interact_text()?;
+
+        let mut env = serde_json::Map::new();
+        for pair in env_input.split(',') {
+            if let Some((key, value)) = pair.split_once('=') {
+                if !key.trim().is_empty() {
+                    env.insert(
+                        key.trim().to_string(),
+                        serde_json::Value::String(value.trim().to_string()),
+                    );
+                }
+            }
+        }
+
+        Ok((
+            transport,
+            Some(command.trim().to_string()).filter(|c| !c.is_empty()),
+            args.split_whitespace().map(|a| a.to_string()).collect(),
+            serde_json::Value::Object(env),
+            None,
+        ))
+    } else {
+        let url: String = Input::new()
+            .with_prompt(t!("mcp.add.prompt_url"))
+            .with_initial_text(existing.and_then(|s| s.url.clone()).unwrap_or_default())
+            .allow_empty(true)
+            .interact_text()?;
+
+        Ok((
+            transport,
+            None,
+            Vec::new(),
+            serde_json::json!({}),
+            Some(url.trim().to_string()).filter(|u| !u.is_empty()),
+        ))
+    }
+}
+
+async fn add_mcp_server(db: &DbState) -> Result<()> {
+    println!("\n{}", t!("mcp.add.title").green().bold());
+    println!("{}", t!("common.input_cancel_hint").yellow());
+
+    let name: String = Input::new()
+        .with_prompt(t!("mcp.add.prompt_name"))
+        .allow_empty(true)
+        .interact_text()?;
+
+    if name.trim().is_empty() || name.trim().eq_ignore_ascii_case("q") {
+        println!("\n{}", t!("common.cancel").yellow());
+        return Ok(());
+    }
+
+    let (transport, command, args, env, url) = prompt_transport_fields(None)?;
+
+    if let Err(e) = validate_mcp_server(&transport, command.as_deref(), url.as_deref()) {
+        println!("\n{}", t!("mcp.add.error").replace("{}", &e).red());
+        return Ok(());
+    }
+
+    let description: String = Input::new()
+        .with_prompt(t!("mcp.add.prompt_description"))
+        .allow_empty(true)
+        .interact_text()?;
+
+    let db_lock = db.lock().await;
+    let request = CreateMcpServerRequest {
+        name: name.trim().to_string(),
+        transport,
+        command,
+        args: Some(args),
+        env: Some(env),
+        url,
+        description: if description.is_empty() {
+            None
+        } else {
+            Some(description)
+        },
+    };
+
+    match db_lock.create_mcp_server(request).await {
+        Ok(_) => {
+            println!(
+                "\n{}",
+                t!("mcp.add.success").replace("{}", name.trim()).green()
+            );
+        }
+        Err(e) => {
+            println!(
+                "\n{}",
+                t!("mcp.add.error").replace("{}", &e.to_string()).red()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+async fn select_mcp_server(db: &DbState, prompt: &str) -> Result<Option<McpServer>> {
+    let db_lock = db.lock().await;
+    let servers = db_lock.get_mcp_servers().await?;
+    drop(db_lock);
+
+    if servers.is_empty() {
+        println!("\n{}", t!("mcp.list.no_records").yellow());
+        return Ok(None);
+    }
+
+    let mut items: Vec<String> = vec![t!("common.cancel").to_string()];
+    items.extend(
+        servers
+            .iter()
+            .map(|s| format!("{} ({}) - {}", s.name, s.transport, format_target(s))),
+    );
+
+    let selection = Select::new()
+        .with_prompt(prompt)
+        .items(&items)
+        .interact_opt()?;
+
+    match selection {
+        Some(idx) if idx > 0 => Ok(servers.into_iter().nth(idx - 1)),
+        _ => Ok(None),
+    }
+}
+
+async fn edit_mcp_server(db: &DbState) -> Result<()> {
+    let server = match select_mcp_server(db, t!("mcp.edit.prompt")).await? {
+        Some(server) => server,
+        None => return Ok(()),
+    };
+
+    println!("{}", t!("common.input_cancel_hint").yellow());
+
+    let name: String = Input::new()
+        .with_prompt(t!("mcp.add.prompt_name"))
+        .default(server.name.clone())
+        .allow_empty(true)
+        .interact_text()?;
+
+    let name = if name.trim().is_empty() {
+        server.name.clone()
+    } else {
+        name.trim().to_string()
+    };
+
+    let (transport, command, args, env, url) = prompt_transport_fields(Some(&server))?;
+
+    if let Err(e) = validate_mcp_server(&transport, command.as_deref(), url.as_deref()) {
+        println!("\n{}", t!("mcp.edit.error").replace("{}", &e).red());
+        return Ok(());
+    }
+
+    let description: String = Input::new()
+        .with_prompt(t!("mcp.add.prompt_description"))
+        .default(server.description.clone().unwrap_or_default())
+        .allow_empty(true)
+        .interact_text()?;
+
+    let db_lock = db.lock().await;
+    let request = UpdateMcpServerRequest {
+        name: Some(name),
+        transport: Some(transport),
+        command: Some(command.unwrap_or_default()),
+        args: Some(args),
+        env: Some(env),
+        url: Some(url.unwrap_or_default()),
+        description: Some(description),
+    };
+
+    match db_lock.update_mcp_server(server.id, request).await {
+        Ok(_) => {
+            println!("\n{}", t!("mcp.edit.success").green());
+        }
+        Err(e) => {
+            println!(
+                "\n{}",
+                t!("mcp.edit.error").replace("{}", &e.to_string()).red()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+async fn delete_mcp_server(db: &DbState) -> Result<()> {
+    let server = match select_mcp_server(db, t!("mcp.delete.prompt")).await? {
+        Some(server) => server,
+        None => return Ok(()),
+    };
+
+    if Confirm::new()
+        .with_prompt(t!("mcp.delete.confirm").replace("{}", &server.name))
+        .default(false)
+        .interact()?
+    {
+        let db_lock = db.lock().await;
+        match db_lock.delete_mcp_server(server.id).await {
+            Ok(_) => {
+                println!("\n{}", t!("mcp.delete.success").green());
+            }
+            Err(e) => {
+                println!(
+                    "\n{}",
+                    t!("mcp.delete.error").replace("{}", &e.to_string()).red()
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// 多选 MCP 服务器，返回选中的 ID 列表（ESC 返回 None）
+fn pick_mcp_servers(servers: &[McpServer], selected_ids: &[i64]) -> Result<Option<Vec<i64>>> {
+    let items: Vec<String> = servers
+        .iter()
+        .map(|s| format!("{} ({})", s.name, s.transport))
+        .collect();
+    let defaults: Vec<bool> = servers
+        .iter()
+        .map(|s| selected_ids.contains(&s.id))
+        .collect();
+
+    let selection = MultiSelect::new()
+        .with_prompt(t!("mcp.assign.prompt_servers"))
+        .items(&items)
+        .defaults(&defaults)
+        .interact_opt()?;
+
+    Ok(selection.map(|indices| indices.into_iter().map(|i| servers[i].id).collect()))
+}
+
+async fn assign_to_directory(db: &DbState) -> Result<()> {
+    let db_lock = db.lock().await;
+    let servers = db_lock.get_mcp_servers().await?;
+    let directories = db_lock.get_directories().await?;
+    drop(db_lock);
+
+    if servers.is_empty() {
+        println!("\n{}", t!("mcp.list.no_records").yellow());
+        return Ok(());
+    }
+    if directories.is_empty() {
+        println!("\n{}", t!("switch.no_directories").yellow());
+        return Ok(());
+    }
+
+    let mut items: Vec<String> = vec![t!("common.cancel").to_string()];
+    items.extend(
+        directories
+            .iter()
+            .map(|d| format!("{} - {}", d.name, d.path)),
+    );
+
+    let selection = Select::new()
+        .with_prompt(t!("switch.select_directory"))
+        .items(&items)
+        .interact_opt()?;
+
+    let directory = match selection {
+        Some(idx) if idx > 0 => &directories[idx - 1],
+        _ => return Ok(()),
+    };
+
+    let db_lock = db.lock().await;
+    let selected_ids = db_lock.get_directory_mcp_server_ids(directory.id).await?;
+    drop(db_lock);
+
+    let server_ids = match pick_mcp_servers(&servers, &selected_ids)? {
+        Some(ids) => ids,
+        None => return Ok(()),
+    };
+
+    let db_lock = db.lock().await;
+    match db_lock
+        .set_directory_mcp_servers(directory.id, &server_ids)
+        .await
+    {
+        Ok(_) => println!("\n{}", t!("mcp.assign.success").green()),
+        Err(e) => println!(
+            "\n{}",
+            t!("mcp.assign.error").replace("{}", &e.to_string()).red()
+        ),
+    }
+
+    Ok(())
+}
+
+async fn assign_to_account(db: &DbState) -> Result<()> {
+    let db_lock = db.lock().await;
+    let servers = db_lock.get_mcp_servers().await?;
+    let accounts_response = db_lock
+        .get_accounts(GetAccountsRequest {
+            page: Some(1),
+            per_page: Some(100),
+            search: None,
+            base_url: None,
+            ..Default::default()
+        })
+        .await?;
+    drop(db_lock);
+
+    if servers.is_empty() {
+        println!("\n{}", t!("mcp.list.no_records").yellow());
+        return Ok(());
+    }
+    if accounts_response.accounts.is_empty() {
+        println!("\n{}", t!("switch.no_accounts").yellow());
+        return Ok(());
+    }
+
+    let mut items: Vec<String> = vec![t!("common.cancel").to_string()];
+    items.extend(
+        accounts_response
+            .accounts
+            .iter()
+            .map(|a| format!("{} - {}", a.name, a.base_url)),
+    );
+
+    let selection = Select::new()
+        .with_prompt(t!("switch.select_account"))
+        .items(&items)
+        .interact_opt()?;
+
+    let account = match selection {
+        Some(idx) if idx > 0 => &accounts_response.accounts[idx - 1],
+        _ => return Ok(()),
+    };
+
+    let db_lock = db.lock().await;
+    let selected_ids = db_lock.get_account_mcp_server_ids(account.id).await?;
+    drop(db_lock);
+
+    let server_ids = match pick_mcp_servers(&servers, &selected_ids)? {
+        Some(ids) => ids,
+        None => return Ok(()),
+    };
+
+    let db_lock = db.lock().await;
+    match db_lock
+        .set_account_mcp_servers(account.id, &server_ids)
+        .await
+    {
+        Ok(_) => println!("\n{}", t!("mcp.assign.success").green()),
+        Err(e) => println!(
+            "\n{}",
+            t!("mcp.assign.error").replace("{}", &e.to_string()).red()
+        ),
+    }
+
+    Ok(())
+}
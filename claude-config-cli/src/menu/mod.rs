@@ -1,15 +1,130 @@
 pub mod account;
 pub mod base_url;
+pub mod definitions;
 pub mod directory;
+pub mod doctor;
+pub mod hooks;
+pub mod import_review;
 pub mod logs;
+pub mod mcp;
+pub mod permissions;
+pub mod picker;
+pub mod pool;
+pub mod proxy;
 pub mod settings;
 pub mod switch;
 pub mod webdav;
 
+use crate::{
+    custom_headers::{self, CustomHeader},
+    models::PoolFailover,
+    t,
+};
+use anyhow::Result;
+use colored::Colorize;
 use comfy_table::{presets::UTF8_FULL, Table};
+use dialoguer::{Confirm, Input, Select};
 
 pub fn create_table() -> Table {
     let mut table = Table::new();
     table.load_preset(UTF8_FULL);
     table
 }
+
+/// 输出账号池故障切换的结果
+pub fn print_failovers(failovers: &[PoolFailover]) {
+    for failover in failovers {
+        let line = t!("pool.failover.line")
+            .replace("{pool}", &failover.pool_name)
+            .replace("{directory}", &failover.directory_name)
+            .replace("{from}", &failover.from_account);
+        match (&failover.to_account, &failover.error) {
+            (Some(to), _) => println!("{}", format!("{} → {}", line, to).yellow()),
+            (None, Some(error)) => println!("{}", format!("{}: {}", line, error).red()),
+            (None, None) => println!(
+                "{}",
+                format!("{}: {}", line, t!("pool.failover.none")).red()
+            ),
+        }
+    }
+}
+
+/// 请求头列表的简短显示，只列出名称
+pub fn header_names(headers: &[CustomHeader]) -> String {
+    if headers.is_empty() {
+        return t!("headers.none").to_string();
+    }
+    headers
+        .iter()
+        .map(|h| h.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// 编辑自定义请求头，不编辑时返回 None 表示保持不变
+pub fn prompt_custom_headers(current: &[CustomHeader]) -> Result<Option<Vec<CustomHeader>>> {
+    println!(
+        "{}",
+        t!("headers.current").replace("{}", &header_names(current))
+    );
+    if !Confirm::new()
+        .with_prompt(t!("headers.prompt_edit"))
+        .default(false)
+        .interact()?
+    {
+        return Ok(None);
+    }
+
+    let mut headers = current.to_vec();
+    loop {
+        let actions = vec![
+            t!("headers.action_add"),
+            t!("headers.action_remove"),
+            t!("headers.action_done"),
+        ];
+        let action = Select::new()
+            .with_prompt(t!("headers.prompt_action"))
+            .items(&actions)
+            .default(0)
+            .interact()?;
+
+        match action {
+            0 => {
+                let line: String = Input::new()
+                    .with_prompt(t!("headers.prompt_line"))
+                    .allow_empty(true)
+                    .interact_text()?;
+                let parsed = custom_headers::parse_header_lines(&line);
+                if parsed.is_empty() {
+                    continue;
+                }
+                if let Err(e) = custom_headers::validate_headers(&parsed) {
+                    println!("{}", t!("headers.invalid").replace("{}", &e).red());
+                    continue;
+                }
+                headers = custom_headers::merge_headers(&[&headers, &parsed]);
+            }
+            1 if !headers.is_empty() => {
+                let items: Vec<String> = headers
+                    .iter()
+                    .map(|h| format!("{}: {}", h.name, h.value))
+                    .collect();
+                if let Some(idx) = Select::new()
+                    .with_prompt(t!("headers.prompt_remove"))
+                    .items(&items)
+                    .interact_opt()?
+                {
+                    headers.remove(idx);
+                }
+            }
+            1 => {}
+            _ => break,
+        }
+        println!(
+            "{}",
+            t!("headers.current").replace("{}", &header_names(&headers))
+        );
+    }
+
+    Ok(Some(headers))
+}
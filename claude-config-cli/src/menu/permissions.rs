@@ -0,0 +1,517 @@
+use crate::{models::*, permissions::*, t, DbState};
+use anyhow::Result;
+use colored::Colorize;
+use comfy_table::{Attribute, Cell, Color};
+use dialoguer::{Confirm, Input, Select};
+
+pub async fn permissions_menu(db: &DbState) -> Result<()> {
+    let mut last_selection = 0;
+
+    loop {
+        let items = vec![
+            t!("common.back"),
+            t!("permissions.menu.list"),
+            t!("permissions.menu.add"),
+            t!("permissions.menu.edit"),
+            t!("permissions.menu.delete"),
+            t!("permissions.menu.assign_directory"),
+            t!("permissions.menu.assign_account"),
+        ];
+
+        let selection = match Select::new()
+            .with_prompt(format!(
+                "\n{} (ESC {})",
+                t!("permissions.menu.title"),
+                t!("common.to_back")
+            ))
+            .items(&items)
+            .default(last_selection)
+            .interact_opt()?
+        {
+            Some(sel) => sel,
+            None => break, // 用户按了ESC，返回上一级
+        };
+
+        last_selection = selection;
+
+        match selection {
+            0 => break,
+            1 => list_presets(db).await?,
+            2 => add_preset(db).await?,
+            3 => edit_preset(db).await?,
+            4 => delete_preset(db).await?,
+            5 => assign_to_directory(db).await?,
+            6 => assign_to_account(db).await?,
+            _ => unreachable!(),
+        }
+    }
+
+    Ok(())
+}
+
+async fn list_presets(db: &DbState) -> Result<()> {
+    let db_lock = db.lock().await;
+    let presets = db_lock.get_permission_presets().await?;
+    drop(db_lock);
+
+    if presets.is_empty() {
+        println!("\n{}", t!("permissions.list.no_records").yellow());
+        return Ok(());
+    }
+
+    let mut table = super::create_table();
+    table.set_header(vec![
+        Cell::new(t!("permissions.list.header_id"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("permissions.list.header_name"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("permissions.list.header_mode"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("permissions.list.header_allow"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("permissions.list.header_deny"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("permissions.list.header_ask"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("permissions.list.header_builtin"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+    ]);
+
+    for preset in &presets {
+        table.add_row(vec![
+            preset.id.to_string(),
+            preset.name.clone(),
+            preset.default_mode.clone(),
+            preset.allow_rules().join("\n"),
+            preset.deny_rules().join("\n"),
+            preset.ask_rules().join("\n"),
+            if preset.is_builtin {
+                t!("url.list.default_yes").to_string()
+            } else {
+                String::new()
+            },
+        ]);
+    }
+
+    println!("\n{}", table);
+    println!(
+        "{}",
+        t!("permissions.list.total").replace("{}", &presets.len().to_string())
+    );
+
+    let _ = Input::<String>::new()
+        .with_prompt(t!("common.continue"))
+        .allow_empty(true)
+        .interact()?;
+
+    Ok(())
+}
+
+/// 按逗号拆分规则，括号内的逗号不拆分（如 `Bash(echo a,b)`）
+fn split_rules(input: &str) -> Vec<String> {
+    let mut rules = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0;
+
+    for c in input.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                rules.push(std::mem::take(&mut current));
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    rules.push(current);
+
+    rules
+        .into_iter()
+        .map(|r| r.trim().to_string())
+        .filter(|r| !r.is_empty())
+        .collect()
+}
+
+/// 输入一组权限规则，输入时即校验每条规则
+fn prompt_rules(prompt: &str, existing: Vec<String>) -> Result<Vec<String>> {
+    let input: String = Input::new()
+        .with_prompt(prompt)
+        .with_initial_text(existing.join(", "))
+        .allow_empty(true)
+        .validate_with(|input: &String| -> Result<(), String> {
+            validate_permission_rules(&split_rules(input))
+        })
+        .interact_text()?;
+
+    Ok(split_rules(&input))
+}
+
+/// 交互式输入预设字段，返回 (default_mode, allow, deny, ask)
+#[allow(clippy::type_complexity)]
+fn prompt_preset_fields(
+    existing: Option<&PermissionPreset>,
+) -> Result<(String, Vec<String>, Vec<String>, Vec<String>)> {
+    let default_mode = existing
+        .and_then(|p| PERMISSION_MODES.iter().position(|m| *m == p.default_mode))
+        .unwrap_or(0);
+    let mode_idx = Select::new()
+        .with_prompt(t!("permissions.add.prompt_mode"))
+        .items(&PERMISSION_MODES)
+        .default(default_mode)
+        .interact()?;
+
+    println!("{}", t!("permissions.add.rules_hint").cyan());
+
+    let allow = prompt_rules(
+        t!("permissions.add.prompt_allow"),
+        existing.map(|p| p.allow_rules()).unwrap_or_default(),
+    )?;
+    let deny = prompt_rules(
+        t!("permissions.add.prompt_deny"),
+        existing.map(|p| p.deny_rules()).unwrap_or_default(),
+    )?;
+    let ask = prompt_rules(
+        t!("permissions.add.prompt_ask"),
+        existing.map(|p| p.ask_rules()).unwrap_or_default(),
+    )?;
+
+    Ok((PERMISSION_MODES[mode_idx].to_string(), allow, deny, ask))
+}
+
+async fn add_preset(db: &DbState) -> Result<()> {
+    println!("\n{}", t!("permissions.add.title").green().bold());
+    println!("{}", t!("common.input_cancel_hint").yellow());
+
+    let name: String = Input::new()
+        .with_prompt(t!("permissions.add.prompt_name"))
+        .allow_empty(true)
+        .interact_text()?;
+
+    if name.trim().is_empty() || name.trim().eq_ignore_ascii_case("q") {
+        println!("\n{}", t!("common.cancel").yellow());
+        return Ok(());
+    }
+
+    let (default_mode, allow, deny, ask) = prompt_preset_fields(None)?;
+
+    if let Err(e) = validate_permission_preset(&default_mode, &allow, &deny, &ask) {
+        println!("\n{}", t!("permissions.add.error").replace("{}", &e).red());
+        return Ok(());
+    }
+
+    let description: String = Input::new()
+        .with_prompt(t!("permissions.add.prompt_description"))
+        .allow_empty(true)
+        .interact_text()?;
+
+    let db_lock = db.lock().await;
+    let request = CreatePermissionPresetRequest {
+        name: name.trim().to_string(),
+        default_mode,
+        allow,
+        deny,
+        ask,
+        description: if description.is_empty() {
+            None
+        } else {
+            Some(description)
+        },
+    };
+
+    match db_lock.create_permission_preset(request).await {
+        Ok(_) => {
+            println!(
+                "\n{}",
+                t!("permissions.add.success")
+                    .replace("{}", name.trim())
+                    .green()
+            );
+        }
+        Err(e) => {
+            println!(
+                "\n{}",
+                t!("permissions.add.error")
+                    .replace("{}", &e.to_string())
+                    .red()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+async fn select_preset(db: &DbState, prompt: &str) -> Result<Option<PermissionPreset>> {
+    let db_lock = db.lock().await;
+    let presets = db_lock.get_permission_presets().await?;
+    drop(db_lock);
+
+    if presets.is_empty() {
+        println!("\n{}", t!("permissions.list.no_records").yellow());
+        return Ok(None);
+    }
+
+    let mut items: Vec<String> = vec![t!("common.cancel").to_string()];
+    items.extend(
+        presets
+            .iter()
+            .map(|p| format!("{} [{}]", p.name, p.default_mode)),
+    );
+
+    let selection = Select::new()
+        .with_prompt(prompt)
+        .items(&items)
+        .interact_opt()?;
+
+    match selection {
+        Some(idx) if idx > 0 => Ok(presets.into_iter().nth(idx - 1)),
+        _ => Ok(None),
+    }
+}
+
+async fn edit_preset(db: &DbState) -> Result<()> {
+    let preset = match select_preset(db, t!("permissions.edit.prompt")).await? {
+        Some(preset) => preset,
+        None => return Ok(()),
+    };
+
+    println!("{}", t!("common.input_cancel_hint").yellow());
+
+    let name: String = Input::new()
+        .with_prompt(t!("permissions.add.prompt_name"))
+        .default(preset.name.clone())
+        .allow_empty(true)
+        .interact_text()?;
+
+    let name = if name.trim().is_empty() {
+        preset.name.clone()
+    } else {
+        name.trim().to_string()
+    };
+
+    let (default_mode, allow, deny, ask) = prompt_preset_fields(Some(&preset))?;
+
+    if let Err(e) = validate_permission_preset(&default_mode, &allow, &deny, &ask) {
+        println!("\n{}", t!("permissions.edit.error").replace("{}", &e).red());
+        return Ok(());
+    }
+
+    let description: String = Input::new()
+        .with_prompt(t!("permissions.add.prompt_description"))
+        .default(preset.description.clone().unwrap_or_default())
+        .allow_empty(true)
+        .interact_text()?;
+
+    let db_lock = db.lock().await;
+    let request = UpdatePermissionPresetRequest {
+        name: Some(name),
+        default_mode: Some(default_mode),
+        allow: Some(allow),
+        deny: Some(deny),
+        ask: Some(ask),
+        description: Some(description),
+    };
+
+    match db_lock.update_permission_preset(preset.id, request).await {
+        Ok(_) => {
+            println!("\n{}", t!("permissions.edit.success").green());
+        }
+        Err(e) => {
+            println!(
+                "\n{}",
+                t!("permissions.edit.error")
+                    .replace("{}", &e.to_string())
+                    .red()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+async fn delete_preset(db: &DbState) -> Result<()> {
+    let preset = match select_preset(db, t!("permissions.delete.prompt")).await? {
+        Some(preset) => preset,
+        None => return Ok(()),
+    };
+
+    if preset.is_builtin {
+        println!("\n{}", t!("permissions.delete.builtin").yellow());
+        return Ok(());
+    }
+
+    if Confirm::new()
+        .with_prompt(t!("permissions.delete.confirm").replace("{}", &preset.name))
+        .default(false)
+        .interact()?
+    {
+        let db_lock = db.lock().await;
+        match db_lock.delete_permission_preset(preset.id).await {
+            Ok(_) => {
+                println!("\n{}", t!("permissions.delete.success").green());
+            }
+            Err(e) => {
+                println!(
+                    "\n{}",
+                    t!("permissions.delete.error")
+                        .replace("{}", &e.to_string())
+                        .red()
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// 选择要绑定的预设，返回 None 表示取消，Some(None) 表示解除绑定
+async fn select_preset_for_binding(
+    db: &DbState,
+    current: Option<i64>,
+) -> Result<Option<Option<i64>>> {
+    let db_lock = db.lock().await;
+    let presets = db_lock.get_permission_presets().await?;
+    drop(db_lock);
+
+    let mut items: Vec<String> = vec![
+        t!("common.cancel").to_string(),
+        t!("permissions.assign.none").to_string(),
+    ];
+    items.extend(
+        presets
+            .iter()
+            .map(|p| format!("{} [{}]", p.name, p.default_mode)),
+    );
+
+    let default = current
+        .and_then(|id| presets.iter().position(|p| p.id == id))
+        .map(|idx| idx + 2)
+        .unwrap_or(1);
+
+    let selection = Select::new()
+        .with_prompt(t!("permissions.assign.prompt_preset"))
+        .items(&items)
+        .default(default)
+        .interact_opt()?;
+
+    Ok(match selection {
+        Some(0) | None => None,
+        Some(1) => Some(None),
+        Some(idx) => Some(Some(presets[idx - 2].id)),
+    })
+}
+
+async fn assign_to_directory(db: &DbState) -> Result<()> {
+    let db_lock = db.lock().await;
+    let directories = db_lock.get_directories().await?;
+    drop(db_lock);
+
+    if directories.is_empty() {
+        println!("\n{}", t!("switch.no_directories").yellow());
+        return Ok(());
+    }
+
+    let mut items: Vec<String> = vec![t!("common.cancel").to_string()];
+    items.extend(
+        directories
+            .iter()
+            .map(|d| format!("{} - {}", d.name, d.path)),
+    );
+
+    let selection = Select::new()
+        .with_prompt(t!("switch.select_directory"))
+        .items(&items)
+        .interact_opt()?;
+
+    let directory = match selection {
+        Some(idx) if idx > 0 => &directories[idx - 1],
+        _ => return Ok(()),
+    };
+
+    let preset_id = match select_preset_for_binding(db, directory.permission_preset_id).await? {
+        Some(preset_id) => preset_id,
+        None => return Ok(()),
+    };
+
+    let db_lock = db.lock().await;
+    match db_lock
+        .set_directory_permission_preset(directory.id, preset_id)
+        .await
+    {
+        Ok(_) => println!("\n{}", t!("permissions.assign.success").green()),
+        Err(e) => println!(
+            "\n{}",
+            t!("permissions.assign.error")
+                .replace("{}", &e.to_string())
+                .red()
+        ),
+    }
+
+    Ok(())
+}
+
+async fn assign_to_account(db: &DbState) -> Result<()> {
+    let db_lock = db.lock().await;
+    let accounts_response = db_lock
+        .get_accounts(GetAccountsRequest {
+            page: Some(1),
+            per_page: Some(100),
+            search: None,
+            base_url: None,
+            ..Default::default()
+        })
+        .await?;
+    drop(db_lock);
+
+    if accounts_response.accounts.is_empty() {
+        println!("\n{}", t!("switch.no_accounts").yellow());
+        return Ok(());
+    }
+
+    let mut items: Vec<String> = vec![t!("common.cancel").to_string()];
+    items.extend(
+        accounts_response
+            .accounts
+            .iter()
+            .map(|a| format!("{} - {}", a.name, a.base_url)),
+    );
+
+    let selection = Select::new()
+        .with_prompt(t!("switch.select_account"))
+        .items(&items)
+        .interact_opt()?;
+
+    let account = match selection {
+        Some(idx) if idx > 0 => &accounts_response.accounts[idx - 1],
+        _ => return Ok(()),
+    };
+
+    let preset_id = match select_preset_for_binding(db, account.permission_preset_id).await? {
+        Some(preset_id) => preset_id,
+        None => return Ok(()),
+    };
+
+    let db_lock = db.lock().await;
+    match db_lock
+        .set_account_permission_preset(account.id, preset_id)
+        .await
+    {
+        Ok(_) => println!("\n{}", t!("permissions.assign.success").green()),
+        Err(e) => println!(
+            "\n{}",
+            t!("permissions.assign.error")
+                .replace("{}", &e.to_string())
+                .red()
+        ),
+    }
+
+    Ok(())
+}
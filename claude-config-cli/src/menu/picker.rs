@@ -0,0 +1,167 @@
+//! 支持模糊搜索的账号、目录和 Base URL 选择器
+//! 候选项由调用方按最近使用排序传入，输入关键字即可过滤
+use crate::{models::*, t};
+use anyhow::Result;
+use dialoguer::FuzzySelect;
+use std::path::Path;
+
+/// 一屏最多显示的候选项数量，其余通过输入关键字过滤
+const MAX_ROWS: usize = 15;
+
+/// 模糊搜索选择，按 ESC 返回 None
+fn fuzzy_select(prompt: &str, items: &[String], default: usize) -> Result<Option<usize>> {
+    if items.is_empty() {
+        return Ok(None);
+    }
+    Ok(FuzzySelect::new()
+        .with_prompt(format!("{} ({})", prompt, t!("common.to_back")))
+        .items(items)
+        .default(default.min(items.len() - 1))
+        .max_length(MAX_ROWS)
+        .interact_opt()?)
+}
+
+/// 选择账号，default_id 为预选的账号
+pub fn pick_account<'a>(
+    prompt: &str,
+    accounts: &'a [Account],
+    default_id: Option<i64>,
+) -> Result<Option<&'a Account>> {
+    let items: Vec<String> = accounts.iter().map(account_label).collect();
+    let default = default_position(accounts, |a| Some(a.id) == default_id);
+    Ok(fuzzy_select(prompt, &items, default)?.map(|idx| &accounts[idx]))
+}
+
+fn account_label(account: &Account) -> String {
+    if account.tags.is_empty() {
+        format!("{} - {}", account.name, account.base_url)
+    } else {
+        format!("{} - {} [{}]", account.name, account.base_url, account.tags)
+    }
+}
+
+/// 预选项的位置，没有匹配时选第一项
+fn default_position<T>(items: &[T], is_default: impl Fn(&T) -> bool) -> usize {
+    items.iter().position(is_default).unwrap_or(0)
+}
+
+/// 选择目录，目录路径不存在时标记 ✗
+pub fn pick_directory<'a>(
+    prompt: &str,
+    directories: &'a [Directory],
+    default_id: Option<i64>,
+) -> Result<Option<&'a Directory>> {
+    let items: Vec<String> = directories.iter().map(directory_label).collect();
+    let default = default_position(directories, |d| Some(d.id) == default_id);
+    Ok(fuzzy_select(prompt, &items, default)?.map(|idx| &directories[idx]))
+}
+
+fn directory_label(directory: &Directory) -> String {
+    let exists = if Path::new(&directory.path).exists() {
+        "✓"
+    } else {
+        "✗"
+    };
+    format!("{} {} - {}", exists, directory.name, directory.path)
+}
+
+/// 选择 Base URL，default_url 为预选的地址
+pub fn pick_base_url<'a>(
+    prompt: &str,
+    base_urls: &'a [BaseUrl],
+    default_url: Option<&str>,
+) -> Result<Option<&'a BaseUrl>> {
+    let items: Vec<String> = base_urls.iter().map(base_url_label).collect();
+    let default = default_position(base_urls, |u| Some(u.url.as_str()) == default_url);
+    Ok(fuzzy_select(prompt, &items, default)?.map(|idx| &base_urls[idx]))
+}
+
+fn base_url_label(base_url: &BaseUrl) -> String {
+    if base_url.is_default {
+        format!(
+            "{} - {} {}",
+            base_url.name,
+            base_url.url,
+            t!("account.default_indicator")
+        )
+    } else {
+        format!("{} - {}", base_url.name, base_url.url)
+    }
+}
+
+/// 包含当前工作目录的已登记目录，存在嵌套时取路径最长的一个
+pub fn directory_for_cwd(directories: &[Directory]) -> Option<i64> {
+    let cwd = std::env::current_dir().ok()?;
+    directory_containing(directories, &cwd)
+}
+
+fn directory_containing(directories: &[Directory], path: &Path) -> Option<i64> {
+    directories
+        .iter()
+        .filter(|d| path.starts_with(Path::new(&d.path)))
+        .max_by_key(|d| d.path.len())
+        .map(|d| d.id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn directory(id: i64, path: &str) -> Directory {
+        Directory {
+            id,
+            path: path.to_string(),
+            name: format!("dir-{}", id),
+            is_active: false,
+            permission_preset_id: None,
+            account_pool_id: None,
+            relay_port: None,
+            proxy_id: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_directory_containing_prefers_deepest_match() {
+        let directories = vec![
+            directory(1, "/work"),
+            directory(2, "/work/app"),
+            directory(3, "/work/application"),
+            directory(4, "/other"),
+        ];
+        assert_eq!(
+            directory_containing(&directories, Path::new("/work/app/src")),
+            Some(2)
+        );
+        assert_eq!(
+            directory_containing(&directories, Path::new("/work/docs")),
+            Some(1)
+        );
+        // 按路径组件匹配，/work/app 不包含 /work/apple
+        assert_eq!(
+            directory_containing(&directories, Path::new("/work/apple")),
+            Some(1)
+        );
+        assert_eq!(directory_containing(&directories, Path::new("/tmp")), None);
+    }
+
+    #[test]
+    fn test_default_position_and_labels() {
+        let directories = vec![
+            directory(1, "/work"),
+            directory(2, "/missing/claude-config"),
+        ];
+        assert_eq!(default_position(&directories, |d| d.id == 2), 1);
+        assert_eq!(default_position(&directories, |d| d.id == 9), 0);
+
+        let root = std::env::temp_dir();
+        let existing = directory(3, root.to_str().unwrap());
+        assert!(directory_label(&existing).starts_with("✓ dir-3 - "));
+        assert_eq!(
+            directory_label(&directories[1]),
+            "✗ dir-2 - /missing/claude-config"
+        );
+    }
+}
@@ -0,0 +1,587 @@
+use crate::{models::*, pools, relay, t, DbState};
+use anyhow::Result;
+use colored::Colorize;
+use comfy_table::{Attribute, Cell, Color};
+use dialoguer::{Confirm, Input, Select};
+
+pub async fn pool_menu(db: &DbState) -> Result<()> {
+    let mut last_selection = 0;
+
+    loop {
+        let items = vec![
+            t!("common.back"),
+            t!("pool.menu.list"),
+            t!("pool.menu.add"),
+            t!("pool.menu.delete"),
+            t!("pool.menu.members"),
+            t!("pool.menu.bind"),
+            t!("pool.menu.relay"),
+            t!("pool.menu.relay_stats"),
+            t!("pool.menu.failovers"),
+        ];
+
+        let selection = match Select::new()
+            .with_prompt(format!(
+                "\n{} (ESC {})",
+                t!("pool.menu.title"),
+                t!("common.to_back")
+            ))
+            .items(&items)
+            .default(last_selection)
+            .interact_opt()?
+        {
+            Some(sel) => sel,
+            None => break, // 用户按了ESC，返回上一级
+        };
+
+        last_selection = selection;
+
+        match selection {
+            0 => break,
+            1 => list_pools(db).await?,
+            2 => add_pool(db).await?,
+            3 => delete_pool(db).await?,
+            4 => manage_members(db).await?,
+            5 => bind_directory(db).await?,
+            6 => route_directory(db).await?,
+            7 => relay_stats(db).await?,
+            8 => failover_history(db).await?,
+            _ => unreachable!(),
+        }
+    }
+
+    Ok(())
+}
+
+async fn select_pool(db: &DbState) -> Result<Option<AccountPool>> {
+    let db_lock = db.lock().await;
+    let pools = db_lock.get_account_pools().await?;
+    drop(db_lock);
+
+    if pools.is_empty() {
+        println!("\n{}", t!("pool.list.no_records").yellow());
+        return Ok(None);
+    }
+
+    let mut items: Vec<String> = vec![t!("common.cancel").to_string()];
+    items.extend(pools.iter().map(|p| p.name.clone()));
+    Ok(
+        match Select::new()
+            .with_prompt(t!("pool.prompt_select"))
+            .items(&items)
+            .default(0)
+            .interact_opt()?
+        {
+            Some(idx) if idx > 0 => Some(pools[idx - 1].clone()),
+            _ => None,
+        },
+    )
+}
+
+async fn list_pools(db: &DbState) -> Result<()> {
+    let db_lock = db.lock().await;
+    let pools = db_lock.get_account_pools().await?;
+    let members = db_lock.get_account_pool_members(None).await?;
+    let accounts = db_lock.get_all_accounts().await?;
+    let directories = db_lock.get_directories().await?;
+    drop(db_lock);
+
+    if pools.is_empty() {
+        println!("\n{}", t!("pool.list.no_records").yellow());
+        return Ok(());
+    }
+
+    for pool in &pools {
+        let bound: Vec<&str> = directories
+            .iter()
+            .filter(|d| d.account_pool_id == Some(pool.id))
+            .map(|d| d.name.as_str())
+            .collect();
+        println!(
+            "\n{} {}",
+            pool.name.green().bold(),
+            pool.description.clone().unwrap_or_default().dimmed()
+        );
+        println!(
+            "{}",
+            t!("pool.list.directories").replace(
+                "{}",
+                &if bound.is_empty() {
+                    "-".to_string()
+                } else {
+                    bound.join(", ")
+                }
+            )
+        );
+
+        let mut table = super::create_table();
+        table.set_header(vec![
+            Cell::new(t!("account.list.header_name"))
+                .add_attribute(Attribute::Bold)
+                .fg(Color::Cyan),
+            Cell::new(t!("pool.list.header_priority"))
+                .add_attribute(Attribute::Bold)
+                .fg(Color::Cyan),
+            Cell::new(t!("pool.list.header_weight"))
+                .add_attribute(Attribute::Bold)
+                .fg(Color::Cyan),
+            Cell::new(t!("account.list.header_token"))
+                .add_attribute(Attribute::Bold)
+                .fg(Color::Cyan),
+        ]);
+        for member in members.iter().filter(|m| m.pool_id == pool.id) {
+            let account = match accounts.iter().find(|a| a.id == member.account_id) {
+                Some(account) => account,
+                None => continue,
+            };
+            table.add_row(vec![
+                Cell::new(&account.name),
+                Cell::new(member.priority),
+                Cell::new(member.weight),
+                Cell::new(account.token_status.as_deref().unwrap_or("-")),
+            ]);
+        }
+        println!("{}", table);
+    }
+
+    let _ = Input::<String>::new()
+        .with_prompt(t!("common.continue"))
+        .allow_empty(true)
+        .interact()?;
+
+    Ok(())
+}
+
+async fn add_pool(db: &DbState) -> Result<()> {
+    println!("\n{}", t!("common.input_cancel_hint").yellow());
+    let name: String = Input::new()
+        .with_prompt(t!("pool.add.prompt_name"))
+        .allow_empty(true)
+        .interact_text()?;
+    if name.trim().is_empty() || name.trim().eq_ignore_ascii_case("q") {
+        println!("\n{}", t!("common.cancel").yellow());
+        return Ok(());
+    }
+
+    let description: String = Input::new()
+        .with_prompt(t!("pool.add.prompt_description"))
+        .allow_empty(true)
+        .interact_text()?;
+
+    let db_lock = db.lock().await;
+    match db_lock
+        .create_account_pool(CreateAccountPoolRequest {
+            name: name.trim().to_string(),
+            description: Some(description).filter(|d| !d.trim().is_empty()),
+        })
+        .await
+    {
+        Ok(pool) => println!(
+            "\n{}",
+            t!("pool.add.success").replace("{}", &pool.name).green()
+        ),
+        Err(e) => println!(
+            "\n{}",
+            t!("pool.add.error").replace("{}", &e.to_string()).red()
+        ),
+    }
+
+    Ok(())
+}
+
+async fn delete_pool(db: &DbState) -> Result<()> {
+    let pool = match select_pool(db).await? {
+        Some(pool) => pool,
+        None => return Ok(()),
+    };
+
+    let confirmed = Confirm::new()
+        .with_prompt(t!("pool.delete.confirm").replace("{}", &pool.name))
+        .default(false)
+        .interact()?;
+    if !confirmed {
+        return Ok(());
+    }
+
+    let db_lock = db.lock().await;
+    db_lock.delete_account_pool(pool.id).await?;
+    println!(
+        "\n{}",
+        t!("pool.delete.success").replace("{}", &pool.name).green()
+    );
+
+    Ok(())
+}
+
+async fn manage_members(db: &DbState) -> Result<()> {
+    let pool = match select_pool(db).await? {
+        Some(pool) => pool,
+        None => return Ok(()),
+    };
+
+    let db_lock = db.lock().await;
+    let members = db_lock.get_account_pool_members(Some(pool.id)).await?;
+    let accounts = db_lock.get_all_accounts().await?;
+    drop(db_lock);
+
+    if accounts.is_empty() {
+        println!("\n{}", t!("account.list.no_records").yellow());
+        return Ok(());
+    }
+
+    // 已在池中的账号显示当前的优先级和权重
+    let mut items: Vec<String> = vec![t!("common.cancel").to_string()];
+    items.extend(accounts.iter().map(|account| {
+        match members.iter().find(|m| m.account_id == account.id) {
+            Some(member) => format!(
+                "✓ {} ({}: {}, {}: {})",
+                account.name,
+                t!("pool.list.header_priority"),
+                member.priority,
+                t!("pool.list.header_weight"),
+                member.weight
+            ),
+            None => format!("  {}", account.name),
+        }
+    }));
+
+    let account = match Select::new()
+        .with_prompt(t!("pool.members.prompt_account"))
+        .items(&items)
+        .default(0)
+        .interact_opt()?
+    {
+        Some(idx) if idx > 0 => &accounts[idx - 1],
+        _ => return Ok(()),
+    };
+    let existing = members.iter().find(|m| m.account_id == account.id);
+
+    if existing.is_some() {
+        let actions = vec![
+            t!("common.cancel"),
+            t!("pool.members.action_edit"),
+            t!("pool.members.action_remove"),
+        ];
+        match Select::new()
+            .with_prompt(t!("common.select_operation"))
+            .items(&actions)
+            .default(1)
+            .interact_opt()?
+        {
+            Some(1) => {}
+            Some(2) => {
+                let db_lock = db.lock().await;
+                db_lock
+                    .remove_account_pool_member(pool.id, account.id)
+                    .await?;
+                println!("\n{}", t!("pool.members.removed").green());
+                return Ok(());
+            }
+            _ => return Ok(()),
+        }
+    }
+
+    let priority: i64 = Input::new()
+        .with_prompt(t!("pool.members.prompt_priority"))
+        .default(existing.map(|m| m.priority).unwrap_or(1))
+        .interact_text()?;
+    let weight: i64 = Input::new()
+        .with_prompt(t!("pool.members.prompt_weight"))
+        .default(existing.map(|m| m.weight).unwrap_or(100))
+        .interact_text()?;
+
+    let db_lock = db.lock().await;
+    db_lock
+        .save_account_pool_member(SaveAccountPoolMemberRequest {
+            pool_id: pool.id,
+            account_id: account.id,
+            weight: Some(weight),
+            priority: Some(priority),
+        })
+        .await?;
+    println!("\n{}", t!("pool.members.saved").green());
+
+    Ok(())
+}
+
+async fn bind_directory(db: &DbState) -> Result<()> {
+    let db_lock = db.lock().await;
+    let directories = db_lock.get_directories().await?;
+    let pools = db_lock.get_account_pools().await?;
+    drop(db_lock);
+
+    if directories.is_empty() {
+        println!("\n{}", t!("switch.no_directories").yellow());
+        return Ok(());
+    }
+    if pools.is_empty() {
+        println!("\n{}", t!("pool.list.no_records").yellow());
+        return Ok(());
+    }
+
+    let mut items: Vec<String> = vec![t!("common.cancel").to_string()];
+    items.extend(directories.iter().map(|d| {
+        let pool = d
+            .account_pool_id
+            .and_then(|id| pools.iter().find(|p| p.id == id))
+            .map(|p| format!(" [{}]", p.name))
+            .unwrap_or_default();
+        format!("{} - {}{}", d.name, d.path, pool)
+    }));
+    let directory = match Select::new()
+        .with_prompt(t!("switch.select_directory"))
+        .items(&items)
+        .default(0)
+        .interact_opt()?
+    {
+        Some(idx) if idx > 0 => &directories[idx - 1],
+        _ => return Ok(()),
+    };
+
+    let mut items: Vec<String> = vec![t!("pool.bind.none").to_string()];
+    items.extend(pools.iter().map(|p| p.name.clone()));
+    let default = directory
+        .account_pool_id
+        .and_then(|id| pools.iter().position(|p| p.id == id))
+        .map(|idx| idx + 1)
+        .unwrap_or(0);
+    let pool_id = match Select::new()
+        .with_prompt(t!("pool.prompt_select"))
+        .items(&items)
+        .default(default)
+        .interact_opt()?
+    {
+        Some(0) => None,
+        Some(idx) => Some(pools[idx - 1].id),
+        None => return Ok(()),
+    };
+
+    let db_lock = db.lock().await;
+    let switched = pools::bind_directory(&db_lock, directory.id, pool_id).await?;
+    drop(db_lock);
+
+    println!("\n{}", t!("pool.bind.success").green());
+    if let Some(switched) = switched {
+        match (&switched.to_account, &switched.error) {
+            (Some(account), _) => {
+                println!("{}", t!("pool.bind.switched").replace("{}", account).cyan())
+            }
+            (None, Some(error)) => println!("{}", error.red()),
+            (None, None) => println!("{}", t!("pool.failover.none").yellow()),
+        }
+    }
+
+    let _ = Input::<String>::new()
+        .with_prompt(t!("common.continue"))
+        .allow_empty(true)
+        .interact()?;
+
+    Ok(())
+}
+
+async fn route_directory(db: &DbState) -> Result<()> {
+    let db_lock = db.lock().await;
+    let directories: Vec<Directory> = db_lock
+        .get_directories()
+        .await?
+        .into_iter()
+        .filter(|d| d.account_pool_id.is_some())
+        .collect();
+    drop(db_lock);
+
+    if directories.is_empty() {
+        println!("\n{}", t!("pool.relay.no_directories").yellow());
+        return Ok(());
+    }
+
+    let mut items: Vec<String> = vec![t!("common.cancel").to_string()];
+    items.extend(directories.iter().map(|d| match d.relay_port {
+        Some(port) => format!("{} - {} [relay :{}]", d.name, d.path, port),
+        None => format!("{} - {}", d.name, d.path),
+    }));
+    let directory = match Select::new()
+        .with_prompt(t!("switch.select_directory"))
+        .items(&items)
+        .default(0)
+        .interact_opt()?
+    {
+        Some(idx) if idx > 0 => &directories[idx - 1],
+        _ => return Ok(()),
+    };
+
+    let modes = vec![t!("pool.relay.direct"), t!("pool.relay.via_relay")];
+    let port = match Select::new()
+        .with_prompt(t!("pool.relay.prompt_mode"))
+        .items(&modes)
+        .default(if directory.relay_port.is_some() { 1 } else { 0 })
+        .interact_opt()?
+    {
+        Some(0) => None,
+        Some(_) => Some(
+            Input::<u16>::new()
+                .with_prompt(t!("pool.relay.prompt_port"))
+                .default(
+                    directory
+                        .relay_port
+                        .map(|p| p as u16)
+                        .unwrap_or(relay::DEFAULT_PORT),
+                )
+                .interact_text()?,
+        ),
+        None => return Ok(()),
+    };
+
+    let db_lock = db.lock().await;
+    match relay::route_directory(&db_lock, directory.id, port).await {
+        Ok(_) => {
+            println!("\n{}", t!("pool.relay.success").green());
+            if let Some(port) = port {
+                println!(
+                    "{}",
+                    t!("pool.relay.serve_hint")
+                        .replace("{}", &port.to_string())
+                        .cyan()
+                );
+            }
+        }
+        Err(e) => println!(
+            "\n{}",
+            t!("pool.relay.error").replace("{}", &e.to_string()).red()
+        ),
+    }
+
+    Ok(())
+}
+
+async fn relay_stats(db: &DbState) -> Result<()> {
+    let db_lock = db.lock().await;
+    let stats = db_lock.get_relay_stats().await?;
+    let accounts = db_lock.get_all_accounts().await?;
+    drop(db_lock);
+
+    if stats.is_empty() {
+        println!("\n{}", t!("pool.relay.no_stats").yellow());
+        return Ok(());
+    }
+
+    let mut table = super::create_table();
+    table.set_header(vec![
+        Cell::new(t!("account.list.header_name"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("pool.relay.header_requests"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("pool.relay.header_failures"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("pool.relay.header_avg_latency"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("pool.relay.header_last_status"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("pool.relay.header_last_used"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+    ]);
+    for stat in &stats {
+        let name = accounts
+            .iter()
+            .find(|a| a.id == stat.account_id)
+            .map(|a| a.name.clone())
+            .unwrap_or_else(|| stat.account_id.to_string());
+        let average = if stat.request_count > 0 {
+            format!("{} ms", stat.total_latency_ms / stat.request_count)
+        } else {
+            "-".to_string()
+        };
+        table.add_row(vec![
+            Cell::new(name),
+            Cell::new(stat.request_count),
+            Cell::new(stat.failure_count),
+            Cell::new(average),
+            Cell::new(
+                stat.last_status
+                    .map(|s| s.to_string())
+                    .unwrap_or_else(|| "-".to_string()),
+            ),
+            Cell::new(
+                stat.last_used_at
+                    .map(|t| {
+                        t.with_timezone(&chrono::Local)
+                            .format("%Y-%m-%d %H:%M:%S")
+                            .to_string()
+                    })
+                    .unwrap_or_else(|| "-".to_string()),
+            ),
+        ]);
+    }
+    println!("{}", table);
+
+    let _ = Input::<String>::new()
+        .with_prompt(t!("common.continue"))
+        .allow_empty(true)
+        .interact()?;
+
+    Ok(())
+}
+
+/// 最近的故障切换记录
+async fn failover_history(db: &DbState) -> Result<()> {
+    let db_lock = db.lock().await;
+    let failovers = db_lock.get_pool_failovers(None, 50).await?;
+    drop(db_lock);
+
+    if failovers.is_empty() {
+        println!("\n{}", t!("pool.failovers.no_records").yellow());
+        return Ok(());
+    }
+
+    let mut table = super::create_table();
+    table.set_header(vec![
+        Cell::new(t!("pool.failovers.header_time"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("pool.failovers.header_pool"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("pool.failovers.header_directory"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("pool.failovers.header_from"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("pool.failovers.header_to"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+    ]);
+    for failover in &failovers {
+        let to = match (&failover.to_account, &failover.error) {
+            (Some(to), _) => Cell::new(to).fg(Color::Green),
+            (None, Some(error)) => Cell::new(error).fg(Color::Red),
+            (None, None) => Cell::new(t!("pool.failover.none")).fg(Color::Red),
+        };
+        table.add_row(vec![
+            Cell::new(
+                failover
+                    .created_at
+                    .with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M:%S")
+                    .to_string(),
+            ),
+            Cell::new(&failover.pool_name),
+            Cell::new(&failover.directory_name),
+            Cell::new(&failover.from_account),
+            to,
+        ]);
+    }
+    println!("{}", table);
+
+    let _ = Input::<String>::new()
+        .with_prompt(t!("common.continue"))
+        .allow_empty(true)
+        .interact()?;
+
+    Ok(())
+}
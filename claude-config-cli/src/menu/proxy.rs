@@ -0,0 +1,546 @@
+use crate::{models::*, proxy, t, webdav, DbState};
+use anyhow::Result;
+use colored::Colorize;
+use comfy_table::{Attribute, Cell, Color};
+use dialoguer::{Confirm, Input, Select};
+use std::time::Duration;
+
+pub async fn proxy_menu(db: &DbState) -> Result<()> {
+    let mut last_selection = 0;
+
+    loop {
+        let items = vec![
+            t!("common.back"),
+            t!("proxy.menu.list"),
+            t!("proxy.menu.add"),
+            t!("proxy.menu.edit"),
+            t!("proxy.menu.delete"),
+            t!("proxy.menu.test"),
+            t!("proxy.menu.assign_base_url"),
+            t!("proxy.menu.assign_account"),
+            t!("proxy.menu.assign_directory"),
+            t!("proxy.menu.assign_webdav"),
+        ];
+
+        let selection = match Select::new()
+            .with_prompt(format!(
+                "\n{} (ESC {})",
+                t!("proxy.menu.title"),
+                t!("common.to_back")
+            ))
+            .items(&items)
+            .default(last_selection)
+            .interact_opt()?
+        {
+            Some(sel) => sel,
+            None => break, // 用户按了ESC，返回上一级
+        };
+
+        last_selection = selection;
+
+        match selection {
+            0 => break,
+            1 => list_proxies(db).await?,
+            2 => add_proxy(db).await?,
+            3 => edit_proxy(db).await?,
+            4 => delete_proxy(db).await?,
+            5 => test_proxy(db).await?,
+            6 => assign_to_base_url(db).await?,
+            7 => assign_to_account(db).await?,
+            8 => assign_to_directory(db).await?,
+            9 => assign_to_webdav(db).await?,
+            _ => unreachable!(),
+        }
+    }
+
+    Ok(())
+}
+
+async fn list_proxies(db: &DbState) -> Result<()> {
+    let db_lock = db.lock().await;
+    let proxies = db_lock.get_proxies().await?;
+    drop(db_lock);
+
+    if proxies.is_empty() {
+        println!("\n{}", t!("proxy.list.no_records").yellow());
+        return Ok(());
+    }
+
+    let mut table = super::create_table();
+    table.set_header(vec![
+        Cell::new(t!("proxy.list.header_id"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("proxy.list.header_name"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("proxy.list.header_url"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("proxy.list.header_no_proxy"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("proxy.list.header_username"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Cell::new(t!("proxy.list.header_description"))
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+    ]);
+
+    for p in &proxies {
+        table.add_row(vec![
+            p.id.to_string(),
+            p.name.clone(),
+            p.url.clone(),
+            p.no_proxy.clone().unwrap_or_default(),
+            p.username.clone().unwrap_or_default(),
+            p.description.clone().unwrap_or_default(),
+        ]);
+    }
+
+    println!("\n{}", table);
+    println!(
+        "{}",
+        t!("proxy.list.total").replace("{}", &proxies.len().to_string())
+    );
+
+    let _ = Input::<String>::new()
+        .with_prompt(t!("common.continue"))
+        .allow_empty(true)
+        .interact()?;
+
+    Ok(())
+}
+
+/// 空字符串视为未设置
+fn non_empty(value: String) -> Option<String> {
+    let value = value.trim().to_string();
+    if value.is_empty() {
+        None
+    } else {
+        Some(value)
+    }
+}
+
+/// 交互式输入代理字段，返回 (url, no_proxy, username, password, description)
+#[allow(clippy::type_complexity)]
+fn prompt_proxy_fields(
+    existing: Option<&Proxy>,
+) -> Result<(
+    String,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+)> {
+    println!("{}", t!("proxy.add.url_hint").cyan());
+
+    let url: String = Input::new()
+        .with_prompt(t!("proxy.add.prompt_url"))
+        .with_initial_text(existing.map(|p| p.url.clone()).unwrap_or_default())
+        .validate_with(|input: &String| -> Result<(), String> { proxy::validate_proxy_url(input) })
+        .interact_text()?;
+
+    let no_proxy: String = Input::new()
+        .with_prompt(t!("proxy.add.prompt_no_proxy"))
+        .with_initial_text(
+            existing
+                .and_then(|p| p.no_proxy.clone())
+                .unwrap_or_default(),
+        )
+        .allow_empty(true)
+        .interact_text()?;
+
+    let username: String = Input::new()
+        .with_prompt(t!("proxy.add.prompt_username"))
+        .with_initial_text(
+            existing
+                .and_then(|p| p.username.clone())
+                .unwrap_or_default(),
+        )
+        .allow_empty(true)
+        .interact_text()?;
+
+    let password = if username.trim().is_empty() {
+        String::new()
+    } else {
+        Input::new()
+            .with_prompt(t!("proxy.add.prompt_password"))
+            .with_initial_text(
+                existing
+                    .and_then(|p| p.password.clone())
+                    .unwrap_or_default(),
+            )
+            .allow_empty(true)
+            .interact_text()?
+    };
+
+    let description: String = Input::new()
+        .with_prompt(t!("proxy.add.prompt_description"))
+        .with_initial_text(
+            existing
+                .and_then(|p| p.description.clone())
+                .unwrap_or_default(),
+        )
+        .allow_empty(true)
+        .interact_text()?;
+
+    Ok((
+        url.trim().to_string(),
+        non_empty(no_proxy),
+        non_empty(username),
+        non_empty(password),
+        non_empty(description),
+    ))
+}
+
+async fn add_proxy(db: &DbState) -> Result<()> {
+    println!("\n{}", t!("proxy.add.title").green().bold());
+    println!("{}", t!("common.input_cancel_hint").yellow());
+
+    let name: String = Input::new()
+        .with_prompt(t!("proxy.add.prompt_name"))
+        .allow_empty(true)
+        .interact_text()?;
+
+    if name.trim().is_empty() || name.trim().eq_ignore_ascii_case("q") {
+        println!("\n{}", t!("common.cancel").yellow());
+        return Ok(());
+    }
+
+    let (url, no_proxy, username, password, description) = prompt_proxy_fields(None)?;
+
+    let db_lock = db.lock().await;
+    let request = CreateProxyRequest {
+        name: name.trim().to_string(),
+        url,
+        no_proxy,
+        username,
+        password,
+        description,
+    };
+
+    match db_lock.create_proxy(request).await {
+        Ok(_) => {
+            println!(
+                "\n{}",
+                t!("proxy.add.success").replace("{}", name.trim()).green()
+            );
+        }
+        Err(e) => {
+            println!(
+                "\n{}",
+                t!("proxy.add.error").replace("{}", &e.to_string()).red()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+async fn select_proxy(db: &DbState, prompt: &str) -> Result<Option<Proxy>> {
+    let db_lock = db.lock().await;
+    let proxies = db_lock.get_proxies().await?;
+    drop(db_lock);
+
+    if proxies.is_empty() {
+        println!("\n{}", t!("proxy.list.no_records").yellow());
+        return Ok(None);
+    }
+
+    let mut items: Vec<String> = vec![t!("common.cancel").to_string()];
+    items.extend(proxies.iter().map(|p| format!("{} [{}]", p.name, p.url)));
+
+    let selection = Select::new()
+        .with_prompt(prompt)
+        .items(&items)
+        .interact_opt()?;
+
+    match selection {
+        Some(idx) if idx > 0 => Ok(proxies.into_iter().nth(idx - 1)),
+        _ => Ok(None),
+    }
+}
+
+async fn edit_proxy(db: &DbState) -> Result<()> {
+    let existing = match select_proxy(db, t!("proxy.edit.prompt")).await? {
+        Some(proxy) => proxy,
+        None => return Ok(()),
+    };
+
+    let name: String = Input::new()
+        .with_prompt(t!("proxy.add.prompt_name"))
+        .default(existing.name.clone())
+        .allow_empty(true)
+        .interact_text()?;
+
+    let name = if name.trim().is_empty() {
+        existing.name.clone()
+    } else {
+        name.trim().to_string()
+    };
+
+    let (url, no_proxy, username, password, description) = prompt_proxy_fields(Some(&existing))?;
+
+    // 清空的可选字段以空字符串写入
+    let db_lock = db.lock().await;
+    let request = UpdateProxyRequest {
+        name: Some(name),
+        url: Some(url),
+        no_proxy: Some(no_proxy.unwrap_or_default()),
+        username: Some(username.unwrap_or_default()),
+        password: Some(password.unwrap_or_default()),
+        description: Some(description.unwrap_or_default()),
+    };
+
+    match db_lock.update_proxy(existing.id, request).await {
+        Ok(_) => println!("\n{}", t!("proxy.edit.success").green()),
+        Err(e) => println!(
+            "\n{}",
+            t!("proxy.edit.error").replace("{}", &e.to_string()).red()
+        ),
+    }
+
+    Ok(())
+}
+
+async fn delete_proxy(db: &DbState) -> Result<()> {
+    let existing = match select_proxy(db, t!("proxy.delete.prompt")).await? {
+        Some(proxy) => proxy,
+        None => return Ok(()),
+    };
+
+    if Confirm::new()
+        .with_prompt(t!("proxy.delete.confirm").replace("{}", &existing.name))
+        .default(false)
+        .interact()?
+    {
+        let db_lock = db.lock().await;
+        match db_lock.delete_proxy(existing.id).await {
+            Ok(_) => println!("\n{}", t!("proxy.delete.success").green()),
+            Err(e) => println!(
+                "\n{}",
+                t!("proxy.delete.error").replace("{}", &e.to_string()).red()
+            ),
+        }
+    }
+
+    Ok(())
+}
+
+async fn test_proxy(db: &DbState) -> Result<()> {
+    let existing = match select_proxy(db, t!("proxy.test.prompt")).await? {
+        Some(proxy) => proxy,
+        None => return Ok(()),
+    };
+
+    let target: String = Input::new()
+        .with_prompt(t!("proxy.test.prompt_target"))
+        .default(proxy::DEFAULT_TEST_URL.to_string())
+        .interact_text()?;
+
+    println!("{}", t!("proxy.test.testing").cyan());
+
+    match proxy::test_proxy(&existing, target.trim(), Duration::from_secs(10)).await {
+        Ok(latency) => println!(
+            "\n{}",
+            t!("proxy.test.success")
+                .replace("{}", &latency.to_string())
+                .green()
+        ),
+        Err(e) => println!("\n{}", t!("proxy.test.error").replace("{}", &e).red()),
+    }
+
+    Ok(())
+}
+
+/// 选择要绑定的代理，返回 None 表示取消，Some(None) 表示解除绑定
+async fn select_proxy_for_binding(
+    db: &DbState,
+    current: Option<i64>,
+) -> Result<Option<Option<i64>>> {
+    let db_lock = db.lock().await;
+    let proxies = db_lock.get_proxies().await?;
+    drop(db_lock);
+
+    let mut items: Vec<String> = vec![
+        t!("common.cancel").to_string(),
+        t!("proxy.assign.none").to_string(),
+    ];
+    items.extend(proxies.iter().map(|p| format!("{} [{}]", p.name, p.url)));
+
+    let default = current
+        .and_then(|id| proxies.iter().position(|p| p.id == id))
+        .map(|idx| idx + 2)
+        .unwrap_or(1);
+
+    let selection = Select::new()
+        .with_prompt(t!("proxy.assign.prompt_proxy"))
+        .items(&items)
+        .default(default)
+        .interact_opt()?;
+
+    Ok(match selection {
+        Some(0) | None => None,
+        Some(1) => Some(None),
+        Some(idx) => Some(Some(proxies[idx - 2].id)),
+    })
+}
+
+/// 从列表中选择绑定对象，返回其下标
+fn select_target(prompt: &str, labels: Vec<String>) -> Result<Option<usize>> {
+    let mut items: Vec<String> = vec![t!("common.cancel").to_string()];
+    items.extend(labels);
+
+    let selection = Select::new()
+        .with_prompt(prompt)
+        .items(&items)
+        .interact_opt()?;
+
+    Ok(match selection {
+        Some(idx) if idx > 0 => Some(idx - 1),
+        _ => None,
+    })
+}
+
+fn print_assign_result<E: std::fmt::Display>(result: std::result::Result<(), E>) {
+    match result {
+        Ok(_) => println!("\n{}", t!("proxy.assign.success").green()),
+        Err(e) => println!(
+            "\n{}",
+            t!("proxy.assign.error").replace("{}", &e.to_string()).red()
+        ),
+    }
+}
+
+async fn assign_to_base_url(db: &DbState) -> Result<()> {
+    let db_lock = db.lock().await;
+    let base_urls = db_lock.get_base_urls().await?;
+    drop(db_lock);
+
+    if base_urls.is_empty() {
+        println!("\n{}", t!("url.list.no_records").yellow());
+        return Ok(());
+    }
+
+    let labels = base_urls
+        .iter()
+        .map(|b| format!("{} - {}", b.name, b.url))
+        .collect();
+    let base_url = match select_target(t!("proxy.assign.select_base_url"), labels)? {
+        Some(idx) => &base_urls[idx],
+        None => return Ok(()),
+    };
+
+    let proxy_id = match select_proxy_for_binding(db, base_url.proxy_id).await? {
+        Some(proxy_id) => proxy_id,
+        None => return Ok(()),
+    };
+
+    let db_lock = db.lock().await;
+    print_assign_result(db_lock.set_base_url_proxy(base_url.id, proxy_id).await);
+
+    Ok(())
+}
+
+async fn assign_to_account(db: &DbState) -> Result<()> {
+    let db_lock = db.lock().await;
+    let accounts_response = db_lock
+        .get_accounts(GetAccountsRequest {
+            page: Some(1),
+            per_page: Some(100),
+            search: None,
+            base_url: None,
+            ..Default::default()
+        })
+        .await?;
+    drop(db_lock);
+
+    if accounts_response.accounts.is_empty() {
+        println!("\n{}", t!("switch.no_accounts").yellow());
+        return Ok(());
+    }
+
+    let labels = accounts_response
+        .accounts
+        .iter()
+        .map(|a| format!("{} - {}", a.name, a.base_url))
+        .collect();
+    let account = match select_target(t!("switch.select_account"), labels)? {
+        Some(idx) => &accounts_response.accounts[idx],
+        None => return Ok(()),
+    };
+
+    let proxy_id = match select_proxy_for_binding(db, account.proxy_id).await? {
+        Some(proxy_id) => proxy_id,
+        None => return Ok(()),
+    };
+
+    let db_lock = db.lock().await;
+    print_assign_result(db_lock.set_account_proxy(account.id, proxy_id).await);
+
+    Ok(())
+}
+
+async fn assign_to_directory(db: &DbState) -> Result<()> {
+    let db_lock = db.lock().await;
+    let directories = db_lock.get_directories().await?;
+    drop(db_lock);
+
+    if directories.is_empty() {
+        println!("\n{}", t!("switch.no_directories").yellow());
+        return Ok(());
+    }
+
+    let labels = directories
+        .iter()
+        .map(|d| format!("{} - {}", d.name, d.path))
+        .collect();
+    let directory = match select_target(t!("switch.select_directory"), labels)? {
+        Some(idx) => &directories[idx],
+        None => return Ok(()),
+    };
+
+    let proxy_id = match select_proxy_for_binding(db, directory.proxy_id).await? {
+        Some(proxy_id) => proxy_id,
+        None => return Ok(()),
+    };
+
+    let db_lock = db.lock().await;
+    print_assign_result(db_lock.set_directory_proxy(directory.id, proxy_id).await);
+
+    Ok(())
+}
+
+async fn assign_to_webdav(db: &DbState) -> Result<()> {
+    let db_lock = db.lock().await;
+    let configs = webdav::get_webdav_configs(db_lock.get_pool()).await?;
+    drop(db_lock);
+
+    if configs.is_empty() {
+        println!("\n{}", t!("webdav.list.no_config").yellow());
+        return Ok(());
+    }
+
+    let labels = configs
+        .iter()
+        .map(|c| format!("{} - {}", c.name, c.url))
+        .collect();
+    let config = match select_target(t!("proxy.assign.select_webdav"), labels)? {
+        Some(idx) => &configs[idx],
+        None => return Ok(()),
+    };
+
+    let proxy_id = match select_proxy_for_binding(db, config.proxy_id).await? {
+        Some(proxy_id) => proxy_id,
+        None => return Ok(()),
+    };
+
+    let db_lock = db.lock().await;
+    print_assign_result(
+        webdav::set_webdav_config_proxy(db_lock.get_pool(), config.id, proxy_id).await,
+    );
+
+    Ok(())
+}
@@ -2,10 +2,14 @@ use anyhow::Result;
 use colored::Colorize;
 use dialoguer::{Input, Select};
 
+use crate::config_manager::ConfigManager;
+use crate::database::Database;
+use crate::db_backend;
 use crate::i18n::{self, Language};
+use crate::DbState;
 
 /// 设置菜单
-pub async fn settings_menu() -> Result<()> {
+pub async fn settings_menu(db: &DbState) -> Result<()> {
     loop {
         let current_lang = i18n::current_language();
         let lang_display = match current_lang {
@@ -33,10 +37,16 @@ pub async fn settings_menu() -> Result<()> {
             i18n::translate("menu.settings.current_lang").cyan(),
             lang_display.green().bold()
         );
+        println!(
+            "{}: {}",
+            i18n::translate("menu.settings.current_db").cyan(),
+            ConfigManager::new().config.current.green().bold()
+        );
         println!();
 
         let items = vec![
             i18n::translate("menu.settings.language"),
+            i18n::translate("menu.settings.database"),
             i18n::translate("menu.settings.back"),
         ];
 
@@ -59,6 +69,9 @@ pub async fn settings_menu() -> Result<()> {
                 language_settings().await?;
             }
             1 => {
+                database_settings(db).await?;
+            }
+            2 => {
                 break;
             }
             _ => unreachable!(),
@@ -128,3 +141,75 @@ async fn language_settings() -> Result<()> {
 
     Ok(())
 }
+
+/// 数据库连接设置：在 config.json 的连接之间切换，立即生效
+async fn database_settings(db: &DbState) -> Result<()> {
+    let mut config_manager = ConfigManager::new();
+    let current = config_manager.config.current.clone();
+
+    let mut names: Vec<&String> = config_manager.config.connections.keys().collect();
+    names.sort();
+    let items: Vec<String> = names
+        .iter()
+        .map(|name| {
+            let url = db_backend::redact_url(&config_manager.config.connections[*name].url);
+            if **name == current {
+                format!("{} ({}) ✓", name, url)
+            } else {
+                format!("{} ({})", name, url)
+            }
+        })
+        .collect();
+    let default_index = names.iter().position(|name| **name == current).unwrap_or(0);
+
+    let selection = match Select::new()
+        .with_prompt(format!(
+            "\n{} (ESC {})",
+            i18n::translate("menu.settings.select_db"),
+            i18n::translate("common.to_back")
+        ))
+        .items(&items)
+        .default(default_index)
+        .interact_opt()?
+    {
+        Some(sel) => sel,
+        None => return Ok(()),
+    };
+
+    let name = names[selection].clone();
+    if name == current {
+        println!(
+            "\n{}",
+            i18n::translate("menu.settings.db_already_current").yellow()
+        );
+        return Ok(());
+    }
+
+    println!("\n{}", i18n::translate("menu.settings.db_switching").cyan());
+    // 新连接在锁外完成连接和迁移，只在换用时短暂持锁
+    match Database::open_connection(&mut config_manager, &name).await {
+        Ok(new_db) => {
+            let old_pool = db.lock().await.replace_connection(new_db);
+            // 释放锁后再等待旧连接池中正在使用的连接归还并关闭
+            old_pool.close().await;
+            println!(
+                "{} {}: {}",
+                "✓".green(),
+                i18n::translate("menu.settings.db_switched").green(),
+                name.green().bold()
+            );
+        }
+        Err(e) => println!(
+            "{}: {}",
+            i18n::translate("menu.settings.db_switch_failed").red(),
+            e
+        ),
+    }
+
+    let _ = Input::<String>::new()
+        .with_prompt(format!("\n{}", i18n::translate("common.continue")))
+        .allow_empty(true)
+        .interact()?;
+
+    Ok(())
+}
@@ -1,24 +1,49 @@
-use crate::{claude_config::ClaudeConfigManager, models::*, t, DbState};
+use super::picker;
+use crate::{
+    claude_config::ClaudeConfigManager, custom_headers, custom_headers::CustomHeader, definitions,
+    hooks::merge_hooks_into_settings, key_helper, models::*, permissions, providers, proxy, t,
+    DbState,
+};
 use anyhow::Result;
 use colored::Colorize;
-use dialoguer::Select;
 use std::fs;
 use std::path::Path;
 
+/// 切换时写入 settings.local.json 的账号、供应商、代理和 Hook 配置
+struct SwitchSettings<'a> {
+    account: &'a Account,
+    api_key_name: &'a str,
+    provider_type: &'a str,
+    // URL 默认环境变量与账号自定义环境变量合并后的结果
+    env_vars: &'a [(String, String)],
+    headers: &'a [CustomHeader],
+    // 权限预设或跳过权限选项，None 表示沿用 Claude 配置中的权限
+    permissions: Option<serde_json::Value>,
+    use_proxy: bool,
+    bound_proxy: Option<&'a Proxy>,
+    hooks: &'a [Hook],
+}
+
 // 写入 Claude 配置到 .claude/settings.local.json
 fn write_claude_settings(
     directory_path: &str,
     claude_settings_json: &str,
-    account_token: &str,
-    account_base_url: &str,
-    account_model: &str,
-    account_name: &str,
-    api_key_name: &str,
-    skip_permissions: bool,
-    use_proxy: bool,
+    options: SwitchSettings,
 ) -> Result<()> {
     use serde_json::Value;
 
+    let SwitchSettings {
+        account,
+        api_key_name,
+        provider_type,
+        env_vars,
+        headers,
+        permissions,
+        use_proxy,
+        bound_proxy,
+        hooks,
+    } = options;
+
     // 解析 Claude 配置
     let mut claude_settings: Value = serde_json::from_str(claude_settings_json)?;
 
@@ -27,27 +52,26 @@ fn write_claude_settings(
         claude_settings = serde_json::json!({});
     }
 
+    // 修正旧版本中无效的 defaultMode
+    permissions::normalize_permission_mode(&mut claude_settings);
+
     let settings_obj = claude_settings.as_object_mut().unwrap();
 
-    // 设置权限配置
-    if skip_permissions {
-        settings_obj.insert(
-            "permissions".to_string(),
-            serde_json::json!({
-                "defaultMode": "bypassPermissions",
-                "allow": ["*"]
-            }),
-        );
-    } else {
-        // 如果不跳过权限，使用默认的权限配置
-        if !settings_obj.contains_key("permissions") {
-            settings_obj.insert(
-                "permissions".to_string(),
-                serde_json::json!({
-                    "defaultMode": "prompt",
-                    "allow": []
-                }),
-            );
+    // 设置权限配置：优先使用权限预设或跳过权限选项，否则沿用 Claude 配置中的权限
+    match permissions {
+        Some(permissions) => {
+            settings_obj.insert("permissions".to_string(), permissions);
+        }
+        None => {
+            if !settings_obj.contains_key("permissions") {
+                settings_obj.insert(
+                    "permissions".to_string(),
+                    serde_json::json!({
+                        "defaultMode": "default",
+                        "allow": []
+                    }),
+                );
+            }
         }
     }
 
@@ -62,25 +86,27 @@ fn write_claude_settings(
         .as_object_mut()
         .unwrap();
 
-    // 添加账号相关的环境变量 - 根据 api_key_name 参数决定使用哪个环境变量名
-    env_obj.insert(
-        api_key_name.to_string(),
-        Value::String(account_token.to_string()),
-    );
-    env_obj.insert(
-        "ANTHROPIC_BASE_URL".to_string(),
-        Value::String(account_base_url.to_string()),
-    );
-    env_obj.insert(
-        "USER_NAME".to_string(),
-        Value::String(account_name.to_string()),
+    // 添加账号相关的环境变量 - 按 URL 的供应商类型写入开关、地址和令牌
+    providers::apply_provider_env(
+        env_obj,
+        provider_type,
+        api_key_name,
+        &account.token,
+        &account.base_url,
     );
+    // URL 默认环境变量和账号自定义环境变量，账号覆盖同名变量
+    for (key, value) in env_vars {
+        env_obj.insert(key.clone(), parse_env_value(value));
+    }
+    // 写入 API 地址和账号的自定义请求头
+    custom_headers::apply_headers_to_env(env_obj, headers);
+    env_obj.insert("USER_NAME".to_string(), Value::String(account.name.clone()));
 
     // 添加模型配置（如果账号设置了模型）
-    if !account_model.is_empty() {
+    if !account.model.is_empty() {
         env_obj.insert(
             "ANTHROPIC_MODEL".to_string(),
-            Value::String(account_model.to_string()),
+            Value::String(account.model.clone()),
         );
     }
 
@@ -116,6 +142,17 @@ fn write_claude_settings(
         }),
     );
 
+    // 目录、账号或 API 地址绑定了代理时，使用该代理覆盖 Claude 配置中的代理
+    if let Some(bound_proxy) = bound_proxy {
+        proxy::apply_proxy_to_settings(&mut claude_settings, bound_proxy);
+    }
+
+    // 辅助命令模式的账号写入 apiKeyHelper，不写入静态令牌
+    key_helper::apply_key_helper_to_settings(&mut claude_settings, account, api_key_name);
+
+    // 合并 Hook 配置（全局 Hook + 目录绑定的 Hook）
+    merge_hooks_into_settings(&mut claude_settings, hooks);
+
     // 创建 .claude 目录
     let claude_dir = Path::new(directory_path).join(".claude");
     fs::create_dir_all(&claude_dir)?;
@@ -138,21 +175,13 @@ fn write_claude_settings(
 pub async fn switch_menu(db: &DbState) -> Result<()> {
     println!("\n{}", t!("switch.title").green().bold());
 
-    // 获取所有账号
+    // 账号和目录均按最近使用排序
     let db_lock = db.lock().await;
-    let accounts_response = db_lock
-        .get_accounts(GetAccountsRequest {
-            page: Some(1),
-            per_page: Some(100),
-            search: None,
-            base_url: None,
-        })
-        .await?;
-
-    let directories = db_lock.get_directories().await?;
+    let accounts = db_lock.get_accounts_by_recent_use(true).await?;
+    let directories = db_lock.get_directories_by_recent_use().await?;
     drop(db_lock);
 
-    if accounts_response.accounts.is_empty() {
+    if accounts.is_empty() {
         println!("\n{}", t!("switch.no_accounts").yellow());
         return Ok(());
     }
@@ -162,47 +191,27 @@ pub async fn switch_menu(db: &DbState) -> Result<()> {
         return Ok(());
     }
 
-    // 选择账号
-    let mut account_items: Vec<String> = vec![t!("common.back_cancel").to_string()];
-    account_items.extend(
-        accounts_response
-            .accounts
-            .iter()
-            .map(|a| format!("{} - {}", a.name, a.base_url)),
-    );
-
-    let account_selection = Select::new()
-        .with_prompt(t!("switch.select_account"))
-        .items(&account_items)
-        .interact_opt()?;
-
-    if account_selection.is_none() || account_selection == Some(0) {
-        return Ok(());
-    }
-
-    let account = &accounts_response.accounts[account_selection.unwrap() - 1];
+    // 选择目录，预选当前工作目录对应的目录
+    let directory = match picker::pick_directory(
+        t!("switch.select_directory"),
+        &directories,
+        picker::directory_for_cwd(&directories),
+    )? {
+        Some(directory) => directory,
+        None => return Ok(()),
+    };
 
-    // 选择目录
-    let mut directory_items: Vec<String> = vec![t!("common.back_cancel").to_string()];
-    directory_items.extend(directories.iter().map(|d| {
-        let exists = if std::path::Path::new(&d.path).exists() {
-            "✓"
-        } else {
-            "✗"
+    // 选择账号，预选该目录上一次使用的账号
+    let last_account_id = db
+        .lock()
+        .await
+        .get_last_account_for_directory(directory.id)
+        .await?;
+    let account =
+        match picker::pick_account(t!("switch.select_account"), &accounts, last_account_id)? {
+            Some(account) => account,
+            None => return Ok(()),
         };
-        format!("{} {} - {}", exists, d.name, d.path)
-    }));
-
-    let directory_selection = Select::new()
-        .with_prompt(t!("switch.select_directory"))
-        .items(&directory_items)
-        .interact_opt()?;
-
-    if directory_selection.is_none() || directory_selection == Some(0) {
-        return Ok(());
-    }
-
-    let directory = &directories[directory_selection.unwrap() - 1];
 
     // 检查目标目录是否存在 CLAUDE.local.md ��件
     let config_manager = ClaudeConfigManager::new(directory.path.clone());
@@ -216,17 +225,68 @@ pub async fn switch_menu(db: &DbState) -> Result<()> {
         false
     };
 
-    // 询问权限配置
-    let skip_permissions = dialoguer::Confirm::new()
-        .with_prompt(t!("switch.prompt_skip_permissions"))
-        .default(true)
-        .interact()?;
+    // 目录或账号绑定了权限预设时直接使用，否则询问是否跳过权限检查
+    let db_lock = db.lock().await;
+    let permission_preset = db_lock
+        .get_permission_preset_for_switch(account.id, directory.id)
+        .await
+        .unwrap_or_default();
+    drop(db_lock);
 
-    // 询问是否使用代理
-    let use_proxy = dialoguer::Confirm::new()
-        .with_prompt(t!("switch.prompt_use_proxy"))
-        .default(false)
-        .interact()?;
+    let (permissions, permission_label) = match &permission_preset {
+        Some(preset) => {
+            println!(
+                "\n{}",
+                t!("switch.using_permission_preset")
+                    .replace("{}", &preset.name)
+                    .cyan()
+            );
+            (
+                Some(preset.to_permissions_json()),
+                t!("switch.permission_preset").replace("{}", &preset.name),
+            )
+        }
+        None => {
+            let skip_permissions = dialoguer::Confirm::new()
+                .with_prompt(t!("switch.prompt_skip_permissions"))
+                .default(true)
+                .interact()?;
+            if skip_permissions {
+                (
+                    Some(serde_json::json!({
+                        "defaultMode": "bypassPermissions",
+                        "allow": ["*"]
+                    })),
+                    t!("switch.permission_skipped").to_string(),
+                )
+            } else {
+                (None, t!("switch.permission_required").to_string())
+            }
+        }
+    };
+
+    // 目录、账号或 API 地址绑定了代理时直接使用，否则询问是否沿用 Claude 配置中的代理
+    let bound_proxy = db
+        .lock()
+        .await
+        .get_proxy_for_switch(account.id, Some(directory.id))
+        .await
+        .unwrap_or_default();
+    let use_proxy = match &bound_proxy {
+        Some(bound_proxy) => {
+            println!(
+                "\n{}",
+                t!("switch.using_proxy")
+                    .replace("{}", &bound_proxy.name)
+                    .cyan()
+            );
+            true
+        }
+        None => dialoguer::Confirm::new()
+            .with_prompt(t!("switch.prompt_use_proxy"))
+            .default(false)
+            .interact()?,
+    };
 
     // 执行切换
     println!("\n{}", t!("switch.switching").cyan());
@@ -242,12 +302,16 @@ pub async fn switch_menu(db: &DbState) -> Result<()> {
             // 获取所有 BaseUrl 列表
             let base_urls = db_lock.get_base_urls().await?;
 
-            // 查找与 account.base_url 匹配的 BaseUrl，获取其 api_key
-            let api_key_name = base_urls
-                .iter()
-                .find(|bu| bu.url == account.base_url)
+            // 查找与 account.base_url 匹配的 BaseUrl，获取其 api_key 和供应商类型
+            let matched_base_url = base_urls.iter().find(|bu| bu.url == account.base_url);
+            let api_key_name = matched_base_url
                 .map(|bu| bu.api_key.clone())
                 .unwrap_or_else(|| "ANTHROPIC_API_KEY".to_string());
+            let provider_type = matched_base_url
+                .map(|bu| bu.provider_type.clone())
+                .unwrap_or_else(|| providers::DEFAULT_PROVIDER.to_string());
+            let env_vars = extra_env_vars(matched_base_url, account);
+            let headers = custom_headers::effective_headers(matched_base_url, Some(account));
 
             // 获取 Claude 配置
             let claude_settings_json = match db_lock.get_claude_settings().await {
@@ -261,10 +325,6 @@ pub async fn switch_menu(db: &DbState) -> Result<()> {
                     );
                     // 使用默认配置
                     serde_json::to_string(&serde_json::json!({
-                        "permissions": {
-                            "defaultMode": "bypassPermissions",
-                            "allow": ["*"]
-                        },
                         "env": {
                             "CLAUDE_CODE_BUBBLEWRAP": "1",
                             "DISABLE_AUTOUPDATER": 1
@@ -274,10 +334,30 @@ pub async fn switch_menu(db: &DbState) -> Result<()> {
                 }
             };
 
+            // 获取需要写入的 Hook
+            let hooks = db_lock
+                .get_hooks_for_switch(directory.id)
+                .await
+                .unwrap_or_default();
+
+            // 获取目录和账号绑定的 MCP 服务器
+            let mcp_servers = db_lock
+                .get_mcp_servers_for_switch(account.id, directory.id)
+                .await
+                .unwrap_or_default();
+
+            // 获取目录启用的命令和子代理定义
+            let claude_definitions = db_lock
+                .get_definitions_for_switch(directory.id)
+                .await
+                .unwrap_or_default();
+
             drop(db_lock);
 
             // 更新环境配置文件
             let config_manager = ClaudeConfigManager::new(directory.path.clone());
+            // settings.local.json 会被重写，先记录已启用的 MCP 服务器以便合并
+            let previously_enabled_mcp = config_manager.get_enabled_mcp_servers();
             match config_manager.update_env_config_with_options_ex(
                 account.token.clone(),
                 account.base_url.clone(),
@@ -289,42 +369,100 @@ pub async fn switch_menu(db: &DbState) -> Result<()> {
                     match write_claude_settings(
                         &directory.path,
                         &claude_settings_json,
-                        &account.token,
-                        &account.base_url,
-                        &account.model,
-                        &account.name,
-                        &api_key_name,
-                        skip_permissions,
-                        use_proxy,
+                        SwitchSettings {
+                            account,
+                            api_key_name: &api_key_name,
+                            provider_type: &provider_type,
+                            env_vars: &env_vars,
+                            headers: &headers,
+                            permissions,
+                            use_proxy,
+                            bound_proxy: bound_proxy.as_ref(),
+                            hooks: &hooks,
+                        },
                     ) {
                         Ok(_) => {
+                            // 写入 MCP 服务器配置（与已有配置合并）
+                            if let Err(e) = config_manager
+                                .apply_mcp_servers(&mcp_servers, previously_enabled_mcp)
+                            {
+                                println!(
+                                    "\n{}",
+                                    t!("switch.warn_mcp_fail")
+                                        .replace("{}", &e.to_string())
+                                        .yellow()
+                                );
+                            }
+
+                            // 部署命令和子代理定义，并移除已禁用的定义
+                            let definition_vars = definitions::template_vars(account, directory);
+                            if let Err(e) = config_manager
+                                .apply_definitions(&claude_definitions, &definition_vars)
+                            {
+                                println!(
+                                    "\n{}",
+                                    t!("switch.warn_definitions_fail")
+                                        .replace("{}", &e.to_string())
+                                        .yellow()
+                                );
+                            }
+
                             println!("\n{}", t!("switch.success").green().bold());
                             println!("{}", t!("switch.account").replace("{}", &account.name));
                             println!("{}", t!("switch.directory").replace("{}", &directory.name));
                             println!("{}", t!("switch.path").replace("{}", &directory.path));
                             println!("{}", t!("switch.sandbox"));
-                            println!(
-                                "{}",
-                                t!("switch.permission").replace(
+                            if account.uses_key_helper() {
+                                println!(
                                     "{}",
-                                    if skip_permissions {
-                                        t!("switch.permission_skipped")
-                                    } else {
-                                        t!("switch.permission_required")
-                                    }
-                                )
-                            );
+                                    t!("switch.key_helper").replace(
+                                        "{}",
+                                        account.api_key_helper.as_deref().unwrap_or("")
+                                    )
+                                );
+                            }
+                            if provider_type != providers::DEFAULT_PROVIDER {
+                                let provider_name = providers::provider_schema(&provider_type)
+                                    .map(|p| p.name)
+                                    .unwrap_or(provider_type.as_str());
+                                println!("{}", t!("switch.provider").replace("{}", provider_name));
+                            }
+                            if !headers.is_empty() {
+                                let names: Vec<&str> =
+                                    headers.iter().map(|h| h.name.as_str()).collect();
+                                println!(
+                                    "{}",
+                                    t!("switch.headers").replace("{}", &names.join(", "))
+                                );
+                            }
                             println!(
                                 "{}",
-                                t!("switch.proxy").replace(
-                                    "{}",
-                                    if use_proxy {
-                                        t!("switch.proxy_enabled")
-                                    } else {
-                                        t!("switch.proxy_disabled")
-                                    }
-                                )
+                                t!("switch.permission").replace("{}", &permission_label)
                             );
+                            let proxy_label = match &bound_proxy {
+                                Some(bound_proxy) => bound_proxy.name.as_str(),
+                                None if use_proxy => t!("switch.proxy_enabled"),
+                                None => t!("switch.proxy_disabled"),
+                            };
+                            println!("{}", t!("switch.proxy").replace("{}", proxy_label));
+                            if !claude_definitions.is_empty() {
+                                let names: Vec<String> = claude_definitions
+                                    .iter()
+                                    .map(|d| d.relative_path())
+                                    .collect();
+                                println!(
+                                    "{}",
+                                    t!("switch.definitions").replace("{}", &names.join(", "))
+                                );
+                            }
+                            if !mcp_servers.is_empty() {
+                                let names: Vec<&str> =
+                                    mcp_servers.iter().map(|m| m.name.as_str()).collect();
+                                println!(
+                                    "{}",
+                                    t!("switch.mcp_servers").replace("{}", &names.join(", "))
+                                );
+                            }
                         }
                         Err(e) => {
                             println!("\n{}", t!("switch.success_env").green().bold());
@@ -1,4 +1,6 @@
-use crate::{models::*, t, webdav, DbState};
+use super::import_review;
+use crate::import_engine::{self, ExistingRecords};
+use crate::{importers, models::*, t, webdav, DbState};
 use anyhow::Result;
 use colored::Colorize;
 use comfy_table::{Attribute, Cell, Color};
@@ -51,6 +53,14 @@ pub async fn webdav_menu(db: &DbState) -> Result<()> {
     Ok(())
 }
 
+/// 根据配置创建 WebDAV 客户端，使用配置绑定的代理
+async fn connect(db: &DbState, config: &WebDavConfig) -> Result<webdav::WebDavManager> {
+    let db_lock = db.lock().await;
+    let proxy = webdav::get_webdav_config_proxy(db_lock.get_pool(), config).await?;
+    drop(db_lock);
+    webdav::WebDavManager::from_config(config.clone(), proxy.as_ref()).await
+}
+
 async fn list_configs(db: &DbState) -> Result<()> {
     let db_lock = db.lock().await;
     let pool = db_lock.get_pool();
@@ -233,7 +243,7 @@ async fn test_connection(db: &DbState) -> Result<()> {
 
         println!("\n{}", t!("webdav.test.testing").cyan());
 
-        let manager = webdav::WebDavManager::from_config(config.clone()).await?;
+        let manager = connect(db, config).await?;
 
         match manager.test_connection().await {
             Ok(_) => {
@@ -302,6 +312,7 @@ async fn upload_config(db: &DbState) -> Result<()> {
                 per_page: Some(1000),
                 search: None,
                 base_url: None,
+                ..Default::default()
             })
             .await?;
 
@@ -318,7 +329,7 @@ async fn upload_config(db: &DbState) -> Result<()> {
             "exported_at": chrono::Utc::now().to_rfc3339(),
         });
 
-        let manager = webdav::WebDavManager::from_config(config.clone()).await?;
+        let manager = connect(db, config).await?;
 
         match manager.upload_config(&data, &filename).await {
             Ok(_) => {
@@ -394,7 +405,7 @@ async fn download_config(db: &DbState) -> Result<()> {
 
         // 列出远程文件
         println!("\n{}", t!("webdav.download.getting_files").cyan());
-        let manager = webdav::WebDavManager::from_config(config.clone()).await?;
+        let manager = connect(db, config).await?;
         let files = manager.list_remote_files().await?;
 
         if files.is_empty() {
@@ -421,100 +432,23 @@ async fn download_config(db: &DbState) -> Result<()> {
 
             match manager.download_config(filename).await {
                 Ok(data) => {
-                    // 导入配置到数据库
-                    let db_lock = db.lock().await;
-                    let pool = db_lock.get_pool();
-
-                    // 先删除所有现有账号和 Base URLs,实现完全覆盖
-                    println!("\n{}", t!("webdav.upload.clearing").yellow());
-
-                    let _ = sqlx::query("DELETE FROM accounts").execute(pool).await;
-
-                    let _ = sqlx::query("DELETE FROM base_urls").execute(pool).await;
-
-                    println!("{}", t!("webdav.upload.cleared").green());
-
-                    // 解析账号数据
-                    if let Some(accounts_array) = data.get("accounts").and_then(|v| v.as_array()) {
-                        println!("\n{}", t!("webdav.upload.importing_accounts").cyan());
-                        let mut success_count = 0;
-
-                        for account_data in accounts_array {
-                            if let (Some(name), Some(token), Some(base_url)) = (
-                                account_data.get("name").and_then(|v| v.as_str()),
-                                account_data.get("token").and_then(|v| v.as_str()),
-                                account_data.get("base_url").and_then(|v| v.as_str()),
-                            ) {
-                                let model = account_data
-                                    .get("model")
-                                    .and_then(|v| v.as_str())
-                                    .unwrap_or("claude-sonnet-4-20250514");
-
-                                let request = CreateAccountRequest {
-                                    name: name.to_string(),
-                                    token: token.to_string(),
-                                    base_url: base_url.to_string(),
-                                    model: model.to_string(),
-                                    custom_env_vars: None,
-                                };
-
-                                if let Ok(_) = db_lock.create_account(request).await {
-                                    success_count += 1;
-                                }
-                            }
+                    // 与已有记录逐项比对，按选择的策略导入
+                    let bundle = match importers::parse_backup(&data) {
+                        Ok(bundle) => bundle,
+                        Err(e) => {
+                            println!("{}", t!("webdav.download.error").replace("{}", &e).red());
+                            return Ok(());
                         }
+                    };
+                    let existing = ExistingRecords::load(&*db.lock().await).await?;
+                    let items = import_engine::plan(&bundle, &existing);
+                    let report = match import_review::review_and_apply(db, &bundle, items).await? {
+                        Some(report) => report,
+                        None => return Ok(()),
+                    };
+                    import_review::print_report(&report);
 
-                        println!(
-                            "{}",
-                            t!("webdav.upload.imported_accounts")
-                                .replace("{}", &success_count.to_string())
-                                .green()
-                        );
-                    }
-
-                    // 解析 Base URLs 数据
-                    if let Some(base_urls_array) = data.get("base_urls").and_then(|v| v.as_array())
-                    {
-                        println!("\n{}", t!("webdav.upload.importing_urls").cyan());
-                        let mut success_count = 0;
-
-                        for base_url_data in base_urls_array {
-                            if let (Some(name), Some(url)) = (
-                                base_url_data.get("name").and_then(|v| v.as_str()),
-                                base_url_data.get("url").and_then(|v| v.as_str()),
-                            ) {
-                                let description = base_url_data
-                                    .get("description")
-                                    .and_then(|v| v.as_str())
-                                    .map(|s| s.to_string());
-                                let api_key = base_url_data
-                                    .get("api_key")
-                                    .and_then(|v| v.as_str())
-                                    .map(|s| s.to_string());
-                                let is_default =
-                                    base_url_data.get("is_default").and_then(|v| v.as_bool());
-
-                                let request = CreateBaseUrlRequest {
-                                    name: name.to_string(),
-                                    url: url.to_string(),
-                                    description,
-                                    api_key,
-                                    is_default,
-                                };
-
-                                if let Ok(_) = db_lock.create_base_url(request).await {
-                                    success_count += 1;
-                                }
-                            }
-                        }
-
-                        println!(
-                            "{}",
-                            t!("webdav.upload.imported_urls")
-                                .replace("{}", &success_count.to_string())
-                                .green()
-                        );
-                    }
+                    let db_lock = db.lock().await;
 
                     // 解析 Claude 设置数据
                     if let Some(claude_settings) = data.get("claude_settings") {
@@ -596,7 +530,7 @@ async fn list_remote_files(db: &DbState) -> Result<()> {
 
         println!("\n{}", t!("webdav.download.getting_files").cyan());
 
-        let manager = webdav::WebDavManager::from_config(config.clone()).await?;
+        let manager = connect(db, config).await?;
 
         match manager.list_remote_files().await {
             Ok(files) => {
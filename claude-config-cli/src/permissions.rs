@@ -0,0 +1,275 @@
+use crate::models::PermissionPreset;
+use serde_json::{json, Value};
+
+/// Claude Code 支持的 defaultMode 取值
+pub const PERMISSION_MODES: [&str; 4] = ["default", "acceptEdits", "plan", "bypassPermissions"];
+
+/// Claude Code 内置工具名称，用于校验权限规则
+pub const KNOWN_TOOLS: [&str; 17] = [
+    "Bash",
+    "BashOutput",
+    "Edit",
+    "ExitPlanMode",
+    "Glob",
+    "Grep",
+    "KillBash",
+    "KillShell",
+    "LS",
+    "MultiEdit",
+    "NotebookEdit",
+    "Read",
+    "Task",
+    "TodoWrite",
+    "WebFetch",
+    "WebSearch",
+    "Write",
+];
+
+/// 内置预设：(名称, defaultMode, allow, deny, ask, 描述)
+type BuiltinPreset = (
+    &'static str,
+    &'static str,
+    &'static [&'static str],
+    &'static [&'static str],
+    &'static [&'static str],
+    &'static str,
+);
+
+pub const BUILTIN_PRESETS: [BuiltinPreset; 3] = [
+    (
+        "read-only",
+        "default",
+        &[
+            "Read",
+            "Glob",
+            "Grep",
+            "LS",
+            "WebSearch",
+            "WebFetch",
+            "TodoWrite",
+        ],
+        &["Edit", "MultiEdit", "Write", "NotebookEdit"],
+        &["Bash"],
+        "只读：允许读取和搜索，禁止修改文件，执行命令需确认",
+    ),
+    (
+        "safe-dev",
+        "acceptEdits",
+        &[
+            "Read",
+            "Glob",
+            "Grep",
+            "LS",
+            "Edit",
+            "MultiEdit",
+            "Write",
+            "TodoWrite",
+            "Bash(git status)",
+            "Bash(git diff:*)",
+            "Bash(git log:*)",
+            "Bash(npm run test:*)",
+            "Bash(cargo build:*)",
+            "Bash(cargo test:*)",
+        ],
+        &["Bash(rm -rf:*)", "Read(./.env)", "Read(./.env.*)"],
+        &["Bash(git push:*)", "WebFetch"],
+        "安全开发：自动批准编辑和常用构建命令，危险操作需确认",
+    ),
+    (
+        "full-bypass",
+        "bypassPermissions",
+        &["*"],
+        &[],
+        &[],
+        "完全放开：跳过所有权限检查（仅限可信环境）",
+    ),
+];
+
+/// 校验 defaultMode 是否为 Claude Code 支持的值
+pub fn validate_permission_mode(mode: &str) -> Result<(), String> {
+    if PERMISSION_MODES.contains(&mode) {
+        Ok(())
+    } else {
+        Err(format!(
+            "无效的权限模式: {}（可选: {}）",
+            mode,
+            PERMISSION_MODES.join(", ")
+        ))
+    }
+}
+
+/// 校验单条权限规则，支持以下写法：
+/// `*`、`Tool`、`Tool(specifier)`、`mcp__server`、`mcp__server__tool`
+pub fn validate_permission_rule(rule: &str) -> Result<(), String> {
+    let rule = rule.trim();
+
+    if rule.is_empty() {
+        return Err("权限规则不能为空".to_string());
+    }
+    if rule == "*" {
+        return Ok(());
+    }
+
+    let (tool, specifier) = match rule.find('(') {
+        Some(start) => {
+            if !rule.ends_with(')') {
+                return Err(format!("权限规则缺少右括号: {}", rule));
+            }
+            (&rule[..start], Some(&rule[start + 1..rule.len() - 1]))
+        }
+        None => {
+            if rule.contains(')') {
+                return Err(format!("权限规则括号不匹配: {}", rule));
+            }
+            (rule, None)
+        }
+    };
+
+    if tool.starts_with("mcp__") {
+        let valid = tool.trim_start_matches("mcp__").split("__").all(|part| {
+            !part.is_empty()
+                && part
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+        });
+        if !valid || specifier.is_some() {
+            return Err(format!("无效的 MCP 权限规则: {}", rule));
+        }
+        return Ok(());
+    }
+
+    if !KNOWN_TOOLS.contains(&tool) {
+        return Err(format!("未知的工具名称: {}", tool));
+    }
+
+    if let Some(specifier) = specifier {
+        if specifier.trim().is_empty() {
+            return Err(format!("权限规则的参数不能为空: {}", rule));
+        }
+        if tool == "WebFetch" && !specifier.starts_with("domain:") {
+            return Err(format!("WebFetch 规则必须使用 domain: 前缀: {}", rule));
+        }
+    }
+
+    Ok(())
+}
+
+/// 校验一组权限规则，返回第一个错误
+pub fn validate_permission_rules(rules: &[String]) -> Result<(), String> {
+    rules
+        .iter()
+        .try_for_each(|rule| validate_permission_rule(rule))
+}
+
+/// 校验完整的权限预设
+pub fn validate_permission_preset(
+    default_mode: &str,
+    allow: &[String],
+    deny: &[String],
+    ask: &[String],
+) -> Result<(), String> {
+    validate_permission_mode(default_mode)?;
+    validate_permission_rules(allow)?;
+    validate_permission_rules(deny)?;
+    validate_permission_rules(ask)?;
+    Ok(())
+}
+
+impl PermissionPreset {
+    pub fn allow_rules(&self) -> Vec<String> {
+        serde_json::from_str(&self.allow).unwrap_or_default()
+    }
+
+    pub fn deny_rules(&self) -> Vec<String> {
+        serde_json::from_str(&self.deny).unwrap_or_default()
+    }
+
+    pub fn ask_rules(&self) -> Vec<String> {
+        serde_json::from_str(&self.ask).unwrap_or_default()
+    }
+
+    /// 生成写入 settings.local.json 的 permissions 段
+    pub fn to_permissions_json(&self) -> Value {
+        let mut permissions = json!({
+            "defaultMode": self.default_mode,
+            "allow": self.allow_rules(),
+        });
+        let deny = self.deny_rules();
+        if !deny.is_empty() {
+            permissions["deny"] = json!(deny);
+        }
+        let ask = self.ask_rules();
+        if !ask.is_empty() {
+            permissions["ask"] = json!(ask);
+        }
+        permissions
+    }
+}
+
+/// 修正旧版本写入的无效 defaultMode（如 "prompt"、"normal"）
+pub fn normalize_permission_mode(settings: &mut Value) {
+    if let Some(mode) = settings
+        .get_mut("permissions")
+        .and_then(|p| p.get_mut("defaultMode"))
+    {
+        let valid = mode
+            .as_str()
+            .map(|m| PERMISSION_MODES.contains(&m))
+            .unwrap_or(false);
+        if !valid {
+            *mode = json!("default");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_permission_rule() {
+        assert!(validate_permission_rule("*").is_ok());
+        assert!(validate_permission_rule("Read").is_ok());
+        assert!(validate_permission_rule("Bash(npm run test:*)").is_ok());
+        assert!(validate_permission_rule("Read(./.env)").is_ok());
+        assert!(validate_permission_rule("WebFetch(domain:example.com)").is_ok());
+        assert!(validate_permission_rule("mcp__github").is_ok());
+        assert!(validate_permission_rule("mcp__github__create_issue").is_ok());
+
+        assert!(validate_permission_rule("").is_err());
+        assert!(validate_permission_rule("Unknown").is_err());
+        assert!(validate_permission_rule("Bash(").is_err());
+        assert!(validate_permission_rule("Bash()").is_err());
+        assert!(validate_permission_rule("WebFetch(example.com)").is_err());
+        assert!(validate_permission_rule("mcp__").is_err());
+    }
+
+    #[test]
+    fn test_validate_permission_mode() {
+        for mode in PERMISSION_MODES {
+            assert!(validate_permission_mode(mode).is_ok());
+        }
+        assert!(validate_permission_mode("prompt").is_err());
+        assert!(validate_permission_mode("normal").is_err());
+    }
+
+    #[test]
+    fn test_builtin_presets_are_valid() {
+        for (name, mode, allow, deny, ask, _) in BUILTIN_PRESETS {
+            let to_vec = |rules: &[&str]| rules.iter().map(|r| r.to_string()).collect::<Vec<_>>();
+            assert!(
+                validate_permission_preset(mode, &to_vec(allow), &to_vec(deny), &to_vec(ask))
+                    .is_ok(),
+                "内置预设 {} 校验失败",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_normalize_permission_mode() {
+        let mut settings = json!({ "permissions": { "defaultMode": "prompt", "allow": [] } });
+        normalize_permission_mode(&mut settings);
+        assert_eq!(settings["permissions"]["defaultMode"], "default");
+    }
+}
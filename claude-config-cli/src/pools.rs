@@ -0,0 +1,524 @@
+use crate::claude_config::ClaudeConfigManager;
+use crate::database::Database;
+use crate::drift;
+use crate::health;
+use crate::models::*;
+use serde_json::json;
+use sqlx::Error as SqlxError;
+use std::collections::HashSet;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 账号是否可用：已启用且未过期，令牌未被标记为无效，且所在 API 地址最近一次健康检查没有失败
+/// 校验出错（网络超时等）不代表令牌失效，不影响可用性
+pub fn is_available(account: &Account, failing_base_urls: &HashSet<String>) -> bool {
+    account.enabled
+        && !account.is_expired()
+        && account.token_status.as_deref() != Some(health::TOKEN_INVALID)
+        && !failing_base_urls.contains(&account.base_url)
+}
+
+/// 从候选账号中选择一个：只考虑优先级最高（priority 最小）的一组，组内按权重加权选择
+/// seed 决定加权选择的落点，相同的输入总是得到相同的结果
+pub fn pick_account<'a>(
+    candidates: &[(&AccountPoolMember, &'a Account)],
+    seed: u64,
+) -> Option<&'a Account> {
+    let best = candidates.iter().map(|(m, _)| m.priority).min()?;
+    let group: Vec<&(&AccountPoolMember, &Account)> = candidates
+        .iter()
+        .filter(|(m, _)| m.priority == best)
+        .collect();
+
+    let total: i64 = group.iter().map(|(m, _)| m.weight.max(0)).sum();
+    if total == 0 {
+        return group.first().map(|(_, a)| *a);
+    }
+
+    let mut point = (seed % total as u64) as i64;
+    for (member, account) in &group {
+        let weight = member.weight.max(0);
+        if point < weight {
+            return Some(*account);
+        }
+        point -= weight;
+    }
+    group.last().map(|(_, a)| *a)
+}
+
+fn random_seed() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0)
+}
+
+/// 最近一次健康检查失败的 API 地址
+async fn failing_base_urls(db: &Database) -> Result<HashSet<String>, SqlxError> {
+    let base_urls = db.get_base_urls().await?;
+    Ok(db
+        .get_latest_endpoint_checks()
+        .await?
+        .into_iter()
+        .filter(|check| !check.success)
+        .filter_map(|check| {
+            base_urls
+                .iter()
+                .find(|bu| bu.id == check.base_url_id)
+                .map(|bu| bu.url.clone())
+        })
+        .collect())
+}
+
+/// 在账号池中选择一个可用账号，exclude 中的账号不参与选择
+pub async fn select_pool_account(
+    db: &Database,
+    pool_id: i64,
+    exclude: &[i64],
+) -> Result<Option<Account>, SqlxError> {
+    let members = db.get_account_pool_members(Some(pool_id)).await?;
+    let accounts = db.get_all_accounts().await?;
+    let failing = failing_base_urls(db).await?;
+
+    let candidates: Vec<(&AccountPoolMember, &Account)> = members
+        .iter()
+        .filter(|m| !exclude.contains(&m.account_id))
+        .filter_map(|m| {
+            accounts
+                .iter()
+                .find(|a| a.id == m.account_id)
+                .map(|a| (m, a))
+        })
+        .filter(|(_, a)| is_available(a, &failing))
+        .collect();
+
+    Ok(pick_account(&candidates, random_seed()).cloned())
+}
+
+/// 将目录切换到指定账号：更新数据库中的绑定，并把账号的环境变量写入目录配置
+pub async fn apply_account(
+    db: &Database,
+    directory: &Directory,
+    account: &Account,
+) -> anyhow::Result<()> {
+    let previous_token_key = drift::expected_state(db, directory)
+        .await?
+        .map(|state| state.token_key);
+
+    db.switch_account(SwitchAccountRequest {
+        account_id: account.id,
+        directory_id: directory.id,
+    })
+    .await?;
+
+    let expected = drift::expected_state(db, directory)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("目录 {} 没有绑定账号", directory.name))?;
+    drift::fix_drift(directory, &expected)?;
+
+    let config_manager = ClaudeConfigManager::new(directory.path.clone());
+    config_manager.merge_env(&[("USER_NAME".to_string(), json!(account.name))])?;
+    // 新账号使用不同的令牌变量名时移除旧的令牌
+    if let Some(previous) = previous_token_key.filter(|key| *key != expected.token_key) {
+        config_manager.remove_env(&[previous.as_str()])?;
+    }
+
+    Ok(())
+}
+
+/// 账号失效后，把正在使用这些账号且绑定了账号池的目录切换到池中的下一个可用账号
+pub async fn failover_accounts(
+    db: &Database,
+    failing: &[i64],
+) -> Result<Vec<PoolFailover>, SqlxError> {
+    let mut results = Vec::new();
+    if failing.is_empty() {
+        return Ok(results);
+    }
+
+    for directory in db.get_directories().await? {
+        let pool_id = match directory.account_pool_id {
+            Some(pool_id) => pool_id,
+            None => continue,
+        };
+        let current = match db.get_directory_bound_account(directory.id).await? {
+            Some(account) if failing.contains(&account.id) => account,
+            _ => continue,
+        };
+        let pool = db.get_account_pool(pool_id).await?;
+
+        let mut result = PoolFailover {
+            pool_id,
+            directory_id: directory.id,
+            directory_name: directory.name.clone(),
+            pool_name: pool.name.clone(),
+            from_account: current.name.clone(),
+            to_account: None,
+            error: None,
+        };
+
+        match select_pool_account(db, pool_id, failing).await? {
+            Some(next) => match apply_account(db, &directory, &next).await {
+                Ok(_) => {
+                    tracing::warn!(
+                        "账号池 {}：目录 {} 的账号 {} 不可用，已切换到 {}",
+                        pool.name,
+                        directory.name,
+                        current.name,
+                        next.name
+                    );
+                    result.to_account = Some(next.name);
+                }
+                Err(e) => {
+                    tracing::error!(
+                        "账号池 {}：目录 {} 切换到账号 {} 失败: {}",
+                        pool.name,
+                        directory.name,
+                        next.name,
+                        e
+                    );
+                    result.error = Some(e.to_string());
+                }
+            },
+            None => {
+                tracing::error!(
+                    "账号池 {}：目录 {} 的账号 {} 不可用，且池中没有其他可用账号",
+                    pool.name,
+                    directory.name,
+                    current.name
+                );
+            }
+        }
+        // 记录失败不影响已完成的切换
+        if let Err(e) = db.create_pool_failover(&result).await {
+            tracing::warn!("保存账号池故障切换记录失败: {}", e);
+        }
+        results.push(result);
+    }
+
+    Ok(results)
+}
+
+/// 健康检查失败后触发故障切换：指定了账号时只切换该账号，否则该 API 地址下的所有账号都视为失效
+pub async fn failover_after_check(
+    db: &Database,
+    check: &EndpointCheck,
+) -> Result<Vec<PoolFailover>, SqlxError> {
+    if check.success {
+        return Ok(Vec::new());
+    }
+
+    let failing: Vec<i64> = match check.account_id {
+        Some(account_id) => vec![account_id],
+        None => {
+            let base_url = db.get_base_url(check.base_url_id).await?;
+            db.get_all_accounts()
+                .await?
+                .into_iter()
+                .filter(|a| a.base_url == base_url.url)
+                .map(|a| a.id)
+                .collect()
+        }
+    };
+    failover_accounts(db, &failing).await
+}
+
+/// 绑定目录到账号池（None 表示取消绑定），目录当前的账号不在池中或不可用时立即切换到池中的可用账号
+pub async fn bind_directory(
+    db: &Database,
+    directory_id: i64,
+    pool_id: Option<i64>,
+) -> Result<Option<PoolFailover>, SqlxError> {
+    db.set_directory_account_pool(directory_id, pool_id).await?;
+    let pool_id = match pool_id {
+        Some(pool_id) => pool_id,
+        None => return Ok(None),
+    };
+
+    let directory = db.get_directory(directory_id).await?;
+    let pool = db.get_account_pool(pool_id).await?;
+    let current = db.get_directory_bound_account(directory_id).await?;
+    let members = db.get_account_pool_members(Some(pool_id)).await?;
+    let failing = failing_base_urls(db).await?;
+
+    if let Some(current) = &current {
+        if members.iter().any(|m| m.account_id == current.id) && is_available(current, &failing) {
+            return Ok(None);
+        }
+    }
+
+    let mut result = PoolFailover {
+        pool_id,
+        directory_id,
+        directory_name: directory.name.clone(),
+        pool_name: pool.name.clone(),
+        from_account: current.map(|a| a.name).unwrap_or_default(),
+        to_account: None,
+        error: None,
+    };
+    match select_pool_account(db, pool_id, &[]).await? {
+        Some(next) => match apply_account(db, &directory, &next).await {
+            Ok(_) => {
+                tracing::info!(
+                    "目录 {} 绑定账号池 {}，使用账号 {}",
+                    directory.name,
+                    pool.name,
+                    next.name
+                );
+                result.to_account = Some(next.name);
+            }
+            Err(e) => result.error = Some(e.to_string()),
+        },
+        None => tracing::warn!("账号池 {} 中没有可用账号", pool.name),
+    }
+    Ok(Some(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn account(id: i64, token_status: Option<&str>) -> Account {
+        Account {
+            id,
+            name: format!("account-{}", id),
+            token: format!("sk-{}", id),
+            base_url: "https://relay.example.com".to_string(),
+            token_status: token_status.map(|s| s.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn member(account_id: i64, weight: i64, priority: i64) -> AccountPoolMember {
+        AccountPoolMember {
+            id: account_id,
+            pool_id: 1,
+            account_id,
+            weight,
+            priority,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_pick_account_by_priority_and_weight() {
+        let accounts = [account(1, None), account(2, None), account(3, None)];
+        let members = [member(1, 100, 2), member(2, 30, 1), member(3, 70, 1)];
+        let candidates: Vec<(&AccountPoolMember, &Account)> =
+            members.iter().zip(accounts.iter()).collect();
+
+        // priority 1 的账号 2、3 按 30:70 分配，priority 2 的账号 1 不会被选中
+        assert_eq!(pick_account(&candidates, 0).map(|a| a.id), Some(2));
+        assert_eq!(pick_account(&candidates, 29).map(|a| a.id), Some(2));
+        assert_eq!(pick_account(&candidates, 30).map(|a| a.id), Some(3));
+        assert_eq!(pick_account(&candidates, 99).map(|a| a.id), Some(3));
+
+        // 高优先级账号都不可用时退到下一优先级
+        assert_eq!(pick_account(&candidates[..1], 42).map(|a| a.id), Some(1));
+        assert!(pick_account(&[], 0).is_none());
+    }
+
+    #[test]
+    fn test_is_available() {
+        let failing: HashSet<String> = HashSet::new();
+        assert!(is_available(&account(1, None), &failing));
+        assert!(is_available(
+            &account(1, Some(health::TOKEN_VALID)),
+            &failing
+        ));
+        assert!(!is_available(
+            &account(1, Some(health::TOKEN_INVALID)),
+            &failing
+        ));
+        // 校验出错可能只是网络问题，账号仍可使用
+        assert!(is_available(
+            &account(1, Some(health::TOKEN_ERROR)),
+            &failing
+        ));
+
+        let mut disabled = account(1, None);
+        disabled.enabled = false;
+        assert!(!is_available(&disabled, &failing));
+
+        let mut expired = account(1, None);
+        expired.expires_at = Some(Utc::now() - chrono::Duration::days(1));
+        assert!(!is_available(&expired, &failing));
+
+        let failing: HashSet<String> = ["https://relay.example.com".to_string()].into();
+        assert!(!is_available(&account(1, None), &failing));
+    }
+
+    #[tokio::test]
+    async fn test_apply_account_switches_credential_mode() {
+        let root = std::env::temp_dir().join(format!("claude-config-pool-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+
+        let pool = crate::db_backend::DbPool::Sqlite(
+            sqlx::sqlite::SqlitePoolOptions::new()
+                .max_connections(1)
+                .connect("sqlite::memory:")
+                .await
+                .unwrap(),
+        );
+        let db = Database { pool };
+        db.migrate().await.unwrap();
+        let directory = db
+            .create_directory(CreateDirectoryRequest {
+                path: root.to_string_lossy().to_string(),
+                name: "project".to_string(),
+            })
+            .await
+            .unwrap();
+        let static_account = db
+            .create_account(CreateAccountRequest {
+                name: "static".to_string(),
+                token: "sk-static".to_string(),
+                base_url: "https://api.anthropic.com".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let helper_account = db
+            .create_account(CreateAccountRequest {
+                name: "helper".to_string(),
+                token: "sk-unused".to_string(),
+                base_url: "https://api.anthropic.com".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let helper_account = db
+            .set_account_credential(
+                helper_account.id,
+                crate::key_helper::MODE_HELPER,
+                Some("vault read -field=key secret/claude"),
+                Some(60000),
+            )
+            .await
+            .unwrap();
+
+        let read_settings = || -> serde_json::Value {
+            let content =
+                std::fs::read_to_string(root.join(".claude/settings.local.json")).unwrap();
+            serde_json::from_str(&content).unwrap()
+        };
+
+        apply_account(&db, &directory, &static_account)
+            .await
+            .unwrap();
+        let settings = read_settings();
+        assert_eq!(settings["env"]["ANTHROPIC_API_KEY"], "sk-static");
+        assert!(settings.get("apiKeyHelper").is_none());
+
+        // 切换到辅助命令模式的账号：写入 apiKeyHelper，移除上一个账号的静态令牌
+        apply_account(&db, &directory, &helper_account)
+            .await
+            .unwrap();
+        let settings = read_settings();
+        assert_eq!(
+            settings["apiKeyHelper"],
+            "vault read -field=key secret/claude"
+        );
+        assert!(settings["env"].get("ANTHROPIC_API_KEY").is_none());
+        assert_eq!(settings["env"][crate::key_helper::HELPER_TTL_ENV], "60000");
+        assert_eq!(settings["env"]["USER_NAME"], "helper");
+
+        // 切回令牌模式：移除 apiKeyHelper 并恢复令牌
+        apply_account(&db, &directory, &static_account)
+            .await
+            .unwrap();
+        let settings = read_settings();
+        assert_eq!(settings["env"]["ANTHROPIC_API_KEY"], "sk-static");
+        assert!(settings.get("apiKeyHelper").is_none());
+        assert!(settings["env"]
+            .get(crate::key_helper::HELPER_TTL_ENV)
+            .is_none());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn test_failover_accounts_records_history() {
+        let root =
+            std::env::temp_dir().join(format!("claude-config-failover-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+
+        let pool = crate::db_backend::DbPool::Sqlite(
+            sqlx::sqlite::SqlitePoolOptions::new()
+                .max_connections(1)
+                .connect("sqlite::memory:")
+                .await
+                .unwrap(),
+        );
+        let db = Database { pool };
+        db.migrate().await.unwrap();
+        let directory = db
+            .create_directory(CreateDirectoryRequest {
+                path: root.to_string_lossy().to_string(),
+                name: "project".to_string(),
+            })
+            .await
+            .unwrap();
+        let account_pool = db
+            .create_account_pool(CreateAccountPoolRequest {
+                name: "team".to_string(),
+                description: None,
+            })
+            .await
+            .unwrap();
+        let mut accounts = Vec::new();
+        for (name, priority) in [("primary", 1), ("backup", 2)] {
+            let account = db
+                .create_account(CreateAccountRequest {
+                    name: name.to_string(),
+                    token: format!("sk-{}", name),
+                    base_url: "https://api.anthropic.com".to_string(),
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+            db.save_account_pool_member(SaveAccountPoolMemberRequest {
+                pool_id: account_pool.id,
+                account_id: account.id,
+                weight: None,
+                priority: Some(priority),
+            })
+            .await
+            .unwrap();
+            accounts.push(account);
+        }
+
+        // 绑定账号池时的初始选择不算故障切换
+        bind_directory(&db, directory.id, Some(account_pool.id))
+            .await
+            .unwrap();
+        assert!(db.get_pool_failovers(None, 10).await.unwrap().is_empty());
+
+        let results = failover_accounts(&db, &[accounts[0].id]).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].to_account.as_deref(), Some("backup"));
+
+        let records = db
+            .get_pool_failovers(Some(account_pool.id), 10)
+            .await
+            .unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].pool_name, "team");
+        assert_eq!(records[0].directory_name, "project");
+        assert_eq!(records[0].from_account, "primary");
+        assert_eq!(records[0].to_account.as_deref(), Some("backup"));
+
+        // 两个账号都不可用时同样记录，切换目标为空
+        failover_accounts(&db, &[accounts[0].id, accounts[1].id])
+            .await
+            .unwrap();
+        let records = db.get_pool_failovers(None, 10).await.unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].from_account, "backup");
+        assert_eq!(records[0].to_account, None);
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
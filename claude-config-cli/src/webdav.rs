@@ -1,10 +1,11 @@
 use anyhow::{Context, Result};
 use reqwest_dav::{Auth, Client, ClientBuilder, Depth};
 use serde_json::Value;
-use sqlx::SqlitePool;
 use tracing::{error, info, warn};
 
-use crate::models::{CreateSyncLogRequest, WebDavConfig};
+use crate::db_backend::DbPool;
+use crate::models::{CreateSyncLogRequest, Proxy, WebDavConfig};
+use crate::proxy;
 
 /// WebDAV 客户端管理器
 pub struct WebDavManager {
@@ -13,11 +14,17 @@ pub struct WebDavManager {
 }
 
 impl WebDavManager {
-    /// 从数据库配置创建 WebDAV 管理器
-    pub async fn from_config(config: WebDavConfig) -> Result<Self> {
+    /// 从数据库配置创建 WebDAV 管理器，proxy 为配置绑定的代理
+    pub async fn from_config(config: WebDavConfig, proxy: Option<&Proxy>) -> Result<Self> {
         let auth = Auth::Basic(config.username.clone(), config.password.clone());
 
+        let agent = proxy::client_builder(proxy)
+            .map_err(|e| anyhow::anyhow!(e))?
+            .build()
+            .context("Failed to build HTTP client for WebDAV")?;
+
         let client = ClientBuilder::new()
+            .set_agent(agent)
             .set_host(config.url.clone())
             .set_auth(auth)
             .build()
@@ -192,20 +199,21 @@ impl WebDavManager {
 }
 
 /// 数据库操作 - WebDAV 配置
-pub async fn get_webdav_configs(pool: &SqlitePool) -> Result<Vec<WebDavConfig>> {
-    let configs =
-        sqlx::query_as::<_, WebDavConfig>("SELECT * FROM webdav_configs ORDER BY created_at DESC")
-            .fetch_all(pool)
-            .await
-            .context("Failed to get WebDAV configs from database")?;
+pub async fn get_webdav_configs(pool: &DbPool) -> Result<Vec<WebDavConfig>> {
+    let configs = pool
+        .query("SELECT * FROM webdav_configs ORDER BY created_at DESC")
+        .fetch_all::<WebDavConfig>()
+        .await
+        .context("Failed to get WebDAV configs from database")?;
 
     Ok(configs)
 }
 
-pub async fn get_webdav_config_by_id(pool: &SqlitePool, id: i64) -> Result<Option<WebDavConfig>> {
-    let config = sqlx::query_as::<_, WebDavConfig>("SELECT * FROM webdav_configs WHERE id = ?")
+pub async fn get_webdav_config_by_id(pool: &DbPool, id: i64) -> Result<Option<WebDavConfig>> {
+    let config = pool
+        .query("SELECT * FROM webdav_configs WHERE id = ?")
         .bind(id)
-        .fetch_optional(pool)
+        .fetch_optional::<WebDavConfig>()
         .await
         .context("Failed to get WebDAV config from database")?;
 
@@ -213,7 +221,7 @@ pub async fn get_webdav_config_by_id(pool: &SqlitePool, id: i64) -> Result<Optio
 }
 
 pub async fn create_webdav_config(
-    pool: &SqlitePool,
+    pool: &DbPool,
     name: &str,
     url: &str,
     username: &str,
@@ -222,7 +230,7 @@ pub async fn create_webdav_config(
     auto_sync: bool,
     sync_interval: i64,
 ) -> Result<WebDavConfig> {
-    let result = sqlx::query(
+    let id = pool.query(
         "INSERT INTO webdav_configs (name, url, username, password, remote_path, auto_sync, sync_interval)
          VALUES (?, ?, ?, ?, ?, ?, ?)"
     )
@@ -233,30 +241,66 @@ pub async fn create_webdav_config(
     .bind(remote_path)
     .bind(auto_sync)
     .bind(sync_interval)
-    .execute(pool)
+    .insert()
     .await
     .context("Failed to create WebDAV config")?;
 
-    let config = get_webdav_config_by_id(pool, result.last_insert_rowid())
+    let config = get_webdav_config_by_id(pool, id)
         .await?
         .ok_or_else(|| anyhow::anyhow!("Failed to retrieve created WebDAV config"))?;
 
     Ok(config)
 }
 
-pub async fn delete_webdav_config(pool: &SqlitePool, id: i64) -> Result<()> {
-    sqlx::query("DELETE FROM webdav_configs WHERE id = ?")
+pub async fn delete_webdav_config(pool: &DbPool, id: i64) -> Result<()> {
+    pool.query("DELETE FROM webdav_configs WHERE id = ?")
         .bind(id)
-        .execute(pool)
+        .execute()
         .await
         .context("Failed to delete WebDAV config")?;
 
     Ok(())
 }
 
+/// 设置 WebDAV 配置使用的代理，None 表示直连
+pub async fn set_webdav_config_proxy(
+    pool: &DbPool,
+    config_id: i64,
+    proxy_id: Option<i64>,
+) -> Result<()> {
+    pool.query(
+        "UPDATE webdav_configs SET proxy_id = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+    )
+    .bind(proxy_id)
+    .bind(config_id)
+    .execute()
+    .await
+    .context("Failed to update WebDAV config proxy")?;
+
+    Ok(())
+}
+
+/// 获取 WebDAV 配置绑定的代理
+pub async fn get_webdav_config_proxy(
+    pool: &DbPool,
+    config: &WebDavConfig,
+) -> Result<Option<Proxy>> {
+    let proxy = match config.proxy_id {
+        Some(proxy_id) => pool
+            .query("SELECT * FROM proxies WHERE id = ?")
+            .bind(proxy_id)
+            .fetch_optional::<Proxy>()
+            .await
+            .context("Failed to get WebDAV config proxy")?,
+        None => None,
+    };
+
+    Ok(proxy)
+}
+
 /// 记录同步日志
-pub async fn create_sync_log(pool: &SqlitePool, log: CreateSyncLogRequest) -> Result<()> {
-    sqlx::query(
+pub async fn create_sync_log(pool: &DbPool, log: CreateSyncLogRequest) -> Result<()> {
+    pool.query(
         "INSERT INTO sync_logs (webdav_config_id, sync_type, status, message)
          VALUES (?, ?, ?, ?)",
     )
@@ -264,7 +308,7 @@ pub async fn create_sync_log(pool: &SqlitePool, log: CreateSyncLogRequest) -> Re
     .bind(log.sync_type)
     .bind(log.status)
     .bind(log.message)
-    .execute(pool)
+    .execute()
     .await
     .context("Failed to create sync log")?;
 
@@ -272,10 +316,10 @@ pub async fn create_sync_log(pool: &SqlitePool, log: CreateSyncLogRequest) -> Re
 }
 
 /// 更新最后同步时间
-pub async fn update_last_sync_time(pool: &SqlitePool, config_id: i64) -> Result<()> {
-    sqlx::query("UPDATE webdav_configs SET last_sync_at = CURRENT_TIMESTAMP WHERE id = ?")
+pub async fn update_last_sync_time(pool: &DbPool, config_id: i64) -> Result<()> {
+    pool.query("UPDATE webdav_configs SET last_sync_at = CURRENT_TIMESTAMP WHERE id = ?")
         .bind(config_id)
-        .execute(pool)
+        .execute()
         .await
         .context("Failed to update last sync time")?;
 
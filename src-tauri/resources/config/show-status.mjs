@@ -61,6 +61,27 @@ function getCurrentModel() {
     return 'Claude (Auto)';
 }
 
+function getBalance() {
+    // 余额由配置管理器定时查询并缓存，按账号名称索引
+    const userName = process.env.USER_NAME || '';
+    if (!userName) {
+        return '';
+    }
+    try {
+        const cacheFile = path.join(os.homedir(), '.claude-config-manager', 'balances.json');
+        if (fs.existsSync(cacheFile)) {
+            const cache = JSON.parse(fs.readFileSync(cacheFile, 'utf8'));
+            const entry = (cache.accounts || {})[userName];
+            if (entry && typeof entry.balance === 'number') {
+                return `💰 ${entry.balance.toFixed(2)} ${entry.unit || ''}`.trim();
+            }
+        }
+    } catch (error) {
+        // 忽略错误
+    }
+    return '';
+}
+
 async function main() {
     try {
         const currentUrl = getDisplayUrl();
@@ -72,6 +93,9 @@ async function main() {
         parts.push(currentModel);
         parts.push(currentUrl);
 
+        const balance = getBalance();
+        if (balance) parts.push(balance);
+
         console.log(parts.join(' | '));
 
     } catch (error) {
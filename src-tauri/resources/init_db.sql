@@ -1,5 +1,15 @@
 -- SQLite database initialization script for Claude Config Manager
 -- This creates all necessary tables with default data
+-- The application builds its schema from the numbered migrations in src/migrations.rs;
+-- a database created from this script has no recorded versions, so every migration
+-- runs once on first start and skips columns that already exist
+
+-- Create schema_migrations table (applied schema versions)
+CREATE TABLE IF NOT EXISTS schema_migrations (
+    version INTEGER PRIMARY KEY,
+    name TEXT NOT NULL,
+    applied_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
 
 -- Create accounts table
 CREATE TABLE IF NOT EXISTS accounts (
@@ -10,6 +20,21 @@ CREATE TABLE IF NOT EXISTS accounts (
     model TEXT NOT NULL DEFAULT '',
     is_active BOOLEAN NOT NULL DEFAULT FALSE,
     custom_env_vars TEXT NOT NULL DEFAULT '{}',
+    permission_preset_id INTEGER,
+    token_status TEXT,
+    token_checked_at DATETIME,
+    token_error TEXT,
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    expires_at DATETIME,
+    notes TEXT NOT NULL DEFAULT '',
+    tags TEXT NOT NULL DEFAULT '',
+    last_used_at DATETIME,
+    use_count INTEGER NOT NULL DEFAULT 0,
+    proxy_id INTEGER,
+    credential_mode TEXT NOT NULL DEFAULT 'token',
+    api_key_helper TEXT,
+    api_key_helper_ttl_ms INTEGER,
+    custom_headers TEXT NOT NULL DEFAULT '[]',
     created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
     updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
 );
@@ -20,6 +45,10 @@ CREATE TABLE IF NOT EXISTS directories (
     path TEXT NOT NULL UNIQUE,
     name TEXT NOT NULL,
     is_active BOOLEAN NOT NULL DEFAULT FALSE,
+    permission_preset_id INTEGER,
+    account_pool_id INTEGER,
+    relay_port INTEGER,
+    proxy_id INTEGER,
     created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
     updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
 );
@@ -31,7 +60,10 @@ CREATE TABLE IF NOT EXISTS base_urls (
     url TEXT NOT NULL UNIQUE,
     description TEXT,
     api_key TEXT NOT NULL DEFAULT 'ANTHROPIC_API_KEY',
+    provider_type TEXT NOT NULL DEFAULT 'anthropic',
     is_default BOOLEAN NOT NULL DEFAULT FALSE,
+    proxy_id INTEGER,
+    custom_headers TEXT NOT NULL DEFAULT '[]',
     default_env_vars TEXT NOT NULL DEFAULT '{}',
     created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
     updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
@@ -73,6 +105,7 @@ CREATE TABLE IF NOT EXISTS webdav_configs (
     sync_interval INTEGER NOT NULL DEFAULT 3600,
     is_active BOOLEAN NOT NULL DEFAULT FALSE,
     last_sync_at DATETIME,
+    proxy_id INTEGER,
     created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
     updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
 );
@@ -86,4 +119,221 @@ CREATE TABLE IF NOT EXISTS sync_logs (
     message TEXT,
     synced_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
     FOREIGN KEY (webdav_config_id) REFERENCES webdav_configs (id) ON DELETE CASCADE
-);
\ No newline at end of file
+);
+-- Create mcp_servers table for MCP server catalog
+CREATE TABLE IF NOT EXISTS mcp_servers (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,
+    transport TEXT NOT NULL DEFAULT 'stdio' CHECK(transport IN ('stdio', 'sse', 'http')),
+    command TEXT,
+    args TEXT NOT NULL DEFAULT '[]',
+    env TEXT NOT NULL DEFAULT '{}',
+    url TEXT,
+    description TEXT,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Create directory_mcp_servers table (MCP servers assigned to directories)
+CREATE TABLE IF NOT EXISTS directory_mcp_servers (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    directory_id INTEGER NOT NULL,
+    mcp_server_id INTEGER NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (directory_id) REFERENCES directories (id) ON DELETE CASCADE,
+    FOREIGN KEY (mcp_server_id) REFERENCES mcp_servers (id) ON DELETE CASCADE,
+    UNIQUE(directory_id, mcp_server_id)
+);
+
+-- Create account_mcp_servers table (MCP servers assigned to accounts)
+CREATE TABLE IF NOT EXISTS account_mcp_servers (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    account_id INTEGER NOT NULL,
+    mcp_server_id INTEGER NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (account_id) REFERENCES accounts (id) ON DELETE CASCADE,
+    FOREIGN KEY (mcp_server_id) REFERENCES mcp_servers (id) ON DELETE CASCADE,
+    UNIQUE(account_id, mcp_server_id)
+);
+
+-- Create hooks table for Claude Code hooks library
+CREATE TABLE IF NOT EXISTS hooks (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,
+    event TEXT NOT NULL,
+    matcher TEXT,
+    command TEXT NOT NULL,
+    timeout INTEGER,
+    is_global BOOLEAN NOT NULL DEFAULT FALSE,
+    description TEXT,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Create directory_hooks table (hooks attached to directories)
+CREATE TABLE IF NOT EXISTS directory_hooks (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    directory_id INTEGER NOT NULL,
+    hook_id INTEGER NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (directory_id) REFERENCES directories (id) ON DELETE CASCADE,
+    FOREIGN KEY (hook_id) REFERENCES hooks (id) ON DELETE CASCADE,
+    UNIQUE(directory_id, hook_id)
+);
+
+-- Create permission_presets table (reusable allow/deny/ask rule sets)
+CREATE TABLE IF NOT EXISTS permission_presets (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,
+    default_mode TEXT NOT NULL DEFAULT 'default',
+    allow TEXT NOT NULL DEFAULT '[]',
+    deny TEXT NOT NULL DEFAULT '[]',
+    ask TEXT NOT NULL DEFAULT '[]',
+    description TEXT,
+    is_builtin BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Insert built-in permission presets
+INSERT OR IGNORE INTO permission_presets (name, default_mode, allow, deny, ask, description, is_builtin) VALUES
+    ('read-only', 'default', '["Read","Glob","Grep","LS","WebSearch","WebFetch","TodoWrite"]', '["Edit","MultiEdit","Write","NotebookEdit"]', '["Bash"]', '只读：允许读取和搜索，禁止修改文件，执行命令需确认', 1),
+    ('safe-dev', 'acceptEdits', '["Read","Glob","Grep","LS","Edit","MultiEdit","Write","TodoWrite","Bash(git status)","Bash(git diff:*)","Bash(git log:*)","Bash(npm run test:*)","Bash(cargo build:*)","Bash(cargo test:*)"]', '["Bash(rm -rf:*)","Read(./.env)","Read(./.env.*)"]', '["Bash(git push:*)","WebFetch"]', '安全开发：自动批准编辑和常用构建命令，危险操作需确认', 1),
+    ('full-bypass', 'bypassPermissions', '["*"]', '[]', '[]', '完全放开：跳过所有权限检查（仅限可信环境）', 1);
+
+-- Create claude_definitions table (slash command and subagent library)
+CREATE TABLE IF NOT EXISTS claude_definitions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    kind TEXT NOT NULL,
+    name TEXT NOT NULL,
+    description TEXT,
+    content TEXT NOT NULL,
+    is_default BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(kind, name)
+);
+
+-- Create directory_definitions table (per-directory enable/disable overrides)
+CREATE TABLE IF NOT EXISTS directory_definitions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    directory_id INTEGER NOT NULL,
+    definition_id INTEGER NOT NULL,
+    enabled BOOLEAN NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (directory_id) REFERENCES directories (id) ON DELETE CASCADE,
+    FOREIGN KEY (definition_id) REFERENCES claude_definitions (id) ON DELETE CASCADE,
+    UNIQUE(directory_id, definition_id)
+);
+
+-- Create discovery_roots table (roots scanned when discovering existing projects)
+CREATE TABLE IF NOT EXISTS discovery_roots (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    path TEXT NOT NULL UNIQUE,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Create endpoint_checks table (base URL health check history)
+CREATE TABLE IF NOT EXISTS endpoint_checks (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    base_url_id INTEGER NOT NULL,
+    account_id INTEGER,
+    connect_ms INTEGER,
+    tls_ms INTEGER,
+    latency_ms INTEGER,
+    status_code INTEGER,
+    auth_status_code INTEGER,
+    success BOOLEAN NOT NULL,
+    error TEXT,
+    checked_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (base_url_id) REFERENCES base_urls (id) ON DELETE CASCADE
+);
+
+-- Create balance_adapters table (relay balance query adapters per base URL)
+CREATE TABLE IF NOT EXISTS balance_adapters (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    base_url_id INTEGER NOT NULL UNIQUE,
+    method TEXT NOT NULL DEFAULT 'GET',
+    url_template TEXT NOT NULL,
+    headers TEXT,
+    body_template TEXT,
+    balance_path TEXT NOT NULL,
+    divisor REAL NOT NULL DEFAULT 1,
+    unit TEXT NOT NULL DEFAULT 'USD',
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (base_url_id) REFERENCES base_urls (id) ON DELETE CASCADE
+);
+
+-- Create account_balances table (cached account balances)
+CREATE TABLE IF NOT EXISTS account_balances (
+    account_id INTEGER PRIMARY KEY,
+    balance REAL,
+    unit TEXT,
+    error TEXT,
+    fetched_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (account_id) REFERENCES accounts (id) ON DELETE CASCADE
+);
+
+-- Create account_pools table (named groups of accounts for failover)
+CREATE TABLE IF NOT EXISTS account_pools (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,
+    description TEXT,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Create account_pool_members table (accounts in a pool with weight and priority)
+CREATE TABLE IF NOT EXISTS account_pool_members (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    pool_id INTEGER NOT NULL,
+    account_id INTEGER NOT NULL,
+    weight INTEGER NOT NULL DEFAULT 100,
+    priority INTEGER NOT NULL DEFAULT 1,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (pool_id) REFERENCES account_pools (id) ON DELETE CASCADE,
+    FOREIGN KEY (account_id) REFERENCES accounts (id) ON DELETE CASCADE,
+    UNIQUE(pool_id, account_id)
+);
+
+-- Create relay_stats table (per-account request statistics of the local relay proxy)
+CREATE TABLE IF NOT EXISTS relay_stats (
+    account_id INTEGER PRIMARY KEY,
+    request_count INTEGER NOT NULL DEFAULT 0,
+    failure_count INTEGER NOT NULL DEFAULT 0,
+    total_latency_ms INTEGER NOT NULL DEFAULT 0,
+    last_status INTEGER,
+    last_latency_ms INTEGER,
+    last_used_at DATETIME,
+    FOREIGN KEY (account_id) REFERENCES accounts (id) ON DELETE CASCADE
+);
+
+-- Create pool_failovers table (account pool failover history)
+CREATE TABLE IF NOT EXISTS pool_failovers (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    pool_id INTEGER NOT NULL,
+    directory_id INTEGER NOT NULL,
+    pool_name TEXT NOT NULL,
+    directory_name TEXT NOT NULL,
+    from_account TEXT NOT NULL,
+    to_account TEXT,
+    error TEXT,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (pool_id) REFERENCES account_pools (id) ON DELETE CASCADE,
+    FOREIGN KEY (directory_id) REFERENCES directories (id) ON DELETE CASCADE
+);
+
+-- Create proxies table (HTTP/SOCKS proxies attachable to base URLs, accounts, directories and WebDAV configs)
+CREATE TABLE IF NOT EXISTS proxies (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,
+    url TEXT NOT NULL,
+    no_proxy TEXT,
+    username TEXT,
+    password TEXT,
+    description TEXT,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -222,16 +222,18 @@ impl ConfigManager {
             return Err(anyhow::anyhow!("Database connection '{}' not found", connection_name));
         }
         
-        // 更新默认连接
-        self.config.current = connection_name.to_string();
-        
-        // 如果有配置文件路径，保存到文件
+        // 如果有配置文件路径，只改写文件中的 current，保留未建模的字段
         if let Some(config_file) = &self.config_file {
-            let content = serde_json::to_string_pretty(&self.config)?;
-            std::fs::write(config_file, content)?;
+            let content = fs::read_to_string(config_file)?;
+            let mut file_config: serde_json::Value = serde_json::from_str(&content)?;
+            file_config["current"] = serde_json::Value::String(connection_name.to_string());
+            fs::write(config_file, serde_json::to_string_pretty(&file_config)?)?;
         }
         
+        // 文件写入成功后再更新内存中的默认连接，避免两者不一致
+        self.config.current = connection_name.to_string();
+        
         Ok(())
     }
     
-}
\ No newline at end of file
+}
\ No newline at end of file
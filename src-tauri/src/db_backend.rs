@@ -0,0 +1,860 @@
+use chrono::{DateTime, Utc};
+use sqlx::mysql::{MySql, MySqlPool, MySqlQueryResult, MySqlRow};
+use sqlx::postgres::{PgPool, PgQueryResult, PgRow, Postgres};
+use sqlx::sqlite::{Sqlite, SqlitePool, SqliteQueryResult, SqliteRow};
+use sqlx::{Decode, Error as SqlxError, FromRow, Transaction, Type};
+use std::borrow::Cow;
+
+/// 数据库类型，由连接 URL 的协议决定
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    Sqlite,
+    MySql,
+    Postgres,
+}
+
+impl Backend {
+    /// 根据 URL 协议判断数据库类型，兼容 Python 版本的 mysql+pymysql:// 等写法
+    pub fn from_url(url: &str) -> Result<Self, SqlxError> {
+        let scheme = url.split(':').next().unwrap_or_default().to_lowercase();
+        match scheme.as_str() {
+            "sqlite" => Ok(Backend::Sqlite),
+            "mysql" | "mysql+pymysql" | "mariadb" => Ok(Backend::MySql),
+            "postgres" | "postgresql" | "postgresql+psycopg2" => Ok(Backend::Postgres),
+            _ => Err(SqlxError::Configuration(
+                format!("不支持的数据库类型: {}", scheme).into(),
+            )),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Backend::Sqlite => "sqlite",
+            Backend::MySql => "mysql",
+            Backend::Postgres => "postgres",
+        }
+    }
+
+    /// 查询语句统一按 SQLite 方言编写，执行前转换为目标数据库的写法
+    pub fn rewrite<'a>(&self, sql: &'a str) -> Cow<'a, str> {
+        match self {
+            Backend::Sqlite => Cow::Borrowed(sql),
+            Backend::MySql => {
+                let sql = sql.replace("INSERT OR IGNORE INTO", "INSERT IGNORE INTO");
+                let sql = replace_upsert(&sql, "ON DUPLICATE KEY UPDATE");
+                let sql = replace_excluded(&sql);
+                // MySQL 的 DATETIME 可以直接比较，不需要 julianday/datetime 转换
+                let sql = replace_calls(&sql, "julianday(", str::to_string);
+                Cow::Owned(replace_calls(&sql, "datetime(", str::to_string))
+            }
+            Backend::Postgres => {
+                let sql = replace_insert_or_ignore(sql);
+                // 文本参数不能直接和 TIMESTAMPTZ 比较，需要显式转换
+                let cast = |inner: &str| {
+                    if inner.trim() == "?" {
+                        "CAST(? AS TIMESTAMPTZ)".to_string()
+                    } else {
+                        inner.to_string()
+                    }
+                };
+                let sql = replace_calls(&sql, "julianday(", cast);
+                let sql = replace_calls(&sql, "datetime(", cast);
+                Cow::Owned(number_placeholders(&sql))
+            }
+        }
+    }
+
+    /// 建表语句统一按 SQLite 方言编写，执行前转换字段类型
+    pub fn translate_ddl<'a>(&self, sql: &'a str) -> Cow<'a, str> {
+        match self {
+            Backend::Sqlite => Cow::Borrowed(sql),
+            Backend::MySql | Backend::Postgres => {
+                let composite = composite_unique_columns(sql);
+                let lines: Vec<String> = sql
+                    .lines()
+                    .map(|line| {
+                        let trimmed = line.trim_start();
+                        let indent = &line[..line.len() - trimmed.len()];
+                        match trimmed.split_once(' ') {
+                            Some((name, definition))
+                                if is_column_name(name) && is_column_type(definition) =>
+                            {
+                                let keyed = composite.iter().any(|c| c == name);
+                                format!(
+                                    "{}{} {}",
+                                    indent,
+                                    name,
+                                    self.translate_column(definition, keyed)
+                                )
+                            }
+                            _ => line.to_string(),
+                        }
+                    })
+                    .collect();
+                Cow::Owned(lines.join("\n"))
+            }
+        }
+    }
+
+    /// 添加字段时使用的字段定义
+    pub fn column_definition<'a>(&self, definition: &'a str) -> Cow<'a, str> {
+        match self {
+            Backend::Sqlite => Cow::Borrowed(definition),
+            Backend::MySql | Backend::Postgres => {
+                Cow::Owned(self.translate_column(definition, false))
+            }
+        }
+    }
+
+    fn translate_column(&self, definition: &str, composite_key: bool) -> String {
+        match self {
+            Backend::Sqlite => definition.to_string(),
+            Backend::MySql => mysql_column_definition(definition, composite_key),
+            Backend::Postgres => postgres_column_definition(definition),
+        }
+    }
+
+    /// 检查字段是否存在，参数依次为表名和字段名
+    pub fn column_exists_sql(&self) -> &'static str {
+        match self {
+            Backend::Sqlite => "SELECT COUNT(*) FROM pragma_table_info(?) WHERE name = ?",
+            Backend::MySql => {
+                "SELECT COUNT(*) FROM information_schema.COLUMNS \
+                 WHERE TABLE_SCHEMA = DATABASE() AND TABLE_NAME = ? AND COLUMN_NAME = ?"
+            }
+            Backend::Postgres => {
+                "SELECT COUNT(*) FROM information_schema.columns \
+                 WHERE table_schema = current_schema() AND table_name = ? AND column_name = ?"
+            }
+        }
+    }
+}
+
+/// sqlx 不认识 SQLAlchemy 风格的协议名，连接前统一为 mysql:// 或 postgres://
+pub fn normalize_url(url: &str) -> String {
+    for (prefix, scheme) in [
+        ("mysql+pymysql://", "mysql://"),
+        ("mariadb://", "mysql://"),
+        ("postgresql+psycopg2://", "postgres://"),
+    ] {
+        if let Some(rest) = url.strip_prefix(prefix) {
+            return format!("{}{}", scheme, rest);
+        }
+    }
+    url.to_string()
+}
+
+/// 隐藏 URL 中的密码，用于写日志
+pub fn redact_url(url: &str) -> String {
+    let Some(scheme_end) = url.find("://").map(|i| i + 3) else {
+        return url.to_string();
+    };
+    let Some(at) = url[scheme_end..].rfind('@').map(|i| scheme_end + i) else {
+        return url.to_string();
+    };
+    match url[scheme_end..at].find(':').map(|i| scheme_end + i) {
+        Some(colon) => format!("{}:***{}", &url[..colon], &url[at..]),
+        None => url.to_string(),
+    }
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// 前一个字符不是标识符的一部分，避免匹配到 xxx_datetime( 这样的名字
+fn at_word_start(sql: &str, index: usize) -> bool {
+    !sql[..index].chars().next_back().is_some_and(is_ident_char)
+}
+
+/// ON CONFLICT(...) DO UPDATE SET 替换为目标数据库的写法
+fn replace_upsert(sql: &str, replacement: &str) -> String {
+    let mut out = String::with_capacity(sql.len());
+    let mut rest = sql;
+    while let Some(start) = rest.find("ON CONFLICT(") {
+        let Some(close) = rest[start..].find(')').map(|i| start + i + 1) else {
+            break;
+        };
+        let after = rest[close..].trim_start();
+        if !after.starts_with("DO UPDATE SET") {
+            out.push_str(&rest[..close]);
+            rest = &rest[close..];
+            continue;
+        }
+        out.push_str(&rest[..start]);
+        out.push_str(replacement);
+        rest = &after["DO UPDATE SET".len()..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// excluded.column 替换为 VALUES(column)
+fn replace_excluded(sql: &str) -> String {
+    let mut out = String::with_capacity(sql.len());
+    let mut rest = sql;
+    while let Some(start) = rest.find("excluded.") {
+        if !at_word_start(rest, start) {
+            out.push_str(&rest[..start + 1]);
+            rest = &rest[start + 1..];
+            continue;
+        }
+        out.push_str(&rest[..start]);
+        let column_start = start + "excluded.".len();
+        let column_end = rest[column_start..]
+            .find(|c: char| !is_ident_char(c))
+            .map(|i| column_start + i)
+            .unwrap_or(rest.len());
+        out.push_str(&format!("VALUES({})", &rest[column_start..column_end]));
+        rest = &rest[column_end..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// 按参数替换函数调用，如 julianday(created_at) 变为 created_at
+fn replace_calls(sql: &str, function: &str, replace: impl Fn(&str) -> String) -> String {
+    let mut out = String::with_capacity(sql.len());
+    let mut rest = sql;
+    while let Some(start) = rest.find(function) {
+        if !at_word_start(rest, start) {
+            out.push_str(&rest[..start + 1]);
+            rest = &rest[start + 1..];
+            continue;
+        }
+        let inner_start = start + function.len();
+        let mut depth = 1;
+        let mut inner_end = None;
+        for (i, c) in rest[inner_start..].char_indices() {
+            match c {
+                '(' => depth += 1,
+                ')' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        inner_end = Some(inner_start + i);
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+        let Some(inner_end) = inner_end else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+        out.push_str(&replace(&rest[inner_start..inner_end]));
+        rest = &rest[inner_end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// INSERT OR IGNORE 改为 ON CONFLICT DO NOTHING，加在 RETURNING 之前
+fn replace_insert_or_ignore(sql: &str) -> String {
+    if !sql.contains("INSERT OR IGNORE INTO") {
+        return sql.to_string();
+    }
+    let sql = sql.replace("INSERT OR IGNORE INTO", "INSERT INTO");
+    match sql.find(" RETURNING ") {
+        Some(index) => format!("{} ON CONFLICT DO NOTHING{}", &sql[..index], &sql[index..]),
+        None => format!("{} ON CONFLICT DO NOTHING", sql.trim_end()),
+    }
+}
+
+/// INSERT OR IGNORE 被忽略时查询已有记录 id 的语句：按插入的非时间字段匹配
+/// 只支持 VALUES 中每个表达式至多一个占位符的写法，其他语句返回 None
+fn conflict_lookup(sql: &str, params: &[Param]) -> Option<(String, Vec<Param>)> {
+    let rest = sql.trim_start().strip_prefix("INSERT OR IGNORE INTO")?;
+    let (table, rest) = rest.split_once('(')?;
+    let (columns, rest) = rest.split_once(')')?;
+    let values = rest.trim_start().strip_prefix("VALUES")?.trim();
+    let values = values.strip_prefix('(')?.strip_suffix(')')?;
+
+    let mut expressions = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in values.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                expressions.push(&values[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    expressions.push(&values[start..]);
+
+    let mut params = params.iter();
+    let mut conditions = Vec::new();
+    let mut lookup_params = Vec::new();
+    for (column, expression) in columns.split(',').zip(expressions) {
+        if !expression.contains('?') {
+            continue;
+        }
+        let param = params.next()?;
+        if !matches!(param, Param::Time(_)) {
+            conditions.push(format!("{} = ?", column.trim()));
+            lookup_params.push(param.clone());
+        }
+    }
+    if conditions.is_empty() {
+        return None;
+    }
+    Some((
+        format!(
+            "SELECT id FROM {} WHERE {}",
+            table.trim(),
+            conditions.join(" AND ")
+        ),
+        lookup_params,
+    ))
+}
+
+/// ? 占位符改为 $1、$2…，跳过字符串字面量中的问号
+fn number_placeholders(sql: &str) -> String {
+    let mut out = String::with_capacity(sql.len() + 8);
+    let mut index = 0;
+    let mut in_string = false;
+    for c in sql.chars() {
+        match c {
+            '\'' => {
+                in_string = !in_string;
+                out.push(c);
+            }
+            '?' if !in_string => {
+                index += 1;
+                out.push_str(&format!("${}", index));
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn is_column_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_lowercase() || c == '_')
+}
+
+fn is_column_type(definition: &str) -> bool {
+    let column_type = definition.split_whitespace().next().unwrap_or_default();
+    matches!(
+        column_type.trim_end_matches(','),
+        "INTEGER" | "TEXT" | "REAL" | "BOOLEAN" | "DATETIME"
+    )
+}
+
+/// 表级 UNIQUE(a, b) 约束中的字段
+fn composite_unique_columns(sql: &str) -> Vec<String> {
+    let mut columns = Vec::new();
+    let mut rest = sql;
+    while let Some(start) = rest.find("UNIQUE(") {
+        let list_start = start + "UNIQUE(".len();
+        let Some(list_end) = rest[list_start..].find(')').map(|i| list_start + i) else {
+            break;
+        };
+        columns.extend(
+            rest[list_start..list_end]
+                .split(',')
+                .map(|c| c.trim().to_string()),
+        );
+        rest = &rest[list_end..];
+    }
+    columns
+}
+
+fn mysql_column_definition(definition: &str, composite_key: bool) -> String {
+    let definition = definition.replace("PRIMARY KEY AUTOINCREMENT", "PRIMARY KEY AUTO_INCREMENT");
+    let (column_type, rest) = definition
+        .split_once(' ')
+        .unwrap_or((definition.as_str(), ""));
+    let (column_type, suffix) = match column_type.strip_suffix(',') {
+        Some(column_type) => (column_type, ","),
+        None => (column_type, ""),
+    };
+    // 索引字段不能是 TEXT；utf8mb4 下单个索引最长 3072 字节，组合索引每个字段限制在 255 字符
+    let mysql_type = match column_type {
+        "INTEGER" => "BIGINT",
+        "REAL" => "DOUBLE",
+        "TEXT" if composite_key => "VARCHAR(255)",
+        "TEXT" if rest.contains("UNIQUE") || rest.contains("PRIMARY KEY") => "VARCHAR(512)",
+        other => other,
+    };
+    // TEXT 字段不能使用字面量默认值，需要写成表达式（MySQL 8.0.13+）
+    let rest = match rest.find("DEFAULT '") {
+        Some(start) if mysql_type == "TEXT" => {
+            let value_start = start + "DEFAULT ".len();
+            match rest[value_start + 1..].find('\'') {
+                Some(i) => {
+                    let value_end = value_start + 1 + i + 1;
+                    format!(
+                        "{}DEFAULT ({}){}",
+                        &rest[..start],
+                        &rest[value_start..value_end],
+                        &rest[value_end..]
+                    )
+                }
+                None => rest.to_string(),
+            }
+        }
+        _ => rest.to_string(),
+    };
+    if rest.is_empty() {
+        format!("{}{}", mysql_type, suffix)
+    } else {
+        format!("{}{} {}", mysql_type, suffix, rest)
+    }
+}
+
+fn postgres_column_definition(definition: &str) -> String {
+    let definition =
+        definition.replace("INTEGER PRIMARY KEY AUTOINCREMENT", "BIGSERIAL PRIMARY KEY");
+    let (column_type, rest) = definition
+        .split_once(' ')
+        .unwrap_or((definition.as_str(), ""));
+    let (column_type, suffix) = match column_type.strip_suffix(',') {
+        Some(column_type) => (column_type, ","),
+        None => (column_type, ""),
+    };
+    let postgres_type = match column_type {
+        "INTEGER" => "BIGINT",
+        "REAL" => "DOUBLE PRECISION",
+        "DATETIME" => "TIMESTAMPTZ",
+        other => other,
+    };
+    if rest.is_empty() {
+        format!("{}{}", postgres_type, suffix)
+    } else {
+        format!("{}{} {}", postgres_type, suffix, rest)
+    }
+}
+
+/// 可以从任意数据库的一行结果读取的类型
+pub trait FromDbRow:
+    for<'r> FromRow<'r, SqliteRow>
+    + for<'r> FromRow<'r, MySqlRow>
+    + for<'r> FromRow<'r, PgRow>
+    + Send
+    + Unpin
+{
+}
+
+impl<T> FromDbRow for T where
+    T: for<'r> FromRow<'r, SqliteRow>
+        + for<'r> FromRow<'r, MySqlRow>
+        + for<'r> FromRow<'r, PgRow>
+        + Send
+        + Unpin
+{
+}
+
+/// 可以从任意数据库读取的单个值
+pub trait DbValue:
+    for<'r> Decode<'r, Sqlite>
+    + Type<Sqlite>
+    + for<'r> Decode<'r, MySql>
+    + Type<MySql>
+    + for<'r> Decode<'r, Postgres>
+    + Type<Postgres>
+    + Send
+    + Unpin
+{
+}
+
+impl<T> DbValue for T where
+    T: for<'r> Decode<'r, Sqlite>
+        + Type<Sqlite>
+        + for<'r> Decode<'r, MySql>
+        + Type<MySql>
+        + for<'r> Decode<'r, Postgres>
+        + Type<Postgres>
+        + Send
+        + Unpin
+{
+}
+
+/// 查询参数，空值也需要带类型
+#[derive(Debug, Clone)]
+pub enum Param {
+    Bool(Option<bool>),
+    Int(Option<i64>),
+    Float(Option<f64>),
+    Text(Option<String>),
+    Time(Option<DateTime<Utc>>),
+}
+
+pub trait IntoParam {
+    fn into_param(self) -> Param;
+    fn null_param() -> Param
+    where
+        Self: Sized;
+}
+
+macro_rules! impl_into_param {
+    ($($ty:ty => $variant:ident($convert:expr)),* $(,)?) => {
+        $(
+            impl IntoParam for $ty {
+                fn into_param(self) -> Param {
+                    Param::$variant(Some($convert(self)))
+                }
+                fn null_param() -> Param {
+                    Param::$variant(None)
+                }
+            }
+        )*
+    };
+}
+
+impl_into_param! {
+    bool => Bool(|v| v),
+    i32 => Int(i64::from),
+    i64 => Int(|v| v),
+    u16 => Int(i64::from),
+    u32 => Int(i64::from),
+    f64 => Float(|v| v),
+    String => Text(|v| v),
+    &str => Text(str::to_string),
+    DateTime<Utc> => Time(|v| v),
+}
+
+impl<T: IntoParam> IntoParam for Option<T> {
+    fn into_param(self) -> Param {
+        match self {
+            Some(value) => value.into_param(),
+            None => T::null_param(),
+        }
+    }
+    fn null_param() -> Param {
+        T::null_param()
+    }
+}
+
+impl<T: IntoParam + Clone> IntoParam for &T {
+    fn into_param(self) -> Param {
+        self.clone().into_param()
+    }
+    fn null_param() -> Param {
+        T::null_param()
+    }
+}
+
+/// 执行结果
+#[derive(Debug, Clone, Copy)]
+pub struct ExecResult {
+    rows_affected: u64,
+    last_insert_id: i64,
+}
+
+impl ExecResult {
+    pub fn rows_affected(&self) -> u64 {
+        self.rows_affected
+    }
+
+    pub fn last_insert_id(&self) -> i64 {
+        self.last_insert_id
+    }
+}
+
+impl From<SqliteQueryResult> for ExecResult {
+    fn from(result: SqliteQueryResult) -> Self {
+        Self {
+            rows_affected: result.rows_affected(),
+            last_insert_id: result.last_insert_rowid(),
+        }
+    }
+}
+
+impl From<MySqlQueryResult> for ExecResult {
+    fn from(result: MySqlQueryResult) -> Self {
+        Self {
+            rows_affected: result.rows_affected(),
+            last_insert_id: result.last_insert_id() as i64,
+        }
+    }
+}
+
+/// PostgreSQL 没有自增 id 的返回值，新记录的 id 由 insert() 通过 RETURNING 获取
+impl From<PgQueryResult> for ExecResult {
+    fn from(result: PgQueryResult) -> Self {
+        Self {
+            rows_affected: result.rows_affected(),
+            last_insert_id: 0,
+        }
+    }
+}
+
+/// 按 URL 连接的数据库连接池
+#[derive(Debug, Clone)]
+pub enum DbPool {
+    Sqlite(SqlitePool),
+    MySql(MySqlPool),
+    Postgres(PgPool),
+}
+
+impl DbPool {
+    pub async fn connect(url: &str) -> Result<Self, SqlxError> {
+        match Backend::from_url(url)? {
+            Backend::Sqlite => Ok(DbPool::Sqlite(SqlitePool::connect(url).await?)),
+            Backend::MySql => Ok(DbPool::MySql(
+                MySqlPool::connect(&normalize_url(url)).await?,
+            )),
+            Backend::Postgres => Ok(DbPool::Postgres(
+                PgPool::connect(&normalize_url(url)).await?,
+            )),
+        }
+    }
+
+    pub fn backend(&self) -> Backend {
+        match self {
+            DbPool::Sqlite(_) => Backend::Sqlite,
+            DbPool::MySql(_) => Backend::MySql,
+            DbPool::Postgres(_) => Backend::Postgres,
+        }
+    }
+
+    pub fn query(&self, sql: impl Into<String>) -> DbQuery<'_> {
+        DbQuery {
+            target: Target::Pool(self),
+            sql: sql.into(),
+            params: Vec::new(),
+        }
+    }
+
+    pub async fn begin(&self) -> Result<DbTransaction, SqlxError> {
+        match self {
+            DbPool::Sqlite(pool) => Ok(DbTransaction::Sqlite(pool.begin().await?)),
+            DbPool::MySql(pool) => Ok(DbTransaction::MySql(pool.begin().await?)),
+            DbPool::Postgres(pool) => Ok(DbTransaction::Postgres(pool.begin().await?)),
+        }
+    }
+
+    /// SQLite 默认不检查外键，MySQL (InnoDB) 和 PostgreSQL 始终检查
+    pub async fn enable_foreign_keys(&self) -> Result<(), SqlxError> {
+        if let DbPool::Sqlite(pool) = self {
+            sqlx::query("PRAGMA foreign_keys = ON")
+                .execute(pool)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// 关闭连接池，等待已借出的连接归还后返回
+    pub async fn close(&self) {
+        match self {
+            DbPool::Sqlite(pool) => pool.close().await,
+            DbPool::MySql(pool) => pool.close().await,
+            DbPool::Postgres(pool) => pool.close().await,
+        }
+    }
+}
+
+/// 数据库事务；MySQL 的 DDL 语句会隐式提交，迁移中的建表语句不受事务保护
+pub enum DbTransaction {
+    Sqlite(Transaction<'static, Sqlite>),
+    MySql(Transaction<'static, MySql>),
+    Postgres(Transaction<'static, Postgres>),
+}
+
+impl DbTransaction {
+    pub fn backend(&self) -> Backend {
+        match self {
+            DbTransaction::Sqlite(_) => Backend::Sqlite,
+            DbTransaction::MySql(_) => Backend::MySql,
+            DbTransaction::Postgres(_) => Backend::Postgres,
+        }
+    }
+
+    pub fn query(&mut self, sql: impl Into<String>) -> DbQuery<'_> {
+        DbQuery {
+            target: Target::Tx(self),
+            sql: sql.into(),
+            params: Vec::new(),
+        }
+    }
+
+    pub async fn commit(self) -> Result<(), SqlxError> {
+        match self {
+            DbTransaction::Sqlite(tx) => tx.commit().await,
+            DbTransaction::MySql(tx) => tx.commit().await,
+            DbTransaction::Postgres(tx) => tx.commit().await,
+        }
+    }
+}
+
+enum Target<'a> {
+    Pool(&'a DbPool),
+    Tx(&'a mut DbTransaction),
+}
+
+impl Target<'_> {
+    fn backend(&self) -> Backend {
+        match self {
+            Target::Pool(pool) => pool.backend(),
+            Target::Tx(tx) => tx.backend(),
+        }
+    }
+
+    fn reborrow(&mut self) -> Target<'_> {
+        match self {
+            Target::Pool(pool) => Target::Pool(pool),
+            Target::Tx(tx) => Target::Tx(tx),
+        }
+    }
+}
+
+/// 与数据库无关的查询，参数在执行时按实际数据库绑定
+pub struct DbQuery<'a> {
+    target: Target<'a>,
+    sql: String,
+    params: Vec<Param>,
+}
+
+macro_rules! bind_params {
+    ($query:expr, $params:expr) => {{
+        let mut query = $query;
+        for param in $params {
+            query = match param {
+                Param::Bool(v) => query.bind(v),
+                Param::Int(v) => query.bind(v),
+                Param::Float(v) => query.bind(v),
+                Param::Text(v) => query.bind(v),
+                Param::Time(v) => query.bind(v),
+            };
+        }
+        query
+    }};
+}
+
+/// 按连接类型分发，$build 为 sqlx::query / query_as / query_scalar
+macro_rules! dispatch {
+    ($self:expr, $build:expr, |$query:ident, $conn:ident| $body:expr) => {{
+        let DbQuery {
+            target,
+            sql,
+            params,
+        } = $self;
+        let sql = target.backend().rewrite(&sql);
+        match target {
+            Target::Pool(DbPool::Sqlite(pool)) => {
+                let $query = bind_params!($build(sql.as_ref()), params);
+                let $conn = pool;
+                $body
+            }
+            Target::Pool(DbPool::MySql(pool)) => {
+                let $query = bind_params!($build(sql.as_ref()), params);
+                let $conn = pool;
+                $body
+            }
+            Target::Tx(DbTransaction::Sqlite(tx)) => {
+                let $query = bind_params!($build(sql.as_ref()), params);
+                let $conn = &mut **tx;
+                $body
+            }
+            Target::Tx(DbTransaction::MySql(tx)) => {
+                let $query = bind_params!($build(sql.as_ref()), params);
+                let $conn = &mut **tx;
+                $body
+            }
+            Target::Pool(DbPool::Postgres(pool)) => {
+                let $query = bind_params!($build(sql.as_ref()), params);
+                let $conn = pool;
+                $body
+            }
+            Target::Tx(DbTransaction::Postgres(tx)) => {
+                let $query = bind_params!($build(sql.as_ref()), params);
+                let $conn = &mut **tx;
+                $body
+            }
+        }
+    }};
+}
+
+impl DbQuery<'_> {
+    pub fn bind(mut self, value: impl IntoParam) -> Self {
+        self.params.push(value.into_param());
+        self
+    }
+
+    pub async fn execute(self) -> Result<ExecResult, SqlxError> {
+        dispatch!(self, sqlx::query, |query, conn| query
+            .execute(conn)
+            .await
+            .map(ExecResult::from))
+    }
+
+    /// 执行 INSERT 并返回新记录的 id
+    /// INSERT OR IGNORE 因冲突被忽略时没有新记录，改为查询已有记录的 id
+    pub async fn insert(mut self) -> Result<i64, SqlxError> {
+        let lookup = conflict_lookup(&self.sql, &self.params);
+        let sql = std::mem::take(&mut self.sql);
+        let params = std::mem::take(&mut self.params);
+        let inserted = if self.target.backend() == Backend::Postgres {
+            DbQuery {
+                target: self.target.reborrow(),
+                sql: format!("{} RETURNING id", sql.trim_end()),
+                params,
+            }
+            .fetch_optional_scalar()
+            .await?
+        } else {
+            let result = DbQuery {
+                target: self.target.reborrow(),
+                sql,
+                params,
+            }
+            .execute()
+            .await?;
+            (result.rows_affected() > 0 || lookup.is_none()).then(|| result.last_insert_id())
+        };
+        match (inserted, lookup) {
+            (Some(id), _) => Ok(id),
+            (None, Some((sql, params))) => {
+                DbQuery {
+                    target: self.target,
+                    sql,
+                    params,
+                }
+                .fetch_scalar()
+                .await
+            }
+            (None, None) => Err(SqlxError::RowNotFound),
+        }
+    }
+
+    pub async fn fetch_all<T: FromDbRow>(self) -> Result<Vec<T>, SqlxError> {
+        dispatch!(self, sqlx::query_as::<_, T>, |query, conn| query
+            .fetch_all(conn)
+            .await)
+    }
+
+    pub async fn fetch_one<T: FromDbRow>(self) -> Result<T, SqlxError> {
+        dispatch!(self, sqlx::query_as::<_, T>, |query, conn| query
+            .fetch_one(conn)
+            .await)
+    }
+
+    pub async fn fetch_optional<T: FromDbRow>(self) -> Result<Option<T>, SqlxError> {
+        dispatch!(self, sqlx::query_as::<_, T>, |query, conn| query
+            .fetch_optional(conn)
+            .await)
+    }
+
+    pub async fn fetch_scalar<T: DbValue>(self) -> Result<T, SqlxError> {
+        dispatch!(self, sqlx::query_scalar::<_, T>, |query, conn| query
+            .fetch_one(conn)
+            .await)
+    }
+
+    pub async fn fetch_optional_scalar<T: DbValue>(self) -> Result<Option<T>, SqlxError> {
+        dispatch!(self, sqlx::query_scalar::<_, T>, |query, conn| query
+            .fetch_optional(conn)
+            .await)
+    }
+
+    pub async fn fetch_all_scalar<T: DbValue>(self) -> Result<Vec<T>, SqlxError> {
+        dispatch!(self, sqlx::query_scalar::<_, T>, |query, conn| query
+            .fetch_all(conn)
+            .await)
+    }
+}
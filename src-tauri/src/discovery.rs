@@ -0,0 +1,286 @@
+use crate::claude_config::ClaudeConfigManager;
+use crate::database::Database;
+use crate::import_engine::{self, ExistingRecords};
+use crate::models::*;
+use sqlx::Error as SqlxError;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 未在 URL 配置中登记时，依次尝试的令牌环境变量
+pub const TOKEN_KEYS: [&str; 3] = [
+    "ANTHROPIC_AUTH_TOKEN",
+    "ANTHROPIC_API_KEY",
+    "CLAUDE_API_KEY",
+];
+
+fn home_dir() -> Option<PathBuf> {
+    std::env::var("HOME")
+        .or_else(|_| std::env::var("USERPROFILE"))
+        .ok()
+        .map(PathBuf::from)
+}
+
+/// 简单的通配符匹配，支持 `*` 和 `?`
+pub fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, n));
+            p += 1;
+        } else if let Some((star_p, star_n)) = backtrack {
+            p = star_p + 1;
+            n = star_n + 1;
+            backtrack = Some((star_p, star_n + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// 展开扫描根目录：支持 `~` 和路径段中的通配符（如 `~/code/*`）
+/// 不含通配符时扫描该目录本身及其直接子目录
+pub fn expand_root(root: &str) -> Vec<PathBuf> {
+    let root = root.trim();
+    if root.is_empty() {
+        return Vec::new();
+    }
+
+    let expanded = match root.strip_prefix('~') {
+        Some(rest) => match home_dir() {
+            Some(home) => home.join(rest.trim_start_matches(['/', '\\'])),
+            None => PathBuf::from(root),
+        },
+        None => PathBuf::from(root),
+    };
+
+    let has_wildcard = root.contains('*') || root.contains('?');
+    let mut current = vec![PathBuf::new()];
+    for component in expanded.components() {
+        let segment = component.as_os_str().to_string_lossy().to_string();
+        if segment.contains('*') || segment.contains('?') {
+            current = current
+                .iter()
+                .flat_map(|base| child_dirs(base))
+                .filter(|path| {
+                    path.file_name()
+                        .map(|name| wildcard_match(&segment, &name.to_string_lossy()))
+                        .unwrap_or(false)
+                })
+                .collect();
+        } else {
+            for path in current.iter_mut() {
+                path.push(component.as_os_str());
+            }
+        }
+    }
+
+    let mut dirs: Vec<PathBuf> = current.into_iter().filter(|p| p.is_dir()).collect();
+    if !has_wildcard {
+        let children: Vec<PathBuf> = dirs.iter().flat_map(|d| child_dirs(d)).collect();
+        dirs.extend(children);
+    }
+    dirs
+}
+
+fn child_dirs(dir: &Path) -> Vec<PathBuf> {
+    let mut children: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|path| path.is_dir())
+                .filter(|path| {
+                    !path
+                        .file_name()
+                        .map(|n| n.to_string_lossy().starts_with('.'))
+                        .unwrap_or(true)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    children.sort();
+    children
+}
+
+/// 包含 .claude 目录或 CLAUDE.md 的目录视为 Claude 项目
+/// 用户主目录下的 .claude 是全局配置，不算作项目
+pub fn is_claude_project(path: &Path) -> bool {
+    if home_dir().map(|home| home == path).unwrap_or(false) {
+        return false;
+    }
+    path.join(".claude").is_dir() || path.join("CLAUDE.md").is_file()
+}
+
+/// 读取项目当前的环境变量，识别令牌、API 地址和模型
+pub fn inspect_project(path: &Path, base_urls: &[BaseUrl]) -> DiscoveredProject {
+    let path_str = path.to_string_lossy().to_string();
+    let env = ClaudeConfigManager::new(path_str.clone())
+        .get_env_config()
+        .unwrap_or_default();
+
+    let base_url = env
+        .get("ANTHROPIC_BASE_URL")
+        .map(|url| url.trim_end_matches('/').to_string())
+        .filter(|url| !url.is_empty());
+    let known_base_url = base_url.as_ref().and_then(|url| {
+        base_urls
+            .iter()
+            .find(|bu| bu.url.trim_end_matches('/') == url)
+    });
+
+    // 优先使用 URL 配置中登记的令牌变量名
+    let token_key = known_base_url
+        .map(|bu| bu.api_key.clone())
+        .filter(|key| env.contains_key(key))
+        .or_else(|| {
+            TOKEN_KEYS
+                .iter()
+                .find(|key| env.contains_key(**key))
+                .map(|key| key.to_string())
+        });
+    let token = token_key
+        .as_ref()
+        .and_then(|key| env.get(key).cloned())
+        .filter(|token| !token.is_empty());
+
+    DiscoveredProject {
+        name: path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| path_str.clone()),
+        path: path_str,
+        directory_id: None,
+        token_key: token.as_ref().and(token_key),
+        token,
+        base_url_known: known_base_url.is_some(),
+        base_url: known_base_url.map(|bu| bu.url.clone()).or(base_url),
+        model: env
+            .get("ANTHROPIC_MODEL")
+            .cloned()
+            .filter(|m| !m.is_empty()),
+        account_id: None,
+        account_name: None,
+    }
+}
+
+/// 扫描所有根目录，返回发现的项目（包括已登记的目录）
+pub async fn discover_projects(
+    db: &Database,
+    roots: &[String],
+) -> Result<Vec<DiscoveredProject>, SqlxError> {
+    let directories = db.get_directories().await?;
+    let base_urls = db.get_base_urls().await?;
+
+    let mut seen = HashSet::new();
+    let mut projects = Vec::new();
+
+    for root in roots {
+        for path in expand_root(root) {
+            if !is_claude_project(&path) || !seen.insert(path.clone()) {
+                continue;
+            }
+
+            let mut project = inspect_project(&path, &base_urls);
+            project.directory_id = directories
+                .iter()
+                .find(|d| Path::new(&d.path) == path)
+                .map(|d| d.id);
+            if let Some(token) = &project.token {
+                if let Some(account) = db.find_account_by_token(token).await? {
+                    project.account_id = Some(account.id);
+                    project.account_name = Some(account.name);
+                }
+            }
+            projects.push(project);
+        }
+    }
+
+    projects.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(projects)
+}
+
+/// 导入发现的项目：登记目录，create_accounts 为 true 时同时创建未登记的账号和 API 地址
+pub async fn import_projects(
+    db: &Database,
+    projects: &[DiscoveredProject],
+    create_accounts: bool,
+) -> Result<DiscoveryImportResult, SqlxError> {
+    let mut bundle = ImportBundle::default();
+    let (mut urls, mut tokens) = (HashSet::new(), HashSet::new());
+    for project in projects {
+        bundle.directories.push(ImportedDirectory {
+            name: project.name.clone(),
+            path: project.path.clone(),
+        });
+
+        if !create_accounts || project.account_id.is_some() {
+            continue;
+        }
+        let (token, base_url) = match (&project.token, &project.base_url) {
+            (Some(token), Some(base_url)) => (token, base_url),
+            _ => continue,
+        };
+        if !project.base_url_known && urls.insert(base_url.clone()) {
+            bundle.base_urls.push(ImportedBaseUrl {
+                name: base_url_name(base_url),
+                url: base_url.clone(),
+                description: Some(format!("从项目 {} 发现", project.name)),
+                api_key: project.token_key.clone(),
+                is_default: false,
+                ..Default::default()
+            });
+        }
+        // 同一令牌可能出现在多个项目中
+        if tokens.insert(token.clone()) {
+            bundle.accounts.push(ImportedAccount {
+                name: project.name.clone(),
+                token: token.clone(),
+                base_url: base_url.clone(),
+                model: project.model.clone().unwrap_or_default(),
+                ..Default::default()
+            });
+        }
+    }
+
+    // 重名的账号和 API 地址追加序号导入，令牌或 URL 已登记的跳过
+    let existing = ExistingRecords::load(db).await?;
+    let items = import_engine::plan(&bundle, &existing);
+    let report = import_engine::apply(db, &bundle, &items, import_engine::STRATEGY_RENAME).await?;
+
+    let mut result = DiscoveryImportResult::default();
+    for item in &report.items {
+        let created = item.action == import_engine::ACTION_CREATED
+            || item.action == import_engine::ACTION_RENAMED;
+        match item.kind.as_str() {
+            _ if item.action == import_engine::ACTION_FAILED => result.errors.push(format!(
+                "{}: {}",
+                item.name,
+                item.detail.clone().unwrap_or_default()
+            )),
+            import_engine::KIND_DIRECTORY if created => result.directories_created += 1,
+            import_engine::KIND_ACCOUNT if created => result.accounts_created += 1,
+            import_engine::KIND_BASE_URL if created => result.base_urls_created += 1,
+            _ => {}
+        }
+    }
+    Ok(result)
+}
+
+/// 以 URL 的主机名作为默认名称
+pub fn base_url_name(url: &str) -> String {
+    url.split("://")
+        .nth(1)
+        .and_then(|rest| rest.split('/').next())
+        .filter(|host| !host.is_empty())
+        .unwrap_or(url)
+        .to_string()
+}
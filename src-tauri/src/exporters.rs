@@ -0,0 +1,433 @@
+use crate::database::Database;
+use crate::models::*;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// 完整导出文件的格式标识和版本
+const EXPORT_FORMAT: &str = "claude-config";
+const EXPORT_VERSION: u32 = 1;
+
+/// 导出时需要的全部数据，已按导出条件过滤
+pub struct ExportData {
+    pub accounts: Vec<Account>,
+    pub base_urls: Vec<BaseUrl>,
+    pub directories: Vec<Directory>,
+    pub bindings: Vec<AccountBinding>,
+    pub pool_members: Vec<AccountPoolMember>,
+}
+
+impl ExportData {
+    /// 账号切换时写入的环境变量：令牌变量名取自 API 地址的配置
+    fn account_env(&self, account: &Account) -> BTreeMap<String, String> {
+        let token_key = self
+            .base_urls
+            .iter()
+            .find(|bu| bu.url == account.base_url)
+            .map(|bu| bu.api_key.clone())
+            .unwrap_or_else(|| "ANTHROPIC_API_KEY".to_string());
+
+        let mut env = BTreeMap::new();
+        env.insert("ANTHROPIC_BASE_URL".to_string(), account.base_url.clone());
+        env.insert(token_key, account.token.clone());
+        if !account.model.is_empty() {
+            env.insert("ANTHROPIC_MODEL".to_string(), account.model.clone());
+        }
+        if let Ok(custom) =
+            serde_json::from_str::<BTreeMap<String, String>>(&account.custom_env_vars)
+        {
+            env.extend(custom);
+        }
+        // 只导出合法的变量名，否则 source 导出的脚本时变量名中的内容会被当作命令执行
+        env.retain(|key, _| is_env_name(key));
+        env
+    }
+}
+
+fn is_env_name(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// 名称写入注释行前去掉换行，防止名称中的内容成为脚本中单独的一行
+fn comment(text: &str) -> String {
+    format!("# {}", text.replace(['\r', '\n'], " "))
+}
+
+/// 导出生成的单个文件
+pub struct ExportFile {
+    pub file_name: String,
+    pub content: String,
+}
+
+/// 账号导出器：把账号渲染为一种文件格式
+pub trait AccountExporter: Send + Sync {
+    /// 格式标识
+    fn id(&self) -> &'static str;
+
+    /// 文件扩展名
+    fn extension(&self) -> &'static str;
+
+    /// 是否每个账号生成一个文件，此时输出路径为目录
+    fn per_account(&self) -> bool {
+        false
+    }
+
+    /// 渲染导出文件
+    fn render(&self, data: &ExportData) -> Result<Vec<ExportFile>, String>;
+}
+
+/// 可选的导出格式，第一个为默认格式
+pub const EXPORTERS: &[&dyn AccountExporter] = &[
+    &JsonExporter,
+    &ProvidersExporter,
+    &DotEnvExporter,
+    &ShellExporter,
+    &CsvExporter,
+];
+
+pub fn find_exporter(format: &str) -> Result<&'static dyn AccountExporter, String> {
+    EXPORTERS
+        .iter()
+        .copied()
+        .find(|exporter| exporter.id() == format)
+        .ok_or_else(|| format!("不支持的导出格式: {}", format))
+}
+
+/// 账号名转为可用作文件名和函数名的片段
+fn slug(name: &str) -> String {
+    let slug: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect::<String>()
+        .trim_matches('_')
+        .to_lowercase();
+    if slug.is_empty() {
+        "account".to_string()
+    } else {
+        slug
+    }
+}
+
+/// 为每个账号生成不重复的名称片段
+fn unique_slugs(accounts: &[Account]) -> Vec<String> {
+    let mut used = HashSet::new();
+    accounts
+        .iter()
+        .map(|account| {
+            let base = slug(&account.name);
+            let mut candidate = base.clone();
+            let mut index = 2;
+            while !used.insert(candidate.clone()) {
+                candidate = format!("{}_{}", base, index);
+                index += 1;
+            }
+            candidate
+        })
+        .collect()
+}
+
+/// 完整导出：账号、API 地址、目录和绑定关系，可被导入功能识别
+struct JsonExporter;
+
+impl AccountExporter for JsonExporter {
+    fn id(&self) -> &'static str {
+        "json"
+    }
+
+    fn extension(&self) -> &'static str {
+        "json"
+    }
+
+    fn render(&self, data: &ExportData) -> Result<Vec<ExportFile>, String> {
+        let document = json!({
+            "format": EXPORT_FORMAT,
+            "version": EXPORT_VERSION,
+            "exported_at": chrono::Utc::now().to_rfc3339(),
+            "accounts": data.accounts,
+            "base_urls": data.base_urls,
+            "directories": data.directories,
+            "bindings": data.bindings,
+        });
+        Ok(vec![ExportFile {
+            file_name: String::new(),
+            content: serde_json::to_string_pretty(&document).map_err(|e| e.to_string())?,
+        }])
+    }
+}
+
+/// 兼容其他切换工具的 providers 格式，权重和优先级取自账号所在的账号池
+struct ProvidersExporter;
+
+impl AccountExporter for ProvidersExporter {
+    fn id(&self) -> &'static str {
+        "providers"
+    }
+
+    fn extension(&self) -> &'static str {
+        "json"
+    }
+
+    fn render(&self, data: &ExportData) -> Result<Vec<ExportFile>, String> {
+        let providers: Vec<Value> = data
+            .accounts
+            .iter()
+            .map(|account| {
+                let member = data
+                    .pool_members
+                    .iter()
+                    .find(|m| m.account_id == account.id);
+                json!({
+                    "name": account.name,
+                    "url": account.base_url,
+                    "key": account.token,
+                    "is_enabled": account.is_active,
+                    "weight": member.map(|m| m.weight).unwrap_or(100),
+                    "priority": member.map(|m| m.priority).unwrap_or(1)
+                })
+            })
+            .collect();
+        Ok(vec![ExportFile {
+            file_name: String::new(),
+            content: serde_json::to_string_pretty(&json!({ "providers": providers }))
+                .map_err(|e| e.to_string())?,
+        }])
+    }
+}
+
+fn dotenv_value(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// 每个账号一个 `.env` 文件
+struct DotEnvExporter;
+
+impl AccountExporter for DotEnvExporter {
+    fn id(&self) -> &'static str {
+        "dotenv"
+    }
+
+    fn extension(&self) -> &'static str {
+        "env"
+    }
+
+    fn per_account(&self) -> bool {
+        true
+    }
+
+    fn render(&self, data: &ExportData) -> Result<Vec<ExportFile>, String> {
+        Ok(data
+            .accounts
+            .iter()
+            .zip(unique_slugs(&data.accounts))
+            .map(|(account, slug)| {
+                let mut content = format!("{}\n", comment(&account.name));
+                for (key, value) in data.account_env(account) {
+                    content.push_str(&format!("{}={}\n", key, dotenv_value(&value)));
+                }
+                ExportFile {
+                    file_name: format!("{}.env", slug),
+                    content,
+                }
+            })
+            .collect())
+    }
+}
+
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// 可 source 的 shell 脚本，每个账号一个 `claude_use_<名称>` 函数
+struct ShellExporter;
+
+impl AccountExporter for ShellExporter {
+    fn id(&self) -> &'static str {
+        "shell"
+    }
+
+    fn extension(&self) -> &'static str {
+        "sh"
+    }
+
+    fn render(&self, data: &ExportData) -> Result<Vec<ExportFile>, String> {
+        let mut content = String::from(
+            "#!/usr/bin/env sh\n# 使用 `source <文件>` 加载后，调用 claude_use_<名称> 切换账号\n",
+        );
+        for (account, slug) in data.accounts.iter().zip(unique_slugs(&data.accounts)) {
+            content.push_str(&format!(
+                "\n{}\nclaude_use_{}() {{\n",
+                comment(&account.name),
+                slug
+            ));
+            for (key, value) in data.account_env(account) {
+                content.push_str(&format!("    export {}={}\n", key, shell_quote(&value)));
+            }
+            content.push_str("}\n");
+        }
+        Ok(vec![ExportFile {
+            file_name: String::new(),
+            content,
+        }])
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// 表格，列名与导入功能识别的 CSV 表头一致
+struct CsvExporter;
+
+impl AccountExporter for CsvExporter {
+    fn id(&self) -> &'static str {
+        "csv"
+    }
+
+    fn extension(&self) -> &'static str {
+        "csv"
+    }
+
+    fn render(&self, data: &ExportData) -> Result<Vec<ExportFile>, String> {
+        let mut content = String::from("name,base_url,token,model,tags,notes,enabled,expires_at\n");
+        for account in &data.accounts {
+            let fields = [
+                account.name.clone(),
+                account.base_url.clone(),
+                account.token.clone(),
+                account.model.clone(),
+                account.tags.clone(),
+                account.notes.clone(),
+                account.enabled.to_string(),
+                account
+                    .expires_at
+                    .map(|t| t.format("%Y-%m-%d").to_string())
+                    .unwrap_or_default(),
+            ];
+            let row: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+            content.push_str(&row.join(","));
+            content.push('\n');
+        }
+        Ok(vec![ExportFile {
+            file_name: String::new(),
+            content,
+        }])
+    }
+}
+
+/// 按导出条件收集账号及其关联的 API 地址、目录和绑定关系
+pub async fn collect(db: &Database, filter: &ExportFilter) -> Result<ExportData, sqlx::Error> {
+    let tag = filter
+        .tag
+        .as_deref()
+        .map(str::trim)
+        .filter(|t| !t.is_empty());
+    let base_url = filter.base_url.as_deref().filter(|u| !u.is_empty());
+    let accounts: Vec<Account> = db
+        .get_all_accounts()
+        .await?
+        .into_iter()
+        .filter(|a| tag.is_none_or(|tag| a.tag_list().iter().any(|t| t == tag)))
+        .filter(|a| base_url.is_none_or(|url| a.base_url == url))
+        .filter(|a| {
+            filter
+                .account_ids
+                .as_ref()
+                .is_none_or(|ids| ids.contains(&a.id))
+        })
+        .collect();
+
+    let account_ids: HashSet<i64> = accounts.iter().map(|a| a.id).collect();
+    let urls: HashSet<&str> = accounts.iter().map(|a| a.base_url.as_str()).collect();
+    let base_urls = db
+        .get_base_urls()
+        .await?
+        .into_iter()
+        .filter(|bu| urls.contains(bu.url.as_str()))
+        .collect();
+    let bindings: Vec<AccountBinding> = db
+        .get_account_bindings()
+        .await?
+        .into_iter()
+        .filter(|b| account_ids.contains(&b.account_id))
+        .collect();
+    let paths: HashSet<&str> = bindings.iter().map(|b| b.directory_path.as_str()).collect();
+    let directories = db
+        .get_directories()
+        .await?
+        .into_iter()
+        .filter(|d| paths.contains(d.path.as_str()))
+        .collect();
+    let pool_members = db.get_account_pool_members(None).await?;
+
+    Ok(ExportData {
+        accounts,
+        base_urls,
+        directories,
+        bindings,
+        pool_members,
+    })
+}
+
+/// 默认输出路径：单文件格式为带时间戳的文件，按账号拆分的格式为同名目录
+pub fn default_output(exporter: &dyn AccountExporter) -> String {
+    let timestamp = chrono::Local::now().format("%Y-%m-%dT%H-%M-%S");
+    if exporter.per_account() {
+        format!("accounts_export_{}", timestamp)
+    } else {
+        format!("accounts_export_{}.{}", timestamp, exporter.extension())
+    }
+}
+
+/// 渲染并写入导出文件，返回写入的文件路径
+pub fn write(
+    exporter: &dyn AccountExporter,
+    data: &ExportData,
+    output: &Path,
+) -> Result<Vec<PathBuf>, String> {
+    let files = exporter.render(data)?;
+    let mut written = Vec::new();
+    if exporter.per_account() {
+        std::fs::create_dir_all(output).map_err(|e| e.to_string())?;
+        for file in files {
+            let path = output.join(&file.file_name);
+            std::fs::write(&path, file.content).map_err(|e| e.to_string())?;
+            written.push(path);
+        }
+    } else {
+        for file in files {
+            std::fs::write(output, file.content).map_err(|e| e.to_string())?;
+            written.push(output.to_path_buf());
+        }
+    }
+    Ok(written)
+}
+
+/// 按条件导出账号到指定路径
+pub async fn export_accounts(
+    db: &Database,
+    format: &str,
+    filter: &ExportFilter,
+    output: &Path,
+) -> Result<ExportResult, String> {
+    let exporter = find_exporter(format)?;
+    let data = collect(db, filter).await.map_err(|e| e.to_string())?;
+    if data.accounts.is_empty() {
+        return Err("没有符合条件的账号".to_string());
+    }
+    let files = write(exporter, &data, output)?;
+    Ok(ExportResult {
+        accounts: data.accounts.len(),
+        files: files
+            .iter()
+            .map(|p| p.to_string_lossy().to_string())
+            .collect(),
+    })
+}
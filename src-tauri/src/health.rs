@@ -0,0 +1,208 @@
+use crate::custom_headers::{self, CustomHeader};
+use crate::models::*;
+use crate::proxy;
+use reqwest::header::{HeaderMap, HeaderValue};
+use std::time::{Duration, Instant};
+use tokio::net::TcpStream;
+use tokio_native_tls::{native_tls, TlsConnector};
+
+/// 单次探测的超时时间
+pub const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+/// 令牌对应的认证头：ANTHROPIC_AUTH_TOKEN 使用 Bearer，其他使用 x-api-key
+pub fn auth_headers(token_key: &str, token: &str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        "anthropic-version",
+        HeaderValue::from_static(ANTHROPIC_VERSION),
+    );
+    let header = if token_key == "ANTHROPIC_AUTH_TOKEN" {
+        ("authorization", format!("Bearer {}", token))
+    } else {
+        ("x-api-key", token.to_string())
+    };
+    if let Ok(value) = HeaderValue::from_str(&header.1) {
+        headers.insert(header.0, value);
+    }
+    headers
+}
+
+fn elapsed_ms(start: Instant) -> Option<i64> {
+    Some(start.elapsed().as_millis() as i64)
+}
+
+/// 在已建立的 TCP 连接上完成 TLS 握手，只用于计时，握手后即断开
+async fn tls_handshake(stream: TcpStream, host: &str, timeout: Duration) -> Result<(), String> {
+    let connector = native_tls::TlsConnector::new().map_err(|e| format!("TLS 初始化失败: {}", e))?;
+    match tokio::time::timeout(timeout, TlsConnector::from(connector).connect(host, stream)).await {
+        Ok(Ok(_)) => Ok(()),
+        Ok(Err(e)) => Err(format!("TLS 握手失败: {}", e)),
+        Err(_) => Err("TLS 握手超时".to_string()),
+    }
+}
+
+/// 探测 API 地址：TCP 连接耗时、https 地址的 TLS 握手耗时、根路径的 HTTP 状态，以及可选的带认证请求（GET /v1/models）
+/// auth 为 (令牌变量名, 令牌)，extra_headers 为随每个请求发送的自定义请求头，返回的请求中 base_url_id 由调用方填写
+/// 指定代理时 HTTP 请求经过代理发送，TCP 连接耗时测量的是到代理服务器的连接，不单独测量 TLS 握手
+pub async fn probe_endpoint(
+    url: &str,
+    auth: Option<(&str, &str)>,
+    extra_headers: &[CustomHeader],
+    proxy: Option<&Proxy>,
+    timeout: Duration,
+) -> CreateEndpointCheckRequest {
+    let mut result = CreateEndpointCheckRequest::default();
+
+    let parsed = match reqwest::Url::parse(url) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            result.error = Some(format!("无效的地址: {}", e));
+            return result;
+        }
+    };
+    let (host, port) = match (parsed.host_str(), parsed.port_or_known_default()) {
+        (Some(host), Some(port)) => (host.to_string(), port),
+        _ => {
+            result.error = Some("地址缺少主机或端口".to_string());
+            return result;
+        }
+    };
+    let tls_host = (parsed.scheme() == "https" && proxy.is_none()).then(|| host.clone());
+    let (host, port) = match proxy {
+        Some(proxy) => match proxy.address() {
+            Some(address) => address,
+            None => {
+                result.error = Some(format!("代理 {} 的地址无效", proxy.name));
+                return result;
+            }
+        },
+        None => (host, port),
+    };
+
+    let start = Instant::now();
+    let stream = match tokio::time::timeout(timeout, TcpStream::connect((host.as_str(), port))).await {
+        Ok(Ok(stream)) => {
+            result.connect_ms = elapsed_ms(start);
+            stream
+        }
+        Ok(Err(e)) => {
+            result.error = Some(format!("连接失败: {}", e));
+            return result;
+        }
+        Err(_) => {
+            result.error = Some("连接超时".to_string());
+            return result;
+        }
+    };
+
+    // 计时用的连接不再使用，不需要 TLS 握手时立即关闭
+    match tls_host {
+        Some(tls_host) => {
+            let start = Instant::now();
+            match tls_handshake(stream, &tls_host, timeout).await {
+                Ok(()) => result.tls_ms = elapsed_ms(start),
+                Err(e) => {
+                    result.error = Some(e);
+                    return result;
+                }
+            }
+        }
+        None => drop(stream),
+    }
+
+    let client = match proxy::build_client(proxy, timeout) {
+        Ok(client) => client,
+        Err(e) => {
+            result.error = Some(e);
+            return result;
+        }
+    };
+
+    let extra_headers = custom_headers::header_map(extra_headers);
+    let start = Instant::now();
+    match client.get(url).headers(extra_headers.clone()).send().await {
+        Ok(response) => {
+            result.latency_ms = elapsed_ms(start);
+            result.status_code = Some(response.status().as_u16() as i64);
+        }
+        Err(e) => {
+            result.error = Some(format!("请求失败: {}", e));
+            return result;
+        }
+    }
+
+    if let Some((token_key, token)) = auth {
+        let models_url = format!("{}/v1/models", url.trim_end_matches('/'));
+        match client
+            .get(&models_url)
+            .headers(extra_headers)
+            .headers(auth_headers(token_key, token))
+            .send()
+            .await
+        {
+            Ok(response) => {
+                let status = response.status();
+                result.auth_status_code = Some(status.as_u16() as i64);
+                if !status.is_success() {
+                    result.error = Some(format!("认证请求返回 HTTP {}", status.as_u16()));
+                }
+            }
+            Err(e) => result.error = Some(format!("认证请求失败: {}", e)),
+        }
+    }
+
+    let reachable = result.status_code.map(|code| code < 500).unwrap_or(false);
+    if !reachable && result.error.is_none() {
+        result.error = Some(format!("服务返回 HTTP {}", result.status_code.unwrap_or(0)));
+    }
+    result.success = reachable && result.error.is_none();
+    result
+}
+
+/// 令牌校验结果
+pub const TOKEN_VALID: &str = "valid";
+pub const TOKEN_INVALID: &str = "invalid";
+pub const TOKEN_ERROR: &str = "error";
+
+/// 使用令牌发送最小的认证请求（GET /v1/models），返回 (校验结果, 错误信息)
+/// 401/403 视为令牌无效，其他失败（网络错误、服务不支持该接口等）记为 error
+pub async fn verify_token(
+    url: &str,
+    token_key: &str,
+    token: &str,
+    extra_headers: &[CustomHeader],
+    proxy: Option<&Proxy>,
+    timeout: Duration,
+) -> (&'static str, Option<String>) {
+    let client = match proxy::build_client(proxy, timeout) {
+        Ok(client) => client,
+        Err(e) => return (TOKEN_ERROR, Some(e)),
+    };
+
+    let models_url = format!("{}/v1/models", url.trim_end_matches('/'));
+    match client
+        .get(&models_url)
+        .headers(custom_headers::header_map(extra_headers))
+        .headers(auth_headers(token_key, token))
+        .send()
+        .await
+    {
+        Ok(response) => {
+            let status = response.status();
+            if status.is_success() {
+                (TOKEN_VALID, None)
+            } else {
+                let body = response.text().await.unwrap_or_default();
+                let message = format!("HTTP {}: {}", status.as_u16(), body.chars().take(200).collect::<String>());
+                if status.as_u16() == 401 || status.as_u16() == 403 {
+                    (TOKEN_INVALID, Some(message))
+                } else {
+                    (TOKEN_ERROR, Some(message))
+                }
+            }
+        }
+        Err(e) => (TOKEN_ERROR, Some(format!("请求失败: {}", e))),
+    }
+}
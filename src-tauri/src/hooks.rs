@@ -0,0 +1,106 @@
+use crate::models::Hook;
+use serde_json::{json, Value};
+
+/// Claude Code 支持的 Hook 事件
+pub const HOOK_EVENTS: [&str; 9] = [
+    "PreToolUse",
+    "PostToolUse",
+    "Notification",
+    "UserPromptSubmit",
+    "Stop",
+    "SubagentStop",
+    "PreCompact",
+    "SessionStart",
+    "SessionEnd",
+];
+
+/// 只有工具相关的事件支持 matcher
+pub fn hook_event_supports_matcher(event: &str) -> bool {
+    matches!(event, "PreToolUse" | "PostToolUse")
+}
+
+/// 校验 Hook 的事件、命令和超时时间
+pub fn validate_hook(event: &str, command: &str, timeout: Option<i64>) -> Result<(), String> {
+    if !HOOK_EVENTS.contains(&event) {
+        return Err(format!("不支持的 Hook 事件: {}", event));
+    }
+    if command.trim().is_empty() {
+        return Err("Hook 命令不能为空".to_string());
+    }
+    if let Some(timeout) = timeout {
+        if timeout <= 0 {
+            return Err("超时时间必须大于 0".to_string());
+        }
+    }
+    Ok(())
+}
+
+/// 将 Hook 合并到 settings 的 hooks 段
+/// 按 事件 + matcher 分组，已存在的相同命令不会重复添加
+pub fn merge_hooks_into_settings(settings: &mut Value, hooks: &[Hook]) {
+    if hooks.is_empty() {
+        return;
+    }
+    if !settings.is_object() {
+        *settings = json!({});
+    }
+    if !settings
+        .get("hooks")
+        .map(|h| h.is_object())
+        .unwrap_or(false)
+    {
+        settings["hooks"] = json!({});
+    }
+
+    let hooks_obj = settings["hooks"].as_object_mut().unwrap();
+
+    for hook in hooks {
+        let matcher = if hook_event_supports_matcher(&hook.event) {
+            hook.matcher.clone().unwrap_or_default()
+        } else {
+            String::new()
+        };
+
+        let mut hook_entry = json!({
+            "type": "command",
+            "command": hook.command,
+        });
+        if let Some(timeout) = hook.timeout {
+            hook_entry["timeout"] = json!(timeout);
+        }
+
+        let groups = hooks_obj
+            .entry(hook.event.clone())
+            .or_insert_with(|| json!([]));
+        if !groups.is_array() {
+            *groups = json!([]);
+        }
+        let groups = groups.as_array_mut().unwrap();
+
+        let group = groups
+            .iter_mut()
+            .find(|g| g.get("matcher").and_then(Value::as_str).unwrap_or("") == matcher);
+
+        match group {
+            Some(group) => {
+                if !group.get("hooks").map(|h| h.is_array()).unwrap_or(false) {
+                    group["hooks"] = json!([]);
+                }
+                let entries = group["hooks"].as_array_mut().unwrap();
+                let exists = entries.iter().any(|e| {
+                    e.get("command").and_then(Value::as_str) == Some(hook.command.as_str())
+                });
+                if !exists {
+                    entries.push(hook_entry);
+                }
+            }
+            None => {
+                let mut new_group = json!({ "hooks": [hook_entry] });
+                if !matcher.is_empty() {
+                    new_group["matcher"] = json!(matcher);
+                }
+                groups.push(new_group);
+            }
+        }
+    }
+}
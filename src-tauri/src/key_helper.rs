@@ -0,0 +1,147 @@
+use crate::models::Account;
+use serde_json::{json, Value};
+use std::time::Duration;
+use tokio::process::Command;
+
+/// 凭据模式：切换时直接写入账号令牌
+pub const MODE_TOKEN: &str = "token";
+
+/// 凭据模式：切换时写入 apiKeyHelper，由命令动态生成密钥
+pub const MODE_HELPER: &str = "helper";
+
+pub const CREDENTIAL_MODES: [&str; 2] = [MODE_TOKEN, MODE_HELPER];
+
+/// Claude Code 重新执行 apiKeyHelper 的间隔（毫秒）
+pub const HELPER_TTL_ENV: &str = "CLAUDE_CODE_API_KEY_HELPER_TTL_MS";
+
+/// 测试运行辅助命令的超时时间
+pub const HELPER_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 校验凭据配置：helper 模式必须填写命令，TTL 必须为正数
+pub fn validate_credential(
+    mode: &str,
+    helper: Option<&str>,
+    ttl_ms: Option<i64>,
+) -> Result<(), String> {
+    if !CREDENTIAL_MODES.contains(&mode) {
+        return Err(format!(
+            "不支持的凭据模式 '{}'，可选值: {}",
+            mode,
+            CREDENTIAL_MODES.join(", ")
+        ));
+    }
+    if mode == MODE_HELPER && helper.map(str::trim).unwrap_or("").is_empty() {
+        return Err("辅助命令模式必须填写命令".to_string());
+    }
+    if ttl_ms.map(|ttl| ttl <= 0).unwrap_or(false) {
+        return Err("缓存时间必须大于 0".to_string());
+    }
+    Ok(())
+}
+
+impl Account {
+    /// 是否通过 apiKeyHelper 命令获取密钥
+    pub fn uses_key_helper(&self) -> bool {
+        self.credential_mode == MODE_HELPER
+            && self
+                .api_key_helper
+                .as_deref()
+                .map(|c| !c.trim().is_empty())
+                .unwrap_or(false)
+    }
+}
+
+/// 按账号的凭据模式处理 settings：helper 模式写入 apiKeyHelper 并移除 env 中的静态密钥，
+/// token 模式移除之前写入的 apiKeyHelper
+pub fn apply_key_helper_to_settings(settings: &mut Value, account: &Account, api_key_name: &str) {
+    if !settings.is_object() {
+        *settings = json!({});
+    }
+    let settings_obj = settings.as_object_mut().unwrap();
+
+    if !account.uses_key_helper() {
+        settings_obj.remove("apiKeyHelper");
+        if let Some(env) = settings_obj.get_mut("env").and_then(Value::as_object_mut) {
+            env.remove(HELPER_TTL_ENV);
+        }
+        return;
+    }
+
+    let command = account.api_key_helper.as_deref().unwrap_or("").trim();
+    settings_obj.insert("apiKeyHelper".to_string(), json!(command));
+
+    if !settings_obj
+        .get("env")
+        .map(Value::is_object)
+        .unwrap_or(false)
+    {
+        settings_obj.insert("env".to_string(), json!({}));
+    }
+    let env = settings_obj
+        .get_mut("env")
+        .and_then(Value::as_object_mut)
+        .unwrap();
+    env.remove(api_key_name);
+    env.remove("ANTHROPIC_API_KEY");
+    env.remove("ANTHROPIC_AUTH_TOKEN");
+    match account.api_key_helper_ttl_ms {
+        Some(ttl) => {
+            env.insert(HELPER_TTL_ENV.to_string(), json!(ttl.to_string()));
+        }
+        None => {
+            env.remove(HELPER_TTL_ENV);
+        }
+    }
+}
+
+/// 执行辅助命令，返回标准输出中的密钥；命令失败、超时或没有输出时返回错误
+pub async fn run_key_helper(command: &str, timeout: Duration) -> Result<String, String> {
+    let command = command.trim();
+    if command.is_empty() {
+        return Err("辅助命令为空".to_string());
+    }
+
+    let mut cmd = if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", command]);
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", command]);
+        cmd
+    };
+    cmd.kill_on_drop(true);
+
+    let output = tokio::time::timeout(timeout, cmd.output())
+        .await
+        .map_err(|_| format!("辅助命令执行超时（{} 秒）", timeout.as_secs()))?
+        .map_err(|e| format!("无法执行辅助命令: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!(
+            "辅助命令退出码 {}: {}",
+            output.status.code().unwrap_or(-1),
+            stderr.trim().chars().take(200).collect::<String>()
+        ));
+    }
+
+    let key = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if key.is_empty() {
+        return Err("辅助命令没有输出密钥".to_string());
+    }
+    Ok(key)
+}
+
+/// 账号用于请求的密钥：helper 模式执行命令获取，否则使用保存的令牌
+pub async fn resolve_token(account: &Account) -> Result<String, String> {
+    if account.uses_key_helper() {
+        run_key_helper(
+            account.api_key_helper.as_deref().unwrap_or(""),
+            HELPER_TIMEOUT,
+        )
+        .await
+    } else {
+        Ok(account.token.clone())
+    }
+}
@@ -1,22 +1,50 @@
 mod models;
 mod database;
+mod db_backend;
 mod claude_config;
 mod config_manager;
 mod logger;
 mod webdav;
+mod permissions;
+mod definitions;
+mod discovery;
+mod drift;
+mod health;
+mod watcher;
+mod balance;
+mod pools;
+mod providers;
+mod proxy;
+mod relay;
+mod import_engine;
+mod importers;
+mod key_helper;
+mod custom_headers;
+mod migrations;
+mod exporters;
+mod mcp;
+mod hooks;
 
 use std::sync::Arc;
-use tauri::{State, Manager, WindowEvent, tray::{TrayIconBuilder, TrayIconEvent}, menu::{Menu, MenuItem}};
+use tauri::{State, Manager, AppHandle, Emitter, WindowEvent, tray::{TrayIconBuilder, TrayIconEvent}, menu::{Menu, MenuItem}};
 use tokio::sync::Mutex;
 use models::*;
+use mcp::validate_mcp_server;
+use hooks::{merge_hooks_into_settings, validate_hook};
 use database::Database;
+use db_backend::Backend;
+use import_engine::ExistingRecords;
 use claude_config::ClaudeConfigManager;
-use include_dir::{include_dir, Dir};
-
-// 在编译时嵌入整个 commands 目录
-static COMMANDS_DIR: Dir = include_dir!("$CARGO_MANIFEST_DIR/resources/config/commands");
 
 type DbState = Arc<Mutex<Database>>;
+type WatcherState = Arc<watcher::ConfigWatcher>;
+/// 本地中转代理的监听端口和后台任务，未运行时为 None
+type RelayState = Arc<Mutex<Option<(u16, tauri::async_runtime::JoinHandle<()>)>>>;
+
+/// 是否为唯一约束冲突：按数据库错误类型判断，不依赖各数据库不同的错误信息
+fn is_unique_violation(e: &sqlx::Error) -> bool {
+    matches!(e, sqlx::Error::Database(db_error) if db_error.is_unique_violation())
+}
 
 #[tauri::command]
 fn save_file(path: String, contents: String) -> Result<(), String> {
@@ -123,6 +151,10 @@ async fn create_account(
     baseUrl: String,
     model: String,
     customEnvVars: Option<serde_json::Value>,
+    enabled: Option<bool>,
+    expiresAt: Option<String>,
+    notes: Option<String>,
+    tags: Option<Vec<String>>,
 ) -> Result<Account, String> {
     tracing::info!("创建账号: name={}, baseUrl={}, model={}", name, baseUrl, model);
 
@@ -133,6 +165,10 @@ async fn create_account(
         base_url: baseUrl,
         model,
         custom_env_vars: customEnvVars,
+        enabled,
+        expires_at: expiresAt,
+        notes,
+        tags,
     };
     
     match db.create_account(request).await {
@@ -143,7 +179,7 @@ async fn create_account(
         Err(e) => {
             let error_msg = e.to_string();
             tracing::error!("创建账号失败: {}", error_msg);
-            if error_msg.contains("UNIQUE constraint failed: accounts.name") {
+            if is_unique_violation(&e) {
                 Err("账号名称已存在".to_string())
             } else {
                 Err(format!("创建账号失败: {}", error_msg))
@@ -162,6 +198,10 @@ async fn update_account(
     baseUrl: Option<String>,
     model: Option<String>,
     customEnvVars: Option<serde_json::Value>,
+    enabled: Option<bool>,
+    expiresAt: Option<String>,
+    notes: Option<String>,
+    tags: Option<Vec<String>>,
 ) -> Result<Account, String> {
     let db = db.lock().await;
     let request = UpdateAccountRequest {
@@ -170,13 +210,17 @@ async fn update_account(
         base_url: baseUrl,
         model,
         custom_env_vars: customEnvVars,
+        enabled,
+        expires_at: expiresAt,
+        notes,
+        tags,
     };
     
     db.update_account(id, request)
         .await
         .map_err(|e| {
             let error_msg = e.to_string();
-            if error_msg.contains("UNIQUE constraint failed: accounts.name") {
+            if is_unique_violation(&e) {
                 "账号名称已存在".to_string()
             } else {
                 error_msg
@@ -219,6 +263,52 @@ async fn get_account_base_urls(db: State<'_, DbState>) -> Result<Vec<String>, St
         .map_err(|e| e.to_string())
 }
 
+/// 获取已过期或即将到期的已启用账号，前端启动时据此提示
+#[tauri::command]
+async fn get_expiring_accounts(db: State<'_, DbState>) -> Result<Vec<Account>, String> {
+    let db = db.lock().await;
+    db.get_expiring_accounts(EXPIRY_WARNING_DAYS)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn preview_account_import(
+    db: State<'_, DbState>,
+    fileName: String,
+    content: String,
+) -> Result<ImportPreview, String> {
+    let db = db.lock().await;
+    let existing = ExistingRecords::load(&db).await.map_err(|e| e.to_string())?;
+    importers::preview(&fileName, &content, &existing)
+}
+
+#[tauri::command]
+async fn import_accounts(
+    db: State<'_, DbState>,
+    bundle: ImportBundle,
+    items: Vec<ImportItem>,
+    strategy: String,
+) -> Result<ImportReport, String> {
+    let db = db.lock().await;
+    import_engine::apply(&db, &bundle, &items, &strategy)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn export_accounts(
+    db: State<'_, DbState>,
+    format: String,
+    filter: ExportFilter,
+    outputPath: String,
+) -> Result<ExportResult, String> {
+    let db = db.lock().await;
+    exporters::export_accounts(&db, &format, &filter, std::path::Path::new(&outputPath)).await
+}
+
 #[tauri::command]
 async fn get_directories(db: State<'_, DbState>) -> Result<Vec<Directory>, String> {
     let db = db.lock().await;
@@ -230,27 +320,32 @@ async fn get_directories(db: State<'_, DbState>) -> Result<Vec<Directory>, Strin
 #[tauri::command]
 async fn create_directory(
     db: State<'_, DbState>,
+    watcher: State<'_, WatcherState>,
     path: String,
     name: String,
 ) -> Result<Directory, String> {
     let db = db.lock().await;
     let request = CreateDirectoryRequest { path, name };
 
-    db.create_directory(request)
+    let directory = db.create_directory(request)
         .await
         .map_err(|e| {
             let error_msg = e.to_string();
-            if error_msg.contains("UNIQUE constraint failed: directories.path") {
+            if is_unique_violation(&e) {
                 "目录已存在".to_string()
             } else {
                 error_msg
             }
-        })
+        })?;
+
+    sync_watched_directories(&db, &watcher).await;
+    Ok(directory)
 }
 
 #[tauri::command]
 async fn update_directory(
     db: State<'_, DbState>,
+    watcher: State<'_, WatcherState>,
     id: i64,
     path: Option<String>,
     name: Option<String>,
@@ -258,20 +353,31 @@ async fn update_directory(
     let db = db.lock().await;
     let request = UpdateDirectoryRequest { path, name };
 
-    db.update_directory(id, request)
+    let directory = db.update_directory(id, request)
         .await
         .map_err(|e| {
             let error_msg = e.to_string();
-            if error_msg.contains("UNIQUE constraint failed: directories.path") {
+            if is_unique_violation(&e) {
                 "目录已存在".to_string()
             } else {
                 error_msg
             }
-        })
+        })?;
+
+    sync_watched_directories(&db, &watcher).await;
+    Ok(directory)
+}
+
+/// 目录列表变化后同步文件监听
+async fn sync_watched_directories(db: &Database, watcher: &watcher::ConfigWatcher) {
+    match db.get_directories().await {
+        Ok(directories) => watcher.sync_directories(&directories),
+        Err(e) => tracing::warn!("同步文件监听列表失败: {}", e),
+    }
 }
 
 #[tauri::command]
-async fn delete_directory(db: State<'_, DbState>, id: i64) -> Result<String, String> {
+async fn delete_directory(db: State<'_, DbState>, watcher: State<'_, WatcherState>, id: i64) -> Result<String, String> {
     use tracing::info;
     
     info!("开始删除目录，ID: {}", id);
@@ -294,6 +400,7 @@ async fn delete_directory(db: State<'_, DbState>, id: i64) -> Result<String, Str
     match db.delete_directory(id).await {
         Ok(_) => {
             info!("成功处理 {}", directory_info);
+            sync_watched_directories(&db, &watcher).await;
             // 检查是否是清理不存在的目录
             if directory_info.contains("文件系统中不存在") {
                 Ok(format!("已清理不存在的目录记录"))
@@ -330,31 +437,29 @@ async fn create_base_url(
     url: String,
     description: Option<String>,
     apiKey: Option<String>,
+    providerType: Option<String>,
     isDefault: Option<bool>,
     defaultEnvVars: Option<serde_json::Value>,
 ) -> Result<BaseUrl, String> {
+    if let Some(provider_type) = &providerType {
+        providers::validate_provider_type(provider_type)?;
+    }
     let db = db.lock().await;
+    let error_name = name.clone();
     let request = CreateBaseUrlRequest {
         name,
         url,
         description,
         api_key: apiKey,
+        provider_type: providerType,
         is_default: isDefault,
         default_env_vars: defaultEnvVars,
     };
     
-    db.create_base_url(request)
-        .await
-        .map_err(|e| {
-            let error_msg = e.to_string();
-            if error_msg.contains("UNIQUE constraint failed: base_urls.name") {
-                "Base URL 名称已存在".to_string()
-            } else if error_msg.contains("UNIQUE constraint failed: base_urls.url") {
-                "Base URL 地址已存在".to_string()
-            } else {
-                error_msg
-            }
-        })
+    match db.create_base_url(request).await {
+        Ok(base_url) => Ok(base_url),
+        Err(e) => Err(map_base_url_error(&db, e, None, Some(&error_name)).await),
+    }
 }
 
 #[tauri::command]
@@ -366,31 +471,47 @@ async fn update_base_url(
     url: Option<String>,
     description: Option<String>,
     apiKey: Option<String>,
+    providerType: Option<String>,
     isDefault: Option<bool>,
     defaultEnvVars: Option<serde_json::Value>,
 ) -> Result<BaseUrl, String> {
+    if let Some(provider_type) = &providerType {
+        providers::validate_provider_type(provider_type)?;
+    }
     let db = db.lock().await;
+    let error_name = name.clone();
     let request = UpdateBaseUrlRequest {
         name,
         url,
         description,
         api_key: apiKey,
+        provider_type: providerType,
         is_default: isDefault,
         default_env_vars: defaultEnvVars,
     };
     
-    db.update_base_url(id, request)
-        .await
-        .map_err(|e| {
-            let error_msg = e.to_string();
-            if error_msg.contains("UNIQUE constraint failed: base_urls.name") {
-                "Base URL 名称已存在".to_string()
-            } else if error_msg.contains("UNIQUE constraint failed: base_urls.url") {
-                "Base URL 地址已存在".to_string()
-            } else {
-                error_msg
-            }
-        })
+    match db.update_base_url(id, request).await {
+        Ok(base_url) => Ok(base_url),
+        Err(e) => Err(map_base_url_error(&db, e, Some(id), error_name.as_deref()).await),
+    }
+}
+
+/// Base URL 的名称和地址各有唯一约束，冲突时按名称是否已被其它记录使用区分
+async fn map_base_url_error(db: &Database, e: sqlx::Error, id: Option<i64>, name: Option<&str>) -> String {
+    if !is_unique_violation(&e) {
+        return e.to_string();
+    }
+    let name_taken = match name {
+        Some(name) => db.get_base_urls().await
+            .map(|base_urls| base_urls.iter().any(|bu| bu.name == name && Some(bu.id) != id))
+            .unwrap_or(false),
+        None => false,
+    };
+    if name_taken {
+        "Base URL 名称已存在".to_string()
+    } else {
+        "Base URL 地址已存在".to_string()
+    }
 }
 
 #[tauri::command]
@@ -433,6 +554,7 @@ async fn check_claude_local_md_exists(
 #[allow(non_snake_case)]
 async fn switch_account(
     db: State<'_, DbState>,
+    watcher: State<'_, WatcherState>,
     accountId: i64,
     directoryId: i64,
     keepClaudeLocalMd: Option<bool>,
@@ -472,34 +594,126 @@ async fn switch_account(
         e.to_string()
     })?;
 
-    // 查找与 account.base_url 匹配的 BaseUrl，获取其 api_key 和默认环境变量
-    let (api_key_name, base_url_default_env_vars) = base_urls
+    // 查找与 account.base_url 匹配的 BaseUrl，获取其 api_key、供应商类型和默认环境变量
+    let (api_key_name, provider_type, base_url_default_env_vars) = base_urls
         .iter()
         .find(|bu| bu.url == account.base_url)
         .map(|bu| {
             let default_env_vars = bu.get_default_env_vars();
-            (bu.api_key.clone(), default_env_vars)
+            (bu.api_key.clone(), bu.provider_type.clone(), default_env_vars)
         })
-        .unwrap_or_else(|| ("ANTHROPIC_API_KEY".to_string(), None));
+        .unwrap_or_else(|| ("ANTHROPIC_API_KEY".to_string(), providers::DEFAULT_PROVIDER.to_string(), None));
 
     // 获取账号的自定义环境变量
     let account_custom_env_vars = account.get_custom_env_vars();
 
+    // API 地址和账号的自定义请求头，账号覆盖同名请求头
+    let custom_header_list = custom_headers::effective_headers(
+        base_urls.iter().find(|bu| bu.url == account.base_url),
+        Some(&account),
+    );
+
+    // 获取需要写入的 Hook（全局 Hook + 目录绑定的 Hook）
+    let hooks = db_lock
+        .get_hooks_for_switch(directoryId)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!("获取 Hook 失败: {}", e);
+            Vec::new()
+        });
+
+    // 获取目录和账号绑定的 MCP 服务器
+    let mcp_servers = db_lock
+        .get_mcp_servers_for_switch(accountId, directoryId)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!("获取 MCP 服务器失败: {}", e);
+            Vec::new()
+        });
+
+    // 获取目录或账号绑定的权限预设（目录优先）
+    let permission_preset = db_lock
+        .get_permission_preset_for_switch(accountId, directoryId)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!("获取权限预设失败: {}", e);
+            None
+        });
+
+    // 获取目录、账号或 API 地址绑定的代理（目录优先）
+    let bound_proxy = db_lock
+        .get_proxy_for_switch(accountId, Some(directoryId))
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!("获取代理失败: {}", e);
+            None
+        });
+
+    // 获取目录启用的命令和子代理定义
+    let claude_definitions = db_lock
+        .get_definitions_for_switch(directoryId)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!("获取命令/子代理定义失败: {}", e);
+            Vec::new()
+        });
+    let definition_vars = definitions::template_vars(&account, &directory);
+
     drop(db_lock); // Release the lock before doing file operations
 
     // Update Claude configuration file with extended environment variables
     let config_manager = ClaudeConfigManager::new(directory.path.clone());
+    let previously_enabled_mcp = config_manager.get_enabled_mcp_servers();
     config_manager
         .update_env_config_with_extended_options(
-            account.token,
-            account.base_url,
-            api_key_name,
+            account.token.clone(),
+            account.base_url.clone(),
+            api_key_name.clone(),
+            &provider_type,
             base_url_default_env_vars,
             account_custom_env_vars,
             keepClaudeLocalMd.unwrap_or(false),
         )
         .map_err(|e| e.to_string())?;
 
+    // 合并 Hook 配置到 settings.local.json
+    if let Err(e) = config_manager.apply_hooks(&hooks) {
+        tracing::warn!("写入 Hook 配置失败: {}", e);
+    }
+
+    // 写入权限预设
+    if let Some(preset) = &permission_preset {
+        if let Err(e) = config_manager.apply_permission_preset(preset) {
+            tracing::warn!("写入权限预设失败: {}", e);
+        } else {
+            tracing::info!("已应用权限预设: {}", preset.name);
+        }
+    }
+
+    // 写入自定义请求头，与自定义环境变量中的 ANTHROPIC_CUSTOM_HEADERS 合并
+    if let Err(e) = config_manager.apply_custom_headers(&custom_header_list) {
+        tracing::warn!("写入自定义请求头失败: {}", e);
+    }
+
+    // 写入绑定的代理
+    if let Some(proxy) = &bound_proxy {
+        if let Err(e) = config_manager.apply_proxy(proxy) {
+            tracing::warn!("写入代理配置失败: {}", e);
+        } else {
+            tracing::info!("已应用代理: {}", proxy.name);
+        }
+    }
+
+    // 辅助命令模式的账号写入 apiKeyHelper，不写入静态令牌
+    if let Err(e) = config_manager.apply_key_helper(&account, &api_key_name) {
+        tracing::warn!("写入 apiKeyHelper 失败: {}", e);
+    }
+
+    // 写入 MCP 服务器配置（与已有配置合并）
+    if let Err(e) = config_manager.apply_mcp_servers(&mcp_servers, previously_enabled_mcp) {
+        tracing::warn!("写入 MCP 服务器配置失败: {}", e);
+    }
+
     // Copy remove-root-check.sh to .claude directory
     let claude_dir = std::path::Path::new(&directory.path).join(".claude");
 
@@ -546,36 +760,14 @@ async fn switch_account(
                 tracing::warn!("复制 show-status.mjs 失败: {}，但不影响主要功能", e);
             }
         }
+    }
 
-        // Copy commands directory files to .claude/commands
-        let commands_dir = claude_dir.join("commands");
-        if let Err(e) = std::fs::create_dir_all(&commands_dir) {
-            tracing::warn!("创建 .claude/commands 目录失败: {}", e);
-        } else {
-            // 遍历并复制所有嵌入的文件
-            for file in COMMANDS_DIR.files() {
-                let file_path = commands_dir.join(file.path());
-
-                // 确保父目录存在（如果有子目录）
-                if let Some(parent) = file_path.parent() {
-                    if !parent.exists() {
-                        let _ = std::fs::create_dir_all(parent);
-                    }
-                }
-
-                // 写入文件内容
-                match std::fs::write(&file_path, file.contents()) {
-                    Ok(_) => {
-                        tracing::info!("{} 已复制到: {}", file.path().display(), file_path.display());
-                    }
-                    Err(e) => {
-                        tracing::warn!("复制 {} 失败: {}，但不影响主要功能", file.path().display(), e);
-                    }
-                }
-            }
-        }
+    // 部署命令和子代理定义，并移除已禁用的定义
+    if let Err(e) = config_manager.apply_definitions(&claude_definitions, &definition_vars) {
+        tracing::warn!("部署命令/子代理定义失败: {}", e);
     }
 
+    watcher.mark_synced(&directory);
     Ok(message)
 }
 
@@ -699,7 +891,11 @@ async fn get_database_connections() -> Result<serde_json::Value, String> {
 }
 
 #[tauri::command]
-async fn switch_database(connection_name: String) -> Result<String, String> {
+async fn switch_database(
+    db: State<'_, DbState>,
+    watcher: State<'_, WatcherState>,
+    connection_name: String,
+) -> Result<String, String> {
     let mut config_manager = config_manager::ConfigManager::new();
     
     // 检查连接是否存在
@@ -707,25 +903,20 @@ async fn switch_database(connection_name: String) -> Result<String, String> {
         return Err(format!("数据库连接 '{}' 不存在", connection_name));
     }
     
-    // 对于非SQLite连接，给出警告但仍然允许切换配置
-    let warning = if connection_name != "default" {
-        "注意：当前版本仅支持SQLite数据库实际连接，其他数据库仅作配置切换。"
-    } else {
-        ""
+    // 新连接在锁外完成连接和迁移，期间其它命令和后台任务继续使用原连接
+    let new_db = Database::open_connection(&mut config_manager, &connection_name).await
+        .map_err(|e| format!("切换数据库连接失败: {}", e))?;
+    let old_pool = {
+        let mut db = db.lock().await;
+        let old_pool = db.replace_connection(new_db);
+        sync_watched_directories(&db, &watcher).await;
+        old_pool
     };
+    // 释放锁后再等待旧连接池中正在使用的连接归还并关闭
+    old_pool.close().await;
+    tracing::info!("已切换到数据库连接: {}", connection_name);
     
-    // 切换默认连接
-    match config_manager.set_default_connection(&connection_name) {
-        Ok(_) => {
-            let message = if warning.is_empty() {
-                format!("已切换到数据库连接: {}", connection_name)
-            } else {
-                format!("已切换到数据库连接: {}。{}", connection_name, warning)
-            };
-            Ok(message)
-        }
-        Err(e) => Err(format!("切换数据库连接失败: {}", e))
-    }
+    Ok(format!("已切换到数据库连接: {}", connection_name))
 }
 
 #[tauri::command]
@@ -741,42 +932,38 @@ async fn test_database(connection_name: String) -> Result<String, String> {
         return Err("数据库URL不能为空".to_string());
     }
     
-    match connection_name.as_str() {
-        "default" => {
+    // 按 URL 协议决定数据库类型
+    match Backend::from_url(&db_config.url).map_err(|e| e.to_string())? {
+        Backend::Sqlite => {
             // 对于默认SQLite，尝试实际连接
             match crate::database::Database::new().await {
                 Ok(_) => Ok("SQLite数据库连接测试成功".to_string()),
                 Err(e) => Err(format!("SQLite数据库连接测试失败: {}", e)),
             }
         }
-        "mysql" => {
+        Backend::MySql => {
             // 对于MySQL，尝试实际连接
-            match test_mysql_connection(&db_config.url).await {
+            match test_remote_connection(&db_config.url).await {
                 Ok(_) => Ok("MySQL数据库连接测试成功".to_string()),
                 Err(e) => Err(format!("MySQL数据库连接测试失败: {}", e)),
             }
         }
-        _ => {
-            Err(format!("不支持的数据库类型: {}", connection_name))
+        Backend::Postgres => {
+            match test_remote_connection(&db_config.url).await {
+                Ok(_) => Ok("PostgreSQL数据库连接测试成功".to_string()),
+                Err(e) => Err(format!("PostgreSQL数据库连接测试失败: {}", e)),
+            }
         }
     }
 }
 
-async fn test_mysql_connection(url: &str) -> Result<(), sqlx::Error> {
-    use sqlx::MySqlPool;
+async fn test_remote_connection(url: &str) -> Result<(), sqlx::Error> {
     use std::time::Duration;
     
-    // 转换Python格式的URL为Rust MySQL格式
-    let mysql_url = if url.starts_with("mysql+pymysql://") {
-        url.replace("mysql+pymysql://", "mysql://")
-    } else {
-        url.to_string()
-    };
-    
     // 创建连接池，设置连接超时
     let pool = tokio::time::timeout(
         Duration::from_secs(10),  // 10秒连接超时
-        MySqlPool::connect(&mysql_url)
+        db_backend::DbPool::connect(url)
     ).await
     .map_err(|_| sqlx::Error::Io(std::io::Error::new(
         std::io::ErrorKind::TimedOut, 
@@ -786,7 +973,7 @@ async fn test_mysql_connection(url: &str) -> Result<(), sqlx::Error> {
     // 执行一个简单的查询来测试连接，设置查询超时
     tokio::time::timeout(
         Duration::from_secs(5),  // 5秒查询超时
-        sqlx::query("SELECT 1").fetch_one(&pool)
+        pool.query("SELECT 1").fetch_scalar::<i64>()
     ).await
     .map_err(|_| sqlx::Error::Io(std::io::Error::new(
         std::io::ErrorKind::TimedOut, 
@@ -981,8 +1168,8 @@ async fn create_webdav_config(
         let error_msg = e.to_string();
         tracing::error!("创建 WebDAV 配置失败: {:?}", e);
 
-        // 处理特定的数据库错误
-        if error_msg.contains("UNIQUE constraint failed: webdav_configs.name") {
+        // 处理特定的数据库错误，数据库错误包在 anyhow 的上下文中
+        if e.downcast_ref::<sqlx::Error>().is_some_and(is_unique_violation) {
             "配置名称已存在，请使用不同的名称".to_string()
         } else {
             format!("创建 WebDAV 配置失败: {}", error_msg)
@@ -1042,9 +1229,13 @@ async fn test_webdav_connection(db: State<'_, DbState>, id: i64) -> Result<Strin
         .map_err(|e| e.to_string())?
         .ok_or_else(|| "WebDAV配置不存在".to_string())?;
 
+    let proxy = webdav::get_webdav_config_proxy(pool, &config)
+        .await
+        .map_err(|e| e.to_string())?;
+
     drop(db);
 
-    let manager = webdav::WebDavManager::from_config(config)
+    let manager = webdav::WebDavManager::from_config(config, proxy.as_ref())
         .await
         .map_err(|e| format!("创建WebDAV客户端失败: {}", e))?;
 
@@ -1075,6 +1266,7 @@ async fn upload_config_to_webdav(
         per_page: Some(1000),
         search: None,
         base_url: None,
+        ..Default::default()
     }).await.map_err(|e| e.to_string())?;
 
     let base_urls = db_lock.get_base_urls().await.map_err(|e| e.to_string())?;
@@ -1082,6 +1274,10 @@ async fn upload_config_to_webdav(
     let claude_settings: serde_json::Value = serde_json::from_str(&claude_settings_json)
         .map_err(|e| format!("解析Claude设置失败: {}", e))?;
 
+    let proxy = webdav::get_webdav_config_proxy(pool, &config)
+        .await
+        .map_err(|e| e.to_string())?;
+
     drop(db_lock);
 
     let data = serde_json::json!({
@@ -1091,7 +1287,7 @@ async fn upload_config_to_webdav(
         "exported_at": chrono::Utc::now().to_rfc3339(),
     });
 
-    let manager = webdav::WebDavManager::from_config(config.clone())
+    let manager = webdav::WebDavManager::from_config(config.clone(), proxy.as_ref())
         .await
         .map_err(|e| format!("创建WebDAV客户端失败: {}", e))?;
 
@@ -1121,12 +1317,12 @@ async fn upload_config_to_webdav(
     Ok(format!("配置已成功上传到WebDAV: {}", filename))
 }
 
-#[tauri::command]
-async fn download_config_from_webdav(
-    db: State<'_, DbState>,
+/// 下载 WebDAV 上的配置备份
+async fn download_webdav_backup(
+    db: &State<'_, DbState>,
     config_id: i64,
-    filename: String,
-) -> Result<String, String> {
+    filename: &str,
+) -> Result<serde_json::Value, String> {
     let db_lock = db.lock().await;
     let pool = db_lock.get_pool();
 
@@ -1135,103 +1331,68 @@ async fn download_config_from_webdav(
         .map_err(|e| e.to_string())?
         .ok_or_else(|| "WebDAV配置不存在".to_string())?;
 
+    let proxy = webdav::get_webdav_config_proxy(pool, &config)
+        .await
+        .map_err(|e| e.to_string())?;
+
     drop(db_lock);
 
-    let manager = webdav::WebDavManager::from_config(config.clone())
+    let manager = webdav::WebDavManager::from_config(config, proxy.as_ref())
         .await
         .map_err(|e| format!("创建WebDAV客户端失败: {}", e))?;
 
-    let data = manager.download_config(&filename)
+    manager.download_config(filename)
         .await
-        .map_err(|e| format!("下载配置失败: {}", e))?;
-
-    // 导入配置到数据库
-    let db_lock = db.lock().await;
-    let pool = db_lock.get_pool();
-
-    // 先删除所有现有账号和 Base URLs,实现完全覆盖
-    tracing::info!("清空现有配置以实现完全覆盖");
-
-    let _ = sqlx::query("DELETE FROM accounts")
-        .execute(pool)
-        .await;
-
-    let _ = sqlx::query("DELETE FROM base_urls")
-        .execute(pool)
-        .await;
-
-    tracing::info!("已清空现有账号和 Base URLs");
-
-    // 解析账号数据
-    if let Some(accounts_array) = data.get("accounts").and_then(|v| v.as_array()) {
-        tracing::info!("开始导入账号, 共 {} 个", accounts_array.len());
-        let mut success_count = 0;
-
-        for account_data in accounts_array {
-            if let (Some(name), Some(token), Some(base_url)) = (
-                account_data.get("name").and_then(|v| v.as_str()),
-                account_data.get("token").and_then(|v| v.as_str()),
-                account_data.get("base_url").and_then(|v| v.as_str()),
-            ) {
-                let model = account_data.get("model")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("claude-sonnet-4-20250514");
-
-                let request = CreateAccountRequest {
-                    name: name.to_string(),
-                    token: token.to_string(),
-                    base_url: base_url.to_string(),
-                    model: model.to_string(),
-                    custom_env_vars: None,
-                };
+        .map_err(|e| format!("下载配置失败: {}", e))
+}
 
-                if let Ok(_) = db_lock.create_account(request).await {
-                    success_count += 1;
-                    tracing::debug!("导入账号成功: {}", name);
-                }
-            }
-        }
+#[tauri::command]
+async fn preview_webdav_download(
+    db: State<'_, DbState>,
+    config_id: i64,
+    filename: String,
+) -> Result<ImportPreview, String> {
+    let data = download_webdav_backup(&db, config_id, &filename).await?;
+    let bundle = importers::parse_backup(&data)?;
 
-        tracing::info!("账号导入完成: 成功 {}/{}", success_count, accounts_array.len());
-    }
+    let db_lock = db.lock().await;
+    let existing = ExistingRecords::load(&db_lock).await.map_err(|e| e.to_string())?;
+    let items = import_engine::plan(&bundle, &existing);
+    Ok(ImportPreview {
+        format: "backup".to_string(),
+        bundle,
+        items,
+    })
+}
 
-    // 解析 Base URLs 数据
-    if let Some(base_urls_array) = data.get("base_urls").and_then(|v| v.as_array()) {
-        tracing::info!("开始导入 Base URLs, 共 {} 个", base_urls_array.len());
-        let mut success_count = 0;
-
-        for base_url_data in base_urls_array {
-            if let (Some(name), Some(url)) = (
-                base_url_data.get("name").and_then(|v| v.as_str()),
-                base_url_data.get("url").and_then(|v| v.as_str()),
-            ) {
-                let description = base_url_data.get("description")
-                    .and_then(|v| v.as_str())
-                    .map(|s| s.to_string());
-                let api_key = base_url_data.get("api_key")
-                    .and_then(|v| v.as_str())
-                    .map(|s| s.to_string());
-                let is_default = base_url_data.get("is_default")
-                    .and_then(|v| v.as_bool());
-
-                let request = CreateBaseUrlRequest {
-                    name: name.to_string(),
-                    url: url.to_string(),
-                    description,
-                    api_key,
-                    is_default,
-                    default_env_vars: None,
-                };
+#[tauri::command]
+async fn download_config_from_webdav(
+    db: State<'_, DbState>,
+    config_id: i64,
+    filename: String,
+    items: Option<Vec<ImportItem>>,
+    strategy: Option<String>,
+) -> Result<ImportReport, String> {
+    let data = download_webdav_backup(&db, config_id, &filename).await?;
+    let bundle = importers::parse_backup(&data)?;
 
-                if let Ok(_) = db_lock.create_base_url(request).await {
-                    success_count += 1;
-                    tracing::debug!("导入 Base URL 成功: {}", name);
-                }
-            }
+    // 与已有记录逐项比对，按选择的策略导入
+    let db_lock = db.lock().await;
+    let items = match items {
+        Some(items) => items,
+        None => {
+            let existing = ExistingRecords::load(&db_lock).await.map_err(|e| e.to_string())?;
+            import_engine::plan(&bundle, &existing)
         }
-
-        tracing::info!("Base URL 导入完成: 成功 {}/{}", success_count, base_urls_array.len());
-    }
+    };
+    let strategy = strategy.unwrap_or_else(|| import_engine::STRATEGY_SKIP.to_string());
+    let report = import_engine::apply(&db_lock, &bundle, &items, &strategy)
+        .await
+        .map_err(|e| e.to_string())?;
+    tracing::info!(
+        "WebDAV 配置导入完成: 新增 {}，更新 {}，跳过 {}，失败 {}",
+        report.created, report.updated, report.skipped, report.failed
+    );
 
     // 解析 Claude 设置数据
     if let Some(claude_settings) = data.get("claude_settings") {
@@ -1265,7 +1426,7 @@ async fn download_config_from_webdav(
 
     drop(db_lock);
 
-    Ok(format!("配置已成功从 WebDAV 下载并导入到数据库: {}", filename))
+    Ok(report)
 }
 
 #[tauri::command]
@@ -1278,9 +1439,13 @@ async fn list_webdav_files(db: State<'_, DbState>, config_id: i64) -> Result<Vec
         .map_err(|e| e.to_string())?
         .ok_or_else(|| "WebDAV配置不存在".to_string())?;
 
+    let proxy = webdav::get_webdav_config_proxy(pool, &config)
+        .await
+        .map_err(|e| e.to_string())?;
+
     drop(db);
 
-    let manager = webdav::WebDavManager::from_config(config)
+    let manager = webdav::WebDavManager::from_config(config, proxy.as_ref())
         .await
         .map_err(|e| format!("创建WebDAV客户端失败: {}", e))?;
 
@@ -1303,9 +1468,13 @@ async fn delete_remote_file(
         .map_err(|e| e.to_string())?
         .ok_or_else(|| "WebDAV配置不存在".to_string())?;
 
+    let proxy = webdav::get_webdav_config_proxy(pool, &config)
+        .await
+        .map_err(|e| e.to_string())?;
+
     drop(db);
 
-    let manager = webdav::WebDavManager::from_config(config)
+    let manager = webdav::WebDavManager::from_config(config, proxy.as_ref())
         .await
         .map_err(|e| format!("创建WebDAV客户端失败: {}", e))?;
 
@@ -1333,6 +1502,7 @@ async fn get_sync_logs(
 #[allow(non_snake_case)]
 async fn switch_account_with_claude_settings(
     db: State<'_, DbState>,
+    watcher: State<'_, WatcherState>,
     accountId: i64,
     directoryId: i64,
     claudeSettings: serde_json::Value,
@@ -1374,19 +1544,71 @@ async fn switch_account_with_claude_settings(
         e.to_string()
     })?;
 
-    // 查找与 account.base_url 匹配的 BaseUrl，获取其 api_key 和默认环境变量
-    let (api_key_name, base_url_default_env_vars) = base_urls
+    // 查找与 account.base_url 匹配的 BaseUrl，获取其 api_key、供应商类型和默认环境变量
+    let (api_key_name, provider_type, base_url_default_env_vars) = base_urls
         .iter()
         .find(|bu| bu.url == account.base_url)
         .map(|bu| {
             let default_env_vars = bu.get_default_env_vars();
-            (bu.api_key.clone(), default_env_vars)
+            (bu.api_key.clone(), bu.provider_type.clone(), default_env_vars)
         })
-        .unwrap_or_else(|| ("ANTHROPIC_API_KEY".to_string(), None));
+        .unwrap_or_else(|| ("ANTHROPIC_API_KEY".to_string(), providers::DEFAULT_PROVIDER.to_string(), None));
 
     // 获取账号的自定义环境变量
     let account_custom_env_vars = account.get_custom_env_vars();
 
+    // API 地址和账号的自定义请求头，账号覆盖同名请求头
+    let custom_header_list = custom_headers::effective_headers(
+        base_urls.iter().find(|bu| bu.url == account.base_url),
+        Some(&account),
+    );
+
+    // 获取需要写入的 Hook（全局 Hook + 目录绑定的 Hook）
+    let hooks = db_lock
+        .get_hooks_for_switch(directoryId)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!("获取 Hook 失败: {}", e);
+            Vec::new()
+        });
+
+    // 获取目录和账号绑定的 MCP 服务器
+    let mcp_servers = db_lock
+        .get_mcp_servers_for_switch(accountId, directoryId)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!("获取 MCP 服务器失败: {}", e);
+            Vec::new()
+        });
+
+    // 获取目录或账号绑定的权限预设（目录优先），会覆盖前端传入的 permissions
+    let permission_preset = db_lock
+        .get_permission_preset_for_switch(accountId, directoryId)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!("获取权限预设失败: {}", e);
+            None
+        });
+
+    // 获取目录、账号或 API 地址绑定的代理（目录优先）
+    let bound_proxy = db_lock
+        .get_proxy_for_switch(accountId, Some(directoryId))
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!("获取代理失败: {}", e);
+            None
+        });
+
+    // 获取目录启用的命令和子代理定义
+    let claude_definitions = db_lock
+        .get_definitions_for_switch(directoryId)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!("获取命令/子代理定义失败: {}", e);
+            Vec::new()
+        });
+    let definition_vars = definitions::template_vars(&account, &directory);
+
     drop(db_lock); // Release the lock before doing file operations
 
     // Clone account information before using it
@@ -1395,11 +1617,14 @@ async fn switch_account_with_claude_settings(
 
     // Update Claude configuration file with extended environment variables
     let config_manager = ClaudeConfigManager::new(directory.path.clone());
+    // settings.local.json 会被重写，先记录已启用的 MCP 服务器以便合并
+    let previously_enabled_mcp = config_manager.get_enabled_mcp_servers();
     config_manager
         .update_env_config_with_extended_options(
             account.token.clone(),
             account.base_url.clone(),
             api_key_name.clone(),
+            &provider_type,
             base_url_default_env_vars.clone(),
             account_custom_env_vars.clone(),
             keepClaudeLocalMd.unwrap_or(false),
@@ -1428,16 +1653,25 @@ async fn switch_account_with_claude_settings(
 
     // 只添加必需的系统环境变量，不覆盖用户自定义配置
     // 这些变量对 Claude Code 正常运行是必需的
-    env_obj.insert(api_key_name.clone(), serde_json::Value::String(account_token.clone()));
-
     // 如果前端没有传递 ANTHROPIC_BASE_URL，则使用数据库中的值
     // 如果前端已经传递（例如替换了宿主机IP），则保留前端的值
-    if !env_obj.contains_key("ANTHROPIC_BASE_URL") {
-        env_obj.insert("ANTHROPIC_BASE_URL".to_string(), serde_json::Value::String(account_base_url.clone()));
-        tracing::info!("使用数据库中的 ANTHROPIC_BASE_URL: {}", account_base_url);
-    } else {
-        tracing::info!("保留前端传递的 ANTHROPIC_BASE_URL: {:?}", env_obj.get("ANTHROPIC_BASE_URL"));
-    }
+    let effective_base_url = match env_obj.get("ANTHROPIC_BASE_URL").and_then(|v| v.as_str()) {
+        Some(frontend_url) => {
+            tracing::info!("保留前端传递的 ANTHROPIC_BASE_URL: {}", frontend_url);
+            frontend_url.to_string()
+        }
+        None => {
+            tracing::info!("使用数据库中的 ANTHROPIC_BASE_URL: {}", account_base_url);
+            account_base_url.clone()
+        }
+    };
+
+    // 按供应商类型写入开关、地址和令牌，并移除其他供应商遗留的变量
+    providers::apply_provider_env(env_obj, &provider_type, &api_key_name, &account_token, &effective_base_url);
+    tracing::info!("供应商类型: {}", provider_type);
+
+    // 写入自定义请求头，与前端合并的 ANTHROPIC_CUSTOM_HEADERS 合并
+    custom_headers::apply_headers_to_env(env_obj, &custom_header_list);
 
     env_obj.insert("USER_NAME".to_string(), serde_json::Value::String(account.name.clone()));
 
@@ -1460,6 +1694,28 @@ async fn switch_account_with_claude_settings(
         "padding": 0
     }));
 
+    // 合并 Hook 配置（全局 Hook + 目录绑定的 Hook）
+    merge_hooks_into_settings(&mut merged_settings, &hooks);
+
+    // 应用权限预设，并修正旧版本遗留的无效 defaultMode
+    if let Some(preset) = &permission_preset {
+        permissions::apply_permission_preset(&mut merged_settings, preset);
+        tracing::info!("已应用权限预设: {}", preset.name);
+    }
+    permissions::normalize_permission_mode(&mut merged_settings);
+
+    // 目录、账号或 API 地址绑定了代理时，覆盖前端传入的代理环境变量
+    if let Some(proxy) = &bound_proxy {
+        proxy::apply_proxy_to_settings(&mut merged_settings, proxy);
+        tracing::info!("已应用代理: {}", proxy.name);
+    }
+
+    // 辅助命令模式的账号写入 apiKeyHelper，不写入静态令牌
+    key_helper::apply_key_helper_to_settings(&mut merged_settings, &account, &api_key_name);
+    if account.uses_key_helper() {
+        tracing::info!("账号 {} 使用 apiKeyHelper 获取密钥", account.name);
+    }
+
     let settings_file = claude_dir.join("settings.local.json");
     let settings_json = serde_json::to_string_pretty(&merged_settings)
         .map_err(|e| format!("序列化Claude设置失败: {}", e))?;
@@ -1468,6 +1724,11 @@ async fn switch_account_with_claude_settings(
         .map_err(|e| format!("写入Claude设置文件失败: {}", e))?;
 
     tracing::info!("Claude设置已写入: {}", settings_file.display());
+
+    // 写入 MCP 服务器配置（与已有配置合并）
+    if let Err(e) = config_manager.apply_mcp_servers(&mcp_servers, previously_enabled_mcp) {
+        tracing::warn!("写入 MCP 服务器配置失败: {}", e);
+    }
     tracing::info!("账号环境变量已合并: ANTHROPIC_API_KEY, ANTHROPIC_AUTH_TOKEN, ANTHROPIC_BASE_URL");
 
     // Copy remove-root-check.sh to .claude directory
@@ -1587,66 +1848,1366 @@ async fn switch_account_with_claude_settings(
         }
     }
 
-    // Copy commands directory files to .claude/commands
-    let commands_dir = claude_dir.join("commands");
-    if let Err(e) = std::fs::create_dir_all(&commands_dir) {
-        tracing::warn!("创建 .claude/commands 目录失败: {}", e);
-    } else {
-        // 遍历并复制所有嵌入的文件
-        for file in COMMANDS_DIR.files() {
-            let file_path = commands_dir.join(file.path());
-
-            // 确保父目录存在（如果有子目录）
-            if let Some(parent) = file_path.parent() {
-                if !parent.exists() {
-                    let _ = std::fs::create_dir_all(parent);
-                }
-            }
-
-            // 写入文件内容
-            match std::fs::write(&file_path, file.contents()) {
-                Ok(_) => {
-                    tracing::info!("{} 已复制到: {}", file.path().display(), file_path.display());
-                }
-                Err(e) => {
-                    tracing::warn!("复制 {} 失败: {}，但不影响主要功能", file.path().display(), e);
-                }
-            }
-        }
+    // 部署命令和子代理定义，并移除已禁用的定义
+    if let Err(e) = config_manager.apply_definitions(&claude_definitions, &definition_vars) {
+        tracing::warn!("部署命令/子代理定义失败: {}", e);
     }
 
     let final_message = format!("{} Claude配置和账号环境变量已写入 .claude/settings.local.json", message);
+    watcher.mark_synced(&directory);
     Ok(final_message)
 }
 
-#[cfg_attr(mobile, tauri::mobile_entry_point)]
-pub fn run() {
-    // 初始化日志系统
-    if let Err(e) = crate::logger::Logger::init() {
-        eprintln!("日志系统初始化失败: {}", e);
-    }
-    
-    tracing::info!("Claude Configuration Manager 启动中...");
-    
-    tauri::Builder::default()
-        .plugin(tauri_plugin_opener::init())
-        .plugin(tauri_plugin_dialog::init())
-        .plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
-            // 当尝试启动第二个实例时的处理逻辑
-            tracing::info!("检测到重复启动，显示现有窗口");
-            if let Some(window) = app.get_webview_window("main") {
-                let _ = window.show();
-                let _ = window.set_focus();
-                let _ = window.unminimize();
+// MCP 服务器管理
+#[tauri::command]
+async fn get_mcp_servers(db: State<'_, DbState>) -> Result<Vec<McpServer>, String> {
+    let db = db.lock().await;
+    db.get_mcp_servers()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn create_mcp_server(
+    db: State<'_, DbState>,
+    name: String,
+    transport: String,
+    command: Option<String>,
+    args: Option<Vec<String>>,
+    env: Option<serde_json::Value>,
+    url: Option<String>,
+    description: Option<String>,
+) -> Result<McpServer, String> {
+    validate_mcp_server(&transport, command.as_deref(), url.as_deref())?;
+
+    let db = db.lock().await;
+    let request = CreateMcpServerRequest { name, transport, command, args, env, url, description };
+
+    db.create_mcp_server(request)
+        .await
+        .map_err(|e| {
+            let error_msg = e.to_string();
+            if is_unique_violation(&e) {
+                "MCP 服务器名称已存在".to_string()
+            } else {
+                error_msg
             }
-        }))
-        .on_window_event(|window, event| match event {
-            WindowEvent::CloseRequested { api, .. } => {
-                // 阻止默认的关闭行为，改为隐藏到托盘
-                api.prevent_close();
-                window.hide().unwrap();
+        })
+}
+
+#[tauri::command]
+async fn update_mcp_server(
+    db: State<'_, DbState>,
+    id: i64,
+    name: Option<String>,
+    transport: Option<String>,
+    command: Option<String>,
+    args: Option<Vec<String>>,
+    env: Option<serde_json::Value>,
+    url: Option<String>,
+    description: Option<String>,
+) -> Result<McpServer, String> {
+    let db = db.lock().await;
+
+    // 使用合并后的字段进行校验
+    let existing = db.get_mcp_server(id).await.map_err(|e| e.to_string())?;
+    validate_mcp_server(
+        transport.as_deref().unwrap_or(&existing.transport),
+        command.as_deref().or(existing.command.as_deref()),
+        url.as_deref().or(existing.url.as_deref()),
+    )?;
+
+    let request = UpdateMcpServerRequest { name, transport, command, args, env, url, description };
+
+    db.update_mcp_server(id, request)
+        .await
+        .map_err(|e| {
+            let error_msg = e.to_string();
+            if is_unique_violation(&e) {
+                "MCP 服务器名称已存在".to_string()
+            } else {
+                error_msg
             }
-            _ => {}
+        })
+}
+
+#[tauri::command]
+async fn delete_mcp_server(db: State<'_, DbState>, id: i64) -> Result<String, String> {
+    let db = db.lock().await;
+    db.delete_mcp_server(id)
+        .await
+        .map(|_| "MCP 服务器删除成功".to_string())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn get_directory_mcp_servers(db: State<'_, DbState>, directoryId: i64) -> Result<Vec<i64>, String> {
+    let db = db.lock().await;
+    db.get_directory_mcp_server_ids(directoryId)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn set_directory_mcp_servers(
+    db: State<'_, DbState>,
+    directoryId: i64,
+    serverIds: Vec<i64>,
+) -> Result<String, String> {
+    let db = db.lock().await;
+    db.set_directory_mcp_servers(directoryId, &serverIds)
+        .await
+        .map(|_| "目录 MCP 服务器已更新".to_string())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn get_account_mcp_servers(db: State<'_, DbState>, accountId: i64) -> Result<Vec<i64>, String> {
+    let db = db.lock().await;
+    db.get_account_mcp_server_ids(accountId)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn set_account_mcp_servers(
+    db: State<'_, DbState>,
+    accountId: i64,
+    serverIds: Vec<i64>,
+) -> Result<String, String> {
+    let db = db.lock().await;
+    db.set_account_mcp_servers(accountId, &serverIds)
+        .await
+        .map(|_| "账号 MCP 服务器已更新".to_string())
+        .map_err(|e| e.to_string())
+}
+
+// Hook 管理
+#[tauri::command]
+async fn get_hooks(db: State<'_, DbState>) -> Result<Vec<Hook>, String> {
+    let db = db.lock().await;
+    db.get_hooks()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn create_hook(
+    db: State<'_, DbState>,
+    name: String,
+    event: String,
+    matcher: Option<String>,
+    command: String,
+    timeout: Option<i64>,
+    isGlobal: Option<bool>,
+    description: Option<String>,
+) -> Result<Hook, String> {
+    validate_hook(&event, &command, timeout)?;
+
+    let db = db.lock().await;
+    let request = CreateHookRequest {
+        name,
+        event,
+        matcher: matcher.filter(|m| !m.trim().is_empty()),
+        command,
+        timeout,
+        is_global: isGlobal,
+        description,
+    };
+
+    db.create_hook(request)
+        .await
+        .map_err(|e| {
+            let error_msg = e.to_string();
+            if is_unique_violation(&e) {
+                "Hook 名称已存在".to_string()
+            } else {
+                error_msg
+            }
+        })
+}
+
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn update_hook(
+    db: State<'_, DbState>,
+    id: i64,
+    name: Option<String>,
+    event: Option<String>,
+    matcher: Option<String>,
+    command: Option<String>,
+    timeout: Option<i64>,
+    isGlobal: Option<bool>,
+    description: Option<String>,
+) -> Result<Hook, String> {
+    let db = db.lock().await;
+
+    // 使用合并后的字段进行校验
+    let existing = db.get_hook(id).await.map_err(|e| e.to_string())?;
+    validate_hook(
+        event.as_deref().unwrap_or(&existing.event),
+        command.as_deref().unwrap_or(&existing.command),
+        timeout.filter(|t| *t != 0),
+    )?;
+
+    let request = UpdateHookRequest {
+        name,
+        event,
+        matcher,
+        command,
+        timeout,
+        is_global: isGlobal,
+        description,
+    };
+
+    db.update_hook(id, request)
+        .await
+        .map_err(|e| {
+            let error_msg = e.to_string();
+            if is_unique_violation(&e) {
+                "Hook 名称已存在".to_string()
+            } else {
+                error_msg
+            }
+        })
+}
+
+#[tauri::command]
+async fn delete_hook(db: State<'_, DbState>, id: i64) -> Result<String, String> {
+    let db = db.lock().await;
+    db.delete_hook(id)
+        .await
+        .map(|_| "Hook 删除成功".to_string())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn get_directory_hooks(db: State<'_, DbState>, directoryId: i64) -> Result<Vec<i64>, String> {
+    let db = db.lock().await;
+    db.get_directory_hook_ids(directoryId)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn set_directory_hooks(
+    db: State<'_, DbState>,
+    directoryId: i64,
+    hookIds: Vec<i64>,
+) -> Result<String, String> {
+    let db = db.lock().await;
+    db.set_directory_hooks(directoryId, &hookIds)
+        .await
+        .map(|_| "目录 Hook 已更新".to_string())
+        .map_err(|e| e.to_string())
+}
+
+// 权限预设管理
+#[tauri::command]
+async fn get_permission_presets(db: State<'_, DbState>) -> Result<Vec<PermissionPreset>, String> {
+    let db = db.lock().await;
+    db.get_permission_presets()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn create_permission_preset(
+    db: State<'_, DbState>,
+    name: String,
+    defaultMode: String,
+    allow: Vec<String>,
+    deny: Vec<String>,
+    ask: Vec<String>,
+    description: Option<String>,
+) -> Result<PermissionPreset, String> {
+    permissions::validate_permission_preset(&defaultMode, &allow, &deny, &ask)?;
+
+    let db = db.lock().await;
+    let request = CreatePermissionPresetRequest {
+        name,
+        default_mode: defaultMode,
+        allow,
+        deny,
+        ask,
+        description,
+    };
+
+    db.create_permission_preset(request)
+        .await
+        .map_err(|e| {
+            let error_msg = e.to_string();
+            if is_unique_violation(&e) {
+                "权限预设名称已存在".to_string()
+            } else {
+                error_msg
+            }
+        })
+}
+
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn update_permission_preset(
+    db: State<'_, DbState>,
+    id: i64,
+    name: Option<String>,
+    defaultMode: Option<String>,
+    allow: Option<Vec<String>>,
+    deny: Option<Vec<String>>,
+    ask: Option<Vec<String>>,
+    description: Option<String>,
+) -> Result<PermissionPreset, String> {
+    let db = db.lock().await;
+
+    // 使用合并后的字段进行校验
+    let existing = db.get_permission_preset(id).await.map_err(|e| e.to_string())?;
+    permissions::validate_permission_preset(
+        defaultMode.as_deref().unwrap_or(&existing.default_mode),
+        allow.as_deref().unwrap_or(&existing.allow_rules()),
+        deny.as_deref().unwrap_or(&existing.deny_rules()),
+        ask.as_deref().unwrap_or(&existing.ask_rules()),
+    )?;
+
+    let request = UpdatePermissionPresetRequest {
+        name,
+        default_mode: defaultMode,
+        allow,
+        deny,
+        ask,
+        description,
+    };
+
+    db.update_permission_preset(id, request)
+        .await
+        .map_err(|e| {
+            let error_msg = e.to_string();
+            if is_unique_violation(&e) {
+                "权限预设名称已存在".to_string()
+            } else {
+                error_msg
+            }
+        })
+}
+
+#[tauri::command]
+async fn delete_permission_preset(db: State<'_, DbState>, id: i64) -> Result<String, String> {
+    let db = db.lock().await;
+    db.delete_permission_preset(id)
+        .await
+        .map(|_| "权限预设删除成功".to_string())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn set_directory_permission_preset(
+    db: State<'_, DbState>,
+    directoryId: i64,
+    presetId: Option<i64>,
+) -> Result<String, String> {
+    let db = db.lock().await;
+    db.set_directory_permission_preset(directoryId, presetId)
+        .await
+        .map(|_| "目录权限预设已更新".to_string())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn set_account_permission_preset(
+    db: State<'_, DbState>,
+    accountId: i64,
+    presetId: Option<i64>,
+) -> Result<String, String> {
+    let db = db.lock().await;
+    db.set_account_permission_preset(accountId, presetId)
+        .await
+        .map(|_| "账号权限预设已更新".to_string())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn validate_permission_rule(rule: String) -> Result<(), String> {
+    permissions::validate_permission_rule(&rule)
+}
+
+// 命令与子代理定义管理
+#[tauri::command]
+async fn get_claude_definitions(db: State<'_, DbState>) -> Result<Vec<ClaudeDefinition>, String> {
+    let db = db.lock().await;
+    db.get_definitions()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn create_claude_definition(
+    db: State<'_, DbState>,
+    kind: String,
+    name: String,
+    content: String,
+    description: Option<String>,
+    isDefault: Option<bool>,
+) -> Result<ClaudeDefinition, String> {
+    definitions::validate_definition(&kind, &name, &content)?;
+
+    let db = db.lock().await;
+    let request = definitions::definition_request(
+        &kind,
+        &name,
+        content,
+        description,
+        isDefault.unwrap_or(false),
+    );
+
+    db.create_definition(request)
+        .await
+        .map_err(|e| {
+            let error_msg = e.to_string();
+            if is_unique_violation(&e) {
+                "同名定义已存在".to_string()
+            } else {
+                error_msg
+            }
+        })
+}
+
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn update_claude_definition(
+    db: State<'_, DbState>,
+    id: i64,
+    name: Option<String>,
+    content: Option<String>,
+    description: Option<String>,
+    isDefault: Option<bool>,
+) -> Result<ClaudeDefinition, String> {
+    let db = db.lock().await;
+
+    // 使用合并后的字段进行校验
+    let existing = db.get_definition(id).await.map_err(|e| e.to_string())?;
+    definitions::validate_definition(
+        &existing.kind,
+        name.as_deref().unwrap_or(&existing.name),
+        content.as_deref().unwrap_or(&existing.content),
+    )?;
+
+    let request = UpdateClaudeDefinitionRequest {
+        name,
+        description,
+        content,
+        is_default: isDefault,
+    };
+
+    db.update_definition(id, request)
+        .await
+        .map_err(|e| {
+            let error_msg = e.to_string();
+            if is_unique_violation(&e) {
+                "同名定义已存在".to_string()
+            } else {
+                error_msg
+            }
+        })
+}
+
+#[tauri::command]
+async fn delete_claude_definition(db: State<'_, DbState>, id: i64) -> Result<String, String> {
+    let db = db.lock().await;
+    db.delete_definition(id)
+        .await
+        .map(|_| "定义删除成功".to_string())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn get_directory_definitions(db: State<'_, DbState>, directoryId: i64) -> Result<Vec<i64>, String> {
+    let db = db.lock().await;
+    db.get_directory_enabled_definition_ids(directoryId)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn set_directory_definitions(
+    db: State<'_, DbState>,
+    directoryId: i64,
+    definitionIds: Vec<i64>,
+) -> Result<String, String> {
+    let db = db.lock().await;
+    db.set_directory_definitions(directoryId, &definitionIds)
+        .await
+        .map(|_| "目录定义已更新".to_string())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn import_claude_definitions(
+    db: State<'_, DbState>,
+    path: String,
+    overwrite: Option<bool>,
+) -> Result<String, String> {
+    let requests = definitions::scan_claude_dir(&path).map_err(|e| e.to_string())?;
+    if requests.is_empty() {
+        return Err("未在 .claude/commands 或 .claude/agents 中找到定义".to_string());
+    }
+
+    let db = db.lock().await;
+    let (imported, skipped) = db
+        .import_definitions(requests, overwrite.unwrap_or(false))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(format!("导入完成：导入 {} 个，跳过 {} 个", imported, skipped))
+}
+
+// 配置漂移检测
+#[tauri::command]
+async fn scan_config_drift(db: State<'_, DbState>) -> Result<Vec<DirectoryDrift>, String> {
+    let db = db.lock().await;
+    drift::scan_drift(&db)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 修复配置漂移，未指定目录时修复所有存在漂移的目录
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn fix_config_drift(
+    db: State<'_, DbState>,
+    watcher: State<'_, WatcherState>,
+    directoryId: Option<i64>,
+) -> Result<String, String> {
+    let db = db.lock().await;
+
+    let directory_id = match directoryId {
+        Some(id) => id,
+        None => {
+            let (fixed, failures) = drift::fix_all_drift(&db).await.map_err(|e| e.to_string())?;
+            for directory in db.get_directories().await.map_err(|e| e.to_string())? {
+                watcher.mark_synced(&directory);
+            }
+            if failures.is_empty() {
+                return Ok(format!("已修复 {} 个目录", fixed));
+            }
+            let details: Vec<String> = failures
+                .iter()
+                .map(|(name, error)| format!("{}: {}", name, error))
+                .collect();
+            return Err(format!("已修复 {} 个目录，以下目录修复失败: {}", fixed, details.join("; ")));
+        }
+    };
+
+    let directory = db.get_directory(directory_id).await.map_err(|e| e.to_string())?;
+    let expected = drift::expected_state(&db, &directory)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "该目录尚未切换过账号".to_string())?;
+    drop(db);
+
+    drift::fix_drift(&directory, &expected).map_err(|e| e.to_string())?;
+    watcher.mark_synced(&directory);
+    Ok(format!("目录 {} 已修复", directory.name))
+}
+
+/// 文件监听检测到的漂移目录
+#[tauri::command]
+async fn get_drifted_directories(watcher: State<'_, WatcherState>) -> Result<Vec<DirectoryDrift>, String> {
+    Ok(watcher.drifted_directories())
+}
+
+#[tauri::command]
+async fn set_drift_notifications(watcher: State<'_, WatcherState>, enabled: bool) -> Result<(), String> {
+    watcher.set_notifications_enabled(enabled);
+    Ok(())
+}
+
+/// 目录漂移状态变化：通知前端刷新，更新托盘提示，新出现漂移时发送系统通知
+fn on_drift_changed(app: &AppHandle, drift: &DirectoryDrift, newly_drifted: bool) {
+    use tauri_plugin_notification::NotificationExt;
+
+    if let Err(e) = app.emit(watcher::DRIFT_EVENT, drift) {
+        tracing::warn!("发送漂移事件失败: {}", e);
+    }
+
+    let config_watcher = app.state::<WatcherState>();
+    let drifted_count = config_watcher.drifted_directories().len();
+    if let Some(tray) = app.tray_by_id("main-tray") {
+        let tooltip = if drifted_count > 0 {
+            format!("Claude Configuration Manager - {} 个目录配置已被修改", drifted_count)
+        } else {
+            "Claude Configuration Manager".to_string()
+        };
+        let _ = tray.set_tooltip(Some(tooltip));
+    }
+
+    if newly_drifted && config_watcher.notifications_enabled() {
+        let body = format!("目录 {} 的配置已不再与账号 {} 一致", drift.directory_name, drift.account_name.as_deref().unwrap_or("-"));
+        if let Err(e) = app.notification().builder().title("配置漂移").body(body).show() {
+            tracing::warn!("发送系统通知失败: {}", e);
+        }
+    }
+}
+
+// 项目发现
+#[tauri::command]
+async fn get_discovery_roots(db: State<'_, DbState>) -> Result<Vec<DiscoveryRoot>, String> {
+    let db = db.lock().await;
+    db.get_discovery_roots()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn add_discovery_root(db: State<'_, DbState>, path: String) -> Result<DiscoveryRoot, String> {
+    let path = path.trim();
+    if path.is_empty() {
+        return Err("扫描根目录不能为空".to_string());
+    }
+
+    let db = db.lock().await;
+    db.add_discovery_root(path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn delete_discovery_root(db: State<'_, DbState>, id: i64) -> Result<String, String> {
+    let db = db.lock().await;
+    db.delete_discovery_root(id)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok("扫描根目录已删除".to_string())
+}
+
+/// 扫描磁盘上的 Claude 项目，未指定根目录时使用已保存的扫描根目录
+#[tauri::command]
+async fn discover_projects(db: State<'_, DbState>, roots: Option<Vec<String>>) -> Result<Vec<DiscoveredProject>, String> {
+    let db = db.lock().await;
+    let roots = match roots {
+        Some(roots) => roots,
+        None => db
+            .get_discovery_roots()
+            .await
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(|root| root.path)
+            .collect(),
+    };
+
+    discovery::discover_projects(&db, &roots)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn import_discovered_projects(
+    db: State<'_, DbState>,
+    watcher: State<'_, WatcherState>,
+    projects: Vec<DiscoveredProject>,
+    createAccounts: bool,
+) -> Result<DiscoveryImportResult, String> {
+    let db = db.lock().await;
+    let result = discovery::import_projects(&db, &projects, createAccounts)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    tracing::info!(
+        "项目发现导入完成: {} 个目录, {} 个账号, {} 个 API 地址",
+        result.directories_created, result.accounts_created, result.base_urls_created
+    );
+    sync_watched_directories(&db, &watcher).await;
+    Ok(result)
+}
+
+// API 地址健康检查
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn check_base_url_health(
+    app: AppHandle,
+    db: State<'_, DbState>,
+    baseUrlId: i64,
+    accountId: Option<i64>,
+) -> Result<EndpointCheck, String> {
+    let db_lock = db.lock().await;
+    let base_url = db_lock.get_base_url(baseUrlId).await.map_err(|e| e.to_string())?;
+    let account = match accountId {
+        Some(id) => Some(db_lock.get_account(id).await.map_err(|e| e.to_string())?),
+        None => None,
+    };
+    // API 地址和账号的自定义请求头随探测请求一起发送
+    let headers = custom_headers::effective_headers(Some(&base_url), account.as_ref());
+    // 指定账号时使用账号绑定的代理，否则使用 API 地址绑定的代理
+    let proxy = match accountId {
+        Some(id) => db_lock.get_proxy_for_switch(id, None).await,
+        None => db_lock.get_proxy_for_base_url(baseUrlId).await,
+    }
+    .map_err(|e| e.to_string())?;
+    // 探测期间不持有数据库锁
+    drop(db_lock);
+
+    // 辅助命令模式的账号先执行命令获取密钥，命令失败时记为检查失败
+    let token = match &account {
+        Some(account) => Some(key_helper::resolve_token(account).await),
+        None => None,
+    };
+    let mut request = match token {
+        Some(Err(e)) => CreateEndpointCheckRequest {
+            error: Some(e),
+            ..Default::default()
+        },
+        Some(Ok(token)) => {
+            let auth = Some((base_url.api_key.as_str(), token.as_str()));
+            health::probe_endpoint(&base_url.url, auth, &headers, proxy.as_ref(), health::PROBE_TIMEOUT).await
+        }
+        None => health::probe_endpoint(&base_url.url, None, &headers, proxy.as_ref(), health::PROBE_TIMEOUT).await,
+    };
+    request.base_url_id = baseUrlId;
+    request.account_id = accountId;
+    if request.success {
+        tracing::info!("API 地址 {} 检查通过", base_url.url);
+    } else {
+        tracing::warn!("API 地址 {} 检查失败: {}", base_url.url, request.error.as_deref().unwrap_or(""));
+    }
+
+    let db_lock = db.lock().await;
+    let check = db_lock.create_endpoint_check(request)
+        .await
+        .map_err(|e| e.to_string())?;
+    let failovers = pools::failover_after_check(&db_lock, &check)
+        .await
+        .map_err(|e| e.to_string())?;
+    report_failovers(&app, &db_lock, &failovers).await;
+    Ok(check)
+}
+
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn get_endpoint_checks(
+    db: State<'_, DbState>,
+    baseUrlId: i64,
+    limit: Option<i64>,
+) -> Result<Vec<EndpointCheck>, String> {
+    let db = db.lock().await;
+    db.get_endpoint_checks(baseUrlId, limit.unwrap_or(20))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_latest_endpoint_checks(db: State<'_, DbState>) -> Result<Vec<EndpointCheck>, String> {
+    let db = db.lock().await;
+    db.get_latest_endpoint_checks()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// 账号令牌校验
+async fn verify_account_token_unlocked(app: &AppHandle, db: &DbState, account: Account) -> Result<Account, String> {
+    let db_lock = db.lock().await;
+    let base_url = db_lock
+        .get_base_urls()
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .find(|bu| bu.url == account.base_url);
+    let token_key = base_url
+        .as_ref()
+        .map(|bu| bu.api_key.clone())
+        .unwrap_or_else(|| "ANTHROPIC_API_KEY".to_string());
+    let headers = custom_headers::effective_headers(base_url.as_ref(), Some(&account));
+    let proxy = db_lock
+        .get_proxy_for_switch(account.id, None)
+        .await
+        .map_err(|e| e.to_string())?;
+    // 校验期间不持有数据库锁
+    drop(db_lock);
+
+    // 辅助命令模式的账号先执行命令获取密钥
+    let (status, error) = match key_helper::resolve_token(&account).await {
+        Ok(token) => health::verify_token(&account.base_url, &token_key, &token, &headers, proxy.as_ref(), health::PROBE_TIMEOUT).await,
+        Err(e) => (health::TOKEN_ERROR, Some(e)),
+    };
+    if status != health::TOKEN_VALID {
+        tracing::warn!("账号 {} 的令牌校验未通过: {}", account.name, error.as_deref().unwrap_or(""));
+    }
+
+    let db_lock = db.lock().await;
+    let verified = db_lock.update_account_token_status(account.id, status, error.as_deref())
+        .await
+        .map_err(|e| e.to_string())?;
+    // 只有令牌确认失效时才切换，校验出错（网络超时等）不触发故障切换
+    if status == health::TOKEN_INVALID {
+        let failovers = pools::failover_accounts(&db_lock, &[verified.id])
+            .await
+            .map_err(|e| e.to_string())?;
+        report_failovers(app, &db_lock, &failovers).await;
+    }
+    Ok(verified)
+}
+
+#[tauri::command]
+async fn verify_account_token(app: AppHandle, db: State<'_, DbState>, id: i64) -> Result<Account, String> {
+    let account = db.lock().await.get_account(id).await.map_err(|e| e.to_string())?;
+    verify_account_token_unlocked(&app, db.inner(), account).await
+}
+
+/// 校验所有账号的令牌
+#[tauri::command]
+async fn verify_all_account_tokens(app: AppHandle, db: State<'_, DbState>) -> Result<Vec<Account>, String> {
+    let mut accounts = Vec::new();
+    let mut page = 1;
+    loop {
+        let response = db.lock().await
+            .get_accounts(GetAccountsRequest {
+                page: Some(page),
+                per_page: Some(100),
+                search: None,
+                base_url: None,
+                ..Default::default()
+            })
+            .await
+            .map_err(|e| e.to_string())?;
+        accounts.extend(response.accounts);
+        if !response.pagination.has_next {
+            break;
+        }
+        page += 1;
+    }
+
+    let mut verified = Vec::new();
+    for account in accounts {
+        verified.push(verify_account_token_unlocked(&app, db.inner(), account).await?);
+    }
+    Ok(verified)
+}
+
+// 余额查询适配器
+#[tauri::command]
+async fn get_balance_adapters(db: State<'_, DbState>) -> Result<Vec<BalanceAdapter>, String> {
+    let db = db.lock().await;
+    db.get_balance_adapters()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn save_balance_adapter(
+    db: State<'_, DbState>,
+    request: SaveBalanceAdapterRequest,
+) -> Result<BalanceAdapter, String> {
+    let db = db.lock().await;
+    db.save_balance_adapter(request)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn delete_balance_adapter(db: State<'_, DbState>, baseUrlId: i64) -> Result<(), String> {
+    let db = db.lock().await;
+    db.delete_balance_adapter(baseUrlId)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// 账号余额
+#[tauri::command]
+async fn get_account_balances(db: State<'_, DbState>) -> Result<Vec<AccountBalance>, String> {
+    let db = db.lock().await;
+    db.get_account_balances()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn refresh_account_balances(db: State<'_, DbState>) -> Result<Vec<AccountBalance>, String> {
+    balance::refresh_balances(db.inner(), true)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// 账号池
+const POOL_FAILOVER_EVENT: &str = "pool-failover";
+
+/// 账号池切换了目录的账号：同步监听器的基准状态，避免被误判为漂移，并通知前端刷新
+async fn report_failovers(app: &AppHandle, db: &Database, failovers: &[PoolFailover]) {
+    if failovers.is_empty() {
+        return;
+    }
+
+    let config_watcher = app.state::<WatcherState>();
+    for failover in failovers.iter().filter(|f| f.to_account.is_some()) {
+        if let Ok(directory) = db.get_directory(failover.directory_id).await {
+            config_watcher.mark_synced(&directory);
+        }
+    }
+
+    if let Err(e) = app.emit(POOL_FAILOVER_EVENT, failovers) {
+        tracing::warn!("发送账号池切换事件失败: {}", e);
+    }
+}
+
+#[tauri::command]
+async fn get_account_pools(db: State<'_, DbState>) -> Result<Vec<AccountPool>, String> {
+    let db = db.lock().await;
+    db.get_account_pools()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn create_account_pool(db: State<'_, DbState>, request: CreateAccountPoolRequest) -> Result<AccountPool, String> {
+    let db = db.lock().await;
+    db.create_account_pool(request)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn delete_account_pool(db: State<'_, DbState>, id: i64) -> Result<String, String> {
+    let db = db.lock().await;
+    db.delete_account_pool(id)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok("账号池删除成功".to_string())
+}
+
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn get_account_pool_members(db: State<'_, DbState>, poolId: Option<i64>) -> Result<Vec<AccountPoolMember>, String> {
+    let db = db.lock().await;
+    db.get_account_pool_members(poolId)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn save_account_pool_member(db: State<'_, DbState>, request: SaveAccountPoolMemberRequest) -> Result<AccountPoolMember, String> {
+    let db = db.lock().await;
+    db.save_account_pool_member(request)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn remove_account_pool_member(db: State<'_, DbState>, poolId: i64, accountId: i64) -> Result<String, String> {
+    let db = db.lock().await;
+    db.remove_account_pool_member(poolId, accountId)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok("已移出账号池".to_string())
+}
+
+/// 目录绑定账号池，当前账号不在池中或不可用时立即切换
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn bind_directory_account_pool(
+    app: AppHandle,
+    db: State<'_, DbState>,
+    directoryId: i64,
+    poolId: Option<i64>,
+) -> Result<Option<PoolFailover>, String> {
+    let db = db.lock().await;
+    let switched = pools::bind_directory(&db, directoryId, poolId)
+        .await
+        .map_err(|e| e.to_string())?;
+    if let Some(switched) = &switched {
+        if switched.to_account.is_some() {
+            if let Ok(directory) = db.get_directory(directoryId).await {
+                app.state::<WatcherState>().mark_synced(&directory);
+            }
+        }
+    }
+    Ok(switched)
+}
+
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn get_pool_failovers(db: State<'_, DbState>, poolId: Option<i64>, limit: Option<i64>) -> Result<Vec<PoolFailoverRecord>, String> {
+    let db = db.lock().await;
+    db.get_pool_failovers(poolId, limit.unwrap_or(50))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// 本地中转代理
+async fn start_relay_unlocked(relay_state: &RelayState, db: &DbState, port: u16) -> Result<RelayStatus, String> {
+    let mut running = relay_state.lock().await;
+    if let Some((current, _)) = running.as_ref() {
+        if *current == port {
+            return Ok(RelayStatus { running: true, port });
+        }
+    }
+    let listener = relay::bind(port)
+        .await
+        .map_err(|e| format!("中转代理无法监听端口 {}: {}", port, e))?;
+    if let Some((_, handle)) = running.take() {
+        handle.abort();
+    }
+    *running = Some((port, tauri::async_runtime::spawn(relay::serve(listener, db.clone()))));
+    tracing::info!("本地中转代理已启动: http://127.0.0.1:{}", port);
+    Ok(RelayStatus { running: true, port })
+}
+
+#[tauri::command]
+async fn get_relay_status(relay: State<'_, RelayState>) -> Result<RelayStatus, String> {
+    let running = relay.lock().await;
+    Ok(match running.as_ref() {
+        Some((port, _)) => RelayStatus { running: true, port: *port },
+        None => RelayStatus { running: false, port: relay::DEFAULT_PORT },
+    })
+}
+
+#[tauri::command]
+async fn start_relay(db: State<'_, DbState>, relay: State<'_, RelayState>, port: Option<u16>) -> Result<RelayStatus, String> {
+    start_relay_unlocked(relay.inner(), db.inner(), port.unwrap_or(relay::DEFAULT_PORT)).await
+}
+
+#[tauri::command]
+async fn stop_relay(relay: State<'_, RelayState>) -> Result<RelayStatus, String> {
+    let mut running = relay.lock().await;
+    let port = match running.take() {
+        Some((port, handle)) => {
+            handle.abort();
+            tracing::info!("本地中转代理已停止");
+            port
+        }
+        None => relay::DEFAULT_PORT,
+    };
+    Ok(RelayStatus { running: false, port })
+}
+
+#[tauri::command]
+async fn get_relay_stats(db: State<'_, DbState>) -> Result<Vec<RelayStat>, String> {
+    let db = db.lock().await;
+    db.get_relay_stats()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 设置目录是否通过中转代理访问，启用时确保代理在对应端口运行
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn set_directory_relay(
+    db: State<'_, DbState>,
+    relay: State<'_, RelayState>,
+    watcher: State<'_, WatcherState>,
+    directoryId: i64,
+    port: Option<u16>,
+) -> Result<String, String> {
+    let db_lock = db.lock().await;
+    relay::route_directory(&db_lock, directoryId, port)
+        .await
+        .map_err(|e| e.to_string())?;
+    let directory = db_lock.get_directory(directoryId).await.map_err(|e| e.to_string())?;
+    watcher.mark_synced(&directory);
+    drop(db_lock);
+
+    if let Some(port) = port {
+        start_relay_unlocked(relay.inner(), db.inner(), port).await?;
+    }
+    Ok("目录配置已更新".to_string())
+}
+
+// 代理管理
+#[tauri::command]
+async fn get_proxies(db: State<'_, DbState>) -> Result<Vec<Proxy>, String> {
+    let db = db.lock().await;
+    db.get_proxies()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+fn map_proxy_error(e: sqlx::Error) -> String {
+    let error_msg = e.to_string();
+    if is_unique_violation(&e) {
+        "代理名称已存在".to_string()
+    } else {
+        error_msg
+    }
+}
+
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn create_proxy(
+    db: State<'_, DbState>,
+    name: String,
+    url: String,
+    noProxy: Option<String>,
+    username: Option<String>,
+    password: Option<String>,
+    description: Option<String>,
+) -> Result<Proxy, String> {
+    proxy::validate_proxy_url(&url)?;
+
+    let db = db.lock().await;
+    let request = CreateProxyRequest {
+        name,
+        url: url.trim().to_string(),
+        no_proxy: noProxy,
+        username,
+        password,
+        description,
+    };
+
+    db.create_proxy(request).await.map_err(map_proxy_error)
+}
+
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn update_proxy(
+    db: State<'_, DbState>,
+    id: i64,
+    name: Option<String>,
+    url: Option<String>,
+    noProxy: Option<String>,
+    username: Option<String>,
+    password: Option<String>,
+    description: Option<String>,
+) -> Result<Proxy, String> {
+    if let Some(url) = &url {
+        proxy::validate_proxy_url(url)?;
+    }
+
+    let db = db.lock().await;
+    let request = UpdateProxyRequest {
+        name,
+        url: url.map(|u| u.trim().to_string()),
+        no_proxy: noProxy,
+        username,
+        password,
+        description,
+    };
+
+    db.update_proxy(id, request).await.map_err(map_proxy_error)
+}
+
+#[tauri::command]
+async fn delete_proxy(db: State<'_, DbState>, id: i64) -> Result<String, String> {
+    let db = db.lock().await;
+    db.delete_proxy(id)
+        .await
+        .map(|_| "代理删除成功".to_string())
+        .map_err(|e| e.to_string())
+}
+
+/// 通过代理访问测试地址，返回耗时（毫秒）
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn test_proxy(
+    db: State<'_, DbState>,
+    id: i64,
+    targetUrl: Option<String>,
+) -> Result<i64, String> {
+    let db_lock = db.lock().await;
+    let bound_proxy = db_lock.get_proxy(id).await.map_err(|e| e.to_string())?;
+    drop(db_lock);
+
+    let target = targetUrl
+        .filter(|t| !t.trim().is_empty())
+        .unwrap_or_else(|| proxy::DEFAULT_TEST_URL.to_string());
+    proxy::test_proxy(&bound_proxy, target.trim(), std::time::Duration::from_secs(10)).await
+}
+
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn set_base_url_proxy(
+    db: State<'_, DbState>,
+    baseUrlId: i64,
+    proxyId: Option<i64>,
+) -> Result<String, String> {
+    let db = db.lock().await;
+    db.set_base_url_proxy(baseUrlId, proxyId)
+        .await
+        .map(|_| "URL 代理已更新".to_string())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn set_account_proxy(
+    db: State<'_, DbState>,
+    accountId: i64,
+    proxyId: Option<i64>,
+) -> Result<String, String> {
+    let db = db.lock().await;
+    db.set_account_proxy(accountId, proxyId)
+        .await
+        .map(|_| "账号代理已更新".to_string())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn set_directory_proxy(
+    db: State<'_, DbState>,
+    directoryId: i64,
+    proxyId: Option<i64>,
+) -> Result<String, String> {
+    let db = db.lock().await;
+    db.set_directory_proxy(directoryId, proxyId)
+        .await
+        .map(|_| "目录代理已更新".to_string())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn set_webdav_config_proxy(
+    db: State<'_, DbState>,
+    configId: i64,
+    proxyId: Option<i64>,
+) -> Result<String, String> {
+    let db = db.lock().await;
+    webdav::set_webdav_config_proxy(db.get_pool(), configId, proxyId)
+        .await
+        .map(|_| "WebDAV 代理已更新".to_string())
+        .map_err(|e| e.to_string())
+}
+
+/// 获取支持的供应商类型及其环境变量定义
+#[tauri::command]
+async fn get_provider_types() -> Result<Vec<providers::ProviderSchema>, String> {
+    Ok(providers::PROVIDERS.to_vec())
+}
+
+/// 按账号所用 URL 的供应商类型校验令牌和环境变量（URL 默认环境变量与账号自定义环境变量合并后校验）
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn validate_account_provider_env(
+    db: State<'_, DbState>,
+    baseUrl: String,
+    token: String,
+    customEnvVars: Option<serde_json::Value>,
+) -> Result<(), String> {
+    let db = db.lock().await;
+    let base_urls = db.get_base_urls().await.map_err(|e| e.to_string())?;
+    let matched = base_urls.iter().find(|bu| bu.url == baseUrl);
+
+    let provider_type = matched
+        .map(|bu| bu.provider_type.clone())
+        .unwrap_or_else(|| providers::DEFAULT_PROVIDER.to_string());
+    let mut env_vars = matched
+        .and_then(|bu| bu.get_default_env_vars())
+        .unwrap_or_default();
+    if let Some(custom) = customEnvVars.as_ref().and_then(|v| v.as_object()) {
+        for (key, value) in custom {
+            let value = match value {
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            env_vars.insert(key.clone(), value);
+        }
+    }
+
+    providers::validate_provider_env(&provider_type, &token, &env_vars)
+}
+
+/// 设置账号的凭据模式：token 直接写入令牌，helper 切换时写入 apiKeyHelper 命令
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn set_account_credential(
+    db: State<'_, DbState>,
+    accountId: i64,
+    credentialMode: String,
+    apiKeyHelper: Option<String>,
+    apiKeyHelperTtlMs: Option<i64>,
+) -> Result<Account, String> {
+    key_helper::validate_credential(&credentialMode, apiKeyHelper.as_deref(), apiKeyHelperTtlMs)?;
+    let db = db.lock().await;
+    db.set_account_credential(accountId, &credentialMode, apiKeyHelper.as_deref(), apiKeyHelperTtlMs)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 试运行 apiKeyHelper 命令，成功时返回隐藏中间部分的密钥
+#[tauri::command]
+async fn test_api_key_helper(command: String) -> Result<String, String> {
+    let key = key_helper::run_key_helper(&command, key_helper::HELPER_TIMEOUT).await?;
+    Ok(drift::mask_secret(&key))
+}
+
+/// 保存 API 地址的自定义请求头，切换时写入 ANTHROPIC_CUSTOM_HEADERS
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn set_base_url_headers(
+    db: State<'_, DbState>,
+    baseUrlId: i64,
+    headers: Vec<custom_headers::CustomHeader>,
+) -> Result<(), String> {
+    custom_headers::validate_headers(&headers)?;
+    let db = db.lock().await;
+    db.set_base_url_headers(baseUrlId, &headers)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 保存账号的自定义请求头，同名请求头覆盖 API 地址上的设置
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn set_account_headers(
+    db: State<'_, DbState>,
+    accountId: i64,
+    headers: Vec<custom_headers::CustomHeader>,
+) -> Result<(), String> {
+    custom_headers::validate_headers(&headers)?;
+    let db = db.lock().await;
+    db.set_account_headers(accountId, &headers)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_migration_status(db: State<'_, DbState>) -> Result<Vec<migrations::MigrationStatus>, String> {
+    let db = db.lock().await;
+    db.migration_status()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[cfg_attr(mobile, tauri::mobile_entry_point)]
+pub fn run() {
+    // 初始化日志系统
+    if let Err(e) = crate::logger::Logger::init() {
+        eprintln!("日志系统初始化失败: {}", e);
+    }
+    
+    tracing::info!("Claude Configuration Manager 启动中...");
+    
+    tauri::Builder::default()
+        .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
+            // 当尝试启动第二个实例时的处理逻辑
+            tracing::info!("检测到重复启动，显示现有窗口");
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.show();
+                let _ = window.set_focus();
+                let _ = window.unminimize();
+            }
+        }))
+        .on_window_event(|window, event| match event {
+            WindowEvent::CloseRequested { api, .. } => {
+                // 阻止默认的关闭行为，改为隐藏到托盘
+                api.prevent_close();
+                window.hide().unwrap();
+            }
+            _ => {}
         })
         .setup(|app| {
             let runtime = tokio::runtime::Runtime::new().unwrap();
@@ -1664,11 +3225,21 @@ pub fn run() {
                             }
                             break database;
                         }
+                        Err(e) if migrations::is_schema_too_new(&e) => {
+                            // 数据库由更新版本的程序迁移过，重试和回退到新数据库都没有意义
+                            tracing::error!("数据库初始化失败: {}", e);
+                            eprintln!("{}", e);
+                            std::process::exit(1);
+                        }
                         Err(e) => {
                             retry_count += 1;
                             tracing::error!("数据库初始化失败 (尝试 {}/{}): {}", retry_count, MAX_RETRIES, e);
                             
-                            if retry_count >= MAX_RETRIES {
+                            if retry_count >= MAX_RETRIES && Database::configured_backend() != Some(Backend::Sqlite) {
+                                // 共享的远程数据库连不上时不回退到本地数据库，避免数据分散在两处
+                                eprintln!("无法连接配置的数据库: {}", e);
+                                std::process::exit(1);
+                            } else if retry_count >= MAX_RETRIES {
                                 // 最后尝试：创建默认数据库配置
                                 eprintln!("数据库初始化失败，正在尝试创建默认配置...");
                                 
@@ -1702,7 +3273,35 @@ pub fn run() {
                 }
             });
             
-            app.manage(Arc::new(Mutex::new(db)));
+            let directories = runtime.block_on(db.get_directories()).unwrap_or_default();
+            let db_state: DbState = Arc::new(Mutex::new(db));
+            app.manage(db_state.clone());
+
+            // 监听已登记目录的受管配置文件，外部修改后标记漂移
+            let (config_watcher, watch_events) = watcher::ConfigWatcher::new();
+            config_watcher.sync_directories(&directories);
+            app.manage(config_watcher.clone());
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(config_watcher.process_events(
+                watch_events,
+                db_state.clone(),
+                move |drift, newly_drifted| on_drift_changed(&app_handle, drift, newly_drifted),
+            ));
+
+            // 后台定时刷新配置了余额查询的账号余额
+            tauri::async_runtime::spawn(balance::run_refresh_loop(db_state.clone()));
+
+            // 有目录通过中转代理访问时自动启动本地中转代理
+            let relay_state: RelayState = Arc::new(Mutex::new(None));
+            app.manage(relay_state.clone());
+            if let Some(port) = directories.iter().find_map(|d| d.relay_port) {
+                let db_state = db_state.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = start_relay_unlocked(&relay_state, &db_state, port as u16).await {
+                        tracing::warn!("启动本地中转代理失败: {}", e);
+                    }
+                });
+            }
 
             // 创建托盘菜单
             let show_item = MenuItem::with_id(app, "show", "显示主窗口", true, None::<&str>).unwrap();
@@ -1751,6 +3350,10 @@ pub fn run() {
             update_account,
             delete_account,
             get_account_base_urls,
+            get_expiring_accounts,
+            preview_account_import,
+            import_accounts,
+            export_accounts,
             get_directories,
             create_directory,
             update_directory,
@@ -1784,10 +3387,87 @@ pub fn run() {
             delete_webdav_config,
             test_webdav_connection,
             upload_config_to_webdav,
+            preview_webdav_download,
             download_config_from_webdav,
             list_webdav_files,
             delete_remote_file,
-            get_sync_logs
+            get_sync_logs,
+            get_mcp_servers,
+            create_mcp_server,
+            update_mcp_server,
+            delete_mcp_server,
+            get_directory_mcp_servers,
+            set_directory_mcp_servers,
+            get_account_mcp_servers,
+            set_account_mcp_servers,
+            get_hooks,
+            create_hook,
+            update_hook,
+            delete_hook,
+            get_directory_hooks,
+            set_directory_hooks,
+            get_permission_presets,
+            create_permission_preset,
+            update_permission_preset,
+            delete_permission_preset,
+            set_directory_permission_preset,
+            set_account_permission_preset,
+            validate_permission_rule,
+            get_claude_definitions,
+            create_claude_definition,
+            update_claude_definition,
+            delete_claude_definition,
+            get_directory_definitions,
+            set_directory_definitions,
+            import_claude_definitions,
+            scan_config_drift,
+            fix_config_drift,
+            get_drifted_directories,
+            set_drift_notifications,
+            get_discovery_roots,
+            add_discovery_root,
+            delete_discovery_root,
+            discover_projects,
+            import_discovered_projects,
+            check_base_url_health,
+            get_endpoint_checks,
+            get_latest_endpoint_checks,
+            verify_account_token,
+            verify_all_account_tokens,
+            get_balance_adapters,
+            save_balance_adapter,
+            delete_balance_adapter,
+            get_account_balances,
+            refresh_account_balances,
+            get_account_pools,
+            create_account_pool,
+            delete_account_pool,
+            get_account_pool_members,
+            save_account_pool_member,
+            remove_account_pool_member,
+            bind_directory_account_pool,
+            get_pool_failovers,
+            get_relay_status,
+            start_relay,
+            stop_relay,
+            get_relay_stats,
+            set_directory_relay,
+            get_proxies,
+            create_proxy,
+            update_proxy,
+            delete_proxy,
+            test_proxy,
+            set_base_url_proxy,
+            set_account_proxy,
+            set_directory_proxy,
+            set_webdav_config_proxy,
+            get_provider_types,
+            validate_account_provider_env,
+            set_account_credential,
+            test_api_key_helper,
+            set_base_url_headers,
+            set_account_headers,
+            get_migration_status
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,805 @@
+use crate::db_backend::{DbPool, DbTransaction};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::Error as SqlxError;
+use std::fmt;
+use tracing::info;
+
+/// 迁移中的一步
+pub enum Step {
+    /// 直接执行的 SQL，必须可以重复执行（如 CREATE TABLE IF NOT EXISTS）
+    Sql(&'static str),
+    /// 添加字段，字段已存在时跳过：旧版本按字段逐个检测升级过的数据库没有迁移记录
+    AddColumn {
+        table: &'static str,
+        column: &'static str,
+        definition: &'static str,
+    },
+}
+
+/// 带编号的迁移，按版本号顺序执行，每个迁移在一个事务中完成
+/// 建表语句按 SQLite 方言编写，在其他数据库上执行前会转换字段类型
+pub struct Migration {
+    pub version: i64,
+    pub name: &'static str,
+    pub steps: &'static [Step],
+}
+
+/// 所有迁移，新的结构变更只能追加到末尾，已发布的迁移不能修改
+pub const MIGRATIONS: &[Migration] = &[
+    // 完整的表结构，新数据库只需要这一个迁移，后面的字段都会被跳过
+    Migration {
+        version: 1,
+        name: "initial_schema",
+        steps: &[
+            // Create accounts table
+            Step::Sql(
+                r#"
+            CREATE TABLE IF NOT EXISTS accounts (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL UNIQUE,
+                token TEXT NOT NULL,
+                base_url TEXT NOT NULL,
+                model TEXT NOT NULL DEFAULT '',
+                is_active BOOLEAN NOT NULL DEFAULT FALSE,
+                custom_env_vars TEXT NOT NULL DEFAULT '{}',
+                permission_preset_id INTEGER,
+                token_status TEXT,
+                token_checked_at DATETIME,
+                token_error TEXT,
+                enabled BOOLEAN NOT NULL DEFAULT TRUE,
+                expires_at DATETIME,
+                notes TEXT NOT NULL DEFAULT '',
+                tags TEXT NOT NULL DEFAULT '',
+                last_used_at DATETIME,
+                use_count INTEGER NOT NULL DEFAULT 0,
+                proxy_id INTEGER,
+                credential_mode TEXT NOT NULL DEFAULT 'token',
+                api_key_helper TEXT,
+                api_key_helper_ttl_ms INTEGER,
+                custom_headers TEXT NOT NULL DEFAULT '[]',
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            )
+            "#,
+            ),
+            // Create directories table
+            Step::Sql(
+                r#"
+            CREATE TABLE IF NOT EXISTS directories (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                path TEXT NOT NULL UNIQUE,
+                name TEXT NOT NULL,
+                is_active BOOLEAN NOT NULL DEFAULT FALSE,
+                permission_preset_id INTEGER,
+                account_pool_id INTEGER,
+                relay_port INTEGER,
+                proxy_id INTEGER,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            )
+            "#,
+            ),
+            // Create base_urls table
+            Step::Sql(
+                r#"
+            CREATE TABLE IF NOT EXISTS base_urls (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL UNIQUE,
+                url TEXT NOT NULL UNIQUE,
+                description TEXT,
+                api_key TEXT NOT NULL DEFAULT 'ANTHROPIC_API_KEY',
+                provider_type TEXT NOT NULL DEFAULT 'anthropic',
+                is_default BOOLEAN NOT NULL DEFAULT FALSE,
+                proxy_id INTEGER,
+                default_env_vars TEXT NOT NULL DEFAULT '{}',
+                custom_headers TEXT NOT NULL DEFAULT '[]',
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            )
+            "#,
+            ),
+            // Create account_directories table
+            Step::Sql(
+                r#"
+            CREATE TABLE IF NOT EXISTS account_directories (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                account_id INTEGER NOT NULL,
+                directory_id INTEGER NOT NULL,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (account_id) REFERENCES accounts (id) ON DELETE CASCADE,
+                FOREIGN KEY (directory_id) REFERENCES directories (id) ON DELETE CASCADE,
+                UNIQUE(account_id, directory_id)
+            )
+            "#,
+            ),
+            // Create claude_settings table
+            Step::Sql(
+                r#"
+            CREATE TABLE IF NOT EXISTS claude_settings (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                settings_json TEXT NOT NULL,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            )
+            "#,
+            ),
+            // Create webdav_configs table for WebDAV synchronization
+            Step::Sql(
+                r#"
+            CREATE TABLE IF NOT EXISTS webdav_configs (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL UNIQUE,
+                url TEXT NOT NULL,
+                username TEXT NOT NULL,
+                password TEXT NOT NULL,
+                remote_path TEXT NOT NULL DEFAULT '/claude-config',
+                auto_sync BOOLEAN NOT NULL DEFAULT FALSE,
+                sync_interval INTEGER NOT NULL DEFAULT 3600,
+                is_active BOOLEAN NOT NULL DEFAULT FALSE,
+                last_sync_at DATETIME,
+                proxy_id INTEGER,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            )
+            "#,
+            ),
+            // Create sync_logs table for tracking synchronization history
+            Step::Sql(
+                r#"
+            CREATE TABLE IF NOT EXISTS sync_logs (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                webdav_config_id INTEGER NOT NULL,
+                sync_type TEXT NOT NULL CHECK(sync_type IN ('upload', 'download', 'auto')),
+                status TEXT NOT NULL CHECK(status IN ('success', 'failed', 'pending')),
+                message TEXT,
+                synced_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (webdav_config_id) REFERENCES webdav_configs (id) ON DELETE CASCADE
+            )
+            "#,
+            ),
+            // Create mcp_servers table for MCP server catalog
+            Step::Sql(
+                r#"
+            CREATE TABLE IF NOT EXISTS mcp_servers (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL UNIQUE,
+                transport TEXT NOT NULL DEFAULT 'stdio' CHECK(transport IN ('stdio', 'sse', 'http')),
+                command TEXT,
+                args TEXT NOT NULL DEFAULT '[]',
+                env TEXT NOT NULL DEFAULT '{}',
+                url TEXT,
+                description TEXT,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            )
+            "#,
+            ),
+            // Create directory_mcp_servers table
+            Step::Sql(
+                r#"
+            CREATE TABLE IF NOT EXISTS directory_mcp_servers (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                directory_id INTEGER NOT NULL,
+                mcp_server_id INTEGER NOT NULL,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (directory_id) REFERENCES directories (id) ON DELETE CASCADE,
+                FOREIGN KEY (mcp_server_id) REFERENCES mcp_servers (id) ON DELETE CASCADE,
+                UNIQUE(directory_id, mcp_server_id)
+            )
+            "#,
+            ),
+            // Create account_mcp_servers table
+            Step::Sql(
+                r#"
+            CREATE TABLE IF NOT EXISTS account_mcp_servers (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                account_id INTEGER NOT NULL,
+                mcp_server_id INTEGER NOT NULL,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (account_id) REFERENCES accounts (id) ON DELETE CASCADE,
+                FOREIGN KEY (mcp_server_id) REFERENCES mcp_servers (id) ON DELETE CASCADE,
+                UNIQUE(account_id, mcp_server_id)
+            )
+            "#,
+            ),
+            // Create hooks table for Claude Code hooks library
+            Step::Sql(
+                r#"
+            CREATE TABLE IF NOT EXISTS hooks (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL UNIQUE,
+                event TEXT NOT NULL,
+                matcher TEXT,
+                command TEXT NOT NULL,
+                timeout INTEGER,
+                is_global BOOLEAN NOT NULL DEFAULT FALSE,
+                description TEXT,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            )
+            "#,
+            ),
+            // Create directory_hooks table
+            Step::Sql(
+                r#"
+            CREATE TABLE IF NOT EXISTS directory_hooks (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                directory_id INTEGER NOT NULL,
+                hook_id INTEGER NOT NULL,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (directory_id) REFERENCES directories (id) ON DELETE CASCADE,
+                FOREIGN KEY (hook_id) REFERENCES hooks (id) ON DELETE CASCADE,
+                UNIQUE(directory_id, hook_id)
+            )
+            "#,
+            ),
+            // Create permission_presets table
+            Step::Sql(
+                r#"
+            CREATE TABLE IF NOT EXISTS permission_presets (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL UNIQUE,
+                default_mode TEXT NOT NULL DEFAULT 'default',
+                allow TEXT NOT NULL DEFAULT '[]',
+                deny TEXT NOT NULL DEFAULT '[]',
+                ask TEXT NOT NULL DEFAULT '[]',
+                description TEXT,
+                is_builtin BOOLEAN NOT NULL DEFAULT FALSE,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            )
+            "#,
+            ),
+            // Create claude_definitions table for slash commands and subagents
+            Step::Sql(
+                r#"
+            CREATE TABLE IF NOT EXISTS claude_definitions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                kind TEXT NOT NULL,
+                name TEXT NOT NULL,
+                description TEXT,
+                content TEXT NOT NULL,
+                is_default BOOLEAN NOT NULL DEFAULT FALSE,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                UNIQUE(kind, name)
+            )
+            "#,
+            ),
+            // Create directory_definitions table (per-directory enable/disable overrides)
+            Step::Sql(
+                r#"
+            CREATE TABLE IF NOT EXISTS directory_definitions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                directory_id INTEGER NOT NULL,
+                definition_id INTEGER NOT NULL,
+                enabled BOOLEAN NOT NULL,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (directory_id) REFERENCES directories (id) ON DELETE CASCADE,
+                FOREIGN KEY (definition_id) REFERENCES claude_definitions (id) ON DELETE CASCADE,
+                UNIQUE(directory_id, definition_id)
+            )
+            "#,
+            ),
+            // Create discovery_roots table (roots scanned when discovering existing projects)
+            Step::Sql(
+                r#"
+            CREATE TABLE IF NOT EXISTS discovery_roots (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                path TEXT NOT NULL UNIQUE,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            )
+            "#,
+            ),
+            // Create endpoint_checks table for base URL health check history
+            Step::Sql(
+                r#"
+            CREATE TABLE IF NOT EXISTS endpoint_checks (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                base_url_id INTEGER NOT NULL,
+                account_id INTEGER,
+                connect_ms INTEGER,
+                latency_ms INTEGER,
+                status_code INTEGER,
+                auth_status_code INTEGER,
+                success BOOLEAN NOT NULL,
+                error TEXT,
+                checked_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (base_url_id) REFERENCES base_urls (id) ON DELETE CASCADE
+            )
+            "#,
+            ),
+            // Create balance_adapters table for relay balance query adapters
+            Step::Sql(
+                r#"
+            CREATE TABLE IF NOT EXISTS balance_adapters (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                base_url_id INTEGER NOT NULL UNIQUE,
+                method TEXT NOT NULL DEFAULT 'GET',
+                url_template TEXT NOT NULL,
+                headers TEXT,
+                body_template TEXT,
+                balance_path TEXT NOT NULL,
+                divisor REAL NOT NULL DEFAULT 1,
+                unit TEXT NOT NULL DEFAULT 'USD',
+                enabled BOOLEAN NOT NULL DEFAULT TRUE,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (base_url_id) REFERENCES base_urls (id) ON DELETE CASCADE
+            )
+            "#,
+            ),
+            // Create account_balances table for cached account balances
+            Step::Sql(
+                r#"
+            CREATE TABLE IF NOT EXISTS account_balances (
+                account_id INTEGER PRIMARY KEY,
+                balance REAL,
+                unit TEXT,
+                error TEXT,
+                fetched_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (account_id) REFERENCES accounts (id) ON DELETE CASCADE
+            )
+            "#,
+            ),
+            // Create account_pools table for named groups of interchangeable accounts
+            Step::Sql(
+                r#"
+            CREATE TABLE IF NOT EXISTS account_pools (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL UNIQUE,
+                description TEXT,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            )
+            "#,
+            ),
+            // Create account_pool_members table for pool membership with weight and priority
+            Step::Sql(
+                r#"
+            CREATE TABLE IF NOT EXISTS account_pool_members (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                pool_id INTEGER NOT NULL,
+                account_id INTEGER NOT NULL,
+                weight INTEGER NOT NULL DEFAULT 100,
+                priority INTEGER NOT NULL DEFAULT 1,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (pool_id) REFERENCES account_pools (id) ON DELETE CASCADE,
+                FOREIGN KEY (account_id) REFERENCES accounts (id) ON DELETE CASCADE,
+                UNIQUE(pool_id, account_id)
+            )
+            "#,
+            ),
+            // Create relay_stats table for per-account request statistics of the local relay proxy
+            Step::Sql(
+                r#"
+            CREATE TABLE IF NOT EXISTS relay_stats (
+                account_id INTEGER PRIMARY KEY,
+                request_count INTEGER NOT NULL DEFAULT 0,
+                failure_count INTEGER NOT NULL DEFAULT 0,
+                total_latency_ms INTEGER NOT NULL DEFAULT 0,
+                last_status INTEGER,
+                last_latency_ms INTEGER,
+                last_used_at DATETIME,
+                FOREIGN KEY (account_id) REFERENCES accounts (id) ON DELETE CASCADE
+            )
+            "#,
+            ),
+            // Create proxies table for HTTP/SOCKS proxies attachable to base URLs, accounts, directories and WebDAV configs
+            Step::Sql(
+                r#"
+            CREATE TABLE IF NOT EXISTS proxies (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL UNIQUE,
+                url TEXT NOT NULL,
+                no_proxy TEXT,
+                username TEXT,
+                password TEXT,
+                description TEXT,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            )
+            "#,
+            ),
+        ],
+    },
+    // 账号模型和 URL 的 API KEY 变量名
+    Migration {
+        version: 2,
+        name: "account_model_and_api_key",
+        steps: &[
+            Step::AddColumn {
+                table: "accounts",
+                column: "model",
+                definition: "TEXT NOT NULL DEFAULT ''",
+            },
+            Step::AddColumn {
+                table: "base_urls",
+                column: "api_key",
+                definition: "TEXT NOT NULL DEFAULT 'ANTHROPIC_API_KEY'",
+            },
+        ],
+    },
+    // URL 默认环境变量和账号自定义环境变量（原 migrate_env_vars.sql）
+    Migration {
+        version: 3,
+        name: "env_vars",
+        steps: &[
+            Step::AddColumn {
+                table: "accounts",
+                column: "custom_env_vars",
+                definition: "TEXT NOT NULL DEFAULT '{}'",
+            },
+            Step::AddColumn {
+                table: "base_urls",
+                column: "default_env_vars",
+                definition: "TEXT NOT NULL DEFAULT '{}'",
+            },
+        ],
+    },
+    // 账号和目录绑定的权限预设
+    Migration {
+        version: 4,
+        name: "permission_presets",
+        steps: &[
+            Step::AddColumn {
+                table: "accounts",
+                column: "permission_preset_id",
+                definition: "INTEGER",
+            },
+            Step::AddColumn {
+                table: "directories",
+                column: "permission_preset_id",
+                definition: "INTEGER",
+            },
+        ],
+    },
+    // 账号令牌校验结果
+    Migration {
+        version: 5,
+        name: "token_status",
+        steps: &[
+            Step::AddColumn {
+                table: "accounts",
+                column: "token_status",
+                definition: "TEXT",
+            },
+            Step::AddColumn {
+                table: "accounts",
+                column: "token_checked_at",
+                definition: "DATETIME",
+            },
+            Step::AddColumn {
+                table: "accounts",
+                column: "token_error",
+                definition: "TEXT",
+            },
+        ],
+    },
+    // 目录绑定的账号池和中转代理端口
+    Migration {
+        version: 6,
+        name: "account_pools",
+        steps: &[
+            Step::AddColumn {
+                table: "directories",
+                column: "account_pool_id",
+                definition: "INTEGER",
+            },
+            Step::AddColumn {
+                table: "directories",
+                column: "relay_port",
+                definition: "INTEGER",
+            },
+        ],
+    },
+    // 账号启用状态、到期时间、备注、标签和使用统计
+    Migration {
+        version: 7,
+        name: "account_lifecycle",
+        steps: &[
+            Step::AddColumn {
+                table: "accounts",
+                column: "enabled",
+                definition: "BOOLEAN NOT NULL DEFAULT TRUE",
+            },
+            Step::AddColumn {
+                table: "accounts",
+                column: "expires_at",
+                definition: "DATETIME",
+            },
+            Step::AddColumn {
+                table: "accounts",
+                column: "notes",
+                definition: "TEXT NOT NULL DEFAULT ''",
+            },
+            Step::AddColumn {
+                table: "accounts",
+                column: "tags",
+                definition: "TEXT NOT NULL DEFAULT ''",
+            },
+            Step::AddColumn {
+                table: "accounts",
+                column: "last_used_at",
+                definition: "DATETIME",
+            },
+            Step::AddColumn {
+                table: "accounts",
+                column: "use_count",
+                definition: "INTEGER NOT NULL DEFAULT 0",
+            },
+        ],
+    },
+    // URL、账号、目录和 WebDAV 配置绑定的代理
+    Migration {
+        version: 8,
+        name: "proxies",
+        steps: &[
+            Step::AddColumn {
+                table: "accounts",
+                column: "proxy_id",
+                definition: "INTEGER",
+            },
+            Step::AddColumn {
+                table: "directories",
+                column: "proxy_id",
+                definition: "INTEGER",
+            },
+            Step::AddColumn {
+                table: "base_urls",
+                column: "proxy_id",
+                definition: "INTEGER",
+            },
+            Step::AddColumn {
+                table: "webdav_configs",
+                column: "proxy_id",
+                definition: "INTEGER",
+            },
+        ],
+    },
+    // 账号凭据模式和 apiKeyHelper 命令
+    Migration {
+        version: 9,
+        name: "credential_mode",
+        steps: &[
+            Step::AddColumn {
+                table: "accounts",
+                column: "credential_mode",
+                definition: "TEXT NOT NULL DEFAULT 'token'",
+            },
+            Step::AddColumn {
+                table: "accounts",
+                column: "api_key_helper",
+                definition: "TEXT",
+            },
+            Step::AddColumn {
+                table: "accounts",
+                column: "api_key_helper_ttl_ms",
+                definition: "INTEGER",
+            },
+        ],
+    },
+    // URL 的供应商类型
+    Migration {
+        version: 10,
+        name: "provider_type",
+        steps: &[Step::AddColumn {
+            table: "base_urls",
+            column: "provider_type",
+            definition: "TEXT NOT NULL DEFAULT 'anthropic'",
+        }],
+    },
+    // URL 和账号的自定义请求头
+    Migration {
+        version: 11,
+        name: "custom_headers",
+        steps: &[
+            Step::AddColumn {
+                table: "base_urls",
+                column: "custom_headers",
+                definition: "TEXT NOT NULL DEFAULT '[]'",
+            },
+            Step::AddColumn {
+                table: "accounts",
+                column: "custom_headers",
+                definition: "TEXT NOT NULL DEFAULT '[]'",
+            },
+        ],
+    },
+    // 健康检查单独记录 TLS 握手耗时
+    Migration {
+        version: 12,
+        name: "endpoint_check_tls",
+        steps: &[Step::AddColumn {
+            table: "endpoint_checks",
+            column: "tls_ms",
+            definition: "INTEGER",
+        }],
+    },
+    // 账号池故障切换记录
+    Migration {
+        version: 13,
+        name: "pool_failovers",
+        steps: &[Step::Sql(
+            r#"
+            CREATE TABLE IF NOT EXISTS pool_failovers (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                pool_id INTEGER NOT NULL,
+                directory_id INTEGER NOT NULL,
+                pool_name TEXT NOT NULL,
+                directory_name TEXT NOT NULL,
+                from_account TEXT NOT NULL,
+                to_account TEXT,
+                error TEXT,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (pool_id) REFERENCES account_pools (id) ON DELETE CASCADE,
+                FOREIGN KEY (directory_id) REFERENCES directories (id) ON DELETE CASCADE
+            )
+            "#,
+        )],
+    },
+];
+
+/// 当前程序支持的最高结构版本
+pub fn latest_version() -> i64 {
+    MIGRATIONS.last().map(|m| m.version).unwrap_or(0)
+}
+
+/// 数据库由更新版本的程序迁移过，继续使用可能损坏数据
+#[derive(Debug)]
+pub struct SchemaTooNew {
+    pub database_version: i64,
+    pub supported_version: i64,
+}
+
+impl fmt::Display for SchemaTooNew {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "数据库结构版本 {} 高于当前程序支持的版本 {}，请升级程序后再使用该数据库",
+            self.database_version, self.supported_version
+        )
+    }
+}
+
+impl std::error::Error for SchemaTooNew {}
+
+/// 是否因为数据库版本高于程序版本而失败，这种情况不应回退到新建数据库
+pub fn is_schema_too_new(error: &SqlxError) -> bool {
+    matches!(error, SqlxError::Configuration(e) if e.is::<SchemaTooNew>())
+}
+
+/// 单个迁移的状态，applied_at 为空表示尚未执行，known 为 false 表示由更新版本的程序执行
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationStatus {
+    pub version: i64,
+    pub name: String,
+    pub applied_at: Option<DateTime<Utc>>,
+    pub known: bool,
+}
+
+async fn ensure_migrations_table(pool: &DbPool) -> Result<(), SqlxError> {
+    let sql = pool.backend().translate_ddl(
+        r#"
+        CREATE TABLE IF NOT EXISTS schema_migrations (
+            version INTEGER PRIMARY KEY,
+            name TEXT NOT NULL,
+            applied_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+        )
+        "#,
+    );
+    pool.query(sql).execute().await?;
+    Ok(())
+}
+
+async fn applied_migrations(pool: &DbPool) -> Result<Vec<(i64, String, DateTime<Utc>)>, SqlxError> {
+    pool.query("SELECT version, name, applied_at FROM schema_migrations ORDER BY version")
+        .fetch_all()
+        .await
+}
+
+async fn apply_step(tx: &mut DbTransaction, step: &Step) -> Result<(), SqlxError> {
+    let backend = tx.backend();
+    match step {
+        Step::Sql(sql) => {
+            tx.query(backend.translate_ddl(sql)).execute().await?;
+        }
+        Step::AddColumn {
+            table,
+            column,
+            definition,
+        } => {
+            let exists: i64 = tx
+                .query(backend.column_exists_sql())
+                .bind(*table)
+                .bind(*column)
+                .fetch_scalar()
+                .await?;
+            if exists == 0 {
+                info!("为 {} 表添加 {} 字段", table, column);
+                tx.query(format!(
+                    "ALTER TABLE {} ADD COLUMN {} {}",
+                    table,
+                    column,
+                    backend.column_definition(definition)
+                ))
+                .execute()
+                .await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 按顺序执行尚未执行的迁移，返回本次执行的版本号
+/// 数据库版本高于程序支持的版本时返回 SchemaTooNew 错误，不做任何修改
+pub async fn run_migrations(pool: &DbPool) -> Result<Vec<i64>, SqlxError> {
+    ensure_migrations_table(pool).await?;
+    let applied: Vec<i64> = applied_migrations(pool)
+        .await?
+        .into_iter()
+        .map(|(version, _, _)| version)
+        .collect();
+
+    let database_version = applied.iter().copied().max().unwrap_or(0);
+    if database_version > latest_version() {
+        return Err(SqlxError::Configuration(Box::new(SchemaTooNew {
+            database_version,
+            supported_version: latest_version(),
+        })));
+    }
+
+    let mut executed = Vec::new();
+    for migration in MIGRATIONS {
+        if applied.contains(&migration.version) {
+            continue;
+        }
+        info!("执行数据库迁移 {}: {}", migration.version, migration.name);
+        let mut tx = pool.begin().await?;
+        for step in migration.steps {
+            apply_step(&mut tx, step).await?;
+        }
+        tx.query("INSERT INTO schema_migrations (version, name, applied_at) VALUES (?, ?, ?)")
+            .bind(migration.version)
+            .bind(migration.name)
+            .bind(Utc::now())
+            .execute()
+            .await?;
+        tx.commit().await?;
+        executed.push(migration.version);
+    }
+
+    Ok(executed)
+}
+
+/// 所有迁移的执行状态，包括由更新版本的程序执行、当前程序不认识的迁移
+pub async fn migration_status(pool: &DbPool) -> Result<Vec<MigrationStatus>, SqlxError> {
+    ensure_migrations_table(pool).await?;
+    let applied = applied_migrations(pool).await?;
+
+    let mut status: Vec<MigrationStatus> = MIGRATIONS
+        .iter()
+        .map(|migration| MigrationStatus {
+            version: migration.version,
+            name: migration.name.to_string(),
+            applied_at: applied
+                .iter()
+                .find(|(version, _, _)| *version == migration.version)
+                .map(|(_, _, applied_at)| *applied_at),
+            known: true,
+        })
+        .collect();
+    for (version, name, applied_at) in applied {
+        if !MIGRATIONS.iter().any(|m| m.version == version) {
+            status.push(MigrationStatus {
+                version,
+                name,
+                applied_at: Some(applied_at),
+                known: false,
+            });
+        }
+    }
+    status.sort_by_key(|s| s.version);
+    Ok(status)
+}
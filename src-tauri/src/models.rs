@@ -12,26 +12,57 @@ pub struct Account {
     pub model: String,
     pub is_active: bool,
     pub custom_env_vars: String, // JSON 字符串存储自定义环境变量
+    pub permission_preset_id: Option<i64>, // 绑定的权限预设
+    pub token_status: Option<String>,      // 令牌校验结果：valid / invalid / error
+    pub token_checked_at: Option<DateTime<Utc>>,
+    pub token_error: Option<String>,
+    pub enabled: bool,                     // 停用的账号不出现在切换列表中
+    pub expires_at: Option<DateTime<Utc>>, // 到期时间
+    pub notes: String,                     // 备注
+    pub tags: String,                      // 逗号分隔的标签
+    pub last_used_at: Option<DateTime<Utc>>, // 最近一次切换时间
+    pub use_count: i64,                    // 累计切换次数
+    pub proxy_id: Option<i64>,             // 绑定的代理
+    pub credential_mode: String,           // 凭据模式：token / helper
+    pub api_key_helper: Option<String>,    // helper 模式下生成密钥的命令
+    pub api_key_helper_ttl_ms: Option<i64>, // helper 结果的缓存时间（毫秒）
+    pub custom_headers: String,            // JSON 数组存储自定义请求头
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Default)]
 pub struct CreateAccountRequest {
     pub name: String,
     pub token: String,
     pub base_url: String,
     pub model: String,
     pub custom_env_vars: Option<serde_json::Value>, // 自定义环境变量
+    #[serde(default)]
+    pub enabled: Option<bool>,
+    #[serde(default)]
+    pub expires_at: Option<String>, // 日期或日期时间，空字符串表示不设置
+    #[serde(default)]
+    pub notes: Option<String>,
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Default)]
 pub struct UpdateAccountRequest {
     pub name: Option<String>,
     pub token: Option<String>,
     pub base_url: Option<String>,
     pub model: Option<String>,
     pub custom_env_vars: Option<serde_json::Value>, // 自定义环境变量
+    #[serde(default)]
+    pub enabled: Option<bool>,
+    #[serde(default)]
+    pub expires_at: Option<String>, // 空字符串表示清除到期时间
+    #[serde(default)]
+    pub notes: Option<String>,
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
@@ -40,17 +71,21 @@ pub struct Directory {
     pub path: String,
     pub name: String,
     pub is_active: bool,
+    pub permission_preset_id: Option<i64>, // 绑定的权限预设
+    pub account_pool_id: Option<i64>,      // 绑定的账号池
+    pub relay_port: Option<i64>,           // 通过本地中转代理访问时的端口
+    pub proxy_id: Option<i64>,             // 绑定的代理
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Default)]
 pub struct CreateDirectoryRequest {
     pub path: String,
     pub name: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Default)]
 pub struct UpdateDirectoryRequest {
     pub path: Option<String>,
     pub name: Option<String>,
@@ -63,28 +98,33 @@ pub struct BaseUrl {
     pub url: String,
     pub description: Option<String>,
     pub api_key: String,
+    pub provider_type: String,    // 供应商类型：anthropic / bedrock / vertex
     pub is_default: bool,
     pub default_env_vars: String, // JSON 字符串存储默认环境变量
+    pub proxy_id: Option<i64>,    // 绑定的代理
+    pub custom_headers: String,   // JSON 数组存储自定义请求头
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Default)]
 pub struct CreateBaseUrlRequest {
     pub name: String,
     pub url: String,
     pub description: Option<String>,
     pub api_key: Option<String>,
+    pub provider_type: Option<String>,
     pub is_default: Option<bool>,
     pub default_env_vars: Option<serde_json::Value>, // 默认环境变量
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Default)]
 pub struct UpdateBaseUrlRequest {
     pub name: Option<String>,
     pub url: Option<String>,
     pub description: Option<String>,
     pub api_key: Option<String>,
+    pub provider_type: Option<String>,
     pub is_default: Option<bool>,
     pub default_env_vars: Option<serde_json::Value>, // 默认环境变量
 }
@@ -114,12 +154,18 @@ pub struct AccountsResponse {
     pub pagination: PaginationInfo,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct GetAccountsRequest {
     pub page: Option<i64>,
     pub per_page: Option<i64>,
     pub search: Option<String>,
     pub base_url: Option<String>,
+    #[serde(default)]
+    pub enabled: Option<bool>,
+    #[serde(default)]
+    pub tag: Option<String>,
+    #[serde(default)]
+    pub expiring_within_days: Option<i64>, // 仅返回该天数内到期（含已过期）的账号
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -168,6 +214,7 @@ pub struct WebDavConfig {
     pub sync_interval: i64,
     pub is_active: bool,
     pub last_sync_at: Option<DateTime<Utc>>,
+    pub proxy_id: Option<i64>, // 访问 WebDAV 时使用的代理
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -193,7 +240,214 @@ pub struct CreateSyncLogRequest {
 }
 
 // 环境变量辅助方法
+/// 导入适配器解析出的账号
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ImportedAccount {
+    pub name: String,
+    pub token: String,
+    pub base_url: String,
+    pub model: String,
+    #[serde(default)]
+    pub custom_env_vars: HashMap<String, String>,
+    #[serde(default)]
+    pub enabled: Option<bool>,
+    #[serde(default)]
+    pub expires_at: Option<String>, // 日期或日期时间
+    #[serde(default)]
+    pub notes: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub credential_mode: Option<String>,
+    #[serde(default)]
+    pub api_key_helper: Option<String>,
+    #[serde(default)]
+    pub api_key_helper_ttl_ms: Option<i64>,
+    #[serde(default)]
+    pub custom_headers: Vec<crate::custom_headers::CustomHeader>,
+}
+
+/// 待导入的 API 地址
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ImportedBaseUrl {
+    pub name: String,
+    pub url: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub api_key: Option<String>,
+    #[serde(default)]
+    pub is_default: bool,
+    #[serde(default)]
+    pub provider_type: Option<String>,
+    #[serde(default)]
+    pub default_env_vars: HashMap<String, String>,
+    #[serde(default)]
+    pub custom_headers: Vec<crate::custom_headers::CustomHeader>,
+}
+
+/// 待导入的目录
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ImportedDirectory {
+    pub name: String,
+    pub path: String,
+}
+
+/// 待导入的账号与目录绑定
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ImportedBinding {
+    pub account_name: String,
+    pub directory_path: String,
+}
+
+/// 一次导入的全部数据
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ImportBundle {
+    #[serde(default)]
+    pub accounts: Vec<ImportedAccount>,
+    #[serde(default)]
+    pub base_urls: Vec<ImportedBaseUrl>,
+    #[serde(default)]
+    pub directories: Vec<ImportedDirectory>,
+    #[serde(default)]
+    pub bindings: Vec<ImportedBinding>,
+}
+
+/// 导入项与已有记录不同的字段
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FieldDiff {
+    pub field: String,
+    pub current: String,
+    pub incoming: String,
+}
+
+/// 导入项与已有记录的比对结果
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImportItem {
+    pub kind: String,   // account / base_url / directory
+    pub index: usize,   // 在 ImportBundle 对应列表中的位置
+    pub name: String,
+    pub status: String, // new / identical / changed / duplicate
+    #[serde(default)]
+    pub existing_id: Option<i64>,
+    #[serde(default)]
+    pub matched_by: Option<String>, // name / url / token / path
+    #[serde(default)]
+    pub diffs: Vec<FieldDiff>,
+    #[serde(default)]
+    pub strategy: Option<String>, // 单独指定的处理方式，为空时使用全局策略
+}
+
+/// 导入预览：识别出的格式、待导入的数据及逐项比对结果
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImportPreview {
+    pub format: String,
+    pub bundle: ImportBundle,
+    pub items: Vec<ImportItem>,
+}
+
+/// 单个导入项的处理结果
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImportReportItem {
+    pub kind: String,
+    pub name: String,
+    pub action: String, // created / overwritten / merged / renamed / unchanged / skipped / failed
+    #[serde(default)]
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ImportReport {
+    pub created: usize,
+    pub updated: usize,
+    pub skipped: usize,
+    pub failed: usize,
+    pub items: Vec<ImportReportItem>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ExportResult {
+    pub accounts: usize,
+    pub files: Vec<String>,
+}
+
+/// 导出条件，均为空时导出全部账号
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ExportFilter {
+    #[serde(default)]
+    pub tag: Option<String>,
+    #[serde(default)]
+    pub base_url: Option<String>,
+    #[serde(default)]
+    pub account_ids: Option<Vec<i64>>,
+}
+
+/// 账号与目录的绑定，导出时以账号名称和目录路径记录
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct AccountBinding {
+    pub account_id: i64,
+    pub account_name: String,
+    pub directory_path: String,
+}
+
+/// 代理定义：可绑定到 API 地址、账号、目录和 WebDAV 配置，切换时写入 HTTP_PROXY 等环境变量
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct Proxy {
+    pub id: i64,
+    pub name: String,
+    pub url: String,              // http://、https://、socks5:// 或 socks5h:// 地址
+    pub no_proxy: Option<String>, // 逗号分隔的不走代理的主机
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub description: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct CreateProxyRequest {
+    pub name: String,
+    pub url: String,
+    pub no_proxy: Option<String>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct UpdateProxyRequest {
+    pub name: Option<String>,
+    pub url: Option<String>,
+    pub no_proxy: Option<String>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub description: Option<String>,
+}
+
 impl Account {
+    /// 获取标签列表
+    pub fn tag_list(&self) -> Vec<String> {
+        self.tags
+            .split(',')
+            .map(|t| t.trim())
+            .filter(|t| !t.is_empty())
+            .map(String::from)
+            .collect()
+    }
+
+    /// 距离到期的天数，已过期为负数；未设置到期时间时返回 None
+    pub fn days_until_expiry(&self) -> Option<i64> {
+        self.expires_at
+            .map(|expires_at| (expires_at - Utc::now()).num_days())
+    }
+
+    /// 是否已过到期时间
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .map(|expires_at| expires_at <= Utc::now())
+            .unwrap_or(false)
+    }
+
     /// 获取解析后的自定义环境变量
     /// 返回Option是为了区分"无环境变量"和"解析失败"
     pub fn get_custom_env_vars(&self) -> Option<HashMap<String, String>> {
@@ -227,6 +481,24 @@ impl BaseUrl {
     }
 }
 
+/// 合并 URL 默认环境变量与账号自定义环境变量，账号覆盖同名变量
+pub fn extra_env_vars(base_url: Option<&BaseUrl>, account: &Account) -> Vec<(String, String)> {
+    let mut extra_vars: Vec<(String, String)> = Vec::new();
+    let sources = [
+        base_url.and_then(|bu| bu.get_default_env_vars()),
+        account.get_custom_env_vars(),
+    ];
+    for vars in sources.into_iter().flatten() {
+        let mut vars: Vec<(String, String)> = vars.into_iter().collect();
+        vars.sort();
+        for (key, value) in vars {
+            extra_vars.retain(|(k, _)| k != &key);
+            extra_vars.push((key, value));
+        }
+    }
+    extra_vars
+}
+
 // 环境变量值类型推断
 pub fn parse_env_value(value: &str) -> serde_json::Value {
     use serde_json::json;
@@ -251,4 +523,354 @@ pub fn parse_env_value(value: &str) -> serde_json::Value {
 
     // 4. 默认作为字符串
     json!(value)
-}
\ No newline at end of file
+}
+// MCP 服务器模型
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct McpServer {
+    pub id: i64,
+    pub name: String,
+    pub transport: String, // stdio / sse / http
+    pub command: Option<String>,
+    pub args: String, // JSON 数组字符串
+    pub env: String,  // JSON 对象字符串
+    pub url: Option<String>,
+    pub description: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateMcpServerRequest {
+    pub name: String,
+    pub transport: String,
+    pub command: Option<String>,
+    pub args: Option<Vec<String>>,
+    pub env: Option<serde_json::Value>,
+    pub url: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateMcpServerRequest {
+    pub name: Option<String>,
+    pub transport: Option<String>,
+    pub command: Option<String>,
+    pub args: Option<Vec<String>>,
+    pub env: Option<serde_json::Value>,
+    pub url: Option<String>,
+    pub description: Option<String>,
+}
+
+// Hook 模型
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct Hook {
+    pub id: i64,
+    pub name: String,
+    pub event: String, // PreToolUse / PostToolUse / Stop 等
+    pub matcher: Option<String>,
+    pub command: String,
+    pub timeout: Option<i64>, // 秒
+    pub is_global: bool,      // 是否挂载到全局 Claude 配置
+    pub description: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateHookRequest {
+    pub name: String,
+    pub event: String,
+    pub matcher: Option<String>,
+    pub command: String,
+    pub timeout: Option<i64>,
+    pub is_global: Option<bool>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateHookRequest {
+    pub name: Option<String>,
+    pub event: Option<String>,
+    pub matcher: Option<String>,
+    pub command: Option<String>,
+    pub timeout: Option<i64>,
+    pub is_global: Option<bool>,
+    pub description: Option<String>,
+}
+
+// 权限预设模型
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct PermissionPreset {
+    pub id: i64,
+    pub name: String,
+    pub default_mode: String, // default / acceptEdits / plan / bypassPermissions
+    pub allow: String,        // JSON 数组字符串
+    pub deny: String,         // JSON 数组字符串
+    pub ask: String,          // JSON 数组字符串
+    pub description: Option<String>,
+    pub is_builtin: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreatePermissionPresetRequest {
+    pub name: String,
+    pub default_mode: String,
+    pub allow: Vec<String>,
+    pub deny: Vec<String>,
+    pub ask: Vec<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdatePermissionPresetRequest {
+    pub name: Option<String>,
+    pub default_mode: Option<String>,
+    pub allow: Option<Vec<String>>,
+    pub deny: Option<Vec<String>>,
+    pub ask: Option<Vec<String>>,
+    pub description: Option<String>,
+}
+
+// 斜杠命令 / 子代理定义模型
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct ClaudeDefinition {
+    pub id: i64,
+    pub kind: String, // command / agent
+    pub name: String, // 命令名称可包含 `/` 作为子目录
+    pub description: Option<String>,
+    pub content: String, // 完整 Markdown（含 front-matter）
+    pub is_default: bool, // 未单独设置的目录是否启用
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateClaudeDefinitionRequest {
+    pub kind: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub content: String,
+    pub is_default: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateClaudeDefinitionRequest {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub content: Option<String>,
+    pub is_default: Option<bool>,
+}
+
+// 配置漂移检测结果
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct DriftIssue {
+    pub kind: String, // missing_directory / missing_settings / invalid_settings / stale_token / env_changed / missing_asset
+    pub key: Option<String>,
+    pub expected: Option<String>,
+    pub actual: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DirectoryDrift {
+    pub directory_id: i64,
+    pub directory_name: String,
+    pub directory_path: String,
+    pub account_id: Option<i64>, // 最近一次切换到该目录的账号
+    pub account_name: Option<String>,
+    pub issues: Vec<DriftIssue>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct DiscoveryRoot {
+    pub id: i64,
+    pub path: String, // 支持 ~ 和 * 通配符，如 ~/code/*
+    pub created_at: DateTime<Utc>,
+}
+
+/// 扫描磁盘发现的 Claude 项目（包含 .claude 目录或 CLAUDE.md）
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DiscoveredProject {
+    pub path: String,
+    pub name: String,
+    pub directory_id: Option<i64>, // 已登记时对应的目录
+    pub token_key: Option<String>,
+    pub token: Option<String>,
+    pub base_url: Option<String>,
+    pub model: Option<String>,
+    pub account_id: Option<i64>, // 令牌已登记时对应的账号
+    pub account_name: Option<String>,
+    pub base_url_known: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct DiscoveryImportResult {
+    pub directories_created: usize,
+    pub accounts_created: usize,
+    pub base_urls_created: usize,
+    pub errors: Vec<String>,
+}
+
+/// API 地址的健康检查记录
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct EndpointCheck {
+    pub id: i64,
+    pub base_url_id: i64,
+    pub account_id: Option<i64>, // 带认证检查时使用的账号
+    pub connect_ms: Option<i64>, // TCP 连接耗时
+    pub tls_ms: Option<i64>,     // TLS 握手耗时，仅直连 https 地址时记录
+    pub latency_ms: Option<i64>, // HTTP 请求总耗时（含 TLS 握手）
+    pub status_code: Option<i64>,
+    pub auth_status_code: Option<i64>,
+    pub success: bool,
+    pub error: Option<String>,
+    pub checked_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct CreateEndpointCheckRequest {
+    pub base_url_id: i64,
+    pub account_id: Option<i64>,
+    pub connect_ms: Option<i64>,
+    pub tls_ms: Option<i64>,
+    pub latency_ms: Option<i64>,
+    pub status_code: Option<i64>,
+    pub auth_status_code: Option<i64>,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+/// API 地址的余额查询适配器：请求模板、剩余额度的 JSON 路径和单位
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct BalanceAdapter {
+    pub id: i64,
+    pub base_url_id: i64,
+    pub method: String,                // GET 或 POST
+    pub url_template: String,          // 支持 {base_url} 和 {token} 占位符
+    pub headers: Option<String>,       // JSON 对象，值支持同样的占位符
+    pub body_template: Option<String>, // POST 请求体
+    pub balance_path: String,          // 剩余额度的 JSON 路径，如 data.balance
+    pub divisor: f64,                  // 原始值除以该数得到显示的余额
+    pub unit: String,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SaveBalanceAdapterRequest {
+    pub base_url_id: i64,
+    pub method: Option<String>,
+    pub url_template: String,
+    pub headers: Option<String>,
+    pub body_template: Option<String>,
+    pub balance_path: String,
+    pub divisor: Option<f64>,
+    pub unit: Option<String>,
+    pub enabled: Option<bool>,
+}
+
+/// 账号余额的缓存
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct AccountBalance {
+    pub account_id: i64,
+    pub balance: Option<f64>,
+    pub unit: Option<String>,
+    pub error: Option<String>,
+    pub fetched_at: DateTime<Utc>,
+}
+
+/// 账号池：一组可相互替换的账号，目录绑定账号池后在当前账号失效时自动切换
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct AccountPool {
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateAccountPoolRequest {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+/// 账号池成员：优先使用 priority 数值小的账号，同一优先级内按 weight 加权选择
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct AccountPoolMember {
+    pub id: i64,
+    pub pool_id: i64,
+    pub account_id: i64,
+    pub weight: i64,
+    pub priority: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SaveAccountPoolMemberRequest {
+    pub pool_id: i64,
+    pub account_id: i64,
+    pub weight: Option<i64>,
+    pub priority: Option<i64>,
+}
+
+/// 账号池故障切换的结果，to_account 为空表示池中没有可用账号
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PoolFailover {
+    pub pool_id: i64,
+    pub directory_id: i64,
+    pub directory_name: String,
+    pub pool_name: String,
+    pub from_account: String,
+    pub to_account: Option<String>,
+    pub error: Option<String>,
+}
+
+/// 已保存的故障切换记录，名称为切换时的快照
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct PoolFailoverRecord {
+    pub id: i64,
+    pub pool_id: i64,
+    pub directory_id: i64,
+    pub pool_name: String,
+    pub directory_name: String,
+    pub from_account: String,
+    pub to_account: Option<String>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 本地中转代理按账号统计的请求次数和延迟
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct RelayStat {
+    pub account_id: i64,
+    pub request_count: i64,
+    pub failure_count: i64,
+    pub total_latency_ms: i64,
+    pub last_status: Option<i64>,
+    pub last_latency_ms: Option<i64>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+/// 本地中转代理的运行状态
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RelayStatus {
+    pub running: bool,
+    pub port: u16,
+}
+
+/// 启动时提示即将到期账号的提前天数
+pub const EXPIRY_WARNING_DAYS: i64 = 7;
+
+/// 规范化标签：拆分逗号、去除空白与重复项后以逗号拼接
+pub fn normalize_tags(tags: &[String]) -> String {
+    let mut result: Vec<&str> = Vec::new();
+    for tag in tags.iter().flat_map(|t| t.split(',')).map(str::trim) {
+        if !tag.is_empty() && !result.contains(&tag) {
+            result.push(tag);
+        }
+    }
+    result.join(",")
+}
@@ -0,0 +1,274 @@
+use crate::claude_config::ClaudeConfigManager;
+use crate::database::Database;
+use crate::drift;
+use crate::health;
+use crate::models::*;
+use serde_json::json;
+use sqlx::Error as SqlxError;
+use std::collections::HashSet;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 账号是否可用：已启用且未过期，令牌未被标记为无效，且所在 API 地址最近一次健康检查没有失败
+/// 校验出错（网络超时等）不代表令牌失效，不影响可用性
+pub fn is_available(account: &Account, failing_base_urls: &HashSet<String>) -> bool {
+    account.enabled
+        && !account.is_expired()
+        && account.token_status.as_deref() != Some(health::TOKEN_INVALID)
+        && !failing_base_urls.contains(&account.base_url)
+}
+
+/// 从候选账号中选择一个：只考虑优先级最高（priority 最小）的一组，组内按权重加权选择
+/// seed 决定加权选择的落点，相同的输入总是得到相同的结果
+pub fn pick_account<'a>(
+    candidates: &[(&AccountPoolMember, &'a Account)],
+    seed: u64,
+) -> Option<&'a Account> {
+    let best = candidates.iter().map(|(m, _)| m.priority).min()?;
+    let group: Vec<&(&AccountPoolMember, &Account)> = candidates
+        .iter()
+        .filter(|(m, _)| m.priority == best)
+        .collect();
+
+    let total: i64 = group.iter().map(|(m, _)| m.weight.max(0)).sum();
+    if total == 0 {
+        return group.first().map(|(_, a)| *a);
+    }
+
+    let mut point = (seed % total as u64) as i64;
+    for (member, account) in &group {
+        let weight = member.weight.max(0);
+        if point < weight {
+            return Some(*account);
+        }
+        point -= weight;
+    }
+    group.last().map(|(_, a)| *a)
+}
+
+fn random_seed() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0)
+}
+
+/// 最近一次健康检查失败的 API 地址
+async fn failing_base_urls(db: &Database) -> Result<HashSet<String>, SqlxError> {
+    let base_urls = db.get_base_urls().await?;
+    Ok(db
+        .get_latest_endpoint_checks()
+        .await?
+        .into_iter()
+        .filter(|check| !check.success)
+        .filter_map(|check| {
+            base_urls
+                .iter()
+                .find(|bu| bu.id == check.base_url_id)
+                .map(|bu| bu.url.clone())
+        })
+        .collect())
+}
+
+/// 在账号池中选择一个可用账号，exclude 中的账号不参与选择
+pub async fn select_pool_account(
+    db: &Database,
+    pool_id: i64,
+    exclude: &[i64],
+) -> Result<Option<Account>, SqlxError> {
+    let members = db.get_account_pool_members(Some(pool_id)).await?;
+    let accounts = db.get_all_accounts().await?;
+    let failing = failing_base_urls(db).await?;
+
+    let candidates: Vec<(&AccountPoolMember, &Account)> = members
+        .iter()
+        .filter(|m| !exclude.contains(&m.account_id))
+        .filter_map(|m| {
+            accounts
+                .iter()
+                .find(|a| a.id == m.account_id)
+                .map(|a| (m, a))
+        })
+        .filter(|(_, a)| is_available(a, &failing))
+        .collect();
+
+    Ok(pick_account(&candidates, random_seed()).cloned())
+}
+
+/// 将目录切换到指定账号：更新数据库中的绑定，并把账号的环境变量写入目录配置
+pub async fn apply_account(
+    db: &Database,
+    directory: &Directory,
+    account: &Account,
+) -> anyhow::Result<()> {
+    let previous_token_key = drift::expected_state(db, directory)
+        .await?
+        .map(|state| state.token_key);
+
+    db.switch_account(SwitchAccountRequest {
+        account_id: account.id,
+        directory_id: directory.id,
+    })
+    .await?;
+
+    let expected = drift::expected_state(db, directory)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("目录 {} 没有绑定账号", directory.name))?;
+    drift::fix_drift(directory, &expected)?;
+
+    let config_manager = ClaudeConfigManager::new(directory.path.clone());
+    config_manager.merge_env(&[("USER_NAME".to_string(), json!(account.name))])?;
+    // 新账号使用不同的令牌变量名时移除旧的令牌
+    if let Some(previous) = previous_token_key.filter(|key| *key != expected.token_key) {
+        config_manager.remove_env(&[previous.as_str()])?;
+    }
+
+    Ok(())
+}
+
+/// 账号失效后，把正在使用这些账号且绑定了账号池的目录切换到池中的下一个可用账号
+pub async fn failover_accounts(
+    db: &Database,
+    failing: &[i64],
+) -> Result<Vec<PoolFailover>, SqlxError> {
+    let mut results = Vec::new();
+    if failing.is_empty() {
+        return Ok(results);
+    }
+
+    for directory in db.get_directories().await? {
+        let pool_id = match directory.account_pool_id {
+            Some(pool_id) => pool_id,
+            None => continue,
+        };
+        let current = match db.get_directory_bound_account(directory.id).await? {
+            Some(account) if failing.contains(&account.id) => account,
+            _ => continue,
+        };
+        let pool = db.get_account_pool(pool_id).await?;
+
+        let mut result = PoolFailover {
+            pool_id,
+            directory_id: directory.id,
+            directory_name: directory.name.clone(),
+            pool_name: pool.name.clone(),
+            from_account: current.name.clone(),
+            to_account: None,
+            error: None,
+        };
+
+        match select_pool_account(db, pool_id, failing).await? {
+            Some(next) => match apply_account(db, &directory, &next).await {
+                Ok(_) => {
+                    tracing::warn!(
+                        "账号池 {}：目录 {} 的账号 {} 不可用，已切换到 {}",
+                        pool.name,
+                        directory.name,
+                        current.name,
+                        next.name
+                    );
+                    result.to_account = Some(next.name);
+                }
+                Err(e) => {
+                    tracing::error!(
+                        "账号池 {}：目录 {} 切换到账号 {} 失败: {}",
+                        pool.name,
+                        directory.name,
+                        next.name,
+                        e
+                    );
+                    result.error = Some(e.to_string());
+                }
+            },
+            None => {
+                tracing::error!(
+                    "账号池 {}：目录 {} 的账号 {} 不可用，且池中没有其他可用账号",
+                    pool.name,
+                    directory.name,
+                    current.name
+                );
+            }
+        }
+        // 记录失败不影响已完成的切换
+        if let Err(e) = db.create_pool_failover(&result).await {
+            tracing::warn!("保存账号池故障切换记录失败: {}", e);
+        }
+        results.push(result);
+    }
+
+    Ok(results)
+}
+
+/// 健康检查失败后触发故障切换：指定了账号时只切换该账号，否则该 API 地址下的所有账号都视为失效
+pub async fn failover_after_check(
+    db: &Database,
+    check: &EndpointCheck,
+) -> Result<Vec<PoolFailover>, SqlxError> {
+    if check.success {
+        return Ok(Vec::new());
+    }
+
+    let failing: Vec<i64> = match check.account_id {
+        Some(account_id) => vec![account_id],
+        None => {
+            let base_url = db.get_base_url(check.base_url_id).await?;
+            db.get_all_accounts()
+                .await?
+                .into_iter()
+                .filter(|a| a.base_url == base_url.url)
+                .map(|a| a.id)
+                .collect()
+        }
+    };
+    failover_accounts(db, &failing).await
+}
+
+/// 绑定目录到账号池（None 表示取消绑定），目录当前的账号不在池中或不可用时立即切换到池中的可用账号
+pub async fn bind_directory(
+    db: &Database,
+    directory_id: i64,
+    pool_id: Option<i64>,
+) -> Result<Option<PoolFailover>, SqlxError> {
+    db.set_directory_account_pool(directory_id, pool_id).await?;
+    let pool_id = match pool_id {
+        Some(pool_id) => pool_id,
+        None => return Ok(None),
+    };
+
+    let directory = db.get_directory(directory_id).await?;
+    let pool = db.get_account_pool(pool_id).await?;
+    let current = db.get_directory_bound_account(directory_id).await?;
+    let members = db.get_account_pool_members(Some(pool_id)).await?;
+    let failing = failing_base_urls(db).await?;
+
+    if let Some(current) = &current {
+        if members.iter().any(|m| m.account_id == current.id) && is_available(current, &failing) {
+            return Ok(None);
+        }
+    }
+
+    let mut result = PoolFailover {
+        pool_id,
+        directory_id,
+        directory_name: directory.name.clone(),
+        pool_name: pool.name.clone(),
+        from_account: current.map(|a| a.name).unwrap_or_default(),
+        to_account: None,
+        error: None,
+    };
+    match select_pool_account(db, pool_id, &[]).await? {
+        Some(next) => match apply_account(db, &directory, &next).await {
+            Ok(_) => {
+                tracing::info!(
+                    "目录 {} 绑定账号池 {}，使用账号 {}",
+                    directory.name,
+                    pool.name,
+                    next.name
+                );
+                result.to_account = Some(next.name);
+            }
+            Err(e) => result.error = Some(e.to_string()),
+        },
+        None => tracing::warn!("账号池 {} 中没有可用账号", pool.name),
+    }
+    Ok(Some(result))
+}
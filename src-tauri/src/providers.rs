@@ -0,0 +1,249 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+/// 默认供应商：直接访问 Anthropic API 或兼容的中转服务
+pub const DEFAULT_PROVIDER: &str = "anthropic";
+
+/// 环境变量说明
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderEnvVar {
+    pub key: &'static str,
+    pub description: &'static str,
+}
+
+/// 账号令牌写入的位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenTarget {
+    /// 写入 URL 上配置的 API KEY 变量（ANTHROPIC_API_KEY / ANTHROPIC_AUTH_TOKEN）
+    ApiKeyName,
+    /// 写入固定的环境变量，令牌可为空
+    Env(&'static str),
+    /// 不使用令牌，凭据由环境变量中的凭据文件提供
+    Unused,
+}
+
+/// 供应商的环境变量定义
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderSchema {
+    pub id: &'static str,
+    pub name: &'static str,
+    /// 启用该供应商的开关变量，写入值为 1
+    pub flag: Option<&'static str>,
+    /// URL 地址写入的变量
+    pub base_url_env: &'static str,
+    pub token: TokenTarget,
+    pub required: &'static [ProviderEnvVar],
+    pub optional: &'static [ProviderEnvVar],
+}
+
+pub const PROVIDERS: [ProviderSchema; 3] = [
+    ProviderSchema {
+        id: "anthropic",
+        name: "Anthropic API",
+        flag: None,
+        base_url_env: "ANTHROPIC_BASE_URL",
+        token: TokenTarget::ApiKeyName,
+        required: &[],
+        optional: &[],
+    },
+    ProviderSchema {
+        id: "bedrock",
+        name: "Amazon Bedrock",
+        flag: Some("CLAUDE_CODE_USE_BEDROCK"),
+        base_url_env: "ANTHROPIC_BEDROCK_BASE_URL",
+        token: TokenTarget::Env("AWS_BEARER_TOKEN_BEDROCK"),
+        required: &[ProviderEnvVar {
+            key: "AWS_REGION",
+            description: "AWS 区域，如 us-east-1",
+        }],
+        optional: &[
+            ProviderEnvVar {
+                key: "AWS_PROFILE",
+                description: "AWS 配置文件中的 profile 名称",
+            },
+            ProviderEnvVar {
+                key: "AWS_SHARED_CREDENTIALS_FILE",
+                description: "AWS 凭据文件路径",
+            },
+            ProviderEnvVar {
+                key: "AWS_ACCESS_KEY_ID",
+                description: "AWS 访问密钥 ID",
+            },
+            ProviderEnvVar {
+                key: "AWS_SECRET_ACCESS_KEY",
+                description: "AWS 访问密钥",
+            },
+            ProviderEnvVar {
+                key: "AWS_SESSION_TOKEN",
+                description: "AWS 临时会话令牌",
+            },
+            ProviderEnvVar {
+                key: "ANTHROPIC_SMALL_FAST_MODEL_AWS_REGION",
+                description: "小模型使用的 AWS 区域",
+            },
+        ],
+    },
+    ProviderSchema {
+        id: "vertex",
+        name: "Google Vertex AI",
+        flag: Some("CLAUDE_CODE_USE_VERTEX"),
+        base_url_env: "ANTHROPIC_VERTEX_BASE_URL",
+        token: TokenTarget::Unused,
+        required: &[
+            ProviderEnvVar {
+                key: "CLOUD_ML_REGION",
+                description: "Vertex AI 区域，如 us-east5 或 global",
+            },
+            ProviderEnvVar {
+                key: "ANTHROPIC_VERTEX_PROJECT_ID",
+                description: "GCP 项目 ID",
+            },
+        ],
+        optional: &[ProviderEnvVar {
+            key: "GOOGLE_APPLICATION_CREDENTIALS",
+            description: "服务账号凭据文件路径",
+        }],
+    },
+];
+
+/// 按 ID 查找供应商
+pub fn provider_schema(provider_type: &str) -> Option<&'static ProviderSchema> {
+    PROVIDERS.iter().find(|p| p.id == provider_type)
+}
+
+/// 查找供应商，未知类型按默认供应商处理
+pub fn schema_or_default(provider_type: &str) -> &'static ProviderSchema {
+    provider_schema(provider_type).unwrap_or(&PROVIDERS[0])
+}
+
+pub fn validate_provider_type(provider_type: &str) -> Result<(), String> {
+    if provider_schema(provider_type).is_some() {
+        Ok(())
+    } else {
+        Err(format!(
+            "不支持的供应商类型 '{}'，可选值: {}",
+            provider_type,
+            PROVIDERS
+                .iter()
+                .map(|p| p.id)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))
+    }
+}
+
+impl ProviderSchema {
+    /// 账号是否必须填写令牌
+    pub fn token_required(&self) -> bool {
+        self.token == TokenTarget::ApiKeyName
+    }
+
+    /// 账号令牌写入的变量，不使用令牌时返回 None
+    pub fn token_env<'a>(&self, api_key_name: &'a str) -> Option<&'a str> {
+        match self.token {
+            TokenTarget::ApiKeyName => Some(api_key_name),
+            TokenTarget::Env(key) => Some(key),
+            TokenTarget::Unused => None,
+        }
+    }
+
+    /// 该供应商定义的全部变量（必填在前）
+    pub fn env_vars(&self) -> impl Iterator<Item = &'static ProviderEnvVar> {
+        self.required.iter().chain(self.optional.iter())
+    }
+}
+
+/// 校验账号是否满足供应商要求：env 为 URL 默认环境变量与账号自定义环境变量合并后的结果
+pub fn validate_provider_env(
+    provider_type: &str,
+    token: &str,
+    env: &HashMap<String, String>,
+) -> Result<(), String> {
+    validate_provider_type(provider_type)?;
+    let schema = schema_or_default(provider_type);
+
+    if schema.token_required() && token.trim().is_empty() {
+        return Err(format!("{} 账号必须填写令牌", schema.name));
+    }
+
+    let missing: Vec<&str> = schema
+        .required
+        .iter()
+        .filter(|var| {
+            env.get(var.key)
+                .map(|value| value.trim().is_empty())
+                .unwrap_or(true)
+        })
+        .map(|var| var.key)
+        .collect();
+    if !missing.is_empty() {
+        return Err(format!(
+            "{} 账号缺少必填环境变量: {}",
+            schema.name,
+            missing.join(", ")
+        ));
+    }
+
+    Ok(())
+}
+
+/// 供应商需要写入的开关、地址和令牌变量
+pub fn provider_env(
+    provider_type: &str,
+    api_key_name: &str,
+    token: &str,
+    base_url: &str,
+) -> Vec<(String, String)> {
+    let schema = schema_or_default(provider_type);
+    let mut vars = Vec::new();
+
+    if let Some(flag) = schema.flag {
+        vars.push((flag.to_string(), "1".to_string()));
+    }
+    if !base_url.trim().is_empty() {
+        vars.push((schema.base_url_env.to_string(), base_url.to_string()));
+    }
+    // 只有 URL 配置的 API KEY 变量必须写入，其他供应商的令牌为空时不写
+    if let Some(key) = schema.token_env(api_key_name) {
+        if schema.token_required() || !token.trim().is_empty() {
+            vars.push((key.to_string(), token.to_string()));
+        }
+    }
+
+    vars
+}
+
+/// 把供应商的开关、地址和令牌写入 env，并移除其他供应商遗留的对应变量
+pub fn apply_provider_env(
+    env: &mut Map<String, Value>,
+    provider_type: &str,
+    api_key_name: &str,
+    token: &str,
+    base_url: &str,
+) {
+    let schema = schema_or_default(provider_type);
+
+    for other in PROVIDERS.iter().filter(|p| p.id != schema.id) {
+        if let Some(flag) = other.flag {
+            env.remove(flag);
+        }
+        env.remove(other.base_url_env);
+        match other.token {
+            TokenTarget::ApiKeyName => {
+                env.remove("ANTHROPIC_API_KEY");
+                env.remove("ANTHROPIC_AUTH_TOKEN");
+                env.remove(api_key_name);
+            }
+            TokenTarget::Env(key) => {
+                env.remove(key);
+            }
+            TokenTarget::Unused => {}
+        }
+    }
+
+    for (key, value) in provider_env(provider_type, api_key_name, token, base_url) {
+        env.insert(key, Value::String(value));
+    }
+}
@@ -0,0 +1,137 @@
+use crate::models::Proxy;
+use reqwest::Url;
+use serde_json::{json, Value};
+use std::time::{Duration, Instant};
+
+/// 支持的代理协议
+pub const PROXY_SCHEMES: [&str; 4] = ["http", "https", "socks5", "socks5h"];
+
+/// 绑定代理后由本工具写入 settings.local.json 的环境变量
+pub const PROXY_ENV_KEYS: [&str; 3] = ["HTTP_PROXY", "HTTPS_PROXY", "NO_PROXY"];
+
+/// 测试代理连通性时默认访问的地址
+pub const DEFAULT_TEST_URL: &str = "https://api.anthropic.com";
+
+/// SOCKS 代理未写端口时使用的默认端口
+const DEFAULT_SOCKS_PORT: u16 = 1080;
+
+/// 校验代理地址：协议必须是 http / https / socks5 / socks5h，且包含主机
+pub fn validate_proxy_url(url: &str) -> Result<(), String> {
+    let parsed = Url::parse(url.trim()).map_err(|e| format!("无效的代理地址: {}", e))?;
+    if !PROXY_SCHEMES.contains(&parsed.scheme()) {
+        return Err(format!(
+            "不支持的代理协议 '{}'，可选值: {}",
+            parsed.scheme(),
+            PROXY_SCHEMES.join(", ")
+        ));
+    }
+    if parsed.host_str().map(|h| h.is_empty()).unwrap_or(true) {
+        return Err("代理地址缺少主机".to_string());
+    }
+    Ok(())
+}
+
+impl Proxy {
+    /// 代理地址，设置了用户名时把用户名和密码写入地址中
+    pub fn url_with_auth(&self) -> String {
+        let username = self.username.as_deref().unwrap_or("");
+        if username.is_empty() {
+            return self.url.clone();
+        }
+        match Url::parse(&self.url) {
+            Ok(mut url) => {
+                let _ = url.set_username(username);
+                let _ = url.set_password(self.password.as_deref().filter(|p| !p.is_empty()));
+                url.to_string()
+            }
+            Err(_) => self.url.clone(),
+        }
+    }
+
+    /// 代理服务器的主机和端口
+    pub fn address(&self) -> Option<(String, u16)> {
+        let url = Url::parse(&self.url).ok()?;
+        let host = url.host_str()?.to_string();
+        let port = url.port_or_known_default().or_else(|| {
+            url.scheme()
+                .starts_with("socks")
+                .then_some(DEFAULT_SOCKS_PORT)
+        })?;
+        Some((host, port))
+    }
+
+    /// 切换时写入的代理环境变量，NO_PROXY 为空时不写入
+    pub fn env_vars(&self) -> Vec<(&'static str, String)> {
+        let url = self.url_with_auth();
+        let mut vars = vec![("HTTP_PROXY", url.clone()), ("HTTPS_PROXY", url)];
+        if let Some(no_proxy) = self.no_proxy.as_deref().map(str::trim) {
+            if !no_proxy.is_empty() {
+                vars.push(("NO_PROXY", no_proxy.to_string()));
+            }
+        }
+        vars
+    }
+}
+
+/// 把代理写入 settings 的 env 段，覆盖 Claude 配置中原有的代理变量
+pub fn apply_proxy_to_settings(settings: &mut Value, proxy: &Proxy) {
+    if !settings.is_object() {
+        *settings = json!({});
+    }
+    let settings_obj = settings.as_object_mut().unwrap();
+    if !settings_obj
+        .get("env")
+        .map(Value::is_object)
+        .unwrap_or(false)
+    {
+        settings_obj.insert("env".to_string(), json!({}));
+    }
+    let env_obj = settings_obj
+        .get_mut("env")
+        .and_then(Value::as_object_mut)
+        .unwrap();
+
+    for key in PROXY_ENV_KEYS {
+        env_obj.remove(key);
+    }
+    for (key, value) in proxy.env_vars() {
+        env_obj.insert(key.to_string(), Value::String(value));
+    }
+}
+
+/// 创建 HTTP 客户端构建器，指定代理时所有请求都经过该代理（NO_PROXY 中的主机除外）
+pub fn client_builder(proxy: Option<&Proxy>) -> Result<reqwest::ClientBuilder, String> {
+    let mut builder = reqwest::Client::builder();
+    if let Some(proxy) = proxy {
+        validate_proxy_url(&proxy.url)?;
+        let no_proxy = proxy
+            .no_proxy
+            .as_deref()
+            .and_then(reqwest::NoProxy::from_string);
+        let reqwest_proxy = reqwest::Proxy::all(proxy.url_with_auth())
+            .map_err(|e| format!("代理 {} 不可用: {}", proxy.name, e))?
+            .no_proxy(no_proxy);
+        builder = builder.proxy(reqwest_proxy);
+    }
+    Ok(builder)
+}
+
+/// 创建带超时的 HTTP 客户端
+pub fn build_client(proxy: Option<&Proxy>, timeout: Duration) -> Result<reqwest::Client, String> {
+    client_builder(proxy)?
+        .timeout(timeout)
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+/// 通过代理访问目标地址，收到任意 HTTP 响应即视为连通，返回耗时（毫秒）
+pub async fn test_proxy(proxy: &Proxy, target: &str, timeout: Duration) -> Result<i64, String> {
+    let client = build_client(Some(proxy), timeout)?;
+    let start = Instant::now();
+    client
+        .get(target)
+        .send()
+        .await
+        .map_err(|e| format!("请求失败: {}", e))?;
+    Ok(start.elapsed().as_millis() as i64)
+}
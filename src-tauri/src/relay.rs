@@ -0,0 +1,389 @@
+use crate::custom_headers::{self, CustomHeader};
+use crate::database::Database;
+use crate::health;
+use crate::key_helper;
+use crate::models::Proxy;
+use crate::pools;
+use crate::proxy;
+use bytes::Bytes;
+use http_body_util::{combinators::UnsyncBoxBody, BodyExt, Full, StreamBody};
+use hyper::body::{Frame, Incoming};
+use hyper::header::HeaderMap;
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use serde_json::json;
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::net::TcpListener;
+use tokio::sync::Mutex;
+
+/// 本地中转代理的默认端口
+pub const DEFAULT_PORT: u16 = 15722;
+
+/// 通过中转代理访问的目录写入的占位令牌，真实令牌由代理注入
+pub const RELAY_TOKEN: &str = "claude-config-relay";
+
+/// 不转发给上游的请求头：逐跳头，以及由代理重新注入的认证头
+const SKIPPED_REQUEST_HEADERS: &[&str] = &[
+    "host",
+    "connection",
+    "keep-alive",
+    "proxy-connection",
+    "transfer-encoding",
+    "upgrade",
+    "content-length",
+    "x-api-key",
+    "authorization",
+];
+
+/// 不回传给客户端的响应头，响应体由 hyper 重新分块
+const SKIPPED_RESPONSE_HEADERS: &[&str] = &[
+    "connection",
+    "keep-alive",
+    "transfer-encoding",
+    "content-length",
+];
+
+type RelayBody = UnsyncBoxBody<Bytes, reqwest::Error>;
+
+/// 目录通过中转代理访问账号池时使用的 ANTHROPIC_BASE_URL
+pub fn relay_base_url(port: u16, pool_id: i64) -> String {
+    format!("http://127.0.0.1:{}/pools/{}", port, pool_id)
+}
+
+/// 解析请求路径 /pools/{账号池ID}/...，返回账号池 ID 和转发到上游的路径（含查询参数）
+pub fn parse_route(path_and_query: &str) -> Option<(i64, String)> {
+    let rest = path_and_query.strip_prefix("/pools/")?;
+    let end = rest.find(['/', '?']).unwrap_or(rest.len());
+    let pool_id = rest[..end].parse().ok()?;
+    let forward = &rest[end..];
+    if forward.starts_with('/') {
+        Some((pool_id, forward.to_string()))
+    } else {
+        Some((pool_id, format!("/{}", forward)))
+    }
+}
+
+/// 上游返回这些状态码时换用池中的下一个账号重试
+pub fn should_failover(status: u16) -> bool {
+    status == 401 || status == 429 || status >= 500
+}
+
+/// 构造转发给上游的请求头：去掉客户端的认证头，加入所选账号的自定义请求头并注入其令牌
+pub fn forward_headers(
+    incoming: &HeaderMap,
+    extra_headers: &[CustomHeader],
+    token_key: &str,
+    token: &str,
+) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    for (name, value) in incoming {
+        if !SKIPPED_REQUEST_HEADERS.contains(&name.as_str()) {
+            headers.append(name.clone(), value.clone());
+        }
+    }
+    for (name, value) in custom_headers::header_map(extra_headers).iter() {
+        headers.insert(name.clone(), value.clone());
+    }
+    for (name, value) in health::auth_headers(token_key, token).iter() {
+        // 保留客户端指定的 API 版本
+        if name == "anthropic-version" && headers.contains_key(name) {
+            continue;
+        }
+        headers.insert(name.clone(), value.clone());
+    }
+    headers
+}
+
+/// 只接受本机 Claude Code 的请求：必须携带占位令牌，且不能是浏览器页面发起的请求
+/// 返回拒绝时的状态码和原因
+pub fn check_client(headers: &HeaderMap) -> Result<(), (StatusCode, &'static str)> {
+    if headers.contains_key("origin") {
+        return Err((StatusCode::FORBIDDEN, "中转代理不接受浏览器发起的请求"));
+    }
+    let api_key = headers.get("x-api-key").and_then(|v| v.to_str().ok());
+    let bearer = headers
+        .get("authorization")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    if api_key == Some(RELAY_TOKEN) || bearer == Some(RELAY_TOKEN) {
+        Ok(())
+    } else {
+        Err((StatusCode::UNAUTHORIZED, "缺少中转代理的占位令牌"))
+    }
+}
+
+fn error_response(status: StatusCode, message: &str) -> Response<RelayBody> {
+    let body = json!({
+        "type": "error",
+        "error": { "type": "relay_error", "message": message }
+    });
+    let mut response = Response::new(
+        Full::new(Bytes::from(body.to_string()))
+            .map_err(|never| match never {})
+            .boxed_unsync(),
+    );
+    *response.status_mut() = status;
+    response.headers_mut().insert(
+        hyper::header::CONTENT_TYPE,
+        hyper::header::HeaderValue::from_static("application/json"),
+    );
+    response
+}
+
+/// 把上游响应原样回传，响应体逐块转发，SSE 流不做任何修改
+fn stream_response(upstream: reqwest::Response) -> Response<RelayBody> {
+    let status = upstream.status();
+    let mut headers = HeaderMap::new();
+    for (name, value) in upstream.headers() {
+        if !SKIPPED_RESPONSE_HEADERS.contains(&name.as_str()) {
+            headers.append(name.clone(), value.clone());
+        }
+    }
+
+    let chunks = futures_util::stream::unfold(Some(upstream), |state| async move {
+        let mut upstream = state?;
+        match upstream.chunk().await {
+            Ok(Some(chunk)) => Some((Ok(Frame::data(chunk)), Some(upstream))),
+            Ok(None) => None,
+            Err(e) => Some((Err(e), None)),
+        }
+    });
+
+    let mut response = Response::new(StreamBody::new(chunks).boxed_unsync());
+    *response.status_mut() = status;
+    *response.headers_mut() = headers;
+    response
+}
+
+/// 创建转发用的 HTTP 客户端：指定代理时经过该代理，关闭自动解压使响应体按原样回传
+fn relay_client(proxy: Option<&Proxy>) -> Result<reqwest::Client, String> {
+    proxy::client_builder(proxy)?
+        .connect_timeout(health::PROBE_TIMEOUT)
+        .no_gzip()
+        .no_brotli()
+        .no_deflate()
+        .no_zstd()
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+async fn record(db: &Arc<Mutex<Database>>, account_id: i64, status: Option<u16>, latency_ms: i64) {
+    let success = status.map(|s| !should_failover(s)).unwrap_or(false);
+    let db_lock = db.lock().await;
+    if let Err(e) = db_lock
+        .record_relay_request(account_id, status.map(i64::from), latency_ms, success)
+        .await
+    {
+        tracing::warn!("记录中转代理统计失败: {}", e);
+    }
+}
+
+async fn handle(
+    db: Arc<Mutex<Database>>,
+    client: reqwest::Client,
+    request: Request<Incoming>,
+) -> Result<Response<RelayBody>, Infallible> {
+    if let Err((status, message)) = check_client(request.headers()) {
+        return Ok(error_response(status, message));
+    }
+    let path = request
+        .uri()
+        .path_and_query()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_default();
+    let (pool_id, forward_path) = match parse_route(&path) {
+        Some(route) => route,
+        None => {
+            return Ok(error_response(
+                StatusCode::NOT_FOUND,
+                "请求路径应为 /pools/{账号池ID}/...",
+            ))
+        }
+    };
+
+    // 故障切换时需要重发请求体，先完整读取
+    let (parts, body) = request.into_parts();
+    let body = match body.collect().await {
+        Ok(collected) => collected.to_bytes(),
+        Err(e) => return Ok(error_response(StatusCode::BAD_REQUEST, &e.to_string())),
+    };
+
+    let mut tried = Vec::new();
+    let mut last_failure: Option<reqwest::Response> = None;
+    let mut last_error: Option<String> = None;
+
+    loop {
+        let db_lock = db.lock().await;
+        let account = match pools::select_pool_account(&db_lock, pool_id, &tried).await {
+            Ok(Some(account)) => account,
+            Ok(None) => break,
+            Err(e) => {
+                return Ok(error_response(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    &e.to_string(),
+                ))
+            }
+        };
+        let base_url = db_lock
+            .get_base_urls()
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .find(|bu| bu.url == account.base_url);
+        let token_key = base_url
+            .as_ref()
+            .map(|bu| bu.api_key.clone())
+            .unwrap_or_else(|| "ANTHROPIC_API_KEY".to_string());
+        // 与直连时一致：使用 API 地址和账号的自定义请求头，以及账号或 API 地址绑定的代理
+        let headers = custom_headers::effective_headers(base_url.as_ref(), Some(&account));
+        let bound_proxy = db_lock
+            .get_proxy_for_switch(account.id, None)
+            .await
+            .unwrap_or_else(|e| {
+                tracing::warn!("获取账号 {} 的代理失败: {}", account.name, e);
+                None
+            });
+        // 转发期间不持有数据库锁
+        drop(db_lock);
+        tried.push(account.id);
+
+        // 辅助命令模式的账号先执行命令获取密钥
+        let token = match key_helper::resolve_token(&account).await {
+            Ok(token) => token,
+            Err(e) => {
+                tracing::warn!("中转代理：账号 {} 获取密钥失败: {}", account.name, e);
+                last_error = Some(e);
+                continue;
+            }
+        };
+
+        let client = match &bound_proxy {
+            Some(bound_proxy) => match relay_client(Some(bound_proxy)) {
+                Ok(client) => client,
+                Err(e) => {
+                    tracing::warn!("中转代理：账号 {} 的代理不可用: {}", account.name, e);
+                    last_error = Some(e);
+                    continue;
+                }
+            },
+            None => client.clone(),
+        };
+
+        let url = format!("{}{}", account.base_url.trim_end_matches('/'), forward_path);
+        let start = Instant::now();
+        let result = client
+            .request(parts.method.clone(), &url)
+            .headers(forward_headers(
+                &parts.headers,
+                &headers,
+                &token_key,
+                &token,
+            ))
+            .body(body.clone())
+            .send()
+            .await;
+        let latency_ms = start.elapsed().as_millis() as i64;
+
+        match result {
+            Ok(upstream) => {
+                let status = upstream.status().as_u16();
+                record(&db, account.id, Some(status), latency_ms).await;
+                if !should_failover(status) {
+                    return Ok(stream_response(upstream));
+                }
+
+                tracing::warn!(
+                    "中转代理：账号 {} 返回 {}，尝试池中的下一个账号",
+                    account.name,
+                    status
+                );
+                // 令牌失效的账号标记为无效，后续请求不再选择
+                if status == 401 {
+                    let db_lock = db.lock().await;
+                    if let Err(e) = db_lock
+                        .update_account_token_status(
+                            account.id,
+                            health::TOKEN_INVALID,
+                            Some("中转代理请求返回 401"),
+                        )
+                        .await
+                    {
+                        tracing::warn!("更新账号 {} 的令牌状态失败: {}", account.name, e);
+                    }
+                }
+                last_failure = Some(upstream);
+            }
+            Err(e) => {
+                record(&db, account.id, None, latency_ms).await;
+                tracing::warn!("中转代理：账号 {} 请求失败: {}", account.name, e);
+                last_error = Some(e.to_string());
+            }
+        }
+    }
+
+    // 池中的账号都失败了：回传最后一次的上游响应
+    Ok(match last_failure {
+        Some(upstream) => stream_response(upstream),
+        None => error_response(
+            StatusCode::BAD_GATEWAY,
+            last_error.as_deref().unwrap_or("账号池中没有可用账号"),
+        ),
+    })
+}
+
+/// 监听本地端口，只接受本机的连接
+pub async fn bind(port: u16) -> std::io::Result<TcpListener> {
+    TcpListener::bind(("127.0.0.1", port)).await
+}
+
+/// 运行中转代理，直到任务被取消
+pub async fn serve(listener: TcpListener, db: Arc<Mutex<Database>>) {
+    // 未绑定代理的账号共用同一个客户端
+    let client = relay_client(None).unwrap_or_default();
+
+    loop {
+        let (stream, _) = match listener.accept().await {
+            Ok(connection) => connection,
+            Err(e) => {
+                tracing::warn!("中转代理接受连接失败: {}", e);
+                continue;
+            }
+        };
+
+        let db = db.clone();
+        let client = client.clone();
+        tokio::spawn(async move {
+            let service = service_fn(move |request| handle(db.clone(), client.clone(), request));
+            if let Err(e) = http1::Builder::new()
+                .serve_connection(TokioIo::new(stream), service)
+                .await
+            {
+                tracing::debug!("中转代理连接结束: {}", e);
+            }
+        });
+    }
+}
+
+/// 设置目录是否通过中转代理访问（None 表示直连），并按新的方式重写目录配置
+pub async fn route_directory(
+    db: &Database,
+    directory_id: i64,
+    port: Option<u16>,
+) -> anyhow::Result<()> {
+    let directory = db.get_directory(directory_id).await?;
+    if port.is_some() && directory.account_pool_id.is_none() {
+        anyhow::bail!("目录 {} 没有绑定账号池", directory.name);
+    }
+
+    db.set_directory_relay_port(directory_id, port.map(i64::from))
+        .await?;
+    let directory = db.get_directory(directory_id).await?;
+    if let Some(expected) = crate::drift::expected_state(db, &directory).await? {
+        crate::drift::fix_drift(&directory, &expected)?;
+    }
+    Ok(())
+}
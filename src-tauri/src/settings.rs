@@ -70,25 +70,6 @@ impl SettingsManager {
         Ok(())
     }
     
-    pub fn get_default_allowed_tools() -> Vec<String> {
-        vec![
-            "Read".to_string(),
-            "Write".to_string(),
-            "Edit".to_string(),
-            "MultiEdit".to_string(),
-            "Bash".to_string(),
-            "Glob".to_string(),
-            "Grep".to_string(),
-            "WebSearch".to_string(),
-            "WebFetch".to_string(),
-            "Task".to_string(),
-            "TodoWrite".to_string(),
-            "BashOutput".to_string(),
-            "KillBash".to_string(),
-            "NotebookEdit".to_string(),
-        ]
-    }
-    
     pub async fn check_for_updates(&self) -> Result<String, Box<dyn std::error::Error>> {
         // 这里是一个模拟的更新检查功能
         // 在实际应用中，这应该连接到实际的更新服务器
@@ -0,0 +1,445 @@
+use crate::config_manager::ConfigManager;
+use crate::database::Database;
+use crate::drift;
+use crate::models::{Directory, DirectoryDrift, DriftIssue};
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::Duration;
+use tokio::sync::{mpsc, Mutex};
+
+/// 目录漂移状态变化时发送给前端的事件名
+pub const DRIFT_EVENT: &str = "config-drift-changed";
+
+// 需要监听的受管文件
+const WATCHED_FILES: [&str; 2] = ["settings.local.json", "CLAUDE.local.md"];
+
+// 合并短时间内的连续写入（编辑器保存、切换账号时的多次写入）
+const DEBOUNCE: Duration = Duration::from_millis(800);
+
+/// 监听已登记目录的 .claude/settings.local.json 和 CLAUDE.local.md，
+/// 文件被外部修改后重新检测漂移并通过回调通知
+pub struct ConfigWatcher {
+    watcher: StdMutex<Option<RecommendedWatcher>>,
+    // 被监听的路径 -> 目录 ID（每个目录监听根目录和 .claude 目录）
+    watched: Arc<StdMutex<HashMap<PathBuf, i64>>>,
+    // 当前处于漂移状态的目录
+    drifted: StdMutex<HashMap<i64, DirectoryDrift>>,
+    // CLAUDE.local.md 被外部修改过的目录，切换或修复后清除
+    external_edits: StdMutex<HashSet<i64>>,
+    notifications_enabled: AtomicBool,
+    sender: mpsc::UnboundedSender<WatchEvent>,
+}
+
+/// 需要重新检测的目录，claude_local_md 表示 CLAUDE.local.md 发生了变化
+#[derive(Debug, Clone, Copy)]
+pub struct WatchEvent {
+    pub directory_id: i64,
+    pub claude_local_md: bool,
+}
+
+impl ConfigWatcher {
+    pub fn new() -> (Arc<Self>, mpsc::UnboundedReceiver<WatchEvent>) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let watched: Arc<StdMutex<HashMap<PathBuf, i64>>> = Arc::new(StdMutex::new(HashMap::new()));
+
+        let event_sender = sender.clone();
+        let event_watched = watched.clone();
+        let watcher = notify::recommended_watcher(move |res: notify::Result<Event>| {
+            let event = match res {
+                Ok(event) => event,
+                Err(e) => {
+                    tracing::warn!("文件监听出错: {}", e);
+                    return;
+                }
+            };
+
+            let watched = event_watched.lock().unwrap();
+            for path in &event.paths {
+                let file_name = match path.file_name().and_then(|n| n.to_str()) {
+                    Some(name) if WATCHED_FILES.contains(&name) => name,
+                    _ => continue,
+                };
+                if let Some(directory_id) = path.parent().and_then(|p| watched.get(p)) {
+                    let _ = event_sender.send(WatchEvent {
+                        directory_id: *directory_id,
+                        claude_local_md: file_name == "CLAUDE.local.md",
+                    });
+                }
+            }
+        });
+
+        let watcher = match watcher {
+            Ok(watcher) => Some(watcher),
+            Err(e) => {
+                tracing::error!("创建文件监听器失败: {}", e);
+                None
+            }
+        };
+
+        let config_watcher = Arc::new(Self {
+            watcher: StdMutex::new(watcher),
+            watched,
+            drifted: StdMutex::new(HashMap::new()),
+            external_edits: StdMutex::new(HashSet::new()),
+            notifications_enabled: AtomicBool::new(true),
+            sender,
+        });
+
+        (config_watcher, receiver)
+    }
+
+    pub fn notifications_enabled(&self) -> bool {
+        self.notifications_enabled.load(Ordering::Relaxed)
+    }
+
+    pub fn set_notifications_enabled(&self, enabled: bool) {
+        self.notifications_enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    /// 当前处于漂移状态的目录
+    pub fn drifted_directories(&self) -> Vec<DirectoryDrift> {
+        let mut drifted: Vec<DirectoryDrift> = self.drifted.lock().unwrap().values().cloned().collect();
+        drifted.sort_by_key(|d| d.directory_id);
+        drifted
+    }
+
+    /// 同步监听列表：新增目录开始监听，已删除的目录停止监听，并重新检测所有目录
+    pub fn sync_directories(&self, directories: &[Directory]) {
+        let desired: HashMap<PathBuf, i64> = directories
+            .iter()
+            .flat_map(|d| watch_paths(d).into_iter().map(move |path| (path, d.id)))
+            .collect();
+
+        {
+            let mut watcher_guard = self.watcher.lock().unwrap();
+            let mut watched = self.watched.lock().unwrap();
+            if let Some(watcher) = watcher_guard.as_mut() {
+                let removed: Vec<PathBuf> = watched
+                    .keys()
+                    .filter(|path| !desired.contains_key(*path))
+                    .cloned()
+                    .collect();
+                for path in removed {
+                    let _ = watcher.unwatch(&path);
+                    watched.remove(&path);
+                }
+                for (path, directory_id) in desired {
+                    add_watch(watcher, &mut watched, path, directory_id);
+                }
+            }
+        }
+
+        // 清理已删除目录的状态
+        let ids: HashSet<i64> = directories.iter().map(|d| d.id).collect();
+        self.drifted.lock().unwrap().retain(|id, _| ids.contains(id));
+        self.external_edits.lock().unwrap().retain(|id| ids.contains(id));
+
+        for directory in directories {
+            let _ = self.sender.send(WatchEvent {
+                directory_id: directory.id,
+                claude_local_md: false,
+            });
+        }
+    }
+
+    /// 切换账号或修复漂移后调用：补充监听新建的 .claude 目录，清除外部修改标记并重新检测
+    pub fn mark_synced(&self, directory: &Directory) {
+        {
+            let mut watcher_guard = self.watcher.lock().unwrap();
+            let mut watched = self.watched.lock().unwrap();
+            if let Some(watcher) = watcher_guard.as_mut() {
+                for path in watch_paths(directory) {
+                    add_watch(watcher, &mut watched, path, directory.id);
+                }
+            }
+        }
+
+        self.external_edits.lock().unwrap().remove(&directory.id);
+        let _ = self.sender.send(WatchEvent {
+            directory_id: directory.id,
+            claude_local_md: false,
+        });
+    }
+
+    /// 处理文件变化事件，on_change 在目录的漂移状态发生变化时调用
+    /// 第二个参数表示该目录是否新进入漂移状态
+    pub async fn process_events<F>(
+        self: Arc<Self>,
+        mut receiver: mpsc::UnboundedReceiver<WatchEvent>,
+        db: Arc<Mutex<Database>>,
+        on_change: F,
+    ) where
+        F: Fn(&DirectoryDrift, bool) + Send + Sync + 'static,
+    {
+        while let Some(first) = receiver.recv().await {
+            let mut pending: HashMap<i64, bool> = HashMap::new();
+            let mut event = Some(first);
+            tokio::time::sleep(DEBOUNCE).await;
+            while let Some(WatchEvent { directory_id, claude_local_md }) = event {
+                *pending.entry(directory_id).or_insert(false) |= claude_local_md;
+                event = receiver.try_recv().ok();
+            }
+
+            for (directory_id, claude_local_md) in pending {
+                if let Some((result, newly_drifted)) = self.check_directory(&db, directory_id, claude_local_md).await {
+                    on_change(&result, newly_drifted);
+                }
+            }
+        }
+    }
+
+    /// 重新检测单个目录，状态有变化时返回 (检测结果, 是否新进入漂移状态)
+    async fn check_directory(
+        &self,
+        db: &Arc<Mutex<Database>>,
+        directory_id: i64,
+        claude_local_md_changed: bool,
+    ) -> Option<(DirectoryDrift, bool)> {
+        let db_lock = db.lock().await;
+        let directory = db_lock.get_directory(directory_id).await.ok()?;
+        let expected = match drift::expected_state(&db_lock, &directory).await {
+            Ok(expected) => expected,
+            Err(e) => {
+                tracing::warn!("获取目录 {} 的期望配置失败: {}", directory.path, e);
+                return None;
+            }
+        };
+        drop(db_lock);
+
+        let mut issues = match &expected {
+            Some(expected) => drift::detect_drift(&directory, expected),
+            None => Vec::new(),
+        };
+        if self.check_claude_local_md(&directory, claude_local_md_changed) {
+            issues.push(DriftIssue {
+                kind: drift::DRIFT_EXTERNAL_EDIT.to_string(),
+                key: Some("CLAUDE.local.md".to_string()),
+                expected: None,
+                actual: None,
+            });
+        }
+
+        let result = DirectoryDrift {
+            directory_id: directory.id,
+            directory_name: directory.name.clone(),
+            directory_path: directory.path.clone(),
+            account_id: expected.as_ref().map(|e| e.account.id),
+            account_name: expected.map(|e| e.account.name),
+            issues,
+        };
+
+        let mut drifted = self.drifted.lock().unwrap();
+        let previous = drifted.get(&directory_id);
+        if result.issues.is_empty() {
+            previous?;
+            drifted.remove(&directory_id);
+            tracing::info!("目录 {} 的配置已恢复一致", directory.path);
+            return Some((result, false));
+        }
+
+        let newly_drifted = previous.is_none();
+        if previous.map(|p| p.issues == result.issues).unwrap_or(false) {
+            return None;
+        }
+        tracing::info!("检测到目录 {} 的配置漂移: {} 项", directory.path, result.issues.len());
+        drifted.insert(directory_id, result.clone());
+        Some((result, newly_drifted))
+    }
+
+    /// 检查 CLAUDE.local.md 是否被外部修改，返回是否仍处于修改状态
+    /// 只在监听到该文件变化时更新标记，避免把用户选择保留的 CLAUDE.local.md 当作漂移
+    fn check_claude_local_md(&self, directory: &Directory, changed: bool) -> bool {
+        let mut external_edits = self.external_edits.lock().unwrap();
+
+        if changed {
+            let target_file = Path::new(&directory.path).join("CLAUDE.local.md");
+            let managed_content = ConfigManager::get_resource_path("config/CLAUDE.local.md")
+                .and_then(|path| std::fs::read_to_string(path).ok());
+
+            match (std::fs::read_to_string(&target_file).ok(), managed_content) {
+                (Some(content), Some(managed)) if content != managed => {
+                    external_edits.insert(directory.id);
+                }
+                _ => {
+                    external_edits.remove(&directory.id);
+                }
+            }
+        }
+
+        external_edits.contains(&directory.id)
+    }
+}
+
+/// 目录需要监听的路径：根目录（CLAUDE.local.md）和 .claude 目录（settings.local.json）
+fn watch_paths(directory: &Directory) -> Vec<PathBuf> {
+    let root = PathBuf::from(&directory.path);
+    if !root.is_dir() {
+        return Vec::new();
+    }
+    let claude_dir = root.join(".claude");
+    if claude_dir.is_dir() {
+        vec![root, claude_dir]
+    } else {
+        vec![root]
+    }
+}
+
+fn add_watch(
+    watcher: &mut RecommendedWatcher,
+    watched: &mut HashMap<PathBuf, i64>,
+    path: PathBuf,
+    directory_id: i64,
+) {
+    if watched.contains_key(&path) {
+        return;
+    }
+    match watcher.watch(&path, RecursiveMode::NonRecursive) {
+        Ok(_) => {
+            watched.insert(path, directory_id);
+        }
+        Err(e) => tracing::warn!("监听目录 {} 失败: {}", path.display(), e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db_backend::DbPool;
+    use crate::models::{CreateAccountRequest, CreateDirectoryRequest, SwitchAccountRequest};
+    use chrono::Utc;
+
+    fn temp_project(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("claude-config-watcher-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join(".claude")).unwrap();
+        dir
+    }
+
+    fn directory(id: i64, path: &Path) -> Directory {
+        Directory {
+            id,
+            path: path.to_string_lossy().to_string(),
+            name: format!("dir-{}", id),
+            is_active: false,
+            permission_preset_id: None,
+            account_pool_id: None,
+            relay_port: None,
+            proxy_id: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    async fn next_event(receiver: &mut mpsc::UnboundedReceiver<WatchEvent>) -> Option<WatchEvent> {
+        tokio::time::timeout(Duration::from_secs(3), receiver.recv()).await.ok().flatten()
+    }
+
+    // 清空已收到的事件，文件系统可能对一次写入报告多个事件
+    async fn drain(receiver: &mut mpsc::UnboundedReceiver<WatchEvent>) {
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        while receiver.try_recv().is_ok() {}
+    }
+
+    #[tokio::test]
+    async fn test_watcher_reports_managed_files_only() {
+        let root = temp_project("events");
+        let (watcher, mut receiver) = ConfigWatcher::new();
+
+        watcher.sync_directories(&[directory(7, &root)]);
+        let initial = next_event(&mut receiver).await.unwrap();
+        assert_eq!(initial.directory_id, 7);
+        assert!(!initial.claude_local_md);
+        drain(&mut receiver).await;
+
+        std::fs::write(root.join(".claude/settings.local.json"), "{}").unwrap();
+        let event = next_event(&mut receiver).await.unwrap();
+        assert_eq!(event.directory_id, 7);
+        assert!(!event.claude_local_md);
+        drain(&mut receiver).await;
+
+        std::fs::write(root.join("CLAUDE.local.md"), "# edited").unwrap();
+        let event = next_event(&mut receiver).await.unwrap();
+        assert_eq!(event.directory_id, 7);
+        assert!(event.claude_local_md);
+        drain(&mut receiver).await;
+
+        // 非受管文件不触发检测
+        std::fs::write(root.join("notes.txt"), "x").unwrap();
+        std::fs::write(root.join(".claude/other.json"), "{}").unwrap();
+        assert!(tokio::time::timeout(Duration::from_millis(500), receiver.recv()).await.is_err());
+
+        // 目录移除后停止监听
+        watcher.sync_directories(&[]);
+        std::fs::write(root.join(".claude/settings.local.json"), "{\"env\":{}}").unwrap();
+        assert!(tokio::time::timeout(Duration::from_millis(500), receiver.recv()).await.is_err());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn test_check_directory_tracks_drift_state() {
+        let root = temp_project("drift");
+        let pool = DbPool::Sqlite(
+            sqlx::sqlite::SqlitePoolOptions::new()
+                .max_connections(1)
+                .connect("sqlite::memory:")
+                .await
+                .unwrap(),
+        );
+        let db = Database { pool };
+        db.migrate().await.unwrap();
+
+        let account = db
+            .create_account(CreateAccountRequest {
+                name: "dev".to_string(),
+                token: "sk-expected".to_string(),
+                base_url: "https://api.anthropic.com".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let dir = db
+            .create_directory(CreateDirectoryRequest {
+                path: root.to_string_lossy().to_string(),
+                name: "project".to_string(),
+            })
+            .await
+            .unwrap();
+        db.switch_account(SwitchAccountRequest { account_id: account.id, directory_id: dir.id })
+            .await
+            .unwrap();
+
+        let db = Arc::new(Mutex::new(db));
+        let expected = {
+            let db_lock = db.lock().await;
+            drift::expected_state(&db_lock, &dir).await.unwrap().unwrap()
+        };
+        let settings_file = root.join(".claude/settings.local.json");
+        drift::fix_drift(&dir, &expected).unwrap();
+        let (watcher, _receiver) = ConfigWatcher::new();
+
+        // 令牌被外部修改：新进入漂移状态
+        let content = std::fs::read_to_string(&settings_file).unwrap();
+        std::fs::write(&settings_file, content.replace("sk-expected", "sk-stale")).unwrap();
+        let (result, newly_drifted) = watcher.check_directory(&db, dir.id, false).await.unwrap();
+        assert!(newly_drifted);
+        assert!(result.issues.iter().any(|i| i.kind == drift::DRIFT_STALE_TOKEN));
+        assert_eq!(watcher.drifted_directories().len(), 1);
+
+        // 状态未变化时不重复通知
+        assert!(watcher.check_directory(&db, dir.id, false).await.is_none());
+
+        // 恢复后通知一次并清除漂移状态
+        drift::fix_drift(&dir, &expected).unwrap();
+        let (result, newly_drifted) = watcher.check_directory(&db, dir.id, false).await.unwrap();
+        assert!(!newly_drifted);
+        assert!(result.issues.is_empty());
+        assert!(watcher.drifted_directories().is_empty());
+        assert!(watcher.check_directory(&db, dir.id, false).await.is_none());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
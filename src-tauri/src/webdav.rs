@@ -1,10 +1,11 @@
 use anyhow::{Context, Result};
 use reqwest_dav::{Auth, Client, ClientBuilder, Depth};
 use serde_json::Value;
-use sqlx::SqlitePool;
 use tracing::{error, info, warn};
 
-use crate::models::{CreateSyncLogRequest, WebDavConfig};
+use crate::db_backend::DbPool;
+use crate::models::{CreateSyncLogRequest, Proxy, WebDavConfig};
+use crate::proxy;
 
 /// WebDAV 客户端管理器
 pub struct WebDavManager {
@@ -13,11 +14,17 @@ pub struct WebDavManager {
 }
 
 impl WebDavManager {
-    /// 从数据库配置创建 WebDAV 管理器
-    pub async fn from_config(config: WebDavConfig) -> Result<Self> {
+    /// 从数据库配置创建 WebDAV 管理器，proxy 为配置绑定的代理
+    pub async fn from_config(config: WebDavConfig, proxy: Option<&Proxy>) -> Result<Self> {
         let auth = Auth::Basic(config.username.clone(), config.password.clone());
 
+        let agent = proxy::client_builder(proxy)
+            .map_err(|e| anyhow::anyhow!(e))?
+            .build()
+            .context("Failed to build HTTP client for WebDAV")?;
+
         let client = ClientBuilder::new()
+            .set_agent(agent)
             .set_host(config.url.clone())
             .set_auth(auth)
             .build()
@@ -163,34 +170,34 @@ impl WebDavManager {
 }
 
 /// 数据库操作 - WebDAV 配置
-pub async fn get_webdav_configs(pool: &SqlitePool) -> Result<Vec<WebDavConfig>> {
-    let configs = sqlx::query_as::<_, WebDavConfig>(
+pub async fn get_webdav_configs(pool: &DbPool) -> Result<Vec<WebDavConfig>> {
+    let configs = pool.query(
         "SELECT * FROM webdav_configs ORDER BY created_at DESC"
     )
-    .fetch_all(pool)
+    .fetch_all::<WebDavConfig>()
     .await
     .context("Failed to get WebDAV configs from database")?;
 
     Ok(configs)
 }
 
-pub async fn get_webdav_config_by_id(pool: &SqlitePool, id: i64) -> Result<Option<WebDavConfig>> {
-    let config = sqlx::query_as::<_, WebDavConfig>(
+pub async fn get_webdav_config_by_id(pool: &DbPool, id: i64) -> Result<Option<WebDavConfig>> {
+    let config = pool.query(
         "SELECT * FROM webdav_configs WHERE id = ?"
     )
     .bind(id)
-    .fetch_optional(pool)
+    .fetch_optional::<WebDavConfig>()
     .await
     .context("Failed to get WebDAV config from database")?;
 
     Ok(config)
 }
 
-pub async fn get_active_webdav_config(pool: &SqlitePool) -> Result<Option<WebDavConfig>> {
-    let config = sqlx::query_as::<_, WebDavConfig>(
-        "SELECT * FROM webdav_configs WHERE is_active = 1 LIMIT 1"
+pub async fn get_active_webdav_config(pool: &DbPool) -> Result<Option<WebDavConfig>> {
+    let config = pool.query(
+        "SELECT * FROM webdav_configs WHERE is_active = TRUE LIMIT 1"
     )
-    .fetch_optional(pool)
+    .fetch_optional::<WebDavConfig>()
     .await
     .context("Failed to get active WebDAV config from database")?;
 
@@ -198,7 +205,7 @@ pub async fn get_active_webdav_config(pool: &SqlitePool) -> Result<Option<WebDav
 }
 
 pub async fn create_webdav_config(
-    pool: &SqlitePool,
+    pool: &DbPool,
     name: &str,
     url: &str,
     username: &str,
@@ -207,7 +214,7 @@ pub async fn create_webdav_config(
     auto_sync: bool,
     sync_interval: i64,
 ) -> Result<WebDavConfig> {
-    let result = sqlx::query(
+    let id = pool.query(
         "INSERT INTO webdav_configs (name, url, username, password, remote_path, auto_sync, sync_interval)
          VALUES (?, ?, ?, ?, ?, ?, ?)"
     )
@@ -218,18 +225,18 @@ pub async fn create_webdav_config(
     .bind(remote_path)
     .bind(auto_sync)
     .bind(sync_interval)
-    .execute(pool)
+    .insert()
     .await
     .context("Failed to create WebDAV config")?;
 
-    let config = get_webdav_config_by_id(pool, result.last_insert_rowid()).await?
+    let config = get_webdav_config_by_id(pool, id).await?
         .ok_or_else(|| anyhow::anyhow!("Failed to retrieve created WebDAV config"))?;
 
     Ok(config)
 }
 
 pub async fn update_webdav_config(
-    pool: &SqlitePool,
+    pool: &DbPool,
     id: i64,
     name: Option<&str>,
     url: Option<&str>,
@@ -242,45 +249,36 @@ pub async fn update_webdav_config(
 ) -> Result<WebDavConfig> {
     // 构建动态更新语句
     let mut updates = Vec::new();
-    let mut values: Vec<Box<dyn sqlx::Encode<'_, sqlx::Sqlite> + Send>> = Vec::new();
 
-    if let Some(v) = name {
+    if name.is_some() {
         updates.push("name = ?");
-        values.push(Box::new(v.to_string()));
     }
-    if let Some(v) = url {
+    if url.is_some() {
         updates.push("url = ?");
-        values.push(Box::new(v.to_string()));
     }
-    if let Some(v) = username {
+    if username.is_some() {
         updates.push("username = ?");
-        values.push(Box::new(v.to_string()));
     }
-    if let Some(v) = password {
+    if password.is_some() {
         updates.push("password = ?");
-        values.push(Box::new(v.to_string()));
     }
-    if let Some(v) = remote_path {
+    if remote_path.is_some() {
         updates.push("remote_path = ?");
-        values.push(Box::new(v.to_string()));
     }
-    if let Some(v) = auto_sync {
+    if auto_sync.is_some() {
         updates.push("auto_sync = ?");
-        values.push(Box::new(v));
     }
-    if let Some(v) = sync_interval {
+    if sync_interval.is_some() {
         updates.push("sync_interval = ?");
-        values.push(Box::new(v));
     }
     if let Some(v) = is_active {
         updates.push("is_active = ?");
-        values.push(Box::new(v));
 
         // 如果设置为活跃,则取消其他配置的活跃状态
         if v {
-            sqlx::query("UPDATE webdav_configs SET is_active = 0 WHERE id != ?")
+            pool.query("UPDATE webdav_configs SET is_active = FALSE WHERE id != ?")
                 .bind(id)
-                .execute(pool)
+                .execute()
                 .await
                 .context("Failed to deactivate other WebDAV configs")?;
         }
@@ -298,7 +296,7 @@ pub async fn update_webdav_config(
     );
 
     // 使用原始参数构建查询
-    let mut final_query = sqlx::query::<sqlx::Sqlite>(&update_sql);
+    let mut final_query = pool.query(update_sql);
     if let Some(v) = name { final_query = final_query.bind(v); }
     if let Some(v) = url { final_query = final_query.bind(v); }
     if let Some(v) = username { final_query = final_query.bind(v); }
@@ -309,7 +307,7 @@ pub async fn update_webdav_config(
     if let Some(v) = is_active { final_query = final_query.bind(v); }
     final_query = final_query.bind(id);
 
-    final_query.execute(pool).await
+    final_query.execute().await
         .context("Failed to update WebDAV config")?;
 
     let config = get_webdav_config_by_id(pool, id).await?
@@ -318,22 +316,55 @@ pub async fn update_webdav_config(
     Ok(config)
 }
 
-pub async fn delete_webdav_config(pool: &SqlitePool, id: i64) -> Result<()> {
-    sqlx::query("DELETE FROM webdav_configs WHERE id = ?")
+pub async fn delete_webdav_config(pool: &DbPool, id: i64) -> Result<()> {
+    pool.query("DELETE FROM webdav_configs WHERE id = ?")
         .bind(id)
-        .execute(pool)
+        .execute()
         .await
         .context("Failed to delete WebDAV config")?;
 
     Ok(())
 }
 
+/// 设置 WebDAV 配置使用的代理，None 表示直连
+pub async fn set_webdav_config_proxy(
+    pool: &DbPool,
+    config_id: i64,
+    proxy_id: Option<i64>,
+) -> Result<()> {
+    pool.query("UPDATE webdav_configs SET proxy_id = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?")
+        .bind(proxy_id)
+        .bind(config_id)
+        .execute()
+        .await
+        .context("Failed to update WebDAV config proxy")?;
+
+    Ok(())
+}
+
+/// 获取 WebDAV 配置绑定的代理
+pub async fn get_webdav_config_proxy(
+    pool: &DbPool,
+    config: &WebDavConfig,
+) -> Result<Option<Proxy>> {
+    let proxy = match config.proxy_id {
+        Some(proxy_id) => pool.query("SELECT * FROM proxies WHERE id = ?")
+            .bind(proxy_id)
+            .fetch_optional::<Proxy>()
+            .await
+            .context("Failed to get WebDAV config proxy")?,
+        None => None,
+    };
+
+    Ok(proxy)
+}
+
 /// 记录同步日志
 pub async fn create_sync_log(
-    pool: &SqlitePool,
+    pool: &DbPool,
     log: CreateSyncLogRequest,
 ) -> Result<()> {
-    sqlx::query(
+    pool.query(
         "INSERT INTO sync_logs (webdav_config_id, sync_type, status, message)
          VALUES (?, ?, ?, ?)"
     )
@@ -341,7 +372,7 @@ pub async fn create_sync_log(
     .bind(log.sync_type)
     .bind(log.status)
     .bind(log.message)
-    .execute(pool)
+    .execute()
     .await
     .context("Failed to create sync log")?;
 
@@ -349,21 +380,21 @@ pub async fn create_sync_log(
 }
 
 /// 获取同步日志
-pub async fn get_sync_logs(pool: &SqlitePool, config_id: Option<i64>, limit: i64) -> Result<Vec<crate::models::SyncLog>> {
+pub async fn get_sync_logs(pool: &DbPool, config_id: Option<i64>, limit: i64) -> Result<Vec<crate::models::SyncLog>> {
     let logs = if let Some(id) = config_id {
-        sqlx::query_as::<_, crate::models::SyncLog>(
+        pool.query(
             "SELECT * FROM sync_logs WHERE webdav_config_id = ? ORDER BY synced_at DESC LIMIT ?"
         )
         .bind(id)
         .bind(limit)
-        .fetch_all(pool)
+        .fetch_all::<crate::models::SyncLog>()
         .await
     } else {
-        sqlx::query_as::<_, crate::models::SyncLog>(
+        pool.query(
             "SELECT * FROM sync_logs ORDER BY synced_at DESC LIMIT ?"
         )
         .bind(limit)
-        .fetch_all(pool)
+        .fetch_all::<crate::models::SyncLog>()
         .await
     }
     .context("Failed to get sync logs from database")?;
@@ -372,10 +403,10 @@ pub async fn get_sync_logs(pool: &SqlitePool, config_id: Option<i64>, limit: i64
 }
 
 /// 更新最后同步时间
-pub async fn update_last_sync_time(pool: &SqlitePool, config_id: i64) -> Result<()> {
-    sqlx::query("UPDATE webdav_configs SET last_sync_at = CURRENT_TIMESTAMP WHERE id = ?")
+pub async fn update_last_sync_time(pool: &DbPool, config_id: i64) -> Result<()> {
+    pool.query("UPDATE webdav_configs SET last_sync_at = CURRENT_TIMESTAMP WHERE id = ?")
         .bind(config_id)
-        .execute(pool)
+        .execute()
         .await
         .context("Failed to update last sync time")?;
 
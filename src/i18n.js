@@ -41,7 +41,7 @@ const translations = {
         'accounts.title': '账号列表',
         'accounts.add': '添加账号',
         'accounts.search': '搜索',
-        'accounts.search_placeholder': '搜索账号名称或Token...',
+        'accounts.search_placeholder': '搜索账号名称、Token或备注...',
         'accounts.filter_url': '筛选URL',
         'accounts.per_page': '每页显示',
         'accounts.items': '条',
@@ -60,8 +60,6 @@ const translations = {
         'accounts.model_help': '指定此账号使用的Claude模型，留空则使用默认模型',
         'accounts.import': '批量导入',
         'accounts.export': '批量导出',
-        'accounts.import_success': '成功导入 {count} 个账号',
-        'accounts.import_skipped': '跳过 {count} 个已存在的账号',
         'accounts.import_error': '导入失败',
         'accounts.export_success': '成功导出 {count} 个账号',
         'accounts.export_error': '导出失败',
@@ -73,7 +71,6 @@ const translations = {
         'accounts.filter_by_url': '按 URL 筛选',
         'accounts.selected_count': '已选择',
         'accounts.no_accounts_for_url': '该 URL 下没有账号',
-        'accounts.import_file_invalid': '无效的导入文件格式',
 
         // 目录管理
         'directories.title': '目录管理',
@@ -153,7 +150,8 @@ const translations = {
         // Claude配置
         'claude.permission_title': '权限配置',
         'claude.permission_mode': '默认权限模式',
-        'claude.permission_normal': '标准模式 (需要确认)',
+        'claude.permission_default': '默认模式 (需要确认)',
+        'claude.permission_plan': '计划模式 (只读分析)',
         'claude.permission_accept_edits': '自动批准编辑',
         'claude.permission_bypass': '绕过所有权限 (仅限安全环境)',
         'claude.permission_warning': 'bypassPermissions模式会跳过所有安全检查，请谨慎使用',
@@ -295,6 +293,324 @@ const translations = {
         'success.url_deleted': 'URL删除成功',
         'success.url_deleted_with_name': 'URL "{name}" 删除成功',
         'success.no_invalid_directories': '没有发现无效目录',
+        'health.title': '健康检查',
+        'health.check': '健康检查',
+        'health.account': '认证账号',
+        'health.no_account': '不使用账号（仅检查连通性）',
+        'health.run': '开始检查',
+        'health.ok': '正常',
+        'health.failed': '异常',
+        'health.no_history': '暂无检查记录',
+        'health.checked_at': '检查时间',
+        'health.result': '结果',
+        'health.connect': 'TCP 连接',
+        'health.tls': 'TLS 握手',
+        'health.latency': 'HTTP 耗时',
+        'health.status': 'HTTP 状态',
+        'health.check_failed': '健康检查失败',
+        'token.verify': '校验',
+        'token.verify_all': '校验全部令牌',
+        'token.valid': '令牌有效',
+        'token.invalid': '令牌无效',
+        'token.error': '校验出错',
+        'token.unchecked': '未校验',
+        'token.verify_success': '账号 "{name}" 的令牌有效',
+        'token.verify_failed': '账号 "{name}" 的令牌校验未通过',
+        'token.verify_all_done': '校验完成：{valid}/{total} 个令牌有效',
+        'token.verify_error': '令牌校验失败',
+        'balance.title': '余额查询配置',
+        'balance.help': '请求地址、请求头和请求体支持 {base_url} 和 {token} 占位符',
+        'balance.method': '请求方法',
+        'balance.url_template': '请求地址',
+        'balance.headers': '请求头（JSON）',
+        'balance.body': '请求体（仅 POST）',
+        'balance.path': '余额字段路径',
+        'balance.divisor': '换算除数',
+        'balance.unit': '单位',
+        'balance.enabled': '启用余额查询',
+        'balance.configure': '余额查询',
+        'balance.configured': '余额查询',
+        'balance.refresh': '刷新余额',
+        'balance.remaining': '余额',
+        'balance.failed': '余额查询失败',
+        'balance.no_adapter': '没有配置余额查询的 URL，请先在 URL 管理中配置',
+        'balance.refresh_done': '余额查询完成：{success}/{total} 个账号成功',
+        'balance.refresh_failed': '刷新余额失败',
+        'balance.invalid_headers': '请求头不是有效的 JSON 对象',
+        'balance.required': '请填写请求地址和余额字段路径',
+        'balance.saved': '余额查询配置已保存',
+        'balance.deleted': '余额查询配置已删除',
+        'balance.save_failed': '保存余额查询配置失败',
+        'pool.open': '账号池',
+        'pool.title': '账号池管理',
+        'pool.help': '目录绑定账号池后，当前账号的令牌校验或健康检查失败时会自动切换到池中优先级最高的可用账号，同一优先级内按权重选择',
+        'pool.select': '账号池',
+        'pool.name': '名称',
+        'pool.description': '描述（可选）',
+        'pool.create': '创建',
+        'pool.members': '成员账号',
+        'pool.directories': '目录绑定',
+        'pool.priority': '优先级',
+        'pool.weight': '权重',
+        'pool.empty': '暂无账号池',
+        'pool.no_pool': '不绑定账号池',
+        'pool.name_required': '请输入账号池名称',
+        'pool.created': '账号池已创建',
+        'pool.deleted': '账号池已删除',
+        'pool.delete_confirm': '确定删除账号池 {name} 吗？绑定的目录将解除绑定',
+        'pool.load_failed': '加载账号池失败',
+        'pool.save_failed': '保存账号池失败',
+        'pool.bound': '目录的账号池绑定已更新',
+        'pool.bound_switched': '目录已绑定账号池并切换到账号 {to}',
+        'pool.failover_line': '账号池 {pool}：目录 {directory} 的账号 {from} 不可用',
+        'pool.switched_to': '已切换到 {to}',
+        'pool.no_available': '池中没有其他可用账号',
+        'pool.failovers': '故障切换记录',
+        'pool.no_failovers': '暂无故障切换记录',
+        'relay.title': '本地中转代理',
+        'relay.port': '端口',
+        'relay.start': '启动',
+        'relay.stop': '停止',
+        'relay.running': '运行中：{url}',
+        'relay.stopped': '未运行',
+        'relay.via_relay': '经中转',
+        'relay.via_relay_hint': '目录的 ANTHROPIC_BASE_URL 指向本地中转代理，由代理按账号池轮换账号并注入令牌',
+        'relay.stat_line': '中转请求 {requests} 次，失败 {failures} 次，平均 {latency} ms',
+        'relay.directory_updated': '目录配置已更新',
+        'relay.failed': '中转代理操作失败',
+        'lifecycle.disabled': '已停用',
+        'lifecycle.expired': '已过期',
+        'lifecycle.expires_in': '{days} 天后到期',
+        'lifecycle.expires_at': '到期',
+        'lifecycle.never_used': '从未使用',
+        'lifecycle.usage': '已切换 {count} 次 · 最近使用：{time}',
+        'lifecycle.expiry_warning': '有 {count} 个账号已过期或即将到期：{names}',
+        'lifecycle.filter_status': '状态',
+        'lifecycle.filter_all': '全部',
+        'lifecycle.filter_enabled': '已启用',
+        'lifecycle.filter_disabled': '已停用',
+        'lifecycle.filter_expiring': '即将到期',
+        'lifecycle.tags': '标签',
+        'lifecycle.tag_filter_placeholder': '按标签筛选',
+        'lifecycle.expires_at_label': '到期日期',
+        'lifecycle.expires_at_help': '留空表示不过期',
+        'lifecycle.tags_placeholder': '多个标签用逗号分隔',
+        'lifecycle.notes': '备注',
+        'lifecycle.enabled_label': '启用账号',
+        'lifecycle.enabled_help': '停用的账号保留在列表中，但不会出现在切换选择里',
+        'import.title': '导入预览',
+        'import.detected_format': '识别格式：{format}。新增 {new} 项，有差异 {changed} 项，相同或重复 {identical} 项',
+        'import.header_kind': '类型',
+        'import.header_name': '名称',
+        'import.header_status': '状态',
+        'import.header_diff': '差异',
+        'import.header_strategy': '处理方式',
+        'import.header_result': '结果',
+        'import.header_detail': '说明',
+        'import.kind.account': '账号',
+        'import.kind.base_url': 'API 地址',
+        'import.kind.directory': '目录',
+        'import.kind.binding': '绑定',
+        'import.status.new': '新增',
+        'import.status.identical': '相同',
+        'import.status.changed': '有差异',
+        'import.status.duplicate': '文件内重复',
+        'import.strategy.prompt': '与已有记录不同的项的处理方式',
+        'import.strategy.default': '使用上方的处理方式',
+        'import.strategy.skip': '跳过，保留已有记录',
+        'import.strategy.overwrite': '覆盖已有记录',
+        'import.strategy.rename': '以新名称导入（仅名称冲突时）',
+        'import.strategy.merge': '合并，只补全缺少的字段',
+        'import.action.created': '已创建',
+        'import.action.renamed': '已以新名称创建',
+        'import.action.overwritten': '已覆盖',
+        'import.action.merged': '已合并',
+        'import.action.unchanged': '无变化',
+        'import.action.skipped': '已跳过',
+        'import.action.failed': '失败',
+        'import.summary': '导入完成：新增 {created}，更新 {updated}，跳过 {skipped}，失败 {failed}',
+        'import.confirm': '导入',
+        'import.format.providers': '供应商列表 (providers)',
+        'import.format.backup': '本工具备份文件',
+        'export.format': '导出格式',
+        'export.format.json': '完整 JSON（账号、API 地址、目录和绑定）',
+        'export.format.dotenv': '.env 文件（每个账号一个）',
+        'export.format.shell': '可 source 的 shell 脚本',
+        'export.filter_by_tag': '按标签筛选',
+        'export.all_tags': '全部标签',
+        'proxies.title': '代理',
+        'proxies.add': '添加代理',
+        'proxies.edit': '编辑代理',
+        'proxies.help': '代理可绑定到 URL、账号或目录，切换时写入 HTTP_PROXY / HTTPS_PROXY / NO_PROXY（目录优先，其次账号，最后 URL）；绑定到 WebDAV 配置后同步也会走该代理',
+        'proxies.empty': '暂无代理',
+        'proxies.name': '代理名称',
+        'proxies.url': '代理地址',
+        'proxies.url_help': '支持 http://、https://、socks5://、socks5h:// 地址',
+        'proxies.no_proxy': '不走代理的主机',
+        'proxies.no_proxy_help': '逗号分隔，写入 NO_PROXY',
+        'proxies.username': '用户名',
+        'proxies.password': '密码',
+        'proxies.description': '描述',
+        'proxies.with_auth': '需认证',
+        'proxies.select_label': '代理',
+        'proxies.none': '不使用代理',
+        'proxies.account_help': '目录未绑定代理时，切换到此账号会使用该代理',
+        'proxies.directory_help': '切换到此目录时使用该代理，优先于账号和 URL 上的代理',
+        'proxies.url_bind_help': '使用此 URL 的账号在未单独绑定代理时使用该代理，健康检查也会走该代理',
+        'proxies.webdav_help': '访问该 WebDAV 服务器时使用的代理',
+        'proxies.test': '测试代理',
+        'proxies.testing': '正在测试代理 {name}...',
+        'proxies.test_success': '代理 {name} 可用，耗时 {ms} ms',
+        'proxies.test_failed': '代理 {name} 不可用',
+        'proxies.saved': '代理已保存',
+        'proxies.save_failed': '保存代理失败',
+        'proxies.load_failed': '加载代理失败',
+        'proxies.delete_title': '删除代理',
+        'proxies.delete_confirm': '确定要删除代理 "{name}" 吗？绑定该代理的 URL、账号、目录和 WebDAV 配置将解除绑定。',
+        'proxies.deleted': '代理已删除',
+        'proxies.delete_failed': '删除代理失败',
+        'mcp.title': 'MCP 服务器',
+        'mcp.add': '添加服务器',
+        'mcp.edit': '编辑服务器',
+        'mcp.help': '在目录或账号上勾选 MCP 服务器后，切换时写入项目的 .mcp.json（目录和账号勾选的服务器合并写入）',
+        'mcp.empty': '暂无 MCP 服务器',
+        'mcp.name': '服务器名称',
+        'mcp.transport': '传输方式',
+        'mcp.target': '命令 / 地址',
+        'mcp.command': '启动命令',
+        'mcp.args': '命令参数',
+        'mcp.args_help': '每行一个参数',
+        'mcp.env': '环境变量',
+        'mcp.env_help': 'JSON 对象，可留空',
+        'mcp.env_invalid': '环境变量必须是 JSON 对象',
+        'mcp.url': '服务器地址',
+        'mcp.description': '描述',
+        'mcp.select_label': 'MCP 服务器',
+        'mcp.account_help': '切换到此账号时写入勾选的服务器，与目录勾选的服务器合并',
+        'mcp.directory_help': '切换到此目录时写入勾选的服务器，与账号勾选的服务器合并',
+        'mcp.saved': 'MCP 服务器已保存',
+        'mcp.save_failed': '保存 MCP 服务器失败',
+        'mcp.load_failed': '加载 MCP 服务器失败',
+        'mcp.delete_title': '删除 MCP 服务器',
+        'mcp.delete_confirm': '确定要删除 MCP 服务器 "{name}" 吗？勾选该服务器的目录和账号将不再写入它。',
+        'mcp.deleted': 'MCP 服务器已删除',
+        'mcp.delete_failed': '删除 MCP 服务器失败',
+        'hooks.title': 'Hook',
+        'hooks.add': '添加 Hook',
+        'hooks.edit': '编辑 Hook',
+        'hooks.help': '全局 Hook 对所有目录生效，其余 Hook 在目录中勾选后生效，切换时合并写入 settings.local.json 的 hooks 段',
+        'hooks.empty': '暂无 Hook',
+        'hooks.name': 'Hook 名称',
+        'hooks.event': '触发事件',
+        'hooks.matcher': '工具匹配',
+        'hooks.matcher_help': '匹配工具名称，留空匹配所有工具',
+        'hooks.command': '命令',
+        'hooks.timeout': '超时时间（秒）',
+        'hooks.timeout_invalid': '超时时间必须是大于 0 的整数',
+        'hooks.is_global': '对所有目录生效',
+        'hooks.global': '全局',
+        'hooks.description': '描述',
+        'hooks.select_label': 'Hook',
+        'hooks.directory_help': '切换到此目录时写入勾选的 Hook，全局 Hook 始终写入',
+        'hooks.saved': 'Hook 已保存',
+        'hooks.save_failed': '保存 Hook 失败',
+        'hooks.load_failed': '加载 Hook 失败',
+        'hooks.delete_title': '删除 Hook',
+        'hooks.delete_confirm': '确定要删除 Hook "{name}" 吗？勾选该 Hook 的目录将不再写入它。',
+        'hooks.deleted': 'Hook 已删除',
+        'hooks.delete_failed': '删除 Hook 失败',
+        'definitions.title': '命令与子代理',
+        'definitions.add': '添加定义',
+        'definitions.edit': '编辑定义',
+        'definitions.help': '切换时将目录启用的定义写入 .claude/commands 和 .claude/agents；默认启用的定义对未单独设置的目录生效',
+        'definitions.empty': '暂无定义',
+        'definitions.kind': '类型',
+        'definitions.kind_command': '斜杠命令',
+        'definitions.kind_agent': '子代理',
+        'definitions.name': '名称',
+        'definitions.name_help': '命令名称可用 / 分隔子目录，子代理名称不能包含 /',
+        'definitions.description': '描述',
+        'definitions.description_placeholder': '留空时读取 front-matter 中的 description',
+        'definitions.content': '内容',
+        'definitions.content_help': '完整的 Markdown 文件内容，可使用 {{ACCOUNT_NAME}} 等模板变量',
+        'definitions.is_default': '默认启用',
+        'definitions.default': '默认',
+        'definitions.select_label': '命令与子代理',
+        'definitions.directory_help': '切换到此目录时写入勾选的定义',
+        'definitions.import': '从项目导入',
+        'definitions.import_select': '选择包含 .claude 目录的项目',
+        'definitions.import_overwrite': '覆盖同名定义',
+        'definitions.import_failed': '导入定义失败',
+        'definitions.saved': '定义已保存',
+        'definitions.save_failed': '保存定义失败',
+        'definitions.load_failed': '加载定义失败',
+        'definitions.delete_title': '删除定义',
+        'definitions.delete_confirm': '确定要删除定义 "{name}" 吗？已写入项目的文件会在下次切换时移除。',
+        'definitions.deleted': '定义已删除',
+        'definitions.delete_failed': '删除定义失败',
+        'providers.select_label': '供应商类型',
+        'providers.url_help': 'Bedrock / Vertex 的 URL 地址会写入对应的 BASE_URL 变量，区域、项目等参数在账号的自定义环境变量中填写',
+        'providers.account_hint': '{name} 账号需要在自定义环境变量中填写: {required}（可选: {optional}）',
+        'providers.invalid': '账号配置不完整',
+        'key_helper.mode_label': '凭据模式',
+        'key_helper.mode_token': '静态令牌',
+        'key_helper.mode_helper': '辅助命令（apiKeyHelper）',
+        'key_helper.command': '辅助命令',
+        'key_helper.command_placeholder': '例如: ~/bin/get-claude-key.sh',
+        'key_helper.command_help': '命令输出的内容作为 API Key，切换时写入 apiKeyHelper 而不是静态令牌',
+        'key_helper.command_required': '辅助命令模式必须填写命令',
+        'key_helper.ttl': '缓存时间（毫秒）',
+        'key_helper.ttl_placeholder': '留空使用 Claude Code 默认值',
+        'key_helper.invalid_ttl': '缓存时间必须是正整数',
+        'key_helper.test': '试运行',
+        'key_helper.test_ok': '辅助命令可用，生成的密钥: {key}',
+        'key_helper.test_failed': '辅助命令运行失败',
+        'headers.label': '自定义请求头',
+        'headers.placeholder': 'X-Tenant-Id: team-a',
+        'headers.url_help': '每行一个 Name: Value，切换时写入 ANTHROPIC_CUSTOM_HEADERS，健康检查也会携带这些请求头',
+        'headers.account_help': '每行一个 Name: Value，与 URL 上的同名请求头合并时以账号为准',
+        'headers.invalid_line': '请求头格式错误，应为 Name: Value: {line}',
+        'migrate.schema_version': '结构版本',
+        'migrate.up_to_date': '最新',
+        'migrate.pending': '{} 个迁移待执行',
+        'migrate.too_new': '数据库版本高于程序版本',
+        'discovery.open': '发现项目',
+        'discovery.title': '发现已有的 Claude 项目',
+        'discovery.help': '扫描根目录下包含 .claude 目录或 CLAUDE.md 的文件夹，支持 ~ 和 * 通配符，如 ~/code/*',
+        'discovery.roots': '扫描根目录',
+        'discovery.add_root': '添加',
+        'discovery.scan': '扫描',
+        'discovery.no_roots': '尚未配置扫描根目录',
+        'discovery.load_roots_failed': '加载扫描根目录失败',
+        'discovery.scan_hint': '添加扫描根目录后点击扫描',
+        'discovery.scanning': '正在扫描...',
+        'discovery.scan_failed': '扫描失败',
+        'discovery.no_projects': '未发现包含 .claude 目录或 CLAUDE.md 的项目',
+        'discovery.registered': '已登记',
+        'discovery.new_token': '未登记的令牌',
+        'discovery.create_accounts': '同时为未登记的令牌创建账号和 API 地址',
+        'discovery.import': '导入选中的项目',
+        'discovery.none_selected': '请至少选择一个项目',
+        'discovery.import_success': '导入完成：新增 {directories} 个目录、{accounts} 个账号、{base_urls} 个 API 地址',
+        'discovery.import_failed': '导入失败',
+        'drift.badge': '配置已被修改',
+        'drift.hint': '与账号 {account} 的配置不一致',
+        'drift.fix': '恢复配置',
+        'drift.fix_failed': '恢复配置失败',
+        'drift.notifications': '配置被修改时通知',
+        'drift.kind.missing_directory': '目录不存在',
+        'drift.kind.missing_settings': '缺少 settings.local.json',
+        'drift.kind.invalid_settings': 'settings.local.json 格式错误',
+        'drift.kind.stale_token': '令牌已变化',
+        'drift.kind.env_changed': '环境变量已变化',
+        'drift.kind.missing_asset': '缺少文件',
+        'drift.kind.external_edit': '文件被外部修改',
+        'drift.scan': '检查配置',
+        'drift.scan_found': '{count} 个目录的配置与账号不一致',
+        'drift.scan_clean': '所有目录的配置均与账号一致',
+        'drift.scan_failed': '检查配置失败',
+        'drift.fix_all': '全部恢复',
+        'drift.fix_all_confirm': '确定要按当前账号恢复 {count} 个目录的配置吗？外部修改将被覆盖。',
         'success.cleanup_directories': '成功清除 {count} 个无效目录',
         'success.cleanup_partial': '清除完成：成功 {success} 个，失败 {failed} 个\n失败详情：\n{errors}',
         'success.webdav_created': 'WebDAV 配置创建成功',
@@ -388,6 +704,32 @@ const translations = {
         'claude.permission_mode_bypass_short': '绕过权限',
         'claude.permission_mode_accept_short': '自动批准编辑',
         'claude.permission_mode_normal_short': '标准模式',
+        'claude.permission_mode_plan_short': '计划模式',
+        'permission_presets.title': '权限预设',
+        'permission_presets.add': '添加预设',
+        'permission_presets.edit': '编辑预设',
+        'permission_presets.help': '在目录或账号上选择权限预设后，切换时会使用预设覆盖 permissions 配置（目录优先于账号）',
+        'permission_presets.empty': '暂无权限预设',
+        'permission_presets.name': '预设名称',
+        'permission_presets.rules': '规则',
+        'permission_presets.builtin': '内置',
+        'permission_presets.allow': '允许 (allow)',
+        'permission_presets.deny': '拒绝 (deny)',
+        'permission_presets.ask': '询问 (ask)',
+        'permission_presets.description': '描述',
+        'permission_presets.rules_help': '每行一条规则，例如 Read、Bash(npm run test:*)、WebFetch(domain:example.com)、mcp__github',
+        'permission_presets.select_label': '权限预设',
+        'permission_presets.none': '不使用预设',
+        'permission_presets.account_help': '目录未设置预设时，切换到此账号会使用该权限预设',
+        'permission_presets.directory_help': '切换到此目录时使用该权限预设，优先于账号上的预设',
+        'permission_presets.invalid_rule': '无效的权限规则',
+        'permission_presets.load_failed': '加载权限预设失败',
+        'permission_presets.saved': '权限预设已保存',
+        'permission_presets.save_failed': '保存权限预设失败',
+        'permission_presets.delete_title': '删除权限预设',
+        'permission_presets.delete_confirm': '确定要删除权限预设 "{name}" 吗？使用该预设的目录和账号将解除绑定',
+        'permission_presets.deleted': '权限预设已删除',
+        'permission_presets.delete_failed': '删除权限预设失败',
 
         // WebDAV相关
         'webdav.load_failed': '加载 WebDAV 配置失败',
@@ -502,8 +844,6 @@ const translations = {
         'text.webdav_list_failed': '获取文件列表失败',
         'text.webdav_confirm_delete': '确定要删除 WebDAV 配置 "{name}" 吗？',
         'text.webdav_confirm_delete_title': '确认删除',
-        'text.webdav_confirm_download': '确定要下载并应用配置文件 "{filename}" 吗？\n这将覆盖当前配置！',
-        'text.webdav_confirm_download_title': '确认下载',
         'text.url_name_exists': 'URL名称已存在，请使用不同的名称',
         'text.url_address_exists': 'URL地址已存在，请使用不同的URL地址',
         'text.account_name_exists_hint': '账号名称已存在，请使用不同的名称',
@@ -551,7 +891,7 @@ const translations = {
         'accounts.title': 'Account List',
         'accounts.add': 'Add Account',
         'accounts.search': 'Search',
-        'accounts.search_placeholder': 'Search account name or token...',
+        'accounts.search_placeholder': 'Search account name, token or notes...',
         'accounts.filter_url': 'Filter by URL',
         'accounts.per_page': 'Per Page',
         'accounts.items': 'items',
@@ -570,8 +910,6 @@ const translations = {
         'accounts.model_help': 'Specify the Claude model for this account, leave empty to use default',
         'accounts.import': 'Batch Import',
         'accounts.export': 'Batch Export',
-        'accounts.import_success': 'Successfully imported {count} account(s)',
-        'accounts.import_skipped': 'Skipped {count} existing account(s)',
         'accounts.import_error': 'Import failed',
         'accounts.export_success': 'Successfully exported {count} account(s)',
         'accounts.export_error': 'Export failed',
@@ -583,7 +921,6 @@ const translations = {
         'accounts.filter_by_url': 'Filter by URL',
         'accounts.selected_count': 'Selected',
         'accounts.no_accounts_for_url': 'No accounts for this URL',
-        'accounts.import_file_invalid': 'Invalid import file format',
 
         // Directories
         'directories.title': 'Directory Management',
@@ -663,7 +1000,8 @@ const translations = {
         // Claude Settings
         'claude.permission_title': 'Permission Configuration',
         'claude.permission_mode': 'Default Permission Mode',
-        'claude.permission_normal': 'Normal Mode (Requires Confirmation)',
+        'claude.permission_default': 'Default Mode (Requires Confirmation)',
+        'claude.permission_plan': 'Plan Mode (Read-only Analysis)',
         'claude.permission_accept_edits': 'Auto Accept Edits',
         'claude.permission_bypass': 'Bypass All Permissions (Secure Environments Only)',
         'claude.permission_warning': 'bypassPermissions mode skips all security checks, use with caution',
@@ -805,6 +1143,324 @@ const translations = {
         'success.url_deleted': 'URL deleted successfully',
         'success.url_deleted_with_name': 'URL "{name}" deleted successfully',
         'success.no_invalid_directories': 'No invalid directories found',
+        'health.title': 'Health Check',
+        'health.check': 'Health Check',
+        'health.account': 'Account',
+        'health.no_account': 'No account (connectivity only)',
+        'health.run': 'Run Check',
+        'health.ok': 'Healthy',
+        'health.failed': 'Unhealthy',
+        'health.no_history': 'No checks yet',
+        'health.checked_at': 'Checked At',
+        'health.result': 'Result',
+        'health.connect': 'TCP Connect',
+        'health.tls': 'TLS Handshake',
+        'health.latency': 'HTTP Latency',
+        'health.status': 'HTTP Status',
+        'health.check_failed': 'Health check failed',
+        'token.verify': 'Verify',
+        'token.verify_all': 'Verify All Tokens',
+        'token.valid': 'Token valid',
+        'token.invalid': 'Token invalid',
+        'token.error': 'Check error',
+        'token.unchecked': 'Unchecked',
+        'token.verify_success': 'Token of account "{name}" is valid',
+        'token.verify_failed': 'Token of account "{name}" failed verification',
+        'token.verify_all_done': 'Verification finished: {valid}/{total} tokens valid',
+        'token.verify_error': 'Token verification failed',
+        'balance.title': 'Balance Query Settings',
+        'balance.help': 'The request URL, headers and body support the {base_url} and {token} placeholders',
+        'balance.method': 'Method',
+        'balance.url_template': 'Request URL',
+        'balance.headers': 'Headers (JSON)',
+        'balance.body': 'Body (POST only)',
+        'balance.path': 'Balance Field Path',
+        'balance.divisor': 'Divisor',
+        'balance.unit': 'Unit',
+        'balance.enabled': 'Enable balance queries',
+        'balance.configure': 'Balance',
+        'balance.configured': 'Balance query',
+        'balance.refresh': 'Refresh Balances',
+        'balance.remaining': 'Balance',
+        'balance.failed': 'Balance query failed',
+        'balance.no_adapter': 'No URL has a balance query configured, set one up under URLs first',
+        'balance.refresh_done': 'Balance query finished: {success}/{total} accounts succeeded',
+        'balance.refresh_failed': 'Failed to refresh balances',
+        'balance.invalid_headers': 'Headers are not a valid JSON object',
+        'balance.required': 'Please enter the request URL and balance field path',
+        'balance.saved': 'Balance query settings saved',
+        'balance.deleted': 'Balance query settings deleted',
+        'balance.save_failed': 'Failed to save balance query settings',
+        'pool.open': 'Pools',
+        'pool.title': 'Account Pools',
+        'pool.help': 'When a directory is bound to a pool and its current account fails token verification or a health check, it switches to the available account with the highest priority; accounts with the same priority are chosen by weight',
+        'pool.select': 'Pool',
+        'pool.name': 'Name',
+        'pool.description': 'Description (optional)',
+        'pool.create': 'Create',
+        'pool.members': 'Member Accounts',
+        'pool.directories': 'Directory Bindings',
+        'pool.priority': 'Priority',
+        'pool.weight': 'Weight',
+        'pool.empty': 'No account pools',
+        'pool.no_pool': 'No pool',
+        'pool.name_required': 'Please enter a pool name',
+        'pool.created': 'Pool created',
+        'pool.deleted': 'Pool deleted',
+        'pool.delete_confirm': 'Delete pool {name}? Bound directories will be unbound',
+        'pool.load_failed': 'Failed to load account pools',
+        'pool.save_failed': 'Failed to save account pool',
+        'pool.bound': 'Directory pool binding updated',
+        'pool.bound_switched': 'Directory bound to the pool and switched to account {to}',
+        'pool.failover_line': 'Pool {pool}: account {from} of directory {directory} is unavailable',
+        'pool.switched_to': 'switched to {to}',
+        'pool.no_available': 'no other available account in the pool',
+        'pool.failovers': 'Failover History',
+        'pool.no_failovers': 'No failovers recorded',
+        'relay.title': 'Local Relay Proxy',
+        'relay.port': 'Port',
+        'relay.start': 'Start',
+        'relay.stop': 'Stop',
+        'relay.running': 'Running: {url}',
+        'relay.stopped': 'Not running',
+        'relay.via_relay': 'Relay',
+        'relay.via_relay_hint': "Point the directory's ANTHROPIC_BASE_URL at the local relay, which rotates pool accounts and injects their tokens",
+        'relay.stat_line': 'Relayed {requests} requests, {failures} failed, avg {latency} ms',
+        'relay.directory_updated': 'Directory configuration updated',
+        'relay.failed': 'Relay operation failed',
+        'lifecycle.disabled': 'Disabled',
+        'lifecycle.expired': 'Expired',
+        'lifecycle.expires_in': 'Expires in {days} days',
+        'lifecycle.expires_at': 'Expires',
+        'lifecycle.never_used': 'never',
+        'lifecycle.usage': 'Switched {count} times · Last used: {time}',
+        'lifecycle.expiry_warning': '{count} account(s) expired or expiring soon: {names}',
+        'lifecycle.filter_status': 'Status',
+        'lifecycle.filter_all': 'All',
+        'lifecycle.filter_enabled': 'Enabled',
+        'lifecycle.filter_disabled': 'Disabled',
+        'lifecycle.filter_expiring': 'Expiring soon',
+        'lifecycle.tags': 'Tags',
+        'lifecycle.tag_filter_placeholder': 'Filter by tag',
+        'lifecycle.expires_at_label': 'Expiry Date',
+        'lifecycle.expires_at_help': 'Leave empty for no expiry',
+        'lifecycle.tags_placeholder': 'Separate multiple tags with commas',
+        'lifecycle.notes': 'Notes',
+        'lifecycle.enabled_label': 'Enable account',
+        'lifecycle.enabled_help': 'Disabled accounts stay in the list but are hidden from switching',
+        'import.title': 'Import Preview',
+        'import.detected_format': 'Detected format: {format}. {new} new, {changed} changed, {identical} identical or duplicate',
+        'import.header_kind': 'Type',
+        'import.header_name': 'Name',
+        'import.header_status': 'Status',
+        'import.header_diff': 'Differences',
+        'import.header_strategy': 'Handling',
+        'import.header_result': 'Result',
+        'import.header_detail': 'Detail',
+        'import.kind.account': 'Account',
+        'import.kind.base_url': 'API URL',
+        'import.kind.directory': 'Directory',
+        'import.kind.binding': 'Binding',
+        'import.status.new': 'New',
+        'import.status.identical': 'Identical',
+        'import.status.changed': 'Changed',
+        'import.status.duplicate': 'Duplicate in file',
+        'import.strategy.prompt': 'How to handle items that differ from existing records',
+        'import.strategy.default': 'Use the option above',
+        'import.strategy.skip': 'Skip, keep existing',
+        'import.strategy.overwrite': 'Overwrite existing',
+        'import.strategy.rename': 'Import under a new name (name conflicts only)',
+        'import.strategy.merge': 'Merge, only fill in missing fields',
+        'import.action.created': 'Created',
+        'import.action.renamed': 'Created under new name',
+        'import.action.overwritten': 'Overwritten',
+        'import.action.merged': 'Merged',
+        'import.action.unchanged': 'Unchanged',
+        'import.action.skipped': 'Skipped',
+        'import.action.failed': 'Failed',
+        'import.summary': 'Import finished: {created} created, {updated} updated, {skipped} skipped, {failed} failed',
+        'import.confirm': 'Import',
+        'import.format.providers': 'Provider list (providers)',
+        'import.format.backup': 'Backup from this app',
+        'export.format': 'Export format',
+        'export.format.json': 'Full JSON (accounts, API URLs, directories and bindings)',
+        'export.format.dotenv': '.env files (one per account)',
+        'export.format.shell': 'Sourceable shell script',
+        'export.filter_by_tag': 'Filter by tag',
+        'export.all_tags': 'All tags',
+        'proxies.title': 'Proxies',
+        'proxies.add': 'Add Proxy',
+        'proxies.edit': 'Edit Proxy',
+        'proxies.help': 'Proxies can be bound to URLs, accounts or directories; switching writes HTTP_PROXY / HTTPS_PROXY / NO_PROXY (directory first, then account, then URL). WebDAV configs bound to a proxy sync through it too',
+        'proxies.empty': 'No proxies',
+        'proxies.name': 'Proxy Name',
+        'proxies.url': 'Proxy URL',
+        'proxies.url_help': 'Supports http://, https://, socks5:// and socks5h:// URLs',
+        'proxies.no_proxy': 'No Proxy',
+        'proxies.no_proxy_help': 'Comma separated, written to NO_PROXY',
+        'proxies.username': 'Username',
+        'proxies.password': 'Password',
+        'proxies.description': 'Description',
+        'proxies.with_auth': 'Auth',
+        'proxies.select_label': 'Proxy',
+        'proxies.none': 'No proxy',
+        'proxies.account_help': 'Used when switching to this account if the directory has no proxy',
+        'proxies.directory_help': 'Used when switching in this directory, takes precedence over account and URL proxies',
+        'proxies.url_bind_help': 'Accounts on this URL use this proxy unless they have their own; health checks go through it too',
+        'proxies.webdav_help': 'Proxy used to reach this WebDAV server',
+        'proxies.test': 'Test proxy',
+        'proxies.testing': 'Testing proxy {name}...',
+        'proxies.test_success': 'Proxy {name} works, took {ms} ms',
+        'proxies.test_failed': 'Proxy {name} is unavailable',
+        'proxies.saved': 'Proxy saved',
+        'proxies.save_failed': 'Failed to save proxy',
+        'proxies.load_failed': 'Failed to load proxies',
+        'proxies.delete_title': 'Delete Proxy',
+        'proxies.delete_confirm': 'Delete proxy "{name}"? URLs, accounts, directories and WebDAV configs using it will be unbound.',
+        'proxies.deleted': 'Proxy deleted',
+        'proxies.delete_failed': 'Failed to delete proxy',
+        'mcp.title': 'MCP Servers',
+        'mcp.add': 'Add Server',
+        'mcp.edit': 'Edit Server',
+        'mcp.help': 'MCP servers checked on a directory or account are written to the project .mcp.json when switching (directory and account selections are combined)',
+        'mcp.empty': 'No MCP servers',
+        'mcp.name': 'Server Name',
+        'mcp.transport': 'Transport',
+        'mcp.target': 'Command / URL',
+        'mcp.command': 'Command',
+        'mcp.args': 'Arguments',
+        'mcp.args_help': 'One argument per line',
+        'mcp.env': 'Environment Variables',
+        'mcp.env_help': 'JSON object, optional',
+        'mcp.env_invalid': 'Environment variables must be a JSON object',
+        'mcp.url': 'Server URL',
+        'mcp.description': 'Description',
+        'mcp.select_label': 'MCP Servers',
+        'mcp.account_help': 'Checked servers are written when switching to this account, combined with the directory selection',
+        'mcp.directory_help': 'Checked servers are written when switching to this directory, combined with the account selection',
+        'mcp.saved': 'MCP server saved',
+        'mcp.save_failed': 'Failed to save MCP server',
+        'mcp.load_failed': 'Failed to load MCP servers',
+        'mcp.delete_title': 'Delete MCP Server',
+        'mcp.delete_confirm': 'Delete MCP server "{name}"? Directories and accounts that use it will no longer write it.',
+        'mcp.deleted': 'MCP server deleted',
+        'mcp.delete_failed': 'Failed to delete MCP server',
+        'hooks.title': 'Hooks',
+        'hooks.add': 'Add Hook',
+        'hooks.edit': 'Edit Hook',
+        'hooks.help': 'Global hooks apply to every directory; other hooks apply to directories that check them. They are merged into the hooks section of settings.local.json when switching',
+        'hooks.empty': 'No hooks',
+        'hooks.name': 'Hook Name',
+        'hooks.event': 'Event',
+        'hooks.matcher': 'Tool Matcher',
+        'hooks.matcher_help': 'Matches tool names; leave empty to match all tools',
+        'hooks.command': 'Command',
+        'hooks.timeout': 'Timeout (seconds)',
+        'hooks.timeout_invalid': 'Timeout must be a positive integer',
+        'hooks.is_global': 'Apply to all directories',
+        'hooks.global': 'Global',
+        'hooks.description': 'Description',
+        'hooks.select_label': 'Hooks',
+        'hooks.directory_help': 'Checked hooks are written when switching to this directory; global hooks are always written',
+        'hooks.saved': 'Hook saved',
+        'hooks.save_failed': 'Failed to save hook',
+        'hooks.load_failed': 'Failed to load hooks',
+        'hooks.delete_title': 'Delete Hook',
+        'hooks.delete_confirm': 'Delete hook "{name}"? Directories that use it will no longer write it.',
+        'hooks.deleted': 'Hook deleted',
+        'hooks.delete_failed': 'Failed to delete hook',
+        'definitions.title': 'Commands & Subagents',
+        'definitions.add': 'Add Definition',
+        'definitions.edit': 'Edit Definition',
+        'definitions.help': 'Definitions enabled for a directory are written to .claude/commands and .claude/agents when switching; default definitions apply to directories without their own selection',
+        'definitions.empty': 'No definitions',
+        'definitions.kind': 'Type',
+        'definitions.kind_command': 'Slash command',
+        'definitions.kind_agent': 'Subagent',
+        'definitions.name': 'Name',
+        'definitions.name_help': 'Command names may use / for subdirectories; subagent names may not contain /',
+        'definitions.description': 'Description',
+        'definitions.description_placeholder': 'Leave empty to use the description from the front-matter',
+        'definitions.content': 'Content',
+        'definitions.content_help': 'Full Markdown file content; template variables such as {{ACCOUNT_NAME}} are supported',
+        'definitions.is_default': 'Enabled by default',
+        'definitions.default': 'Default',
+        'definitions.select_label': 'Commands & Subagents',
+        'definitions.directory_help': 'Checked definitions are written when switching to this directory',
+        'definitions.import': 'Import from Project',
+        'definitions.import_select': 'Select a project containing a .claude directory',
+        'definitions.import_overwrite': 'Overwrite existing',
+        'definitions.import_failed': 'Failed to import definitions',
+        'definitions.saved': 'Definition saved',
+        'definitions.save_failed': 'Failed to save definition',
+        'definitions.load_failed': 'Failed to load definitions',
+        'definitions.delete_title': 'Delete Definition',
+        'definitions.delete_confirm': 'Delete definition "{name}"? Files already written to projects are removed on the next switch.',
+        'definitions.deleted': 'Definition deleted',
+        'definitions.delete_failed': 'Failed to delete definition',
+        'providers.select_label': 'Provider',
+        'providers.url_help': 'For Bedrock / Vertex the URL is written to the provider BASE_URL variable; set region, project and similar values in the account custom env vars',
+        'providers.account_hint': '{name} accounts need these custom env vars: {required} (optional: {optional})',
+        'providers.invalid': 'Incomplete account settings',
+        'key_helper.mode_label': 'Credential mode',
+        'key_helper.mode_token': 'Static token',
+        'key_helper.mode_helper': 'Helper command (apiKeyHelper)',
+        'key_helper.command': 'Helper command',
+        'key_helper.command_placeholder': 'e.g. ~/bin/get-claude-key.sh',
+        'key_helper.command_help': 'The command output is used as the API key; switching writes apiKeyHelper instead of a static token',
+        'key_helper.command_required': 'A command is required in helper mode',
+        'key_helper.ttl': 'Cache TTL (ms)',
+        'key_helper.ttl_placeholder': 'Leave empty for the Claude Code default',
+        'key_helper.invalid_ttl': 'TTL must be a positive integer',
+        'key_helper.test': 'Test run',
+        'key_helper.test_ok': 'Helper works, generated key: {key}',
+        'key_helper.test_failed': 'Helper command failed',
+        'headers.label': 'Custom headers',
+        'headers.placeholder': 'X-Tenant-Id: team-a',
+        'headers.url_help': 'One Name: Value per line. Written to ANTHROPIC_CUSTOM_HEADERS on switch and sent with health checks',
+        'headers.account_help': 'One Name: Value per line. Overrides headers with the same name set on the URL',
+        'headers.invalid_line': 'Invalid header, expected Name: Value: {line}',
+        'migrate.schema_version': 'Schema Version',
+        'migrate.up_to_date': 'Up to date',
+        'migrate.pending': '{} pending migrations',
+        'migrate.too_new': 'Database is newer than this app',
+        'discovery.open': 'Discover',
+        'discovery.title': 'Discover Existing Claude Projects',
+        'discovery.help': 'Scans roots for folders containing a .claude directory or CLAUDE.md. Supports ~ and * wildcards, e.g. ~/code/*',
+        'discovery.roots': 'Scan roots',
+        'discovery.add_root': 'Add',
+        'discovery.scan': 'Scan',
+        'discovery.no_roots': 'No scan roots configured',
+        'discovery.load_roots_failed': 'Failed to load scan roots',
+        'discovery.scan_hint': 'Add a scan root and click Scan',
+        'discovery.scanning': 'Scanning...',
+        'discovery.scan_failed': 'Scan failed',
+        'discovery.no_projects': 'No folders with a .claude directory or CLAUDE.md found',
+        'discovery.registered': 'Registered',
+        'discovery.new_token': 'Unregistered token',
+        'discovery.create_accounts': 'Also create accounts and base URLs for unregistered tokens',
+        'discovery.import': 'Import Selected',
+        'discovery.none_selected': 'Select at least one project',
+        'discovery.import_success': 'Import finished: {directories} directories, {accounts} accounts and {base_urls} base URLs created',
+        'discovery.import_failed': 'Import failed',
+        'drift.badge': 'Modified externally',
+        'drift.hint': 'No longer matches account {account}',
+        'drift.fix': 'Restore',
+        'drift.fix_failed': 'Failed to restore configuration',
+        'drift.notifications': 'Notify on external changes',
+        'drift.kind.missing_directory': 'Directory missing',
+        'drift.kind.missing_settings': 'settings.local.json missing',
+        'drift.kind.invalid_settings': 'Invalid settings.local.json',
+        'drift.kind.stale_token': 'Token changed',
+        'drift.kind.env_changed': 'Environment variable changed',
+        'drift.kind.missing_asset': 'File missing',
+        'drift.kind.external_edit': 'File modified externally',
+        'drift.scan': 'Check Config',
+        'drift.scan_found': '{count} directories no longer match their account',
+        'drift.scan_clean': 'All directories match their account',
+        'drift.scan_failed': 'Failed to check configuration',
+        'drift.fix_all': 'Restore All',
+        'drift.fix_all_confirm': 'Restore the configuration of {count} directories from their current account? External changes will be overwritten.',
         'success.cleanup_directories': 'Successfully cleaned up {count} invalid directories',
         'success.cleanup_partial': 'Cleanup completed: {success} succeeded, {failed} failed\nFailure details:\n{errors}',
         'success.webdav_created': 'WebDAV configuration created successfully',
@@ -898,6 +1554,32 @@ const translations = {
         'claude.permission_mode_bypass_short': 'Bypass Permissions',
         'claude.permission_mode_accept_short': 'Auto Accept Edits',
         'claude.permission_mode_normal_short': 'Normal Mode',
+        'claude.permission_mode_plan_short': 'Plan Mode',
+        'permission_presets.title': 'Permission Presets',
+        'permission_presets.add': 'Add Preset',
+        'permission_presets.edit': 'Edit Preset',
+        'permission_presets.help': 'When a directory or account has a preset, switching uses it to replace the permissions section (directory takes precedence over account)',
+        'permission_presets.empty': 'No permission presets',
+        'permission_presets.name': 'Preset Name',
+        'permission_presets.rules': 'Rules',
+        'permission_presets.builtin': 'Built-in',
+        'permission_presets.allow': 'Allow',
+        'permission_presets.deny': 'Deny',
+        'permission_presets.ask': 'Ask',
+        'permission_presets.description': 'Description',
+        'permission_presets.rules_help': 'One rule per line, e.g. Read, Bash(npm run test:*), WebFetch(domain:example.com), mcp__github',
+        'permission_presets.select_label': 'Permission Preset',
+        'permission_presets.none': 'No preset',
+        'permission_presets.account_help': 'Used when switching to this account if the directory has no preset',
+        'permission_presets.directory_help': 'Used when switching to this directory; takes precedence over the account preset',
+        'permission_presets.invalid_rule': 'Invalid permission rule',
+        'permission_presets.load_failed': 'Failed to load permission presets',
+        'permission_presets.saved': 'Permission preset saved',
+        'permission_presets.save_failed': 'Failed to save permission preset',
+        'permission_presets.delete_title': 'Delete Permission Preset',
+        'permission_presets.delete_confirm': 'Delete permission preset "{name}"? Directories and accounts using it will be unassigned',
+        'permission_presets.deleted': 'Permission preset deleted',
+        'permission_presets.delete_failed': 'Failed to delete permission preset',
 
         // WebDAV
         'webdav.load_failed': 'Failed to load WebDAV configurations',
@@ -1012,8 +1694,6 @@ const translations = {
         'text.webdav_list_failed': 'Failed to get file list',
         'text.webdav_confirm_delete': 'Are you sure you want to delete WebDAV configuration "{name}"?',
         'text.webdav_confirm_delete_title': 'Confirm Delete',
-        'text.webdav_confirm_download': 'Are you sure you want to download and apply configuration file "{filename}"?\nThis will overwrite the current configuration!',
-        'text.webdav_confirm_download_title': 'Confirm Download',
         'text.url_name_exists': 'URL name already exists, please use a different name',
         'text.url_address_exists': 'URL address already exists, please use a different URL',
         'text.account_name_exists_hint': 'Account name already exists, please use a different name',
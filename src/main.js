@@ -1,5 +1,6 @@
 const { invoke } = window.__TAURI__.core;
 const { open, ask, save } = window.__TAURI__.dialog;
+const { listen } = window.__TAURI__.event;
 
 // 全局变量跟踪测试状态
 let lastTestedConnection = null;
@@ -58,30 +59,53 @@ async function customConfirm(message, title = window.i18n.t('confirm.default_tit
 // Global variables
 let accounts = [];
 let directories = [];
+let driftedDirectories = {}; // 文件监听检测到的漂移目录，按目录 ID 索引
 let baseUrls = [];
+let latestEndpointChecks = {}; // 每个 API 地址最近一次的健康检查结果，按 base_url_id 索引
+let balanceAdapters = {}; // 余额查询配置，按 base_url_id 索引
+let providerTypes = []; // 供应商类型及其环境变量定义
+let accountBalances = {}; // 账号余额缓存，按 account_id 索引
+let accountPools = []; // 账号池列表
 let currentAccountPage = 1;
 let accountsPerPage = 5;
 let currentAccountFilter = {
     search: '',
-    base_url: ''
+    base_url: '',
+    status: '',
+    tag: ''
 };
+const EXPIRY_WARNING_DAYS = 7; // 与后端 EXPIRY_WARNING_DAYS 保持一致
 let currentDirectoryForAssociation = null;
 let associationAccounts = [];
 let associationDirectories = [];
 let currentPaginationData = null; // 存储当前分页数据用于语言切换时重新渲染
+let permissionPresets = [];
+let editingPermissionPresetId = null;
+let proxies = [];
+let editingProxyId = null;
+let mcpServers = [];
+let editingMcpServerId = null;
+let hooks = [];
+let editingHookId = null;
+let definitions = [];
+let editingDefinitionId = null;
 
 // Tauri command wrappers
 async function tauriGetAccounts(params = {}) {
     return await invoke('get_accounts', { request: params });
 }
 
-async function tauriCreateAccount(name, token, base_url, model, customEnvVars = null) {
+async function tauriCreateAccount(name, token, base_url, model, customEnvVars = null, lifecycle = {}) {
     return await invoke('create_account', {
         name,
         token,
         baseUrl: base_url,
         model,
-        customEnvVars
+        customEnvVars,
+        enabled: lifecycle.enabled,
+        expiresAt: lifecycle.expires_at,
+        notes: lifecycle.notes,
+        tags: lifecycle.tags
     });
 }
 
@@ -93,6 +117,8 @@ async function tauriUpdateAccount(id, params) {
             requestParams['baseUrl'] = params[key];
         } else if (key === 'custom_env_vars') {
             requestParams['customEnvVars'] = params[key];
+        } else if (key === 'expires_at') {
+            requestParams['expiresAt'] = params[key];
         } else {
             requestParams[key] = params[key];
         }
@@ -104,6 +130,10 @@ async function tauriDeleteAccount(id) {
     return await invoke('delete_account', { id });
 }
 
+async function tauriGetExpiringAccounts() {
+    return await invoke('get_expiring_accounts');
+}
+
 async function tauriGetAccountBaseUrls() {
     return await invoke('get_account_base_urls');
 }
@@ -128,16 +158,317 @@ async function tauriCheckDirectoryExists(path) {
     return await invoke('check_directory_exists', { path });
 }
 
+async function tauriCheckBaseUrlHealth(baseUrlId, accountId) {
+    return await invoke('check_base_url_health', { baseUrlId, accountId });
+}
+
+async function tauriVerifyAccountToken(id) {
+    return await invoke('verify_account_token', { id });
+}
+
+async function tauriVerifyAllAccountTokens() {
+    return await invoke('verify_all_account_tokens');
+}
+
+async function tauriGetBalanceAdapters() {
+    return await invoke('get_balance_adapters');
+}
+
+async function tauriSaveBalanceAdapter(request) {
+    return await invoke('save_balance_adapter', { request });
+}
+
+async function tauriDeleteBalanceAdapter(baseUrlId) {
+    return await invoke('delete_balance_adapter', { baseUrlId });
+}
+
+async function tauriGetAccountBalances() {
+    return await invoke('get_account_balances');
+}
+
+async function tauriRefreshAccountBalances() {
+    return await invoke('refresh_account_balances');
+}
+
+async function tauriGetAccountPools() {
+    return await invoke('get_account_pools');
+}
+
+async function tauriCreateAccountPool(request) {
+    return await invoke('create_account_pool', { request });
+}
+
+async function tauriDeleteAccountPool(id) {
+    return await invoke('delete_account_pool', { id });
+}
+
+async function tauriGetAccountPoolMembers(poolId) {
+    return await invoke('get_account_pool_members', { poolId });
+}
+
+async function tauriSaveAccountPoolMember(request) {
+    return await invoke('save_account_pool_member', { request });
+}
+
+async function tauriRemoveAccountPoolMember(poolId, accountId) {
+    return await invoke('remove_account_pool_member', { poolId, accountId });
+}
+
+async function tauriBindDirectoryAccountPool(directoryId, poolId) {
+    return await invoke('bind_directory_account_pool', { directoryId, poolId });
+}
+
+async function tauriGetPoolFailovers(poolId, limit) {
+    return await invoke('get_pool_failovers', { poolId, limit });
+}
+
+async function tauriGetRelayStatus() {
+    return await invoke('get_relay_status');
+}
+
+async function tauriStartRelay(port) {
+    return await invoke('start_relay', { port });
+}
+
+async function tauriStopRelay() {
+    return await invoke('stop_relay');
+}
+
+async function tauriGetRelayStats() {
+    return await invoke('get_relay_stats');
+}
+
+async function tauriSetDirectoryRelay(directoryId, port) {
+    return await invoke('set_directory_relay', { directoryId, port });
+}
+
+async function tauriGetEndpointChecks(baseUrlId) {
+    return await invoke('get_endpoint_checks', { baseUrlId, limit: 20 });
+}
+
+async function tauriGetLatestEndpointChecks() {
+    return await invoke('get_latest_endpoint_checks');
+}
+
+async function tauriGetDiscoveryRoots() {
+    return await invoke('get_discovery_roots');
+}
+
+async function tauriAddDiscoveryRoot(path) {
+    return await invoke('add_discovery_root', { path });
+}
+
+async function tauriDeleteDiscoveryRoot(id) {
+    return await invoke('delete_discovery_root', { id });
+}
+
+async function tauriDiscoverProjects() {
+    return await invoke('discover_projects', { roots: null });
+}
+
+async function tauriImportDiscoveredProjects(projects, createAccounts) {
+    return await invoke('import_discovered_projects', { projects, createAccounts });
+}
+
+async function tauriPreviewAccountImport(fileName, content) {
+    return await invoke('preview_account_import', { fileName, content });
+}
+
+async function tauriImportAccounts(bundle, items, strategy) {
+    return await invoke('import_accounts', { bundle, items, strategy });
+}
+
+async function tauriExportAccounts(format, filter, outputPath) {
+    return await invoke('export_accounts', { format, filter, outputPath });
+}
+
+async function tauriGetDriftedDirectories() {
+    return await invoke('get_drifted_directories');
+}
+
+async function tauriScanConfigDrift() {
+    return await invoke('scan_config_drift');
+}
+
+async function tauriFixConfigDrift(directoryId) {
+    return await invoke('fix_config_drift', { directoryId });
+}
+
+async function tauriSetDriftNotifications(enabled) {
+    return await invoke('set_drift_notifications', { enabled });
+}
+
+async function tauriGetPermissionPresets() {
+    return await invoke('get_permission_presets');
+}
+
+async function tauriCreatePermissionPreset(params) {
+    return await invoke('create_permission_preset', params);
+}
+
+async function tauriUpdatePermissionPreset(id, params) {
+    return await invoke('update_permission_preset', { id, ...params });
+}
+
+async function tauriDeletePermissionPreset(id) {
+    return await invoke('delete_permission_preset', { id });
+}
+
+async function tauriSetDirectoryPermissionPreset(directoryId, presetId) {
+    return await invoke('set_directory_permission_preset', { directoryId, presetId });
+}
+
+async function tauriSetAccountPermissionPreset(accountId, presetId) {
+    return await invoke('set_account_permission_preset', { accountId, presetId });
+}
+
+async function tauriValidatePermissionRule(rule) {
+    return await invoke('validate_permission_rule', { rule });
+}
+
+async function tauriGetProxies() {
+    return await invoke('get_proxies');
+}
+
+async function tauriCreateProxy(params) {
+    return await invoke('create_proxy', params);
+}
+
+async function tauriUpdateProxy(id, params) {
+    return await invoke('update_proxy', { id, ...params });
+}
+
+async function tauriDeleteProxy(id) {
+    return await invoke('delete_proxy', { id });
+}
+
+async function tauriTestProxy(id, targetUrl = null) {
+    return await invoke('test_proxy', { id, targetUrl });
+}
+
+async function tauriSetBaseUrlProxy(baseUrlId, proxyId) {
+    return await invoke('set_base_url_proxy', { baseUrlId, proxyId });
+}
+
+async function tauriSetAccountCredential(accountId, credentialMode, apiKeyHelper, apiKeyHelperTtlMs) {
+    return await invoke('set_account_credential', { accountId, credentialMode, apiKeyHelper, apiKeyHelperTtlMs });
+}
+
+async function tauriTestApiKeyHelper(command) {
+    return await invoke('test_api_key_helper', { command });
+}
+
+async function tauriSetAccountProxy(accountId, proxyId) {
+    return await invoke('set_account_proxy', { accountId, proxyId });
+}
+
+async function tauriSetBaseUrlHeaders(baseUrlId, headers) {
+    return await invoke('set_base_url_headers', { baseUrlId, headers });
+}
+
+async function tauriSetAccountHeaders(accountId, headers) {
+    return await invoke('set_account_headers', { accountId, headers });
+}
+
+async function tauriSetDirectoryProxy(directoryId, proxyId) {
+    return await invoke('set_directory_proxy', { directoryId, proxyId });
+}
+
+async function tauriGetMcpServers() {
+    return await invoke('get_mcp_servers');
+}
+
+async function tauriCreateMcpServer(params) {
+    return await invoke('create_mcp_server', params);
+}
+
+async function tauriUpdateMcpServer(id, params) {
+    return await invoke('update_mcp_server', { id, ...params });
+}
+
+async function tauriDeleteMcpServer(id) {
+    return await invoke('delete_mcp_server', { id });
+}
+
+async function tauriGetDirectoryMcpServers(directoryId) {
+    return await invoke('get_directory_mcp_servers', { directoryId });
+}
+
+async function tauriSetDirectoryMcpServers(directoryId, serverIds) {
+    return await invoke('set_directory_mcp_servers', { directoryId, serverIds });
+}
+
+async function tauriGetAccountMcpServers(accountId) {
+    return await invoke('get_account_mcp_servers', { accountId });
+}
+
+async function tauriSetAccountMcpServers(accountId, serverIds) {
+    return await invoke('set_account_mcp_servers', { accountId, serverIds });
+}
+
+async function tauriGetHooks() {
+    return await invoke('get_hooks');
+}
+
+async function tauriCreateHook(params) {
+    return await invoke('create_hook', params);
+}
+
+async function tauriUpdateHook(id, params) {
+    return await invoke('update_hook', { id, ...params });
+}
+
+async function tauriDeleteHook(id) {
+    return await invoke('delete_hook', { id });
+}
+
+async function tauriGetDirectoryHooks(directoryId) {
+    return await invoke('get_directory_hooks', { directoryId });
+}
+
+async function tauriSetDirectoryHooks(directoryId, hookIds) {
+    return await invoke('set_directory_hooks', { directoryId, hookIds });
+}
+
+async function tauriGetClaudeDefinitions() {
+    return await invoke('get_claude_definitions');
+}
+
+async function tauriCreateClaudeDefinition(params) {
+    return await invoke('create_claude_definition', params);
+}
+
+async function tauriUpdateClaudeDefinition(id, params) {
+    return await invoke('update_claude_definition', { id, ...params });
+}
+
+async function tauriDeleteClaudeDefinition(id) {
+    return await invoke('delete_claude_definition', { id });
+}
+
+async function tauriGetDirectoryDefinitions(directoryId) {
+    return await invoke('get_directory_definitions', { directoryId });
+}
+
+async function tauriSetDirectoryDefinitions(directoryId, definitionIds) {
+    return await invoke('set_directory_definitions', { directoryId, definitionIds });
+}
+
+async function tauriImportClaudeDefinitions(path, overwrite) {
+    return await invoke('import_claude_definitions', { path, overwrite });
+}
+
 async function tauriGetBaseUrls() {
     return await invoke('get_base_urls');
 }
 
-async function tauriCreateBaseUrl(name, url, description, api_key, is_default, defaultEnvVars = null) {
+async function tauriCreateBaseUrl(name, url, description, api_key, is_default, defaultEnvVars = null, provider_type = null) {
     return await invoke('create_base_url', {
         name,
         url,
         description,
         apiKey: api_key,
+        providerType: provider_type,
         isDefault: is_default,
         defaultEnvVars
     });
@@ -150,6 +481,7 @@ async function tauriUpdateBaseUrl(id, params) {
     if (params.url !== undefined) requestParams.url = params.url;
     if (params.description !== undefined) requestParams.description = params.description;
     if (params.api_key !== undefined) requestParams.apiKey = params.api_key;
+    if (params.provider_type !== undefined) requestParams.providerType = params.provider_type;
     if (params.is_default !== undefined) requestParams.isDefault = params.is_default;
     if (params.default_env_vars !== undefined) requestParams.defaultEnvVars = params.default_env_vars;
     return await invoke('update_base_url', requestParams);
@@ -159,6 +491,14 @@ async function tauriDeleteBaseUrl(id) {
     return await invoke('delete_base_url', { id });
 }
 
+async function tauriGetProviderTypes() {
+    return await invoke('get_provider_types');
+}
+
+async function tauriValidateAccountProviderEnv(baseUrl, token, customEnvVars) {
+    return await invoke('validate_account_provider_env', { baseUrl, token, customEnvVars });
+}
+
 async function tauriCheckClaudeLocalMdExists(directory_path) {
     return await invoke('check_claude_local_md_exists', {
         directoryPath: directory_path
@@ -209,6 +549,10 @@ async function tauriGetDatabaseInfo() {
     return await invoke('get_database_info');
 }
 
+async function tauriGetMigrationStatus() {
+    return await invoke('get_migration_status');
+}
+
 // API call function for consistency
 async function apiCall(fn, params = {}) {
     try {
@@ -253,9 +597,28 @@ async function loadAccounts(page = 1, resetPage = false) {
         if (currentAccountFilter.base_url) {
             params.base_url = currentAccountFilter.base_url;
         }
+
+        if (currentAccountFilter.status === 'enabled') {
+            params.enabled = true;
+        } else if (currentAccountFilter.status === 'disabled') {
+            params.enabled = false;
+        } else if (currentAccountFilter.status === 'expiring') {
+            params.expiring_within_days = EXPIRY_WARNING_DAYS;
+        }
+
+        if (currentAccountFilter.tag) {
+            params.tag = currentAccountFilter.tag;
+        }
         
         const response = await tauriGetAccounts(params);
         accounts = response.accounts || [];
+
+        try {
+            const balances = await tauriGetAccountBalances();
+            accountBalances = Object.fromEntries(balances.map(balance => [balance.account_id, balance]));
+        } catch (error) {
+            console.warn('获取账号余额失败:', error);
+        }
         
         await renderAccounts();
         renderAccountsPagination(response.pagination);
@@ -290,12 +653,21 @@ async function renderAccounts() {
                         ${account.has_associations ? '<span class="association-indicator me-2"></span>' : ''}
                         ${account.name}
                         ${account.is_active ? '<span class="badge bg-success ms-2">' + window.i18n.t('text.current_active') + '</span>' : ''}
+                        ${account.enabled ? '' : '<span class="badge bg-secondary ms-2">' + window.i18n.t('lifecycle.disabled') + '</span>'}
+                        ${renderAccountExpiryBadge(account)}
                     </div>
-                    <div class="small token-preview">${account.token.substring(0, 20)}...</div>
+                    ${account.credential_mode === 'helper'
+                        ? `<div class="small token-preview"><i class="fas fa-terminal me-1"></i>apiKeyHelper: ${account.api_key_helper || ''}</div>`
+                        : `<div class="small token-preview">${account.token.substring(0, 20)}...</div>`}
                     <div class="small">${account.base_url}</div>
                     ${account.model ? '<div class="small text-muted"><i class="fas fa-microchip me-1"></i>' + window.i18n.t('accounts.model') + ': ' + account.model + '</div>' : ''}
+                    ${renderCustomHeadersBadge(account.custom_headers)}
+                    ${renderTokenStatusBadge(account)}
+                    ${renderAccountBalance(accountBalances[account.id])}
+                    ${renderAccountLifecycle(account)}
                 </div>
                 <div class="account-actions">
+                    <button class="btn btn-sm btn-outline-info" onclick="verifyAccountToken(${account.id}, this)">${window.i18n.t('token.verify')}</button>
                     <button class="btn btn-sm btn-outline-primary" onclick="editAccount(${account.id})">${window.i18n.t('text.edit')}</button>
                     <button class="btn btn-sm btn-outline-danger" onclick="promptDeleteAccount(${account.id})">${window.i18n.t('text.delete')}</button>
                 </div>
@@ -322,6 +694,138 @@ async function renderAccounts() {
     }
 }
 
+function renderTokenStatusBadge(account) {
+    const styles = { valid: 'bg-success', invalid: 'bg-danger', error: 'bg-warning text-dark' };
+    if (!account.token_status) {
+        return `<div class="small"><span class="badge bg-secondary">${window.i18n.t('token.unchecked')}</span></div>`;
+    }
+    const time = account.token_checked_at ? new Date(account.token_checked_at).toLocaleString() : '';
+    return `<div class="small"><span class="badge ${styles[account.token_status] || 'bg-secondary'}" title="${escapeHtml(account.token_error)}">${window.i18n.t('token.' + account.token_status)}</span> <span class="text-muted">${time}</span></div>`;
+}
+
+function renderAccountExpiryBadge(account) {
+    if (!account.expires_at) {
+        return '';
+    }
+    const expiresAt = new Date(account.expires_at);
+    const days = Math.floor((expiresAt - Date.now()) / 86400000);
+    const date = account.expires_at.substring(0, 10);
+    if (expiresAt <= Date.now()) {
+        return `<span class="badge bg-danger ms-2">${window.i18n.t('lifecycle.expired')} ${date}</span>`;
+    }
+    if (days < EXPIRY_WARNING_DAYS) {
+        return `<span class="badge bg-warning text-dark ms-2">${window.i18n.t('lifecycle.expires_in').replace('{days}', days)}</span>`;
+    }
+    return `<span class="badge bg-light text-dark ms-2">${window.i18n.t('lifecycle.expires_at')} ${date}</span>`;
+}
+
+// 标签、备注与使用情况
+function renderAccountLifecycle(account) {
+    const tags = (account.tags || '').split(',').map(tag => tag.trim()).filter(Boolean);
+    const tagsHtml = tags.length > 0
+        ? `<div class="small"><i class="fas fa-tags me-1 text-muted"></i>${tags.map(tag => `<span class="badge bg-info text-dark me-1">${escapeHtml(tag)}</span>`).join('')}</div>`
+        : '';
+    const notesHtml = account.notes
+        ? `<div class="small text-muted"><i class="fas fa-sticky-note me-1"></i>${escapeHtml(account.notes)}</div>`
+        : '';
+    const lastUsed = account.last_used_at ? new Date(account.last_used_at).toLocaleString() : window.i18n.t('lifecycle.never_used');
+    const usageHtml = `<div class="small text-muted"><i class="fas fa-history me-1"></i>${window.i18n.t('lifecycle.usage').replace('{count}', account.use_count || 0).replace('{time}', lastUsed)}</div>`;
+    return tagsHtml + notesHtml + usageHtml;
+}
+
+// 读取账号弹窗中的生命周期字段
+function getAccountLifecycleForm() {
+    return {
+        enabled: document.getElementById('accountEnabled').checked,
+        expires_at: document.getElementById('accountExpiresAt').value,
+        notes: document.getElementById('accountNotes').value.trim(),
+        tags: document.getElementById('accountTags').value.split(',').map(tag => tag.trim()).filter(Boolean)
+    };
+}
+
+// 启动时提示已过期或即将到期的账号
+async function checkExpiringAccounts() {
+    try {
+        const expiring = await tauriGetExpiringAccounts();
+        if (expiring.length > 0) {
+            showGlobalMessage(window.i18n.t('lifecycle.expiry_warning')
+                .replace('{count}', expiring.length)
+                .replace('{names}', expiring.map(account => escapeHtml(account.name)).join(', ')), 'warning', 0);
+        }
+    } catch (error) {
+        console.warn('获取即将到期账号失败:', error);
+    }
+}
+
+async function verifyAccountToken(id, button) {
+    if (button) {
+        button.disabled = true;
+    }
+    try {
+        const account = await tauriVerifyAccountToken(id);
+        if (account.token_status === 'valid') {
+            showSuccess(window.i18n.t('token.verify_success').replace('{name}', account.name));
+        } else {
+            showError(window.i18n.t('token.verify_failed').replace('{name}', account.name) + ': ' + (account.token_error || ''));
+        }
+        await loadAccounts(currentAccountPage);
+    } catch (error) {
+        showError(window.i18n.t('token.verify_error') + ': ' + getErrorMessage(error));
+        if (button) {
+            button.disabled = false;
+        }
+    }
+}
+
+function renderAccountBalance(balance) {
+    if (!balance) {
+        return '';
+    }
+    const time = new Date(balance.fetched_at).toLocaleString();
+    if (balance.balance === null || balance.balance === undefined) {
+        return `<div class="small"><i class="fas fa-coins me-1 text-danger"></i><span class="text-danger" title="${escapeHtml(balance.error)}">${window.i18n.t('balance.failed')}</span> <span class="text-muted">${time}</span></div>`;
+    }
+    return `<div class="small"><i class="fas fa-coins me-1 text-warning"></i>${window.i18n.t('balance.remaining')}: ${balance.balance.toFixed(2)} ${balance.unit || ''} <span class="text-muted">${time}</span></div>`;
+}
+
+async function refreshAccountBalances() {
+    const button = document.getElementById('refreshBalancesBtn');
+    button.disabled = true;
+    try {
+        const results = await tauriRefreshAccountBalances();
+        if (results.length === 0) {
+            showError(window.i18n.t('balance.no_adapter'));
+        } else {
+            const succeeded = results.filter(balance => balance.balance !== null).length;
+            showSuccess(window.i18n.t('balance.refresh_done')
+                .replace('{success}', succeeded)
+                .replace('{total}', results.length));
+        }
+        await loadAccounts(currentAccountPage);
+    } catch (error) {
+        showError(window.i18n.t('balance.refresh_failed') + ': ' + getErrorMessage(error));
+    } finally {
+        button.disabled = false;
+    }
+}
+
+async function verifyAllAccountTokens() {
+    const button = document.getElementById('verifyAllTokensBtn');
+    button.disabled = true;
+    try {
+        const results = await tauriVerifyAllAccountTokens();
+        const valid = results.filter(account => account.token_status === 'valid').length;
+        showSuccess(window.i18n.t('token.verify_all_done')
+            .replace('{valid}', valid)
+            .replace('{total}', results.length));
+        await loadAccounts(currentAccountPage);
+    } catch (error) {
+        showError(window.i18n.t('token.verify_error') + ': ' + getErrorMessage(error));
+    } finally {
+        button.disabled = false;
+    }
+}
+
 // Render pagination component
 function renderAccountsPagination(pagination) {
     const container = document.getElementById('accountsPagination');
@@ -462,6 +966,22 @@ function setupAccountFilters() {
         currentAccountFilter.base_url = this.value;
         loadAccounts(1, true);
     });
+
+    // 启用状态 / 到期筛选
+    document.getElementById('accountStatusFilter').addEventListener('change', function() {
+        currentAccountFilter.status = this.value;
+        loadAccounts(1, true);
+    });
+
+    // 标签筛选
+    let tagTimeout;
+    document.getElementById('accountTagFilter').addEventListener('input', function() {
+        clearTimeout(tagTimeout);
+        tagTimeout = setTimeout(() => {
+            currentAccountFilter.tag = this.value.trim();
+            loadAccounts(1, true);
+        }, 500);
+    });
     
     // Per page count
     perPageSelect.addEventListener('change', function() {
@@ -511,6 +1031,12 @@ async function renderDirectories() {
         cleanupBtn.style.display = hasInvalidDirectories ? 'inline-block' : 'none';
     }
 
+    // 存在漂移目录时显示全部修复按钮
+    const fixAllBtn = document.getElementById('fixAllDriftBtn');
+    if (fixAllBtn) {
+        fixAllBtn.style.display = Object.keys(driftedDirectories).length > 0 ? 'inline-block' : 'none';
+    }
+
     container.innerHTML = directoriesWithStatus.map(directory => `
         <div class="list-group-item ${!directory.exists ? 'directory-missing' : ''}">
             <div class="directory-item">
@@ -518,11 +1044,15 @@ async function renderDirectories() {
                     <div class="fw-bold">
                         ${directory.is_active ? '<span class="directory-active-indicator"></span>' : ''}${directory.name}
                         ${!directory.exists ? '<span class="badge bg-warning text-dark ms-2">' + window.i18n.t('text.folder_not_exist') + '</span>' : ''}
+                        ${directory.exists && driftedDirectories[directory.id] ? '<span class="badge bg-danger ms-2">' + window.i18n.t('drift.badge') + '</span>' : ''}
+                        ${renderDirectoryPoolBadge(directory)}
                     </div>
                     <div class="small text-muted">${directory.path}</div>
                     ${!directory.exists ? '<div class="small text-warning"><i class="fas fa-exclamation-triangle me-1"></i>' + window.i18n.t('text.dir_not_exist_hint') + '</div>' : ''}
+                    ${directory.exists && driftedDirectories[directory.id] ? '<div class="small text-danger"><i class="fas fa-exclamation-circle me-1"></i>' + describeDrift(driftedDirectories[directory.id]) + '</div>' : ''}
                 </div>
                 <div class="directory-actions">
+                    ${directory.exists && driftedDirectories[directory.id] ? '<button class="btn btn-sm btn-outline-danger" onclick="fixDirectoryDrift(' + directory.id + ')">' + window.i18n.t('drift.fix') + '</button>' : ''}
                     <button class="btn btn-sm btn-outline-info" onclick="viewConfig(${directory.id})" ${!directory.exists ? 'disabled title="' + window.i18n.t('text.dir_not_exist_cannot_view') + '"' : ''}>${window.i18n.t('text.view_config')}</button>
                     <button class="btn btn-sm btn-outline-primary" onclick="editDirectory(${directory.id})" ${!directory.exists ? 'disabled title="' + window.i18n.t('text.dir_not_exist_delete_first') + '"' : ''}>${window.i18n.t('text.edit')}</button>
                     <button class="btn btn-sm ${!directory.exists ? 'btn-warning' : 'btn-outline-danger'}" onclick="promptDeleteDirectory(${directory.id}, '${directory.name.replace(/'/g, '\\\'')}')" ${isDeleting ? 'disabled' : ''}>
@@ -534,242 +1064,554 @@ async function renderDirectories() {
     `).join('');
 }
 
-// Save account
-async function saveAccount() {
-    const name = document.getElementById('accountName').value.trim();
-    const token = document.getElementById('accountToken').value.trim();
-    const base_url = document.getElementById('accountBaseUrl').value.trim();
-    const model = document.getElementById('accountModel').value.trim();
+function renderDirectoryPoolBadge(directory) {
+    const pool = accountPools.find(pool => pool.id === directory.account_pool_id);
+    if (!pool) {
+        return '';
+    }
+    const relay = directory.relay_port ? ` <i class="fas fa-random ms-1" title="${window.i18n.t('relay.via_relay')} :${directory.relay_port}"></i>` : '';
+    return `<span class="badge bg-info text-dark ms-2"><i class="fas fa-layer-group me-1"></i>${escapeHtml(pool.name)}${relay}</span>`;
+}
 
-    // 获取和验证自定义环境变量JSON
-    const customEnvVarsJson = document.getElementById('accountCustomEnvVarsJson').value.trim();
-    let customEnvVars = {};
+// ===== 账号池 =====
+let poolAccounts = [];
+let relayStats = {}; // 中转代理统计，按 account_id 索引
 
-    if (customEnvVarsJson) {
-        try {
-            const parsedEnvVars = JSON.parse(customEnvVarsJson);
-            // 验证解析后的是一个对象
-            if (typeof parsedEnvVars !== 'object' || Array.isArray(parsedEnvVars) || parsedEnvVars === null) {
-                throw new Error('环境变量必须是一个JSON对象');
-            }
-            customEnvVars = parsedEnvVars;
-        } catch (e) {
-            showError('自定义环境变量JSON格式错误: ' + e.message);
-            return;
-        }
+function describeFailover(failover) {
+    const prefix = window.i18n.t('pool.failover_line')
+        .replace('{pool}', failover.pool_name)
+        .replace('{directory}', failover.directory_name)
+        .replace('{from}', failover.from_account);
+    if (failover.to_account) {
+        return prefix + '，' + window.i18n.t('pool.switched_to').replace('{to}', failover.to_account);
     }
+    return prefix + '，' + (failover.error || window.i18n.t('pool.no_available'));
+}
 
-    if (!name || !token || !base_url) {
-        showError(window.i18n.t('validation.required_fields'));
-        return;
+// 加载账号池并监听后台的故障切换事件
+async function initAccountPools() {
+    try {
+        accountPools = await tauriGetAccountPools();
+    } catch (error) {
+        console.warn('获取账号池失败:', error);
     }
 
-    // 验证base_url格式
-    try {
-        new URL(base_url);
-    } catch (e) {
-        showError(window.i18n.t('validation.invalid_url'));
-        return;
+    await listen('pool-failover', event => {
+        event.payload.forEach(failover => {
+            if (failover.to_account) {
+                showSuccess(describeFailover(failover));
+            } else {
+                showError(describeFailover(failover));
+            }
+        });
+        loadDirectories();
+        loadAccounts(currentAccountPage);
+    });
+
+    if (directories.length > 0) {
+        renderDirectories();
     }
+}
 
-    // 确保URL以http或https开头
-    if (!base_url.startsWith('http://') && !base_url.startsWith('https://')) {
-        showError(window.i18n.t('validation.url_protocol'));
+async function openAccountPoolModal() {
+    try {
+        const [pools, response, stats] = await Promise.all([
+            tauriGetAccountPools(),
+            tauriGetAccounts({ page: 1, per_page: 10000 }),
+            tauriGetRelayStats()
+        ]);
+        accountPools = pools;
+        poolAccounts = response.accounts;
+        relayStats = Object.fromEntries(stats.map(stat => [stat.account_id, stat]));
+    } catch (error) {
+        showError(window.i18n.t('pool.load_failed') + ': ' + getErrorMessage(error));
+        return;
+    }
+    document.getElementById('poolName').value = '';
+    document.getElementById('poolDescription').value = '';
+    renderPoolSelect();
+    await renderPoolMembers();
+    await renderPoolFailovers();
+    renderPoolDirectories();
+    await renderRelayStatus();
+    new bootstrap.Modal(document.getElementById('accountPoolModal')).show();
+}
+
+function renderPoolSelect(selectedId) {
+    const select = document.getElementById('poolSelect');
+    const current = selectedId || parseInt(select.value);
+    select.innerHTML = accountPools.length === 0
+        ? '<option value="">' + window.i18n.t('pool.empty') + '</option>'
+        : accountPools.map(pool => `<option value="${pool.id}" ${pool.id === current ? 'selected' : ''}>${escapeHtml(pool.name)}</option>`).join('');
+    document.getElementById('poolDeleteBtn').disabled = accountPools.length === 0;
+}
+
+async function renderPoolMembers() {
+    const container = document.getElementById('poolMembersList');
+    const poolId = parseInt(document.getElementById('poolSelect').value);
+    if (!poolId) {
+        container.innerHTML = '<div class="text-muted small">' + window.i18n.t('pool.empty') + '</div>';
         return;
     }
 
+    let members = [];
     try {
-        const result = await tauriCreateAccount(name, token, base_url, model, customEnvVars);
-
-        // Close modal and reset form
-        const modal = bootstrap.Modal.getInstance(document.getElementById('accountModal'));
-        modal.hide();
-        document.getElementById('accountForm').reset();
-
-        // 重置环境变量输入框
-        document.getElementById('accountCustomEnvVarsJson').value = '';
-
-        // Reload accounts list and base_url options
-        await loadAccounts(currentAccountPage);
-        await loadAccountBaseUrlOptions();
-        showSuccess(window.i18n.t('success.account_added'));
+        members = await tauriGetAccountPoolMembers(poolId);
     } catch (error) {
-        // 处理特定的数据库错误
-        let errorMessage = getErrorMessage(error);
-        if (errorMessage.includes('UNIQUE constraint failed: accounts.name')) {
-            errorMessage = window.i18n.t('error.account_name_exists');
-        }
-
-        showError(window.i18n.t('error.add_account') + ': ' + errorMessage);
+        showError(window.i18n.t('pool.load_failed') + ': ' + getErrorMessage(error));
     }
-}
 
-// Global variable to track editing state
-let editingDirectoryId = null;
+    container.innerHTML = poolAccounts.length === 0
+        ? '<div class="text-muted small">' + window.i18n.t('text.no_accounts') + '</div>'
+        : poolAccounts.map(account => {
+            const member = members.find(member => member.account_id === account.id);
+            return `
+                <div class="list-group-item d-flex align-items-center gap-2">
+                    <div class="form-check flex-grow-1">
+                        <input class="form-check-input" type="checkbox" id="poolMember${account.id}" ${member ? 'checked' : ''} onchange="togglePoolMember(${account.id}, this.checked)">
+                        <label class="form-check-label" for="poolMember${account.id}">${account.name} ${renderTokenStatusBadge(account)}</label>
+                        <div class="small text-muted">${account.base_url}</div>
+                        ${renderRelayStat(relayStats[account.id])}
+                    </div>
+                    <label class="small text-muted" for="poolPriority${account.id}">${window.i18n.t('pool.priority')}</label>
+                    <input type="number" class="form-control form-control-sm" style="width: 70px;" id="poolPriority${account.id}" value="${member ? member.priority : 1}" ${member ? '' : 'disabled'} onchange="togglePoolMember(${account.id}, true)">
+                    <label class="small text-muted" for="poolWeight${account.id}">${window.i18n.t('pool.weight')}</label>
+                    <input type="number" class="form-control form-control-sm" style="width: 80px;" id="poolWeight${account.id}" value="${member ? member.weight : 100}" min="0" ${member ? '' : 'disabled'} onchange="togglePoolMember(${account.id}, true)">
+                </div>
+            `;
+        }).join('');
+}
 
-// Save directory (handles both create and update)
-async function saveDirectory() {
-    const name = document.getElementById('directoryName').value.trim();
-    const path = document.getElementById('directoryPath').value.trim();
+function renderPoolDirectories() {
+    const container = document.getElementById('poolDirectoriesList');
+    if (directories.length === 0) {
+        container.innerHTML = '<div class="text-muted small">' + window.i18n.t('text.no_directories') + '</div>';
+        return;
+    }
+    const options = '<option value="">' + window.i18n.t('pool.no_pool') + '</option>' +
+        accountPools.map(pool => `<option value="${pool.id}">${escapeHtml(pool.name)}</option>`).join('');
+    container.innerHTML = directories.map(directory => `
+        <div class="list-group-item d-flex align-items-center gap-2">
+            <div class="flex-grow-1">
+                <div class="fw-bold">${directory.name}</div>
+                <div class="small text-muted">${directory.path}</div>
+            </div>
+            <select class="form-select form-select-sm" style="width: 180px;" id="poolBinding${directory.id}" onchange="bindDirectoryPool(${directory.id}, this.value)">${options}</select>
+            <div class="form-check form-switch mb-0" title="${window.i18n.t('relay.via_relay_hint')}">
+                <input class="form-check-input" type="checkbox" id="poolRelay${directory.id}" ${directory.relay_port ? 'checked' : ''} ${directory.account_pool_id ? '' : 'disabled'} onchange="setDirectoryRelay(${directory.id}, this.checked)">
+                <label class="form-check-label small" for="poolRelay${directory.id}">${window.i18n.t('relay.via_relay')}</label>
+            </div>
+        </div>
+    `).join('');
+    directories.forEach(directory => {
+        document.getElementById('poolBinding' + directory.id).value = directory.account_pool_id || '';
+    });
+}
 
-    if (!name || !path) {
-        showError(window.i18n.t('validation.required_fields'));
+// 选中账号池最近的故障切换记录
+async function renderPoolFailovers() {
+    const container = document.getElementById('poolFailoversList');
+    const poolId = parseInt(document.getElementById('poolSelect').value);
+    if (!poolId) {
+        container.innerHTML = '';
         return;
     }
 
-    // 验证路径是否存在
+    let failovers = [];
     try {
-        const pathExists = await tauriCheckDirectoryExists(path);
-        if (!pathExists) {
-            const confirmed = await customConfirm(
-                window.i18n.t('confirm.path_not_exist').replace('{path}', path),
-                window.i18n.t('confirm.path_not_exist_title')
-            );
-            if (!confirmed) {
-                return;
-            }
-        }
+        failovers = await tauriGetPoolFailovers(poolId, 20);
     } catch (error) {
-        console.warn(window.i18n.t('error.select_directory') + ':', error);
-        // 继续执行，不阻止操作
+        showError(window.i18n.t('pool.load_failed') + ': ' + getErrorMessage(error));
     }
 
-    try {
-        if (editingDirectoryId) {
-            // Update existing directory
-            await tauriUpdateDirectory(editingDirectoryId, { name, path });
-            showSuccess(window.i18n.t('success.directory_updated'));
-        } else {
-            // Create new directory
-            await tauriCreateDirectory(path, name);
-            showSuccess(window.i18n.t('success.directory_added'));
-        }
+    container.innerHTML = failovers.length === 0
+        ? '<div class="text-muted small">' + window.i18n.t('pool.no_failovers') + '</div>'
+        : failovers.map(failover => `
+            <div class="list-group-item small">
+                <span class="text-muted me-2">${new Date(failover.created_at).toLocaleString()}</span>
+                <span class="${failover.to_account ? 'text-warning' : 'text-danger'}">${escapeHtml(describeFailover(failover))}</span>
+            </div>
+        `).join('');
+}
 
-        // Close modal and reset form
-        const modal = bootstrap.Modal.getInstance(document.getElementById('directoryModal'));
-        modal.hide();
-        resetDirectoryModal();
+function renderRelayStat(stat) {
+    if (!stat || stat.request_count === 0) {
+        return '';
+    }
+    const average = Math.round(stat.total_latency_ms / stat.request_count);
+    return `<div class="small text-muted"><i class="fas fa-random me-1"></i>${window.i18n.t('relay.stat_line')
+        .replace('{requests}', stat.request_count)
+        .replace('{failures}', stat.failure_count)
+        .replace('{latency}', average)}</div>`;
+}
 
-        // Reload directories list
-        await loadDirectories();
+async function renderRelayStatus() {
+    const label = document.getElementById('relayStatusText');
+    const button = document.getElementById('relayToggleBtn');
+    try {
+        const status = await tauriGetRelayStatus();
+        document.getElementById('relayPort').value = status.port;
+        label.textContent = status.running
+            ? window.i18n.t('relay.running').replace('{url}', 'http://127.0.0.1:' + status.port)
+            : window.i18n.t('relay.stopped');
+        label.className = 'small ' + (status.running ? 'text-success' : 'text-muted');
+        button.textContent = status.running ? window.i18n.t('relay.stop') : window.i18n.t('relay.start');
+        button.dataset.running = status.running ? '1' : '0';
     } catch (error) {
-        if (editingDirectoryId) {
-            showError(window.i18n.t('error.update_directory') + ': ' + getErrorMessage(error));
+        showError(window.i18n.t('relay.failed') + ': ' + getErrorMessage(error));
+    }
+}
+
+async function toggleRelay() {
+    const button = document.getElementById('relayToggleBtn');
+    button.disabled = true;
+    try {
+        if (button.dataset.running === '1') {
+            await tauriStopRelay();
         } else {
-            showError(window.i18n.t('error.add_directory') + ': ' + getErrorMessage(error));
+            await tauriStartRelay(parseInt(document.getElementById('relayPort').value) || null);
         }
+        await renderRelayStatus();
+    } catch (error) {
+        showError(window.i18n.t('relay.failed') + ': ' + getErrorMessage(error));
+    } finally {
+        button.disabled = false;
     }
 }
 
-// Edit account
-async function editAccount(accountId) {
+async function setDirectoryRelay(directoryId, enabled) {
     try {
-        const account = accounts.find(acc => acc.id === accountId);
-        if (!account) {
-            showError(window.i18n.t('error.account_not_found'));
-            return;
-        }
+        const port = enabled ? (parseInt(document.getElementById('relayPort').value) || 15722) : null;
+        await tauriSetDirectoryRelay(directoryId, port);
+        directories = await tauriGetDirectories();
+        renderDirectories();
+        await renderRelayStatus();
+        showSuccess(window.i18n.t('relay.directory_updated'));
+    } catch (error) {
+        document.getElementById('poolRelay' + directoryId).checked = !enabled;
+        showError(window.i18n.t('relay.failed') + ': ' + getErrorMessage(error));
+    }
+}
 
-        // Fill form
-        document.getElementById('accountName').value = account.name;
-        document.getElementById('accountToken').value = account.token;
-        document.getElementById('accountBaseUrl').value = account.base_url;
-        document.getElementById('accountModel').value = account.model || '';
+async function createAccountPool() {
+    const name = document.getElementById('poolName').value.trim();
+    if (!name) {
+        showError(window.i18n.t('pool.name_required'));
+        return;
+    }
+    try {
+        const pool = await tauriCreateAccountPool({
+            name,
+            description: document.getElementById('poolDescription').value.trim() || null
+        });
+        accountPools = await tauriGetAccountPools();
+        document.getElementById('poolName').value = '';
+        document.getElementById('poolDescription').value = '';
+        renderPoolSelect(pool.id);
+        await renderPoolMembers();
+        await renderPoolFailovers();
+        renderPoolDirectories();
+        showSuccess(window.i18n.t('pool.created'));
+    } catch (error) {
+        showError(window.i18n.t('pool.save_failed') + ': ' + getErrorMessage(error));
+    }
+}
 
-        // 设置Base URL下拉框的值
-        const baseUrlSelect = document.getElementById('accountBaseUrlSelect');
-        if (baseUrlSelect && baseUrls.length > 0) {
-            // 检查账号的base_url是否在下拉框选项中
-            const selectOptions = Array.from(baseUrlSelect.options);
-            const matchingOption = selectOptions.find(option => option.value === account.base_url);
+async function deleteAccountPool() {
+    const poolId = parseInt(document.getElementById('poolSelect').value);
+    const pool = accountPools.find(pool => pool.id === poolId);
+    if (!pool || !confirm(window.i18n.t('pool.delete_confirm').replace('{name}', pool.name))) {
+        return;
+    }
+    try {
+        await tauriDeleteAccountPool(poolId);
+        accountPools = await tauriGetAccountPools();
+        directories = await tauriGetDirectories();
+        renderPoolSelect();
+        await renderPoolMembers();
+        await renderPoolFailovers();
+        renderPoolDirectories();
+        renderDirectories();
+        showSuccess(window.i18n.t('pool.deleted'));
+    } catch (error) {
+        showError(window.i18n.t('pool.save_failed') + ': ' + getErrorMessage(error));
+    }
+}
 
-            if (matchingOption) {
-                // 如果找到匹配项，设置下拉框的值
-                baseUrlSelect.value = account.base_url;
-            } else {
-                // 如果不在预设URL中，设置为空（自定义URL）
-                baseUrlSelect.value = '';
-            }
+async function togglePoolMember(accountId, checked) {
+    const poolId = parseInt(document.getElementById('poolSelect').value);
+    if (!poolId) {
+        return;
+    }
+    const priority = document.getElementById('poolPriority' + accountId);
+    const weight = document.getElementById('poolWeight' + accountId);
+    try {
+        if (checked) {
+            await tauriSaveAccountPoolMember({
+                pool_id: poolId,
+                account_id: accountId,
+                priority: parseInt(priority.value) || 1,
+                weight: Math.max(parseInt(weight.value) || 0, 0)
+            });
+        } else {
+            await tauriRemoveAccountPoolMember(poolId, accountId);
         }
+        priority.disabled = !checked;
+        weight.disabled = !checked;
+    } catch (error) {
+        showError(window.i18n.t('pool.save_failed') + ': ' + getErrorMessage(error));
+    }
+}
 
-        // 加载自定义环境变量到JSON文本框
-        try {
-            // 只显示账号的自定义环境变量，不合并URL默认环境变量
-            let accountCustomEnvVars = {};
-            if (account.custom_env_vars) {
-                if (typeof account.custom_env_vars === 'string') {
-                    accountCustomEnvVars = JSON.parse(account.custom_env_vars);
-                } else {
-                    accountCustomEnvVars = account.custom_env_vars;
-                }
-            }
-
-            // 设置JSON文本框 - 只显示账号的自定义环境变量
-            document.getElementById('accountCustomEnvVarsJson').value =
-                Object.keys(accountCustomEnvVars).length > 0 ?
-                    JSON.stringify(accountCustomEnvVars, null, 2) : '{}';
-
-        } catch (error) {
-            console.error('解析账号自定义环境变量失败:', error);
-            document.getElementById('accountCustomEnvVarsJson').value = '{}';
+async function bindDirectoryPool(directoryId, value) {
+    try {
+        const switched = await tauriBindDirectoryAccountPool(directoryId, value ? parseInt(value) : null);
+        directories = await tauriGetDirectories();
+        renderDirectories();
+        renderPoolDirectories();
+        if (switched && switched.to_account) {
+            showSuccess(window.i18n.t('pool.bound_switched').replace('{to}', switched.to_account));
+            await loadAccounts(currentAccountPage);
+        } else if (switched) {
+            showError(switched.error || window.i18n.t('pool.no_available'));
+        } else {
+            showSuccess(window.i18n.t('pool.bound'));
         }
+    } catch (error) {
+        showError(window.i18n.t('pool.save_failed') + ': ' + getErrorMessage(error));
+    }
+}
 
-        // Change modal title
-        document.querySelector('#accountModal .modal-title').textContent = window.i18n.t('modal.edit_account');
+// ===== 项目发现 =====
+let discoveredProjects = [];
 
-        // Change save button behavior
-        const saveBtn = document.getElementById('saveAccount');
-        // 移除所有现有的事件监听器
-        saveBtn.removeEventListener('click', saveAccount);
-        // 创建新的更新账号处理函数
-        const updateAccountHandler = async function() {
-            await updateAccount(accountId);
-        };
-        saveBtn.addEventListener('click', updateAccountHandler);
-        // 保存处理函数引用，以便后续清理
-        saveBtn._updateHandler = updateAccountHandler;
+async function openDiscoveryModal() {
+    discoveredProjects = [];
+    document.getElementById('discoveryProjectsList').innerHTML = '<div class="text-muted">' + window.i18n.t('discovery.scan_hint') + '</div>';
+    await renderDiscoveryRoots();
+    new bootstrap.Modal(document.getElementById('discoveryModal')).show();
+}
 
-        // Show modal
-        const modal = new bootstrap.Modal(document.getElementById('accountModal'));
-        modal.show();
+async function renderDiscoveryRoots() {
+    const container = document.getElementById('discoveryRootsList');
+    try {
+        const roots = await tauriGetDiscoveryRoots();
+        container.innerHTML = roots.length === 0
+            ? '<div class="small text-muted">' + window.i18n.t('discovery.no_roots') + '</div>'
+            : roots.map(root => `
+                <span class="badge bg-light text-dark border me-1 mb-1">
+                    ${escapeHtml(root.path)}
+                    <i class="fas fa-times ms-1" style="cursor: pointer;" onclick="deleteDiscoveryRoot(${root.id})"></i>
+                </span>
+            `).join('');
+    } catch (error) {
+        showError(window.i18n.t('discovery.load_roots_failed') + ': ' + getErrorMessage(error));
+    }
+}
 
+async function addDiscoveryRoot() {
+    const input = document.getElementById('discoveryRootInput');
+    const path = input.value.trim();
+    if (!path) {
+        return;
+    }
+    try {
+        await tauriAddDiscoveryRoot(path);
+        input.value = '';
+        await renderDiscoveryRoots();
     } catch (error) {
-        showError(window.i18n.t('error.edit_account') + ': ' + getErrorMessage(error));
+        showError(getErrorMessage(error));
     }
 }
 
-// Update account
-async function updateAccount(accountId) {
-    const name = document.getElementById('accountName').value.trim();
-    const token = document.getElementById('accountToken').value.trim();
-    const base_url = document.getElementById('accountBaseUrl').value.trim();
-    const model = document.getElementById('accountModel').value.trim();
+async function deleteDiscoveryRoot(id) {
+    try {
+        await tauriDeleteDiscoveryRoot(id);
+        await renderDiscoveryRoots();
+    } catch (error) {
+        showError(getErrorMessage(error));
+    }
+}
 
-    if (!name || !token || !base_url) {
-        showError(window.i18n.t('validation.required_fields'));
+async function scanDiscoveryProjects() {
+    const container = document.getElementById('discoveryProjectsList');
+    container.innerHTML = '<div class="text-muted"><i class="fas fa-spinner fa-spin me-1"></i>' + window.i18n.t('discovery.scanning') + '</div>';
+
+    try {
+        discoveredProjects = await tauriDiscoverProjects();
+    } catch (error) {
+        container.innerHTML = '';
+        showError(window.i18n.t('discovery.scan_failed') + ': ' + getErrorMessage(error));
         return;
     }
 
-    // 验证base_url格式
-    try {
-        new URL(base_url);
-    } catch (e) {
-        showError(window.i18n.t('validation.invalid_url'));
+    if (discoveredProjects.length === 0) {
+        container.innerHTML = '<div class="text-muted">' + window.i18n.t('discovery.no_projects') + '</div>';
         return;
     }
 
-    // 确保URL以http或https开头
-    if (!base_url.startsWith('http://') && !base_url.startsWith('https://')) {
-        showError(window.i18n.t('validation.url_protocol'));
+    container.innerHTML = discoveredProjects.map((project, index) => {
+        const importable = project.directory_id === null || (project.account_id === null && project.token);
+        const accountInfo = project.account_name
+            ? '<span class="badge bg-secondary ms-1">' + escapeHtml(project.account_name) + '</span>'
+            : (project.token ? '<span class="badge bg-warning text-dark ms-1">' + window.i18n.t('discovery.new_token') + '</span>' : '');
+        return `
+            <div class="form-check mb-2">
+                <input class="form-check-input discovery-project-checkbox" type="checkbox" value="${index}" id="discoveryProject${index}"
+                       ${importable ? 'checked' : 'disabled'}>
+                <label class="form-check-label" for="discoveryProject${index}">
+                    <span class="fw-bold">${escapeHtml(project.name)}</span>
+                    ${project.directory_id !== null ? '<span class="badge bg-light text-muted border ms-1">' + window.i18n.t('discovery.registered') + '</span>' : ''}
+                    ${accountInfo}
+                    <div class="small text-muted">${escapeHtml(project.path)}${project.base_url ? ' · ' + escapeHtml(project.base_url) : ''}</div>
+                </label>
+            </div>
+        `;
+    }).join('');
+}
+
+async function importDiscoveredProjects() {
+    const selected = Array.from(document.querySelectorAll('.discovery-project-checkbox:checked'))
+        .map(checkbox => discoveredProjects[parseInt(checkbox.value)]);
+    if (selected.length === 0) {
+        showError(window.i18n.t('discovery.none_selected'));
         return;
     }
 
-    // 获取和验证自定义环境变量JSON
-    const customEnvVarsJson = document.getElementById('accountCustomEnvVarsJson').value.trim();
-    let customEnvVars = {};
+    const createAccounts = document.getElementById('discoveryCreateAccounts').checked;
+    try {
+        const result = await tauriImportDiscoveredProjects(selected, createAccounts);
+        showSuccess(window.i18n.t('discovery.import_success')
+            .replace('{directories}', result.directories_created)
+            .replace('{accounts}', result.accounts_created)
+            .replace('{base_urls}', result.base_urls_created));
+        if (result.errors.length > 0) {
+            showError(result.errors.join('\n'));
+        }
+        bootstrap.Modal.getInstance(document.getElementById('discoveryModal')).hide();
+        await loadDirectories();
+    } catch (error) {
+        showError(window.i18n.t('discovery.import_failed') + ': ' + getErrorMessage(error));
+    }
+}
 
-    if (customEnvVarsJson) {
-        try {
+// 漂移提示：列出被修改的配置项
+function describeDrift(drift) {
+    const keys = drift.issues.map(issue => issue.key || window.i18n.t('drift.kind.' + issue.kind));
+    return window.i18n.t('drift.hint').replace('{account}', drift.account_name || '-') + ': ' + keys.join(', ');
+}
+
+// 按账号重新写入目录配置
+async function fixDirectoryDrift(directoryId) {
+    try {
+        const message = await tauriFixConfigDrift(directoryId);
+        showSuccess(message);
+    } catch (error) {
+        showError(window.i18n.t('drift.fix_failed') + ': ' + getErrorMessage(error));
+    }
+}
+
+// 手动扫描所有目录的配置漂移，并保留文件监听器记录的外部修改
+async function scanConfigDrift() {
+    try {
+        const [watched, results] = await Promise.all([tauriGetDriftedDirectories(), tauriScanConfigDrift()]);
+        const drifted = [...watched, ...results.filter(drift => drift.issues.length > 0)];
+        driftedDirectories = Object.fromEntries(drifted.map(drift => [drift.directory_id, drift]));
+        await renderDirectories();
+        const count = Object.keys(driftedDirectories).length;
+        if (count > 0) {
+            showGlobalMessage(window.i18n.t('drift.scan_found').replace('{count}', count), 'warning', 5000);
+        } else {
+            showSuccess(window.i18n.t('drift.scan_clean'));
+        }
+    } catch (error) {
+        showError(window.i18n.t('drift.scan_failed') + ': ' + getErrorMessage(error));
+    }
+}
+
+// 按目录当前账号恢复所有存在漂移的目录
+async function fixAllDrift() {
+    const count = Object.keys(driftedDirectories).length;
+    const confirmed = await customConfirm(
+        window.i18n.t('drift.fix_all_confirm').replace('{count}', count),
+        window.i18n.t('drift.fix_all')
+    );
+    if (!confirmed) return;
+
+    try {
+        showSuccess(await tauriFixConfigDrift(null));
+    } catch (error) {
+        showError(window.i18n.t('drift.fix_failed') + ': ' + getErrorMessage(error));
+    }
+
+    // 文件监听器会异步重新检查，这里先按扫描结果刷新列表
+    try {
+        const results = await tauriScanConfigDrift();
+        driftedDirectories = Object.fromEntries(
+            results.filter(drift => drift.issues.length > 0).map(drift => [drift.directory_id, drift])
+        );
+        await renderDirectories();
+    } catch (error) {
+        console.warn('刷新漂移状态失败:', error);
+    }
+}
+
+// 切换漂移系统通知
+async function toggleDriftNotifications(enabled) {
+    localStorage.setItem('driftNotifications', enabled ? '1' : '0');
+    try {
+        await tauriSetDriftNotifications(enabled);
+    } catch (error) {
+        console.warn('设置漂移通知失败:', error);
+    }
+}
+
+// 加载当前漂移状态并监听后台文件监听器的变化事件
+async function initDriftWatcher() {
+    const notificationsEnabled = localStorage.getItem('driftNotifications') !== '0';
+    const toggle = document.getElementById('driftNotificationsToggle');
+    if (toggle) {
+        toggle.checked = notificationsEnabled;
+    }
+    await toggleDriftNotifications(notificationsEnabled);
+
+    try {
+        const drifted = await tauriGetDriftedDirectories();
+        driftedDirectories = Object.fromEntries(drifted.map(drift => [drift.directory_id, drift]));
+    } catch (error) {
+        console.warn('获取漂移目录失败:', error);
+    }
+
+    await listen('config-drift-changed', event => {
+        const drift = event.payload;
+        if (drift.issues.length > 0) {
+            driftedDirectories[drift.directory_id] = drift;
+        } else {
+            delete driftedDirectories[drift.directory_id];
+        }
+        if (directories.length > 0) {
+            renderDirectories();
+        }
+    });
+
+    if (directories.length > 0) {
+        renderDirectories();
+    }
+}
+
+// Save account
+async function saveAccount() {
+    const name = document.getElementById('accountName').value.trim();
+    const token = document.getElementById('accountToken').value.trim();
+    const base_url = document.getElementById('accountBaseUrl').value.trim();
+    const model = document.getElementById('accountModel').value.trim();
+
+    // 获取和验证自定义环境变量JSON
+    const customEnvVarsJson = document.getElementById('accountCustomEnvVarsJson').value.trim();
+    let customEnvVars = {};
+
+    if (customEnvVarsJson) {
+        try {
             const parsedEnvVars = JSON.parse(customEnvVarsJson);
             // 验证解析后的是一个对象
             if (typeof parsedEnvVars !== 'object' || Array.isArray(parsedEnvVars) || parsedEnvVars === null) {
@@ -782,664 +1624,1248 @@ async function updateAccount(accountId) {
         }
     }
 
+    // 令牌是否必填由 URL 的供应商类型决定（Bedrock / Vertex 可不填）
+    if (!name || !base_url) {
+        showError(window.i18n.t('validation.required_fields'));
+        return;
+    }
+
+    // 验证base_url格式
     try {
-        const result = await tauriUpdateAccount(accountId, {
-            name,
-            token,
-            base_url,
-            model,
-            custom_env_vars: customEnvVars
-        });
+        new URL(base_url);
+    } catch (e) {
+        showError(window.i18n.t('validation.invalid_url'));
+        return;
+    }
+
+    // 确保URL以http或https开头
+    if (!base_url.startsWith('http://') && !base_url.startsWith('https://')) {
+        showError(window.i18n.t('validation.url_protocol'));
+        return;
+    }
+
+    const credential = getAccountCredentialForm();
+    if (!credential) {
+        return;
+    }
+    const customHeaders = getCustomHeadersForm('accountCustomHeaders');
+    if (!customHeaders) {
+        return;
+    }
+    if (!await validateAccountProviderEnv(base_url, credential.mode === 'helper' ? credential.helper : token, customEnvVars)) {
+        return;
+    }
+
+    try {
+        const result = await tauriCreateAccount(name, token, base_url, model, customEnvVars, getAccountLifecycleForm());
+
+        // 保存账号的权限预设
+        const presetId = getSelectedPermissionPresetId('accountPermissionPreset');
+        if (presetId !== null) {
+            await tauriSetAccountPermissionPreset(result.id, presetId);
+        }
+
+        // 保存账号绑定的代理
+        const proxyId = getSelectedProxyId('accountProxy');
+        if (proxyId !== null) {
+            await tauriSetAccountProxy(result.id, proxyId);
+        }
+
+        // 保存账号启用的 MCP 服务器
+        const mcpServerIds = getCheckedIds('accountMcpServers');
+        if (mcpServerIds.length > 0) {
+            await tauriSetAccountMcpServers(result.id, mcpServerIds);
+        }
+
+        // 保存账号的凭据模式
+        if (credential.mode !== 'token') {
+            await tauriSetAccountCredential(result.id, credential.mode, credential.helper, credential.ttlMs);
+        }
+
+        // 保存账号的自定义请求头
+        if (customHeaders.length > 0) {
+            await tauriSetAccountHeaders(result.id, customHeaders);
+        }
 
         // Close modal and reset form
         const modal = bootstrap.Modal.getInstance(document.getElementById('accountModal'));
         modal.hide();
+        document.getElementById('accountForm').reset();
 
-        // Reset form and modal state
-        resetAccountModal();
+        // 重置环境变量输入框
+        document.getElementById('accountCustomEnvVarsJson').value = '';
 
         // Reload accounts list and base_url options
         await loadAccounts(currentAccountPage);
         await loadAccountBaseUrlOptions();
-        showSuccess(window.i18n.t('success.account_updated'));
+        showSuccess(window.i18n.t('success.account_added'));
     } catch (error) {
         // 处理特定的数据库错误
         let errorMessage = getErrorMessage(error);
         if (errorMessage.includes('UNIQUE constraint failed: accounts.name')) {
-            errorMessage = window.i18n.t('text.account_name_exists_hint');
+            errorMessage = window.i18n.t('error.account_name_exists');
         }
 
-        showError(window.i18n.t('error.update_account') + ': ' + errorMessage);
+        showError(window.i18n.t('error.add_account') + ': ' + errorMessage);
     }
 }
 
-// Reset account modal state
-function resetAccountModal() {
-    document.getElementById('accountForm').reset();
-    document.querySelector('#accountModal .modal-title').textContent = window.i18n.t('modal.add_account');
-
-    // Reset save button behavior
-    const saveBtn = document.getElementById('saveAccount');
-    // 移除可能存在的更新账号处理函数
-    if (saveBtn._updateHandler) {
-        saveBtn.removeEventListener('click', saveBtn._updateHandler);
-        saveBtn._updateHandler = null;
-    }
-    // 确保添加账号处理函数存在
-    saveBtn.removeEventListener('click', saveAccount);
-    saveBtn.addEventListener('click', saveAccount);
-    saveBtn.onclick = null; // 清除onclick属性
+// Global variable to track editing state
+let editingDirectoryId = null;
 
-    // 确保默认URL被选中
-    updateBaseUrlSelect();
+// Save directory (handles both create and update)
+async function saveDirectory() {
+    const name = document.getElementById('directoryName').value.trim();
+    const path = document.getElementById('directoryPath').value.trim();
 
-    // 重置环境变量输入框
-    document.getElementById('accountCustomEnvVarsJson').value = '';
-}
+    if (!name || !path) {
+        showError(window.i18n.t('validation.required_fields'));
+        return;
+    }
 
-// 根据选中的URL更新账号环境变量JSON显示
-function updateAccountEnvVarsFromUrl(selectedUrl) {
+    // 验证路径是否存在
     try {
-        // 找到选中的URL对应的baseUrl对象
-        const selectedBaseUrl = baseUrls.find(url => url.url === selectedUrl);
-
-        let envVarsToShow = {};
-
-        if (selectedBaseUrl && selectedBaseUrl.default_env_vars) {
-            try {
-                // 直接使用URL的默认环境变量，不合并
-                envVarsToShow = JSON.parse(selectedBaseUrl.default_env_vars);
-            } catch (e) {
-                console.warn('解析URL默认环境变量失败:', e);
-                envVarsToShow = {};
+        const pathExists = await tauriCheckDirectoryExists(path);
+        if (!pathExists) {
+            const confirmed = await customConfirm(
+                window.i18n.t('confirm.path_not_exist').replace('{path}', path),
+                window.i18n.t('confirm.path_not_exist_title')
+            );
+            if (!confirmed) {
+                return;
             }
         }
-
-        // 更新JSON文本框 - 直接显示URL的默认环境变量
-        document.getElementById('accountCustomEnvVarsJson').value =
-            Object.keys(envVarsToShow).length > 0 ?
-                JSON.stringify(envVarsToShow, null, 2) : '{}';
-
     } catch (error) {
-        console.error('更新账号环境变量失败:', error);
-        showError('更新环境变量失败: ' + getErrorMessage(error));
+        console.warn(window.i18n.t('error.select_directory') + ':', error);
+        // 继续执行，不阻止操作
     }
-}
 
-// Prompt delete account - shows confirmation first
-async function promptDeleteAccount(accountId) {
-    // 找到要删除的账号信息
-    const account = accounts.find(acc => acc.id === accountId);
-    const accountName = account ? account.name : `ID: ${accountId}`;
-    
-    // 显示确认框
     try {
-        const userConfirmed = await ask(
-            window.i18n.t('confirm.delete_account_with_name').replace('{name}', accountName),
-            {
-                title: window.i18n.t('confirm.delete_account_title'),
-                type: 'warning'
+        const presetId = getSelectedPermissionPresetId('directoryPermissionPreset');
+        const proxyId = getSelectedProxyId('directoryProxy');
+        const mcpServerIds = getCheckedIds('directoryMcpServers');
+        const hookIds = getCheckedIds('directoryHooks');
+        const definitionIds = getCheckedIds('directoryDefinitions');
+        if (editingDirectoryId) {
+            // Update existing directory
+            await tauriUpdateDirectory(editingDirectoryId, { name, path });
+            await tauriSetDirectoryPermissionPreset(editingDirectoryId, presetId);
+            await tauriSetDirectoryProxy(editingDirectoryId, proxyId);
+            await tauriSetDirectoryMcpServers(editingDirectoryId, mcpServerIds);
+            await tauriSetDirectoryHooks(editingDirectoryId, hookIds);
+            await tauriSetDirectoryDefinitions(editingDirectoryId, definitionIds);
+            showSuccess(window.i18n.t('success.directory_updated'));
+        } else {
+            // Create new directory
+            const directory = await tauriCreateDirectory(path, name);
+            if (presetId !== null) {
+                await tauriSetDirectoryPermissionPreset(directory.id, presetId);
             }
-        );
-
-        if (!userConfirmed) {
-            return;
+            if (proxyId !== null) {
+                await tauriSetDirectoryProxy(directory.id, proxyId);
+            }
+            if (mcpServerIds.length > 0) {
+                await tauriSetDirectoryMcpServers(directory.id, mcpServerIds);
+            }
+            if (hookIds.length > 0) {
+                await tauriSetDirectoryHooks(directory.id, hookIds);
+            }
+            // 只保存与默认启用状态不同的定义
+            await tauriSetDirectoryDefinitions(directory.id, definitionIds);
+            showSuccess(window.i18n.t('success.directory_added'));
         }
 
-        // 用户确认后才执行删除
-        await executeDeleteAccount(accountId, accountName);
+        // Close modal and reset form
+        const modal = bootstrap.Modal.getInstance(document.getElementById('directoryModal'));
+        modal.hide();
+        resetDirectoryModal();
+
+        // Reload directories list
+        await loadDirectories();
     } catch (error) {
-        // 如果 Tauri 对话框失败，fallback 到自定义确认对话框
-        const userConfirmed = await customConfirm(
-            window.i18n.t('confirm.delete_account_with_name').replace('{name}', accountName),
-            window.i18n.t('confirm.delete_account_title')
-        );
-        if (userConfirmed) {
-            await executeDeleteAccount(accountId, accountName);
+        if (editingDirectoryId) {
+            showError(window.i18n.t('error.update_directory') + ': ' + getErrorMessage(error));
+        } else {
+            showError(window.i18n.t('error.add_directory') + ': ' + getErrorMessage(error));
         }
     }
 }
 
-// 执行实际的账号删除操作
-async function executeDeleteAccount(accountId, accountName) {
+// Edit account
+async function editAccount(accountId) {
     try {
-        await tauriDeleteAccount(accountId);
-        await loadAccounts(currentAccountPage);
-        await loadAccountBaseUrlOptions();
-        showSuccess(window.i18n.t('success.account_deleted_with_name').replace('{name}', accountName));
-    } catch (error) {
-        showError(window.i18n.t('error.delete_account') + ' "' + accountName + '": ' + getErrorMessage(error));
-    }
-}
-
-// Delete account (legacy function for compatibility)
-async function deleteAccount(accountId) {
-    const confirmed = await customConfirm(
-        window.i18n.t('confirm.delete_account'),
-        window.i18n.t('confirm.delete_account_title')
-    );
-    if (!confirmed) {
-        return;
-    }
+        const account = accounts.find(acc => acc.id === accountId);
+        if (!account) {
+            showError(window.i18n.t('error.account_not_found'));
+            return;
+        }
 
-    try {
-        await tauriDeleteAccount(accountId);
-        await loadAccounts(currentAccountPage);
-        await loadAccountBaseUrlOptions();
-        showSuccess(window.i18n.t('success.account_deleted'));
-    } catch (error) {
-        showError(window.i18n.t('error.delete_account') + ': ' + getErrorMessage(error));
-    }
-}
-
-// 全局变量存储要导出的账号
-let exportAccountsData = [];
-let exportCurrentUrlFilter = ''; // 当前导出URL筛选
-let exportBaseUrlsMap = {}; // URL地址到名称的映射
+        // Fill form
+        document.getElementById('accountName').value = account.name;
+        document.getElementById('accountToken').value = account.token;
+        document.getElementById('accountBaseUrl').value = account.base_url;
+        document.getElementById('accountModel').value = account.model || '';
+        document.getElementById('accountEnabled').checked = account.enabled;
+        document.getElementById('accountExpiresAt').value = account.expires_at ? account.expires_at.substring(0, 10) : '';
+        document.getElementById('accountNotes').value = account.notes || '';
+        document.getElementById('accountTags').value = (account.tags || '').split(',').filter(Boolean).join(', ');
+        setAccountCredentialForm(account);
+        setCustomHeadersForm('accountCustomHeaders', account.custom_headers);
 
-// 批量导出账号
-async function exportAccounts() {
-    try {
-        // 获取所有账号（不分页）
-        const response = await tauriGetAccounts({
-            page: 1,
-            per_page: 10000,
-            search: null,
-            base_url: null
-        });
+        // 设置Base URL下拉框的值
+        const baseUrlSelect = document.getElementById('accountBaseUrlSelect');
+        if (baseUrlSelect && baseUrls.length > 0) {
+            // 检查账号的base_url是否在下拉框选项中
+            const selectOptions = Array.from(baseUrlSelect.options);
+            const matchingOption = selectOptions.find(option => option.value === account.base_url);
 
-        if (!response || !response.accounts || response.accounts.length === 0) {
-            showError(window.i18n.t('error.no_accounts_to_export') || '没有账号可导出');
-            return;
+            if (matchingOption) {
+                // 如果找到匹配项，设置下拉框的值
+                baseUrlSelect.value = account.base_url;
+            } else {
+                // 如果不在预设URL中，设置为空（自定义URL）
+                baseUrlSelect.value = '';
+            }
         }
+        renderAccountProviderHint(account.base_url);
 
-        // 获取所有 base_urls 用于显示名称
-        const baseUrls = await tauriGetBaseUrls();
+        // 加载自定义环境变量到JSON文本框
+        try {
+            // 只显示账号的自定义环境变量，不合并URL默认环境变量
+            let accountCustomEnvVars = {};
+            if (account.custom_env_vars) {
+                if (typeof account.custom_env_vars === 'string') {
+                    accountCustomEnvVars = JSON.parse(account.custom_env_vars);
+                } else {
+                    accountCustomEnvVars = account.custom_env_vars;
+                }
+            }
 
-        // 创建 URL 地址到名称的映射
-        exportBaseUrlsMap = {};
-        baseUrls.forEach(bu => {
-            exportBaseUrlsMap[bu.url] = bu.name;
-        });
+            // 设置JSON文本框 - 只显示账号的自定义环境变量
+            document.getElementById('accountCustomEnvVarsJson').value =
+                Object.keys(accountCustomEnvVars).length > 0 ?
+                    JSON.stringify(accountCustomEnvVars, null, 2) : '{}';
 
-        // 存储账号数据
-        exportAccountsData = response.accounts;
-        exportCurrentUrlFilter = ''; // 重置筛选
+        } catch (error) {
+            console.error('解析账号自定义环境变量失败:', error);
+            document.getElementById('accountCustomEnvVarsJson').value = '{}';
+        }
 
-        // 填充 URL 筛选下拉框
-        const urlFilter = document.getElementById('exportUrlFilter');
-        const uniqueUrls = [...new Set(exportAccountsData.map(acc => acc.base_url))];
+        // 加载权限预设选项
+        await loadPermissionPresetOptions('accountPermissionPreset', account.permission_preset_id);
+        await loadProxyOptions('accountProxy', account.proxy_id);
+        await loadMcpServerChecks('accountMcpServers', await tauriGetAccountMcpServers(account.id));
 
-        urlFilter.innerHTML = '<option value="">' + window.i18n.t('select.all_urls') + '</option>' +
-            uniqueUrls.map(url => {
-                const urlName = exportBaseUrlsMap[url] || url;
-                return `<option value="${url}">${urlName}</option>`;
-            }).join('');
+        // Change modal title
+        document.querySelector('#accountModal .modal-title').textContent = window.i18n.t('modal.edit_account');
 
-        // 渲染账号列表
-        renderExportAccountsList();
+        // Change save button behavior
+        const saveBtn = document.getElementById('saveAccount');
+        // 移除所有现有的事件监听器
+        saveBtn.removeEventListener('click', saveAccount);
+        // 创建新的更新账号处理函数
+        const updateAccountHandler = async function() {
+            await updateAccount(accountId);
+        };
+        saveBtn.addEventListener('click', updateAccountHandler);
+        // 保存处理函数引用，以便后续清理
+        saveBtn._updateHandler = updateAccountHandler;
 
-        // 显示模态框
-        const modal = new bootstrap.Modal(document.getElementById('exportSelectModal'));
+        // Show modal
+        const modal = new bootstrap.Modal(document.getElementById('accountModal'));
         modal.show();
 
     } catch (error) {
-        showError(window.i18n.t('accounts.export_error') + ': ' + getErrorMessage(error));
+        showError(window.i18n.t('error.edit_account') + ': ' + getErrorMessage(error));
     }
 }
 
-// 渲染导出账号列表
-function renderExportAccountsList(filteredUrl = '') {
-    const container = document.getElementById('exportAccountsList');
-
-    // 根据 URL 筛选账号
-    const accountsToShow = filteredUrl
-        ? exportAccountsData.filter(acc => acc.base_url === filteredUrl)
-        : exportAccountsData;
+// Update account
+async function updateAccount(accountId) {
+    const name = document.getElementById('accountName').value.trim();
+    const token = document.getElementById('accountToken').value.trim();
+    const base_url = document.getElementById('accountBaseUrl').value.trim();
+    const model = document.getElementById('accountModel').value.trim();
 
-    if (accountsToShow.length === 0) {
-        container.innerHTML = '<div class="text-muted text-center py-3">' +
-            window.i18n.t('accounts.no_accounts_for_url') + '</div>';
-        updateExportSelectedCount();
+    // 令牌是否必填由 URL 的供应商类型决定（Bedrock / Vertex 可不填）
+    if (!name || !base_url) {
+        showError(window.i18n.t('validation.required_fields'));
         return;
     }
 
-    container.innerHTML = accountsToShow.map((account, index) => {
-        // 使用账号在原始数组中的索引
-        const originalIndex = exportAccountsData.indexOf(account);
-        const urlName = exportBaseUrlsMap[account.base_url] || account.base_url;
-        return `
-            <div class="form-check mb-2">
-                <input class="form-check-input export-account-checkbox" type="checkbox"
-                       value="${originalIndex}" id="exportAccount${originalIndex}"
-                       onchange="updateExportSelectedCount()" checked>
-                <label class="form-check-label" for="exportAccount${originalIndex}">
-                    <strong>${account.name}</strong>
-                    <br>
-                    <small class="text-muted">
-                        ${urlName} | ${account.token.substring(0, 20)}...
-                        ${account.model ? ` | <i class="fas fa-microchip"></i> ${account.model}` : ''}
-                    </small>
-                </label>
-            </div>
-        `;
-    }).join('');
-
-    updateExportSelectedCount();
-}
+    // 验证base_url格式
+    try {
+        new URL(base_url);
+    } catch (e) {
+        showError(window.i18n.t('validation.invalid_url'));
+        return;
+    }
 
-// 按 URL 筛选导出账号
-function filterExportAccountsByUrl() {
-    const urlFilter = document.getElementById('exportUrlFilter');
-    exportCurrentUrlFilter = urlFilter.value;
-    renderExportAccountsList(exportCurrentUrlFilter);
-}
+    // 确保URL以http或https开头
+    if (!base_url.startsWith('http://') && !base_url.startsWith('https://')) {
+        showError(window.i18n.t('validation.url_protocol'));
+        return;
+    }
 
-// 更新选中数量显示
-function updateExportSelectedCount() {
-    const selectedCount = document.querySelectorAll('.export-account-checkbox:checked').length;
-    const totalCount = document.querySelectorAll('.export-account-checkbox').length;
+    // 获取和验证自定义环境变量JSON
+    const customEnvVarsJson = document.getElementById('accountCustomEnvVarsJson').value.trim();
+    let customEnvVars = {};
 
-    const countElement = document.getElementById('exportSelectedCount');
-    if (countElement) {
-        const countText = window.i18n.t('accounts.selected_count') || '已选择';
-        countElement.innerHTML = `${countText}: <strong>${selectedCount}</strong> / ${totalCount}`;
+    if (customEnvVarsJson) {
+        try {
+            const parsedEnvVars = JSON.parse(customEnvVarsJson);
+            // 验证解析后的是一个对象
+            if (typeof parsedEnvVars !== 'object' || Array.isArray(parsedEnvVars) || parsedEnvVars === null) {
+                throw new Error('环境变量必须是一个JSON对象');
+            }
+            customEnvVars = parsedEnvVars;
+        } catch (e) {
+            showError('自定义环境变量JSON格式错误: ' + e.message);
+            return;
+        }
     }
-}
-
-// 全选导出账号
-function selectAllExportAccounts() {
-    document.querySelectorAll('.export-account-checkbox').forEach(checkbox => {
-        checkbox.checked = true;
-    });
-    updateExportSelectedCount();
-}
 
-// 取消全选导出账号
-function deselectAllExportAccounts() {
-    document.querySelectorAll('.export-account-checkbox').forEach(checkbox => {
-        checkbox.checked = false;
-    });
-    updateExportSelectedCount();
-}
+    const credential = getAccountCredentialForm();
+    if (!credential) {
+        return;
+    }
+    const customHeaders = getCustomHeadersForm('accountCustomHeaders');
+    if (!customHeaders) {
+        return;
+    }
+    if (!await validateAccountProviderEnv(base_url, credential.mode === 'helper' ? credential.helper : token, customEnvVars)) {
+        return;
+    }
 
-// 确认导出账号
-async function confirmExportAccounts() {
     try {
-        // 获取选中的账号
-        const selectedIndexes = Array.from(document.querySelectorAll('.export-account-checkbox:checked'))
-            .map(checkbox => parseInt(checkbox.value));
-
-        if (selectedIndexes.length === 0) {
-            showError(window.i18n.t('accounts.export_no_selection') || '请至少选择一个账号');
-            return;
-        }
+        const result = await tauriUpdateAccount(accountId, {
+            name,
+            token,
+            base_url,
+            model,
+            custom_env_vars: customEnvVars,
+            ...getAccountLifecycleForm()
+        });
 
-        const selectedAccounts = selectedIndexes.map(index => exportAccountsData[index]);
+        // 保存账号的权限预设
+        await tauriSetAccountPermissionPreset(accountId, getSelectedPermissionPresetId('accountPermissionPreset'));
 
-        // 转换为导出格式（providers格式）
-        const exportData = {
-            providers: selectedAccounts.map(account => ({
-                name: account.name,
-                url: account.base_url,
-                key: account.token,
-                is_enabled: account.is_active || false,
-                weight: 100,
-                priority: 1
-            }))
-        };
+        // 保存账号绑定的代理
+        await tauriSetAccountProxy(accountId, getSelectedProxyId('accountProxy'));
 
-        // 生成默认文件名
-        const timestamp = new Date().toISOString().replace(/:/g, '-').split('.')[0];
-        const defaultFilename = `accounts_export_${timestamp}.json`;
-
-        // 使用 Tauri 的保存对话框
-        const filePath = await save({
-            defaultPath: defaultFilename,
-            filters: [{
-                name: 'JSON',
-                extensions: ['json']
-            }]
-        });
+        // 保存账号启用的 MCP 服务器
+        await tauriSetAccountMcpServers(accountId, getCheckedIds('accountMcpServers'));
 
-        // 如果用户取消了保存
-        if (!filePath) {
-            return;
-        }
+        // 保存账号的凭据模式
+        await tauriSetAccountCredential(accountId, credential.mode, credential.helper, credential.ttlMs);
 
-        // 保存文件
-        const jsonStr = JSON.stringify(exportData, null, 2);
-        await invoke('save_file', {
-            path: filePath,
-            contents: jsonStr
-        });
+        // 保存账号的自定义请求头
+        await tauriSetAccountHeaders(accountId, customHeaders);
 
-        // 关闭模态框
-        const modal = bootstrap.Modal.getInstance(document.getElementById('exportSelectModal'));
+        // Close modal and reset form
+        const modal = bootstrap.Modal.getInstance(document.getElementById('accountModal'));
         modal.hide();
 
-        const message = window.i18n.t('accounts.export_success').replace('{count}', selectedAccounts.length);
-        showSuccess(message);
+        // Reset form and modal state
+        resetAccountModal();
 
+        // Reload accounts list and base_url options
+        await loadAccounts(currentAccountPage);
+        await loadAccountBaseUrlOptions();
+        showSuccess(window.i18n.t('success.account_updated'));
     } catch (error) {
-        showError(window.i18n.t('accounts.export_error') + ': ' + getErrorMessage(error));
+        // 处理特定的数据库错误
+        let errorMessage = getErrorMessage(error);
+        if (errorMessage.includes('UNIQUE constraint failed: accounts.name')) {
+            errorMessage = window.i18n.t('text.account_name_exists_hint');
+        }
+
+        showError(window.i18n.t('error.update_account') + ': ' + errorMessage);
     }
 }
 
-// 批量导入账号
-async function importAccounts() {
-    // 创建文件输入元素
-    const input = document.createElement('input');
-    input.type = 'file';
-    input.accept = '.json';
+// Reset account modal state
+function resetAccountModal() {
+    document.getElementById('accountForm').reset();
+    loadPermissionPresetOptions('accountPermissionPreset', null);
+    loadProxyOptions('accountProxy', null);
+    document.querySelector('#accountModal .modal-title').textContent = window.i18n.t('modal.add_account');
 
-    input.onchange = async (e) => {
-        const file = e.target.files[0];
-        if (!file) return;
-
-        try {
-            const text = await file.text();
-            const data = JSON.parse(text);
-
-            // 验证文件格式
-            if (!data.providers || !Array.isArray(data.providers)) {
-                showError(window.i18n.t('accounts.import_file_invalid'));
-                return;
-            }
+    // Reset save button behavior
+    const saveBtn = document.getElementById('saveAccount');
+    // 移除可能存在的更新账号处理函数
+    if (saveBtn._updateHandler) {
+        saveBtn.removeEventListener('click', saveBtn._updateHandler);
+        saveBtn._updateHandler = null;
+    }
+    // 确保添加账号处理函数存在
+    saveBtn.removeEventListener('click', saveAccount);
+    saveBtn.addEventListener('click', saveAccount);
+    saveBtn.onclick = null; // 清除onclick属性
 
-            let importedCount = 0;
-            let skippedCount = 0;
+    // 确保默认URL被选中
+    updateBaseUrlSelect();
 
-            // 先获取所有现有账号，避免每次都查询
-            const existingAccountsResponse = await tauriGetAccounts({
-                page: 1,
-                per_page: 10000,
-                search: null,
-                base_url: null
-            });
+    // 重置环境变量输入框
+    document.getElementById('accountCustomEnvVarsJson').value = '';
+    setAccountCredentialForm(null);
+    setCustomHeadersForm('accountCustomHeaders', null);
+}
 
-            // 导入每个账号
-            for (const provider of data.providers) {
-                try {
-                    // 检查是否存在同名或同token的账号（精确匹配）
-                    const isDuplicate = existingAccountsResponse.accounts.some(acc =>
-                        acc.name === provider.name || acc.token === provider.key
-                    );
-
-                    if (isDuplicate) {
-                        console.log(`跳过重复账号: ${provider.name}`);
-                        skippedCount++;
-                        continue; // 跳过已存在的账号
-                    }
+// 显示或隐藏辅助命令输入框
+function toggleAccountCredentialMode() {
+    const isHelper = document.getElementById('accountCredentialMode').value === 'helper';
+    document.getElementById('accountHelperFields').style.display = isHelper ? '' : 'none';
+}
 
-                    // 创建新账号
-                    await tauriCreateAccount(
-                        provider.name,
-                        provider.key,
-                        provider.url,
-                        '' // 默认为空
-                    );
-                    console.log(`成功导入账号: ${provider.name}`);
-                    importedCount++;
-                } catch (error) {
-                    console.error(`导入账号 ${provider.name} 失败:`, error);
-                    skippedCount++; // 失败的也算跳过
-                    // 继续导入下一个
-                }
-            }
+function setAccountCredentialForm(account) {
+    document.getElementById('accountCredentialMode').value = account && account.credential_mode === 'helper' ? 'helper' : 'token';
+    document.getElementById('accountApiKeyHelper').value = account ? (account.api_key_helper || '') : '';
+    document.getElementById('accountApiKeyHelperTtl').value = account && account.api_key_helper_ttl_ms ? account.api_key_helper_ttl_ms : '';
+    toggleAccountCredentialMode();
+}
 
-            // 重新加载账号列表
-            await loadAccounts(currentAccountPage);
+// 读取账号表单中的凭据模式，辅助命令模式下命令必填，校验失败时返回 null
+function getAccountCredentialForm() {
+    const mode = document.getElementById('accountCredentialMode').value || 'token';
+    const helper = document.getElementById('accountApiKeyHelper').value.trim();
+    const ttl = document.getElementById('accountApiKeyHelperTtl').value.trim();
+    if (mode === 'helper' && !helper) {
+        showError(window.i18n.t('key_helper.command_required'));
+        return null;
+    }
+    const ttlMs = ttl ? parseInt(ttl, 10) : null;
+    if (ttl && (!Number.isInteger(ttlMs) || ttlMs <= 0)) {
+        showError(window.i18n.t('key_helper.invalid_ttl'));
+        return null;
+    }
+    return { mode, helper: helper || null, ttlMs };
+}
 
-            // 显示导入结果
-            let message = window.i18n.t('accounts.import_success').replace('{count}', importedCount);
-            if (skippedCount > 0) {
-                message += '\n' + window.i18n.t('accounts.import_skipped').replace('{count}', skippedCount);
-            }
-            showSuccess(message);
+// 把保存的请求头列表填入文本框，每行一个 "Name: Value"
+function setCustomHeadersForm(elementId, customHeaders) {
+    let headers = [];
+    try {
+        headers = customHeaders ? JSON.parse(customHeaders) : [];
+    } catch (e) {
+        headers = [];
+    }
+    document.getElementById(elementId).value = headers.map(h => `${h.name}: ${h.value}`).join('\n');
+}
 
-        } catch (error) {
-            showError(window.i18n.t('accounts.import_error') + ': ' + getErrorMessage(error));
+// 读取文本框中的请求头，格式错误时提示并返回 null
+function getCustomHeadersForm(elementId) {
+    const headers = [];
+    const lines = document.getElementById(elementId).value.split('\n');
+    for (const line of lines) {
+        if (!line.trim()) {
+            continue;
         }
-    };
+        const index = line.indexOf(':');
+        const name = index > 0 ? line.substring(0, index).trim() : '';
+        if (!name) {
+            showError(window.i18n.t('headers.invalid_line').replace('{line}', line.trim()));
+            return null;
+        }
+        headers.push({ name, value: line.substring(index + 1).trim() });
+    }
+    return headers;
+}
 
-    input.click();
+// 列表中显示自定义请求头的名称
+function renderCustomHeadersBadge(customHeaders) {
+    let headers = [];
+    try {
+        headers = customHeaders ? JSON.parse(customHeaders) : [];
+    } catch (e) {
+        headers = [];
+    }
+    if (headers.length === 0) {
+        return '';
+    }
+    return `<div class="small text-muted"><i class="fas fa-heading me-1"></i>${window.i18n.t('headers.label')}: ${headers.map(h => h.name).join(', ')}</div>`;
 }
 
-// Edit directory
-async function editDirectory(directoryId) {
+// 试运行辅助命令，成功时显示隐藏中间部分的密钥
+async function testAccountKeyHelper() {
+    const command = document.getElementById('accountApiKeyHelper').value.trim();
+    if (!command) {
+        showError(window.i18n.t('key_helper.command_required'));
+        return;
+    }
+    const button = document.getElementById('accountTestKeyHelper');
+    button.disabled = true;
     try {
-        const directory = directories.find(dir => dir.id === directoryId);
-        if (!directory) {
-            showError(window.i18n.t('error.directory_not_found'));
-            return;
-        }
-        
-        // Set editing state
-        editingDirectoryId = directoryId;
-        
-        // Fill form
-        document.getElementById('directoryName').value = directory.name;
-        document.getElementById('directoryPath').value = directory.path;
-        
-        // Change modal title
-        document.querySelector('#directoryModal .modal-title').textContent = window.i18n.t('modal.edit_directory');
-        
-        // Show modal
-        const modal = new bootstrap.Modal(document.getElementById('directoryModal'));
-        modal.show();
-        
+        const maskedKey = await tauriTestApiKeyHelper(command);
+        showSuccess(window.i18n.t('key_helper.test_ok').replace('{key}', maskedKey));
     } catch (error) {
-        showError(window.i18n.t('error.edit_directory') + ': ' + getErrorMessage(error));
+        showError(window.i18n.t('key_helper.test_failed') + ': ' + getErrorMessage(error));
+    } finally {
+        button.disabled = false;
     }
 }
 
+// 根据选中的URL更新账号环境变量JSON显示
+function updateAccountEnvVarsFromUrl(selectedUrl) {
+    try {
+        // 找到选中的URL对应的baseUrl对象
+        const selectedBaseUrl = baseUrls.find(url => url.url === selectedUrl);
+
+        let envVarsToShow = {};
 
-// Reset directory modal state
-function resetDirectoryModal() {
-    document.getElementById('directoryForm').reset();
-    document.querySelector('#directoryModal .modal-title').textContent = window.i18n.t('modal.add_directory');
-    
-    // Reset editing state
-    editingDirectoryId = null;
+        if (selectedBaseUrl && selectedBaseUrl.default_env_vars) {
+            try {
+                // 直接使用URL的默认环境变量，不合并
+                envVarsToShow = JSON.parse(selectedBaseUrl.default_env_vars);
+            } catch (e) {
+                console.warn('解析URL默认环境变量失败:', e);
+                envVarsToShow = {};
+            }
+        }
+
+        // Bedrock / Vertex 的必填变量预先列出，方便填写
+        const provider = getProviderType(selectedBaseUrl ? selectedBaseUrl.provider_type : null);
+        if (provider) {
+            provider.required.forEach(v => {
+                if (!(v.key in envVarsToShow)) {
+                    envVarsToShow[v.key] = '';
+                }
+            });
+        }
+        renderAccountProviderHint(selectedUrl);
+
+        // 更新JSON文本框 - 直接显示URL的默认环境变量
+        document.getElementById('accountCustomEnvVarsJson').value =
+            Object.keys(envVarsToShow).length > 0 ?
+                JSON.stringify(envVarsToShow, null, 2) : '{}';
+
+    } catch (error) {
+        console.error('更新账号环境变量失败:', error);
+        showError('更新环境变量失败: ' + getErrorMessage(error));
+    }
 }
 
-// Global variable to prevent multiple delete operations
-let isDeleting = false;
+// 查找供应商类型定义
+function getProviderType(providerType) {
+    return providerTypes.find(p => p.id === (providerType || 'anthropic')) || null;
+}
 
-// Prompt delete directory - shows confirmation first
-async function promptDeleteDirectory(directoryId, directoryName) {
-    // 防止重复删除操作
-    if (isDeleting) {
-        await ask( window.i18n.t('info.deleting'), { title: window.i18n.t('info.deleting_title'), type: 'info' });
+// 在账号表单中提示所选 URL 的供应商需要的环境变量
+function renderAccountProviderHint(selectedUrl) {
+    const hint = document.getElementById('accountProviderHint');
+    if (!hint) {
         return;
     }
-    
-    // 获取目录信息以检查是否存在
-    const directory = directories.find(dir => dir.id === directoryId);
-    let exists = true;
-    if (directory) {
-        try {
-            exists = await tauriCheckDirectoryExists(directory.path);
-        } catch (error) {
-            console.warn(window.i18n.t('error.check_directory_exists') + ':', error);
-        }
+    const selectedBaseUrl = baseUrls.find(url => url.url === selectedUrl);
+    const provider = getProviderType(selectedBaseUrl ? selectedBaseUrl.provider_type : null);
+    if (!provider || (provider.required.length === 0 && provider.optional.length === 0)) {
+        hint.textContent = '';
+        hint.style.display = 'none';
+        return;
     }
-    
-    // 根据目录是否存在显示不同的确认信息
-    let confirmMessage, confirmTitle;
-    if (exists) {
-        confirmMessage = window.i18n.t('confirm.delete_directory').replace('{name}', directoryName);
-        confirmTitle = window.i18n.t('confirm.delete_directory_title');
-    } else {
-        confirmMessage = window.i18n.t('confirm.cleanup_directory').replace('{name}', directoryName);
-        confirmTitle = window.i18n.t('confirm.cleanup_directory_title');
+    const required = provider.required.map(v => v.key).join(', ');
+    const optional = provider.optional.map(v => v.key).join(', ');
+    hint.textContent = window.i18n.t('providers.account_hint')
+        .replace('{name}', provider.name)
+        .replace('{required}', required || '-')
+        .replace('{optional}', optional || '-');
+    hint.style.display = '';
+}
+
+// 按 URL 的供应商类型校验账号令牌和环境变量，失败时提示并返回 false
+async function validateAccountProviderEnv(baseUrl, token, customEnvVars) {
+    try {
+        await tauriValidateAccountProviderEnv(baseUrl, token, customEnvVars);
+        return true;
+    } catch (error) {
+        showError(window.i18n.t('providers.invalid') + ': ' + getErrorMessage(error));
+        return false;
+    }
+}
+
+// 填充 URL 表单中的供应商类型下拉框
+function updateProviderTypeSelect() {
+    const select = document.getElementById('urlProviderType');
+    if (!select || providerTypes.length === 0) {
+        return;
     }
+    const current = select.value || 'anthropic';
+    select.innerHTML = providerTypes.map(p => `<option value="${p.id}">${p.name}</option>`).join('');
+    select.value = current;
+}
+
+// Prompt delete account - shows confirmation first
+async function promptDeleteAccount(accountId) {
+    // 找到要删除的账号信息
+    const account = accounts.find(acc => acc.id === accountId);
+    const accountName = account ? account.name : `ID: ${accountId}`;
     
     // 显示确认框
     try {
         const userConfirmed = await ask(
-            confirmMessage,
-            { 
-                title: confirmTitle, 
-                type: 'warning' 
+            window.i18n.t('confirm.delete_account_with_name').replace('{name}', accountName),
+            {
+                title: window.i18n.t('confirm.delete_account_title'),
+                type: 'warning'
             }
         );
-        
+
         if (!userConfirmed) {
             return;
         }
-        
+
         // 用户确认后才执行删除
-        await executeDelete(directoryId, directoryName);
+        await executeDeleteAccount(accountId, accountName);
     } catch (error) {
         // 如果 Tauri 对话框失败，fallback 到自定义确认对话框
-        const userConfirmed = await customConfirm(confirmMessage, confirmTitle);
+        const userConfirmed = await customConfirm(
+            window.i18n.t('confirm.delete_account_with_name').replace('{name}', accountName),
+            window.i18n.t('confirm.delete_account_title')
+        );
         if (userConfirmed) {
-            await executeDelete(directoryId, directoryName);
+            await executeDeleteAccount(accountId, accountName);
         }
     }
 }
 
-// 清除所有无效目录
-async function cleanupInvalidDirectories() {
-    // 防止重复操作
-    if (isDeleting) {
-        await ask( window.i18n.t('info.deleting'), { title: window.i18n.t('info.deleting_title'), type: 'info' });
+// 执行实际的账号删除操作
+async function executeDeleteAccount(accountId, accountName) {
+    try {
+        await tauriDeleteAccount(accountId);
+        await loadAccounts(currentAccountPage);
+        await loadAccountBaseUrlOptions();
+        showSuccess(window.i18n.t('success.account_deleted_with_name').replace('{name}', accountName));
+    } catch (error) {
+        showError(window.i18n.t('error.delete_account') + ' "' + accountName + '": ' + getErrorMessage(error));
+    }
+}
+
+// Delete account (legacy function for compatibility)
+async function deleteAccount(accountId) {
+    const confirmed = await customConfirm(
+        window.i18n.t('confirm.delete_account'),
+        window.i18n.t('confirm.delete_account_title')
+    );
+    if (!confirmed) {
         return;
     }
 
     try {
-        // 检查所有目录的存在性
-        const directoriesWithStatus = await Promise.all(
-            directories.map(async directory => {
-                try {
-                    const exists = await tauriCheckDirectoryExists(directory.path);
-                    return { ...directory, exists };
-                } catch (error) {
-                    console.warn(window.i18n.t('error.check_directory_exists') + ':', error);
-                    return { ...directory, exists: true }; // 默认认为存在
-                }
-            })
-        );
+        await tauriDeleteAccount(accountId);
+        await loadAccounts(currentAccountPage);
+        await loadAccountBaseUrlOptions();
+        showSuccess(window.i18n.t('success.account_deleted'));
+    } catch (error) {
+        showError(window.i18n.t('error.delete_account') + ': ' + getErrorMessage(error));
+    }
+}
 
-        // 找出所有不存在的目录
-        const invalidDirectories = directoriesWithStatus.filter(dir => !dir.exists);
+// 全局变量存储要导出的账号
+let exportAccountsData = [];
+let exportCurrentUrlFilter = ''; // 当前导出URL筛选
+let exportBaseUrlsMap = {}; // URL地址到名称的映射
 
-        if (invalidDirectories.length === 0) {
-            showSuccess(window.i18n.t('success.no_invalid_directories'));
+// 批量导出账号
+async function exportAccounts() {
+    try {
+        // 获取所有账号（不分页）
+        const response = await tauriGetAccounts({
+            page: 1,
+            per_page: 10000,
+            search: null,
+            base_url: null
+        });
+
+        if (!response || !response.accounts || response.accounts.length === 0) {
+            showError(window.i18n.t('error.no_accounts_to_export') || '没有账号可导出');
             return;
         }
 
-        // 显示确认对话框
-        const directoryNames = invalidDirectories.map(d => d.name).join('\n- ');
-        const confirmMessage = window.i18n.t('confirm.cleanup_directories')
-            .replace('{count}', invalidDirectories.length)
-            .replace('{names}', directoryNames);
+        // 获取所有 base_urls 用于显示名称
+        const baseUrls = await tauriGetBaseUrls();
 
-        const userConfirmed = await ask(
-            confirmMessage,
-            {
-                title: window.i18n.t('confirm.cleanup_directories_title'),
-                type: 'warning'
-            }
-        );
+        // 创建 URL 地址到名称的映射
+        exportBaseUrlsMap = {};
+        baseUrls.forEach(bu => {
+            exportBaseUrlsMap[bu.url] = bu.name;
+        });
 
-        if (!userConfirmed) {
+        // 存储账号数据
+        exportAccountsData = response.accounts;
+        exportCurrentUrlFilter = ''; // 重置筛选
+
+        // 填充 URL 筛选下拉框
+        const urlFilter = document.getElementById('exportUrlFilter');
+        const uniqueUrls = [...new Set(exportAccountsData.map(acc => acc.base_url))];
+
+        urlFilter.innerHTML = '<option value="">' + window.i18n.t('select.all_urls') + '</option>' +
+            uniqueUrls.map(url => {
+                const urlName = exportBaseUrlsMap[url] || url;
+                return `<option value="${url}">${urlName}</option>`;
+            }).join('');
+
+        // 填充标签筛选下拉框
+        const tagFilter = document.getElementById('exportTagFilter');
+        const uniqueTags = [...new Set(exportAccountsData.flatMap(acc => acc.tags.split(',').map(t => t.trim()).filter(t => t)))];
+        tagFilter.innerHTML = '<option value="">' + window.i18n.t('export.all_tags') + '</option>' +
+            uniqueTags.map(tag => `<option value="${escapeHtml(tag)}">${escapeHtml(tag)}</option>`).join('');
+
+        // 渲染账号列表
+        renderExportAccountsList();
+
+        // 显示模态框
+        const modal = new bootstrap.Modal(document.getElementById('exportSelectModal'));
+        modal.show();
+
+    } catch (error) {
+        showError(window.i18n.t('accounts.export_error') + ': ' + getErrorMessage(error));
+    }
+}
+
+// 渲染导出账号列表
+function renderExportAccountsList(filteredUrl = '') {
+    const container = document.getElementById('exportAccountsList');
+
+    // 根据 URL 和标签筛选账号
+    const tag = document.getElementById('exportTagFilter').value;
+    const accountsToShow = exportAccountsData
+        .filter(acc => !filteredUrl || acc.base_url === filteredUrl)
+        .filter(acc => !tag || acc.tags.split(',').map(t => t.trim()).includes(tag));
+
+    if (accountsToShow.length === 0) {
+        container.innerHTML = '<div class="text-muted text-center py-3">' +
+            window.i18n.t('accounts.no_accounts_for_url') + '</div>';
+        updateExportSelectedCount();
+        return;
+    }
+
+    container.innerHTML = accountsToShow.map((account, index) => {
+        // 使用账号在原始数组中的索引
+        const originalIndex = exportAccountsData.indexOf(account);
+        const urlName = exportBaseUrlsMap[account.base_url] || account.base_url;
+        return `
+            <div class="form-check mb-2">
+                <input class="form-check-input export-account-checkbox" type="checkbox"
+                       value="${originalIndex}" id="exportAccount${originalIndex}"
+                       onchange="updateExportSelectedCount()" checked>
+                <label class="form-check-label" for="exportAccount${originalIndex}">
+                    <strong>${account.name}</strong>
+                    <br>
+                    <small class="text-muted">
+                        ${urlName} | ${account.token.substring(0, 20)}...
+                        ${account.model ? ` | <i class="fas fa-microchip"></i> ${account.model}` : ''}
+                    </small>
+                </label>
+            </div>
+        `;
+    }).join('');
+
+    updateExportSelectedCount();
+}
+
+// 按 URL 筛选导出账号
+function filterExportAccountsByUrl() {
+    const urlFilter = document.getElementById('exportUrlFilter');
+    exportCurrentUrlFilter = urlFilter.value;
+    renderExportAccountsList(exportCurrentUrlFilter);
+}
+
+// 更新选中数量显示
+function updateExportSelectedCount() {
+    const selectedCount = document.querySelectorAll('.export-account-checkbox:checked').length;
+    const totalCount = document.querySelectorAll('.export-account-checkbox').length;
+
+    const countElement = document.getElementById('exportSelectedCount');
+    if (countElement) {
+        const countText = window.i18n.t('accounts.selected_count') || '已选择';
+        countElement.innerHTML = `${countText}: <strong>${selectedCount}</strong> / ${totalCount}`;
+    }
+}
+
+// 全选导出账号
+function selectAllExportAccounts() {
+    document.querySelectorAll('.export-account-checkbox').forEach(checkbox => {
+        checkbox.checked = true;
+    });
+    updateExportSelectedCount();
+}
+
+// 取消全选导出账号
+function deselectAllExportAccounts() {
+    document.querySelectorAll('.export-account-checkbox').forEach(checkbox => {
+        checkbox.checked = false;
+    });
+    updateExportSelectedCount();
+}
+
+// 确认导出账号
+async function confirmExportAccounts() {
+    try {
+        // 获取选中的账号
+        const selectedIndexes = Array.from(document.querySelectorAll('.export-account-checkbox:checked'))
+            .map(checkbox => parseInt(checkbox.value));
+
+        if (selectedIndexes.length === 0) {
+            showError(window.i18n.t('accounts.export_no_selection') || '请至少选择一个账号');
             return;
         }
 
-        // 批量删除无效目录
-        isDeleting = true;
-        let successCount = 0;
-        let failCount = 0;
-        const errors = [];
+        const selectedAccounts = selectedIndexes.map(index => exportAccountsData[index]);
+        const format = document.getElementById('exportFormat').value;
 
-        for (const directory of invalidDirectories) {
-            try {
-                await tauriDeleteDirectory(directory.id);
-                successCount++;
-            } catch (error) {
-                failCount++;
-                errors.push(`${directory.name}: ${getErrorMessage(error)}`);
-                console.error(window.i18n.t('error.delete_directory_failed').replace('{name}', directory.name) + ':', error);
-            }
+        // 生成默认文件名
+        const timestamp = new Date().toISOString().replace(/:/g, '-').split('.')[0];
+        const extensions = { json: 'json', providers: 'json', shell: 'sh', csv: 'csv' };
+
+        // .env 格式每个账号一个文件，选择输出目录；其他格式使用 Tauri 的保存对话框
+        const outputPath = format === 'dotenv'
+            ? await open({ directory: true })
+            : await save({
+                defaultPath: `accounts_export_${timestamp}.${extensions[format]}`,
+                filters: [{
+                    name: format.toUpperCase(),
+                    extensions: [extensions[format]]
+                }]
+            });
+
+        // 如果用户取消了保存
+        if (!outputPath) {
+            return;
         }
 
-        // 重新加载目录列表
-        await loadDirectories();
+        const result = await tauriExportAccounts(format, {
+            account_ids: selectedAccounts.map(account => account.id)
+        }, outputPath);
+
+        // 关闭模态框
+        const modal = bootstrap.Modal.getInstance(document.getElementById('exportSelectModal'));
+        modal.hide();
+
+        const message = window.i18n.t('accounts.export_success').replace('{count}', result.accounts);
+        showSuccess(message);
 
-        // 显示结果
-        if (failCount === 0) {
-            showSuccess(window.i18n.t('success.cleanup_directories').replace('{count}', successCount));
-        } else {
-            showError(window.i18n.t('success.cleanup_partial')
-                .replace('{success}', successCount)
-                .replace('{failed}', failCount)
-                .replace('{errors}', errors.join('\n')));
-        }
     } catch (error) {
-        showError(window.i18n.t('error.cleanup_directories') + ': ' + getErrorMessage(error));
-    } finally {
-        isDeleting = false;
+        showError(window.i18n.t('accounts.export_error') + ': ' + getErrorMessage(error));
     }
 }
 
-// 执行实际的删除操作
-async function executeDelete(directoryId, directoryName) {
-    isDeleting = true;
+// 导入预览：逐项显示与已有记录的比对结果，有差异的项可单独选择处理方式
+let pendingImport = null;
+
+function getImportFormatName(format) {
+    const names = {
+        cc_switch: 'cc-switch',
+        claude_code_router: 'claude-code-router',
+        settings_json: 'settings.json (env)',
+        csv: 'CSV',
+        dotenv: '.env'
+    };
+    return names[format] || window.i18n.t('import.format.' + format);
+}
+
+const IMPORT_STATUS_BADGES = {
+    new: 'bg-success',
+    changed: 'bg-warning text-dark',
+    identical: 'bg-light text-muted border',
+    duplicate: 'bg-secondary'
+};
+
+const IMPORT_ACTION_BADGES = {
+    created: 'bg-success',
+    renamed: 'bg-success',
+    overwritten: 'bg-info text-dark',
+    merged: 'bg-info text-dark',
+    unchanged: 'bg-light text-muted border',
+    skipped: 'bg-secondary',
+    failed: 'bg-danger'
+};
+
+function renderImportStrategyOptions(selected, includeDefault) {
+    const options = includeDefault ? ['<option value="">' + window.i18n.t('import.strategy.default') + '</option>'] : [];
+    ['skip', 'overwrite', 'rename', 'merge'].forEach(strategy => {
+        options.push(`<option value="${strategy}" ${strategy === selected ? 'selected' : ''}>${window.i18n.t('import.strategy.' + strategy)}</option>`);
+    });
+    return options.join('');
+}
+
+// 显示导入预览，apply(items, strategy) 负责写入并返回处理结果
+function showImportPreview(preview, apply, afterApply) {
+    pendingImport = { preview, apply, afterApply };
+    const counts = { new: 0, changed: 0, identical: 0, duplicate: 0 };
+    preview.items.forEach(item => counts[item.status]++);
+
+    document.getElementById('importPreviewFormat').textContent = window.i18n.t('import.detected_format')
+        .replace('{format}', getImportFormatName(preview.format))
+        .replace('{new}', counts.new)
+        .replace('{changed}', counts.changed)
+        .replace('{identical}', counts.identical + counts.duplicate);
+
+    const strategyGroup = document.getElementById('importStrategyGroup');
+    strategyGroup.style.display = counts.changed > 0 ? '' : 'none';
+    document.getElementById('importStrategy').innerHTML = renderImportStrategyOptions('skip', false);
+
+    document.getElementById('importPreviewHead').innerHTML = `
+        <tr>
+            <th>${window.i18n.t('import.header_kind')}</th>
+            <th>${window.i18n.t('import.header_name')}</th>
+            <th>${window.i18n.t('import.header_status')}</th>
+            <th>${window.i18n.t('import.header_diff')}</th>
+            <th>${window.i18n.t('import.header_strategy')}</th>
+        </tr>
+    `;
+    document.getElementById('importPreviewList').innerHTML = preview.items.map((item, index) => `
+        <tr class="${item.status === 'identical' || item.status === 'duplicate' ? 'text-muted' : ''}">
+            <td class="small">${window.i18n.t('import.kind.' + item.kind)}</td>
+            <td>${escapeHtml(item.name)}</td>
+            <td><span class="badge ${IMPORT_STATUS_BADGES[item.status]}">${window.i18n.t('import.status.' + item.status)}</span></td>
+            <td class="small">
+                ${item.diffs.map(diff => `<div><strong>${escapeHtml(diff.field)}</strong>: ${escapeHtml(diff.current || '-')} → ${escapeHtml(diff.incoming)}</div>`).join('')}
+            </td>
+            <td>
+                ${item.status === 'changed'
+                    ? `<select class="form-select form-select-sm import-item-strategy" data-index="${index}">${renderImportStrategyOptions('', true)}</select>`
+                    : ''}
+            </td>
+        </tr>
+    `).join('');
+
+    const confirmButton = document.getElementById('importPreviewConfirm');
+    confirmButton.style.display = '';
+    confirmButton.disabled = counts.new + counts.changed === 0 && preview.bundle.bindings.length === 0;
+
+    bootstrap.Modal.getOrCreateInstance(document.getElementById('importPreviewModal')).show();
+}
+
+async function confirmImport() {
+    if (!pendingImport) return;
+    const { preview, apply, afterApply } = pendingImport;
+
+    const items = preview.items.map(item => ({ ...item }));
+    document.querySelectorAll('.import-item-strategy').forEach(select => {
+        if (select.value) {
+            items[parseInt(select.dataset.index)].strategy = select.value;
+        }
+    });
+    const strategy = document.getElementById('importStrategy').value;
 
     try {
-        await tauriDeleteDirectory(directoryId);
-        await loadDirectories();
-        showSuccess(window.i18n.t('success.directory_deleted').replace('{name}', directoryName));
+        const report = await apply(items, strategy);
+        pendingImport = null;
+        renderImportReport(report);
+        if (afterApply) {
+            await afterApply(report);
+        }
+        const message = window.i18n.t('import.summary')
+            .replace('{created}', report.created)
+            .replace('{updated}', report.updated)
+            .replace('{skipped}', report.skipped)
+            .replace('{failed}', report.failed);
+        if (report.failed > 0) {
+            showError(message);
+        } else {
+            showSuccess(message);
+        }
     } catch (error) {
-        showError(window.i18n.t('error.delete_directory') + ' "' + directoryName + '": ' + getErrorMessage(error));
-    } finally {
-        isDeleting = false;
+        showError(window.i18n.t('accounts.import_error') + ': ' + getErrorMessage(error));
     }
 }
 
+// 导入完成后在同一窗口中列出每一项的处理结果
+function renderImportReport(report) {
+    document.getElementById('importStrategyGroup').style.display = 'none';
+    document.getElementById('importPreviewConfirm').style.display = 'none';
+    document.getElementById('importPreviewHead').innerHTML = `
+        <tr>
+            <th>${window.i18n.t('import.header_kind')}</th>
+            <th>${window.i18n.t('import.header_name')}</th>
+            <th>${window.i18n.t('import.header_result')}</th>
+            <th>${window.i18n.t('import.header_detail')}</th>
+        </tr>
+    `;
+    document.getElementById('importPreviewList').innerHTML = report.items.map(item => `
+        <tr>
+            <td class="small">${window.i18n.t('import.kind.' + item.kind)}</td>
+            <td>${escapeHtml(item.name)}</td>
+            <td><span class="badge ${IMPORT_ACTION_BADGES[item.action]}">${window.i18n.t('import.action.' + item.action)}</span></td>
+            <td class="small">${escapeHtml(item.detail || '')}</td>
+        </tr>
+    `).join('');
+}
 
-// View config
-async function viewConfig(directoryId) {
+// 批量导入账号：自动识别文件格式，预览后再导入
+async function importAccounts() {
+    // 创建文件输入元素
+    const input = document.createElement('input');
+    input.type = 'file';
+    input.accept = '.json,.csv,.env,.txt';
+
+    input.onchange = async (e) => {
+        const file = e.target.files[0];
+        if (!file) return;
+
+        try {
+            const text = await file.text();
+            const preview = await tauriPreviewAccountImport(file.name, text);
+            showImportPreview(
+                preview,
+                (items, strategy) => tauriImportAccounts(preview.bundle, items, strategy),
+                async () => {
+                    // 重新加载账号列表
+                    await loadAccounts(currentAccountPage);
+                    await loadAccountBaseUrlOptions();
+                }
+            );
+        } catch (error) {
+            showError(window.i18n.t('accounts.import_error') + ': ' + getErrorMessage(error));
+        }
+    };
+
+    input.click();
+}
+
+// Edit directory
+async function editDirectory(directoryId) {
     try {
-        const config = await tauriGetCurrentConfig(directoryId);
-        const envConfig = config.env_config;
-        const directory = config.directory;
+        const directory = directories.find(dir => dir.id === directoryId);
+        if (!directory) {
+            showError(window.i18n.t('error.directory_not_found'));
+            return;
+        }
         
-        // Build config content HTML
-        let configHtml = `
-            <div class="mb-4">
-                <h6 class="fw-bold text-primary">
-                    <i class="fas fa-folder me-2"></i>${window.i18n.t('text.directory_info')}
-                </h6>
-                <div class="card bg-light">
-                    <div class="card-body">
-                        <div class="row">
-                            <div class="col-sm-3"><strong>${window.i18n.t('text.directory_name')}:</strong></div>
-                            <div class="col-sm-9">${directory.name}</div>
-                        </div>
-                        <div class="row mt-2">
-                            <div class="col-sm-3"><strong>${window.i18n.t('text.directory_path')}:</strong></div>
-                            <div class="col-sm-9"><code>${directory.path}</code></div>
-                        </div>
-                        <div class="row mt-2">
-                            <div class="col-sm-3"><strong>${window.i18n.t('text.directory_status')}:</strong></div>
-                            <div class="col-sm-9">
-                                <span class="badge ${directory.is_active ? 'bg-success' : 'bg-secondary'}">
-                                    ${directory.is_active ? window.i18n.t('status.active') : window.i18n.t('status.inactive')}
-                                </span>
-                            </div>
-                        </div>
-                    </div>
-                </div>
-            </div>
-        `;
+        // Set editing state
+        editingDirectoryId = directoryId;
         
-        if (Object.keys(envConfig).length > 0) {
-            configHtml += `
-                <div class="mb-4">
+        // Fill form
+        document.getElementById('directoryName').value = directory.name;
+        document.getElementById('directoryPath').value = directory.path;
+
+        // 加载权限预设选项
+        await loadPermissionPresetOptions('directoryPermissionPreset', directory.permission_preset_id);
+        await loadProxyOptions('directoryProxy', directory.proxy_id);
+        await loadMcpServerChecks('directoryMcpServers', await tauriGetDirectoryMcpServers(directory.id));
+        await loadHookChecks('directoryHooks', await tauriGetDirectoryHooks(directory.id));
+        await loadDefinitionChecks('directoryDefinitions', await tauriGetDirectoryDefinitions(directory.id));
+        
+        // Change modal title
+        document.querySelector('#directoryModal .modal-title').textContent = window.i18n.t('modal.edit_directory');
+        
+        // Show modal
+        const modal = new bootstrap.Modal(document.getElementById('directoryModal'));
+        modal.show();
+        
+    } catch (error) {
+        showError(window.i18n.t('error.edit_directory') + ': ' + getErrorMessage(error));
+    }
+}
+
+
+// Reset directory modal state
+function resetDirectoryModal() {
+    document.getElementById('directoryForm').reset();
+    loadPermissionPresetOptions('directoryPermissionPreset', null);
+    loadProxyOptions('directoryProxy', null);
+    document.querySelector('#directoryModal .modal-title').textContent = window.i18n.t('modal.add_directory');
+    
+    // Reset editing state
+    editingDirectoryId = null;
+}
+
+// Global variable to prevent multiple delete operations
+let isDeleting = false;
+
+// Prompt delete directory - shows confirmation first
+async function promptDeleteDirectory(directoryId, directoryName) {
+    // 防止重复删除操作
+    if (isDeleting) {
+        await ask( window.i18n.t('info.deleting'), { title: window.i18n.t('info.deleting_title'), type: 'info' });
+        return;
+    }
+    
+    // 获取目录信息以检查是否存在
+    const directory = directories.find(dir => dir.id === directoryId);
+    let exists = true;
+    if (directory) {
+        try {
+            exists = await tauriCheckDirectoryExists(directory.path);
+        } catch (error) {
+            console.warn(window.i18n.t('error.check_directory_exists') + ':', error);
+        }
+    }
+    
+    // 根据目录是否存在显示不同的确认信息
+    let confirmMessage, confirmTitle;
+    if (exists) {
+        confirmMessage = window.i18n.t('confirm.delete_directory').replace('{name}', directoryName);
+        confirmTitle = window.i18n.t('confirm.delete_directory_title');
+    } else {
+        confirmMessage = window.i18n.t('confirm.cleanup_directory').replace('{name}', directoryName);
+        confirmTitle = window.i18n.t('confirm.cleanup_directory_title');
+    }
+    
+    // 显示确认框
+    try {
+        const userConfirmed = await ask(
+            confirmMessage,
+            { 
+                title: confirmTitle, 
+                type: 'warning' 
+            }
+        );
+        
+        if (!userConfirmed) {
+            return;
+        }
+        
+        // 用户确认后才执行删除
+        await executeDelete(directoryId, directoryName);
+    } catch (error) {
+        // 如果 Tauri 对话框失败，fallback 到自定义确认对话框
+        const userConfirmed = await customConfirm(confirmMessage, confirmTitle);
+        if (userConfirmed) {
+            await executeDelete(directoryId, directoryName);
+        }
+    }
+}
+
+// 清除所有无效目录
+async function cleanupInvalidDirectories() {
+    // 防止重复操作
+    if (isDeleting) {
+        await ask( window.i18n.t('info.deleting'), { title: window.i18n.t('info.deleting_title'), type: 'info' });
+        return;
+    }
+
+    try {
+        // 检查所有目录的存在性
+        const directoriesWithStatus = await Promise.all(
+            directories.map(async directory => {
+                try {
+                    const exists = await tauriCheckDirectoryExists(directory.path);
+                    return { ...directory, exists };
+                } catch (error) {
+                    console.warn(window.i18n.t('error.check_directory_exists') + ':', error);
+                    return { ...directory, exists: true }; // 默认认为存在
+                }
+            })
+        );
+
+        // 找出所有不存在的目录
+        const invalidDirectories = directoriesWithStatus.filter(dir => !dir.exists);
+
+        if (invalidDirectories.length === 0) {
+            showSuccess(window.i18n.t('success.no_invalid_directories'));
+            return;
+        }
+
+        // 显示确认对话框
+        const directoryNames = invalidDirectories.map(d => d.name).join('\n- ');
+        const confirmMessage = window.i18n.t('confirm.cleanup_directories')
+            .replace('{count}', invalidDirectories.length)
+            .replace('{names}', directoryNames);
+
+        const userConfirmed = await ask(
+            confirmMessage,
+            {
+                title: window.i18n.t('confirm.cleanup_directories_title'),
+                type: 'warning'
+            }
+        );
+
+        if (!userConfirmed) {
+            return;
+        }
+
+        // 批量删除无效目录
+        isDeleting = true;
+        let successCount = 0;
+        let failCount = 0;
+        const errors = [];
+
+        for (const directory of invalidDirectories) {
+            try {
+                await tauriDeleteDirectory(directory.id);
+                successCount++;
+            } catch (error) {
+                failCount++;
+                errors.push(`${directory.name}: ${getErrorMessage(error)}`);
+                console.error(window.i18n.t('error.delete_directory_failed').replace('{name}', directory.name) + ':', error);
+            }
+        }
+
+        // 重新加载目录列表
+        await loadDirectories();
+
+        // 显示结果
+        if (failCount === 0) {
+            showSuccess(window.i18n.t('success.cleanup_directories').replace('{count}', successCount));
+        } else {
+            showError(window.i18n.t('success.cleanup_partial')
+                .replace('{success}', successCount)
+                .replace('{failed}', failCount)
+                .replace('{errors}', errors.join('\n')));
+        }
+    } catch (error) {
+        showError(window.i18n.t('error.cleanup_directories') + ': ' + getErrorMessage(error));
+    } finally {
+        isDeleting = false;
+    }
+}
+
+// 执行实际的删除操作
+async function executeDelete(directoryId, directoryName) {
+    isDeleting = true;
+
+    try {
+        await tauriDeleteDirectory(directoryId);
+        await loadDirectories();
+        showSuccess(window.i18n.t('success.directory_deleted').replace('{name}', directoryName));
+    } catch (error) {
+        showError(window.i18n.t('error.delete_directory') + ' "' + directoryName + '": ' + getErrorMessage(error));
+    } finally {
+        isDeleting = false;
+    }
+}
+
+
+// View config
+async function viewConfig(directoryId) {
+    try {
+        const config = await tauriGetCurrentConfig(directoryId);
+        const envConfig = config.env_config;
+        const directory = config.directory;
+        
+        // Build config content HTML
+        let configHtml = `
+            <div class="mb-4">
+                <h6 class="fw-bold text-primary">
+                    <i class="fas fa-folder me-2"></i>${window.i18n.t('text.directory_info')}
+                </h6>
+                <div class="card bg-light">
+                    <div class="card-body">
+                        <div class="row">
+                            <div class="col-sm-3"><strong>${window.i18n.t('text.directory_name')}:</strong></div>
+                            <div class="col-sm-9">${directory.name}</div>
+                        </div>
+                        <div class="row mt-2">
+                            <div class="col-sm-3"><strong>${window.i18n.t('text.directory_path')}:</strong></div>
+                            <div class="col-sm-9"><code>${directory.path}</code></div>
+                        </div>
+                        <div class="row mt-2">
+                            <div class="col-sm-3"><strong>${window.i18n.t('text.directory_status')}:</strong></div>
+                            <div class="col-sm-9">
+                                <span class="badge ${directory.is_active ? 'bg-success' : 'bg-secondary'}">
+                                    ${directory.is_active ? window.i18n.t('status.active') : window.i18n.t('status.inactive')}
+                                </span>
+                            </div>
+                        </div>
+                    </div>
+                </div>
+            </div>
+        `;
+        
+        if (Object.keys(envConfig).length > 0) {
+            configHtml += `
+                <div class="mb-4">
                     <h6 class="fw-bold text-success">
                         <i class="fas fa-cog me-2"></i>${window.i18n.t('text.env_config')}
                     </h6>
@@ -1565,7 +2991,7 @@ async function loadAssociationDirectories() {
 // Load association accounts
 async function loadAssociationAccounts() {
     try {
-        const accountsResponse = await tauriGetAccounts({ per_page: 1000 });
+        const accountsResponse = await tauriGetAccounts({ per_page: 1000, enabled: true });
         associationAccounts = accountsResponse.accounts || accountsResponse;
 
         const select = document.getElementById('associationAccountSelect');
@@ -1976,13 +3402,14 @@ function updateSwitchButtonState() {
 // Database management functions
 async function loadDatabaseInfo() {
     try {
-        const [info, connections] = await Promise.all([
+        const [info, connections, migrations] = await Promise.all([
             tauriGetDatabaseInfo(),
-            tauriGetDatabaseConnections()
+            tauriGetDatabaseConnections(),
+            tauriGetMigrationStatus().catch(() => null)
         ]);
         
         // 显示当前连接信息
-        displayDatabaseInfo(info);
+        displayDatabaseInfo(info, migrations);
         
         // 更新连接选择器
         const select = document.getElementById('dbConnectionSelect');
@@ -2021,7 +3448,27 @@ async function loadDatabaseInfo() {
 }
 
 // 显示数据库信息
-function displayDatabaseInfo(info) {
+// 结构版本：已执行的最高版本 / 程序支持的最高版本，附带待执行和未知迁移提示
+function renderSchemaVersion(migrations) {
+    if (!migrations) {
+        return '';
+    }
+    const known = migrations.filter(m => m.known);
+    const applied = migrations.filter(m => m.applied_at);
+    const current = applied.length ? Math.max(...applied.map(m => m.version)) : 0;
+    const latest = known.length ? Math.max(...known.map(m => m.version)) : 0;
+    const pending = known.filter(m => !m.applied_at).length;
+
+    let badge = `<span class="badge bg-success ms-1">${window.i18n.t('migrate.up_to_date')}</span>`;
+    if (migrations.some(m => !m.known)) {
+        badge = `<span class="badge bg-danger ms-1">${window.i18n.t('migrate.too_new')}</span>`;
+    } else if (pending > 0) {
+        badge = `<span class="badge bg-warning text-dark ms-1">${window.i18n.t('migrate.pending').replace('{}', pending)}</span>`;
+    }
+    return `<br><strong>${window.i18n.t('migrate.schema_version')}:</strong> ${current} / ${latest}${badge}`;
+}
+
+function displayDatabaseInfo(info, migrations = null) {
     const currentDbInfo = document.getElementById('currentDbInfo');
     if (info.name) {
         currentDbInfo.innerHTML = `
@@ -2029,7 +3476,7 @@ function displayDatabaseInfo(info) {
             <strong>${window.i18n.t('text.database_url')}:</strong> <code>${info.url}</code><br>
             <strong>${window.i18n.t('text.pool_size')}:</strong> ${info.pool_size}<br>
             <strong>${window.i18n.t('text.checked_out')}:</strong> ${info.checked_out}<br>
-            <strong>${window.i18n.t('text.checked_in')}:</strong> ${info.checked_in}
+            <strong>${window.i18n.t('text.checked_in')}:</strong> ${info.checked_in}${renderSchemaVersion(migrations)}
         `;
         currentDbInfo.className = 'alert alert-success';
     } else {
@@ -2111,11 +3558,10 @@ async function switchDatabase() {
         // 刷新数据库信息以显示当前连接状态
         await loadDatabaseInfo();
         
-        // 重新加载数据以确保使用新连接（仅在实际切换时需要）
-        if (connectionName === 'sqlite') {
-            await loadAccounts();
-            await loadDirectories();
-        }
+        // 切换立即生效，重新加载数据以显示新数据库中的内容
+        await loadAccounts(1, true);
+        await loadDirectories();
+        await loadBaseUrls();
     } catch (error) {
         showDbMessage(window.i18n.t('error.switch_database') + ': ' + getErrorMessage(error), 'error');
     }
@@ -2213,6 +3659,26 @@ function showDbMessage(message, type) {
 async function loadBaseUrls() {
     try {
         baseUrls = await tauriGetBaseUrls();
+        try {
+            const checks = await tauriGetLatestEndpointChecks();
+            latestEndpointChecks = Object.fromEntries(checks.map(check => [check.base_url_id, check]));
+        } catch (error) {
+            console.warn('获取健康检查结果失败:', error);
+        }
+        try {
+            const adapters = await tauriGetBalanceAdapters();
+            balanceAdapters = Object.fromEntries(adapters.map(adapter => [adapter.base_url_id, adapter]));
+        } catch (error) {
+            console.warn('获取余额查询配置失败:', error);
+        }
+        if (providerTypes.length === 0) {
+            try {
+                providerTypes = await tauriGetProviderTypes();
+                updateProviderTypeSelect();
+            } catch (error) {
+                console.warn('获取供应商类型失败:', error);
+            }
+        }
         renderBaseUrls();
         updateBaseUrlSelect();
     } catch (error) {
@@ -2238,9 +3704,15 @@ function renderBaseUrls() {
                     </div>
                     <div class="small text-muted">${url.url}</div>
                     ${url.description ? `<div class="small">${url.description}</div>` : ''}
-                    ${url.api_key ? `<div class="small text-info"><i class="fas fa-key me-1"></i>${window.i18n.t('urls.api_key')}: ${url.api_key}</div>` : ''}
+                    ${url.provider_type && url.provider_type !== 'anthropic' ? `<div class="small text-primary"><i class="fas fa-cloud me-1"></i>${window.i18n.t('providers.select_label')}: ${getProviderType(url.provider_type) ? getProviderType(url.provider_type).name : url.provider_type}</div>` : ''}
+                    ${url.api_key && (!url.provider_type || url.provider_type === 'anthropic') ? `<div class="small text-info"><i class="fas fa-key me-1"></i>${window.i18n.t('urls.api_key')}: ${url.api_key}</div>` : ''}
+                    ${renderCustomHeadersBadge(url.custom_headers)}
+                    ${renderEndpointCheckBadge(latestEndpointChecks[url.id])}
+                    ${balanceAdapters[url.id] ? `<div class="small ${balanceAdapters[url.id].enabled ? 'text-warning' : 'text-muted'}"><i class="fas fa-coins me-1"></i>${window.i18n.t('balance.configured')}: ${balanceAdapters[url.id].balance_path} (${balanceAdapters[url.id].unit})</div>` : ''}
                 </div>
                 <div class="url-actions">
+                    <button class="btn btn-sm btn-outline-success" onclick="openEndpointCheck(${url.id})">${window.i18n.t('health.check')}</button>
+                    <button class="btn btn-sm btn-outline-warning" onclick="openBalanceAdapter(${url.id})">${window.i18n.t('balance.configure')}</button>
                     <button class="btn btn-sm btn-outline-primary" onclick="editBaseUrl(${url.id})">${window.i18n.t('text.edit')}</button>
                     <button class="btn btn-sm btn-outline-danger" onclick="promptDeleteBaseUrl(${url.id})">${window.i18n.t('text.delete')}</button>
                 </div>
@@ -2249,22 +3721,198 @@ function renderBaseUrls() {
     `).join('');
 }
 
-// Update base URL select in account form
-function updateBaseUrlSelect() {
-    const select = document.getElementById('accountBaseUrlSelect');
-    if (select) {
-        select.innerHTML = '<option value="">' + window.i18n.t('text.select_preset_url') + '</option>' +
-            baseUrls.map(url => `<option value="${url.url}">${url.name}</option>`).join('');
-
-        // Auto-select default URL
-        const defaultUrl = baseUrls.find(url => url.is_default);
-        if (defaultUrl) {
-            select.value = defaultUrl.url;
-            document.getElementById('accountBaseUrl').value = defaultUrl.url;
+// ===== 余额查询配置 =====
+let balanceAdapterBaseUrlId = null;
 
-            // 同时更新环境变量显示默认URL的环境变量
-            updateAccountEnvVarsFromUrl(defaultUrl.url);
-        }
+function openBalanceAdapter(baseUrlId) {
+    const baseUrl = baseUrls.find(url => url.id === baseUrlId);
+    if (!baseUrl) {
+        return;
+    }
+    balanceAdapterBaseUrlId = baseUrlId;
+    const adapter = balanceAdapters[baseUrlId];
+    document.getElementById('balanceAdapterUrl').textContent = baseUrl.url;
+    document.getElementById('balanceMethod').value = adapter ? adapter.method : 'GET';
+    document.getElementById('balanceUrlTemplate').value = adapter ? adapter.url_template : '{base_url}/api/user/self';
+    document.getElementById('balanceHeaders').value = adapter ? (adapter.headers || '') : '{"Authorization": "Bearer {token}"}';
+    document.getElementById('balanceBody').value = adapter ? (adapter.body_template || '') : '';
+    document.getElementById('balancePath').value = adapter ? adapter.balance_path : 'data.quota';
+    document.getElementById('balanceDivisor').value = adapter ? adapter.divisor : 1;
+    document.getElementById('balanceUnit').value = adapter ? adapter.unit : 'USD';
+    document.getElementById('balanceEnabled').checked = adapter ? adapter.enabled : true;
+    document.getElementById('balanceAdapterDeleteBtn').style.display = adapter ? '' : 'none';
+    new bootstrap.Modal(document.getElementById('balanceAdapterModal')).show();
+}
+
+async function saveBalanceAdapter() {
+    const headers = document.getElementById('balanceHeaders').value.trim();
+    if (headers) {
+        try {
+            JSON.parse(headers);
+        } catch (error) {
+            showError(window.i18n.t('balance.invalid_headers'));
+            return;
+        }
+    }
+    const urlTemplate = document.getElementById('balanceUrlTemplate').value.trim();
+    const balancePath = document.getElementById('balancePath').value.trim();
+    if (!urlTemplate || !balancePath) {
+        showError(window.i18n.t('balance.required'));
+        return;
+    }
+
+    const request = {
+        base_url_id: balanceAdapterBaseUrlId,
+        method: document.getElementById('balanceMethod').value,
+        url_template: urlTemplate,
+        headers: headers || null,
+        body_template: document.getElementById('balanceBody').value.trim() || null,
+        balance_path: balancePath,
+        divisor: parseFloat(document.getElementById('balanceDivisor').value) || 1,
+        unit: document.getElementById('balanceUnit').value.trim() || 'USD',
+        enabled: document.getElementById('balanceEnabled').checked
+    };
+
+    try {
+        const adapter = await tauriSaveBalanceAdapter(request);
+        balanceAdapters[adapter.base_url_id] = adapter;
+        renderBaseUrls();
+        bootstrap.Modal.getInstance(document.getElementById('balanceAdapterModal')).hide();
+        showSuccess(window.i18n.t('balance.saved'));
+    } catch (error) {
+        showError(window.i18n.t('balance.save_failed') + ': ' + getErrorMessage(error));
+    }
+}
+
+async function deleteBalanceAdapter() {
+    try {
+        await tauriDeleteBalanceAdapter(balanceAdapterBaseUrlId);
+        delete balanceAdapters[balanceAdapterBaseUrlId];
+        renderBaseUrls();
+        bootstrap.Modal.getInstance(document.getElementById('balanceAdapterModal')).hide();
+        showSuccess(window.i18n.t('balance.deleted'));
+    } catch (error) {
+        showError(window.i18n.t('balance.save_failed') + ': ' + getErrorMessage(error));
+    }
+}
+
+// ===== API 地址健康检查 =====
+let endpointCheckBaseUrlId = null;
+
+function formatCheckMs(value) {
+    return value === null || value === undefined ? '-' : value + ' ms';
+}
+
+function renderEndpointCheckBadge(check) {
+    if (!check) {
+        return '';
+    }
+    const time = new Date(check.checked_at).toLocaleString();
+    return check.success
+        ? `<div class="small"><span class="badge bg-success">${window.i18n.t('health.ok')}</span> <span class="text-muted">${formatCheckMs(check.latency_ms)} · ${time}</span></div>`
+        : `<div class="small"><span class="badge bg-danger" title="${check.error || ''}">${window.i18n.t('health.failed')}</span> <span class="text-muted">${time}</span></div>`;
+}
+
+async function openEndpointCheck(baseUrlId) {
+    const baseUrl = baseUrls.find(url => url.id === baseUrlId);
+    if (!baseUrl) {
+        return;
+    }
+    endpointCheckBaseUrlId = baseUrlId;
+    document.getElementById('endpointCheckUrl').textContent = baseUrl.url;
+
+    const select = document.getElementById('endpointCheckAccount');
+    select.innerHTML = '<option value="">' + window.i18n.t('health.no_account') + '</option>';
+    try {
+        const response = await tauriGetAccounts({ page: 1, per_page: 100, base_url: baseUrl.url });
+        select.innerHTML += response.accounts.map(account => `<option value="${account.id}">${account.name}</option>`).join('');
+    } catch (error) {
+        console.warn('获取账号列表失败:', error);
+    }
+
+    await renderEndpointCheckHistory();
+    new bootstrap.Modal(document.getElementById('endpointCheckModal')).show();
+}
+
+async function renderEndpointCheckHistory() {
+    const container = document.getElementById('endpointCheckHistory');
+    let checks = [];
+    try {
+        checks = await tauriGetEndpointChecks(endpointCheckBaseUrlId);
+    } catch (error) {
+        showError(getErrorMessage(error));
+    }
+
+    if (checks.length === 0) {
+        container.innerHTML = '<div class="text-muted">' + window.i18n.t('health.no_history') + '</div>';
+        return;
+    }
+
+    container.innerHTML = `
+        <table class="table table-sm align-middle">
+            <thead>
+                <tr>
+                    <th>${window.i18n.t('health.checked_at')}</th>
+                    <th>${window.i18n.t('health.result')}</th>
+                    <th>${window.i18n.t('health.connect')}</th>
+                    <th>${window.i18n.t('health.tls')}</th>
+                    <th>${window.i18n.t('health.latency')}</th>
+                    <th>${window.i18n.t('health.status')}</th>
+                </tr>
+            </thead>
+            <tbody>
+                ${checks.map(check => `
+                    <tr>
+                        <td class="small">${new Date(check.checked_at).toLocaleString()}</td>
+                        <td>
+                            <span class="badge ${check.success ? 'bg-success' : 'bg-danger'}">${window.i18n.t(check.success ? 'health.ok' : 'health.failed')}</span>
+                            ${check.error ? `<div class="small text-danger">${escapeHtml(check.error)}</div>` : ''}
+                        </td>
+                        <td class="small">${formatCheckMs(check.connect_ms)}</td>
+                        <td class="small">${formatCheckMs(check.tls_ms)}</td>
+                        <td class="small">${formatCheckMs(check.latency_ms)}</td>
+                        <td class="small">${check.status_code ?? '-'}${check.auth_status_code !== null ? ' / ' + check.auth_status_code : ''}</td>
+                    </tr>
+                `).join('')}
+            </tbody>
+        </table>
+    `;
+}
+
+async function runEndpointCheck() {
+    const button = document.getElementById('endpointCheckRunBtn');
+    const accountValue = document.getElementById('endpointCheckAccount').value;
+    const accountId = accountValue ? parseInt(accountValue) : null;
+
+    button.disabled = true;
+    try {
+        const check = await tauriCheckBaseUrlHealth(endpointCheckBaseUrlId, accountId);
+        latestEndpointChecks[check.base_url_id] = check;
+        renderBaseUrls();
+        await renderEndpointCheckHistory();
+    } catch (error) {
+        showError(window.i18n.t('health.check_failed') + ': ' + getErrorMessage(error));
+    } finally {
+        button.disabled = false;
+    }
+}
+
+// Update base URL select in account form
+function updateBaseUrlSelect() {
+    const select = document.getElementById('accountBaseUrlSelect');
+    if (select) {
+        select.innerHTML = '<option value="">' + window.i18n.t('text.select_preset_url') + '</option>' +
+            baseUrls.map(url => `<option value="${url.url}">${url.name}</option>`).join('');
+
+        // Auto-select default URL
+        const defaultUrl = baseUrls.find(url => url.is_default);
+        if (defaultUrl) {
+            select.value = defaultUrl.url;
+            document.getElementById('accountBaseUrl').value = defaultUrl.url;
+
+            // 同时更新环境变量显示默认URL的环境变量
+            updateAccountEnvVarsFromUrl(defaultUrl.url);
+        }
     }
 }
 
@@ -2274,6 +3922,7 @@ async function saveBaseUrl() {
     const url = document.getElementById('urlAddress').value.trim();
     const description = document.getElementById('urlDescription').value;
     const apiKey = document.getElementById('urlApiKey').value.trim();
+    const providerType = document.getElementById('urlProviderType').value || 'anthropic';
     const isDefault = document.getElementById('urlIsDefault').checked;
 
     if (!name || !url) {
@@ -2315,8 +3964,24 @@ async function saveBaseUrl() {
         }
     }
 
+    const customHeaders = getCustomHeadersForm('urlCustomHeaders');
+    if (!customHeaders) {
+        return;
+    }
+
     try {
-        await tauriCreateBaseUrl(name, url, description, apiKey, isDefault, defaultEnvVars);
+        const baseUrl = await tauriCreateBaseUrl(name, url, description, apiKey, isDefault, defaultEnvVars, providerType);
+
+        // 保存 URL 绑定的代理
+        const proxyId = getSelectedProxyId('urlProxy');
+        if (proxyId !== null) {
+            await tauriSetBaseUrlProxy(baseUrl.id, proxyId);
+        }
+
+        // 保存 URL 的自定义请求头
+        if (customHeaders.length > 0) {
+            await tauriSetBaseUrlHeaders(baseUrl.id, customHeaders);
+        }
 
         // Close modal and reset form
         const modal = bootstrap.Modal.getInstance(document.getElementById('urlModal'));
@@ -2353,7 +4018,9 @@ async function editBaseUrl(urlId) {
         document.getElementById('urlAddress').value = url.url;
         document.getElementById('urlDescription').value = url.description || '';
         document.getElementById('urlApiKey').value = url.api_key || 'ANTHROPIC_API_KEY';
+        document.getElementById('urlProviderType').value = url.provider_type || 'anthropic';
         document.getElementById('urlIsDefault').checked = url.is_default;
+        setCustomHeadersForm('urlCustomHeaders', url.custom_headers);
 
         // 加载默认环境变量到JSON文本框
         try {
@@ -2373,6 +4040,9 @@ async function editBaseUrl(urlId) {
             document.getElementById('urlDefaultEnvVarsJson').value = '{}';
         }
 
+        // 加载代理选项
+        await loadProxyOptions('urlProxy', url.proxy_id);
+
         // Change modal title
         document.querySelector('#urlModal .modal-title').textContent = window.i18n.t('modal.edit_url');
         
@@ -2407,6 +4077,7 @@ async function updateBaseUrl(urlId) {
     const url = document.getElementById('urlAddress').value.trim();
     const description = document.getElementById('urlDescription').value;
     const apiKey = document.getElementById('urlApiKey').value.trim();
+    const providerType = document.getElementById('urlProviderType').value || 'anthropic';
     const isDefault = document.getElementById('urlIsDefault').checked;
 
     if (!name || !url) {
@@ -2448,15 +4119,27 @@ async function updateBaseUrl(urlId) {
         }
     }
 
+    const customHeaders = getCustomHeadersForm('urlCustomHeaders');
+    if (!customHeaders) {
+        return;
+    }
+
     try {
         await tauriUpdateBaseUrl(urlId, {
             name,
             url,
             description,
             api_key: apiKey,
+            provider_type: providerType,
             is_default: isDefault,
             default_env_vars: defaultEnvVars
         });
+
+        // 保存 URL 绑定的代理
+        await tauriSetBaseUrlProxy(urlId, getSelectedProxyId('urlProxy'));
+
+        // 保存 URL 的自定义请求头
+        await tauriSetBaseUrlHeaders(urlId, customHeaders);
         
         // Close modal and reset form
         const modal = bootstrap.Modal.getInstance(document.getElementById('urlModal'));
@@ -2500,6 +4183,9 @@ function resetUrlModal() {
 
     // 重置环境变量输入框
     document.getElementById('urlDefaultEnvVarsJson').value = '';
+    document.getElementById('urlProviderType').value = 'anthropic';
+    setCustomHeadersForm('urlCustomHeaders', null);
+    loadProxyOptions('urlProxy', null);
 }
 
 // Prompt delete base URL - shows confirmation first
@@ -2623,633 +4309,1610 @@ function showGlobalMessage(message, type = 'error', duration = 3000) {
         setTimeout(removeMessage, duration);
     }
     
-    // Manual close button event
-    const closeBtn = messageElement.querySelector('.btn-close');
-    if (closeBtn) {
-        closeBtn.addEventListener('click', removeMessage);
+    // Manual close button event
+    const closeBtn = messageElement.querySelector('.btn-close');
+    if (closeBtn) {
+        closeBtn.addEventListener('click', removeMessage);
+    }
+}
+
+// Show success message
+function showSuccess(message) {
+    showGlobalMessage(message, 'success', 3000);
+}
+
+// Show error message
+function showError(message) {
+    showGlobalMessage(message, 'error', 0); // 设置duration为0，错误消息不自动关闭
+}
+
+// 导出全局消息函数供其他模块使用
+window.showSuccess = showSuccess;
+window.showError = showError;
+window.showGlobalMessage = showGlobalMessage;
+window.getErrorMessage = getErrorMessage;
+window.customConfirm = customConfirm;
+
+// Tab management functions
+function saveActiveTab(tabId) {
+    localStorage.setItem('activeTab', tabId);
+}
+
+function getActiveTab() {
+    return localStorage.getItem('activeTab') || 'accounts-pane';
+}
+
+function activateTab(tabId) {
+    // Remove all active states
+    document.querySelectorAll('#mainTabs .nav-link').forEach(link => {
+        link.classList.remove('active');
+        link.setAttribute('aria-selected', 'false');
+    });
+    
+    document.querySelectorAll('.tab-pane').forEach(pane => {
+        pane.classList.remove('show', 'active');
+    });
+    
+    // Activate specified tab
+    const tabButton = document.querySelector(`#mainTabs button[data-bs-target="#${tabId}"]`);
+    const tabPane = document.querySelector(`#${tabId}`);
+    
+    if (tabButton && tabPane) {
+        tabButton.classList.add('active');
+        tabButton.setAttribute('aria-selected', 'true');
+        tabPane.classList.add('show', 'active');
+    }
+}
+
+// Event listeners
+window.addEventListener("DOMContentLoaded", () => {
+    // Bind save button events
+    document.getElementById('saveAccount').addEventListener('click', saveAccount);
+    document.getElementById('saveDirectory').addEventListener('click', saveDirectory);
+    // URL按钮事件在resetUrlModal中绑定，避免重复绑定
+
+    
+    // 账号凭据模式切换和辅助命令试运行
+    document.getElementById('accountCredentialMode').addEventListener('change', toggleAccountCredentialMode);
+    document.getElementById('accountTestKeyHelper').addEventListener('click', testAccountKeyHelper);
+
+    // URL selector change event
+    const urlSelect = document.getElementById('accountBaseUrlSelect');
+    if (urlSelect) {
+        urlSelect.addEventListener('change', function() {
+            if (this.value) {
+                document.getElementById('accountBaseUrl').value = this.value;
+
+                // 更新JSON环境变量 - 显示选中URL的默认环境变量
+                updateAccountEnvVarsFromUrl(this.value);
+            }
+        });
+    }
+    
+    // Modal close event listeners to reset state
+    const accountModal = document.getElementById('accountModal');
+    accountModal.addEventListener('hidden.bs.modal', function () {
+        resetAccountModal();
+    });
+    // 通过添加按钮打开时加载最新的可选列表，编辑时由 editAccount 加载
+    accountModal.addEventListener('show.bs.modal', function (event) {
+        if (event.relatedTarget) {
+            loadMcpServerChecks('accountMcpServers', []);
+        }
+    });
+
+
+    const directoryModal = document.getElementById('directoryModal');
+    directoryModal.addEventListener('hidden.bs.modal', function () {
+        resetDirectoryModal();
+    });
+    directoryModal.addEventListener('show.bs.modal', function (event) {
+        if (event.relatedTarget) {
+            loadMcpServerChecks('directoryMcpServers', []);
+            loadHookChecks('directoryHooks', []);
+            loadDefinitionChecks('directoryDefinitions', null);
+        }
+    });
+
+    const urlModal = document.getElementById('urlModal');
+    urlModal.addEventListener('hidden.bs.modal', function () {
+        resetUrlModal();
+    });
+    
+    // Initialize modal states and data loading
+    resetDirectoryModal();
+    resetUrlModal();
+    loadBaseUrls();
+    initDriftWatcher();
+    initAccountPools();
+    checkExpiringAccounts();
+    
+    // Set up account filters and search
+    setupAccountFilters();
+    
+    // Restore last active tab
+    const lastActiveTab = getActiveTab();
+    activateTab(lastActiveTab);
+    
+    // Load data based on active tab
+    if (lastActiveTab === 'accounts-pane') {
+        loadAccounts();
+        loadAccountBaseUrlOptions();
+    } else if (lastActiveTab === 'directories-pane') {
+        loadDirectories();
+    } else if (lastActiveTab === 'association-pane') {
+        loadAssociationPage();
+    } else if (lastActiveTab === 'urls-pane') {
+        loadBaseUrls();
+    } else if (lastActiveTab === 'database-pane') {
+        loadDatabaseInfo();
+    } else if (lastActiveTab === 'claude-settings-pane') {
+        loadClaudeSettingsPage();
+    }
+    
+    // Tab switch event listeners
+    const tabTriggerList = document.querySelectorAll('#mainTabs button[data-bs-toggle="tab"]');
+    tabTriggerList.forEach(function (tabTrigger) {
+        tabTrigger.addEventListener('click', function (event) {
+            const target = event.target.getAttribute('data-bs-target');
+            const tabId = target.substring(1); // Remove # sign
+            saveActiveTab(tabId);
+        });
+        
+        tabTrigger.addEventListener('shown.bs.tab', function (event) {
+            const target = event.target.getAttribute('data-bs-target');
+            if (target === '#database-pane') {
+                loadDatabaseInfo();
+            } else if (target === '#directories-pane') {
+                loadDirectories();
+            } else if (target === '#association-pane') {
+                loadAssociationPage();
+            } else if (target === '#urls-pane') {
+                loadBaseUrls();
+            } else if (target === '#accounts-pane') {
+                loadAccounts();
+                loadAccountBaseUrlOptions();
+            } else if (target === '#claude-settings-pane') {
+                loadClaudeSettingsPage();
+            }
+        });
+    });
+    
+    // Set up association page event listeners
+    setupAssociationEventListeners();
+    
+    // Bind directory selection button
+    const selectDirBtn = document.getElementById('selectDirectoryBtn');
+    if (selectDirBtn) {
+        selectDirBtn.addEventListener('click', selectDirectory);
+    }
+});
+
+// Set up association page event listeners
+function setupAssociationEventListeners() {
+    // Directory selection change
+    const directorySelect = document.getElementById('directorySelect');
+    if (directorySelect) {
+        directorySelect.addEventListener('change', function() {
+            onDirectorySelectionChange(this.value);
+        });
+    }
+    
+    // Account selection change
+    const accountSelect = document.getElementById('associationAccountSelect');
+    if (accountSelect) {
+        accountSelect.addEventListener('change', function() {
+            updateSwitchButtonState();
+        });
+    }
+}
+
+// Directory selection function
+async function selectDirectory() {
+    try {
+        const selected = await open({
+            directory: true,
+            multiple: false,
+            title: window.i18n.t('button.select_directory')
+        });
+        
+        if (selected) {
+            document.getElementById('directoryPath').value = selected;
+            
+            // Auto-generate directory name if empty
+            const nameInput = document.getElementById('directoryName');
+            if (!nameInput.value.trim()) {
+                const pathParts = selected.split(/[/\\]/);
+                const folderName = pathParts[pathParts.length - 1] || pathParts[pathParts.length - 2];
+                if (folderName) {
+                    nameInput.value = folderName;
+                }
+            }
+        }
+    } catch (error) {
+        showError(window.i18n.t('error.select_directory') + ': ' + getErrorMessage(error));
+    }
+}
+
+// Claude Settings Management
+let claudeSettingsData = {
+    permissions: {
+        defaultMode: 'bypassPermissions',
+        allow: ['*'],
+        deny: []
+    },
+    env: {
+        CLAUDE_CODE_BUBBLEWRAP: '1',
+        DISABLE_AUTOUPDATER: 1
+    }
+};
+
+// 标志位，防止重复绑定事件监听器
+let claudeSettingsEventListenersSetup = false;
+
+// Available Claude tools
+const availableClaudeTools = [
+    'Read', 'Write', 'Edit', 'MultiEdit', 'Bash', 'Glob', 'Grep', 
+    'WebSearch', 'WebFetch', 'Task', 'TodoWrite', 'BashOutput', 
+    'KillBash', 'NotebookEdit', 'ExitPlanMode'
+];
+
+// Load Claude settings page
+async function loadClaudeSettingsPage() {
+    try {
+        // 加载现有的配置文件
+        const loadResult = await loadCurrentClaudeSettings();
+
+        // 如果加载失败，显示详细警告信息
+        if (loadResult !== true) {
+            if (loadResult && loadResult.error) {
+                showClaudeSettingsMessage(
+                    window.i18n.t('claude.loading_warning').replace('{error}', loadResult.error),
+                    'warning'
+                );
+            } else {
+                showClaudeSettingsMessage(window.i18n.t('claude.loading_warning_simple'), 'warning');
+            }
+        }
+
+        // 初始化工具列表
+        renderToolsList();
+
+        // 加载权限预设列表
+        await loadPermissionPresets();
+
+        // 加载代理列表
+        await loadProxies();
+
+        // 加载 MCP 服务器列表
+        await loadMcpServers();
+
+        // 加载 Hook 列表
+        await loadHooks();
+
+        // 加载命令与子代理定义
+        await loadDefinitions();
+
+        // 初始化环境变量列表
+        renderCustomEnvVars();
+
+        // 更新预览
+        updatePreview();
+
+        // 绑定事件监听器
+        setupClaudeSettingsEventListeners();
+
+    } catch (error) {
+        showClaudeSettingsMessage(window.i18n.t('error.load_claude_settings') + ': ' + getErrorMessage(error), 'error');
+    }
+}
+
+// 加载当前的Claude配置
+async function loadCurrentClaudeSettings() {
+    try {
+        const settings = await invoke('get_claude_settings_from_db');
+
+        console.log(window.i18n.t('claude.config_loaded') + ':', settings);
+
+        // 验证数据结构完整性
+        if (!settings || typeof settings !== 'object') {
+            throw new Error(window.i18n.t('claude.invalid_format'));
+        }
+
+        // 确保 permissions 对象存在
+        if (!settings.permissions) {
+            settings.permissions = {
+                defaultMode: 'bypassPermissions',
+                allow: ['*'],
+                deny: []
+            };
+        }
+
+        // 确保 permissions.allow 是数组
+        if (!Array.isArray(settings.permissions.allow)) {
+            settings.permissions.allow = ['*'];
+        }
+
+        // 确保 permissions.deny 是数组
+        if (!Array.isArray(settings.permissions.deny)) {
+            settings.permissions.deny = [];
+        }
+
+        // 确保 env 对象存在
+        if (!settings.env) {
+            settings.env = {
+                CLAUDE_CODE_BUBBLEWRAP: '1',
+                DISABLE_AUTOUPDATER: 1
+            };
+        }
+
+        claudeSettingsData = settings;
+
+        console.log(window.i18n.t('claude.config_verified') + ':', claudeSettingsData);
+
+        // 旧版本使用的 normal / prompt 不是有效的 defaultMode，统一修正为 default
+        if (!['default', 'acceptEdits', 'plan', 'bypassPermissions'].includes(claudeSettingsData.permissions.defaultMode)) {
+            claudeSettingsData.permissions.defaultMode = 'default';
+        }
+
+        // 更新UI
+        document.getElementById('defaultPermissionMode').value =
+            claudeSettingsData.permissions.defaultMode;
+
+        // 更新工具选择状态
+        updateToolsSelection();
+
+        // 更新环境变量开关
+        document.getElementById('disableAutoUpdater').checked =
+            claudeSettingsData.env.DISABLE_AUTOUPDATER === 1 || claudeSettingsData.env.DISABLE_AUTOUPDATER === '1';
+        document.getElementById('disablePromptCaching').checked =
+            claudeSettingsData.env.DISABLE_PROMPT_CACHING === 1 || claudeSettingsData.env.DISABLE_PROMPT_CACHING === '1';
+
+        // 更新数值型环境变量
+        document.getElementById('maxOutputTokens').value = claudeSettingsData.env.CLAUDE_CODE_MAX_OUTPUT_TOKENS || '';
+        document.getElementById('maxThinkingTokens').value = claudeSettingsData.env.MAX_THINKING_TOKENS || '';
+        document.getElementById('maxMcpOutputTokens').value = claudeSettingsData.env.MAX_MCP_OUTPUT_TOKENS || '';
+
+        // 更新代理配置
+        document.getElementById('httpProxy').value = claudeSettingsData.env.HTTP_PROXY || '';
+        document.getElementById('httpsProxy').value = claudeSettingsData.env.HTTPS_PROXY || '';
+
+        return true; // 加载成功
+
+    } catch (error) {
+        // 显示详细错误信息
+        const errorMsg = getErrorMessage(error);
+        console.error(window.i18n.t('claude.load_failed') + ', ' + window.i18n.t('claude.using_default') + ':', error);
+        console.error(window.i18n.t('claude.error_detail') + ':', errorMsg);
+
+        // 如果数据库中没有配置，使用默认设置
+        claudeSettingsData = {
+            permissions: {
+                defaultMode: 'bypassPermissions',
+                allow: ['*'],
+                deny: []
+            },
+            env: {
+                CLAUDE_CODE_BUBBLEWRAP: '1',
+                DISABLE_AUTOUPDATER: 1
+            }
+        };
+
+        // 更新UI为默认值
+        document.getElementById('defaultPermissionMode').value = 'bypassPermissions';
+        updateToolsSelection();
+        document.getElementById('disableAutoUpdater').checked = true;
+
+        // 返回错误信息以便显示
+        return { success: false, error: errorMsg };
+    }
+}
+
+// 渲染工具列表
+function renderToolsList() {
+    const container = document.getElementById('toolsList');
+
+    // 确保数据结构存在
+    if (!claudeSettingsData.permissions || !Array.isArray(claudeSettingsData.permissions.allow)) {
+        console.warn('Claude配置数据不完整，使用默认值');
+        claudeSettingsData.permissions = {
+            defaultMode: 'bypassPermissions',
+            allow: ['*'],
+            deny: []
+        };
+    }
+
+    const allowAll = claudeSettingsData.permissions.allow.includes('*');
+
+    document.getElementById('allowAllTools').checked = allowAll;
+
+    if (allowAll) {
+        container.innerHTML = '<div class="text-muted">' + window.i18n.t('text.all_tools_allowed') + '</div>';
+    } else {
+        container.innerHTML = availableClaudeTools.map(tool => `
+            <div class="form-check">
+                <input class="form-check-input tool-checkbox" type="checkbox"
+                       id="tool-${tool}" value="${tool}"
+                       ${claudeSettingsData.permissions.allow.includes(tool) ? 'checked' : ''}>
+                <label class="form-check-label" for="tool-${tool}">
+                    ${tool}
+                </label>
+            </div>
+        `).join('');
+    }
+
+    // 渲染禁用工具列表
+    renderDeniedTools();
+}
+
+// 渲染禁用工具列表
+function renderDeniedTools() {
+    const container = document.getElementById('deniedToolsList');
+
+    // 确保数据结构存在
+    if (!claudeSettingsData.permissions || !Array.isArray(claudeSettingsData.permissions.deny)) {
+        console.warn('Claude配置的deny数据不完整，初始化为空数组');
+        if (!claudeSettingsData.permissions) {
+            claudeSettingsData.permissions = {
+                defaultMode: 'bypassPermissions',
+                allow: ['*'],
+                deny: []
+            };
+        } else {
+            claudeSettingsData.permissions.deny = [];
+        }
+    }
+
+    if (claudeSettingsData.permissions.deny.length === 0) {
+        container.innerHTML = '<div class="text-muted small">' + window.i18n.t('text.no_denied_tools') + '</div>';
+    } else {
+        container.innerHTML = claudeSettingsData.permissions.deny.map(tool => `
+            <span class="badge bg-danger me-2 mb-2">
+                ${tool}
+                <button type="button" class="btn-close btn-close-white ms-1"
+                        onclick="removeDeniedTool('${tool}')" style="font-size: 0.7em;"></button>
+            </span>
+        `).join('');
+    }
+}
+
+// 更新工具选择状态
+function updateToolsSelection() {
+    // 确保数据结构存在
+    if (!claudeSettingsData.permissions || !Array.isArray(claudeSettingsData.permissions.allow)) {
+        console.warn('updateToolsSelection: Claude配置数据不完整，使用默认值');
+        claudeSettingsData.permissions = {
+            defaultMode: 'bypassPermissions',
+            allow: ['*'],
+            deny: []
+        };
+    }
+
+    const allowAll = claudeSettingsData.permissions.allow.includes('*');
+    document.getElementById('allowAllTools').checked = allowAll;
+    renderToolsList();
+}
+
+// 渲染自定义环境变量
+function renderCustomEnvVars() {
+    const container = document.getElementById('customEnvList');
+
+    // 确保 env 对象存在
+    if (!claudeSettingsData.env || typeof claudeSettingsData.env !== 'object') {
+        console.warn('Claude配置的env数据不完整，初始化为默认值');
+        claudeSettingsData.env = {
+            CLAUDE_CODE_BUBBLEWRAP: '1',
+            DISABLE_AUTOUPDATER: 1
+        };
+    }
+
+    // 过滤掉系统管理的环境变量
+    const systemManagedEnvVars = [
+        'DISABLE_AUTOUPDATER',
+        'DISABLE_PROMPT_CACHING',
+        'CLAUDE_CODE_MAX_OUTPUT_TOKENS',
+        'MAX_THINKING_TOKENS',
+        'MAX_MCP_OUTPUT_TOKENS',
+        'HTTP_PROXY',
+        'HTTPS_PROXY'
+    ];
+
+    const customEnvVars = Object.entries(claudeSettingsData.env)
+        .filter(([key]) => !systemManagedEnvVars.includes(key));
+
+    if (customEnvVars.length === 0) {
+        container.innerHTML = '<div class="text-muted small">' + window.i18n.t('text.no_custom_env_vars') + '</div>';
+    } else {
+        container.innerHTML = customEnvVars.map(([key, value]) => `
+            <div class="d-flex align-items-center mb-2 p-2 bg-light rounded">
+                <code class="flex-grow-1">${key} = ${value}</code>
+                <button class="btn btn-sm btn-outline-danger ms-2"
+                        onclick="removeCustomEnvVar('${key}')">
+                    <i class="fas fa-times"></i>
+                </button>
+            </div>
+        `).join('');
+    }
+}
+
+// 设置事件监听器
+function setupClaudeSettingsEventListeners() {
+    // 如果已经设置过，则不重复设置
+    if (claudeSettingsEventListenersSetup) {
+        console.log(window.i18n.t('claude.listeners_already_setup'));
+        return;
+    }
+
+    console.log(window.i18n.t('claude.setting_listeners'));
+
+    // 权限模式变更
+    document.getElementById('defaultPermissionMode').addEventListener('change', function() {
+        claudeSettingsData.permissions.defaultMode = this.value;
+        updatePreview();
+    });
+
+    // 全选工具变更
+    document.getElementById('allowAllTools').addEventListener('change', function() {
+        if (this.checked) {
+            claudeSettingsData.permissions.allow = ['*'];
+        } else {
+            claudeSettingsData.permissions.allow = [];
+        }
+        updateToolsSelection();
+        updatePreview();
+    });
+
+    // 工具选择变更
+    document.addEventListener('change', function(e) {
+        if (e.target.classList.contains('tool-checkbox')) {
+            const tool = e.target.value;
+            if (e.target.checked) {
+                if (!claudeSettingsData.permissions.allow.includes(tool)) {
+                    claudeSettingsData.permissions.allow.push(tool);
+                }
+            } else {
+                claudeSettingsData.permissions.allow =
+                    claudeSettingsData.permissions.allow.filter(t => t !== tool);
+            }
+            updatePreview();
+        }
+    });
+
+    // 自动更新变更
+    document.getElementById('disableAutoUpdater').addEventListener('change', function() {
+        claudeSettingsData.env.DISABLE_AUTOUPDATER = this.checked ? 1 : 0;
+        updatePreview();
+    });
+
+    // 禁用提示缓存变更
+    document.getElementById('disablePromptCaching').addEventListener('change', function() {
+        claudeSettingsData.env.DISABLE_PROMPT_CACHING = this.checked ? 1 : 0;
+        updatePreview();
+    });
+
+    // 最大输出Token数变更
+    document.getElementById('maxOutputTokens').addEventListener('input', function() {
+        const value = this.value.trim();
+        if (value) {
+            claudeSettingsData.env.CLAUDE_CODE_MAX_OUTPUT_TOKENS = parseInt(value);
+        } else {
+            delete claudeSettingsData.env.CLAUDE_CODE_MAX_OUTPUT_TOKENS;
+        }
+        updatePreview();
+    });
+
+    // 最大思考Token数变更
+    document.getElementById('maxThinkingTokens').addEventListener('input', function() {
+        const value = this.value.trim();
+        if (value) {
+            claudeSettingsData.env.MAX_THINKING_TOKENS = parseInt(value);
+        } else {
+            delete claudeSettingsData.env.MAX_THINKING_TOKENS;
+        }
+        updatePreview();
+    });
+
+    // MCP输出Token限制变更
+    document.getElementById('maxMcpOutputTokens').addEventListener('input', function() {
+        const value = this.value.trim();
+        if (value) {
+            claudeSettingsData.env.MAX_MCP_OUTPUT_TOKENS = parseInt(value);
+        } else {
+            delete claudeSettingsData.env.MAX_MCP_OUTPUT_TOKENS;
+        }
+        updatePreview();
+    });
+
+    // HTTP代理变更
+    document.getElementById('httpProxy').addEventListener('input', function() {
+        const value = this.value.trim();
+        if (value) {
+            claudeSettingsData.env.HTTP_PROXY = value;
+        } else {
+            delete claudeSettingsData.env.HTTP_PROXY;
+        }
+        updatePreview();
+    });
+
+    // HTTPS代理变更
+    document.getElementById('httpsProxy').addEventListener('input', function() {
+        const value = this.value.trim();
+        if (value) {
+            claudeSettingsData.env.HTTPS_PROXY = value;
+        } else {
+            delete claudeSettingsData.env.HTTPS_PROXY;
+        }
+        updatePreview();
+    });
+
+    // 标记为已设置
+    claudeSettingsEventListenersSetup = true;
+}
+
+// 添加禁用工具
+async function addDeniedTool() {
+    const input = document.getElementById('newDeniedTool');
+    const tool = input.value.trim();
+    
+    if (!tool) {
+        showClaudeSettingsMessage(window.i18n.t('claude.tool_name_required'), 'warning');
+        return;
+    }
+
+    try {
+        await tauriValidatePermissionRule(tool);
+    } catch (error) {
+        showClaudeSettingsMessage(window.i18n.t('permission_presets.invalid_rule') + ': ' + getErrorMessage(error), 'warning');
+        return;
+    }
+    
+    if (claudeSettingsData.permissions.deny.includes(tool)) {
+        showClaudeSettingsMessage(window.i18n.t('claude.tool_already_denied'), 'warning');
+        return;
     }
+    
+    claudeSettingsData.permissions.deny.push(tool);
+    input.value = '';
+    renderDeniedTools();
+    updatePreview();
 }
 
-// Show success message
-function showSuccess(message) {
-    showGlobalMessage(message, 'success', 3000);
+// 移除禁用工具
+function removeDeniedTool(tool) {
+    claudeSettingsData.permissions.deny = 
+        claudeSettingsData.permissions.deny.filter(t => t !== tool);
+    renderDeniedTools();
+    updatePreview();
 }
 
-// Show error message
-function showError(message) {
-    showGlobalMessage(message, 'error', 0); // 设置duration为0，错误消息不自动关闭
+// 权限预设管理
+// 规则中可能包含 < > 等字符，渲染前需要转义；同时转义引号，结果也可以放进属性值
+const HTML_ESCAPES = { '&': '&amp;', '<': '&lt;', '>': '&gt;', '"': '&quot;', "'": '&#39;' };
+
+function escapeHtml(text) {
+    return (text == null ? '' : String(text)).replace(/[&<>"']/g, c => HTML_ESCAPES[c]);
 }
 
-// 导出全局消息函数供其他模块使用
-window.showSuccess = showSuccess;
-window.showError = showError;
-window.showGlobalMessage = showGlobalMessage;
-window.getErrorMessage = getErrorMessage;
-window.customConfirm = customConfirm;
+async function loadPermissionPresets() {
+    try {
+        permissionPresets = await tauriGetPermissionPresets();
+        renderPermissionPresets();
+    } catch (error) {
+        showClaudeSettingsMessage(window.i18n.t('permission_presets.load_failed') + ': ' + getErrorMessage(error), 'error');
+    }
+}
 
-// Tab management functions
-function saveActiveTab(tabId) {
-    localStorage.setItem('activeTab', tabId);
+function parsePresetRules(rules) {
+    try {
+        const parsed = typeof rules === 'string' ? JSON.parse(rules) : rules;
+        return Array.isArray(parsed) ? parsed : [];
+    } catch (e) {
+        return [];
+    }
 }
 
-function getActiveTab() {
-    return localStorage.getItem('activeTab') || 'accounts-pane';
+function renderPermissionPresets() {
+    const container = document.getElementById('permissionPresetsList');
+    if (!container) return;
+
+    if (permissionPresets.length === 0) {
+        container.innerHTML = '<div class="text-muted small">' + window.i18n.t('permission_presets.empty') + '</div>';
+        return;
+    }
+
+    const renderRules = (rules, color) => rules.map(rule =>
+        `<span class="badge bg-${color} me-1 mb-1 font-monospace">${escapeHtml(rule)}</span>`
+    ).join('');
+
+    container.innerHTML = `
+        <div class="table-responsive">
+            <table class="table table-sm align-middle">
+                <thead>
+                    <tr>
+                        <th>${window.i18n.t('permission_presets.name')}</th>
+                        <th>${window.i18n.t('claude.permission_mode')}</th>
+                        <th>${window.i18n.t('permission_presets.rules')}</th>
+                        <th></th>
+                    </tr>
+                </thead>
+                <tbody>
+                    ${permissionPresets.map(preset => `
+                        <tr>
+                            <td>
+                                <strong>${escapeHtml(preset.name)}</strong>
+                                ${preset.is_builtin ? `<span class="badge bg-secondary ms-1">${window.i18n.t('permission_presets.builtin')}</span>` : ''}
+                                ${preset.description ? `<div class="text-muted small">${escapeHtml(preset.description)}</div>` : ''}
+                            </td>
+                            <td><code>${escapeHtml(preset.default_mode)}</code></td>
+                            <td>
+                                ${renderRules(parsePresetRules(preset.allow), 'success')}
+                                ${renderRules(parsePresetRules(preset.ask), 'warning')}
+                                ${renderRules(parsePresetRules(preset.deny), 'danger')}
+                            </td>
+                            <td class="text-nowrap">
+                                <button class="btn btn-sm btn-outline-primary" onclick="editPermissionPreset(${preset.id})">
+                                    <i class="fas fa-edit"></i>
+                                </button>
+                                ${preset.is_builtin ? '' : `
+                                <button class="btn btn-sm btn-outline-danger" onclick="deletePermissionPreset(${preset.id})">
+                                    <i class="fas fa-trash"></i>
+                                </button>`}
+                            </td>
+                        </tr>
+                    `).join('')}
+                </tbody>
+            </table>
+        </div>
+    `;
 }
 
-function activateTab(tabId) {
-    // Remove all active states
-    document.querySelectorAll('#mainTabs .nav-link').forEach(link => {
-        link.classList.remove('active');
-        link.setAttribute('aria-selected', 'false');
-    });
-    
-    document.querySelectorAll('.tab-pane').forEach(pane => {
-        pane.classList.remove('show', 'active');
-    });
-    
-    // Activate specified tab
-    const tabButton = document.querySelector(`#mainTabs button[data-bs-target="#${tabId}"]`);
-    const tabPane = document.querySelector(`#${tabId}`);
-    
-    if (tabButton && tabPane) {
-        tabButton.classList.add('active');
-        tabButton.setAttribute('aria-selected', 'true');
-        tabPane.classList.add('show', 'active');
+// 填充目录/账号模态框中的权限预设下拉框
+async function loadPermissionPresetOptions(selectId, selectedId) {
+    const select = document.getElementById(selectId);
+    if (!select) return;
+
+    try {
+        permissionPresets = await tauriGetPermissionPresets();
+    } catch (error) {
+        console.error('加载权限预设失败:', error);
     }
+
+    select.innerHTML = `<option value="">${window.i18n.t('permission_presets.none')}</option>` +
+        permissionPresets.map(preset =>
+            `<option value="${preset.id}">${escapeHtml(preset.name)} (${escapeHtml(preset.default_mode)})</option>`
+        ).join('');
+    select.value = selectedId ? String(selectedId) : '';
 }
 
-// Event listeners
-window.addEventListener("DOMContentLoaded", () => {
-    // Bind save button events
-    document.getElementById('saveAccount').addEventListener('click', saveAccount);
-    document.getElementById('saveDirectory').addEventListener('click', saveDirectory);
-    // URL按钮事件在resetUrlModal中绑定，避免重复绑定
+function getSelectedPermissionPresetId(selectId) {
+    const value = document.getElementById(selectId)?.value;
+    return value ? parseInt(value) : null;
+}
 
-    
-    // URL selector change event
-    const urlSelect = document.getElementById('accountBaseUrlSelect');
-    if (urlSelect) {
-        urlSelect.addEventListener('change', function() {
-            if (this.value) {
-                document.getElementById('accountBaseUrl').value = this.value;
+function splitRuleLines(text) {
+    return text.split('\n').map(line => line.trim()).filter(line => line.length > 0);
+}
 
-                // 更新JSON环境变量 - 显示选中URL的默认环境变量
-                updateAccountEnvVarsFromUrl(this.value);
-            }
-        });
+function showAddPermissionPresetModal() {
+    editingPermissionPresetId = null;
+    document.getElementById('permissionPresetForm').reset();
+    document.getElementById('permissionPresetName').disabled = false;
+    document.querySelector('#permissionPresetModal .modal-title').textContent = window.i18n.t('permission_presets.add');
+    new bootstrap.Modal(document.getElementById('permissionPresetModal')).show();
+}
+
+function editPermissionPreset(presetId) {
+    const preset = permissionPresets.find(p => p.id === presetId);
+    if (!preset) return;
+
+    editingPermissionPresetId = presetId;
+    document.getElementById('permissionPresetName').value = preset.name;
+    // 内置预设不允许改名，避免重新初始化时重复创建
+    document.getElementById('permissionPresetName').disabled = preset.is_builtin;
+    document.getElementById('permissionPresetMode').value = preset.default_mode;
+    document.getElementById('permissionPresetAllow').value = parsePresetRules(preset.allow).join('\n');
+    document.getElementById('permissionPresetDeny').value = parsePresetRules(preset.deny).join('\n');
+    document.getElementById('permissionPresetAsk').value = parsePresetRules(preset.ask).join('\n');
+    document.getElementById('permissionPresetDescription').value = preset.description || '';
+    document.querySelector('#permissionPresetModal .modal-title').textContent = window.i18n.t('permission_presets.edit');
+    new bootstrap.Modal(document.getElementById('permissionPresetModal')).show();
+}
+
+async function savePermissionPreset() {
+    const name = document.getElementById('permissionPresetName').value.trim();
+    if (!name) {
+        showError(window.i18n.t('validation.required_fields'));
+        return;
     }
-    
-    // Modal close event listeners to reset state
-    const accountModal = document.getElementById('accountModal');
-    accountModal.addEventListener('hidden.bs.modal', function () {
-        resetAccountModal();
-    });
 
+    const params = {
+        name,
+        defaultMode: document.getElementById('permissionPresetMode').value,
+        allow: splitRuleLines(document.getElementById('permissionPresetAllow').value),
+        deny: splitRuleLines(document.getElementById('permissionPresetDeny').value),
+        ask: splitRuleLines(document.getElementById('permissionPresetAsk').value),
+        description: document.getElementById('permissionPresetDescription').value.trim() || null
+    };
 
-    const directoryModal = document.getElementById('directoryModal');
-    directoryModal.addEventListener('hidden.bs.modal', function () {
-        resetDirectoryModal();
-    });
+    try {
+        if (editingPermissionPresetId) {
+            await tauriUpdatePermissionPreset(editingPermissionPresetId, params);
+        } else {
+            await tauriCreatePermissionPreset(params);
+        }
 
-    const urlModal = document.getElementById('urlModal');
-    urlModal.addEventListener('hidden.bs.modal', function () {
-        resetUrlModal();
-    });
-    
-    // Initialize modal states and data loading
-    resetDirectoryModal();
-    resetUrlModal();
-    loadBaseUrls();
-    
-    // Set up account filters and search
-    setupAccountFilters();
-    
-    // Restore last active tab
-    const lastActiveTab = getActiveTab();
-    activateTab(lastActiveTab);
-    
-    // Load data based on active tab
-    if (lastActiveTab === 'accounts-pane') {
-        loadAccounts();
-        loadAccountBaseUrlOptions();
-    } else if (lastActiveTab === 'directories-pane') {
-        loadDirectories();
-    } else if (lastActiveTab === 'association-pane') {
-        loadAssociationPage();
-    } else if (lastActiveTab === 'urls-pane') {
-        loadBaseUrls();
-    } else if (lastActiveTab === 'database-pane') {
-        loadDatabaseInfo();
-    } else if (lastActiveTab === 'claude-settings-pane') {
-        loadClaudeSettingsPage();
+        bootstrap.Modal.getInstance(document.getElementById('permissionPresetModal')).hide();
+        editingPermissionPresetId = null;
+        await loadPermissionPresets();
+        showClaudeSettingsMessage(window.i18n.t('permission_presets.saved'), 'success');
+    } catch (error) {
+        // 规则校验失败时保留模态框，方便用户修改
+        showError(window.i18n.t('permission_presets.save_failed') + ': ' + getErrorMessage(error));
     }
-    
-    // Tab switch event listeners
-    const tabTriggerList = document.querySelectorAll('#mainTabs button[data-bs-toggle="tab"]');
-    tabTriggerList.forEach(function (tabTrigger) {
-        tabTrigger.addEventListener('click', function (event) {
-            const target = event.target.getAttribute('data-bs-target');
-            const tabId = target.substring(1); // Remove # sign
-            saveActiveTab(tabId);
-        });
-        
-        tabTrigger.addEventListener('shown.bs.tab', function (event) {
-            const target = event.target.getAttribute('data-bs-target');
-            if (target === '#database-pane') {
-                loadDatabaseInfo();
-            } else if (target === '#directories-pane') {
-                loadDirectories();
-            } else if (target === '#association-pane') {
-                loadAssociationPage();
-            } else if (target === '#urls-pane') {
-                loadBaseUrls();
-            } else if (target === '#accounts-pane') {
-                loadAccounts();
-                loadAccountBaseUrlOptions();
-            } else if (target === '#claude-settings-pane') {
-                loadClaudeSettingsPage();
-            }
-        });
-    });
-    
-    // Set up association page event listeners
-    setupAssociationEventListeners();
-    
-    // Bind directory selection button
-    const selectDirBtn = document.getElementById('selectDirectoryBtn');
-    if (selectDirBtn) {
-        selectDirBtn.addEventListener('click', selectDirectory);
+}
+
+async function deletePermissionPreset(presetId) {
+    const preset = permissionPresets.find(p => p.id === presetId);
+    if (!preset) return;
+
+    const confirmed = await customConfirm(
+        window.i18n.t('permission_presets.delete_confirm').replace('{name}', preset.name),
+        window.i18n.t('permission_presets.delete_title')
+    );
+    if (!confirmed) return;
+
+    try {
+        await tauriDeletePermissionPreset(presetId);
+        await loadPermissionPresets();
+        showClaudeSettingsMessage(window.i18n.t('permission_presets.deleted'), 'success');
+    } catch (error) {
+        showClaudeSettingsMessage(window.i18n.t('permission_presets.delete_failed') + ': ' + getErrorMessage(error), 'error');
     }
-});
+}
 
-// Set up association page event listeners
-function setupAssociationEventListeners() {
-    // Directory selection change
-    const directorySelect = document.getElementById('directorySelect');
-    if (directorySelect) {
-        directorySelect.addEventListener('change', function() {
-            onDirectorySelectionChange(this.value);
-        });
+// ============= 代理管理 =============
+
+async function loadProxies() {
+    try {
+        proxies = await tauriGetProxies();
+        renderProxies();
+    } catch (error) {
+        showClaudeSettingsMessage(window.i18n.t('proxies.load_failed') + ': ' + getErrorMessage(error), 'error');
     }
-    
-    // Account selection change
-    const accountSelect = document.getElementById('associationAccountSelect');
-    if (accountSelect) {
-        accountSelect.addEventListener('change', function() {
-            updateSwitchButtonState();
-        });
+}
+
+function renderProxies() {
+    const container = document.getElementById('proxiesList');
+    if (!container) return;
+
+    if (proxies.length === 0) {
+        container.innerHTML = '<div class="text-muted small">' + window.i18n.t('proxies.empty') + '</div>';
+        return;
     }
+
+    container.innerHTML = `
+        <div class="table-responsive">
+            <table class="table table-sm align-middle">
+                <thead>
+                    <tr>
+                        <th>${window.i18n.t('proxies.name')}</th>
+                        <th>${window.i18n.t('proxies.url')}</th>
+                        <th>${window.i18n.t('proxies.no_proxy')}</th>
+                        <th></th>
+                    </tr>
+                </thead>
+                <tbody>
+                    ${proxies.map(proxy => `
+                        <tr>
+                            <td>
+                                <strong>${escapeHtml(proxy.name)}</strong>
+                                ${proxy.username ? `<span class="badge bg-secondary ms-1">${window.i18n.t('proxies.with_auth')}</span>` : ''}
+                                ${proxy.description ? `<div class="text-muted small">${escapeHtml(proxy.description)}</div>` : ''}
+                            </td>
+                            <td><code>${escapeHtml(proxy.url)}</code></td>
+                            <td class="small">${escapeHtml(proxy.no_proxy || '')}</td>
+                            <td class="text-nowrap">
+                                <button class="btn btn-sm btn-outline-info" onclick="testProxy(${proxy.id})" title="${window.i18n.t('proxies.test')}">
+                                    <i class="fas fa-plug"></i>
+                                </button>
+                                <button class="btn btn-sm btn-outline-primary" onclick="editProxy(${proxy.id})">
+                                    <i class="fas fa-edit"></i>
+                                </button>
+                                <button class="btn btn-sm btn-outline-danger" onclick="deleteProxy(${proxy.id})">
+                                    <i class="fas fa-trash"></i>
+                                </button>
+                            </td>
+                        </tr>
+                    `).join('')}
+                </tbody>
+            </table>
+        </div>
+    `;
 }
 
-// Directory selection function
-async function selectDirectory() {
+// 填充账号/目录/URL/WebDAV 模态框中的代理下拉框
+async function loadProxyOptions(selectId, selectedId) {
+    const select = document.getElementById(selectId);
+    if (!select) return;
+
     try {
-        const selected = await open({
-            directory: true,
-            multiple: false,
-            title: window.i18n.t('button.select_directory')
-        });
-        
-        if (selected) {
-            document.getElementById('directoryPath').value = selected;
-            
-            // Auto-generate directory name if empty
-            const nameInput = document.getElementById('directoryName');
-            if (!nameInput.value.trim()) {
-                const pathParts = selected.split(/[/\\]/);
-                const folderName = pathParts[pathParts.length - 1] || pathParts[pathParts.length - 2];
-                if (folderName) {
-                    nameInput.value = folderName;
-                }
-            }
+        proxies = await tauriGetProxies();
+    } catch (error) {
+        console.error('加载代理失败:', error);
+    }
+
+    select.innerHTML = `<option value="">${window.i18n.t('proxies.none')}</option>` +
+        proxies.map(proxy =>
+            `<option value="${proxy.id}">${escapeHtml(proxy.name)} (${escapeHtml(proxy.url)})</option>`
+        ).join('');
+    select.value = selectedId ? String(selectedId) : '';
+}
+
+function getSelectedProxyId(selectId) {
+    const value = document.getElementById(selectId)?.value;
+    return value ? parseInt(value) : null;
+}
+
+function showAddProxyModal() {
+    editingProxyId = null;
+    document.getElementById('proxyForm').reset();
+    document.querySelector('#proxyModal .modal-title').textContent = window.i18n.t('proxies.add');
+    new bootstrap.Modal(document.getElementById('proxyModal')).show();
+}
+
+function editProxy(proxyId) {
+    const proxy = proxies.find(p => p.id === proxyId);
+    if (!proxy) return;
+
+    editingProxyId = proxyId;
+    document.getElementById('proxyName').value = proxy.name;
+    document.getElementById('proxyUrl').value = proxy.url;
+    document.getElementById('proxyNoProxy').value = proxy.no_proxy || '';
+    document.getElementById('proxyUsername').value = proxy.username || '';
+    document.getElementById('proxyPassword').value = proxy.password || '';
+    document.getElementById('proxyDescription').value = proxy.description || '';
+    document.querySelector('#proxyModal .modal-title').textContent = window.i18n.t('proxies.edit');
+    new bootstrap.Modal(document.getElementById('proxyModal')).show();
+}
+
+async function saveProxy() {
+    const name = document.getElementById('proxyName').value.trim();
+    const url = document.getElementById('proxyUrl').value.trim();
+    if (!name || !url) {
+        showError(window.i18n.t('validation.required_fields'));
+        return;
+    }
+
+    // 编辑时以空字符串清空可选字段
+    const optional = (id) => {
+        const value = document.getElementById(id).value.trim();
+        return value || (editingProxyId ? '' : null);
+    };
+    const params = {
+        name,
+        url,
+        noProxy: optional('proxyNoProxy'),
+        username: optional('proxyUsername'),
+        password: optional('proxyPassword'),
+        description: optional('proxyDescription')
+    };
+
+    try {
+        if (editingProxyId) {
+            await tauriUpdateProxy(editingProxyId, params);
+        } else {
+            await tauriCreateProxy(params);
         }
+
+        bootstrap.Modal.getInstance(document.getElementById('proxyModal')).hide();
+        editingProxyId = null;
+        await loadProxies();
+        showClaudeSettingsMessage(window.i18n.t('proxies.saved'), 'success');
     } catch (error) {
-        showError(window.i18n.t('error.select_directory') + ': ' + getErrorMessage(error));
+        // 地址校验失败时保留模态框，方便用户修改
+        showError(window.i18n.t('proxies.save_failed') + ': ' + getErrorMessage(error));
     }
 }
 
-// Claude Settings Management
-let claudeSettingsData = {
-    permissions: {
-        defaultMode: 'bypassPermissions',
-        allow: ['*'],
-        deny: []
-    },
-    env: {
-        CLAUDE_CODE_BUBBLEWRAP: '1',
-        DISABLE_AUTOUPDATER: 1
-    }
-};
+async function deleteProxy(proxyId) {
+    const proxy = proxies.find(p => p.id === proxyId);
+    if (!proxy) return;
 
-// 标志位，防止重复绑定事件监听器
-let claudeSettingsEventListenersSetup = false;
+    const confirmed = await customConfirm(
+        window.i18n.t('proxies.delete_confirm').replace('{name}', proxy.name),
+        window.i18n.t('proxies.delete_title')
+    );
+    if (!confirmed) return;
 
-// Available Claude tools
-const availableClaudeTools = [
-    'Read', 'Write', 'Edit', 'MultiEdit', 'Bash', 'Glob', 'Grep', 
-    'WebSearch', 'WebFetch', 'Task', 'TodoWrite', 'BashOutput', 
-    'KillBash', 'NotebookEdit', 'ExitPlanMode'
-];
+    try {
+        await tauriDeleteProxy(proxyId);
+        await loadProxies();
+        showClaudeSettingsMessage(window.i18n.t('proxies.deleted'), 'success');
+    } catch (error) {
+        showClaudeSettingsMessage(window.i18n.t('proxies.delete_failed') + ': ' + getErrorMessage(error), 'error');
+    }
+}
 
-// Load Claude settings page
-async function loadClaudeSettingsPage() {
+async function testProxy(proxyId) {
+    const proxy = proxies.find(p => p.id === proxyId);
+    if (!proxy) return;
+
+    showClaudeSettingsMessage(window.i18n.t('proxies.testing').replace('{name}', proxy.name), 'info');
     try {
-        // 加载现有的配置文件
-        const loadResult = await loadCurrentClaudeSettings();
+        const latency = await tauriTestProxy(proxyId);
+        showClaudeSettingsMessage(
+            window.i18n.t('proxies.test_success').replace('{name}', proxy.name).replace('{ms}', latency),
+            'success'
+        );
+    } catch (error) {
+        showClaudeSettingsMessage(
+            window.i18n.t('proxies.test_failed').replace('{name}', proxy.name) + ': ' + getErrorMessage(error),
+            'error'
+        );
+    }
+}
 
-        // 如果加载失败，显示详细警告信息
-        if (loadResult !== true) {
-            if (loadResult && loadResult.error) {
-                showClaudeSettingsMessage(
-                    window.i18n.t('claude.loading_warning').replace('{error}', loadResult.error),
-                    'warning'
-                );
-            } else {
-                showClaudeSettingsMessage(window.i18n.t('claude.loading_warning_simple'), 'warning');
-            }
-        }
+// ============= MCP 服务器 =============
 
-        // 初始化工具列表
-        renderToolsList();
+async function loadMcpServers() {
+    try {
+        mcpServers = await tauriGetMcpServers();
+        renderMcpServers();
+    } catch (error) {
+        showClaudeSettingsMessage(window.i18n.t('mcp.load_failed') + ': ' + getErrorMessage(error), 'error');
+    }
+}
 
-        // 初始化环境变量列表
-        renderCustomEnvVars();
+function renderMcpServers() {
+    const container = document.getElementById('mcpServersList');
+    if (!container) return;
 
-        // 更新预览
-        updatePreview();
+    if (mcpServers.length === 0) {
+        container.innerHTML = '<div class="text-muted small">' + window.i18n.t('mcp.empty') + '</div>';
+        return;
+    }
 
-        // 绑定事件监听器
-        setupClaudeSettingsEventListeners();
+    container.innerHTML = `
+        <div class="table-responsive">
+            <table class="table table-sm align-middle">
+                <thead>
+                    <tr>
+                        <th>${window.i18n.t('mcp.name')}</th>
+                        <th>${window.i18n.t('mcp.transport')}</th>
+                        <th>${window.i18n.t('mcp.target')}</th>
+                        <th></th>
+                    </tr>
+                </thead>
+                <tbody>
+                    ${mcpServers.map(server => `
+                        <tr>
+                            <td>
+                                <strong>${escapeHtml(server.name)}</strong>
+                                ${server.description ? `<div class="text-muted small">${escapeHtml(server.description)}</div>` : ''}
+                            </td>
+                            <td><span class="badge bg-secondary">${escapeHtml(server.transport)}</span></td>
+                            <td><code>${escapeHtml(describeMcpTarget(server))}</code></td>
+                            <td class="text-nowrap">
+                                <button class="btn btn-sm btn-outline-primary" onclick="editMcpServer(${server.id})">
+                                    <i class="fas fa-edit"></i>
+                                </button>
+                                <button class="btn btn-sm btn-outline-danger" onclick="deleteMcpServer(${server.id})">
+                                    <i class="fas fa-trash"></i>
+                                </button>
+                            </td>
+                        </tr>
+                    `).join('')}
+                </tbody>
+            </table>
+        </div>
+    `;
+}
 
+function describeMcpTarget(server) {
+    if (server.transport !== 'stdio') {
+        return server.url || '';
+    }
+    let args = [];
+    try {
+        args = JSON.parse(server.args || '[]');
     } catch (error) {
-        showClaudeSettingsMessage(window.i18n.t('error.load_claude_settings') + ': ' + getErrorMessage(error), 'error');
+        console.error('解析 MCP 参数失败:', error);
     }
+    return [server.command || '', ...args].join(' ');
 }
 
-// 加载当前的Claude配置
-async function loadCurrentClaudeSettings() {
-    try {
-        const settings = await invoke('get_claude_settings_from_db');
+// 按传输方式切换命令/地址输入项
+function updateMcpTransportFields() {
+    const stdio = document.getElementById('mcpTransport').value === 'stdio';
+    document.getElementById('mcpStdioFields').style.display = stdio ? '' : 'none';
+    document.getElementById('mcpUrlFields').style.display = stdio ? 'none' : '';
+}
 
-        console.log(window.i18n.t('claude.config_loaded') + ':', settings);
+function showAddMcpServerModal() {
+    editingMcpServerId = null;
+    document.getElementById('mcpServerForm').reset();
+    updateMcpTransportFields();
+    document.querySelector('#mcpServerModal .modal-title').textContent = window.i18n.t('mcp.add');
+    new bootstrap.Modal(document.getElementById('mcpServerModal')).show();
+}
 
-        // 验证数据结构完整性
-        if (!settings || typeof settings !== 'object') {
-            throw new Error(window.i18n.t('claude.invalid_format'));
-        }
+function editMcpServer(serverId) {
+    const server = mcpServers.find(s => s.id === serverId);
+    if (!server) return;
 
-        // 确保 permissions 对象存在
-        if (!settings.permissions) {
-            settings.permissions = {
-                defaultMode: 'bypassPermissions',
-                allow: ['*'],
-                deny: []
-            };
-        }
+    editingMcpServerId = serverId;
+    document.getElementById('mcpServerName').value = server.name;
+    document.getElementById('mcpTransport').value = server.transport;
+    document.getElementById('mcpCommand').value = server.command || '';
+    try {
+        document.getElementById('mcpArgs').value = JSON.parse(server.args || '[]').join('\n');
+        const env = JSON.parse(server.env || '{}');
+        document.getElementById('mcpEnv').value = Object.keys(env).length > 0 ? JSON.stringify(env, null, 2) : '';
+    } catch (error) {
+        console.error('解析 MCP 服务器配置失败:', error);
+    }
+    document.getElementById('mcpUrl').value = server.url || '';
+    document.getElementById('mcpDescription').value = server.description || '';
+    updateMcpTransportFields();
+    document.querySelector('#mcpServerModal .modal-title').textContent = window.i18n.t('mcp.edit');
+    new bootstrap.Modal(document.getElementById('mcpServerModal')).show();
+}
 
-        // 确保 permissions.allow 是数组
-        if (!Array.isArray(settings.permissions.allow)) {
-            settings.permissions.allow = ['*'];
-        }
+async function saveMcpServer() {
+    const name = document.getElementById('mcpServerName').value.trim();
+    const transport = document.getElementById('mcpTransport').value;
+    if (!name) {
+        showError(window.i18n.t('validation.required_fields'));
+        return;
+    }
 
-        // 确保 permissions.deny 是数组
-        if (!Array.isArray(settings.permissions.deny)) {
-            settings.permissions.deny = [];
+    let env = {};
+    const envText = document.getElementById('mcpEnv').value.trim();
+    if (envText) {
+        try {
+            env = JSON.parse(envText);
+        } catch (error) {
+            showError(window.i18n.t('mcp.env_invalid'));
+            return;
         }
+        if (typeof env !== 'object' || env === null || Array.isArray(env)) {
+            showError(window.i18n.t('mcp.env_invalid'));
+            return;
+        }
+    }
 
-        // 确保 env 对象存在
-        if (!settings.env) {
-            settings.env = {
-                CLAUDE_CODE_BUBBLEWRAP: '1',
-                DISABLE_AUTOUPDATER: 1
-            };
+    const stdio = transport === 'stdio';
+    const description = document.getElementById('mcpDescription').value.trim();
+    const params = {
+        name,
+        transport,
+        command: stdio ? document.getElementById('mcpCommand').value.trim() : null,
+        args: stdio
+            ? document.getElementById('mcpArgs').value.split('\n').map(arg => arg.trim()).filter(arg => arg)
+            : null,
+        env: stdio ? env : null,
+        url: stdio ? null : document.getElementById('mcpUrl').value.trim(),
+        // 编辑时以空字符串清空描述
+        description: description || (editingMcpServerId ? '' : null)
+    };
+
+    try {
+        if (editingMcpServerId) {
+            await tauriUpdateMcpServer(editingMcpServerId, params);
+        } else {
+            await tauriCreateMcpServer(params);
         }
 
-        claudeSettingsData = settings;
+        bootstrap.Modal.getInstance(document.getElementById('mcpServerModal')).hide();
+        editingMcpServerId = null;
+        await loadMcpServers();
+        showClaudeSettingsMessage(window.i18n.t('mcp.saved'), 'success');
+    } catch (error) {
+        // 校验失败时保留模态框，方便用户修改
+        showError(window.i18n.t('mcp.save_failed') + ': ' + getErrorMessage(error));
+    }
+}
 
-        console.log(window.i18n.t('claude.config_verified') + ':', claudeSettingsData);
+async function deleteMcpServer(serverId) {
+    const server = mcpServers.find(s => s.id === serverId);
+    if (!server) return;
 
-        // 更新UI
-        document.getElementById('defaultPermissionMode').value =
-            claudeSettingsData.permissions.defaultMode || 'bypassPermissions';
+    const confirmed = await customConfirm(
+        window.i18n.t('mcp.delete_confirm').replace('{name}', server.name),
+        window.i18n.t('mcp.delete_title')
+    );
+    if (!confirmed) return;
 
-        // 更新工具选择状态
-        updateToolsSelection();
+    try {
+        await tauriDeleteMcpServer(serverId);
+        await loadMcpServers();
+        showClaudeSettingsMessage(window.i18n.t('mcp.deleted'), 'success');
+    } catch (error) {
+        showClaudeSettingsMessage(window.i18n.t('mcp.delete_failed') + ': ' + getErrorMessage(error), 'error');
+    }
+}
 
-        // 更新环境变量开关
-        document.getElementById('disableAutoUpdater').checked =
-            claudeSettingsData.env.DISABLE_AUTOUPDATER === 1 || claudeSettingsData.env.DISABLE_AUTOUPDATER === '1';
-        document.getElementById('disablePromptCaching').checked =
-            claudeSettingsData.env.DISABLE_PROMPT_CACHING === 1 || claudeSettingsData.env.DISABLE_PROMPT_CACHING === '1';
+// 在账号/目录模态框中渲染可勾选的列表
+function renderCheckList(containerId, items, selectedIds, label, emptyText) {
+    const container = document.getElementById(containerId);
+    if (!container) return;
 
-        // 更新数值型环境变量
-        document.getElementById('maxOutputTokens').value = claudeSettingsData.env.CLAUDE_CODE_MAX_OUTPUT_TOKENS || '';
-        document.getElementById('maxThinkingTokens').value = claudeSettingsData.env.MAX_THINKING_TOKENS || '';
-        document.getElementById('maxMcpOutputTokens').value = claudeSettingsData.env.MAX_MCP_OUTPUT_TOKENS || '';
+    if (items.length === 0) {
+        container.innerHTML = `<div class="text-muted small">${emptyText}</div>`;
+        return;
+    }
 
-        // 更新代理配置
-        document.getElementById('httpProxy').value = claudeSettingsData.env.HTTP_PROXY || '';
-        document.getElementById('httpsProxy').value = claudeSettingsData.env.HTTPS_PROXY || '';
+    const selected = new Set(selectedIds || []);
+    container.innerHTML = items.map(item => `
+        <div class="form-check">
+            <input class="form-check-input" type="checkbox" value="${item.id}" id="${containerId}-${item.id}"
+                   ${selected.has(item.id) ? 'checked' : ''}>
+            <label class="form-check-label" for="${containerId}-${item.id}">${label(item)}</label>
+        </div>
+    `).join('');
+}
 
-        return true; // 加载成功
+function getCheckedIds(containerId) {
+    return Array.from(document.querySelectorAll(`#${containerId} input[type="checkbox"]:checked`))
+        .map(input => parseInt(input.value));
+}
 
+async function loadMcpServerChecks(containerId, selectedIds) {
+    try {
+        mcpServers = await tauriGetMcpServers();
     } catch (error) {
-        // 显示详细错误信息
-        const errorMsg = getErrorMessage(error);
-        console.error(window.i18n.t('claude.load_failed') + ', ' + window.i18n.t('claude.using_default') + ':', error);
-        console.error(window.i18n.t('claude.error_detail') + ':', errorMsg);
+        console.error('加载 MCP 服务器失败:', error);
+    }
 
-        // 如果数据库中没有配置，使用默认设置
-        claudeSettingsData = {
-            permissions: {
-                defaultMode: 'bypassPermissions',
-                allow: ['*'],
-                deny: []
-            },
-            env: {
-                CLAUDE_CODE_BUBBLEWRAP: '1',
-                DISABLE_AUTOUPDATER: 1
-            }
-        };
+    renderCheckList(
+        containerId,
+        mcpServers,
+        selectedIds,
+        server => `${escapeHtml(server.name)} <span class="badge bg-secondary">${escapeHtml(server.transport)}</span>`,
+        window.i18n.t('mcp.empty')
+    );
+}
 
-        // 更新UI为默认值
-        document.getElementById('defaultPermissionMode').value = 'bypassPermissions';
-        updateToolsSelection();
-        document.getElementById('disableAutoUpdater').checked = true;
+// ============= Hook =============
 
-        // 返回错误信息以便显示
-        return { success: false, error: errorMsg };
+async function loadHooks() {
+    try {
+        hooks = await tauriGetHooks();
+        renderHooks();
+    } catch (error) {
+        showClaudeSettingsMessage(window.i18n.t('hooks.load_failed') + ': ' + getErrorMessage(error), 'error');
     }
 }
 
-// 渲染工具列表
-function renderToolsList() {
-    const container = document.getElementById('toolsList');
+function renderHooks() {
+    const container = document.getElementById('hooksList');
+    if (!container) return;
 
-    // 确保数据结构存在
-    if (!claudeSettingsData.permissions || !Array.isArray(claudeSettingsData.permissions.allow)) {
-        console.warn('Claude配置数据不完整，使用默认值');
-        claudeSettingsData.permissions = {
-            defaultMode: 'bypassPermissions',
-            allow: ['*'],
-            deny: []
-        };
+    if (hooks.length === 0) {
+        container.innerHTML = '<div class="text-muted small">' + window.i18n.t('hooks.empty') + '</div>';
+        return;
     }
 
-    const allowAll = claudeSettingsData.permissions.allow.includes('*');
+    container.innerHTML = `
+        <div class="table-responsive">
+            <table class="table table-sm align-middle">
+                <thead>
+                    <tr>
+                        <th>${window.i18n.t('hooks.name')}</th>
+                        <th>${window.i18n.t('hooks.event')}</th>
+                        <th>${window.i18n.t('hooks.command')}</th>
+                        <th></th>
+                    </tr>
+                </thead>
+                <tbody>
+                    ${hooks.map(hook => `
+                        <tr>
+                            <td>
+                                <strong>${escapeHtml(hook.name)}</strong>
+                                ${hook.is_global ? `<span class="badge bg-info ms-1">${window.i18n.t('hooks.global')}</span>` : ''}
+                                ${hook.description ? `<div class="text-muted small">${escapeHtml(hook.description)}</div>` : ''}
+                            </td>
+                            <td>
+                                <span class="badge bg-secondary">${escapeHtml(hook.event)}</span>
+                                ${hook.matcher ? `<code class="small ms-1">${escapeHtml(hook.matcher)}</code>` : ''}
+                            </td>
+                            <td><code>${escapeHtml(hook.command)}</code></td>
+                            <td class="text-nowrap">
+                                <button class="btn btn-sm btn-outline-primary" onclick="editHook(${hook.id})">
+                                    <i class="fas fa-edit"></i>
+                                </button>
+                                <button class="btn btn-sm btn-outline-danger" onclick="deleteHook(${hook.id})">
+                                    <i class="fas fa-trash"></i>
+                                </button>
+                            </td>
+                        </tr>
+                    `).join('')}
+                </tbody>
+            </table>
+        </div>
+    `;
+}
 
-    document.getElementById('allowAllTools').checked = allowAll;
+// 只有工具相关的事件支持 matcher
+function updateHookMatcherField() {
+    const event = document.getElementById('hookEvent').value;
+    document.getElementById('hookMatcherField').style.display =
+        event === 'PreToolUse' || event === 'PostToolUse' ? '' : 'none';
+}
 
-    if (allowAll) {
-        container.innerHTML = '<div class="text-muted">' + window.i18n.t('text.all_tools_allowed') + '</div>';
-    } else {
-        container.innerHTML = availableClaudeTools.map(tool => `
-            <div class="form-check">
-                <input class="form-check-input tool-checkbox" type="checkbox"
-                       id="tool-${tool}" value="${tool}"
-                       ${claudeSettingsData.permissions.allow.includes(tool) ? 'checked' : ''}>
-                <label class="form-check-label" for="tool-${tool}">
-                    ${tool}
-                </label>
-            </div>
-        `).join('');
-    }
+function showAddHookModal() {
+    editingHookId = null;
+    document.getElementById('hookForm').reset();
+    updateHookMatcherField();
+    document.querySelector('#hookModal .modal-title').textContent = window.i18n.t('hooks.add');
+    new bootstrap.Modal(document.getElementById('hookModal')).show();
+}
 
-    // 渲染禁用工具列表
-    renderDeniedTools();
+function editHook(hookId) {
+    const hook = hooks.find(h => h.id === hookId);
+    if (!hook) return;
+
+    editingHookId = hookId;
+    document.getElementById('hookName').value = hook.name;
+    document.getElementById('hookEvent').value = hook.event;
+    document.getElementById('hookMatcher').value = hook.matcher || '';
+    document.getElementById('hookCommand').value = hook.command;
+    document.getElementById('hookTimeout').value = hook.timeout || '';
+    document.getElementById('hookIsGlobal').checked = hook.is_global;
+    document.getElementById('hookDescription').value = hook.description || '';
+    updateHookMatcherField();
+    document.querySelector('#hookModal .modal-title').textContent = window.i18n.t('hooks.edit');
+    new bootstrap.Modal(document.getElementById('hookModal')).show();
 }
 
-// 渲染禁用工具列表
-function renderDeniedTools() {
-    const container = document.getElementById('deniedToolsList');
+async function saveHook() {
+    const name = document.getElementById('hookName').value.trim();
+    const event = document.getElementById('hookEvent').value;
+    const command = document.getElementById('hookCommand').value.trim();
+    if (!name || !command) {
+        showError(window.i18n.t('validation.required_fields'));
+        return;
+    }
+
+    const timeoutText = document.getElementById('hookTimeout').value.trim();
+    const timeout = timeoutText ? parseInt(timeoutText) : null;
+    if (timeoutText && (isNaN(timeout) || timeout <= 0)) {
+        showError(window.i18n.t('hooks.timeout_invalid'));
+        return;
+    }
+
+    // 编辑时以空字符串清空 matcher 和描述，以 0 清除超时时间
+    const supportsMatcher = event === 'PreToolUse' || event === 'PostToolUse';
+    const matcher = supportsMatcher ? document.getElementById('hookMatcher').value.trim() : '';
+    const description = document.getElementById('hookDescription').value.trim();
+    const params = {
+        name,
+        event,
+        matcher: matcher || (editingHookId ? '' : null),
+        command,
+        timeout: timeout || (editingHookId ? 0 : null),
+        isGlobal: document.getElementById('hookIsGlobal').checked,
+        description: description || (editingHookId ? '' : null)
+    };
 
-    // 确保数据结构存在
-    if (!claudeSettingsData.permissions || !Array.isArray(claudeSettingsData.permissions.deny)) {
-        console.warn('Claude配置的deny数据不完整，初始化为空数组');
-        if (!claudeSettingsData.permissions) {
-            claudeSettingsData.permissions = {
-                defaultMode: 'bypassPermissions',
-                allow: ['*'],
-                deny: []
-            };
+    try {
+        if (editingHookId) {
+            await tauriUpdateHook(editingHookId, params);
         } else {
-            claudeSettingsData.permissions.deny = [];
+            await tauriCreateHook(params);
         }
-    }
 
-    if (claudeSettingsData.permissions.deny.length === 0) {
-        container.innerHTML = '<div class="text-muted small">' + window.i18n.t('text.no_denied_tools') + '</div>';
-    } else {
-        container.innerHTML = claudeSettingsData.permissions.deny.map(tool => `
-            <span class="badge bg-danger me-2 mb-2">
-                ${tool}
-                <button type="button" class="btn-close btn-close-white ms-1"
-                        onclick="removeDeniedTool('${tool}')" style="font-size: 0.7em;"></button>
-            </span>
-        `).join('');
+        bootstrap.Modal.getInstance(document.getElementById('hookModal')).hide();
+        editingHookId = null;
+        await loadHooks();
+        showClaudeSettingsMessage(window.i18n.t('hooks.saved'), 'success');
+    } catch (error) {
+        // 校验失败时保留模态框，方便用户修改
+        showError(window.i18n.t('hooks.save_failed') + ': ' + getErrorMessage(error));
     }
 }
 
-// 更新工具选择状态
-function updateToolsSelection() {
-    // 确保数据结构存在
-    if (!claudeSettingsData.permissions || !Array.isArray(claudeSettingsData.permissions.allow)) {
-        console.warn('updateToolsSelection: Claude配置数据不完整，使用默认值');
-        claudeSettingsData.permissions = {
-            defaultMode: 'bypassPermissions',
-            allow: ['*'],
-            deny: []
-        };
-    }
+async function deleteHook(hookId) {
+    const hook = hooks.find(h => h.id === hookId);
+    if (!hook) return;
 
-    const allowAll = claudeSettingsData.permissions.allow.includes('*');
-    document.getElementById('allowAllTools').checked = allowAll;
-    renderToolsList();
-}
+    const confirmed = await customConfirm(
+        window.i18n.t('hooks.delete_confirm').replace('{name}', hook.name),
+        window.i18n.t('hooks.delete_title')
+    );
+    if (!confirmed) return;
 
-// 渲染自定义环境变量
-function renderCustomEnvVars() {
-    const container = document.getElementById('customEnvList');
+    try {
+        await tauriDeleteHook(hookId);
+        await loadHooks();
+        showClaudeSettingsMessage(window.i18n.t('hooks.deleted'), 'success');
+    } catch (error) {
+        showClaudeSettingsMessage(window.i18n.t('hooks.delete_failed') + ': ' + getErrorMessage(error), 'error');
+    }
+}
 
-    // 确保 env 对象存在
-    if (!claudeSettingsData.env || typeof claudeSettingsData.env !== 'object') {
-        console.warn('Claude配置的env数据不完整，初始化为默认值');
-        claudeSettingsData.env = {
-            CLAUDE_CODE_BUBBLEWRAP: '1',
-            DISABLE_AUTOUPDATER: 1
-        };
+// 全局 Hook 对所有目录生效，目录中只列出非全局的 Hook
+async function loadHookChecks(containerId, selectedIds) {
+    try {
+        hooks = await tauriGetHooks();
+    } catch (error) {
+        console.error('加载 Hook 失败:', error);
     }
 
-    // 过滤掉系统管理的环境变量
-    const systemManagedEnvVars = [
-        'DISABLE_AUTOUPDATER',
-        'DISABLE_PROMPT_CACHING',
-        'CLAUDE_CODE_MAX_OUTPUT_TOKENS',
-        'MAX_THINKING_TOKENS',
-        'MAX_MCP_OUTPUT_TOKENS',
-        'HTTP_PROXY',
-        'HTTPS_PROXY'
-    ];
+    renderCheckList(
+        containerId,
+        hooks.filter(hook => !hook.is_global),
+        selectedIds,
+        hook => `${escapeHtml(hook.name)} <span class="badge bg-secondary">${escapeHtml(hook.event)}</span>`,
+        window.i18n.t('hooks.empty')
+    );
+}
 
-    const customEnvVars = Object.entries(claudeSettingsData.env)
-        .filter(([key]) => !systemManagedEnvVars.includes(key));
+// ============= 命令与子代理定义 =============
 
-    if (customEnvVars.length === 0) {
-        container.innerHTML = '<div class="text-muted small">' + window.i18n.t('text.no_custom_env_vars') + '</div>';
-    } else {
-        container.innerHTML = customEnvVars.map(([key, value]) => `
-            <div class="d-flex align-items-center mb-2 p-2 bg-light rounded">
-                <code class="flex-grow-1">${key} = ${value}</code>
-                <button class="btn btn-sm btn-outline-danger ms-2"
-                        onclick="removeCustomEnvVar('${key}')">
-                    <i class="fas fa-times"></i>
-                </button>
-            </div>
-        `).join('');
+async function loadDefinitions() {
+    try {
+        definitions = await tauriGetClaudeDefinitions();
+        renderDefinitions();
+    } catch (error) {
+        showClaudeSettingsMessage(window.i18n.t('definitions.load_failed') + ': ' + getErrorMessage(error), 'error');
     }
 }
 
-// 设置事件监听器
-function setupClaudeSettingsEventListeners() {
-    // 如果已经设置过，则不重复设置
-    if (claudeSettingsEventListenersSetup) {
-        console.log(window.i18n.t('claude.listeners_already_setup'));
+function renderDefinitions() {
+    const container = document.getElementById('definitionsList');
+    if (!container) return;
+
+    if (definitions.length === 0) {
+        container.innerHTML = '<div class="text-muted small">' + window.i18n.t('definitions.empty') + '</div>';
         return;
     }
 
-    console.log(window.i18n.t('claude.setting_listeners'));
-
-    // 权限模式变更
-    document.getElementById('defaultPermissionMode').addEventListener('change', function() {
-        claudeSettingsData.permissions.defaultMode = this.value;
-        updatePreview();
-    });
+    container.innerHTML = `
+        <div class="table-responsive">
+            <table class="table table-sm align-middle">
+                <thead>
+                    <tr>
+                        <th>${window.i18n.t('definitions.name')}</th>
+                        <th>${window.i18n.t('definitions.kind')}</th>
+                        <th>${window.i18n.t('definitions.description')}</th>
+                        <th></th>
+                    </tr>
+                </thead>
+                <tbody>
+                    ${definitions.map(definition => `
+                        <tr>
+                            <td>
+                                <code>${definition.kind === 'command' ? '/' : ''}${escapeHtml(definition.name)}</code>
+                                ${definition.is_default ? `<span class="badge bg-info ms-1">${window.i18n.t('definitions.default')}</span>` : ''}
+                            </td>
+                            <td><span class="badge bg-secondary">${window.i18n.t('definitions.kind_' + definition.kind)}</span></td>
+                            <td class="small text-muted">${escapeHtml(definition.description || '')}</td>
+                            <td class="text-nowrap">
+                                <button class="btn btn-sm btn-outline-primary" onclick="editDefinition(${definition.id})">
+                                    <i class="fas fa-edit"></i>
+                                </button>
+                                <button class="btn btn-sm btn-outline-danger" onclick="deleteDefinition(${definition.id})">
+                                    <i class="fas fa-trash"></i>
+                                </button>
+                            </td>
+                        </tr>
+                    `).join('')}
+                </tbody>
+            </table>
+        </div>
+    `;
+}
 
-    // 全选工具变更
-    document.getElementById('allowAllTools').addEventListener('change', function() {
-        if (this.checked) {
-            claudeSettingsData.permissions.allow = ['*'];
-        } else {
-            claudeSettingsData.permissions.allow = [];
-        }
-        updateToolsSelection();
-        updatePreview();
-    });
+function showAddDefinitionModal() {
+    editingDefinitionId = null;
+    document.getElementById('definitionForm').reset();
+    document.getElementById('definitionKind').disabled = false;
+    document.querySelector('#definitionModal .modal-title').textContent = window.i18n.t('definitions.add');
+    new bootstrap.Modal(document.getElementById('definitionModal')).show();
+}
 
-    // 工具选择变更
-    document.addEventListener('change', function(e) {
-        if (e.target.classList.contains('tool-checkbox')) {
-            const tool = e.target.value;
-            if (e.target.checked) {
-                if (!claudeSettingsData.permissions.allow.includes(tool)) {
-                    claudeSettingsData.permissions.allow.push(tool);
-                }
-            } else {
-                claudeSettingsData.permissions.allow =
-                    claudeSettingsData.permissions.allow.filter(t => t !== tool);
-            }
-            updatePreview();
-        }
-    });
+function editDefinition(definitionId) {
+    const definition = definitions.find(d => d.id === definitionId);
+    if (!definition) return;
 
-    // 自动更新变更
-    document.getElementById('disableAutoUpdater').addEventListener('change', function() {
-        claudeSettingsData.env.DISABLE_AUTOUPDATER = this.checked ? 1 : 0;
-        updatePreview();
-    });
+    editingDefinitionId = definitionId;
+    document.getElementById('definitionKind').value = definition.kind;
+    // 类型决定写入的子目录，创建后不能修改
+    document.getElementById('definitionKind').disabled = true;
+    document.getElementById('definitionName').value = definition.name;
+    document.getElementById('definitionDescription').value = definition.description || '';
+    document.getElementById('definitionContent').value = definition.content;
+    document.getElementById('definitionIsDefault').checked = definition.is_default;
+    document.querySelector('#definitionModal .modal-title').textContent = window.i18n.t('definitions.edit');
+    new bootstrap.Modal(document.getElementById('definitionModal')).show();
+}
 
-    // 禁用提示缓存变更
-    document.getElementById('disablePromptCaching').addEventListener('change', function() {
-        claudeSettingsData.env.DISABLE_PROMPT_CACHING = this.checked ? 1 : 0;
-        updatePreview();
-    });
+async function saveDefinition() {
+    const kind = document.getElementById('definitionKind').value;
+    const name = document.getElementById('definitionName').value.trim();
+    const content = document.getElementById('definitionContent').value;
+    if (!name || !content.trim()) {
+        showError(window.i18n.t('validation.required_fields'));
+        return;
+    }
 
-    // 最大输出Token数变更
-    document.getElementById('maxOutputTokens').addEventListener('input', function() {
-        const value = this.value.trim();
-        if (value) {
-            claudeSettingsData.env.CLAUDE_CODE_MAX_OUTPUT_TOKENS = parseInt(value);
-        } else {
-            delete claudeSettingsData.env.CLAUDE_CODE_MAX_OUTPUT_TOKENS;
-        }
-        updatePreview();
-    });
+    // 新建时描述留空由后端从 front-matter 读取，编辑时以空字符串清空
+    const description = document.getElementById('definitionDescription').value.trim() ||
+        (editingDefinitionId ? '' : null);
+    const isDefault = document.getElementById('definitionIsDefault').checked;
 
-    // 最大思考Token数变更
-    document.getElementById('maxThinkingTokens').addEventListener('input', function() {
-        const value = this.value.trim();
-        if (value) {
-            claudeSettingsData.env.MAX_THINKING_TOKENS = parseInt(value);
+    try {
+        if (editingDefinitionId) {
+            await tauriUpdateClaudeDefinition(editingDefinitionId, { name, content, description, isDefault });
         } else {
-            delete claudeSettingsData.env.MAX_THINKING_TOKENS;
+            await tauriCreateClaudeDefinition({ kind, name, content, description, isDefault });
         }
-        updatePreview();
-    });
 
-    // MCP输出Token限制变更
-    document.getElementById('maxMcpOutputTokens').addEventListener('input', function() {
-        const value = this.value.trim();
-        if (value) {
-            claudeSettingsData.env.MAX_MCP_OUTPUT_TOKENS = parseInt(value);
-        } else {
-            delete claudeSettingsData.env.MAX_MCP_OUTPUT_TOKENS;
-        }
-        updatePreview();
-    });
+        bootstrap.Modal.getInstance(document.getElementById('definitionModal')).hide();
+        editingDefinitionId = null;
+        await loadDefinitions();
+        showClaudeSettingsMessage(window.i18n.t('definitions.saved'), 'success');
+    } catch (error) {
+        // 校验失败时保留模态框，方便用户修改
+        showError(window.i18n.t('definitions.save_failed') + ': ' + getErrorMessage(error));
+    }
+}
 
-    // HTTP代理变更
-    document.getElementById('httpProxy').addEventListener('input', function() {
-        const value = this.value.trim();
-        if (value) {
-            claudeSettingsData.env.HTTP_PROXY = value;
-        } else {
-            delete claudeSettingsData.env.HTTP_PROXY;
-        }
-        updatePreview();
-    });
+async function deleteDefinition(definitionId) {
+    const definition = definitions.find(d => d.id === definitionId);
+    if (!definition) return;
 
-    // HTTPS代理变更
-    document.getElementById('httpsProxy').addEventListener('input', function() {
-        const value = this.value.trim();
-        if (value) {
-            claudeSettingsData.env.HTTPS_PROXY = value;
-        } else {
-            delete claudeSettingsData.env.HTTPS_PROXY;
-        }
-        updatePreview();
-    });
+    const confirmed = await customConfirm(
+        window.i18n.t('definitions.delete_confirm').replace('{name}', definition.name),
+        window.i18n.t('definitions.delete_title')
+    );
+    if (!confirmed) return;
 
-    // 标记为已设置
-    claudeSettingsEventListenersSetup = true;
+    try {
+        await tauriDeleteClaudeDefinition(definitionId);
+        await loadDefinitions();
+        showClaudeSettingsMessage(window.i18n.t('definitions.deleted'), 'success');
+    } catch (error) {
+        showClaudeSettingsMessage(window.i18n.t('definitions.delete_failed') + ': ' + getErrorMessage(error), 'error');
+    }
 }
 
-// 添加禁用工具
-function addDeniedTool() {
-    const input = document.getElementById('newDeniedTool');
-    const tool = input.value.trim();
-    
-    if (!tool) {
-        showClaudeSettingsMessage(window.i18n.t('claude.tool_name_required'), 'warning');
-        return;
-    }
-    
-    if (claudeSettingsData.permissions.deny.includes(tool)) {
-        showClaudeSettingsMessage(window.i18n.t('claude.tool_already_denied'), 'warning');
-        return;
+// 从项目的 .claude/commands 和 .claude/agents 导入定义
+async function importDefinitions() {
+    try {
+        const path = await open({
+            directory: true,
+            multiple: false,
+            title: window.i18n.t('definitions.import_select')
+        });
+        if (!path) return;
+
+        const overwrite = document.getElementById('definitionImportOverwrite').checked;
+        const message = await tauriImportClaudeDefinitions(path, overwrite);
+        await loadDefinitions();
+        showClaudeSettingsMessage(message, 'success');
+    } catch (error) {
+        showClaudeSettingsMessage(window.i18n.t('definitions.import_failed') + ': ' + getErrorMessage(error), 'error');
     }
-    
-    claudeSettingsData.permissions.deny.push(tool);
-    input.value = '';
-    renderDeniedTools();
-    updatePreview();
 }
 
-// 移除禁用工具
-function removeDeniedTool(tool) {
-    claudeSettingsData.permissions.deny = 
-        claudeSettingsData.permissions.deny.filter(t => t !== tool);
-    renderDeniedTools();
-    updatePreview();
+// selectedIds 为 null 时勾选默认启用的定义
+async function loadDefinitionChecks(containerId, selectedIds) {
+    try {
+        definitions = await tauriGetClaudeDefinitions();
+    } catch (error) {
+        console.error('加载定义失败:', error);
+    }
+
+    renderCheckList(
+        containerId,
+        definitions,
+        selectedIds ?? definitions.filter(d => d.is_default).map(d => d.id),
+        definition => `<code>${definition.kind === 'command' ? '/' : ''}${escapeHtml(definition.name)}</code> ` +
+            `<span class="badge bg-secondary">${window.i18n.t('definitions.kind_' + definition.kind)}</span>`,
+        window.i18n.t('definitions.empty')
+    );
 }
 
 // 添加自定义环境变量
@@ -3447,7 +6110,7 @@ async function loadClaudeConfigStatusInAssociation() {
         document.getElementById('claudeConfigStatus').style.display = 'block';
         
         // 更新权限模式
-        const permissionMode = settings.permissions?.defaultMode || 'normal';
+        const permissionMode = settings.permissions?.defaultMode || 'default';
         const permissionElement = document.getElementById('claudePermissionMode');
         
         switch (permissionMode) {
@@ -3459,7 +6122,11 @@ async function loadClaudeConfigStatusInAssociation() {
                 permissionElement.textContent = window.i18n.t('claude.permission_mode_accept_short');
                 permissionElement.className = 'badge bg-info';
                 break;
-            case 'normal':
+            case 'plan':
+                permissionElement.textContent = window.i18n.t('claude.permission_mode_plan_short');
+                permissionElement.className = 'badge bg-primary';
+                break;
+            case 'default':
             default:
                 permissionElement.textContent = window.i18n.t('claude.permission_mode_normal_short');
                 permissionElement.className = 'badge bg-success';
@@ -3509,6 +6176,8 @@ window.saveBaseUrl = saveBaseUrl;
 window.editAccount = editAccount;
 window.promptDeleteAccount = promptDeleteAccount;
 window.importAccounts = importAccounts;
+window.confirmImport = confirmImport;
+window.showImportPreview = showImportPreview;
 window.exportAccounts = exportAccounts;
 window.selectAllExportAccounts = selectAllExportAccounts;
 window.deselectAllExportAccounts = deselectAllExportAccounts;
@@ -3530,6 +6199,32 @@ window.testDatabase = testDatabase;
 window.selectDirectory = selectDirectory;
 window.promptDeleteDirectory = promptDeleteDirectory;
 window.cleanupInvalidDirectories = cleanupInvalidDirectories;
+window.fixDirectoryDrift = fixDirectoryDrift;
+window.openDiscoveryModal = openDiscoveryModal;
+window.verifyAccountToken = verifyAccountToken;
+window.verifyAllAccountTokens = verifyAllAccountTokens;
+window.refreshAccountBalances = refreshAccountBalances;
+window.openBalanceAdapter = openBalanceAdapter;
+window.saveBalanceAdapter = saveBalanceAdapter;
+window.deleteBalanceAdapter = deleteBalanceAdapter;
+window.openAccountPoolModal = openAccountPoolModal;
+window.renderPoolMembers = renderPoolMembers;
+window.renderPoolFailovers = renderPoolFailovers;
+window.createAccountPool = createAccountPool;
+window.deleteAccountPool = deleteAccountPool;
+window.togglePoolMember = togglePoolMember;
+window.bindDirectoryPool = bindDirectoryPool;
+window.toggleRelay = toggleRelay;
+window.setDirectoryRelay = setDirectoryRelay;
+window.openEndpointCheck = openEndpointCheck;
+window.runEndpointCheck = runEndpointCheck;
+window.addDiscoveryRoot = addDiscoveryRoot;
+window.deleteDiscoveryRoot = deleteDiscoveryRoot;
+window.scanDiscoveryProjects = scanDiscoveryProjects;
+window.importDiscoveredProjects = importDiscoveredProjects;
+window.toggleDriftNotifications = toggleDriftNotifications;
+window.scanConfigDrift = scanConfigDrift;
+window.fixAllDrift = fixAllDrift;
 
 // Claude Settings functions
 window.loadClaudeSettingsPage = loadClaudeSettingsPage;
@@ -3540,6 +6235,32 @@ window.removeCustomEnvVar = removeCustomEnvVar;
 window.updatePreview = updatePreview;
 window.saveClaudeConfigToDatabase = saveClaudeConfigToDatabase;
 window.getClaudeSettingsForSwitch = getClaudeSettingsForSwitch;
+window.showAddPermissionPresetModal = showAddPermissionPresetModal;
+window.editPermissionPreset = editPermissionPreset;
+window.savePermissionPreset = savePermissionPreset;
+window.deletePermissionPreset = deletePermissionPreset;
+window.showAddProxyModal = showAddProxyModal;
+window.editProxy = editProxy;
+window.showAddMcpServerModal = showAddMcpServerModal;
+window.editMcpServer = editMcpServer;
+window.saveMcpServer = saveMcpServer;
+window.deleteMcpServer = deleteMcpServer;
+window.updateMcpTransportFields = updateMcpTransportFields;
+window.showAddHookModal = showAddHookModal;
+window.editHook = editHook;
+window.saveHook = saveHook;
+window.deleteHook = deleteHook;
+window.updateHookMatcherField = updateHookMatcherField;
+window.showAddDefinitionModal = showAddDefinitionModal;
+window.editDefinition = editDefinition;
+window.saveDefinition = saveDefinition;
+window.deleteDefinition = deleteDefinition;
+window.importDefinitions = importDefinitions;
+window.saveProxy = saveProxy;
+window.deleteProxy = deleteProxy;
+window.testProxy = testProxy;
+window.loadProxyOptions = loadProxyOptions;
+window.getSelectedProxyId = getSelectedProxyId;
 
 // ============= WebDAV 功能 =============
 
@@ -3597,13 +6318,22 @@ async function tauriUploadConfigToWebdav(configId, filename) {
     });
 }
 
-async function tauriDownloadConfigFromWebdav(configId, filename) {
-    return await invoke('download_config_from_webdav', {
+async function tauriPreviewWebdavDownload(configId, filename) {
+    return await invoke('preview_webdav_download', {
         configId,
         filename
     });
 }
 
+async function tauriDownloadConfigFromWebdav(configId, filename, items, strategy) {
+    return await invoke('download_config_from_webdav', {
+        configId,
+        filename,
+        items,
+        strategy
+    });
+}
+
 async function tauriListWebdavFiles(configId) {
     return await invoke('list_webdav_files', { configId });
 }
@@ -3881,23 +6611,23 @@ async function listRemoteFiles() {
     }
 }
 
-// 从 WebDAV 下载配置
+// 从 WebDAV 下载配置：与已有记录比对后按选择的策略导入
 async function downloadConfigFromWebdav(filename) {
     if (!selectedWebdavConfig) return;
 
-    const confirmed = await customConfirm(
-        window.i18n.t('text.webdav_confirm_download').replace('{filename}', filename),
-        window.i18n.t('text.webdav_confirm_download_title')
-    );
-
-    if (confirmed) {
-        try {
-            const result = await tauriDownloadConfigFromWebdav(selectedWebdavConfig.id, filename);
-            showMessage(window.i18n.t('text.webdav_download_success'), 'success');
-            await loadSyncLogs();
-        } catch (error) {
-            showMessage(window.i18n.t('text.webdav_download_failed') + ': ' + getErrorMessage(error), 'danger');
-        }
+    const configId = selectedWebdavConfig.id;
+    try {
+        const preview = await tauriPreviewWebdavDownload(configId, filename);
+        showImportPreview(
+            preview,
+            (items, strategy) => tauriDownloadConfigFromWebdav(configId, filename, items, strategy),
+            async () => {
+                showMessage(window.i18n.t('text.webdav_download_success'), 'success');
+                await loadSyncLogs();
+            }
+        );
+    } catch (error) {
+        showMessage(window.i18n.t('text.webdav_download_failed') + ': ' + getErrorMessage(error), 'danger');
     }
 }
 
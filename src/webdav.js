@@ -58,6 +58,10 @@ async function tauriDeleteWebdavConfig(id) {
     return await invoke('delete_webdav_config', { id });
 }
 
+async function tauriSetWebdavConfigProxy(configId, proxyId) {
+    return await invoke('set_webdav_config_proxy', { configId, proxyId });
+}
+
 async function tauriTestWebdavConnection(id) {
     return await invoke('test_webdav_connection', { id });
 }
@@ -69,13 +73,22 @@ async function tauriUploadConfigToWebdav(configId, filename) {
     });
 }
 
-async function tauriDownloadConfigFromWebdav(configId, filename) {
-    return await invoke('download_config_from_webdav', {
+async function tauriPreviewWebdavDownload(configId, filename) {
+    return await invoke('preview_webdav_download', {
         configId,
         filename
     });
 }
 
+async function tauriDownloadConfigFromWebdav(configId, filename, items, strategy) {
+    return await invoke('download_config_from_webdav', {
+        configId,
+        filename,
+        items,
+        strategy
+    });
+}
+
 async function tauriListWebdavFiles(configId) {
     return await invoke('list_webdav_files', { configId });
 }
@@ -229,7 +242,11 @@ async function saveWebdavConfig() {
     };
 
     try {
-        await tauriCreateWebdavConfig(config);
+        const created = await tauriCreateWebdavConfig(config);
+        const proxyId = window.getSelectedProxyId('webdavProxy');
+        if (proxyId !== null) {
+            await tauriSetWebdavConfigProxy(created.id, proxyId);
+        }
         window.showSuccess(window.i18n.t('text.webdav_create_success'));
         bootstrap.Modal.getInstance(document.getElementById('webdavConfigModal')).hide();
         await loadWebdavConfigs();
@@ -251,6 +268,7 @@ async function editWebdavConfig(id) {
     document.getElementById('webdavRemotePath').value = config.remote_path;
     document.getElementById('webdavAutoSync').checked = config.auto_sync;
     document.getElementById('webdavSyncInterval').value = config.sync_interval;
+    await window.loadProxyOptions('webdavProxy', config.proxy_id);
 
     const modal = new bootstrap.Modal(document.getElementById('webdavConfigModal'));
     modal.show();
@@ -271,6 +289,7 @@ async function editWebdavConfig(id) {
 
         try {
             await tauriUpdateWebdavConfig(id, updatedConfig);
+            await tauriSetWebdavConfigProxy(id, window.getSelectedProxyId('webdavProxy'));
             window.showSuccess(window.i18n.t('text.webdav_update_success'));
             modal.hide();
             // 如果更新的是当前选中的配置，需要更新 selectedWebdavConfig
@@ -400,23 +419,23 @@ async function listRemoteFiles() {
     }
 }
 
-// 从 WebDAV 下载配置
+// 从 WebDAV 下载配置：与已有记录比对后按选择的策略导入
 async function downloadConfigFromWebdav(filename) {
     if (!selectedWebdavConfig) return;
 
-    const confirmed = await window.customConfirm(
-        window.i18n.t('text.webdav_confirm_download').replace('{filename}', filename),
-        window.i18n.t('text.webdav_confirm_download_title')
-    );
-
-    if (confirmed) {
-        try {
-            const result = await tauriDownloadConfigFromWebdav(selectedWebdavConfig.id, filename);
-            window.showSuccess(window.i18n.t('text.webdav_download_success'));
-            await loadSyncLogs();
-        } catch (error) {
-            window.showError(window.i18n.t('text.webdav_download_failed') + ': ' + getErrorMessage(error));
-        }
+    const configId = selectedWebdavConfig.id;
+    try {
+        const preview = await tauriPreviewWebdavDownload(configId, filename);
+        window.showImportPreview(
+            preview,
+            (items, strategy) => tauriDownloadConfigFromWebdav(configId, filename, items, strategy),
+            async () => {
+                window.showSuccess(window.i18n.t('text.webdav_download_success'));
+                await loadSyncLogs();
+            }
+        );
+    } catch (error) {
+        window.showError(window.i18n.t('text.webdav_download_failed') + ': ' + getErrorMessage(error));
     }
 }
 
@@ -504,6 +523,16 @@ document.addEventListener('DOMContentLoaded', () => {
         saveBtn.onclick = saveWebdavConfig;
     }
 
+    // 通过“添加”按钮打开配置框时重新加载代理选项，编辑时由 editWebdavConfig 负责
+    const configModal = document.getElementById('webdavConfigModal');
+    if (configModal) {
+        configModal.addEventListener('show.bs.modal', (event) => {
+            if (event.relatedTarget) {
+                window.loadProxyOptions('webdavProxy', null);
+            }
+        });
+    }
+
     // 当切换到 WebDAV 标签页时加载数据
     const webdavTab = document.getElementById('webdav-tab');
     if (webdavTab) {